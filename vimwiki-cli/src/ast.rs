@@ -1,4 +1,4 @@
-use crate::IndexOrName;
+use crate::{IndexOrName, PageCrypt};
 use log::*;
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
@@ -9,7 +9,14 @@ use std::{
     io::{self, Write},
     path::{Path, PathBuf},
 };
-use vimwiki::{HtmlConfig, HtmlWikiConfig, Language, Page};
+use vimwiki::{
+    dynamic::QueryIndex,
+    graph::{self, LinkGraph},
+    schemes, sources,
+    stats::{PageStats, WikiStats},
+    tasks::TaskIndex,
+    HtmlConfig, HtmlWikiConfig, Language, Page, ParserConfig, Timestamp,
+};
 use walkdir::WalkDir;
 
 #[derive(Default, Serialize, Deserialize)]
@@ -20,12 +27,22 @@ pub struct Ast {
 impl Ast {
     pub fn load(
         config: &HtmlConfig,
+        syntax: &ParserConfig,
         include: &[IndexOrName],
         cache: &Path,
         no_cache: bool,
         no_prune_cache: bool,
+        crypt: &PageCrypt,
     ) -> io::Result<Self> {
-        load_ast(config, include, cache, no_cache, no_prune_cache)
+        load_ast(
+            config,
+            syntax,
+            include,
+            cache,
+            no_cache,
+            no_prune_cache,
+            crypt,
+        )
     }
 
     /// Loads a file by either loading it from an external cache file or
@@ -36,10 +53,12 @@ impl Ast {
     pub fn load_file(
         &mut self,
         path: &Path,
+        syntax: &ParserConfig,
         cache: &Path,
         no_cache: bool,
+        crypt: &PageCrypt,
     ) -> io::Result<&WikiFile> {
-        let file = WikiFile::load(path, cache, no_cache)?;
+        let file = WikiFile::load(path, syntax, cache, no_cache, crypt)?;
 
         // Figure out which wiki to put the file
         if let Some(wiki) = self
@@ -73,6 +92,132 @@ impl Ast {
         })
     }
 
+    /// Loads a file like [`Self::load_file`], replacing any version of the
+    /// file already within the ast so that changes on disk are picked up
+    pub fn reload_file(
+        &mut self,
+        path: &Path,
+        syntax: &ParserConfig,
+        cache: &Path,
+        no_cache: bool,
+        crypt: &PageCrypt,
+    ) -> io::Result<&WikiFile> {
+        for wiki in self.wikis.iter_mut() {
+            wiki.files.retain(|f| f.path != path);
+        }
+        self.load_file(path, syntax, cache, no_cache, crypt)
+    }
+
+    /// Builds a graph of links from every loaded file, treating the first
+    /// loaded wiki as the primary wiki
+    pub fn to_link_graph(&self, config: &HtmlConfig) -> LinkGraph {
+        let mut graph = LinkGraph::new();
+
+        if let Some(wiki) = self.wikis.first() {
+            graph = graph.with_primary_wiki(wiki.index as u32);
+
+            if let Some(wiki_config) = config.wikis.get(wiki.index) {
+                graph = graph
+                    .with_diary_rel_path(
+                        wiki_config
+                            .diary_rel_path
+                            .to_string_lossy()
+                            .replace('\\', "/"),
+                    )
+                    .with_link_spaces(wiki_config.link_spaces);
+            }
+        }
+
+        for (index, wiki_config) in config.wikis.iter().enumerate() {
+            if let Some(name) = wiki_config.name.as_ref() {
+                graph = graph.with_wiki_name(name, index as u32);
+            }
+        }
+
+        for wiki in self.wikis.iter() {
+            for file in wiki.files.iter() {
+                match file.page_name(wiki.path.as_path()) {
+                    Some(name) => graph.add_page(
+                        Some(wiki.index as u32),
+                        &name,
+                        &file.data,
+                    ),
+                    None => {
+                        warn!("{:?} is not within {:?}", file.path, wiki.path)
+                    }
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Builds an index of dated tasks from every loaded file
+    pub fn to_task_index(&self) -> TaskIndex {
+        let mut index = TaskIndex::new();
+
+        for wiki in self.wikis.iter() {
+            for file in wiki.files.iter() {
+                match file.page_name(wiki.path.as_path()) {
+                    Some(name) => index.add_page(&name, &file.data),
+                    None => {
+                        warn!("{:?} is not within {:?}", file.path, wiki.path)
+                    }
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Builds an index of pages, tags, and tasks from every loaded file to
+    /// evaluate the queries of dynamic blocks
+    pub fn to_query_index(&self) -> QueryIndex {
+        let mut index = QueryIndex::new();
+
+        for wiki in self.wikis.iter() {
+            for file in wiki.files.iter() {
+                match file.page_name(wiki.path.as_path()) {
+                    Some(name) => index.add_page(&name, &file.data),
+                    None => {
+                        warn!("{:?} is not within {:?}", file.path, wiki.path)
+                    }
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Computes statistics about the pages of each loaded wiki, using the
+    /// time each file was last modified on disk
+    pub fn to_wiki_stats(&self) -> Vec<(&Wiki, WikiStats)> {
+        let mut all_stats = Vec::new();
+
+        for wiki in self.wikis.iter() {
+            let mut stats = WikiStats::new();
+            for file in wiki.files.iter() {
+                match file.page_name(wiki.path.as_path()) {
+                    Some(name) => stats.add_page(
+                        &name,
+                        PageStats::from_page(&file.data).with_last_modified(
+                            fs::metadata(&file.path)
+                                .and_then(|x| x.modified())
+                                .ok()
+                                .map(Timestamp::from),
+                        ),
+                    ),
+                    None => {
+                        warn!("{:?} is not within {:?}", file.path, wiki.path)
+                    }
+                }
+            }
+            all_stats.push((wiki, stats));
+        }
+
+        all_stats
+    }
+
     /// Finds first file that matches a loaded wiki file path
     pub fn find_file_by_path<P: AsRef<Path>>(
         &self,
@@ -97,20 +242,43 @@ pub struct WikiFile {
     pub path: PathBuf,
     pub checksum: String,
     pub data: Page<'static>,
+
+    /// Whether or not the file was decrypted to produce the page
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 impl WikiFile {
-    pub fn load(path: &Path, cache: &Path, no_cache: bool) -> io::Result<Self> {
-        load_wiki_file(path, cache, no_cache)
+    pub fn load(
+        path: &Path,
+        syntax: &ParserConfig,
+        cache: &Path,
+        no_cache: bool,
+        crypt: &PageCrypt,
+    ) -> io::Result<Self> {
+        load_wiki_file(path, syntax, cache, no_cache, crypt)
+    }
+
+    /// Produces the name of the file's page as its path relative to the wiki
+    /// root, using `/` as the separator and excluding the extension (and any
+    /// encrypted extension)
+    pub fn page_name(&self, root: &Path) -> Option<String> {
+        if self.encrypted {
+            graph::page_name(root, &self.path.with_extension(""))
+        } else {
+            graph::page_name(root, &self.path)
+        }
     }
 }
 
 fn load_ast(
     config: &HtmlConfig,
+    syntax: &ParserConfig,
     include: &[IndexOrName],
     cache: &Path,
     no_cache: bool,
     no_prune_cache: bool,
+    crypt: &PageCrypt,
 ) -> io::Result<Ast> {
     trace!(
         "load_ast(_, include = {:?}, cache = {:?}, no_cache = {}, no_prune_cache = {})",
@@ -154,11 +322,20 @@ fn load_ast(
             .filter_map(|e| e.ok())
             .filter(|e| {
                 e.file_type().is_file()
-                    && e.path().extension().and_then(OsStr::to_str)
+                    && crypt
+                        .strip_encrypted_extension(e.path())
+                        .extension()
+                        .and_then(OsStr::to_str)
                         == Some(wiki.ext.as_str())
             })
         {
-            let file = load_wiki_file(entry.path(), cache, no_cache)?;
+            if crypt.is_encrypted(entry.path()) && !crypt.can_decrypt() {
+                warn!("{:?} :: skipped as encrypted", entry.path());
+                continue;
+            }
+
+            let file =
+                load_wiki_file(entry.path(), syntax, cache, no_cache, crypt)?;
             if let Some(wiki) = ast.wikis.get_mut(index) {
                 wiki.files.push(file);
             }
@@ -212,8 +389,10 @@ fn load_ast(
 
 fn load_wiki_file(
     path: &Path,
+    syntax: &ParserConfig,
     cache: &Path,
     no_cache: bool,
+    crypt: &PageCrypt,
 ) -> io::Result<WikiFile> {
     trace!(
         "load_wiki_file(path = {:?}, cache = {:?}, no_cache = {})",
@@ -222,12 +401,39 @@ fn load_wiki_file(
         no_cache
     );
 
+    // Encrypted pages are decrypted in memory and bypass the cache entirely
+    // so that their contents never touch the disk
+    let encrypted = crypt.is_encrypted(path);
+    let no_cache = no_cache || encrypted;
+
     // Load the file contents and calculate the checksum to see how it
     // compares to our cached version
-    let text = fs::read_to_string(path)?;
+    let text = if encrypted {
+        let bytes = fs::read(path)?;
+        debug!("{:?} :: encrypted bytes loaded", path);
+        crypt.decrypt(path, &bytes)?
+    } else {
+        let reader = io::BufReader::new(fs::File::open(path)?);
+        let (text, encoding) = sources::read_to_string(reader)?;
+        debug!("{:?} :: decoded as {:?}", path, encoding);
+        text
+    };
     debug!("{:?} :: text loaded", path);
 
-    let checksum = format!("{:x}", Sha1::digest(text.as_bytes()));
+    // Custom uri schemes, keywords, and email autolinks change how pages are
+    // parsed, so pages cached with different syntax settings are not reused
+    let mut hasher = Sha1::new();
+    hasher.update(text.as_bytes());
+    for scheme in schemes::registered() {
+        hasher.update(scheme.as_bytes());
+    }
+    for keyword in syntax.keywords.iter() {
+        hasher.update(keyword.as_bytes());
+    }
+    if syntax.email_autolinks {
+        hasher.update(b"email_autolinks");
+    }
+    let checksum = format!("{:x}", hasher.finalize());
     debug!("{:?} :: checksum = {}", path, checksum);
 
     let cached_page: Option<Page> = if !no_cache {
@@ -276,7 +482,7 @@ fn load_wiki_file(
         page
     } else {
         Language::from_vimwiki_str(&text)
-            .parse::<Page>()
+            .parse_with_config::<Page>(syntax)
             .map(Page::into_owned)
             .map_err(|x| {
                 io::Error::new(io::ErrorKind::InvalidData, x.to_string())
@@ -284,7 +490,7 @@ fn load_wiki_file(
     };
 
     // Update our cache with the new file; old files get cleaned later
-    if !has_cached_page {
+    if !has_cached_page && !no_cache {
         let cache_file_path = cache.join(checksum.as_str());
         match fs::File::create(cache_file_path.as_path()) {
             Ok(file) => {
@@ -318,5 +524,6 @@ fn load_wiki_file(
         path: path.to_path_buf(),
         checksum,
         data: page,
+        encrypted,
     })
 }
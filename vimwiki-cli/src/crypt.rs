@@ -0,0 +1,167 @@
+use log::*;
+use std::{
+    ffi::OsStr,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// Callback that transforms the bytes of the file at the given path
+pub type CryptFn = dyn Fn(&Path, &[u8]) -> io::Result<Vec<u8>> + Send + Sync;
+
+/// Decrypts and encrypts pages whose files end with one of the encrypted
+/// extensions (e.g. `page.wiki.gpg`), delegating the actual cryptography to
+/// user-supplied callbacks
+#[derive(Default)]
+pub struct PageCrypt {
+    extensions: Vec<String>,
+    decrypt: Option<Box<CryptFn>>,
+    encrypt: Option<Box<CryptFn>>,
+}
+
+impl PageCrypt {
+    /// Creates a new instance that treats files ending with any of the
+    /// extensions as encrypted
+    pub fn new(extensions: Vec<String>) -> Self {
+        Self {
+            extensions,
+            decrypt: None,
+            encrypt: None,
+        }
+    }
+
+    /// Sets the callback used to decrypt the bytes of an encrypted page
+    pub fn with_decrypt<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Path, &[u8]) -> io::Result<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.decrypt = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the callback used to encrypt the bytes of a page before it is
+    /// written back to an encrypted file
+    pub fn with_encrypt<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Path, &[u8]) -> io::Result<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.encrypt = Some(Box::new(f));
+        self
+    }
+
+    /// Creates a new instance whose callbacks pipe a file's bytes through the
+    /// given commands (e.g. `gpg --decrypt --quiet`), where each command is
+    /// split on whitespace and run with the path of the file available as
+    /// `VIMWIKI_PAGE_PATH`
+    pub fn from_commands(
+        extensions: Vec<String>,
+        decrypt_cmd: Option<String>,
+        encrypt_cmd: Option<String>,
+    ) -> Self {
+        let mut crypt = Self::new(extensions);
+
+        if let Some(cmd) = decrypt_cmd {
+            crypt = crypt.with_decrypt(move |path, bytes| {
+                pipe_through_command(&cmd, path, bytes)
+            });
+        }
+
+        if let Some(cmd) = encrypt_cmd {
+            crypt = crypt.with_encrypt(move |path, bytes| {
+                pipe_through_command(&cmd, path, bytes)
+            });
+        }
+
+        crypt
+    }
+
+    /// Returns true if a callback is available to decrypt pages
+    pub fn can_decrypt(&self) -> bool {
+        self.decrypt.is_some()
+    }
+
+    /// Returns true if the file at the path is considered encrypted
+    pub fn is_encrypted(&self, path: &Path) -> bool {
+        matches!(
+            path.extension().and_then(OsStr::to_str),
+            Some(ext) if self.extensions.iter().any(|x| x == ext)
+        )
+    }
+
+    /// Returns the path without its encrypted extension, or the path as-is
+    /// if it is not encrypted
+    pub fn strip_encrypted_extension(&self, path: &Path) -> PathBuf {
+        if self.is_encrypted(path) {
+            path.with_extension("")
+        } else {
+            path.to_path_buf()
+        }
+    }
+
+    /// Decrypts the bytes of the file at the path into text
+    pub fn decrypt(&self, path: &Path, bytes: &[u8]) -> io::Result<String> {
+        let decrypt = self.decrypt.as_ref().ok_or_else(|| {
+            io::Error::other(format!(
+                "{:?} is encrypted, but no decrypt is available",
+                path
+            ))
+        })?;
+
+        String::from_utf8(decrypt(path, bytes)?)
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))
+    }
+
+    /// Encrypts text to be written to the file at the path
+    pub fn encrypt(&self, path: &Path, text: &str) -> io::Result<Vec<u8>> {
+        let encrypt = self.encrypt.as_ref().ok_or_else(|| {
+            io::Error::other(format!(
+                "{:?} is encrypted, but no encrypt is available",
+                path
+            ))
+        })?;
+
+        encrypt(path, text.as_bytes())
+    }
+}
+
+fn pipe_through_command(
+    cmd: &str,
+    path: &Path,
+    bytes: &[u8],
+) -> io::Result<Vec<u8>> {
+    let mut args = cmd.split_whitespace();
+    let program = args.next().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "Empty crypt command")
+    })?;
+
+    debug!("{:?} :: piping through {}", path, program);
+    let mut child = Command::new(program)
+        .args(args)
+        .env("VIMWIKI_PAGE_PATH", path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    // Write on a separate thread so that a command producing large output
+    // does not block waiting on us to read it
+    let mut stdin = child.stdin.take().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::BrokenPipe, "Crypt command has no stdin")
+    })?;
+    let input = bytes.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = child.wait_with_output()?;
+    writer
+        .join()
+        .map_err(|_| io::Error::other("Crypt command writer panicked"))??;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(io::Error::other(format!(
+            "{} exited with {}",
+            program, output.status
+        )))
+    }
+}
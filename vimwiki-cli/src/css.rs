@@ -1,3 +1,7 @@
+use log::*;
+use std::{fs, io, path::Path};
+use walkdir::WalkDir;
+
 /// Default css styles provided by vimwiki
 pub static DEFAULT_STYLE_FILE: &str = r#"
 body {
@@ -188,3 +192,131 @@ code {
   background-color: #f9f9f9;
 }
 "#;
+
+/// Css layered on top of the default styles to use light text on a dark
+/// background
+static DARK_STYLE_OVERRIDES: &str = r#"
+body {
+  background-color: #1e1e1e;
+  color: #d4d4d4;
+}
+
+h1 {color: #ffffff;}
+h2 {color: #e0e0e0;}
+h3 {color: #c8c8c8;}
+h4 {color: #b0b0b0;}
+h5 {color: #a0a0a0;}
+h6 {color: #989898;}
+
+pre, code {
+  background-color: #2d2d2d;
+  border-color: #444444;
+  color: #d4d4d4;
+}
+
+blockquote { color: #a0a0a0; border-left-color: #444444; }
+th { background-color: #2d2d2d; }
+th, td, hr { border-color: #444444; }
+.tag { background-color: #333333; }
+li > a { color: #4fa3ff; }
+"#;
+
+/// Plain css without colors or decorations beyond what keeps pages readable
+static MINIMAL_STYLE_FILE: &str = r#"
+body {
+  font-family: sans-serif;
+  max-width: 50em;
+  margin: 2em auto;
+  padding: 0 1em;
+  line-height: 1.5;
+}
+
+pre, code { font-family: monospace; }
+pre { overflow-x: auto; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.3em; }
+.toc li { list-style-type: none; }
+.todo { font-weight: bold; }
+.justleft { text-align: left; }
+.justright { text-align: right; }
+.justcenter { text-align: center; }
+"#;
+
+/// Represents a css theme bundled with vimwiki
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Name used to select the theme
+    pub name: &'static str,
+
+    /// Short summary of how the theme looks
+    pub description: &'static str,
+
+    /// Pieces of css that are joined together to form the theme
+    parts: &'static [&'static str],
+}
+
+impl Theme {
+    /// Returns the complete css of the theme
+    pub fn css(&self) -> String {
+        self.parts.concat()
+    }
+}
+
+/// Themes bundled with vimwiki, where the first is the default
+pub static THEMES: &[Theme] = &[
+    Theme {
+        name: "default",
+        description: "Styles provided by vimwiki",
+        parts: &[DEFAULT_STYLE_FILE],
+    },
+    Theme {
+        name: "dark",
+        description: "Styles provided by vimwiki with a dark background",
+        parts: &[DEFAULT_STYLE_FILE, DARK_STYLE_OVERRIDES],
+    },
+    Theme {
+        name: "minimal",
+        description: "Plain, narrow layout with few colors",
+        parts: &[MINIMAL_STYLE_FILE],
+    },
+];
+
+/// Loads the css of the bundled theme with the given name, or of the css
+/// file at the given path if no bundled theme has that name
+pub fn load_theme(name_or_path: &str) -> io::Result<String> {
+    match THEMES.iter().find(|theme| theme.name == name_or_path) {
+        Some(theme) => Ok(theme.css()),
+        None if Path::new(name_or_path).is_file() => {
+            fs::read_to_string(name_or_path)
+        }
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Unknown theme: {}", name_or_path),
+        )),
+    }
+}
+
+/// Copies every file within the assets directory (such as css, js, or
+/// images) into the output directory, keeping their relative paths and
+/// replacing files that already exist
+pub fn copy_assets(assets: &Path, output: &Path) -> io::Result<()> {
+    for entry in WalkDir::new(assets).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let rel_path = entry
+            .path()
+            .strip_prefix(assets)
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?;
+        let path = output.join(rel_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        debug!("Copying {:?} to {:?}", entry.path(), path);
+        fs::copy(entry.path(), path)?;
+    }
+
+    Ok(())
+}
@@ -1,14 +1,17 @@
 mod ast;
+mod crypt;
 mod css;
+mod manifest;
 mod opt;
 mod subcommand;
 mod utils;
 
 use ast::Ast;
+pub use crypt::PageCrypt;
 use log::*;
 use std::path::PathBuf;
 use structopt::StructOpt;
-use vimwiki::{HtmlConfig, VimwikiConfig};
+use vimwiki::{settings::Settings, HtmlConfig, VimwikiConfig};
 
 pub use opt::*;
 
@@ -31,33 +34,159 @@ pub fn load_opt_from_args() -> Opt {
 
 /// Runs the CLI using the provided options, returning success if completed
 /// or an error containing the appropriate exit code to return via [`Exitcodes::exit`]
-pub fn run(opt: Opt) -> Result<(), ExitCodes> {
+pub fn run(mut opt: Opt) -> Result<(), ExitCodes> {
     #[cfg(feature = "timekeeper")]
     let timekeeper = opt.common.timekeeper;
 
+    #[cfg(feature = "timekeeper")]
+    let timekeeper_format = opt.common.timekeeper_format;
+
+    #[cfg(feature = "timekeeper")]
+    let timekeeper_output = opt.common.timekeeper_output.clone();
+
     #[cfg(feature = "timekeeper")]
     if timekeeper {
         vimwiki::timekeeper::enable();
     }
 
+    if opt.subcommand.has_json_flag() {
+        warn!("--json is deprecated, use --format json instead");
+    }
+
+    let settings = load_settings(&mut opt.common)?;
+
     let res = match opt.subcommand {
-        Subcommand::Convert(cmd) => {
+        Subcommand::Cat(cmd) => {
+            let extra_paths: Vec<PathBuf> =
+                cmd.page_path().into_iter().collect();
             let (config, ast) =
-                load_html_config_and_ast(&opt.common, &cmd.extra_paths)?;
-            subcommand::convert(cmd, opt.common, config, ast)
+                load_html_config_and_ast(&opt.common, &settings, &extra_paths)?;
+            subcommand::cat(cmd, opt.common, config, ast)
+        }
+        Subcommand::Check(mut cmd) => {
+            let lint = &settings.lint;
+            cmd.stub_threshold = cmd.stub_threshold.or(lint.stub_threshold);
+            cmd.no_links |= lint.links == Some(false);
+            cmd.no_format |= lint.format == Some(false);
+            let config = load_html_config(&opt.common, &settings)?;
+            let format_config = load_format_config(&opt.common, &settings)?;
+            subcommand::check(cmd, opt.common, config, format_config)
+        }
+        Subcommand::Completions(cmd) => {
+            subcommand::completions(cmd, opt.common)
+        }
+        Subcommand::Convert(cmd) => {
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::convert(cmd, opt.common, config, ast, &settings.syntax)
+        }
+        Subcommand::Daemon(cmd) => {
+            subcommand::daemon(cmd, opt.common, &settings.server)
+        }
+        Subcommand::Export(cmd) => {
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::export(cmd, opt.common, config, ast)
         }
         Subcommand::Format(cmd) => {
-            let config = load_format_config(&opt.common)?;
+            let config = load_format_config(&opt.common, &settings)?;
             subcommand::format(cmd, opt.common, config)
         }
+        Subcommand::Graph(cmd) => {
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::graph(cmd, opt.common, config, ast)
+        }
+        Subcommand::Ics(cmd) => {
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::ics(cmd, opt.common, config, ast)
+        }
+        Subcommand::Import(cmd) => subcommand::import(cmd, opt.common),
+        Subcommand::Keywords(cmd) => {
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::keywords(cmd, opt.common, config, ast)
+        }
+        Subcommand::Lint(mut cmd) => {
+            let lint = &settings.lint;
+            cmd.index = cmd.index.or_else(|| lint.index.clone());
+            cmd.stub_threshold = cmd.stub_threshold.or(lint.stub_threshold);
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::lint(cmd, opt.common, config, ast)
+        }
+        Subcommand::Man(cmd) => subcommand::man(cmd, opt.common),
+        Subcommand::Merge(cmd) => subcommand::merge(cmd, opt.common),
+        Subcommand::Refresh(cmd) => {
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::refresh(cmd, opt.common, config, ast)
+        }
         Subcommand::Serve(cmd) => {
-            let (config, ast) =
-                load_html_config_and_ast(&opt.common, &cmd.extra_paths)?;
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
             subcommand::serve(cmd, opt.common, config, ast)
         }
+        Subcommand::Stats(cmd) => {
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::stats(cmd, opt.common, config, ast)
+        }
+        Subcommand::Tasks(cmd) => {
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::tasks(cmd, opt.common, config, ast)
+        }
+        Subcommand::Theme(cmd) => {
+            let config = load_html_config(&opt.common, &settings)?;
+            subcommand::theme(cmd, opt.common, config)
+        }
+        #[cfg(feature = "tui")]
+        Subcommand::Tui(cmd) => {
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
+            subcommand::tui(cmd, opt.common, config, ast)
+        }
         Subcommand::Inspect(cmd) => {
-            let (config, ast) =
-                load_html_config_and_ast(&opt.common, &cmd.extra_paths)?;
+            let (config, ast) = load_html_config_and_ast(
+                &opt.common,
+                &settings,
+                &cmd.extra_paths,
+            )?;
             subcommand::inspect(cmd, opt.common, config, ast)
         }
     };
@@ -65,7 +194,11 @@ pub fn run(opt: Opt) -> Result<(), ExitCodes> {
     #[cfg(feature = "timekeeper")]
     if timekeeper {
         vimwiki::timekeeper::disable();
-        vimwiki::timekeeper::print_report(true);
+        if let Err(x) =
+            report_timekeeper(timekeeper_format, timekeeper_output.as_deref())
+        {
+            error!("Failed to report timekeeper results: {}", x);
+        }
     }
 
     if let Err(x) = res {
@@ -76,22 +209,84 @@ pub fn run(opt: Opt) -> Result<(), ExitCodes> {
     Ok(())
 }
 
-fn load_format_config(opt: &CommonOpt) -> Result<VimwikiConfig, ExitCodes> {
+/// Writes the timekeeper's results in the specified format, clearing the
+/// timekeeper's memory afterwards
+#[cfg(feature = "timekeeper")]
+fn report_timekeeper(
+    format: TimekeeperFormat,
+    output: Option<&std::path::Path>,
+) -> std::io::Result<()> {
+    use vimwiki::timekeeper;
+
+    let profile = timekeeper::profile();
+    timekeeper::clear();
+
+    let report = match format {
+        TimekeeperFormat::Text => profile.to_string(),
+        TimekeeperFormat::Json => serde_json::to_string_pretty(&profile)?,
+        TimekeeperFormat::Folded => profile.to_folded_stacks(),
+    };
+
+    if let Some(path) = output {
+        std::fs::write(path, report)
+    } else {
+        println!("{}", report);
+        Ok(())
+    }
+}
+
+/// Loads the layered settings files for the current directory, filling in
+/// any common options that were not provided with their settings
+fn load_settings(opt: &mut CommonOpt) -> Result<Settings, ExitCodes> {
+    let settings = std::env::current_dir()
+        .and_then(|dir| Settings::load(&dir))
+        .map_err(|x| {
+            error!("Failed to load settings: {}", x);
+            ExitCodes::FailedToLoadConfig
+        })?;
+
+    if opt.format.is_none() {
+        if let Some(format) = settings.output.format.as_deref() {
+            opt.format = Some(format.parse().map_err(|x| {
+                error!("Failed to load settings: {}", x);
+                ExitCodes::FailedToLoadConfig
+            })?);
+        }
+    }
+
+    Ok(settings)
+}
+
+fn load_format_config(
+    opt: &CommonOpt,
+    settings: &Settings,
+) -> Result<VimwikiConfig, ExitCodes> {
     if let Some(path) = opt.config.as_ref() {
         utils::load_format_config(path).map_err(|x| {
             error!("Failed to load config: {}", x);
             ExitCodes::FailedToLoadConfig
         })
     } else {
-        Ok(VimwikiConfig::default())
+        Ok(settings.format.clone())
     }
 }
 
+fn load_html_config(
+    opt: &CommonOpt,
+    settings: &Settings,
+) -> Result<HtmlConfig, ExitCodes> {
+    utils::load_html_config(opt, settings, &[]).map_err(|x| {
+        error!("Failed to load config: {}", x);
+        ExitCodes::FailedToLoadConfig
+    })
+}
+
 fn load_html_config_and_ast(
     opt: &CommonOpt,
+    settings: &Settings,
     extra_paths: &[PathBuf],
 ) -> Result<(HtmlConfig, Ast), ExitCodes> {
-    let config = match utils::load_html_config(opt, extra_paths) {
+    let config = match utils::load_html_config(opt, settings, extra_paths) {
         Ok(config) => config,
         Err(x) => {
             error!("Failed to load config: {}", x);
@@ -101,10 +296,12 @@ fn load_html_config_and_ast(
 
     let ast = match Ast::load(
         &config,
+        &settings.syntax,
         &opt.include,
         &opt.cache,
         opt.no_cache,
         opt.no_prune_cache,
+        &opt.to_page_crypt(),
     ) {
         Ok(ast) => ast,
         Err(x) => {
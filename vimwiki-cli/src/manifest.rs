@@ -0,0 +1,152 @@
+use log::*;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Represents the record of how each page was last converted, used to skip
+/// pages whose html would not change
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuildManifest {
+    /// Fingerprint of everything shared by all pages (configuration, flags,
+    /// and so on) that was used to convert the pages
+    fingerprint: String,
+
+    /// Record of each converted page keyed by the path to its file
+    pages: HashMap<PathBuf, BuildEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BuildEntry {
+    /// Checksum of the page's file when it was converted
+    checksum: String,
+
+    /// Path to the html written for the page
+    output: PathBuf,
+
+    /// Paths to other files whose contents affect the page's html alongside
+    /// their checksums, or None if they did not exist
+    dependencies: Vec<(PathBuf, Option<String>)>,
+}
+
+impl BuildManifest {
+    /// Creates an empty manifest for pages converted using the fingerprint
+    pub fn new(fingerprint: &str) -> Self {
+        Self {
+            fingerprint: fingerprint.to_string(),
+            pages: HashMap::new(),
+        }
+    }
+
+    /// Returns the path to the manifest of the pages found at the input path
+    /// within the output directory, where each input path has its own
+    /// manifest as several can share an output directory
+    pub fn path_for(output_dir: &Path, input_path: &Path) -> PathBuf {
+        let mut hasher = Sha1::new();
+        hasher.update(input_path.to_string_lossy().as_bytes());
+        output_dir.join(format!(".vimwiki-build-{:x}.json", hasher.finalize()))
+    }
+
+    /// Loads the manifest at the given path, starting over with an empty
+    /// manifest if it is missing, unreadable, or has a different fingerprint
+    pub fn load(path: &Path, fingerprint: &str) -> Self {
+        let manifest = fs::read(path)
+            .ok()
+            .and_then(|x| serde_json::from_slice::<Self>(&x).ok())
+            .filter(|x| x.fingerprint == fingerprint);
+
+        match manifest {
+            Some(manifest) => manifest,
+            None => {
+                debug!("{:?} :: starting new build manifest", path);
+                Self::new(fingerprint)
+            }
+        }
+    }
+
+    /// Writes the manifest to the given path
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec(self)?)
+    }
+
+    /// Returns true if the page with the given checksum was already converted
+    /// to the output path and none of its dependencies have changed since
+    pub fn is_fresh(&self, page: &Path, checksum: &str, output: &Path) -> bool {
+        match self.pages.get(page) {
+            Some(entry) => {
+                entry.checksum == checksum
+                    && entry.output == output
+                    && output.is_file()
+                    && entry.dependencies.iter().all(|(path, checksum)| {
+                        &file_checksum(path) == checksum
+                    })
+            }
+            None => false,
+        }
+    }
+
+    /// Records that the page with the given checksum was converted to the
+    /// output path using the given dependencies
+    pub fn update(
+        &mut self,
+        page: &Path,
+        checksum: &str,
+        output: &Path,
+        dependencies: Vec<PathBuf>,
+    ) {
+        self.pages.insert(
+            page.to_path_buf(),
+            BuildEntry {
+                checksum: checksum.to_string(),
+                output: output.to_path_buf(),
+                dependencies: dependencies
+                    .into_iter()
+                    .map(|path| {
+                        let checksum = file_checksum(&path);
+                        (path, checksum)
+                    })
+                    .collect(),
+            },
+        );
+    }
+
+    /// Forgets every page that is not one of the given pages, removing the
+    /// html previously written for each of them and returning how many pages
+    /// were forgotten
+    pub fn retain(&mut self, pages: &[PathBuf]) -> usize {
+        let removed: Vec<PathBuf> = self
+            .pages
+            .keys()
+            .filter(|x| !pages.contains(x))
+            .cloned()
+            .collect();
+
+        for page in removed.iter() {
+            if let Some(entry) = self.pages.remove(page) {
+                info!("Removing {:?}", entry.output);
+                if let Err(x) = fs::remove_file(&entry.output) {
+                    if x.kind() != io::ErrorKind::NotFound {
+                        error!("Failed to remove {:?}: {}", entry.output, x);
+                    }
+                }
+            }
+        }
+
+        removed.len()
+    }
+}
+
+/// Produces a checksum (sha1) of the contents of the file at the path, or
+/// None if the file cannot be read
+fn file_checksum(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    let mut hasher = Sha1::new();
+    hasher.update(&bytes);
+    Some(format!("{:x}", hasher.finalize()))
+}
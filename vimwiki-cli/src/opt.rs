@@ -1,7 +1,9 @@
+use crate::PageCrypt;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use std::path::PathBuf;
 use structopt::StructOpt;
+use vimwiki::{import::ImportFormat, WikiDate};
 
 lazy_static! {
     static ref DEFAULT_CACHE_DIR: String =
@@ -35,6 +37,13 @@ pub struct CommonOpt {
     #[structopt(short, long, global = true)]
     pub timestamp: Option<stderrlog::Timestamp>,
 
+    /// Format of the output of subcommands that report information (text,
+    /// json), where json produces structured output suitable for scripts
+    /// and editors; defaults to the output format of the settings files or
+    /// text
+    #[structopt(long, global = true)]
+    pub format: Option<OutputFormat>,
+
     /// Directory where cache information is stored
     #[structopt(long, default_value = &DEFAULT_CACHE_DIR, global = true)]
     pub cache: PathBuf,
@@ -47,10 +56,32 @@ pub struct CommonOpt {
     #[structopt(long, global = true)]
     pub no_prune_cache: bool,
 
-    /// Path to config file
+    /// Path to config file, used in place of the html and format settings
+    /// loaded from the system, user, and project vimwiki.toml files
     #[structopt(short, long, global = true)]
     pub config: Option<PathBuf>,
 
+    /// Command that decrypts an encrypted page, receiving the encrypted bytes
+    /// on stdin and writing the page's text to stdout (e.g. `gpg --decrypt
+    /// --quiet`); encrypted pages are never written to the cache
+    #[structopt(long, global = true)]
+    pub decrypt_cmd: Option<String>,
+
+    /// Command that encrypts a page, receiving the page's text on stdin and
+    /// writing the encrypted bytes to stdout (e.g. `gpg --encrypt -r ME`)
+    #[structopt(long, global = true)]
+    pub encrypt_cmd: Option<String>,
+
+    /// Extensions that follow a page's extension to mark it as encrypted
+    /// (e.g. page.wiki.gpg)
+    #[structopt(
+        long = "encrypted-ext",
+        default_value = "gpg,age",
+        use_delimiter = true,
+        global = true
+    )]
+    pub encrypted_extensions: Vec<String>,
+
     /// If specified, will attempt to merge wikis loaded from vim/neovim
     /// with wikis defined via a config file if accessible. Wikis from
     /// vim/neovim will be first such that their indexes align with those
@@ -71,9 +102,35 @@ pub struct CommonOpt {
     #[cfg(feature = "timekeeper")]
     #[structopt(long, global = true)]
     pub timekeeper: bool,
+
+    /// Format of the timekeeper report (text, json, folded) where folded
+    /// produces stacks that can be fed into flamegraph tooling
+    #[cfg(feature = "timekeeper")]
+    #[structopt(long, default_value = "text", global = true)]
+    pub timekeeper_format: TimekeeperFormat,
+
+    /// If specified, writes the timekeeper report to the given file instead
+    /// of stdout
+    #[cfg(feature = "timekeeper")]
+    #[structopt(long, global = true)]
+    pub timekeeper_output: Option<PathBuf>,
 }
 
 impl CommonOpt {
+    /// Format of the output of subcommands that report information
+    pub fn output_format(&self) -> OutputFormat {
+        self.format.unwrap_or(OutputFormat::Text)
+    }
+
+    /// Produces the means to decrypt and encrypt pages based on options
+    pub fn to_page_crypt(&self) -> PageCrypt {
+        PageCrypt::from_commands(
+            self.encrypted_extensions.clone(),
+            self.decrypt_cmd.clone(),
+            self.encrypt_cmd.clone(),
+        )
+    }
+
     /// Filter for wikis to process, defaulting to every wiki unless given a
     /// filter of wikis to include
     pub fn filter_by_wiki_idx_and_name(
@@ -88,20 +145,103 @@ impl CommonOpt {
 
 #[derive(Debug, StructOpt)]
 pub enum Subcommand {
+    Cat(CatSubcommand),
+    Check(CheckSubcommand),
+    Completions(CompletionsSubcommand),
     Convert(ConvertSubcommand),
+    Daemon(DaemonSubcommand),
+    Export(ExportSubcommand),
     Format(FormatSubcommand),
+    Graph(GraphSubcommand),
+    Ics(IcsSubcommand),
+    Import(ImportSubcommand),
     Inspect(InspectSubcommand),
+    Keywords(KeywordsSubcommand),
+    Lint(LintSubcommand),
+    Man(ManSubcommand),
+    Merge(MergeSubcommand),
+    Refresh(RefreshSubcommand),
     Serve(ServeSubcommand),
+    Stats(StatsSubcommand),
+    Tasks(TasksSubcommand),
+    Theme(ThemeSubcommand),
+
+    #[cfg(feature = "tui")]
+    Tui(TuiSubcommand),
 }
 
 impl Subcommand {
     /// Returns extra paths to process
     pub fn extra_paths(&self) -> &[PathBuf] {
         match self {
+            Self::Cat(_) => &[],
+            Self::Check(x) => &x.paths,
+            Self::Completions(_) => &[],
             Self::Convert(x) => &x.extra_paths,
+            Self::Daemon(_) => &[],
+            Self::Export(x) => &x.extra_paths,
             Self::Format(x) => &x.paths,
+            Self::Graph(x) => &x.extra_paths,
+            Self::Ics(x) => &x.extra_paths,
+            Self::Import(_) => &[],
             Self::Inspect(x) => &x.extra_paths,
+            Self::Keywords(x) => &x.extra_paths,
+            Self::Lint(x) => &x.extra_paths,
+            Self::Man(_) => &[],
+            Self::Merge(_) => &[],
+            Self::Refresh(x) => &x.extra_paths,
             Self::Serve(x) => &x.extra_paths,
+            Self::Stats(x) => &x.extra_paths,
+            Self::Tasks(x) => &x.extra_paths,
+            Self::Theme(_) => &[],
+
+            #[cfg(feature = "tui")]
+            Self::Tui(x) => &x.extra_paths,
+        }
+    }
+
+    /// Returns true if the deprecated `--json` flag of the subcommand was
+    /// provided
+    pub fn has_json_flag(&self) -> bool {
+        match self {
+            Self::Check(x) => x.json,
+            Self::Keywords(x) => x.json,
+            Self::Lint(x) => x.json,
+            Self::Stats(x) => x.json,
+            Self::Tasks(x) => x.json,
+            _ => false,
+        }
+    }
+}
+
+/// Display a page in the terminal with colors and styles
+#[derive(Debug, StructOpt)]
+pub struct CatSubcommand {
+    /// Write the page as plain text without any colors or styles, which is
+    /// always the case when not writing to a terminal or when `NO_COLOR` is
+    /// set
+    #[structopt(long)]
+    pub plain: bool,
+
+    /// Write the target of each link after its description
+    #[structopt(long)]
+    pub link_targets: bool,
+
+    /// Path to the file of the page, or name of the page relative to the
+    /// root of its wiki and without extension (e.g. `projects/index`)
+    #[structopt(name = "PAGE")]
+    pub page: String,
+}
+
+impl CatSubcommand {
+    /// Returns the path to the file of the page if the page refers to an
+    /// existing file rather than the name of a page within a wiki
+    pub fn page_path(&self) -> Option<PathBuf> {
+        let path = PathBuf::from(&self.page);
+        if path.is_file() {
+            Some(path)
+        } else {
+            None
         }
     }
 }
@@ -118,11 +258,68 @@ pub struct ConvertSubcommand {
     #[structopt(long)]
     pub include_vimwiki_css: bool,
 
+    /// Name of a bundled theme (see `theme list`) or path to a css file to
+    /// write as the css file of the wiki's output directory, implying
+    /// `--include-vimwiki-css`
+    #[structopt(long)]
+    pub theme: Option<String>,
+
+    /// Directories of additional files (such as css or js) to copy into the
+    /// root of the wiki's output directory
+    #[structopt(long = "assets", parse(from_os_str))]
+    pub assets: Vec<PathBuf>,
+
+    /// If provided, will add breadcrumbs leading from the root of the wiki
+    /// to each page
+    #[structopt(long)]
+    pub breadcrumbs: bool,
+
+    /// If provided, will add links to the previous and next entries of each
+    /// diary page
+    #[structopt(long)]
+    pub diary_nav: bool,
+
+    /// If provided, will add a sidebar listing every page of the wiki
+    #[structopt(long)]
+    pub sidebar: bool,
+
+    /// If provided, will write a sitemap.xml of every page of the wiki to
+    /// its output directory, locating pages relative to this base url
+    #[structopt(long, name = "BASE_URL")]
+    pub sitemap: Option<String>,
+
+    /// If provided, will write a search-index.json of the plain text of
+    /// every page of the wiki to its output directory for use with
+    /// client-side search such as lunr or elasticlunr
+    #[structopt(long)]
+    pub search_index: bool,
+
+    /// If provided, will convert every page even if its html would not
+    /// change since it was last converted
+    #[structopt(long)]
+    pub force: bool,
+
+    /// If provided, will keep running and convert pages again whenever they
+    /// or the templates they use change
+    #[structopt(long, conflicts_with = "stdout")]
+    pub watch: bool,
+
     /// Additional standalone files (or directories) to process
     #[structopt(name = "PATH", parse(from_os_str))]
     pub extra_paths: Vec<PathBuf>,
 }
 
+impl ConvertSubcommand {
+    /// Returns the name or path of the theme whose css to write, if any
+    pub fn theme(&self) -> Option<&str> {
+        match self.theme.as_deref() {
+            Some(theme) => Some(theme),
+            None if self.include_vimwiki_css => Some("default"),
+            None => None,
+        }
+    }
+}
+
 /// Format vimwiki files following a configuration
 #[derive(Debug, StructOpt)]
 pub struct FormatSubcommand {
@@ -172,6 +369,449 @@ pub struct InspectSubcommand {
     pub extra_paths: Vec<PathBuf>,
 }
 
+/// Export a graph of pages and the links between them
+#[derive(Debug, StructOpt)]
+pub struct GraphSubcommand {
+    /// Writes to output file instead of stdout
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Format of the graph (dot, graphml, json), defaulting to json if the
+    /// output format is json and dot otherwise
+    #[structopt(short = "f", long)]
+    pub graph_format: Option<GraphFormat>,
+
+    /// If specified, reports metrics about the graph such as orphan and
+    /// most-linked pages instead of the graph itself
+    #[structopt(long)]
+    pub metrics: bool,
+
+    /// Maximum number of most-linked pages to report with metrics
+    #[structopt(long, default_value = "10")]
+    pub top: usize,
+
+    /// Additional standalone files (or directories) to process
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub extra_paths: Vec<PathBuf>,
+}
+
+/// Export wikis as Obsidian vaults, placing diary entries in the daily notes
+/// folder
+#[derive(Debug, StructOpt)]
+pub struct ExportSubcommand {
+    /// Directory to write the vault to, where each wiki is written to its
+    /// own subdirectory if more than one wiki is exported
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: PathBuf,
+
+    /// Folder within the vault where diary entries are placed
+    #[structopt(long, default_value = "Daily Notes")]
+    pub daily_notes_dir: String,
+
+    /// Skip copying files other than wiki pages (such as images) into the
+    /// vault
+    #[structopt(long)]
+    pub no_assets: bool,
+
+    /// Additional standalone files (or directories) to process
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub extra_paths: Vec<PathBuf>,
+}
+
+/// Export diary entries and dated tasks as an iCalendar (.ics) feed
+#[derive(Debug, StructOpt)]
+pub struct IcsSubcommand {
+    /// Writes to output file instead of stdout
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Name of the calendar shown by calendar applications
+    #[structopt(long, default_value = "vimwiki")]
+    pub name: String,
+
+    /// Exclude diary entries from the calendar
+    #[structopt(long)]
+    pub no_diary: bool,
+
+    /// Exclude dated tasks from the calendar
+    #[structopt(long)]
+    pub no_tasks: bool,
+
+    /// Additional standalone files (or directories) to process
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub extra_paths: Vec<PathBuf>,
+}
+
+/// Convert files from other formats (such as markdown or org) into vimwiki
+/// files
+#[derive(Debug, StructOpt)]
+pub struct ImportSubcommand {
+    /// Format of the files to import (markdown or org), otherwise determined by
+    /// each file's extension
+    #[structopt(long)]
+    pub from: Option<ImportFormat>,
+
+    /// Directory to write converted files to, mirroring the structure of
+    /// imported directories, instead of alongside the original files
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Extension to give converted files
+    #[structopt(long, default_value = "wiki")]
+    pub ext: String,
+
+    /// Write converted text to stdout instead of the file system
+    #[structopt(long)]
+    pub stdout: bool,
+
+    /// Overwrite converted files that already exist
+    #[structopt(long)]
+    pub force: bool,
+
+    /// Files (or directories) to import
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub paths: Vec<PathBuf>,
+}
+
+/// Merge two versions of a page with their common base, comparing the pages
+/// block by block (usable as a git merge driver via `%O %A %B`)
+#[derive(Debug, StructOpt)]
+pub struct MergeSubcommand {
+    /// Write the merged page to stdout instead of overwriting our version
+    #[structopt(long)]
+    pub stdout: bool,
+
+    /// Common base version of the page
+    #[structopt(parse(from_os_str))]
+    pub base: PathBuf,
+
+    /// Our version of the page, which is overwritten with the merged page
+    #[structopt(parse(from_os_str))]
+    pub ours: PathBuf,
+
+    /// Their version of the page
+    #[structopt(parse(from_os_str))]
+    pub theirs: PathBuf,
+}
+
+/// List every keyword (TODO, FIXME, XXX, etc) within the pages of each wiki
+#[derive(Debug, StructOpt)]
+pub struct KeywordsSubcommand {
+    /// Writes to output file instead of stdout
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Produce the report as json instead of text (deprecated, use
+    /// `--format json` instead)
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Only report the given keyword (case insensitive), which can be
+    /// provided multiple times
+    #[structopt(short, long = "keyword", name = "KEYWORD")]
+    pub keywords: Vec<String>,
+
+    /// Additional standalone files (or directories) to process
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub extra_paths: Vec<PathBuf>,
+}
+
+/// Check files for problems such as broken links and unformatted text,
+/// failing if any are found so it can be used as a pre-commit hook
+#[derive(Debug, StructOpt)]
+pub struct CheckSubcommand {
+    /// Writes to output file instead of stdout
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Produce the report as json instead of text (deprecated, use
+    /// `--format json` instead)
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Also check the files staged within the git repository of the current
+    /// directory
+    #[structopt(long)]
+    pub staged: bool,
+
+    /// Skip checking that links target existing files
+    #[structopt(long)]
+    pub no_links: bool,
+
+    /// Skip checking that files are formatted
+    #[structopt(long)]
+    pub no_format: bool,
+
+    /// If provided, pages with fewer bytes of content than this are reported
+    /// as stubs
+    #[structopt(long)]
+    pub stub_threshold: Option<usize>,
+
+    /// Extensions to use when searching through directories and staged files
+    #[structopt(long = "ext", default_value = "wiki")]
+    pub extensions: Vec<String>,
+
+    /// Files (or directories) to check
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub paths: Vec<PathBuf>,
+}
+
+/// Generate a script that completes the subcommands and options of the cli
+/// for a shell, written to stdout
+#[derive(Debug, StructOpt)]
+pub struct CompletionsSubcommand {
+    /// Shell to generate completions for (bash, zsh, fish, powershell,
+    /// elvish)
+    #[structopt(
+        name = "SHELL",
+        possible_values = &structopt::clap::Shell::variants(),
+        case_insensitive = true
+    )]
+    pub shell: structopt::clap::Shell,
+}
+
+/// Start, stop, or check on the vimwiki-server shared by editors working
+/// within the current workspace, which listens on a unix socket
+#[derive(Debug, StructOpt)]
+pub struct DaemonSubcommand {
+    /// Path of the unix socket of the server [default: the socket of the
+    /// current workspace]
+    #[structopt(long)]
+    pub socket: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    pub action: DaemonAction,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum DaemonAction {
+    /// Start the server in the background unless it is already running
+    Start {
+        /// Program to run as the server
+        #[structopt(long, default_value = "vimwiki-server")]
+        server: PathBuf,
+
+        /// Directory to store log output of the server as files
+        #[structopt(long)]
+        log_dir: Option<PathBuf>,
+
+        /// Seconds to wait for the server to begin listening
+        #[structopt(long, default_value = "30")]
+        timeout: u64,
+    },
+
+    /// Stop the server and wait for it to shut down
+    Stop {
+        /// Seconds to wait for the server to shut down
+        #[structopt(long, default_value = "30")]
+        timeout: u64,
+    },
+
+    /// Report whether or not the server is running
+    Status,
+}
+
+/// Generate a man page describing the cli and each of its subcommands
+#[derive(Debug, StructOpt)]
+pub struct ManSubcommand {
+    /// Writes to output file instead of stdout
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Check wikis for pages and links that likely need attention
+#[derive(Debug, StructOpt)]
+pub struct LintSubcommand {
+    /// Writes to output file instead of stdout
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Produce the report as json instead of text (deprecated, use
+    /// `--format json` instead)
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Name of the page (relative to the wiki root and without extension)
+    /// from which all other pages should be reachable [default: index]
+    #[structopt(long)]
+    pub index: Option<String>,
+
+    /// Pages with fewer bytes of content than this are reported as stubs
+    /// [default: 100]
+    #[structopt(long)]
+    pub stub_threshold: Option<usize>,
+
+    /// Additional standalone files (or directories) to process
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub extra_paths: Vec<PathBuf>,
+}
+
+/// Regenerate the content of dynamic blocks (`{{{query` code blocks) from
+/// the current state of the wikis
+#[derive(Debug, StructOpt)]
+pub struct RefreshSubcommand {
+    /// Rewrite the generated content of each file in place instead of only
+    /// reporting the files whose generated content is out of date
+    #[structopt(short, long)]
+    pub inline: bool,
+
+    /// Additional standalone files (or directories) to process
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub extra_paths: Vec<PathBuf>,
+}
+
+/// Report statistics about the pages of each wiki
+#[derive(Debug, StructOpt)]
+pub struct StatsSubcommand {
+    /// Writes to output file instead of stdout
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Produce the report as json instead of text (deprecated, use
+    /// `--format json` instead)
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Include the statistics of each page alongside those of each wiki
+    #[structopt(long)]
+    pub pages: bool,
+
+    /// Additional standalone files (or directories) to process
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub extra_paths: Vec<PathBuf>,
+}
+
+/// Report tasks with due dates that are overdue or coming up
+#[derive(Debug, StructOpt)]
+pub struct TasksSubcommand {
+    /// Writes to output file instead of stdout
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Produce the report as json instead of text (deprecated, use
+    /// `--format json` instead)
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Only report tasks that are overdue
+    #[structopt(long, conflicts_with = "upcoming")]
+    pub overdue: bool,
+
+    /// Only report tasks that are coming up
+    #[structopt(long)]
+    pub upcoming: bool,
+
+    /// Total days after today for a task to be considered upcoming
+    #[structopt(long, default_value = "7")]
+    pub days: u32,
+
+    /// Date (YYYY-MM-DD) to treat as today instead of the current date
+    #[structopt(long)]
+    pub today: Option<WikiDate>,
+
+    /// Additional standalone files (or directories) to process
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub extra_paths: Vec<PathBuf>,
+}
+
+/// List the bundled css themes or apply one to the output of wikis
+#[derive(Debug, StructOpt)]
+pub enum ThemeSubcommand {
+    /// List the names and descriptions of the bundled themes
+    List,
+
+    /// Write the css of a theme to the output directory of each wiki
+    Apply {
+        /// Name of a bundled theme or path to a css file
+        #[structopt(name = "THEME")]
+        theme: String,
+
+        /// Directories of additional files (such as css or js) to copy into
+        /// the root of each wiki's output directory
+        #[structopt(long = "assets", parse(from_os_str))]
+        assets: Vec<PathBuf>,
+    },
+}
+
+/// Browse wikis in an interactive terminal interface, following links and
+/// toggling tasks
+#[cfg(feature = "tui")]
+#[derive(Debug, StructOpt)]
+pub struct TuiSubcommand {
+    /// Additional standalone files (or directories) to process
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub extra_paths: Vec<PathBuf>,
+}
+
+/// Represents the format of the output produced by subcommands
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn is_json(self) -> bool {
+        self == Self::Json
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            x => Err(format!("Unknown output format: {}", x)),
+        }
+    }
+}
+
+/// Represents the format of a graph produced by the graph subcommand
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    GraphMl,
+    Json,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "graphml" => Ok(Self::GraphMl),
+            "json" => Ok(Self::Json),
+            x => Err(format!("Unknown graph format: {}", x)),
+        }
+    }
+}
+
+/// Represents the format of the report produced by the timekeeper
+#[cfg(feature = "timekeeper")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimekeeperFormat {
+    Text,
+    Json,
+    Folded,
+}
+
+#[cfg(feature = "timekeeper")]
+impl std::str::FromStr for TimekeeperFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "folded" => Ok(Self::Folded),
+            x => Err(format!("Unknown timekeeper format: {}", x)),
+        }
+    }
+}
+
 /// Represents either a wiki index or a wiki name
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum IndexOrName {
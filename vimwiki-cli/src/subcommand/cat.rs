@@ -0,0 +1,63 @@
+use crate::{Ast, CatSubcommand, CommonOpt};
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+};
+use vimwiki::{
+    AnsiConfig, AnsiLinkConfig, HtmlConfig, PlainConfig, PlainLinkConfig,
+    ToAnsiString, ToPlainText,
+};
+
+pub fn cat(
+    cmd: CatSubcommand,
+    _opt: CommonOpt,
+    _config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    // Look up the page by its file if it is one, otherwise by its name
+    // within the first wiki that has a page with the name
+    let file = match cmd.page_path() {
+        Some(path) => ast.find_file_by_path(path.canonicalize()?),
+        None => ast.wikis.iter().find_map(|wiki| {
+            wiki.files.iter().find(|file| {
+                file.page_name(&wiki.path).as_deref() == Some(cmd.page.as_str())
+            })
+        }),
+    }
+    .ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No page found for {}", cmd.page),
+        )
+    })?;
+
+    let text = if cmd.plain || !use_color() {
+        file.data
+            .to_plain_text(PlainConfig {
+                link: PlainLinkConfig {
+                    include_target: cmd.link_targets,
+                },
+                ..Default::default()
+            })
+            .map_err(|x| io::Error::other(x.to_string()))?
+    } else {
+        file.data
+            .to_ansi_string(AnsiConfig {
+                link: AnsiLinkConfig {
+                    include_target: cmd.link_targets,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .map_err(|x| io::Error::other(x.to_string()))?
+    };
+
+    write!(io::stdout(), "{}", text)
+}
+
+/// Returns true if the page should be written with colors and styles, which
+/// is only when writing to a terminal and `NO_COLOR` is not set to anything
+fn use_color() -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|x| !x.is_empty());
+    io::stdout().is_terminal() && !no_color
+}
@@ -0,0 +1,140 @@
+use crate::{CheckSubcommand, CommonOpt};
+use log::*;
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+use vimwiki::{
+    check::{self, CheckOptions},
+    resolve::LinkResolver,
+    HtmlConfig, VimwikiConfig,
+};
+use walkdir::WalkDir;
+
+pub fn check(
+    cmd: CheckSubcommand,
+    opt: CommonOpt,
+    config: HtmlConfig,
+    format_config: VimwikiConfig,
+) -> io::Result<()> {
+    let extensions: HashSet<String> = cmd.extensions.into_iter().collect();
+    let has_extension = |path: &Path| {
+        path.extension()
+            .and_then(OsStr::to_str)
+            .is_some_and(|ext| extensions.contains(ext))
+    };
+
+    let mut files = Vec::new();
+    for path in cmd.paths {
+        if path.is_file() {
+            files.push(path);
+        } else {
+            files.extend(
+                WalkDir::new(path)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_file())
+                    .map(|e| e.into_path())
+                    .filter(|path| has_extension(path)),
+            );
+        }
+    }
+
+    if cmd.staged {
+        files.extend(
+            staged_files()?
+                .into_iter()
+                .filter(|path| has_extension(path)),
+        );
+    }
+
+    files.sort();
+    files.dedup();
+
+    let options = CheckOptions {
+        stub_threshold: cmd.stub_threshold,
+        links: if cmd.no_links {
+            None
+        } else {
+            Some(LinkResolver::from(&config))
+        },
+        format: if cmd.no_format {
+            None
+        } else {
+            Some(format_config)
+        },
+    };
+
+    debug!("Checking {} files", files.len());
+    let report = check::check(&files, &options);
+
+    let text = if cmd.json || opt.output_format().is_json() {
+        serde_json::to_string_pretty(&report)?
+    } else {
+        let mut text = String::new();
+        for issue in report.issues.iter() {
+            match issue.region {
+                Some(region) => text.push_str(&format!(
+                    "{}:{}: {}\n",
+                    issue.path.to_string_lossy(),
+                    region.offset(),
+                    issue.message
+                )),
+                None => text.push_str(&format!(
+                    "{}: {}\n",
+                    issue.path.to_string_lossy(),
+                    issue.message
+                )),
+            }
+        }
+        text
+    };
+
+    if let Some(path) = cmd.output {
+        fs::write(path, text)?;
+    } else {
+        write!(io::stdout(), "{}", text)?;
+    }
+
+    if report.is_ok() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "Found {} problems across {} files",
+            report.issues.len(),
+            report.files
+        )))
+    }
+}
+
+/// Returns the files that are added, copied, modified, or renamed within
+/// the index of the git repository of the current directory, relative to
+/// the current directory
+fn staged_files() -> io::Result<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .args([
+            "diff",
+            "--cached",
+            "--name-only",
+            "--relative",
+            "--diff-filter=ACMR",
+            "-z",
+        ])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|x| !x.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
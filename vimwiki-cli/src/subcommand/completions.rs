@@ -0,0 +1,11 @@
+use crate::{CommonOpt, CompletionsSubcommand, Opt};
+use std::io;
+use structopt::StructOpt;
+
+pub fn completions(
+    cmd: CompletionsSubcommand,
+    _opt: CommonOpt,
+) -> io::Result<()> {
+    Opt::clap().gen_completions_to("vimwiki", cmd.shell, &mut io::stdout());
+    Ok(())
+}
@@ -1,14 +1,92 @@
-use crate::{css, Ast, CommonOpt, ConvertSubcommand};
+use crate::{
+    css, manifest::BuildManifest, Ast, CommonOpt, ConvertSubcommand, PageCrypt,
+};
 use log::*;
-use std::{ffi::OsStr, io, path::Path};
-use vimwiki::*;
+use sha1::{Digest, Sha1};
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use vimwiki::{
+    site::{SearchIndex, Sitemap},
+    *,
+};
 use walkdir::WalkDir;
 
+/// Time to wait between checks for changes when watching
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Represents how pages are loaded from disk, matching how the ast was
+/// originally loaded so that reloading a page parses, caches, and decrypts
+/// it the same way
+struct PageLoader<'a> {
+    syntax: &'a ParserConfig,
+    cache: &'a Path,
+    no_cache: bool,
+    crypt: PageCrypt,
+}
+
 pub fn convert(
-    cmd: ConvertSubcommand,
+    mut cmd: ConvertSubcommand,
     opt: CommonOpt,
-    config: HtmlConfig,
+    mut config: HtmlConfig,
     mut ast: Ast,
+    syntax: &ParserConfig,
+) -> io::Result<()> {
+    // Flags only enable navigation, leaving whatever the config enables
+    config.navigation.breadcrumbs |= cmd.breadcrumbs;
+    config.navigation.diary |= cmd.diary_nav;
+    config.navigation.sidebar |= cmd.sidebar;
+
+    // Need to make sure any paths provided adhoc are legit
+    let mut extra_paths = Vec::new();
+    for path in std::mem::take(&mut cmd.extra_paths) {
+        match path.canonicalize() {
+            Ok(path) => extra_paths.push(path),
+            Err(x) => {
+                error!("{:?} failed to canonicalize: {}", path, x);
+                return Err(x);
+            }
+        }
+    }
+    cmd.extra_paths = extra_paths;
+
+    let loader = PageLoader {
+        syntax,
+        cache: &opt.cache,
+        no_cache: opt.no_cache,
+        crypt: opt.to_page_crypt(),
+    };
+    convert_once(&cmd, &opt, &loader, &config, &mut ast, false)?;
+
+    // Keep checking for changes, where only the pages affected by a change
+    // are converted again
+    if cmd.watch {
+        info!("Watching for changes");
+        loop {
+            std::thread::sleep(WATCH_INTERVAL);
+            if let Err(x) =
+                convert_once(&cmd, &opt, &loader, &config, &mut ast, true)
+            {
+                error!("Failed to convert: {}", x);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts every page once, reloading pages from disk rather than using
+/// those already within the ast if `reload` is true
+fn convert_once(
+    cmd: &ConvertSubcommand,
+    opt: &CommonOpt,
+    loader: &PageLoader,
+    config: &HtmlConfig,
+    ast: &mut Ast,
+    reload: bool,
 ) -> io::Result<()> {
     // Process all wikis that match the given filters if we aren't given
     // specific files/wikis to convert
@@ -18,168 +96,254 @@ pub fn convert(
                 opt.filter_by_wiki_idx_and_name(*idx, wiki.name.as_deref())
             })
         {
-            process_path(
-                config.clone(),
-                &mut ast,
-                wiki.path.as_path(),
-                opt.cache.as_path(),
-                opt.no_cache,
-                cmd.stdout,
-                &wiki.ext,
-            )?;
-
-            // If writing to a file, we want to make sure there is a css
-            // file generated if necessary
-            if !cmd.stdout && cmd.include_vimwiki_css {
-                let css_path =
-                    wiki.path_html.join(HtmlWikiConfig::default_css_name());
-                debug!("Writing css to {:?}", css_path);
-                std::fs::write(css_path, css::DEFAULT_STYLE_FILE)?;
-            }
+            process_wiki(cmd, loader, config, ast, wiki, &wiki.path, reload)?;
         }
     }
 
     // Additionally, we process any directories & files provided adhoc
-    for path in cmd.extra_paths {
-        // Need to make sure the path is legit
-        let path = match path.canonicalize() {
-            Ok(path) => path,
-            Err(x) => {
-                error!("{:?} failed to canonicalize: {}", path, x);
-                return Err(x);
-            }
-        };
-
-        process_path(
-            config.clone(),
-            &mut ast,
-            path.as_path(),
-            opt.cache.as_path(),
-            opt.no_cache,
-            cmd.stdout,
-            &HtmlWikiConfig::default_ext(),
-        )?;
-
-        // If writing to a file, we want to make sure there is a css
-        // file generated if necessary
-        if !cmd.stdout && cmd.include_vimwiki_css {
-            let wiki = config.runtime.to_tmp_wiki();
-            let css_path = wiki.path_html.join("style.css");
-            debug!("Writing css to {:?}", css_path);
-            std::fs::write(css_path, css::DEFAULT_STYLE_FILE)?;
-        }
+    for path in cmd.extra_paths.iter() {
+        let wiki = config.runtime.to_tmp_wiki();
+        process_wiki(cmd, loader, config, ast, &wiki, path, reload)?;
     }
 
     Ok(())
 }
 
-fn process_path(
-    config: HtmlConfig,
+/// Converts the pages of a wiki found at the input path, skipping pages
+/// whose html would not change since they were last converted
+fn process_wiki(
+    cmd: &ConvertSubcommand,
+    loader: &PageLoader,
+    config: &HtmlConfig,
     ast: &mut Ast,
+    wiki: &HtmlWikiConfig,
     input_path: &Path,
-    cache: &Path,
-    no_cache: bool,
-    stdout: bool,
-    ext: &str,
+    reload: bool,
 ) -> io::Result<()> {
     trace!(
-        "process_path(_, input_path = {:?}, stdout = {}, ext = {})",
+        "process_wiki(_, input_path = {:?}, reload = {})",
         input_path,
-        stdout,
-        ext
+        reload
     );
 
-    // Walk through all entries in directory (or singular file), processing
-    // each file as it is encountered that has a valid file extension
-    for entry in WalkDir::new(input_path)
+    // Walk through all entries in directory (or singular file), gathering
+    // each file that has a valid file extension so every page knows of the
+    // others when generating navigation
+    let mut pages: Vec<PathBuf> = WalkDir::new(input_path)
         .into_iter()
         .filter_map(|e| e.ok())
         .filter(|e| {
             e.file_type().is_file()
-                && e.path().extension().and_then(OsStr::to_str) == Some(ext)
+                && e.path().extension().and_then(OsStr::to_str)
+                    == Some(wiki.ext.as_str())
         })
-    {
-        let mut config = config.clone();
-        let page_path = entry.path().to_path_buf();
-
-        // Figure out which wiki this page belongs to (if any)
-        let wiki_index = config.find_wiki_index_by_path(page_path.as_path());
-        debug!("{:?}: Wiki {:?}", page_path, wiki_index);
-
-        config.map_runtime(|mut rt| {
-            rt.page = page_path.to_path_buf();
-            rt.wiki_index = wiki_index;
-            rt
-        });
-
-        process_file(
-            config,
-            ast,
-            page_path.as_path(),
-            cache,
-            no_cache,
-            stdout,
-        )?;
+        .map(|e| e.path().to_path_buf())
+        .collect();
+    pages.sort();
+
+    let manifest_path = BuildManifest::path_for(&wiki.path_html, input_path);
+    let fingerprint = fingerprint(config, &pages)?;
+    let mut manifest = if cmd.force || cmd.stdout {
+        BuildManifest::new(&fingerprint)
+    } else {
+        BuildManifest::load(&manifest_path, &fingerprint)
+    };
+
+    // Load every page up front so that rendering can share the ast
+    for page_path in pages.iter() {
+        if reload || ast.find_file_by_path(page_path).is_none() {
+            ast.reload_file(
+                page_path,
+                loader.syntax,
+                loader.cache,
+                loader.no_cache,
+                &loader.crypt,
+            )?;
+        }
+    }
+
+    // Every page shares the same list of pages for navigation
+    let mut config = config.clone();
+    config.runtime.pages = pages.clone().into();
+
+    // Figure out which pages changed since they were last converted
+    let mut stale = Vec::new();
+    for page_path in pages.iter() {
+        let file = ast.find_file_by_path(page_path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "Loaded file is now missing",
+            )
+        })?;
+
+        let output_path = config
+            .find_wiki_by_path(page_path)
+            .cloned()
+            .unwrap_or_default()
+            .make_page_output_path(page_path);
+        if !cmd.stdout
+            && manifest.is_fresh(page_path, &file.checksum, &output_path)
+        {
+            debug!("{:?} :: unchanged", page_path);
+            continue;
+        }
+
+        stale.push((file, output_path));
+    }
+
+    // Render the changed pages in parallel, writing them out afterwards in
+    // order
+    let jobs: Vec<(PathBuf, &Page)> = stale
+        .iter()
+        .map(|(file, _)| (file.path.to_path_buf(), &file.data))
+        .collect();
+    let results = render_html_pages(&jobs, &config, |progress| {
+        debug!(
+            "Rendered {} of {} pages",
+            progress.completed, progress.total
+        );
+    });
+
+    let converted = stale.len();
+    for ((file, output_path), result) in stale.into_iter().zip(results) {
+        let html = result.map_err(|x| {
+            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+        })?;
+        debug!("{:?} :: html generated!", file.path);
+
+        // If told to print to stdout, do so
+        if cmd.stdout {
+            println!("{}", html);
+
+        // Otherwise, we generate files based on resolved output paths
+        } else {
+            info!("Writing to {:?}", output_path);
+            if let Some(parent) = output_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&output_path, html)?;
+
+            manifest.update(
+                &file.path,
+                &file.checksum,
+                &output_path,
+                html_page_dependencies(&file.data, &config),
+            );
+        }
+    }
+
+    if !cmd.stdout {
+        let removed = manifest.retain(&pages);
+
+        // If writing to a file, we want to make sure there is a css
+        // file generated if necessary
+        if !reload || converted > 0 || removed > 0 {
+            write_theme_and_assets(cmd, &wiki.path_html, &wiki.css_name)?;
+            write_site_files(cmd, &config, ast, wiki, &pages)?;
+        }
+
+        if converted > 0 || removed > 0 {
+            info!(
+                "Converted {} of {} pages, removed {}",
+                converted,
+                pages.len(),
+                removed
+            );
+        }
+        manifest.save(&manifest_path)?;
     }
 
     Ok(())
 }
 
-fn process_file(
-    config: HtmlConfig,
-    ast: &mut Ast,
-    input_path: &Path,
-    cache: &Path,
-    no_cache: bool,
-    stdout: bool,
+/// Produces a fingerprint of everything shared by all pages that affects
+/// their html, so that a change to any of it converts every page again
+fn fingerprint(config: &HtmlConfig, pages: &[PathBuf]) -> io::Result<String> {
+    let mut hasher = Sha1::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update(serde_json::to_vec(config)?);
+
+    // Navigation between pages changes whenever a page is added or removed
+    if config.navigation.sidebar || config.navigation.diary {
+        for page in pages {
+            hasher.update(page.to_string_lossy().as_bytes());
+            hasher.update(b"\0");
+        }
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Writes the css of the selected theme (if any) and copies the assets into
+/// the output directory of a wiki
+fn write_theme_and_assets(
+    cmd: &ConvertSubcommand,
+    path_html: &Path,
+    css_name: &str,
 ) -> io::Result<()> {
-    trace!(
-        "process_file(_, input_path = {:?}, stdout = {})",
-        input_path,
-        stdout
-    );
+    if let Some(theme) = cmd.theme() {
+        let css_path = path_html.join(css_name);
+        debug!("Writing css to {:?}", css_path);
+        std::fs::write(css_path, css::load_theme(theme)?)?;
+    }
 
-    // Go ahead and figure out the necessary wiki if we need it so that we
-    // don't need to clone our entire config later
-    let maybe_wiki = if !stdout {
-        config.find_wiki_by_path(input_path).cloned()
-    } else {
-        None
-    };
+    for path in cmd.assets.iter() {
+        css::copy_assets(path, path_html)?;
+    }
 
-    // If we already have a file loaded at this path, use it
-    let html = if let Some(file) = ast.find_file_by_path(input_path) {
-        debug!("{:?} :: loaded from cache!", input_path);
+    Ok(())
+}
 
-        file.data.to_html_page(config).map_err(|x| {
-            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
-        })?
+/// Writes the sitemap and search index (if requested) of the converted pages
+/// into the output directory of a wiki
+fn write_site_files(
+    cmd: &ConvertSubcommand,
+    config: &HtmlConfig,
+    ast: &Ast,
+    wiki: &HtmlWikiConfig,
+    pages: &[PathBuf],
+) -> io::Result<()> {
+    if cmd.sitemap.is_none() && !cmd.search_index {
+        return Ok(());
+    }
 
-    // Otherwise, we need to load the file
-    } else {
-        let file = ast.load_file(input_path, cache, no_cache)?;
+    let mut sitemap = cmd.sitemap.as_deref().map(Sitemap::new);
+    let mut search_index = SearchIndex::new();
+    for path in pages {
+        // NOTE: Pages are located the same way as when writing their html
+        let page_wiki =
+            config.find_wiki_by_path(path).cloned().unwrap_or_default();
+        let name = match page_wiki.page_name(path) {
+            Some(x) => x,
+            None => continue,
+        };
+        let url = page_wiki.page_url(&name);
 
-        file.data.to_html_page(config).map_err(|x| {
-            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
-        })?
-    };
-    debug!("{:?} :: html generated!", input_path);
+        if let Some(sitemap) = sitemap.as_mut() {
+            let last_modified = std::fs::metadata(path)
+                .and_then(|x| x.modified())
+                .ok()
+                .map(|x| Timestamp::from(x).date());
+            sitemap.add_page(&url, last_modified);
+        }
 
-    // If told to print to stdout, do so
-    if stdout {
-        println!("{}", html);
+        if cmd.search_index {
+            if let Some(file) = ast.find_file_by_path(path) {
+                search_index.add_page(&name, &url, &file.data);
+            }
+        }
+    }
 
-    // Otherwise, we generate files based on resolved output paths
-    } else {
-        let path = maybe_wiki
-            .unwrap_or_default()
-            .make_output_path(input_path, "html");
+    if let Some(sitemap) = sitemap {
+        let path = wiki.path_html.join("sitemap.xml");
+        info!("Writing to {:?}", path);
+        std::fs::write(path, sitemap.to_xml_string())?;
+    }
 
+    if cmd.search_index {
+        let path = wiki.path_html.join("search-index.json");
         info!("Writing to {:?}", path);
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-        std::fs::write(path, html)?;
+        std::fs::write(path, serde_json::to_string(&search_index)?)?;
     }
 
     Ok(())
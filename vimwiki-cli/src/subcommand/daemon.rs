@@ -0,0 +1,200 @@
+use crate::{CommonOpt, DaemonAction, DaemonSubcommand};
+use log::*;
+use serde::Serialize;
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+use vimwiki::settings::{socket_lock_path, ServerSettings};
+
+/// Time between checks on the server while waiting for it
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Represents the state of the server of a socket
+#[derive(Debug, Serialize)]
+struct DaemonStatus {
+    /// Path of the unix socket of the server
+    socket: PathBuf,
+
+    /// Whether or not the server is accepting connections
+    running: bool,
+
+    /// Id of the server's process, if running
+    pid: Option<u32>,
+}
+
+impl DaemonStatus {
+    fn load(socket: &Path) -> Self {
+        let running = is_listening(socket);
+        let pid = if running {
+            fs::read_to_string(socket_lock_path(socket))
+                .ok()
+                .and_then(|x| x.trim().parse().ok())
+        } else {
+            None
+        };
+
+        Self {
+            socket: socket.to_path_buf(),
+            running,
+            pid,
+        }
+    }
+}
+
+impl fmt::Display for DaemonStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.running, self.pid) {
+            (true, Some(pid)) => {
+                write!(f, "running on {:?} (pid {})", self.socket, pid)
+            }
+            (true, None) => write!(f, "running on {:?}", self.socket),
+            (false, _) => write!(f, "not running on {:?}", self.socket),
+        }
+    }
+}
+
+pub fn daemon(
+    cmd: DaemonSubcommand,
+    opt: CommonOpt,
+    settings: &ServerSettings,
+) -> io::Result<()> {
+    let socket = match cmd.socket {
+        Some(socket) => socket,
+        None => settings.socket_path(&std::env::current_dir()?),
+    };
+
+    let status = match cmd.action {
+        DaemonAction::Start {
+            server,
+            log_dir,
+            timeout,
+        } => start(&socket, &server, log_dir.as_deref(), timeout)?,
+        DaemonAction::Stop { timeout } => stop(&socket, timeout)?,
+        DaemonAction::Status => DaemonStatus::load(&socket),
+    };
+
+    if opt.output_format().is_json() {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+    } else {
+        println!("{}", status);
+    }
+
+    Ok(())
+}
+
+/// Starts the server in the background unless it is already running, waiting
+/// for it to accept connections
+fn start(
+    socket: &Path,
+    server: &Path,
+    log_dir: Option<&Path>,
+    timeout: u64,
+) -> io::Result<DaemonStatus> {
+    let status = DaemonStatus::load(socket);
+    if status.running {
+        return Ok(status);
+    }
+
+    let mut command = Command::new(server);
+    command
+        .arg("--mode")
+        .arg("socket")
+        .arg("--socket")
+        .arg(socket)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(log_dir) = log_dir {
+        command.arg("--log-dir").arg(log_dir);
+    }
+
+    // Leave the process group of the terminal so that interrupting the
+    // terminal does not also stop the server
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+    info!("Starting {:?}", server);
+    let mut child = command.spawn()?;
+    wait_until(timeout, || {
+        // NOTE: Another server starting at the same time wins the socket,
+        //       which is just as good as ours starting
+        if is_listening(socket) {
+            return Ok(true);
+        }
+
+        match child.try_wait()? {
+            Some(exit) => {
+                Err(io::Error::other(format!("Server exited with {}", exit)))
+            }
+            None => Ok(false),
+        }
+    })?;
+
+    Ok(DaemonStatus::load(socket))
+}
+
+/// Asks the server to shut down if it is running, waiting for it to finish
+/// storing its cache and remove its socket
+fn stop(socket: &Path, timeout: u64) -> io::Result<DaemonStatus> {
+    let status = DaemonStatus::load(socket);
+    let pid = match status.pid {
+        Some(pid) if status.running => pid,
+        _ => return Ok(status),
+    };
+
+    info!("Stopping server (pid {})", pid);
+    terminate(pid)?;
+    wait_until(timeout, || Ok(!socket.exists()))?;
+
+    Ok(DaemonStatus::load(socket))
+}
+
+/// Checks the condition until it holds, failing once the timeout (in seconds)
+/// has passed
+fn wait_until(
+    timeout: u64,
+    mut f: impl FnMut() -> io::Result<bool>,
+) -> io::Result<()> {
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+    while !f()? {
+        if Instant::now() >= deadline {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "Timed out waiting for server",
+            ));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_listening(socket: &Path) -> bool {
+    std::os::unix::net::UnixStream::connect(socket).is_ok()
+}
+
+#[cfg(not(unix))]
+fn is_listening(_socket: &Path) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn terminate(pid: u32) -> io::Result<()> {
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(unix))]
+fn terminate(_pid: u32) -> io::Result<()> {
+    Err(io::Error::other(
+        "Stopping the server requires unix signals",
+    ))
+}
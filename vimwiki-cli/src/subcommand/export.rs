@@ -0,0 +1,107 @@
+use crate::{Ast, CommonOpt, ExportSubcommand};
+use log::*;
+use std::{ffi::OsStr, fs, io, path::Path};
+use vimwiki::{export::ObsidianVault, HtmlConfig, HtmlWikiConfig};
+use walkdir::WalkDir;
+
+pub fn export(
+    cmd: ExportSubcommand,
+    opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let crypt = opt.to_page_crypt();
+
+    for wiki in ast.wikis.iter() {
+        let wiki_config = config.wikis.get(wiki.index);
+        let mut vault = ObsidianVault::new()
+            .with_daily_notes_dir(cmd.daily_notes_dir.as_str());
+        if let Some(x) = wiki_config {
+            vault = vault.with_diary_rel_path(
+                x.diary_rel_path.to_string_lossy().replace('\\', "/"),
+            );
+        }
+
+        // Each wiki becomes its own vault, so we only nest them when
+        // exporting more than one
+        let root = if ast.wikis.len() > 1 {
+            cmd.output.join(
+                wiki.name
+                    .clone()
+                    .unwrap_or_else(|| format!("wiki{}", wiki.index)),
+            )
+        } else {
+            cmd.output.clone()
+        };
+
+        for file in wiki.files.iter() {
+            if file.encrypted {
+                warn!("{:?} :: skipped as encrypted", file.path);
+                continue;
+            }
+
+            let name = match file.page_name(wiki.path.as_path()) {
+                Some(name) => name,
+                None => {
+                    warn!("{:?} is not within {:?}", file.path, wiki.path);
+                    continue;
+                }
+            };
+
+            let output_path =
+                root.join(format!("{}.md", vault.note_name(&name)));
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            debug!("{:?} :: writing to {:?}", file.path, output_path);
+            fs::write(output_path, vault.page_to_markdown(&name, &file.data))?;
+        }
+
+        if !cmd.no_assets {
+            let ext = wiki_config
+                .map(|x| x.ext.clone())
+                .unwrap_or_else(HtmlWikiConfig::default_ext);
+            copy_assets(wiki.path.as_path(), root.as_path(), &ext, |path| {
+                crypt.is_encrypted(path)
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies every file of the wiki that is not a page (such as images) into
+/// the vault, skipping hidden files and directories
+fn copy_assets(
+    wiki: &Path,
+    root: &Path,
+    ext: &str,
+    is_encrypted: impl Fn(&Path) -> bool,
+) -> io::Result<()> {
+    for entry in WalkDir::new(wiki)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.')
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().and_then(OsStr::to_str) != Some(ext))
+        .filter(|e| !is_encrypted(e.path()))
+    {
+        let rel_path = match entry.path().strip_prefix(wiki) {
+            Ok(rel_path) => rel_path,
+            Err(_) => continue,
+        };
+
+        let output_path = root.join(rel_path);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        debug!("{:?} :: copying to {:?}", entry.path(), output_path);
+        fs::copy(entry.path(), output_path)?;
+    }
+
+    Ok(())
+}
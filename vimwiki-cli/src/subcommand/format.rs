@@ -1,4 +1,4 @@
-use crate::{CommonOpt, FormatSubcommand};
+use crate::{CommonOpt, FormatSubcommand, PageCrypt};
 use log::*;
 use std::{collections::HashSet, ffi::OsStr, io, path::Path};
 use vimwiki::*;
@@ -6,10 +6,11 @@ use walkdir::WalkDir;
 
 pub fn format(
     cmd: FormatSubcommand,
-    _opt: CommonOpt,
+    opt: CommonOpt,
     config: VimwikiConfig,
 ) -> io::Result<()> {
     let extensions: HashSet<String> = cmd.extensions.into_iter().collect();
+    let crypt = opt.to_page_crypt();
 
     for path in cmd.paths {
         // Need to make sure the path is legit
@@ -24,7 +25,7 @@ pub fn format(
         // If path is to a file, we want to process it directly regardless of
         // the extension
         if path.is_file() {
-            process_file(config.clone(), path.as_path(), cmd.inline)?;
+            process_file(config.clone(), path.as_path(), cmd.inline, &crypt)?;
 
         // Otherwise, we walk the directory
         } else {
@@ -33,11 +34,15 @@ pub fn format(
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_file())
             {
-                if let Some(ext) =
-                    entry.path().extension().and_then(OsStr::to_str)
-                {
+                let path = crypt.strip_encrypted_extension(entry.path());
+                if let Some(ext) = path.extension().and_then(OsStr::to_str) {
                     if extensions.contains(ext) {
-                        process_file(config.clone(), entry.path(), cmd.inline)?;
+                        process_file(
+                            config.clone(),
+                            entry.path(),
+                            cmd.inline,
+                            &crypt,
+                        )?;
                     } else {
                         warn!(
                             "{:?} :: skipped due to unrecognized extension ({})!",
@@ -62,6 +67,7 @@ fn process_file(
     config: VimwikiConfig,
     input_path: &Path,
     inplace: bool,
+    crypt: &PageCrypt,
 ) -> io::Result<()> {
     trace!(
         "process_file(_, input_path = {:?}, inplace = {})",
@@ -69,8 +75,13 @@ fn process_file(
         inplace
     );
 
-    // Load the file's text
-    let text = std::fs::read_to_string(input_path)?;
+    // Load the file's text, decrypting it first if needed
+    let encrypted = crypt.is_encrypted(input_path);
+    let text = if encrypted {
+        crypt.decrypt(input_path, &std::fs::read(input_path)?)?
+    } else {
+        std::fs::read_to_string(input_path)?
+    };
 
     debug!("{:?} :: file loaded!", input_path);
 
@@ -94,7 +105,11 @@ fn process_file(
     // If indicated, we replace the file's contents inline
     if inplace {
         info!("Writing to {:?}", input_path);
-        std::fs::write(input_path, text)?;
+        if encrypted {
+            std::fs::write(input_path, crypt.encrypt(input_path, &text)?)?;
+        } else {
+            std::fs::write(input_path, text)?;
+        }
 
     // Otherwise, print to stdout
     } else {
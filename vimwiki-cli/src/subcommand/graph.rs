@@ -0,0 +1,53 @@
+use crate::{Ast, CommonOpt, GraphFormat, GraphSubcommand};
+use std::{
+    fs,
+    io::{self, Write},
+};
+use vimwiki::HtmlConfig;
+
+pub fn graph(
+    cmd: GraphSubcommand,
+    opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let graph = ast.to_link_graph(&config);
+    let format = cmd
+        .graph_format
+        .unwrap_or(if opt.output_format().is_json() {
+            GraphFormat::Json
+        } else {
+            GraphFormat::Dot
+        });
+
+    let text = if cmd.metrics {
+        let metrics = graph.metrics(cmd.top);
+        match format {
+            GraphFormat::Json => serde_json::to_string_pretty(&metrics)?,
+            _ => {
+                let mut text = String::from("Orphan pages:\n");
+                for id in metrics.orphans.iter() {
+                    text.push_str(&format!("- {}\n", id));
+                }
+                text.push_str("\nMost linked pages:\n");
+                for x in metrics.most_linked.iter() {
+                    text.push_str(&format!("- {} ({})\n", x.id, x.count));
+                }
+                text
+            }
+        }
+    } else {
+        match format {
+            GraphFormat::Dot => graph.to_dot(),
+            GraphFormat::GraphMl => graph.to_graphml(),
+            GraphFormat::Json => serde_json::to_string_pretty(&graph)?,
+        }
+    };
+
+    if let Some(path) = cmd.output {
+        let mut file = fs::File::create(path)?;
+        writeln!(file, "{}", text)
+    } else {
+        writeln!(io::stdout(), "{}", text)
+    }
+}
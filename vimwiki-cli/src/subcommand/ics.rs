@@ -0,0 +1,71 @@
+use crate::{Ast, CommonOpt, IcsSubcommand};
+use log::*;
+use std::{
+    fs,
+    io::{self, Write},
+};
+use vimwiki::{
+    ics::Calendar, tasks::TaskIndex, HtmlConfig, Timestamp, WikiDate,
+};
+
+pub fn ics(
+    cmd: IcsSubcommand,
+    _opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let mut calendar =
+        Calendar::new(Timestamp::now()).with_name(cmd.name.as_str());
+
+    if !cmd.no_diary {
+        for wiki in ast.wikis.iter() {
+            let diary_path = match config.wikis.get(wiki.index) {
+                Some(x) => wiki.path.join(&x.diary_rel_path),
+                None => continue,
+            };
+
+            for file in wiki.files.iter() {
+                if file.path.parent() != Some(diary_path.as_path()) {
+                    continue;
+                }
+
+                let date =
+                    file.path.file_stem().and_then(|x| x.to_str()).and_then(
+                        |x| WikiDate::parse_from_str(x, "%Y-%m-%d").ok(),
+                    );
+                match date {
+                    Some(date) => calendar.add_diary_page(
+                        &wiki.path.to_string_lossy(),
+                        date,
+                        &file.data,
+                    ),
+                    None => {
+                        debug!("Skipping undated diary page {:?}", file.path)
+                    }
+                }
+            }
+        }
+    }
+
+    if !cmd.no_tasks {
+        for wiki in ast.wikis.iter() {
+            let mut index = TaskIndex::new();
+            for file in wiki.files.iter() {
+                if let Some(name) = file.page_name(wiki.path.as_path()) {
+                    index.add_page(&name, &file.data);
+                }
+            }
+
+            for task in index.tasks() {
+                calendar.add_task(&wiki.path.to_string_lossy(), task);
+            }
+        }
+    }
+
+    let text = calendar.to_ics_string();
+    if let Some(path) = cmd.output {
+        fs::write(path, text)
+    } else {
+        write!(io::stdout(), "{}", text)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{CommonOpt, ImportSubcommand};
+use log::*;
+use std::{
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+};
+use vimwiki::import::ImportFormat;
+use walkdir::WalkDir;
+
+pub fn import(cmd: ImportSubcommand, _opt: CommonOpt) -> io::Result<()> {
+    for path in cmd.paths.iter() {
+        // If path is to a file, we want to process it directly using the
+        // output directory as-is
+        if path.is_file() {
+            let output_path = match cmd.output.as_ref() {
+                Some(dir) => dir.join(path.file_name().unwrap_or_default()),
+                None => path.clone(),
+            };
+            import_file(&cmd, path, output_path)?;
+
+        // Otherwise, we walk the directory and mirror its structure within
+        // the output directory
+        } else {
+            for entry in WalkDir::new(path)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .filter(|e| file_format(&cmd, e.path()).is_some())
+            {
+                let output_path = match cmd.output.as_ref() {
+                    Some(dir) => match entry.path().strip_prefix(path) {
+                        Ok(rel_path) => dir.join(rel_path),
+                        Err(_) => dir.join(entry.file_name()),
+                    },
+                    None => entry.path().to_path_buf(),
+                };
+                import_file(&cmd, entry.path(), output_path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Determines the format of the file from the subcommand or, if not
+/// specified, from the file's extension
+fn file_format(cmd: &ImportSubcommand, path: &Path) -> Option<ImportFormat> {
+    cmd.from.or_else(|| {
+        path.extension()
+            .and_then(OsStr::to_str)
+            .and_then(ImportFormat::from_extension)
+    })
+}
+
+fn import_file(
+    cmd: &ImportSubcommand,
+    input_path: &Path,
+    output_path: PathBuf,
+) -> io::Result<()> {
+    let format = match file_format(cmd, input_path) {
+        Some(format) => format,
+        None => {
+            warn!("{:?} :: skipped due to unknown format!", input_path);
+            return Ok(());
+        }
+    };
+
+    let text = fs::read_to_string(input_path)?;
+    let vimwiki = format.to_vimwiki(&text);
+
+    if cmd.stdout {
+        println!("{}", vimwiki);
+        return Ok(());
+    }
+
+    let output_path = output_path.with_extension(&cmd.ext);
+    if output_path.exists() && !cmd.force {
+        warn!(
+            "{:?} :: skipped as {:?} already exists!",
+            input_path, output_path
+        );
+        return Ok(());
+    }
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    debug!("{:?} :: writing to {:?}", input_path, output_path);
+    fs::write(output_path, vimwiki)
+}
@@ -0,0 +1,124 @@
+use crate::{Ast, CommonOpt, KeywordsSubcommand};
+use serde::Serialize;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+use vimwiki::{
+    keywords::{find_keywords, KeywordOccurrence},
+    positions::{ColumnUnit, LineIndex, Position},
+    sources, HtmlConfig,
+};
+
+#[derive(Serialize)]
+struct PageKeyword {
+    path: PathBuf,
+    page: String,
+
+    /// Position of the keyword (counted from zero), or None if the text of
+    /// the page is unavailable such as when it is encrypted
+    position: Option<Position>,
+
+    /// Line of text containing the keyword
+    line: Option<String>,
+
+    #[serde(flatten)]
+    occurrence: KeywordOccurrence,
+}
+
+pub fn keywords(
+    cmd: KeywordsSubcommand,
+    opt: CommonOpt,
+    _config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let mut keywords = Vec::new();
+    for wiki in ast.wikis.iter() {
+        for file in wiki.files.iter() {
+            let page = file
+                .page_name(wiki.path.as_path())
+                .unwrap_or_else(|| file.path.to_string_lossy().to_string());
+
+            // NOTE: Encrypted pages are never read from disk as plain text,
+            //       so their keywords are reported without positions
+            let text = if file.encrypted {
+                None
+            } else {
+                fs::File::open(&file.path)
+                    .and_then(|x| {
+                        sources::read_to_string(io::BufReader::new(x))
+                    })
+                    .map(|(text, _)| text)
+                    .ok()
+            };
+            let index = text.as_deref().map(LineIndex::new);
+
+            for occurrence in find_keywords(&file.data) {
+                let name = occurrence.keyword.to_string();
+                if !cmd.keywords.is_empty()
+                    && !cmd
+                        .keywords
+                        .iter()
+                        .any(|x| x.eq_ignore_ascii_case(&name))
+                {
+                    continue;
+                }
+
+                let position = index.as_ref().and_then(|x| {
+                    x.position(occurrence.region.offset(), ColumnUnit::Char)
+                });
+                let line = position.and_then(|p| {
+                    index
+                        .as_ref()
+                        .and_then(|x| x.line(p.line))
+                        .map(|x| x.trim().to_string())
+                });
+
+                keywords.push(PageKeyword {
+                    path: file.path.to_path_buf(),
+                    page: page.clone(),
+                    position,
+                    line,
+                    occurrence,
+                });
+            }
+        }
+    }
+
+    let text = if cmd.json || opt.output_format().is_json() {
+        serde_json::to_string_pretty(&keywords)?
+    } else {
+        // Mirror the path:line:column format of compiler diagnostics so
+        // editors can jump to each keyword
+        let mut text = String::new();
+        for x in keywords.iter() {
+            let location = match x.position {
+                Some(p) => format!(
+                    "{}:{}:{}",
+                    x.path.to_string_lossy(),
+                    p.line + 1,
+                    p.column + 1
+                ),
+                None => x.path.to_string_lossy().to_string(),
+            };
+            let context = x
+                .line
+                .as_deref()
+                .or(x.occurrence.list_item.as_deref())
+                .or(x.occurrence.header.as_deref())
+                .unwrap_or_default();
+            text.push_str(&format!(
+                "{}: {}: {}\n",
+                location, x.occurrence.keyword, context
+            ));
+        }
+        text
+    };
+
+    if let Some(path) = cmd.output {
+        fs::write(path, text)
+    } else {
+        write!(io::stdout(), "{}", text)
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Ast, CommonOpt, LintSubcommand};
+use serde::Serialize;
+use std::{
+    fs,
+    io::{self, Write},
+};
+use vimwiki::{
+    graph::{AnalysisOptions, PageAnalysis},
+    resolve::{BrokenLink, LinkResolver},
+    HtmlConfig,
+};
+
+#[derive(Serialize)]
+struct LintReport {
+    #[serde(flatten)]
+    analysis: PageAnalysis,
+    broken_links: Vec<PageBrokenLink>,
+}
+
+#[derive(Serialize)]
+struct PageBrokenLink {
+    page: String,
+    #[serde(flatten)]
+    link: BrokenLink,
+}
+
+pub fn lint(
+    cmd: LintSubcommand,
+    opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let analysis = ast.to_link_graph(&config).analyze(&AnalysisOptions {
+        index: cmd.index.unwrap_or_else(|| String::from("index")),
+        stub_threshold: cmd.stub_threshold.unwrap_or(100),
+    });
+
+    let resolver = LinkResolver::from(&config);
+    let mut broken_links = Vec::new();
+    for wiki in ast.wikis.iter() {
+        for file in wiki.files.iter() {
+            let page = file
+                .page_name(wiki.path.as_path())
+                .unwrap_or_else(|| file.path.to_string_lossy().to_string());
+            for link in resolver.find_broken_links(&file.path, &file.data) {
+                broken_links.push(PageBrokenLink {
+                    page: page.clone(),
+                    link,
+                });
+            }
+        }
+    }
+
+    let text = if cmd.json || opt.output_format().is_json() {
+        serde_json::to_string_pretty(&LintReport {
+            analysis,
+            broken_links,
+        })?
+    } else {
+        let mut text = String::new();
+        for id in analysis.orphans.iter() {
+            text.push_str(&format!(
+                "{}: orphan page (no incoming links)\n",
+                id
+            ));
+        }
+        for id in analysis.stubs.iter() {
+            text.push_str(&format!("{}: stub page\n", id));
+        }
+        for id in analysis.unreachable.iter() {
+            text.push_str(&format!("{}: unreachable from index\n", id));
+        }
+        for x in broken_links.iter() {
+            text.push_str(&format!(
+                "{}: broken link {} ({})\n",
+                x.page, x.link.link, x.link.reason
+            ));
+        }
+        text
+    };
+
+    if let Some(path) = cmd.output {
+        fs::write(path, text)
+    } else {
+        write!(io::stdout(), "{}", text)
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{CommonOpt, ManSubcommand, Opt};
+use std::{
+    fs,
+    io::{self, Write},
+};
+use structopt::{
+    clap::{AppSettings, ErrorKind},
+    StructOpt,
+};
+
+pub fn man(cmd: ManSubcommand, _opt: CommonOpt) -> io::Result<()> {
+    let app = Opt::clap();
+    let about = app.p.meta.about.unwrap_or_default();
+
+    let mut page = format!(
+        ".TH VIMWIKI 1 \"\" \"vimwiki {}\"\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    page.push_str(&format!(".SH NAME\nvimwiki \\- {}\n", escape(about)));
+    page.push_str(".SH DESCRIPTION\n");
+    page.push_str(&long_help(&[])?);
+
+    page.push_str(".SH SUBCOMMANDS\n");
+    for sub in app.p.subcommands.iter() {
+        // NOTE: The help subcommand is added by clap and has no options of
+        //       its own worth describing
+        if sub.get_name() == "help" {
+            continue;
+        }
+
+        page.push_str(&format!(".SS {}\n", escape(sub.get_name())));
+        page.push_str(&long_help(&[sub.get_name()])?);
+    }
+
+    if let Some(path) = cmd.output {
+        fs::write(path, page)
+    } else {
+        write!(io::stdout(), "{}", page)
+    }
+}
+
+/// Produces the long help of the given subcommands (or of the cli itself if
+/// none are given) as a block of roff that is displayed as-is rather than
+/// filled
+///
+/// NOTE: Help is produced by parsing `--help` with a fresh parser as clap
+///       adds global arguments to subcommands each time help is written,
+///       failing when written more than once
+fn long_help(subcommands: &[&str]) -> io::Result<String> {
+    let args = std::iter::once("vimwiki")
+        .chain(subcommands.iter().copied())
+        .chain(std::iter::once("--help"));
+    let app = Opt::clap().global_setting(AppSettings::ColorNever);
+    let help = match app.get_matches_from_safe(args) {
+        Err(x) if x.kind == ErrorKind::HelpDisplayed => x.message,
+        Err(x) => return Err(io::Error::other(x.message)),
+        Ok(_) => return Err(io::Error::other("No help available")),
+    };
+
+    let mut text = String::from(".nf\n");
+    for line in help.lines() {
+        text.push_str(&escape(line));
+        text.push('\n');
+    }
+    text.push_str(".fi\n");
+    Ok(text)
+}
+
+/// Escapes text so roff does not interpret backslashes or lines starting
+/// with a control character as requests
+fn escape(text: &str) -> String {
+    let text = text.replace('\\', "\\e");
+    if text.starts_with('.') || text.starts_with('\'') {
+        format!("\\&{}", text)
+    } else {
+        text
+    }
+}
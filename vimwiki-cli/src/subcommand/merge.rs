@@ -0,0 +1,31 @@
+use crate::{CommonOpt, MergeSubcommand};
+use log::*;
+use std::{fs, io};
+use vimwiki::merge::merge_pages;
+
+pub fn merge(cmd: MergeSubcommand, _opt: CommonOpt) -> io::Result<()> {
+    let base = fs::read_to_string(&cmd.base)?;
+    let ours = fs::read_to_string(&cmd.ours)?;
+    let theirs = fs::read_to_string(&cmd.theirs)?;
+
+    let merge = merge_pages(&base, &ours, &theirs).map_err(|x| {
+        io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+    })?;
+
+    if cmd.stdout {
+        print!("{}", merge.text);
+    } else {
+        debug!("Writing merged page to {:?}", cmd.ours);
+        fs::write(&cmd.ours, &merge.text)?;
+    }
+
+    // Report conflicts as a failure so git knows to leave the file unmerged
+    if merge.has_conflicts() {
+        Err(io::Error::other(format!(
+            "{:?} has {} conflict(s)",
+            cmd.ours, merge.conflicts
+        )))
+    } else {
+        Ok(())
+    }
+}
@@ -1,9 +1,45 @@
+mod cat;
+mod check;
+mod completions;
 mod convert;
+mod daemon;
+mod export;
 mod format;
+mod graph;
+mod ics;
+mod import;
 mod inspect;
+mod keywords;
+mod lint;
+mod man;
+mod merge;
+mod refresh;
 mod serve;
+mod stats;
+mod tasks;
+mod theme;
+#[cfg(feature = "tui")]
+mod tui;
 
+pub use cat::cat;
+pub use check::check;
+pub use completions::completions;
 pub use convert::convert;
+pub use daemon::daemon;
+pub use export::export;
 pub use format::format;
+pub use graph::graph;
+pub use ics::ics;
+pub use import::import;
 pub use inspect::inspect;
+pub use keywords::keywords;
+pub use lint::lint;
+pub use man::man;
+pub use merge::merge;
+pub use refresh::refresh;
 pub use serve::serve;
+pub use stats::stats;
+pub use tasks::tasks;
+pub use theme::theme;
+#[cfg(feature = "tui")]
+pub use tui::tui;
@@ -0,0 +1,52 @@
+use crate::{Ast, CommonOpt, RefreshSubcommand};
+use log::*;
+use std::{
+    fs,
+    io::{self, Write},
+};
+use vimwiki::{dynamic, edit, HtmlConfig, Language, Page, ParseError};
+
+pub fn refresh(
+    cmd: RefreshSubcommand,
+    _opt: CommonOpt,
+    _config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let index = ast.to_query_index();
+
+    for wiki in ast.wikis.iter() {
+        for file in wiki.files.iter() {
+            if file.encrypted {
+                warn!("{:?} :: skipped as encrypted", file.path);
+                continue;
+            }
+
+            // NOTE: Parse the text again rather than use the loaded page so
+            //       regions are guaranteed to match the text being edited
+            let text = fs::read_to_string(&file.path)?;
+            let page: Page = Language::from_vimwiki_str(&text)
+                .parse()
+                .map_err(|x: ParseError| {
+                    io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+                })?;
+            let edits = dynamic::refresh(&text, &page, &index)
+                .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))?;
+
+            if edits.is_empty() {
+                continue;
+            }
+
+            if cmd.inline {
+                let text = edit::apply_edits(&text, &edits).map_err(|x| {
+                    io::Error::new(io::ErrorKind::InvalidData, x)
+                })?;
+                fs::write(&file.path, text)?;
+                info!("{:?} :: refreshed {} block(s)", file.path, edits.len());
+            } else {
+                writeln!(io::stdout(), "{}", file.path.to_string_lossy())?;
+            }
+        }
+    }
+
+    Ok(())
+}
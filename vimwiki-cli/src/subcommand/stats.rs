@@ -0,0 +1,132 @@
+use crate::{Ast, CommonOpt, StatsSubcommand};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+use vimwiki::{stats::PageStats, HtmlConfig};
+
+#[derive(Serialize)]
+struct StatsReport<'a> {
+    index: usize,
+    name: Option<&'a str>,
+    path: &'a Path,
+    page_count: usize,
+    total: StatsEntry<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pages: Option<BTreeMap<&'a str, StatsEntry<'a>>>,
+}
+
+#[derive(Serialize)]
+struct StatsEntry<'a> {
+    #[serde(flatten)]
+    stats: &'a PageStats,
+    reading_minutes: usize,
+    task_completion: Option<f64>,
+}
+
+impl<'a> From<&'a PageStats> for StatsEntry<'a> {
+    fn from(stats: &'a PageStats) -> Self {
+        Self {
+            stats,
+            reading_minutes: stats.reading_minutes(),
+            task_completion: stats.task_completion(),
+        }
+    }
+}
+
+pub fn stats(
+    cmd: StatsSubcommand,
+    opt: CommonOpt,
+    _config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let all_stats = ast.to_wiki_stats();
+    let reports: Vec<StatsReport> = all_stats
+        .iter()
+        .map(|(wiki, stats)| StatsReport {
+            index: wiki.index,
+            name: wiki.name.as_deref(),
+            path: wiki.path.as_path(),
+            page_count: stats.pages().len(),
+            total: StatsEntry::from(stats.total()),
+            pages: cmd.pages.then(|| {
+                stats
+                    .pages()
+                    .iter()
+                    .map(|(name, stats)| {
+                        (name.as_str(), StatsEntry::from(stats))
+                    })
+                    .collect()
+            }),
+        })
+        .collect();
+
+    let text = if cmd.json || opt.output_format().is_json() {
+        serde_json::to_string_pretty(&reports)?
+    } else {
+        let mut text = String::new();
+        for report in reports.iter() {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&format!(
+                "Wiki {}{} ({}): {} page(s)\n",
+                report.index,
+                report
+                    .name
+                    .map(|x| format!(" \"{}\"", x))
+                    .unwrap_or_default(),
+                report.path.to_string_lossy(),
+                report.page_count,
+            ));
+            write_entry(&mut text, "  ", &report.total);
+
+            for (name, entry) in report.pages.iter().flatten() {
+                text.push_str(&format!("  {}:\n", name));
+                write_entry(&mut text, "    ", entry);
+            }
+        }
+        text
+    };
+
+    if let Some(path) = cmd.output {
+        fs::write(path, text)
+    } else {
+        write!(io::stdout(), "{}", text)
+    }
+}
+
+fn write_entry(text: &mut String, indent: &str, entry: &StatsEntry) {
+    let stats = entry.stats;
+    text.push_str(&format!(
+        "{}words: {} (~{} min read)\n",
+        indent, stats.words, entry.reading_minutes
+    ));
+    text.push_str(&format!(
+        "{}tasks: {}/{} complete{}\n",
+        indent,
+        stats.completed_tasks,
+        stats.tasks,
+        entry
+            .task_completion
+            .map(|x| format!(" ({:.0}%)", x * 100.0))
+            .unwrap_or_default(),
+    ));
+    text.push_str(&format!(
+        "{}links: {} ({} external)\n",
+        indent, stats.links, stats.external_links
+    ));
+    if let Some(last_modified) = stats.last_modified {
+        text.push_str(&format!("{}last modified: {}\n", indent, last_modified));
+    }
+    let elements: Vec<String> = stats
+        .elements
+        .iter()
+        .map(|(ty, count)| format!("{}={}", ty, count))
+        .collect();
+    text.push_str(&format!("{}elements: {}\n", indent, elements.join(", ")));
+}
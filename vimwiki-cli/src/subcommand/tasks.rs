@@ -0,0 +1,64 @@
+use crate::{Ast, CommonOpt, TasksSubcommand};
+use serde::Serialize;
+use std::{
+    fs,
+    io::{self, Write},
+};
+use vimwiki::{tasks::DatedTask, HtmlConfig, WikiDate};
+
+#[derive(Serialize)]
+struct TasksReport<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overdue: Option<Vec<&'a DatedTask>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upcoming: Option<Vec<&'a DatedTask>>,
+}
+
+pub fn tasks(
+    cmd: TasksSubcommand,
+    opt: CommonOpt,
+    _config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let index = ast.to_task_index();
+    let today = cmd.today.unwrap_or_else(WikiDate::today);
+
+    // If neither filter is specified, we report both
+    let report = TasksReport {
+        overdue: (!cmd.upcoming).then(|| index.overdue(today)),
+        upcoming: (!cmd.overdue).then(|| index.upcoming(today, cmd.days)),
+    };
+
+    let text = if cmd.json || opt.output_format().is_json() {
+        serde_json::to_string_pretty(&report)?
+    } else {
+        let mut text = String::new();
+        for (title, tasks) in [
+            ("Overdue", report.overdue.as_ref()),
+            ("Upcoming", report.upcoming.as_ref()),
+        ]
+        .iter()
+        {
+            if let Some(tasks) = tasks {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(&format!("{}:\n", title));
+                for task in tasks.iter() {
+                    text.push_str(&format!(
+                        "- {} {}: {}\n",
+                        task.due, task.page, task.description
+                    ));
+                }
+            }
+        }
+        text
+    };
+
+    if let Some(path) = cmd.output {
+        fs::write(path, text)
+    } else {
+        write!(io::stdout(), "{}", text)
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{css, CommonOpt, ThemeSubcommand};
+use log::*;
+use std::io;
+use vimwiki::HtmlConfig;
+
+pub fn theme(
+    cmd: ThemeSubcommand,
+    opt: CommonOpt,
+    config: HtmlConfig,
+) -> io::Result<()> {
+    match cmd {
+        ThemeSubcommand::List => {
+            for theme in css::THEMES {
+                println!("{}\t{}", theme.name, theme.description);
+            }
+        }
+        ThemeSubcommand::Apply { theme, assets } => {
+            let css = css::load_theme(&theme)?;
+            for (_, wiki) in
+                config.wikis.iter().enumerate().filter(|(idx, wiki)| {
+                    opt.filter_by_wiki_idx_and_name(*idx, wiki.name.as_deref())
+                })
+            {
+                std::fs::create_dir_all(&wiki.path_html)?;
+                let css_path = wiki.path_html.join(&wiki.css_name);
+                info!("Writing css to {:?}", css_path);
+                std::fs::write(css_path, &css)?;
+
+                for path in assets.iter() {
+                    css::copy_assets(path, &wiki.path_html)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
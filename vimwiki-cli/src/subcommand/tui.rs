@@ -0,0 +1,473 @@
+use crate::{Ast, CommonOpt, TuiSubcommand};
+use log::*;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+use std::{io, path::PathBuf};
+use vimwiki::{
+    edit::FileEditor,
+    graph::{collect_links, LinkGraph},
+    tasks::{find_tasks, Task},
+    HtmlConfig, Language, Page, ParseError, PlainConfig, ToPlainText,
+};
+
+pub fn tui(
+    _cmd: TuiSubcommand,
+    _opt: CommonOpt,
+    config: HtmlConfig,
+    ast: Ast,
+) -> io::Result<()> {
+    let mut app = App::new(&config, ast)?;
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+/// Represents a page that can be browsed
+struct Entry {
+    /// Index of the wiki containing the page
+    wiki: u32,
+
+    /// Name of the page relative to the root of its wiki
+    name: String,
+
+    /// Id of the page within the link graph, which is also displayed
+    id: String,
+
+    path: PathBuf,
+    encrypted: bool,
+    page: Page<'static>,
+
+    /// Page rendered as plain text, used both for viewing and searching
+    text: String,
+}
+
+impl Entry {
+    fn set_page(&mut self, page: Page<'static>) -> io::Result<()> {
+        self.text = page
+            .to_plain_text(PlainConfig::default())
+            .map_err(|x| io::Error::other(x.to_string()))?;
+        self.page = page;
+        Ok(())
+    }
+}
+
+/// Represents something within the current page that can be acted upon
+enum Target {
+    /// Link with its text and the id of the page it targets, if any
+    Link {
+        text: String,
+        id: Option<String>,
+    },
+    Task(Task),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Focus {
+    List,
+    Page,
+}
+
+struct App {
+    entries: Vec<Entry>,
+    graph: LinkGraph,
+
+    /// Indexes of entries that match the search query
+    filtered: Vec<usize>,
+    list_state: ListState,
+    query: String,
+    searching: bool,
+
+    /// Index of the entry being viewed alongside the entries viewed before it
+    current: Option<usize>,
+    history: Vec<usize>,
+    scroll: u16,
+
+    targets: Vec<Target>,
+    target_idx: Option<usize>,
+
+    focus: Focus,
+    message: String,
+    quit: bool,
+}
+
+impl App {
+    fn new(config: &HtmlConfig, ast: Ast) -> io::Result<Self> {
+        let graph = ast.to_link_graph(config);
+        let mut entries = Vec::new();
+
+        for wiki in ast.wikis {
+            for file in wiki.files {
+                let name = match file.page_name(wiki.path.as_path()) {
+                    Some(name) => name,
+                    None => {
+                        warn!("{:?} is not within {:?}", file.path, wiki.path);
+                        continue;
+                    }
+                };
+                let mut entry = Entry {
+                    wiki: wiki.index as u32,
+                    id: graph.page_id(Some(wiki.index as u32), &name),
+                    name,
+                    path: file.path,
+                    encrypted: file.encrypted,
+                    page: Page::default(),
+                    text: String::new(),
+                };
+                entry.set_page(file.data)?;
+                entries.push(entry);
+            }
+        }
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut app = Self {
+            entries,
+            graph,
+            filtered: Vec::new(),
+            list_state: ListState::default(),
+            query: String::new(),
+            searching: false,
+            current: None,
+            history: Vec::new(),
+            scroll: 0,
+            targets: Vec::new(),
+            target_idx: None,
+            focus: Focus::List,
+            message: String::new(),
+            quit: false,
+        };
+        app.refilter();
+        Ok(app)
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key.code)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)])
+                .areas(frame.area());
+        let [list, view] = Layout::horizontal([
+            Constraint::Percentage(30),
+            Constraint::Percentage(70),
+        ])
+        .areas(main);
+
+        let current_focus = self.focus;
+        let focused = |focus| {
+            if current_focus == focus {
+                Style::new().bold()
+            } else {
+                Style::new().dim()
+            }
+        };
+
+        let entries = &self.entries;
+        let items: Vec<&str> = self
+            .filtered
+            .iter()
+            .map(|idx| entries[*idx].id.as_str())
+            .collect();
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(
+                    Block::bordered()
+                        .title("Pages")
+                        .border_style(focused(Focus::List)),
+                )
+                .highlight_style(Style::new().reversed()),
+            list,
+            &mut self.list_state,
+        );
+
+        let (title, text) = match self.current {
+            Some(idx) => (
+                self.entries[idx].id.as_str(),
+                self.entries[idx].text.as_str(),
+            ),
+            None => ("", ""),
+        };
+        frame.render_widget(
+            Paragraph::new(text)
+                .block(
+                    Block::bordered()
+                        .title(title)
+                        .border_style(focused(Focus::Page)),
+                )
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            view,
+        );
+
+        frame.render_widget(Line::from(self.status_line()), status);
+    }
+
+    fn status_line(&self) -> String {
+        if self.searching {
+            return format!("/{}", self.query);
+        }
+
+        if !self.message.is_empty() {
+            return self.message.clone();
+        }
+
+        match self.target_idx.map(|idx| &self.targets[idx]) {
+            Some(Target::Link { text, id }) => format!(
+                "link {} -> {} (enter to follow)",
+                text,
+                id.as_deref().unwrap_or("?")
+            ),
+            Some(Target::Task(task)) => format!(
+                "task {} (enter to toggle)",
+                task.description
+            ),
+            None => match self.focus {
+                Focus::List => String::from(
+                    "j/k move, enter open, / search, tab view page, q quit",
+                ),
+                Focus::Page => String::from(
+                    "j/k scroll, n/p select link or task, enter act, backspace back, tab pages, q quit",
+                ),
+            },
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> io::Result<()> {
+        self.message.clear();
+
+        if self.searching {
+            match code {
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.query.clear();
+                    self.refilter();
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.refilter();
+                }
+                KeyCode::Char(c) => {
+                    self.query.push(c);
+                    self.refilter();
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match (self.focus, code) {
+            (_, KeyCode::Char('q')) => self.quit = true,
+            (Focus::List, KeyCode::Char('j') | KeyCode::Down) => {
+                self.list_state.select_next()
+            }
+            (Focus::List, KeyCode::Char('k') | KeyCode::Up) => {
+                self.list_state.select_previous()
+            }
+            (Focus::List, KeyCode::Char('/')) => self.searching = true,
+            (Focus::List, KeyCode::Enter | KeyCode::Char('l')) => {
+                let selected = self
+                    .list_state
+                    .selected()
+                    .and_then(|idx| self.filtered.get(idx).copied());
+                if let Some(idx) = selected {
+                    self.open(idx);
+                }
+            }
+            (Focus::List, KeyCode::Tab) if self.current.is_some() => {
+                self.focus = Focus::Page
+            }
+            (Focus::Page, KeyCode::Char('j') | KeyCode::Down) => {
+                self.scroll = self.scroll.saturating_add(1)
+            }
+            (Focus::Page, KeyCode::Char('k') | KeyCode::Up) => {
+                self.scroll = self.scroll.saturating_sub(1)
+            }
+            (Focus::Page, KeyCode::Char('n')) => self.select_target(true),
+            (Focus::Page, KeyCode::Char('p')) => self.select_target(false),
+            (Focus::Page, KeyCode::Enter | KeyCode::Char(' ')) => {
+                self.act_on_target()?
+            }
+            (Focus::Page, KeyCode::Backspace | KeyCode::Char('h')) => {
+                if let Some(idx) = self.history.pop() {
+                    self.show(idx);
+                }
+            }
+            (Focus::Page, KeyCode::Tab | KeyCode::Esc) => {
+                self.focus = Focus::List
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Updates the entries shown in the list to those whose name or text
+    /// contains the search query, ignoring case
+    fn refilter(&mut self) {
+        let query = self.query.to_lowercase();
+        self.filtered = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                query.is_empty()
+                    || entry.id.to_lowercase().contains(&query)
+                    || entry.text.to_lowercase().contains(&query)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        self.list_state.select(if self.filtered.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    /// Views the entry at the index, remembering the entry being viewed
+    /// before it
+    fn open(&mut self, idx: usize) {
+        if let Some(current) = self.current {
+            if current != idx {
+                self.history.push(current);
+            }
+        }
+        self.show(idx);
+    }
+
+    fn show(&mut self, idx: usize) {
+        self.current = Some(idx);
+        self.focus = Focus::Page;
+        self.scroll = 0;
+        self.target_idx = None;
+        self.load_targets();
+    }
+
+    /// Gathers the links and tasks of the current page in document order
+    fn load_targets(&mut self) {
+        let entry = match self.current {
+            Some(idx) => &self.entries[idx],
+            None => return,
+        };
+
+        let mut targets: Vec<(usize, Target)> = collect_links(&entry.page)
+            .into_iter()
+            .map(|link| {
+                let offset = link.region().offset();
+                let text = link
+                    .to_plain_text(PlainConfig::default())
+                    .unwrap_or_default();
+                let id = self.graph.resolve_link(
+                    Some(entry.wiki),
+                    &entry.name,
+                    link.as_inner(),
+                );
+                (offset, Target::Link { text, id })
+            })
+            .chain(
+                find_tasks(&entry.page)
+                    .into_iter()
+                    .map(|task| (task.region.offset(), Target::Task(task))),
+            )
+            .collect();
+        targets.sort_by_key(|(offset, _)| *offset);
+
+        self.targets = targets.into_iter().map(|(_, x)| x).collect();
+        if let Some(idx) = self.target_idx {
+            if idx >= self.targets.len() {
+                self.target_idx = None;
+            }
+        }
+    }
+
+    fn select_target(&mut self, forward: bool) {
+        let len = self.targets.len();
+        if len == 0 {
+            self.message = String::from("No links or tasks in page");
+            return;
+        }
+
+        self.target_idx = Some(match (self.target_idx, forward) {
+            (None, true) => 0,
+            (None, false) => len - 1,
+            (Some(idx), true) => (idx + 1) % len,
+            (Some(idx), false) => (idx + len - 1) % len,
+        });
+    }
+
+    fn act_on_target(&mut self) -> io::Result<()> {
+        let target = match self.target_idx.map(|idx| &self.targets[idx]) {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+
+        match target {
+            Target::Link { id: Some(id), .. } => {
+                match self.entries.iter().position(|entry| &entry.id == id) {
+                    Some(idx) => self.open(idx),
+                    None => self.message = format!("No page named {}", id),
+                }
+            }
+            Target::Link { text, id: None } => {
+                self.message = format!("{} does not link to a page", text)
+            }
+            Target::Task(task) => {
+                let task = task.clone();
+                if let Err(x) = self.toggle_task(&task) {
+                    self.message = format!("Failed to toggle task: {}", x);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Toggles the task within the file of the current page, reloading the
+    /// page afterwards
+    fn toggle_task(&mut self, task: &Task) -> io::Result<()> {
+        let entry = match self.current {
+            Some(idx) => &mut self.entries[idx],
+            None => return Ok(()),
+        };
+
+        if entry.encrypted {
+            return Err(io::Error::other("encrypted pages are read-only"));
+        }
+
+        let mut editor =
+            FileEditor::open(entry.path.as_path()).map_err(io::Error::other)?;
+        let edit = task.to_toggle_edit(editor.text()).ok_or_else(|| {
+            io::Error::other("page changed since it was loaded")
+        })?;
+        editor.apply(&[edit]).map_err(io::Error::other)?;
+        editor.save().map_err(io::Error::other)?;
+
+        let page = Language::from_vimwiki_str(editor.text())
+            .parse::<Page>()
+            .map(Page::into_owned)
+            .map_err(|x: ParseError| io::Error::other(x.to_string()))?;
+        entry.set_page(page)?;
+        self.load_targets();
+
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     io,
     path::{Path, PathBuf},
 };
-use vimwiki::{HtmlConfig, HtmlWikiConfig, VimwikiConfig};
+use vimwiki::{settings::Settings, HtmlConfig, HtmlWikiConfig, VimwikiConfig};
 
 /// Attempts to load a vimwiki format config from a file
 pub fn load_format_config(path: &Path) -> io::Result<VimwikiConfig> {
@@ -16,10 +16,12 @@ pub fn load_format_config(path: &Path) -> io::Result<VimwikiConfig> {
     Ok(config)
 }
 
-/// Attempts to load an html config from a file, attempting to load wikis from
-/// vim/neovim if no wikis are defined or if merge = true
+/// Attempts to load an html config from a file, or from the settings if no
+/// file is given, attempting to load wikis from vim/neovim if no wikis are
+/// defined or if merge = true
 pub fn load_html_config(
     opt: &CommonOpt,
+    settings: &Settings,
     extra_paths: &[PathBuf],
 ) -> io::Result<HtmlConfig> {
     let CommonOpt {
@@ -41,7 +43,7 @@ pub fn load_html_config(
         let config_string = std::fs::read_to_string(path)?;
         toml::from_str(config_string.as_str())?
     } else {
-        HtmlConfig::default()
+        settings.to_html_config()
     };
 
     // Attempt to load wikis from vim if html config has no wikis or if
@@ -95,6 +97,9 @@ pub fn load_html_config(
         .map(|(_, wiki)| wiki)
         .collect();
 
+    // Custom uri schemes need to be known before any page is parsed
+    config.link.register_schemes();
+
     Ok(config)
 }
 
@@ -0,0 +1,126 @@
+use crate::{protocol, ClientError, ClientResult, Page, SearchResult};
+use serde::de::DeserializeOwned;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+};
+
+/// Represents a client of vimwiki-server, sending graphql queries over a
+/// stream in the same form as the server's stdin and socket modes
+pub struct Client<S> {
+    stream: BufReader<S>,
+    next_id: usize,
+}
+
+#[cfg(unix)]
+impl Client<std::os::unix::net::UnixStream> {
+    /// Connects to the server listening on the unix socket at the given path
+    pub fn connect(path: impl AsRef<Path>) -> ClientResult<Self> {
+        Ok(Self::new(std::os::unix::net::UnixStream::connect(path)?))
+    }
+}
+
+impl<S: Read + Write> Client<S> {
+    /// Creates a client communicating with the server over the stream
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            next_id: 0,
+        }
+    }
+
+    /// Sends the graphql query (or mutation) and waits for its data
+    pub fn query<T: DeserializeOwned>(
+        &mut self,
+        query: &str,
+    ) -> ClientResult<T> {
+        self.next_id += 1;
+        let id = self.next_id;
+
+        let request = protocol::encode_request(id, query)?;
+        self.stream.get_mut().write_all(request.as_bytes())?;
+        self.stream.get_mut().flush()?;
+
+        let mut line = String::new();
+        if self.stream.read_line(&mut line)? == 0 {
+            return Err(ClientError::Disconnected);
+        }
+        protocol::decode_response(id, &line)
+    }
+
+    /// Finds the page with the given name (its path relative to the wiki
+    /// without extension, e.g. `projects/todo`) within the wiki with the
+    /// given index
+    pub fn get_page(
+        &mut self,
+        wiki: usize,
+        name: &str,
+    ) -> ClientResult<Option<Page>> {
+        let data: protocol::PageByName =
+            self.query(&protocol::get_page(wiki, name))?;
+        Ok(data.page_by_name)
+    }
+
+    /// Searches the pages of the wiki with the given index, or of every wiki
+    /// if not given, for those containing every term of the text, returning
+    /// up to `limit` results from best to worst match
+    pub fn search(
+        &mut self,
+        text: &str,
+        wiki: Option<usize>,
+        limit: usize,
+    ) -> ClientResult<Vec<SearchResult>> {
+        let data: protocol::Search =
+            self.query(&protocol::search(text, wiki, limit))?;
+        Ok(data.search)
+    }
+
+    /// Toggles the task whose list item contains the given byte offset of
+    /// the file at the path, returning the file as parsed afterwards
+    pub fn toggle_task(
+        &mut self,
+        path: impl AsRef<Path>,
+        offset: usize,
+    ) -> ClientResult<Page> {
+        let data: protocol::ToggleTask =
+            self.query(&protocol::toggle_task(path.as_ref(), offset))?;
+        Ok(data.toggle_task)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn search_should_send_query_and_decode_response() {
+        let (client, server) = UnixStream::pair().unwrap();
+        let mut client = Client::new(client);
+
+        let server = std::thread::spawn(move || {
+            let mut server = BufReader::new(server);
+            let mut line = String::new();
+            server.read_line(&mut line).unwrap();
+            let input: Value = serde_json::from_str(&line).unwrap();
+            let payload = json!({ "data": { "search": [{
+                "wiki": 0,
+                "path": "/wiki/rust.wiki",
+                "page": "rust",
+                "title": "Rust",
+                "excerpt": null,
+                "score": 10
+            }] } });
+            let output =
+                json!({ "id": input["id"], "payload": payload.to_string() });
+            writeln!(server.get_mut(), "{}", output).unwrap();
+        });
+
+        let results = client.search("rust", Some(0), 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].page, "rust");
+        assert_eq!(results[0].score, 10);
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{protocol, ClientError, ClientResult, Page, SearchResult};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use tokio::io::{
+    AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader,
+};
+
+/// Represents a client of vimwiki-server, sending graphql queries over a
+/// stream in the same form as the server's stdin and socket modes
+pub struct Client<S> {
+    stream: BufReader<S>,
+    next_id: usize,
+}
+
+#[cfg(unix)]
+impl Client<tokio::net::UnixStream> {
+    /// Connects to the server listening on the unix socket at the given path
+    pub async fn connect(path: impl AsRef<Path>) -> ClientResult<Self> {
+        Ok(Self::new(tokio::net::UnixStream::connect(path).await?))
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Creates a client communicating with the server over the stream
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            next_id: 0,
+        }
+    }
+
+    /// Sends the graphql query (or mutation) and waits for its data
+    pub async fn query<T: DeserializeOwned>(
+        &mut self,
+        query: &str,
+    ) -> ClientResult<T> {
+        self.next_id += 1;
+        let id = self.next_id;
+
+        let request = protocol::encode_request(id, query)?;
+        self.stream.get_mut().write_all(request.as_bytes()).await?;
+        self.stream.get_mut().flush().await?;
+
+        let mut line = String::new();
+        if self.stream.read_line(&mut line).await? == 0 {
+            return Err(ClientError::Disconnected);
+        }
+        protocol::decode_response(id, &line)
+    }
+
+    /// Finds the page with the given name (its path relative to the wiki
+    /// without extension, e.g. `projects/todo`) within the wiki with the
+    /// given index
+    pub async fn get_page(
+        &mut self,
+        wiki: usize,
+        name: &str,
+    ) -> ClientResult<Option<Page>> {
+        let data: protocol::PageByName =
+            self.query(&protocol::get_page(wiki, name)).await?;
+        Ok(data.page_by_name)
+    }
+
+    /// Searches the pages of the wiki with the given index, or of every wiki
+    /// if not given, for those containing every term of the text, returning
+    /// up to `limit` results from best to worst match
+    pub async fn search(
+        &mut self,
+        text: &str,
+        wiki: Option<usize>,
+        limit: usize,
+    ) -> ClientResult<Vec<SearchResult>> {
+        let data: protocol::Search =
+            self.query(&protocol::search(text, wiki, limit)).await?;
+        Ok(data.search)
+    }
+
+    /// Toggles the task whose list item contains the given byte offset of
+    /// the file at the path, returning the file as parsed afterwards
+    pub async fn toggle_task(
+        &mut self,
+        path: impl AsRef<Path>,
+        offset: usize,
+    ) -> ClientResult<Page> {
+        let data: protocol::ToggleTask = self
+            .query(&protocol::toggle_task(path.as_ref(), offset))
+            .await?;
+        Ok(data.toggle_task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[tokio::test]
+    async fn get_page_should_send_query_and_decode_response() {
+        let (client, server) = tokio::io::duplex(1024);
+        let mut client = Client::new(client);
+
+        let server = tokio::spawn(async move {
+            let mut server = BufReader::new(server);
+            let mut line = String::new();
+            server.read_line(&mut line).await.unwrap();
+            let input: Value = serde_json::from_str(&line).unwrap();
+            let payload = json!({ "data": { "pageByName": {
+                "id": 9, "path": "/wiki/index.wiki", "checksum": "abc"
+            } } });
+            let output =
+                json!({ "id": input["id"], "payload": payload.to_string() });
+            server
+                .get_mut()
+                .write_all(format!("{}\n", output).as_bytes())
+                .await
+                .unwrap();
+            input["payload"].as_str().unwrap().to_string()
+        });
+
+        let page = client.get_page(0, "index").await.unwrap().unwrap();
+        assert_eq!(page.id, 9);
+        assert_eq!(page.path, Path::new("/wiki/index.wiki"));
+        assert_eq!(page.checksum, "abc");
+        assert!(server.await.unwrap().contains("pageByName"));
+    }
+
+    #[tokio::test]
+    async fn query_should_fail_if_server_disconnects() {
+        let (client, server) = tokio::io::duplex(1024);
+        let mut client = Client::new(client);
+        drop(server);
+
+        assert!(client.search("rust", None, 10).await.is_err());
+    }
+}
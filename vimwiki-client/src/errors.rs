@@ -0,0 +1,27 @@
+use derive_more::{Display, Error, From};
+use std::io;
+
+/// Alias for a result with a client error
+pub type ClientResult<T, E = ClientError> = std::result::Result<T, E>;
+
+#[derive(Debug, Display, Error, From)]
+pub enum ClientError {
+    #[display(fmt = "Could not communicate with server: {}", _0)]
+    Io(io::Error),
+
+    #[display(fmt = "Could not encode or decode message: {}", _0)]
+    Json(serde_json::Error),
+
+    #[display(fmt = "Server closed the connection")]
+    Disconnected,
+
+    #[display(fmt = "Expected response {} but got {}", expected, actual)]
+    UnexpectedId { expected: usize, actual: usize },
+
+    #[display(fmt = "Server responded with errors: {}", "_0.join(\"; \")")]
+    #[from(ignore)]
+    Server(#[error(not(source))] Vec<String>),
+
+    #[display(fmt = "Server responded without data")]
+    MissingData,
+}
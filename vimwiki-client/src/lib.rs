@@ -0,0 +1,12 @@
+mod client;
+mod errors;
+mod protocol;
+mod types;
+
+pub use client::Client;
+pub use errors::{ClientError, ClientResult};
+pub use types::{Page, SearchResult};
+
+/// Client that blocks the current thread while waiting on the server
+#[cfg(feature = "blocking")]
+pub mod blocking;
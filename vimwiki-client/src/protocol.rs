@@ -0,0 +1,179 @@
+use crate::{ClientError, ClientResult, Page, SearchResult};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path;
+
+/// Fields requested of each page
+const PAGE_FIELDS: &str = "id path checksum";
+
+/// Fields requested of each search result
+const SEARCH_RESULT_FIELDS: &str = "wiki path page title excerpt score";
+
+/// Represents a line sent to the server, in the same form as the input of
+/// its stdin and socket modes
+#[derive(Serialize)]
+struct Input<'a> {
+    id: usize,
+    payload: &'a str,
+}
+
+/// Represents a line received from the server
+#[derive(Deserialize)]
+struct Output {
+    id: usize,
+    payload: String,
+}
+
+/// Represents the serialized graphql response within the payload of output
+#[derive(Deserialize)]
+struct Response {
+    #[serde(default)]
+    data: Option<serde_json::Value>,
+
+    #[serde(default)]
+    errors: Vec<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+    message: String,
+}
+
+/// Encodes the query as a line to send to the server, ending with a newline
+pub fn encode_request(id: usize, query: &str) -> ClientResult<String> {
+    let mut line = serde_json::to_string(&Input { id, payload: query })?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// Decodes the line received from the server in response to the request
+/// with the given id into the data of the query
+pub fn decode_response<T: DeserializeOwned>(
+    id: usize,
+    line: &str,
+) -> ClientResult<T> {
+    let output: Output = serde_json::from_str(line)?;
+    if output.id != id {
+        return Err(ClientError::UnexpectedId {
+            expected: id,
+            actual: output.id,
+        });
+    }
+
+    let response: Response = serde_json::from_str(&output.payload)?;
+    if !response.errors.is_empty() {
+        return Err(ClientError::Server(
+            response.errors.into_iter().map(|x| x.message).collect(),
+        ));
+    }
+
+    match response.data {
+        Some(data) => Ok(serde_json::from_value(data)?),
+        None => Err(ClientError::MissingData),
+    }
+}
+
+/// Produces a graphql string literal containing the text
+fn string_literal(text: &str) -> String {
+    // NOTE: Json strings are also valid graphql strings
+    serde_json::Value::from(text).to_string()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageByName {
+    pub page_by_name: Option<Page>,
+}
+
+pub fn get_page(wiki: usize, name: &str) -> String {
+    format!(
+        "{{ pageByName(wiki: {}, name: {}) {{ {} }} }}",
+        wiki,
+        string_literal(name),
+        PAGE_FIELDS
+    )
+}
+
+#[derive(Deserialize)]
+pub struct Search {
+    pub search: Vec<SearchResult>,
+}
+
+pub fn search(text: &str, wiki: Option<usize>, limit: usize) -> String {
+    let wiki = wiki.map(|x| format!(", wiki: {}", x)).unwrap_or_default();
+    format!(
+        "{{ search(text: {}{}, limit: {}) {{ {} }} }}",
+        string_literal(text),
+        wiki,
+        limit,
+        SEARCH_RESULT_FIELDS
+    )
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToggleTask {
+    pub toggle_task: Page,
+}
+
+pub fn toggle_task(path: &Path, offset: usize) -> String {
+    format!(
+        "mutation {{ toggleTask(path: {}, offset: {}) {{ {} }} }}",
+        string_literal(&path.to_string_lossy()),
+        offset,
+        PAGE_FIELDS
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(id: usize, payload: &str) -> String {
+        serde_json::json!({ "id": id, "payload": payload }).to_string()
+    }
+
+    #[test]
+    fn encode_request_should_produce_a_line_of_input() {
+        let line = encode_request(3, "{ wikis { index } }").unwrap();
+        assert_eq!(line, "{\"id\":3,\"payload\":\"{ wikis { index } }\"}\n");
+    }
+
+    #[test]
+    fn decode_response_should_return_data_or_errors() {
+        let line = output(1, r#"{"data":{"search":[]}}"#);
+        let data: Search = decode_response(1, &line).unwrap();
+        assert!(data.search.is_empty());
+
+        let line = output(2, r#"{"data":null,"errors":[{"message":"bad"}]}"#);
+        match decode_response::<Search>(2, &line) {
+            Err(ClientError::Server(x)) => assert_eq!(x, ["bad"]),
+            x => panic!("Unexpected result: {:?}", x.map(|_| ())),
+        }
+
+        match decode_response::<Search>(5, &output(4, "{}")) {
+            Err(ClientError::UnexpectedId { expected, actual }) => {
+                assert_eq!((expected, actual), (5, 4))
+            }
+            x => panic!("Unexpected result: {:?}", x.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn queries_should_escape_strings() {
+        assert_eq!(
+            get_page(1, "a \"b\"\n"),
+            "{ pageByName(wiki: 1, name: \"a \\\"b\\\"\\n\") { id path checksum } }"
+        );
+        assert_eq!(
+            search("rust", None, 5),
+            format!(
+                "{{ search(text: \"rust\", limit: 5) {{ {} }} }}",
+                SEARCH_RESULT_FIELDS
+            )
+        );
+        assert!(search("rust", Some(2), 5).contains(", wiki: 2,"));
+        assert!(toggle_task(Path::new("/a.wiki"), 7).starts_with(
+            "mutation { toggleTask(path: \"/a.wiki\", offset: 7)"
+        ));
+    }
+}
@@ -0,0 +1,39 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Represents a file parsed into a page by the server
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Page {
+    /// Id of the file within the server
+    pub id: u64,
+
+    /// Path to the file
+    pub path: PathBuf,
+
+    /// Checksum of the contents of the file when it was last parsed
+    pub checksum: String,
+}
+
+/// Represents a page whose title or text contains every term of a search
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct SearchResult {
+    /// Index of the wiki containing the page
+    pub wiki: usize,
+
+    /// Path to the file of the page
+    pub path: PathBuf,
+
+    /// Name of the page relative to its wiki
+    pub page: String,
+
+    /// Title of the page, otherwise the text of its first header, otherwise
+    /// its name
+    pub title: String,
+
+    /// Line of plain text containing the first term, trimmed and shortened
+    pub excerpt: Option<String>,
+
+    /// How well the page matches, where occurrences of terms within the
+    /// title count for more than those within the text
+    pub score: usize,
+}
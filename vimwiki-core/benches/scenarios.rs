@@ -0,0 +1,27 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::time::Duration;
+use vimwiki_core::{benchmarks, Language, Page};
+
+fn parse_scenarios_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse scenario");
+
+    for scenario in benchmarks::scenarios() {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(scenario.name),
+            &scenario.input,
+            |b, s| {
+                let language = Language::from_vimwiki_str(s);
+                b.iter(|| language.parse::<Page>().expect("Failed to parse"))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().measurement_time(Duration::new(15, 0));
+    targets = parse_scenarios_benchmark
+}
+criterion_main!(benches);
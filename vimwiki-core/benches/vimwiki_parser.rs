@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use std::{fs, path::PathBuf, time::Duration};
-use vimwiki::{Language, Page};
+use vimwiki::{Element, InlineElement, IntoChildren, Language, Located, Page};
 
 fn parse_page_benchmark(c: &mut Criterion) {
     let base =
@@ -20,9 +20,68 @@ fn parse_page_benchmark(c: &mut Criterion) {
     );
 }
 
+/// Generates a page made mostly of links, one paragraph per line with a mix of
+/// raw, wiki, and transclusion links
+fn link_heavy_page(lines: usize) -> String {
+    (0..lines)
+        .map(|i| {
+            format!(
+                "See https://example.com/page/{0}?q={0} and [[notes/page {0}|\
+                 note {0}]] or [[#section-{0}]] with {{{{images/{0}.png}}}} \
+                 and [[https://example.org/{0}#top|more]]\n\n",
+                i
+            )
+        })
+        .collect()
+}
+
+/// Visits every link of the page, requesting the path of each uri so that
+/// it has to be parsed into its parts
+fn inspect_links(page: &Page) -> usize {
+    let mut stack: Vec<Located<Element>> = page
+        .elements()
+        .iter()
+        .map(|x| x.as_ref().map(|x| Element::from(x.to_borrowed())))
+        .collect();
+
+    let mut total = 0;
+    while let Some(next) = stack.pop() {
+        if let Element::Inline(InlineElement::Link(x)) = next.as_inner() {
+            total += x.data().uri_ref.path().len();
+        }
+        stack.extend(next.into_inner().into_children());
+    }
+    total
+}
+
+fn parse_link_heavy_page_benchmark(c: &mut Criterion) {
+    let file_contents = link_heavy_page(500);
+
+    c.bench_with_input(
+        BenchmarkId::new("parse page", "link-heavy"),
+        &file_contents,
+        |b, s| {
+            let language = Language::from_vimwiki_str(s);
+            b.iter(|| language.parse::<Page>().expect("Failed to parse"))
+        },
+    );
+
+    c.bench_with_input(
+        BenchmarkId::new("parse page and inspect links", "link-heavy"),
+        &file_contents,
+        |b, s| {
+            let language = Language::from_vimwiki_str(s);
+            b.iter(|| {
+                let page: Page = language.parse().expect("Failed to parse");
+                inspect_links(&page)
+            })
+        },
+    );
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default().measurement_time(Duration::new(15, 0));
-    targets = parse_page_benchmark
+    targets = parse_page_benchmark, parse_link_heavy_page_benchmark
 }
 criterion_main!(benches);
@@ -0,0 +1,266 @@
+use crate::{Language, Page};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Page used as a representative example of a typical vimwiki document
+pub static TYPICAL_PAGE: &str =
+    include_str!("../benches/fixtures/vimwiki/specification.wiki");
+
+/// Represents some vimwiki input whose parsing time is measured
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scenario {
+    /// Unique name of the scenario
+    pub name: &'static str,
+
+    /// Vimwiki text to parse
+    pub input: String,
+}
+
+impl Scenario {
+    pub fn new(name: &'static str, input: impl Into<String>) -> Self {
+        Self {
+            name,
+            input: input.into(),
+        }
+    }
+
+    /// Parses the input of the scenario as a page, panicking if the input
+    /// fails to parse
+    pub fn parse(&self) -> Page<'_> {
+        Language::from_vimwiki_str(&self.input)
+            .parse()
+            .unwrap_or_else(|x| panic!("{} failed to parse: {}", self.name, x))
+    }
+}
+
+/// Returns the standard set of scenarios used to evaluate parser performance
+pub fn scenarios() -> Vec<Scenario> {
+    vec![
+        Scenario::new("typical page", TYPICAL_PAGE),
+        Scenario::new("pathological nesting", pathological_nesting(16)),
+        Scenario::new("huge table", huge_table(1000, 10)),
+        Scenario::new("huge list", huge_list(5000)),
+    ]
+}
+
+/// Produces a list nested `depth` levels deep where each item also contains
+/// decorated text nested within itself, which exercises the parsers that
+/// have to look ahead the most
+pub fn pathological_nesting(depth: usize) -> String {
+    let mut text = String::new();
+
+    for i in 0..depth {
+        let indent = " ".repeat(i * 2);
+        let open = "*_~~".repeat(1 + i % 3);
+        let close: String = open.chars().rev().collect();
+        text.push_str(&format!(
+            "{}- item {} with {}nested{} text and [[link{}|desc]]\n",
+            indent, i, open, close, i
+        ));
+    }
+
+    text
+}
+
+/// Produces a table with a header row, a divider row, and `rows` rows of
+/// `cols` columns each
+pub fn huge_table(rows: usize, cols: usize) -> String {
+    let make_row = |f: &dyn Fn(usize) -> String| {
+        let cells: Vec<String> = (0..cols).map(f).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    let mut text = make_row(&|c| format!("Header {}", c));
+    text.push_str(&make_row(&|_| String::from("---")));
+    for r in 0..rows {
+        text.push_str(&make_row(&|c| format!("*cell* {}x{}", r, c)));
+    }
+
+    text
+}
+
+/// Produces a list of `items` items alternating between different list
+/// types and todo statuses
+pub fn huge_list(items: usize) -> String {
+    let mut text = String::new();
+
+    for i in 0..items {
+        let status = match i % 3 {
+            0 => "[ ] ",
+            1 => "[X] ",
+            _ => "",
+        };
+        text.push_str(&format!(
+            "- {}item {} with `code` and $math$ :tag{}:\n",
+            status, i, i
+        ));
+    }
+
+    text
+}
+
+/// Represents the time taken to parse a scenario some number of times
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Measurement {
+    /// Name of the scenario that was measured
+    pub name: String,
+
+    /// Total number of times the scenario was parsed
+    pub iterations: u32,
+
+    /// Total time spent parsing the scenario across all iterations
+    pub total: Duration,
+}
+
+impl Measurement {
+    /// Average time spent parsing the scenario once
+    pub fn mean(&self) -> Duration {
+        if self.iterations > 0 {
+            self.total / self.iterations
+        } else {
+            Duration::default()
+        }
+    }
+}
+
+/// Parses the scenario the given number of times, measuring the total time
+/// spent parsing
+pub fn run(scenario: &Scenario, iterations: u32) -> Measurement {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        let _ = scenario.parse();
+    }
+
+    Measurement {
+        name: scenario.name.to_string(),
+        iterations,
+        total: start.elapsed(),
+    }
+}
+
+/// Runs all standard [`scenarios`] the given number of times each
+pub fn run_all(iterations: u32) -> Vec<Measurement> {
+    scenarios().iter().map(|s| run(s, iterations)).collect()
+}
+
+/// Represents a scenario whose mean parsing time increased beyond what is
+/// allowed relative to a baseline
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Regression {
+    /// Name of the scenario that regressed
+    pub name: String,
+
+    /// Mean parsing time of the baseline
+    pub baseline: Duration,
+
+    /// Mean parsing time of the current measurement
+    pub current: Duration,
+
+    /// Ratio of current mean to baseline mean (e.g. 1.5 means 50% slower)
+    pub ratio: f64,
+}
+
+/// Compares current measurements against baseline measurements, returning
+/// any scenario whose mean became slower by more than the given tolerance
+/// (e.g. 0.1 allows up to 10% slower); scenarios missing from the baseline
+/// are ignored
+pub fn find_regressions(
+    baseline: &[Measurement],
+    current: &[Measurement],
+    tolerance: f64,
+) -> Vec<Regression> {
+    current
+        .iter()
+        .filter_map(|c| {
+            let b = baseline.iter().find(|b| b.name == c.name)?;
+            let (b_mean, c_mean) = (b.mean(), c.mean());
+            if b_mean.as_nanos() == 0 {
+                return None;
+            }
+
+            let ratio = c_mean.as_secs_f64() / b_mean.as_secs_f64();
+            if ratio > 1.0 + tolerance {
+                Some(Regression {
+                    name: c.name.to_string(),
+                    baseline: b_mean,
+                    current: c_mean,
+                    ratio,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(name: &str, millis: u64) -> Measurement {
+        Measurement {
+            name: name.to_string(),
+            iterations: 2,
+            total: Duration::from_millis(millis * 2),
+        }
+    }
+
+    #[test]
+    fn scenarios_should_all_parse() {
+        for scenario in scenarios() {
+            assert!(
+                !scenario.parse().elements().is_empty(),
+                "{}",
+                scenario.name
+            );
+        }
+    }
+
+    #[test]
+    fn pathological_nesting_beyond_max_depth_should_still_parse() {
+        let input = pathological_nesting(100);
+        let result: Result<Page, _> =
+            Language::from_vimwiki_str(&input).parse();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn huge_table_should_parse_as_a_single_table() {
+        let scenario = Scenario::new("table", huge_table(10, 3));
+        let page = scenario.parse();
+        assert_eq!(page.elements().len(), 1);
+        assert!(page.elements()[0].as_inner().is_table());
+    }
+
+    #[test]
+    fn huge_list_should_parse_as_a_single_list() {
+        let scenario = Scenario::new("list", huge_list(10));
+        let page = scenario.parse();
+        assert_eq!(page.elements().len(), 1);
+        assert!(page.elements()[0].as_inner().is_list());
+    }
+
+    #[test]
+    fn run_should_record_iterations() {
+        let scenario = Scenario::new("small", "some text");
+        let measurement = run(&scenario, 3);
+        assert_eq!(measurement.name, "small");
+        assert_eq!(measurement.iterations, 3);
+    }
+
+    #[test]
+    fn find_regressions_should_report_scenarios_slower_than_tolerance() {
+        let baseline = vec![measurement("a", 10), measurement("b", 10)];
+        let current = vec![
+            measurement("a", 11),
+            measurement("b", 20),
+            measurement("c", 100),
+        ];
+
+        let regressions = find_regressions(&baseline, &current, 0.2);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].name, "b");
+        assert_eq!(regressions[0].baseline, Duration::from_millis(10));
+        assert_eq!(regressions[0].current, Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    resolve::LinkResolver, sources, Language, Page, ParseError, Region,
+    ToVimwikiString, VimwikiConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+/// Represents the checks to run over files, where each check that is not
+/// configured is skipped
+#[derive(Clone, Debug, Default)]
+pub struct CheckOptions {
+    /// Pages with fewer bytes of content than this are reported as stubs
+    pub stub_threshold: Option<usize>,
+
+    /// Resolver used to report links whose targets do not exist
+    pub links: Option<LinkResolver>,
+
+    /// Config used to report files whose text differs from their formatted
+    /// text
+    pub format: Option<VimwikiConfig>,
+}
+
+/// Represents the kind of problem found by a check
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    /// File could not be read
+    Read,
+
+    /// File could not be parsed as vimwiki
+    Parse,
+
+    /// Page has less content than the stub threshold
+    Stub,
+
+    /// Link targets a file that does not exist
+    BrokenLink,
+
+    /// File is not formatted
+    Format,
+}
+
+/// Represents a problem found within a file by a check
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckIssue {
+    /// Path to the file containing the problem
+    pub path: PathBuf,
+
+    /// Kind of problem
+    pub kind: CheckKind,
+
+    /// Region of the file where the problem was found, or none if the
+    /// problem concerns the file as a whole
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<Region>,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+/// Represents the problems found by checking a set of files
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckReport {
+    /// Total files that were checked
+    pub files: usize,
+
+    /// Problems found within the files in the order the files were checked
+    pub issues: Vec<CheckIssue>,
+}
+
+impl CheckReport {
+    /// Whether or not no problems were found
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Runs the configured checks over each file at the given paths
+pub fn check<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    options: &CheckOptions,
+) -> CheckReport {
+    let mut report = CheckReport::default();
+
+    for path in paths {
+        let path = path.as_ref();
+        report.files += 1;
+
+        match File::open(path).and_then(sources::read_to_string) {
+            Ok((text, _)) => {
+                report.issues.extend(check_text(path, &text, options))
+            }
+            Err(x) => report.issues.push(CheckIssue {
+                path: path.to_path_buf(),
+                kind: CheckKind::Read,
+                region: None,
+                message: x.to_string(),
+            }),
+        }
+    }
+
+    report
+}
+
+/// Runs the configured checks over the text of the file at the given path
+pub fn check_text(
+    path: &Path,
+    text: &str,
+    options: &CheckOptions,
+) -> Vec<CheckIssue> {
+    let issue = |kind, region, message| CheckIssue {
+        path: path.to_path_buf(),
+        kind,
+        region,
+        message,
+    };
+
+    let page: Page = match Language::from_vimwiki_str(text).parse() {
+        Ok(page) => page,
+        Err(x) => {
+            let x: ParseError = x;
+            return vec![issue(CheckKind::Parse, None, x.to_string())];
+        }
+    };
+
+    let mut issues = Vec::new();
+
+    if let Some(threshold) = options.stub_threshold {
+        let size = page
+            .elements()
+            .iter()
+            .map(|x| x.region().offset() + x.region().len())
+            .max()
+            .unwrap_or_default();
+        if size < threshold {
+            issues.push(issue(
+                CheckKind::Stub,
+                None,
+                format!(
+                    "Page has {} bytes of content, fewer than {}",
+                    size, threshold
+                ),
+            ));
+        }
+    }
+
+    if let Some(resolver) = options.links.as_ref() {
+        for link in resolver.find_broken_links(path, &page) {
+            issues.push(issue(
+                CheckKind::BrokenLink,
+                Some(link.region),
+                format!("Broken link {}: {}", link.link, link.reason),
+            ));
+        }
+    }
+
+    if let Some(config) = options.format.as_ref() {
+        match page.to_vimwiki_string(config.clone()) {
+            Ok(formatted) if formatted.trim_end() != text.trim_end() => issues
+                .push(issue(
+                    CheckKind::Format,
+                    None,
+                    String::from("File is not formatted"),
+                )),
+            Ok(_) => {}
+            Err(x) => {
+                issues.push(issue(CheckKind::Format, None, x.to_string()))
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(issues: &[CheckIssue]) -> Vec<CheckKind> {
+        issues.iter().map(|x| x.kind).collect()
+    }
+
+    #[test]
+    fn check_text_should_only_run_configured_checks() {
+        let path = Path::new("/missing/wiki/index.wiki");
+        let text = "= Header =\n[[missing page]]\n";
+        assert!(check_text(path, text, &CheckOptions::default()).is_empty());
+
+        let issues = check_text(
+            path,
+            text,
+            &CheckOptions {
+                stub_threshold: Some(100),
+                links: Some(LinkResolver::default()),
+                format: None,
+            },
+        );
+        assert_eq!(kinds(&issues), [CheckKind::Stub, CheckKind::BrokenLink]);
+        assert_eq!(issues[1].region, Some(Region::new_at_depth(11, 16, 1)));
+    }
+
+    #[test]
+    fn check_text_should_report_unformatted_files() {
+        let path = Path::new("index.wiki");
+        let options = CheckOptions {
+            format: Some(VimwikiConfig::default()),
+            ..Default::default()
+        };
+
+        assert!(check_text(path, "= Header =\n", &options).is_empty());
+        assert_eq!(
+            kinds(&check_text(path, "=   Header   =\n", &options)),
+            [CheckKind::Format]
+        );
+    }
+
+    #[test]
+    fn check_should_report_files_that_cannot_be_read() {
+        let report =
+            check(["/missing/wiki/index.wiki"], &CheckOptions::default());
+        assert_eq!(report.files, 1);
+        assert_eq!(kinds(&report.issues), [CheckKind::Read]);
+        assert!(!report.is_ok());
+    }
+}
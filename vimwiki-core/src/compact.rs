@@ -0,0 +1,36 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Re-exported so that code generated by the `_compact` macros can
+/// deserialize its element once, on first use
+pub use once_cell::sync::Lazy;
+
+/// Error encountered when converting elements to or from bytes
+pub type CompactError = bincode::Error;
+
+/// Converts the element into its compact binary representation
+pub fn to_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, CompactError> {
+    bincode::serialize(value)
+}
+
+/// Restores an element from its compact binary representation
+pub fn from_bytes<T: DeserializeOwned>(
+    bytes: &[u8],
+) -> Result<T, CompactError> {
+    bincode::deserialize(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, Page, StrictEq};
+
+    #[test]
+    fn from_bytes_should_restore_element_from_to_bytes() {
+        let text =
+            include_str!("../benches/fixtures/vimwiki/specification.wiki");
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let bytes = to_bytes(&page).unwrap();
+        let restored: Page<'static> = from_bytes(&bytes).unwrap();
+        assert!(restored.strict_eq(&page));
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    graph::normalize_path, tags::TagConfig, tags::TagIndex, BlockElement,
+    Element, Page,
+};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Represents the kind of item being completed
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionKind {
+    /// Name of a page, completed within a link such as `[[Proj`
+    #[display(fmt = "page")]
+    Page,
+
+    /// Header within a page, completed within a link such as `[[page#Intr`
+    #[display(fmt = "anchor")]
+    Anchor,
+
+    /// Tag, completed within tags such as `:wo`
+    #[display(fmt = "tag")]
+    Tag,
+}
+
+/// Represents a candidate to complete some partial input
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Completion {
+    /// Kind of item being completed
+    pub kind: CompletionKind,
+
+    /// Text that replaces the partial input being completed
+    pub text: String,
+
+    /// How closely the candidate matches the partial input, where a higher
+    /// score is a better match
+    pub score: u32,
+}
+
+/// Represents the portion of some input that is being completed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompletionContext<'a> {
+    /// Completing the name of a page, such as `[[Proj`
+    Page { partial: &'a str },
+
+    /// Completing a header of a page, such as `[[Proj#Intr`, where an empty
+    /// page refers to the current page
+    Anchor { page: &'a str, partial: &'a str },
+
+    /// Completing a tag, such as `:work:wo`
+    Tag { partial: &'a str },
+}
+
+impl<'a> CompletionContext<'a> {
+    /// Determines what is being completed at the end of the input, or None
+    /// if the end of the input is not within a link or tags
+    pub fn from_input(input: &'a str) -> Option<Self> {
+        // Within a link when the last opened link has not been closed and
+        // we have not yet reached its description
+        if let Some(idx) = input.rfind("[[") {
+            let target = &input[idx + 2..];
+            if !target.contains("]]") && !target.contains('|') {
+                return Some(match target.split_once('#') {
+                    Some((page, anchor)) => Self::Anchor {
+                        page,
+                        partial: anchor.rsplit('#').next().unwrap_or(anchor),
+                    },
+                    None => Self::Page { partial: target },
+                });
+            }
+        }
+
+        // Within tags when the last word begins with a colon, completing
+        // the tag following the last colon
+        let word = input.rsplit(char::is_whitespace).next()?;
+        if word.starts_with(':') {
+            return Some(Self::Tag {
+                partial: word.rsplit(':').next().unwrap_or_default(),
+            });
+        }
+
+        None
+    }
+}
+
+/// Represents a collection of page names, headers, and tags gathered across
+/// pages to produce completions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompletionIndex {
+    /// Mapping of page name -> text of each header within the page
+    pages: BTreeMap<String, Vec<String>>,
+    tags: TagIndex,
+}
+
+impl CompletionIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the index to organize tags based on the config
+    pub fn with_tag_config(mut self, config: TagConfig) -> Self {
+        self.tags = self.tags.with_config(config);
+        self
+    }
+
+    /// Adds the headers and tags of the page with the given name, which is
+    /// the path of the page relative to the root of its wiki without an
+    /// extension
+    pub fn add_page(&mut self, name: &str, page: &Page) {
+        let headers = page
+            .descendants()
+            .filter_map(|x| match x.into_inner() {
+                Element::Block(BlockElement::Header(x)) => {
+                    Some(x.content.to_string().trim().to_string())
+                }
+                _ => None,
+            })
+            .filter(|x| !x.is_empty())
+            .collect();
+
+        self.pages.insert(normalize_path(name.split('/')), headers);
+        self.tags.add_page(name, page);
+    }
+
+    /// Produces completions for the end of the input, ranked from best to
+    /// worst match, where `current` is the name of the page being edited
+    ///
+    /// Pages are completed relative to the directory of the current page,
+    /// the same way that links are resolved, using an absolute link (e.g.
+    /// `/notes`) for pages outside of that directory.
+    pub fn complete(
+        &self,
+        input: &str,
+        current: Option<&str>,
+    ) -> Vec<Completion> {
+        let current = current.map(|x| normalize_path(x.split('/')));
+        let dir = current
+            .as_deref()
+            .and_then(|x| x.rsplit_once('/'))
+            .map(|x| format!("{}/", x.0))
+            .unwrap_or_default();
+
+        let (kind, partial, candidates): (_, _, Vec<String>) =
+            match CompletionContext::from_input(input) {
+                Some(CompletionContext::Page { partial }) => (
+                    CompletionKind::Page,
+                    partial,
+                    self.pages
+                        .keys()
+                        .map(|name| match name.strip_prefix(dir.as_str()) {
+                            Some(name) => name.to_string(),
+                            None => format!("/{}", name),
+                        })
+                        .collect(),
+                ),
+                Some(CompletionContext::Anchor { page, partial }) => {
+                    let name = if page.is_empty() {
+                        current.clone().unwrap_or_default()
+                    } else if let Some(page) = page.strip_prefix('/') {
+                        normalize_path(page.split('/'))
+                    } else {
+                        normalize_path(dir.split('/').chain(page.split('/')))
+                    };
+                    (
+                        CompletionKind::Anchor,
+                        partial,
+                        self.pages.get(&name).cloned().unwrap_or_default(),
+                    )
+                }
+                Some(CompletionContext::Tag { partial }) => (
+                    CompletionKind::Tag,
+                    partial,
+                    self.tags.tags().into_iter().collect(),
+                ),
+                None => return Vec::new(),
+            };
+
+        let mut completions: Vec<Completion> = candidates
+            .into_iter()
+            .filter_map(|text| {
+                score(&text, partial).map(|score| Completion {
+                    kind,
+                    text,
+                    score,
+                })
+            })
+            .collect();
+        completions.sort_by(|a, b| {
+            b.score.cmp(&a.score).then_with(|| a.text.cmp(&b.text))
+        });
+        completions.dedup_by(|a, b| a.text == b.text);
+        completions
+    }
+}
+
+/// Scores how well the candidate matches the partial input, or None if it
+/// does not match at all
+///
+/// From best to worst, a candidate can start with the input, start with the
+/// input ignoring case, have a word starting with the input ignoring case,
+/// contain the input ignoring case, or contain the characters of the input
+/// in order ignoring case.
+fn score(candidate: &str, partial: &str) -> Option<u32> {
+    if partial.is_empty() {
+        return Some(1);
+    }
+
+    let lower = candidate.to_lowercase();
+    let partial_lower = partial.to_lowercase();
+
+    if candidate.starts_with(partial) {
+        Some(500)
+    } else if lower.starts_with(&partial_lower) {
+        Some(400)
+    } else if lower
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| word.starts_with(&partial_lower))
+    {
+        Some(300)
+    } else if lower.contains(&partial_lower) {
+        Some(200)
+    } else {
+        let mut chars = lower.chars();
+        if partial_lower.chars().all(|c| chars.any(|x| x == c)) {
+            Some(100)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn make_index() -> CompletionIndex {
+        let mut index = CompletionIndex::new();
+        for (name, text) in [
+            ("index", "= Welcome =\n:work:\n"),
+            ("projects/index", "= Overview =\n== Setup ==\n:work/docs:\n"),
+            ("projects/roadmap", "= Goals =\n:planning:\n"),
+            ("notes/programming", "text\n"),
+        ] {
+            let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+            index.add_page(name, &page);
+        }
+        index
+    }
+
+    fn texts(completions: Vec<Completion>) -> Vec<String> {
+        completions.into_iter().map(|x| x.text).collect()
+    }
+
+    #[test]
+    fn from_input_should_detect_what_is_being_completed() {
+        use CompletionContext as C;
+        assert_eq!(
+            C::from_input("see [[Proj"),
+            Some(C::Page { partial: "Proj" })
+        );
+        assert_eq!(
+            C::from_input("[[page#Intro#Se"),
+            Some(C::Anchor {
+                page: "page",
+                partial: "Se"
+            })
+        );
+        assert_eq!(
+            C::from_input("tags :work:wo"),
+            Some(C::Tag { partial: "wo" })
+        );
+        assert_eq!(C::from_input("[[page]] text"), None);
+        assert_eq!(C::from_input("[[page|desc"), None);
+    }
+
+    #[test]
+    fn complete_should_rank_pages_relative_to_current_page() {
+        let index = make_index();
+        assert_eq!(
+            texts(index.complete("[[road", Some("projects/index"))),
+            ["roadmap"]
+        );
+        assert_eq!(
+            texts(index.complete("[[pro", None)),
+            ["projects/index", "projects/roadmap", "notes/programming"]
+        );
+        assert_eq!(
+            texts(index.complete("[[not", Some("projects/index"))),
+            ["/notes/programming"]
+        );
+    }
+
+    #[test]
+    fn complete_should_produce_anchors_and_tags() {
+        let index = make_index();
+        assert_eq!(
+            texts(index.complete("[[#se", Some("projects/index"))),
+            ["Setup"]
+        );
+        assert_eq!(
+            texts(index.complete("[[roadmap#", Some("projects/index"))),
+            ["Goals"]
+        );
+        assert_eq!(texts(index.complete(":wo", None)), ["work", "work/docs"]);
+        assert_eq!(texts(index.complete(":do", None)), ["work/docs"]);
+    }
+}
@@ -0,0 +1,603 @@
+#[cfg(feature = "chrono")]
+mod imp {
+    pub use chrono::NaiveDate;
+
+    /// Returns the date the given number of days after (or before, if
+    /// negative) the date
+    pub fn add_days(date: NaiveDate, days: i64) -> NaiveDate {
+        date + chrono::Duration::days(days)
+    }
+
+    /// Returns the days since 1970-01-01
+    pub fn days_since_epoch(date: NaiveDate) -> i64 {
+        date.signed_duration_since(NaiveDate::from_ymd(1970, 1, 1))
+            .num_days()
+    }
+}
+
+#[cfg(not(feature = "chrono"))]
+mod imp {
+    use super::ParseDateError;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    use std::{convert::TryFrom, fmt, str::FromStr};
+
+    /// Represents a date of the proleptic Gregorian calendar without a
+    /// timezone, providing the subset of `chrono::NaiveDate` used by vimwiki
+    #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct NaiveDate {
+        year: i32,
+        month: u32,
+        day: u32,
+    }
+
+    impl NaiveDate {
+        /// Makes a new date from the year, month (1-12), and day (1-31),
+        /// returning None if it does not exist
+        pub fn from_ymd_opt(year: i32, month: u32, day: u32) -> Option<Self> {
+            if (1..=12).contains(&month)
+                && day >= 1
+                && day <= days_in_month(year, month)
+            {
+                Some(Self { year, month, day })
+            } else {
+                None
+            }
+        }
+
+        /// Makes a new date from the year, month (1-12), and day (1-31),
+        /// panicking if it does not exist
+        pub fn from_ymd(year: i32, month: u32, day: u32) -> Self {
+            Self::from_ymd_opt(year, month, day)
+                .expect("invalid or out-of-range date")
+        }
+
+        /// Parses the text as a date with the given format, which supports
+        /// `%Y`, `%m`, `%d`, and `%%` alongside literal characters
+        pub fn parse_from_str(
+            s: &str,
+            fmt: &str,
+        ) -> Result<Self, ParseDateError> {
+            let (mut year, mut month, mut day) = (None, None, None);
+            let mut s = s;
+            let mut fmt = fmt.chars();
+            while let Some(c) = fmt.next() {
+                match (c, fmt.clone().next()) {
+                    ('%', Some('Y')) => {
+                        let (x, rest) = take_number(s, true, usize::MAX)?;
+                        year = Some(x);
+                        s = rest;
+                    }
+                    ('%', Some('m')) => {
+                        let (x, rest) = take_number(s, false, 2)?;
+                        month = Some(x);
+                        s = rest;
+                    }
+                    ('%', Some('d')) => {
+                        let (x, rest) = take_number(s, false, 2)?;
+                        day = Some(x);
+                        s = rest;
+                    }
+                    ('%', Some('%')) => {
+                        s = s.strip_prefix('%').ok_or(ParseDateError)?;
+                    }
+                    ('%', _) => return Err(ParseDateError),
+                    (c, _) => {
+                        s = s.strip_prefix(c).ok_or(ParseDateError)?;
+                        continue;
+                    }
+                }
+                fmt.next();
+            }
+
+            if !s.is_empty() {
+                return Err(ParseDateError);
+            }
+
+            match (year, month, day) {
+                (Some(year), Some(month), Some(day)) => Self::from_ymd_opt(
+                    i32::try_from(year).map_err(|_| ParseDateError)?,
+                    u32::try_from(month).map_err(|_| ParseDateError)?,
+                    u32::try_from(day).map_err(|_| ParseDateError)?,
+                )
+                .ok_or(ParseDateError),
+                _ => Err(ParseDateError),
+            }
+        }
+
+        /// Formats the date with the given format, which supports `%Y`,
+        /// `%m`, `%d`, and `%%` alongside literal characters; any other
+        /// specifier fails to display
+        pub fn format<'a>(&self, fmt: &'a str) -> impl fmt::Display + 'a {
+            DateFormat { date: *self, fmt }
+        }
+
+        pub fn year(&self) -> i32 {
+            self.year
+        }
+
+        pub fn month(&self) -> u32 {
+            self.month
+        }
+
+        pub fn day(&self) -> u32 {
+            self.day
+        }
+
+        /// Returns the days since 1970-01-01
+        fn to_days(self) -> i64 {
+            // Days from civil, by Howard Hinnant
+            let y = i64::from(self.year) - i64::from(self.month <= 2);
+            let era = y.div_euclid(400);
+            let yoe = y - era * 400;
+            let m = i64::from(self.month);
+            let mp = (m + 9) % 12;
+            let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+            let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+            era * 146_097 + doe - 719_468
+        }
+
+        /// Returns the date that is the given days since 1970-01-01
+        fn from_days(days: i64) -> Self {
+            // Civil from days, by Howard Hinnant
+            let z = days + 719_468;
+            let era = z.div_euclid(146_097);
+            let doe = z - era * 146_097;
+            let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+            let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+            let mp = (5 * doy + 2) / 153;
+            let day = doy - (153 * mp + 2) / 5 + 1;
+            let month = if mp < 10 { mp + 3 } else { mp - 9 };
+            let year = yoe + era * 400 + i64::from(month <= 2);
+            Self::from_ymd(year as i32, month as u32, day as u32)
+        }
+    }
+
+    /// Takes up to `max` digits (and a sign if allowed) from the start of
+    /// the text, returning the number and the remaining text
+    fn take_number(
+        s: &str,
+        signed: bool,
+        max: usize,
+    ) -> Result<(i64, &str), ParseDateError> {
+        let sign_len = usize::from(signed && s.starts_with(['+', '-']));
+        let digits = s[sign_len..]
+            .bytes()
+            .take(max)
+            .take_while(u8::is_ascii_digit)
+            .count();
+        if digits == 0 {
+            return Err(ParseDateError);
+        }
+
+        let (number, rest) = s.split_at(sign_len + digits);
+        let number = number.parse().map_err(|_| ParseDateError)?;
+        Ok((number, rest))
+    }
+
+    fn is_leap_year(year: i32) -> bool {
+        (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+    }
+
+    fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            2 if is_leap_year(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    struct DateFormat<'a> {
+        date: NaiveDate,
+        fmt: &'a str,
+    }
+
+    impl fmt::Display for DateFormat<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let mut chars = self.fmt.chars();
+            while let Some(c) = chars.next() {
+                if c != '%' {
+                    write!(f, "{}", c)?;
+                    continue;
+                }
+
+                match chars.next() {
+                    Some('Y') if self.date.year < 0 => {
+                        write!(f, "{:05}", self.date.year)?
+                    }
+                    Some('Y') => write!(f, "{:04}", self.date.year)?,
+                    Some('m') => write!(f, "{:02}", self.date.month)?,
+                    Some('d') => write!(f, "{:02}", self.date.day)?,
+                    Some('%') => write!(f, "%")?,
+                    _ => return Err(fmt::Error),
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl fmt::Display for NaiveDate {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.format("%Y-%m-%d"))
+        }
+    }
+
+    impl fmt::Debug for NaiveDate {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Display::fmt(self, f)
+        }
+    }
+
+    impl FromStr for NaiveDate {
+        type Err = ParseDateError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Self::parse_from_str(s, "%Y-%m-%d")
+        }
+    }
+
+    impl Serialize for NaiveDate {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            s.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for NaiveDate {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            let s = <std::borrow::Cow<'de, str>>::deserialize(d)?;
+            s.parse().map_err(de::Error::custom)
+        }
+    }
+
+    /// Returns the date the given number of days after (or before, if
+    /// negative) the date
+    pub fn add_days(date: NaiveDate, days: i64) -> NaiveDate {
+        NaiveDate::from_days(date.to_days() + days)
+    }
+
+    /// Returns the days since 1970-01-01
+    pub fn days_since_epoch(date: NaiveDate) -> i64 {
+        date.to_days()
+    }
+}
+
+#[cfg(feature = "chrono")]
+use chrono::Datelike;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Dates are those of chrono when its feature is enabled (the default), and
+// otherwise a minimal date supporting the subset of operations used here
+use imp::{add_days, days_since_epoch, NaiveDate};
+
+/// Represents a failure to parse a date from text
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParseDateError;
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "input is not a valid date")
+    }
+}
+
+impl std::error::Error for ParseDateError {}
+
+/// Represents a date without a timezone, such as that of a diary page or
+/// the due date of a task
+///
+/// The date is owned by this crate so that the library implementing it can
+/// change without affecting the API; conversions to and from
+/// `chrono::NaiveDate` are available when the `chrono` feature is enabled
+#[derive(
+    Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct WikiDate(NaiveDate);
+
+impl WikiDate {
+    /// Makes a new date from the year, month (1-12), and day (1-31),
+    /// returning None if it does not exist
+    pub fn from_ymd_opt(year: i32, month: u32, day: u32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month, day).map(Self)
+    }
+
+    /// Makes a new date from the year, month (1-12), and day (1-31),
+    /// panicking if it does not exist
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Self {
+        Self::from_ymd_opt(year, month, day)
+            .expect("invalid or out-of-range date")
+    }
+
+    /// Parses the text as a date with the given format, which supports
+    /// `%Y`, `%m`, `%d`, and `%%` alongside literal characters
+    pub fn parse_from_str(s: &str, fmt: &str) -> Result<Self, ParseDateError> {
+        NaiveDate::parse_from_str(s, fmt)
+            .map(Self)
+            .map_err(|_| ParseDateError)
+    }
+
+    /// Formats the date with the given format, which supports `%Y`, `%m`,
+    /// `%d`, and `%%` alongside literal characters
+    pub fn format<'a>(&self, fmt: &'a str) -> impl fmt::Display + 'a {
+        self.0.format(fmt)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.0.year()
+    }
+
+    pub fn month(&self) -> u32 {
+        self.0.month()
+    }
+
+    pub fn day(&self) -> u32 {
+        self.0.day()
+    }
+
+    /// Returns the date the given number of days after (or before, if
+    /// negative) this date
+    pub fn add_days(self, days: i64) -> Self {
+        Self(add_days(self.0, days))
+    }
+
+    /// Returns the current date in the local timezone, or in UTC if the
+    /// `chrono` feature is disabled
+    pub fn today() -> Self {
+        #[cfg(feature = "chrono")]
+        return Self(chrono::Local::now().naive_local().date());
+
+        #[cfg(not(feature = "chrono"))]
+        return Timestamp::now().date();
+    }
+
+    fn days_since_epoch(self) -> i64 {
+        days_since_epoch(self.0)
+    }
+}
+
+impl fmt::Display for WikiDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format("%Y-%m-%d"))
+    }
+}
+
+impl fmt::Debug for WikiDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for WikiDate {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_from_str(s, "%Y-%m-%d")
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for WikiDate {
+    fn from(date: chrono::NaiveDate) -> Self {
+        Self(date)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<WikiDate> for chrono::NaiveDate {
+    fn from(date: WikiDate) -> Self {
+        date.0
+    }
+}
+
+/// Represents a moment in time to the second in UTC, which is formatted and
+/// serialized as RFC 3339 (e.g. `2021-04-05T06:07:08Z`)
+///
+/// Like [`WikiDate`], the timestamp is owned by this crate so that the
+/// library implementing dates can change without affecting the API, with
+/// conversions to and from `chrono::DateTime<Utc>` when the `chrono` feature
+/// is enabled
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    const SECONDS_PER_DAY: i64 = 86_400;
+
+    /// Makes a new timestamp the given seconds after 1970-01-01T00:00:00Z
+    pub fn from_unix_seconds(seconds: i64) -> Self {
+        Self(seconds)
+    }
+
+    /// Returns the current time
+    pub fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+
+    /// Returns the seconds since 1970-01-01T00:00:00Z
+    pub fn unix_seconds(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the date of the timestamp in UTC
+    pub fn date(self) -> WikiDate {
+        WikiDate::from_ymd(1970, 1, 1)
+            .add_days(self.0.div_euclid(Self::SECONDS_PER_DAY))
+    }
+
+    pub fn hour(self) -> u32 {
+        (self.seconds_of_day() / 3600) as u32
+    }
+
+    pub fn minute(self) -> u32 {
+        (self.seconds_of_day() % 3600 / 60) as u32
+    }
+
+    pub fn second(self) -> u32 {
+        (self.seconds_of_day() % 60) as u32
+    }
+
+    fn seconds_of_day(self) -> i64 {
+        self.0.rem_euclid(Self::SECONDS_PER_DAY)
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(x) => Self(x.as_secs() as i64),
+            Err(x) => {
+                let x = x.duration();
+                Self(-(x.as_secs() as i64) - i64::from(x.subsec_nanos() > 0))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}T{:02}:{:02}:{:02}Z",
+            self.date(),
+            self.hour(),
+            self.minute(),
+            self.second()
+        )
+    }
+}
+
+impl fmt::Debug for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = ParseDateError;
+
+    /// Parses an RFC 3339 timestamp in UTC without fractional seconds, such
+    /// as `2021-04-05T06:07:08Z` or `2021-04-05T06:07:08+00:00`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (date, time) = s.split_once(['T', 't']).ok_or(ParseDateError)?;
+        let time = time
+            .strip_suffix(['Z', 'z'])
+            .or_else(|| time.strip_suffix("+00:00"))
+            .ok_or(ParseDateError)?;
+
+        let mut parts = time.split(':').map(|x| match x.len() {
+            2 => x.parse::<i64>().map_err(|_| ParseDateError),
+            _ => Err(ParseDateError),
+        });
+        let mut next = |max: i64| match parts.next() {
+            Some(Ok(x)) if x <= max => Ok(x),
+            _ => Err(ParseDateError),
+        };
+        let (hour, minute, second) = (next(23)?, next(59)?, next(59)?);
+        if parts.next().is_some() {
+            return Err(ParseDateError);
+        }
+
+        let days = date.parse::<WikiDate>()?.days_since_epoch();
+        Ok(Self(
+            days * Self::SECONDS_PER_DAY + hour * 3600 + minute * 60 + second,
+        ))
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(d)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Timestamp {
+    fn from(time: chrono::DateTime<chrono::Utc>) -> Self {
+        Self(time.timestamp())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for chrono::DateTime<chrono::Utc> {
+    fn from(timestamp: Timestamp) -> Self {
+        use chrono::TimeZone;
+        chrono::Utc.timestamp(timestamp.0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_from_str_should_support_diary_dates() {
+        let date = WikiDate::parse_from_str("2012-03-05", "%Y-%m-%d").unwrap();
+        assert_eq!(date, WikiDate::from_ymd(2012, 3, 5));
+        assert_eq!(date.format("%Y%m%d").to_string(), "20120305");
+        assert_eq!(date.to_string(), "2012-03-05");
+
+        assert!(WikiDate::parse_from_str("2012-02-30", "%Y-%m-%d").is_err());
+        assert!(WikiDate::parse_from_str("2012-03-05x", "%Y-%m-%d").is_err());
+        assert!(WikiDate::parse_from_str("2012/03/05", "%Y-%m-%d").is_err());
+    }
+
+    #[test]
+    fn add_days_should_cross_months_and_years() {
+        let date = WikiDate::from_ymd(2020, 2, 28);
+        assert_eq!(date.add_days(1), WikiDate::from_ymd(2020, 2, 29));
+        assert_eq!(date.add_days(2), WikiDate::from_ymd(2020, 3, 1));
+        assert_eq!(date.add_days(308), WikiDate::from_ymd(2021, 1, 1));
+        assert_eq!(date.add_days(-59), WikiDate::from_ymd(2019, 12, 31));
+    }
+
+    #[test]
+    fn dates_should_serialize_as_strings() {
+        let date = WikiDate::from_ymd(2021, 4, 5);
+        let json = serde_json::to_string(&date).unwrap();
+        assert_eq!(json, "\"2021-04-05\"");
+        assert_eq!(serde_json::from_str::<WikiDate>(&json).unwrap(), date);
+    }
+
+    #[test]
+    fn timestamps_should_format_as_rfc3339() {
+        let timestamp = Timestamp::from_unix_seconds(1_617_602_828);
+        assert_eq!(timestamp.date(), WikiDate::from_ymd(2021, 4, 5));
+        assert_eq!(timestamp.to_string(), "2021-04-05T06:07:08Z");
+        assert_eq!(
+            Timestamp::from_unix_seconds(-1).to_string(),
+            "1969-12-31T23:59:59Z"
+        );
+    }
+
+    #[test]
+    fn timestamps_should_parse_from_rfc3339() {
+        let timestamp = Timestamp::from_unix_seconds(1_617_602_828);
+        assert_eq!("2021-04-05T06:07:08Z".parse(), Ok(timestamp));
+        assert_eq!("2021-04-05T06:07:08+00:00".parse(), Ok(timestamp));
+
+        assert!("2021-04-05".parse::<Timestamp>().is_err());
+        assert!("2021-04-05T24:00:00Z".parse::<Timestamp>().is_err());
+        assert!("2021-04-05T06:07:08+01:00".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn timestamps_should_serialize_as_strings() {
+        let timestamp = Timestamp::from_unix_seconds(1_617_602_828);
+        let json = serde_json::to_string(&timestamp).unwrap();
+        assert_eq!(json, "\"2021-04-05T06:07:08Z\"");
+        assert_eq!(
+            serde_json::from_str::<Timestamp>(&json).unwrap(),
+            timestamp
+        );
+    }
+}
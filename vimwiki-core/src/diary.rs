@@ -0,0 +1,102 @@
+use crate::{date::WikiDate, resolve::ResolverWiki};
+use std::{fs, io, path::Path};
+
+/// Returns the date of the diary entry at the given path within the wiki, or
+/// None if the path is not a diary page of the wiki
+pub fn entry_date(wiki: &ResolverWiki, path: &Path) -> Option<WikiDate> {
+    let dir = wiki.path.join(wiki.diary_rel_path.as_path());
+    if path.parent()? != dir {
+        return None;
+    }
+
+    let has_ext = wiki.ext.is_empty()
+        || path
+            .extension()
+            .is_some_and(|ext| ext.to_string_lossy() == wiki.ext);
+    if !has_ext {
+        return None;
+    }
+
+    let stem = path.file_stem()?.to_string_lossy();
+    WikiDate::parse_from_str(&stem, "%Y-%m-%d").ok()
+}
+
+/// Returns the dates of every diary entry that exists within the wiki,
+/// sorted from oldest to newest, where a missing diary directory has no
+/// entries
+pub fn diary_entries(wiki: &ResolverWiki) -> io::Result<Vec<WikiDate>> {
+    let dir = wiki.path.join(wiki.diary_rel_path.as_path());
+    let read_dir = match fs::read_dir(dir) {
+        Ok(x) => x,
+        Err(x) if x.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(x) => return Err(x),
+    };
+
+    let mut dates = Vec::new();
+    for entry in read_dir {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            dates.extend(entry_date(wiki, &entry.path()));
+        }
+    }
+
+    dates.sort_unstable();
+    Ok(dates)
+}
+
+/// Finds the latest entry strictly before the date within sorted entries
+pub fn previous_entry(
+    entries: &[WikiDate],
+    date: WikiDate,
+) -> Option<WikiDate> {
+    let idx = entries.partition_point(|x| *x < date);
+    idx.checked_sub(1).map(|idx| entries[idx])
+}
+
+/// Finds the earliest entry strictly after the date within sorted entries
+pub fn next_entry(entries: &[WikiDate], date: WikiDate) -> Option<WikiDate> {
+    let idx = entries.partition_point(|x| *x <= date);
+    entries.get(idx).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> WikiDate {
+        WikiDate::from_ymd(2021, 4, day)
+    }
+
+    #[test]
+    fn diary_entries_should_list_dated_pages_in_order() {
+        let root = std::env::temp_dir()
+            .join(format!("vimwiki-diary-{}", std::process::id()));
+        let wiki = ResolverWiki::new(&root);
+        let dir = root.join("diary");
+        assert_eq!(diary_entries(&wiki).unwrap(), Vec::new());
+
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["2021-04-05.wiki", "2021-04-01.wiki", "diary.wiki"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        fs::write(dir.join("2021-04-03.md"), "").unwrap();
+
+        let entries = diary_entries(&wiki).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(entries, [date(1), date(5)]);
+
+        assert_eq!(entry_date(&wiki, &wiki.diary_path(date(3))), Some(date(3)));
+        assert_eq!(entry_date(&wiki, &root.join("2021-04-03.wiki")), None);
+    }
+
+    #[test]
+    fn previous_and_next_entry_should_skip_missing_dates() {
+        let entries = [date(1), date(5), date(9)];
+        assert_eq!(previous_entry(&entries, date(5)), Some(date(1)));
+        assert_eq!(previous_entry(&entries, date(7)), Some(date(5)));
+        assert_eq!(previous_entry(&entries, date(1)), None);
+        assert_eq!(next_entry(&entries, date(5)), Some(date(9)));
+        assert_eq!(next_entry(&entries, date(2)), Some(date(5)));
+        assert_eq!(next_entry(&entries, date(9)), None);
+    }
+}
@@ -0,0 +1,373 @@
+use crate::{BlockElement, InlineElement, Located, Page, Region};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+/// Represents the kind of change made to a block or inline element
+#[derive(
+    Copy, Clone, Debug, Display, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Element only exists within the new page
+    #[display(fmt = "added")]
+    Added,
+
+    /// Element only exists within the old page
+    #[display(fmt = "removed")]
+    Removed,
+
+    /// Element exists unchanged within both pages, but its position relative
+    /// to the other elements changed
+    #[display(fmt = "moved")]
+    Moved,
+
+    /// Element of the same type exists at the same position within both
+    /// pages, but its content changed
+    #[display(fmt = "modified")]
+    Modified,
+}
+
+/// Represents a change to a top-level block between two pages
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockChange {
+    /// Kind of change made to the block
+    pub kind: ChangeKind,
+
+    /// Name of the type of block that changed (e.g. paragraph or list)
+    pub block: String,
+
+    /// Region of the block within the old page, if it exists there
+    pub old_region: Option<Region>,
+
+    /// Region of the block within the new page, if it exists there
+    pub new_region: Option<Region>,
+
+    /// Inline elements added and removed within a modified paragraph
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inline: Vec<InlineChange>,
+}
+
+/// Represents an inline element added to or removed from a paragraph
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InlineChange {
+    /// Kind of change made to the element, either added or removed
+    pub kind: ChangeKind,
+
+    /// Name of the type of element that changed (e.g. text or link)
+    pub element: String,
+
+    /// Text of the element as it would be displayed
+    pub text: String,
+
+    /// Region of the element within the old page, if it exists there
+    pub old_region: Option<Region>,
+
+    /// Region of the element within the new page, if it exists there
+    pub new_region: Option<Region>,
+}
+
+/// Represents a step in turning one sequence into another
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Step {
+    Keep(usize, usize),
+    Remove(usize),
+    Add(usize),
+}
+
+/// Produces the steps to turn the old sequence into the new sequence using
+/// the longest common subsequence, where removals precede additions
+pub(crate) fn edit_script<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Step> {
+    // lcs[i][j] is the length of the subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut steps = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            steps.push(Step::Keep(i, j));
+            i += 1;
+            j += 1;
+        } else if i < old.len()
+            && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1])
+        {
+            steps.push(Step::Remove(i));
+            i += 1;
+        } else {
+            steps.push(Step::Add(j));
+            j += 1;
+        }
+    }
+
+    steps
+}
+
+/// Compares the top-level blocks of two pages, ignoring their positions,
+/// and produces the changes needed to turn the old page into the new page
+/// ordered by their position in the pages
+///
+/// Blocks that appear unchanged elsewhere in the other page are reported as
+/// moved, and removed blocks replaced by an added block of the same type
+/// are reported as modified, including inline-level detail for paragraphs
+pub fn diff_pages(old: &Page, new: &Page) -> Vec<BlockChange> {
+    let old = old.elements();
+    let new = new.elements();
+    let steps = edit_script(old, new);
+
+    // Pair up removed and added blocks that are identical as moves
+    let mut moved_to = vec![None; old.len()];
+    let mut moved_from = vec![None; new.len()];
+    for &step in steps.iter() {
+        if let Step::Remove(i) = step {
+            let found = steps.iter().find_map(|&step| match step {
+                Step::Add(j) if moved_from[j].is_none() && old[i] == new[j] => {
+                    Some(j)
+                }
+                _ => None,
+            });
+            if let Some(j) = found {
+                moved_to[i] = Some(j);
+                moved_from[j] = Some(i);
+            }
+        }
+    }
+
+    // Within each run of consecutive removals and additions, pair up the
+    // remaining blocks of the same type as modifications
+    let mut modified_from = vec![None; new.len()];
+    let mut is_modified = vec![false; old.len()];
+    for run in steps.split(|step| matches!(step, Step::Keep(..))) {
+        for &step in run {
+            if let Step::Add(j) = step {
+                if moved_from[j].is_some() {
+                    continue;
+                }
+
+                let found = run.iter().find_map(|&step| match step {
+                    Step::Remove(i)
+                        if moved_to[i].is_none()
+                            && !is_modified[i]
+                            && old[i].as_inner().kind_name()
+                                == new[j].as_inner().kind_name() =>
+                    {
+                        Some(i)
+                    }
+                    _ => None,
+                });
+                if let Some(i) = found {
+                    is_modified[i] = true;
+                    modified_from[j] = Some(i);
+                }
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    for step in steps {
+        match step {
+            Step::Keep(..) => {}
+            Step::Remove(i) if moved_to[i].is_some() || is_modified[i] => {}
+            Step::Remove(i) => changes.push(removed(&old[i])),
+            Step::Add(j) => match (moved_from[j], modified_from[j]) {
+                (Some(i), _) => changes.push(moved(&old[i], &new[j])),
+                (_, Some(i)) => changes.push(modified(&old[i], &new[j])),
+                _ => changes.push(added(&new[j])),
+            },
+        }
+    }
+
+    changes
+}
+
+fn added(block: &Located<BlockElement>) -> BlockChange {
+    BlockChange {
+        kind: ChangeKind::Added,
+        block: block.as_inner().kind_name().to_string(),
+        old_region: None,
+        new_region: Some(block.region()),
+        inline: Vec::new(),
+    }
+}
+
+fn removed(block: &Located<BlockElement>) -> BlockChange {
+    BlockChange {
+        kind: ChangeKind::Removed,
+        block: block.as_inner().kind_name().to_string(),
+        old_region: Some(block.region()),
+        new_region: None,
+        inline: Vec::new(),
+    }
+}
+
+fn moved(
+    old: &Located<BlockElement>,
+    new: &Located<BlockElement>,
+) -> BlockChange {
+    BlockChange {
+        kind: ChangeKind::Moved,
+        block: new.as_inner().kind_name().to_string(),
+        old_region: Some(old.region()),
+        new_region: Some(new.region()),
+        inline: Vec::new(),
+    }
+}
+
+fn modified(
+    old: &Located<BlockElement>,
+    new: &Located<BlockElement>,
+) -> BlockChange {
+    let inline = match (old.as_inner(), new.as_inner()) {
+        (BlockElement::Paragraph(old), BlockElement::Paragraph(new)) => {
+            let old: Vec<_> = old.lines.iter().flat_map(|x| x.iter()).collect();
+            let new: Vec<_> = new.lines.iter().flat_map(|x| x.iter()).collect();
+            diff_inline(&old, &new)
+        }
+        _ => Vec::new(),
+    };
+
+    BlockChange {
+        kind: ChangeKind::Modified,
+        block: new.as_inner().kind_name().to_string(),
+        old_region: Some(old.region()),
+        new_region: Some(new.region()),
+        inline,
+    }
+}
+
+fn diff_inline(
+    old: &[&Located<InlineElement>],
+    new: &[&Located<InlineElement>],
+) -> Vec<InlineChange> {
+    edit_script(old, new)
+        .into_iter()
+        .filter_map(|step| match step {
+            Step::Keep(..) => None,
+            Step::Remove(i) => Some(InlineChange {
+                kind: ChangeKind::Removed,
+                element: old[i].as_inner().kind_name().to_string(),
+                text: old[i].to_string(),
+                old_region: Some(old[i].region()),
+                new_region: None,
+            }),
+            Step::Add(j) => Some(InlineChange {
+                kind: ChangeKind::Added,
+                element: new[j].as_inner().kind_name().to_string(),
+                text: new[j].to_string(),
+                old_region: None,
+                new_region: Some(new[j].region()),
+            }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn diff_full(old: &str, new: &str) -> Vec<BlockChange> {
+        let old: Page = Language::from_vimwiki_str(old).parse().unwrap();
+        let new: Page = Language::from_vimwiki_str(new).parse().unwrap();
+        diff_pages(&old, &new)
+    }
+
+    fn diff(old: &str, new: &str) -> Vec<(ChangeKind, String)> {
+        diff_full(old, new)
+            .into_iter()
+            .map(|c| (c.kind, c.block))
+            .collect()
+    }
+
+    #[test]
+    fn diff_pages_should_ignore_blocks_that_only_shifted_offsets() {
+        assert!(diff("= a =\n\ntext", "\n\n= a =\n\n\ntext").is_empty());
+    }
+
+    #[test]
+    fn diff_pages_should_report_added_and_removed_blocks() {
+        assert_eq!(
+            diff("= a =\n\ntext\n\n- item", "= a =\n\n----\n\n{{{\ncode\n}}}"),
+            [
+                (ChangeKind::Removed, String::from("paragraph")),
+                (ChangeKind::Removed, String::from("list")),
+                (ChangeKind::Added, String::from("divider")),
+                (ChangeKind::Added, String::from("code_block")),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_pages_should_report_moved_blocks() {
+        let changes =
+            diff_full("= a =\n\n- item\n\ntext", "text\n\n= a =\n\n- item");
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ChangeKind::Moved);
+        assert_eq!(changes[0].block, "paragraph");
+        assert_eq!(changes[0].old_region, Some(Region::new_at_depth(15, 4, 0)));
+        assert_eq!(changes[0].new_region, Some(Region::new_at_depth(0, 5, 0)));
+    }
+
+    #[test]
+    fn diff_pages_should_report_modified_paragraphs_with_inline_detail() {
+        let changes = diff_full(
+            "= a =\n\nsome *bold* text\n\n----",
+            "= a =\n\nsome _italic_ text\n\n----",
+        );
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ChangeKind::Modified);
+        assert_eq!(changes[0].block, "paragraph");
+
+        let inline: Vec<_> = changes[0]
+            .inline
+            .iter()
+            .map(|c| (c.kind, c.element.as_str(), c.text.as_str()))
+            .collect();
+        assert_eq!(
+            inline,
+            [
+                (ChangeKind::Removed, "decorated_text", "bold"),
+                (ChangeKind::Added, "decorated_text", "italic"),
+            ]
+        );
+        assert_eq!(
+            changes[0].inline[1].new_region,
+            Some(Region::new_at_depth(12, 8, 1))
+        );
+    }
+
+    #[test]
+    fn diff_pages_should_name_inline_elements_by_their_kind() {
+        let changes = diff_full("some `code` text", "some $math$ text");
+        let inline: Vec<_> = changes[0]
+            .inline
+            .iter()
+            .map(|c| (c.kind, c.element.as_str()))
+            .collect();
+        assert_eq!(
+            inline,
+            [
+                (ChangeKind::Removed, "code_inline"),
+                (ChangeKind::Added, "math_inline"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_pages_should_include_regions_within_each_page() {
+        let old: Page = Language::from_vimwiki_str("text").parse().unwrap();
+        let new: Page =
+            Language::from_vimwiki_str("text\n\n= a =").parse().unwrap();
+        let changes = diff_pages(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].old_region, None);
+        assert_eq!(changes[0].new_region, Some(Region::new_at_depth(6, 5, 0)));
+    }
+}
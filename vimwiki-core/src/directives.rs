@@ -0,0 +1,130 @@
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+lazy_static! {
+    static ref DIRECTIVE_HANDLERS: RwLock<HashMap<String, Arc<dyn DirectiveHandler>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Represents the error produced by a directive handler that fails
+pub type DirectiveError = Box<dyn Error + Send + Sync>;
+
+/// Represents a handler of placeholders (`%name value`) that are not
+/// otherwise understood, deciding what is written in their place when a page
+/// is rendered
+pub trait DirectiveHandler: Send + Sync {
+    /// Returns the name of the placeholder (without the leading `%`) that is
+    /// handled
+    fn name(&self) -> &str;
+
+    /// Produces the HTML written in place of a placeholder with the given
+    /// value
+    fn html(&self, value: &str) -> Result<String, DirectiveError>;
+}
+
+impl fmt::Debug for dyn DirectiveHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DirectiveHandler")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+/// Represents the built-in handler of `%plainhtml`, which writes the rest of
+/// its line as-is into HTML output
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlainHtml;
+
+impl DirectiveHandler for PlainHtml {
+    fn name(&self) -> &str {
+        "plainhtml"
+    }
+
+    fn html(&self, value: &str) -> Result<String, DirectiveError> {
+        Ok(value.to_string())
+    }
+}
+
+/// Registers a handler for placeholders of its name, replacing any handler
+/// (including a built-in one) previously registered for the same name
+///
+/// Handlers are shared by every render that follows, so they should be
+/// registered before any pages are rendered.
+pub fn register(handler: impl DirectiveHandler + 'static) {
+    if let Ok(mut x) = DIRECTIVE_HANDLERS.write() {
+        x.insert(handler.name().to_string(), Arc::new(handler));
+    }
+}
+
+/// Removes the handler of the name, returning true if one was registered
+pub fn unregister(name: &str) -> bool {
+    DIRECTIVE_HANDLERS
+        .write()
+        .map(|mut x| x.remove(name).is_some())
+        .unwrap_or_default()
+}
+
+/// Finds the handler registered for the name, falling back to a built-in
+/// handler such as the one of `%plainhtml`
+pub fn find(name: &str) -> Option<Arc<dyn DirectiveHandler>> {
+    let registered = DIRECTIVE_HANDLERS
+        .read()
+        .ok()
+        .and_then(|x| x.get(name).cloned());
+
+    registered.or_else(|| match name {
+        "plainhtml" => Some(Arc::new(PlainHtml) as Arc<dyn DirectiveHandler>),
+        _ => None,
+    })
+}
+
+/// Returns the names that have a registered handler in sorted order
+pub fn registered() -> Vec<String> {
+    let mut names: Vec<String> = DIRECTIVE_HANDLERS
+        .read()
+        .map(|x| x.keys().cloned().collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Shout;
+
+    impl DirectiveHandler for Shout {
+        fn name(&self) -> &str {
+            "shout"
+        }
+
+        fn html(&self, value: &str) -> Result<String, DirectiveError> {
+            Ok(format!("<strong>{}</strong>", value.to_uppercase()))
+        }
+    }
+
+    #[test]
+    fn find_should_support_registered_and_built_in_handlers() {
+        assert!(find("shout").is_none());
+        assert_eq!(
+            find("plainhtml").unwrap().html("<hr />").unwrap(),
+            "<hr />"
+        );
+
+        register(Shout);
+        assert_eq!(
+            find("shout").unwrap().html("hi").unwrap(),
+            "<strong>HI</strong>"
+        );
+        assert!(registered().contains(&String::from("shout")));
+
+        assert!(unregister("shout"));
+        assert!(find("shout").is_none());
+    }
+}
@@ -0,0 +1,499 @@
+use crate::{
+    edit::{EditError, TextEdit},
+    intern::Symbol,
+    tags::{TagConfig, TagIndex},
+    tasks::{collect_list_items, describe},
+    BlockElement, Comment, Element, InlineElement, ListItemTodoStatus, Page,
+    Region,
+};
+use derive_more::{Display, Error};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, str::FromStr};
+
+/// Language of a code block whose contents are a query to materialize
+/// (e.g. `{{{query` ... `}}}`)
+pub const QUERY_LANGUAGE: &str = "query";
+
+/// Text of the comment (`%% generated:begin`) that marks the start of the
+/// content generated for a query
+pub const GENERATED_BEGIN: &str = "generated:begin";
+
+/// Text of the comment (`%% generated:end`) that marks the end of the
+/// content generated for a query
+pub const GENERATED_END: &str = "generated:end";
+
+#[derive(Debug, Display, Error)]
+pub enum DynamicError {
+    #[display(fmt = "Invalid query: {}", reason)]
+    InvalidQuery {
+        #[error(not(source))]
+        reason: String,
+    },
+
+    #[display(fmt = "Generated content at {:?} is missing its end", region)]
+    MissingGeneratedEnd {
+        #[error(not(source))]
+        region: Region,
+    },
+
+    #[display(fmt = "{}", source)]
+    Edit {
+        #[error(source)]
+        source: EditError,
+    },
+}
+
+impl From<EditError> for DynamicError {
+    fn from(source: EditError) -> Self {
+        Self::Edit { source }
+    }
+}
+
+/// Represents which tasks are listed by a query based on their TODO status
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskStatusFilter {
+    /// Tasks that have not been completed or rejected
+    Incomplete,
+
+    /// Tasks that have been completed or rejected
+    Complete,
+
+    /// All tasks regardless of status
+    Any,
+}
+
+impl TaskStatusFilter {
+    fn matches(&self, status: ListItemTodoStatus) -> bool {
+        let done = matches!(
+            status,
+            ListItemTodoStatus::Complete | ListItemTodoStatus::Rejected
+        );
+
+        match self {
+            Self::Incomplete => !done,
+            Self::Complete => done,
+            Self::Any => true,
+        }
+    }
+}
+
+/// Represents a query within a dynamic block, written as a kind followed by
+/// filters of the form `name:value`
+///
+/// * `tasks [tag:TAG] [status:incomplete|complete|any]` lists TODO list items
+/// * `pages [tag:TAG]` lists links to pages
+///
+/// A tag filter of `project/*` matches the tag and every tag below it, and a
+/// task is considered tagged if the tag is within the task itself or within
+/// its page outside of any list item.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DynamicQuery {
+    Tasks {
+        tag: Option<String>,
+        status: TaskStatusFilter,
+    },
+    Pages {
+        tag: Option<String>,
+    },
+}
+
+impl FromStr for DynamicQuery {
+    type Err = DynamicError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| DynamicError::InvalidQuery { reason };
+        let mut words = s.split_whitespace();
+        let kind = words.next().ok_or_else(|| invalid("empty".to_string()))?;
+
+        let mut tag = None;
+        let mut status = TaskStatusFilter::Any;
+        for word in words {
+            match word.split_once(':') {
+                Some(("tag", x)) => tag = Some(x.trim_matches(':').to_string()),
+                Some(("status", "incomplete")) => {
+                    status = TaskStatusFilter::Incomplete
+                }
+                Some(("status", "complete")) => {
+                    status = TaskStatusFilter::Complete
+                }
+                Some(("status", "any")) => status = TaskStatusFilter::Any,
+                _ => return Err(invalid(format!("unknown filter {}", word))),
+            }
+        }
+
+        match kind {
+            "tasks" => Ok(Self::Tasks { tag, status }),
+            "pages" if status == TaskStatusFilter::Any => {
+                Ok(Self::Pages { tag })
+            }
+            "pages" => Err(invalid("pages have no status".to_string())),
+            x => Err(invalid(format!("unknown kind {}", x))),
+        }
+    }
+}
+
+/// Represents a list item that can be listed by a query
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct QueryItem {
+    page: Symbol,
+    description: String,
+    todo_status: Option<ListItemTodoStatus>,
+    region: Region,
+}
+
+/// Represents the pages, tags, and tasks that queries are evaluated against
+///
+/// Content generated for queries is excluded from the index so that it is
+/// never listed by other queries.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryIndex {
+    pages: BTreeSet<String>,
+    tags: TagIndex,
+    items: Vec<QueryItem>,
+}
+
+impl QueryIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the index to organize tags based on the config
+    pub fn with_tag_config(mut self, config: TagConfig) -> Self {
+        self.tags = self.tags.with_config(config);
+        self
+    }
+
+    /// Adds the tags and list items of the page with the given name
+    pub fn add_page(&mut self, name: &str, page: &Page) {
+        let generated = generated_regions(page);
+        let page = Page::new(
+            page.elements()
+                .iter()
+                .filter(|x| {
+                    !generated.iter().any(|r| r.contains(x.region().offset()))
+                })
+                .cloned()
+                .collect(),
+        );
+
+        self.pages.insert(name.to_string());
+        self.tags.add_page(name, &page);
+        let name = self.tags.intern(name);
+        for item in collect_list_items(&page) {
+            let region = item.region();
+            let item = item.into_inner();
+            self.items.push(QueryItem {
+                page: name,
+                description: describe(&item),
+                todo_status: item.attributes.todo_status,
+                region,
+            });
+        }
+    }
+
+    /// Evaluates the query, producing the vimwiki text of a list of results
+    pub fn evaluate(&self, query: &DynamicQuery) -> String {
+        let mut text = String::new();
+
+        match query {
+            DynamicQuery::Tasks { tag, status } => {
+                let tagged = tag.as_deref().map(|x| self.tags.find(x));
+                for item in self.items.iter() {
+                    let has_status = item
+                        .todo_status
+                        .map(|x| status.matches(x))
+                        .unwrap_or_default();
+                    let has_tag = match tagged.as_ref() {
+                        Some(entries) => entries.iter().any(|x| {
+                            x.page == item.page
+                                && (item.region.contains(x.region.offset())
+                                    || !self.is_in_list_item(
+                                        x.page,
+                                        x.region.offset(),
+                                    ))
+                        }),
+                        None => true,
+                    };
+
+                    if has_status && has_tag {
+                        text.push_str(&format!(
+                            "- {} ([[{}]])\n",
+                            item.description,
+                            self.tags.resolve(item.page)
+                        ));
+                    }
+                }
+            }
+            DynamicQuery::Pages { tag } => {
+                let pages = match tag.as_deref() {
+                    Some(x) => self.tags.pages(x),
+                    None => self.pages.iter().map(String::as_str).collect(),
+                };
+                for page in pages {
+                    text.push_str(&format!("- [[{}]]\n", page));
+                }
+            }
+        }
+
+        text
+    }
+
+    fn is_in_list_item(&self, page: Symbol, offset: usize) -> bool {
+        self.items
+            .iter()
+            .any(|x| x.page == page && x.region.contains(offset))
+    }
+}
+
+/// Represents a code block containing a query alongside the content
+/// generated for it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DynamicBlock {
+    /// Text of the query
+    pub query: String,
+
+    /// Region of the code block containing the query
+    pub region: Region,
+
+    /// Region of the generated content (including its markers) that
+    /// directly follows the code block, if it has been generated
+    pub generated: Option<Region>,
+}
+
+/// Finds all code blocks containing a query within the page, in document
+/// order
+pub fn find_blocks(page: &Page) -> Result<Vec<DynamicBlock>, DynamicError> {
+    let markers = collect_markers(page);
+    let mut blocks = Vec::new();
+
+    for element in page.descendants() {
+        let region = element.region();
+        let code = match element.as_inner() {
+            Element::Block(BlockElement::CodeBlock(x))
+                if x.language.as_deref() == Some(QUERY_LANGUAGE) =>
+            {
+                x
+            }
+            _ => continue,
+        };
+
+        let end = region.offset() + region.len();
+        let generated = match markers
+            .iter()
+            .position(|(r, begin)| *begin && r.offset() == end)
+        {
+            Some(i) => {
+                match markers[i + 1..].iter().find(|(_, begin)| !begin) {
+                    Some((r, _)) => {
+                        Some(Region::new(end, r.offset() + r.len() - end))
+                    }
+                    None => {
+                        return Err(DynamicError::MissingGeneratedEnd {
+                            region: markers[i].0,
+                        })
+                    }
+                }
+            }
+            None => None,
+        };
+
+        blocks.push(DynamicBlock {
+            query: code.lines.join("\n"),
+            region,
+            generated,
+        });
+    }
+
+    Ok(blocks)
+}
+
+/// Produces the edits that replace the content generated for each query
+/// within the text of the page with fresh results from the index, inserting
+/// the content after any query that has yet to be generated
+///
+/// Only generated content is touched, leaving the rest of the text as-is.
+pub fn refresh(
+    text: &str,
+    page: &Page,
+    index: &QueryIndex,
+) -> Result<Vec<TextEdit>, DynamicError> {
+    let mut edits = Vec::new();
+
+    for block in find_blocks(page)? {
+        let query: DynamicQuery = block.query.parse()?;
+        let end = block.region.offset() + block.region.len();
+
+        let mut generated = String::new();
+        if !text[..end].ends_with('\n') {
+            generated.push('\n');
+        }
+        generated.push_str(&format!("%% {}\n", GENERATED_BEGIN));
+        generated.push_str(&index.evaluate(&query));
+        generated.push_str(&format!("%% {}\n", GENERATED_END));
+
+        let region = match block.generated {
+            Some(r) if text[r.offset() + r.len()..].starts_with('\n') => {
+                Region::new(r.offset(), r.len() + 1)
+            }
+            Some(r) => Region::new(r.offset(), r.len()),
+            None => Region::new(end, 0),
+        };
+
+        if text.get(region.offset()..region.offset() + region.len())
+            != Some(generated.as_str())
+        {
+            edits.push(TextEdit::new(region, generated));
+        }
+    }
+
+    Ok(edits)
+}
+
+/// Returns the regions of all content generated for queries, including the
+/// markers surrounding the content
+pub fn generated_regions(page: &Page) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut start = None;
+
+    for (region, begin) in collect_markers(page) {
+        match (start, begin) {
+            (None, true) => start = Some(region.offset()),
+            (Some(offset), false) => {
+                regions.push(Region::new(
+                    offset,
+                    region.offset() + region.len() - offset,
+                ));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    regions
+}
+
+/// Collects the regions of all comments marking the beginning (true) or end
+/// (false) of generated content, in document order
+fn collect_markers(page: &Page) -> Vec<(Region, bool)> {
+    page.descendants()
+        .filter_map(|x| match x.as_inner() {
+            Element::Inline(InlineElement::Comment(Comment::Line(c))) => {
+                match c.as_str().trim() {
+                    GENERATED_BEGIN => Some((x.region(), true)),
+                    GENERATED_END => Some((x.region(), false)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{edit::apply_edits, Language};
+    use indoc::indoc;
+
+    fn make_index() -> QueryIndex {
+        let mut index = QueryIndex::new();
+        for (name, text) in [
+            (
+                "work",
+                indoc! {"
+                    :work:
+                    - [ ] write report
+                    - [X] send email
+                "},
+            ),
+            (
+                "home",
+                indoc! {"
+                    - [ ] fix sink :work:
+                    - [ ] water plants
+                "},
+            ),
+        ]
+        .iter()
+        {
+            let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+            index.add_page(name, &page);
+        }
+        index
+    }
+
+    #[test]
+    fn query_should_parse_kind_and_filters() {
+        assert_eq!(
+            "tasks tag:work status:incomplete"
+                .parse::<DynamicQuery>()
+                .unwrap(),
+            DynamicQuery::Tasks {
+                tag: Some(String::from("work")),
+                status: TaskStatusFilter::Incomplete,
+            }
+        );
+        assert_eq!(
+            "pages".parse::<DynamicQuery>().unwrap(),
+            DynamicQuery::Pages { tag: None }
+        );
+        assert!("tasks due:soon".parse::<DynamicQuery>().is_err());
+        assert!("notes".parse::<DynamicQuery>().is_err());
+    }
+
+    #[test]
+    fn evaluate_should_list_tasks_tagged_by_item_or_page() {
+        let index = make_index();
+        let query = "tasks tag:work status:incomplete".parse().unwrap();
+        assert_eq!(
+            index.evaluate(&query),
+            "- write report ([[work]])\n- fix sink :work: ([[home]])\n"
+        );
+
+        let query = "pages tag:work".parse().unwrap();
+        assert_eq!(index.evaluate(&query), "- [[home]]\n- [[work]]\n");
+    }
+
+    #[test]
+    fn refresh_should_insert_and_then_replace_only_generated_content() {
+        let index = make_index();
+        let text = indoc! {"
+            = Todo =
+            {{{query
+            tasks status:complete
+            }}}
+            Some notes
+        "};
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let text =
+            apply_edits(text, &refresh(text, &page, &index).unwrap()).unwrap();
+        assert_eq!(
+            text,
+            indoc! {"
+                = Todo =
+                {{{query
+                tasks status:complete
+                }}}
+                %% generated:begin
+                - send email ([[work]])
+                %% generated:end
+                Some notes
+            "}
+        );
+
+        // Generated content is excluded from the index and unchanged
+        // content produces no edits
+        let page: Page = Language::from_vimwiki_str(&text).parse().unwrap();
+        let mut index = make_index();
+        index.add_page("todo", &page);
+        assert_eq!(refresh(&text, &page, &index).unwrap(), []);
+
+        let stale = text.replace("send email", "old task");
+        let page: Page = Language::from_vimwiki_str(&stale).parse().unwrap();
+        assert_eq!(
+            apply_edits(&stale, &refresh(&stale, &page, &index).unwrap())
+                .unwrap(),
+            text
+        );
+    }
+}
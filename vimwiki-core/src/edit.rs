@@ -0,0 +1,458 @@
+use crate::{LineEnding, Region};
+use derive_more::{Constructor, Display, Error};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Represents a replacement of the bytes within a region of text, where an
+/// empty region inserts text and empty text deletes the region
+#[derive(Constructor, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    /// Region of bytes within the original text to replace
+    pub region: Region,
+
+    /// Text to put in place of the region
+    pub text: String,
+}
+
+#[derive(Debug, Display, Error)]
+pub enum EditError {
+    #[display(fmt = "Edit at {:?} extends beyond the text", region)]
+    OutOfBounds {
+        #[error(not(source))]
+        region: Region,
+    },
+
+    #[display(fmt = "Edit at {:?} splits a character", region)]
+    NotCharBoundary {
+        #[error(not(source))]
+        region: Region,
+    },
+
+    #[display(fmt = "Edit at {:?} overlaps another edit", region)]
+    Overlapping {
+        #[error(not(source))]
+        region: Region,
+    },
+
+    #[display(fmt = "{:?} was modified since it was read", path)]
+    ConcurrentModification {
+        #[error(not(source))]
+        path: PathBuf,
+    },
+
+    #[display(fmt = "{}", source)]
+    Io {
+        #[error(source)]
+        source: io::Error,
+    },
+}
+
+impl From<io::Error> for EditError {
+    fn from(source: io::Error) -> Self {
+        Self::Io { source }
+    }
+}
+
+/// Applies all edits to the text, where each edit's region refers to the
+/// original text; fails without applying anything if any edit is out of
+/// bounds, splits a character, or overlaps another edit
+pub fn apply_edits(
+    text: &str,
+    edits: &[TextEdit],
+) -> Result<String, EditError> {
+    let mut sorted: Vec<&TextEdit> = edits.iter().collect();
+    sorted.sort_by_key(|e| (e.region.offset(), e.region.len()));
+
+    let mut result = String::with_capacity(text.len());
+    let mut pos = 0;
+    for edit in sorted {
+        let start = edit.region.offset();
+        let end = start + edit.region.len();
+        if end > text.len() {
+            return Err(EditError::OutOfBounds {
+                region: edit.region,
+            });
+        }
+
+        if !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            return Err(EditError::NotCharBoundary {
+                region: edit.region,
+            });
+        }
+
+        if start < pos {
+            return Err(EditError::Overlapping {
+                region: edit.region,
+            });
+        }
+
+        result.push_str(&text[pos..start]);
+        result.push_str(&edit.text);
+        pos = end;
+    }
+    result.push_str(&text[pos..]);
+
+    Ok(result)
+}
+
+/// Produces the edits that, when applied to the result of applying the given
+/// edits to the text, restore the original text
+pub fn invert_edits(
+    text: &str,
+    edits: &[TextEdit],
+) -> Result<Vec<TextEdit>, EditError> {
+    // Validate the edits up front so the regions below are safe to slice
+    apply_edits(text, edits)?;
+
+    let mut sorted: Vec<&TextEdit> = edits.iter().collect();
+    sorted.sort_by_key(|e| (e.region.offset(), e.region.len()));
+
+    let mut shift: isize = 0;
+    let mut inverted = Vec::with_capacity(sorted.len());
+    for edit in sorted {
+        let start = edit.region.offset();
+        let end = start + edit.region.len();
+        let new_start = (start as isize + shift) as usize;
+
+        inverted.push(TextEdit::new(
+            Region::new(new_start, edit.text.len()),
+            text[start..end].to_string(),
+        ));
+        shift += edit.text.len() as isize - edit.region.len() as isize;
+    }
+
+    Ok(inverted)
+}
+
+/// Represents the state of a file when it was last read or written, used to
+/// detect modifications made by others
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Fingerprint {
+    modified: Option<SystemTime>,
+    hash: u64,
+}
+
+impl Fingerprint {
+    fn new(metadata: &fs::Metadata, text: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+
+        Self {
+            modified: metadata.modified().ok(),
+            hash: hasher.finish(),
+        }
+    }
+
+    fn read(path: &Path) -> io::Result<(Self, String)> {
+        let text = fs::read_to_string(path)?;
+        let metadata = fs::metadata(path)?;
+        Ok((Self::new(&metadata, &text), text))
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn read_async(path: &Path) -> io::Result<(Self, String)> {
+        let text = tokio::fs::read_to_string(path).await?;
+        let metadata = tokio::fs::metadata(path).await?;
+        Ok((Self::new(&metadata, &text), text))
+    }
+}
+
+/// Applies edits to the text of a file and persists them atomically by
+/// writing to a temporary file that replaces the original, refusing to save
+/// if the file changed on disk since it was read
+#[derive(Clone, Debug)]
+pub struct FileEditor {
+    path: PathBuf,
+    text: String,
+    fingerprint: Fingerprint,
+}
+
+impl FileEditor {
+    /// Reads the file at the path, remembering its state to detect changes
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, EditError> {
+        let path = path.into();
+        let (fingerprint, text) = Fingerprint::read(&path)?;
+
+        Ok(Self {
+            path,
+            text,
+            fingerprint,
+        })
+    }
+
+    /// Path to the file being edited
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Current text of the file including any unsaved edits
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Applies all edits to the current text, where each edit's region
+    /// refers to the text prior to this call; nothing is applied if any
+    /// edit is invalid
+    ///
+    /// Line endings within the text of each edit are converted to those
+    /// already used by the file, so that edits to a file with `\r\n` line
+    /// endings do not introduce `\n` line endings and vice versa. Returns
+    /// the edits as they were applied, after this conversion.
+    pub fn apply(
+        &mut self,
+        edits: &[TextEdit],
+    ) -> Result<Vec<TextEdit>, EditError> {
+        let line_ending = LineEnding::detect(&self.text);
+        let edits: Vec<TextEdit> = edits
+            .iter()
+            .map(|edit| {
+                TextEdit::new(
+                    edit.region,
+                    line_ending.apply(&edit.text).into_owned(),
+                )
+            })
+            .collect();
+
+        self.text = apply_edits(&self.text, &edits)?;
+        Ok(edits)
+    }
+
+    /// Whether or not the file on disk differs from when it was last read or
+    /// saved by this editor
+    pub fn is_modified_on_disk(&self) -> Result<bool, EditError> {
+        let (fingerprint, _) = Fingerprint::read(&self.path)?;
+        Ok(fingerprint != self.fingerprint)
+    }
+
+    /// Writes the current text to a temporary file alongside the original and
+    /// renames it over the original, failing if the original was modified
+    /// since it was read
+    pub fn save(&mut self) -> Result<(), EditError> {
+        if self.is_modified_on_disk()? {
+            return Err(EditError::ConcurrentModification {
+                path: self.path.clone(),
+            });
+        }
+
+        let tmp_path = self.tmp_path();
+        let result = self.write_and_rename(&tmp_path);
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        result?;
+
+        self.fingerprint =
+            Fingerprint::new(&fs::metadata(&self.path)?, &self.text);
+        Ok(())
+    }
+
+    fn write_and_rename(&self, tmp_path: &Path) -> io::Result<()> {
+        let permissions = fs::metadata(&self.path)?.permissions();
+
+        let mut file = fs::File::create(tmp_path)?;
+        file.write_all(self.text.as_bytes())?;
+        file.sync_all()?;
+        fs::set_permissions(tmp_path, permissions)?;
+
+        fs::rename(tmp_path, &self.path)
+    }
+
+    /// Produces a path in the same directory as the file so that renaming
+    /// does not cross filesystems
+    fn tmp_path(&self) -> PathBuf {
+        let name = self
+            .path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.path.with_file_name(format!(
+            ".{}.{}.tmp",
+            name,
+            std::process::id()
+        ))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl FileEditor {
+    /// Reads the file at the path without blocking the async executor,
+    /// remembering its state to detect changes
+    pub async fn open_async(
+        path: impl Into<PathBuf>,
+    ) -> Result<Self, EditError> {
+        let path = path.into();
+        let (fingerprint, text) = Fingerprint::read_async(&path).await?;
+
+        Ok(Self {
+            path,
+            text,
+            fingerprint,
+        })
+    }
+
+    /// Whether or not the file on disk differs from when it was last read or
+    /// saved by this editor, checked without blocking the async executor
+    pub async fn is_modified_on_disk_async(&self) -> Result<bool, EditError> {
+        let (fingerprint, _) = Fingerprint::read_async(&self.path).await?;
+        Ok(fingerprint != self.fingerprint)
+    }
+
+    /// Same as [`FileEditor::save`] without blocking the async executor
+    pub async fn save_async(&mut self) -> Result<(), EditError> {
+        if self.is_modified_on_disk_async().await? {
+            return Err(EditError::ConcurrentModification {
+                path: self.path.clone(),
+            });
+        }
+
+        let tmp_path = self.tmp_path();
+        let result = self.write_and_rename_async(&tmp_path).await;
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+        }
+        result?;
+
+        self.fingerprint = Fingerprint::new(
+            &tokio::fs::metadata(&self.path).await?,
+            &self.text,
+        );
+        Ok(())
+    }
+
+    async fn write_and_rename_async(&self, tmp_path: &Path) -> io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let permissions = tokio::fs::metadata(&self.path).await?.permissions();
+
+        let mut file = tokio::fs::File::create(tmp_path).await?;
+        file.write_all(self.text.as_bytes()).await?;
+        file.sync_all().await?;
+        tokio::fs::set_permissions(tmp_path, permissions).await?;
+
+        tokio::fs::rename(tmp_path, &self.path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(offset: usize, len: usize, text: &str) -> TextEdit {
+        TextEdit::new(Region::new(offset, len), text.to_string())
+    }
+
+    fn tmp_file(name: &str, text: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "vimwiki-edit-{}-{}.wiki",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn apply_edits_should_apply_edits_relative_to_original_text() {
+        let text = apply_edits(
+            "- [ ] one\n- [ ] two\n",
+            &[edit(13, 1, "X"), edit(3, 1, "X"), edit(20, 0, "- three\n")],
+        )
+        .unwrap();
+        assert_eq!(text, "- [X] one\n- [X] two\n- three\n");
+    }
+
+    #[test]
+    fn apply_edits_should_reject_invalid_edits() {
+        assert!(matches!(
+            apply_edits("abc", &[edit(2, 2, "")]),
+            Err(EditError::OutOfBounds { .. })
+        ));
+        assert!(matches!(
+            apply_edits("é", &[edit(1, 0, "")]),
+            Err(EditError::NotCharBoundary { .. })
+        ));
+        assert!(matches!(
+            apply_edits("abc", &[edit(0, 2, ""), edit(1, 1, "")]),
+            Err(EditError::Overlapping { .. })
+        ));
+    }
+
+    #[test]
+    fn invert_edits_should_restore_original_text() {
+        let original = "- [ ] one\n- [ ] two\n";
+        let edits =
+            [edit(13, 1, "X"), edit(0, 2, ""), edit(20, 0, "- three\n")];
+        let edited = apply_edits(original, &edits).unwrap();
+        let inverted = invert_edits(original, &edits).unwrap();
+        assert_eq!(apply_edits(&edited, &inverted).unwrap(), original);
+    }
+
+    #[test]
+    fn save_should_replace_file_contents() {
+        let path = tmp_file("save", "some text");
+        let mut editor = FileEditor::open(&path).unwrap();
+        editor.apply(&[edit(0, 4, "other")]).unwrap();
+        editor.save().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "other text");
+
+        // Saving again should succeed as we know the latest state
+        editor.apply(&[edit(0, 0, "> ")]).unwrap();
+        editor.save().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "> other text");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn apply_should_keep_line_endings_of_file() {
+        let path = tmp_file("line-endings", "\u{FEFF}one\r\ntwo\r\n");
+        let mut editor = FileEditor::open(&path).unwrap();
+        let applied = editor.apply(&[edit(8, 0, "- a\n- b\n")]).unwrap();
+        assert_eq!(editor.text(), "\u{FEFF}one\r\n- a\r\n- b\r\ntwo\r\n");
+        assert_eq!(applied, [edit(8, 0, "- a\r\n- b\r\n")]);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn save_should_refuse_to_clobber_concurrent_modifications() {
+        let path = tmp_file("concurrent", "some text");
+        let mut editor = FileEditor::open(&path).unwrap();
+        fs::write(&path, "changed elsewhere").unwrap();
+
+        editor.apply(&[edit(0, 4, "other")]).unwrap();
+        assert!(matches!(
+            editor.save(),
+            Err(EditError::ConcurrentModification { .. })
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "changed elsewhere");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn save_async_should_write_applied_edits() {
+        let path = tmp_file("save-async", "some text");
+        let mut editor = FileEditor::open_async(&path).await.unwrap();
+        editor.apply(&[edit(0, 4, "other")]).unwrap();
+        editor.save_async().await.unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "other text");
+        assert!(!editor.is_modified_on_disk_async().await.unwrap());
+
+        fs::write(&path, "changed elsewhere").unwrap();
+        assert!(matches!(
+            editor.save_async().await,
+            Err(EditError::ConcurrentModification { .. })
+        ));
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,2 @@
+mod obsidian;
+pub use obsidian::ObsidianVault;
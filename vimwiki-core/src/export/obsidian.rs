@@ -0,0 +1,555 @@
+use crate::{
+    graph::normalize_path, schemes, BlockElement, Cell, ColumnAlign, Comment,
+    DecoratedText, DefinitionList, Description, InlineElement,
+    InlineElementContainer, Link, LinkData, List, ListItemTodoStatus, Page,
+    Placeholder, Table,
+};
+
+/// Converts the pages of a wiki into the notes of an
+/// [Obsidian](https://obsidian.md) vault
+///
+/// Pages are identified by their path relative to the root of the wiki
+/// without an extension (e.g. `projects/index`). Diary pages are moved into
+/// the vault's daily notes folder, and links to pages are rewritten to use
+/// the path of the linked note relative to the root of the vault so that
+/// they resolve the same way regardless of where the linking note lives.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObsidianVault {
+    diary_rel_path: String,
+    daily_notes_dir: String,
+}
+
+impl Default for ObsidianVault {
+    fn default() -> Self {
+        Self {
+            diary_rel_path: String::from("diary"),
+            daily_notes_dir: String::from("Daily Notes"),
+        }
+    }
+}
+
+impl ObsidianVault {
+    /// Creates a vault using the default diary and daily notes locations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the path (relative to the wiki root) where diary pages live,
+    /// defaulting to `diary`
+    pub fn with_diary_rel_path(mut self, path: impl Into<String>) -> Self {
+        self.diary_rel_path = path.into().trim_matches('/').to_string();
+        self
+    }
+
+    /// Sets the folder (relative to the vault root) where diary pages are
+    /// placed as daily notes, defaulting to `Daily Notes`
+    pub fn with_daily_notes_dir(mut self, path: impl Into<String>) -> Self {
+        self.daily_notes_dir = path.into().trim_matches('/').to_string();
+        self
+    }
+
+    /// Returns the path of the note (relative to the vault root and without
+    /// an extension) that the named page is exported to
+    pub fn note_name(&self, page: &str) -> String {
+        let page = normalize_path(page.split('/'));
+        let entry = page
+            .strip_prefix(self.diary_rel_path.as_str())
+            .and_then(|x| x.strip_prefix('/'));
+
+        match entry {
+            Some(entry) if !self.diary_rel_path.is_empty() => {
+                normalize_path(self.daily_notes_dir.split('/').chain([entry]))
+            }
+            _ => page,
+        }
+    }
+
+    /// Converts the named page into the markdown of an Obsidian note
+    ///
+    /// Placeholders such as `%title` and `%date` become properties within the
+    /// note's front matter, tags become `#tags`, transclusions become
+    /// embeds, and comments become Obsidian comments.
+    pub fn page_to_markdown(&self, name: &str, page: &Page) -> String {
+        let mut properties = Vec::new();
+        let mut blocks = Vec::new();
+
+        for element in page.elements() {
+            match element.as_inner() {
+                BlockElement::Placeholder(x) => {
+                    properties.extend(property(x));
+                }
+                x => blocks.push(self.block(name, x)),
+            }
+        }
+
+        let mut text = String::new();
+        if !properties.is_empty() {
+            text.push_str("---\n");
+            for (key, value) in properties {
+                text.push_str(&format!("{}: {}\n", key, value));
+            }
+            text.push_str("---\n");
+        }
+
+        blocks.retain(|x| !x.is_empty());
+        if !blocks.is_empty() {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&blocks.join("\n\n"));
+            text.push('\n');
+        }
+
+        text
+    }
+
+    fn block(&self, name: &str, block: &BlockElement) -> String {
+        match block {
+            BlockElement::Blockquote(x) => x
+                .lines
+                .iter()
+                .map(|line| match line.trim() {
+                    "" => String::from(">"),
+                    line => format!("> {}", line),
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+            BlockElement::CodeBlock(x) => format!(
+                "```{}\n{}```",
+                x.language.as_deref().unwrap_or_default(),
+                x.lines
+                    .iter()
+                    .map(|line| format!("{}\n", line))
+                    .collect::<String>()
+            ),
+            BlockElement::DefinitionList(x) => self.definition_list(name, x),
+            BlockElement::Divider(_) => String::from("---"),
+            BlockElement::FootnoteDefinition(x) => format!(
+                "[^{}]: {}",
+                x.label,
+                self.inline(name, &x.contents).trim()
+            ),
+            BlockElement::Header(x) => format!(
+                "{} {}",
+                "#".repeat(x.level),
+                self.inline(name, &x.content).trim()
+            ),
+            BlockElement::List(x) => self.list(name, x).join("\n"),
+            BlockElement::MathBlock(x) => {
+                let mut lines = vec![String::from("$$")];
+                if let Some(env) = x.environment.as_ref() {
+                    lines.push(format!("\\begin{{{}}}", env));
+                }
+                lines.extend(x.lines.iter().map(ToString::to_string));
+                if let Some(env) = x.environment.as_ref() {
+                    lines.push(format!("\\end{{{}}}", env));
+                }
+                lines.push(String::from("$$"));
+                lines.join("\n")
+            }
+            BlockElement::Paragraph(x) => x
+                .lines
+                .iter()
+                .map(|line| self.inline(name, line))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            BlockElement::Placeholder(_) => String::new(),
+            BlockElement::Table(x) => self.table(name, x),
+        }
+    }
+
+    /// Converts a definition list into a list of bold terms, as markdown has
+    /// no definition lists
+    fn definition_list(&self, name: &str, list: &DefinitionList) -> String {
+        let mut entries: Vec<_> = list.iter().collect();
+        entries.sort_by_key(|(term, _)| term.region().offset());
+
+        let mut lines = Vec::new();
+        for (term, defs) in entries {
+            let term = self.inline(name, term.as_inner().as_inner());
+            match defs {
+                [def] => lines.push(format!(
+                    "- **{}**: {}",
+                    term.trim(),
+                    self.inline(name, def.as_inner().as_inner()).trim()
+                )),
+                _ => {
+                    lines.push(format!("- **{}**", term.trim()));
+                    for def in defs {
+                        lines.push(format!(
+                            "    - {}",
+                            self.inline(name, def.as_inner().as_inner()).trim()
+                        ));
+                    }
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    fn list(&self, name: &str, list: &List) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for item in list.iter() {
+            let item = item.as_inner();
+            let mut marker = if item.is_ordered() {
+                format!("{}.", item.pos + 1)
+            } else {
+                String::from("-")
+            };
+            let indent = " ".repeat(marker.len() + 1);
+
+            marker.push_str(match item.attributes.todo_status {
+                Some(ListItemTodoStatus::Complete) => " [x]",
+                Some(ListItemTodoStatus::Rejected) => " [-]",
+                Some(_) => " [ ]",
+                None => "",
+            });
+
+            let mut item_lines = Vec::new();
+            for content in item.contents.iter() {
+                match content.as_inner() {
+                    BlockElement::List(x) => {
+                        item_lines.extend(self.list(name, x))
+                    }
+                    x => item_lines.extend(
+                        self.block(name, x).lines().map(ToString::to_string),
+                    ),
+                }
+            }
+
+            if item_lines.is_empty() {
+                lines.push(marker);
+                continue;
+            }
+
+            for (idx, line) in item_lines.into_iter().enumerate() {
+                lines.push(if idx == 0 {
+                    format!("{} {}", marker, line.trim_start())
+                } else if line.is_empty() {
+                    line
+                } else {
+                    format!("{}{}", indent, line)
+                });
+            }
+        }
+
+        lines
+    }
+
+    /// Converts a table into a markdown table, using the first row as the
+    /// header as markdown tables require one
+    fn table(&self, name: &str, table: &Table) -> String {
+        let rows: Vec<Vec<String>> = (0..table.row_cnt())
+            .filter(|row| {
+                !(0..table.col_cnt()).any(|col| {
+                    table
+                        .get_cell(*row, col)
+                        .is_some_and(|x| x.as_inner().get_align().is_some())
+                })
+            })
+            .map(|row| {
+                (0..table.col_cnt())
+                    .map(|col| match table.get_cell(row, col) {
+                        Some(cell) => match cell.as_inner() {
+                            Cell::Content(x) => {
+                                self.inline(name, x).trim().replace('|', "\\|")
+                            }
+                            _ => String::new(),
+                        },
+                        None => String::new(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let to_line = |cells: &[String]| format!("| {} |", cells.join(" | "));
+        let mut lines = Vec::new();
+        for (idx, row) in rows.iter().enumerate() {
+            lines.push(to_line(row));
+
+            if idx == 0 {
+                let separators: Vec<String> = (0..table.col_cnt())
+                    .map(|col| match table.get_column_alignment(col) {
+                        ColumnAlign::None => String::from("---"),
+                        ColumnAlign::Left => String::from(":---"),
+                        ColumnAlign::Center => String::from(":---:"),
+                        ColumnAlign::Right => String::from("---:"),
+                    })
+                    .collect();
+                lines.push(to_line(&separators));
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    fn inline(&self, name: &str, container: &InlineElementContainer) -> String {
+        container
+            .iter()
+            .map(|x| self.inline_element(name, x.as_inner()))
+            .collect()
+    }
+
+    fn inline_element(&self, name: &str, element: &InlineElement) -> String {
+        match element {
+            InlineElement::Text(x) => x.as_str().to_string(),
+            InlineElement::DecoratedText(x) => self.decorated_text(name, x),
+            InlineElement::Keyword(x) => x.to_string(),
+            InlineElement::Link(x) => self.link(name, x),
+            InlineElement::Tags(x) => x
+                .into_iter()
+                .map(|tag| format!("#{}", tag_name(tag.as_str())))
+                .collect::<Vec<String>>()
+                .join(" "),
+            InlineElement::Code(x) => format!("`{}`", x.as_str()),
+            InlineElement::Math(x) => format!("${}$", x.as_str()),
+            InlineElement::FootnoteReference(x) => format!("[^{}]", x.as_str()),
+            InlineElement::Comment(Comment::Line(x)) => {
+                format!("%%{}%%", x.as_str())
+            }
+            InlineElement::Comment(Comment::MultiLine(x)) => {
+                format!("%%{}%%", x)
+            }
+        }
+    }
+
+    fn decorated_text(&self, name: &str, text: &DecoratedText) -> String {
+        let contents: String = text
+            .iter()
+            .map(|x| self.inline_element(name, &x.to_inline_element()))
+            .collect();
+
+        match text {
+            DecoratedText::Bold(_) => format!("**{}**", contents),
+            DecoratedText::Italic(_) => format!("*{}*", contents),
+            DecoratedText::Strikeout(_) => format!("~~{}~~", contents),
+            DecoratedText::Superscript(_) => format!("<sup>{}</sup>", contents),
+            DecoratedText::Subscript(_) => format!("<sub>{}</sub>", contents),
+        }
+    }
+
+    /// Converts a link found within the named page, keeping links to pages
+    /// as wikilinks and turning all other links into markdown links
+    fn link(&self, name: &str, link: &Link) -> String {
+        let data = link.data();
+        let description = match data.description.as_ref() {
+            Some(Description::Text(x)) => Some(x.to_string()),
+            Some(Description::TransclusionLink(x)) => Some(self.embed(x)),
+            None => None,
+        };
+        let anchor: String = data
+            .to_anchor()
+            .map(|anchor| {
+                anchor
+                    .iter()
+                    .map(|x| format!("#{}", LinkData::decode_uri(x.as_bytes())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let segments: Vec<String> = data
+            .uri_ref
+            .path_segments()
+            .map(LinkData::decode_uri)
+            .collect();
+        let path = normalize_path(segments.iter().map(String::as_str));
+
+        let target = match link {
+            Link::Raw { data } => {
+                return match data.scheme().and_then(schemes::find) {
+                    Some(handler) => format!(
+                        "[{}]({})",
+                        handler.label(&data.uri_ref),
+                        handler.href(&data.uri_ref)
+                    ),
+                    None => data.uri_ref.to_string(),
+                }
+            }
+            Link::Transclusion { data } => return self.embed(data),
+            Link::Diary { date, .. } => {
+                self.note_name(&format!("{}/{}", self.diary_rel_path, date))
+            }
+            _ if data.scheme().is_some() => {
+                let uri = data.uri_ref.to_string();
+                return format!(
+                    "[{}]({})",
+                    description.as_deref().unwrap_or(uri.as_str()),
+                    uri
+                );
+            }
+            _ if data.is_local_anchor() => String::new(),
+            Link::Wiki { .. } if !data.uri_ref.is_path_absolute() => {
+                let parent = name.rsplit_once('/').map_or("", |x| x.0);
+                self.note_name(&normalize_path(
+                    parent
+                        .split('/')
+                        .chain(segments.iter().map(String::as_str)),
+                ))
+            }
+            Link::Wiki { .. } => self.note_name(&path),
+
+            // Other wikis are separate vaults, so we can only keep the path
+            _ => path,
+        };
+
+        match description {
+            Some(description) => {
+                format!("[[{}{}|{}]]", target, anchor, description)
+            }
+            None => format!("[[{}{}]]", target, anchor),
+        }
+    }
+
+    /// Converts a transclusion into an embed, using an internal embed for
+    /// files within the wiki and a markdown image otherwise
+    fn embed(&self, data: &LinkData) -> String {
+        let alt = match data.description.as_ref() {
+            Some(Description::Text(x)) => Some(x.to_string()),
+            _ => None,
+        };
+
+        let is_internal = match data.scheme() {
+            None => true,
+            Some(x) => x.eq_ignore_ascii_case("local"),
+        };
+
+        if is_internal {
+            let segments: Vec<String> = data
+                .uri_ref
+                .path_segments()
+                .map(LinkData::decode_uri)
+                .collect();
+            let path = normalize_path(segments.iter().map(String::as_str));
+            match alt {
+                Some(alt) => format!("![[{}|{}]]", path, alt),
+                None => format!("![[{}]]", path),
+            }
+        } else {
+            format!("![{}]({})", alt.unwrap_or_default(), data.uri_ref)
+        }
+    }
+}
+
+/// Converts a placeholder into a property of the note's front matter, if it
+/// has an equivalent
+fn property(placeholder: &Placeholder) -> Option<(String, String)> {
+    match placeholder {
+        Placeholder::Title(x) => Some((String::from("title"), quoted(x))),
+        Placeholder::Date(x) => Some((String::from("date"), x.to_string())),
+        Placeholder::Template(x) => Some((String::from("template"), quoted(x))),
+        Placeholder::Other { name, value } => {
+            Some((name.to_string(), quoted(value)))
+        }
+        Placeholder::NoHtml => None,
+    }
+}
+
+/// Produces a double-quoted YAML string
+fn quoted(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.trim().replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// Converts the name of a tag into one supported by Obsidian, which does not
+/// allow whitespace or punctuation other than `_`, `-`, and `/`
+fn tag_name(tag: &str) -> String {
+    tag.trim()
+        .chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() || "_-/".contains(c) => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn convert(vault: &ObsidianVault, name: &str, text: &str) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        vault.page_to_markdown(name, &page)
+    }
+
+    #[test]
+    fn note_name_should_move_diary_pages_into_daily_notes() {
+        let vault = ObsidianVault::new()
+            .with_diary_rel_path("journal/")
+            .with_daily_notes_dir("Daily");
+        assert_eq!(vault.note_name("journal/2021-05-27"), "Daily/2021-05-27");
+        assert_eq!(vault.note_name("projects/index"), "projects/index");
+        assert_eq!(vault.note_name("journalism"), "journalism");
+    }
+
+    #[test]
+    fn page_to_markdown_should_convert_blocks() {
+        let text = [
+            "%title My Page",
+            "= Header =",
+            "some *bold* and _italic_ text :tag.one:two:",
+            "- [X] done",
+            "- [ ] todo",
+            "    1. nested",
+            "|a|b|",
+            "|-|-|",
+            "|1|2|",
+            "{{{rust",
+            "fn main() {}",
+            "}}}",
+            "term:: definition",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            convert(&ObsidianVault::new(), "index", &text),
+            [
+                "---",
+                "title: \"My Page\"",
+                "---",
+                "",
+                "# Header",
+                "",
+                "some **bold** and *italic* text #tag-one #two",
+                "",
+                "- [x] done",
+                "- [ ] todo",
+                "  1. nested",
+                "",
+                "| a | b |",
+                "| --- | --- |",
+                "| 1 | 2 |",
+                "",
+                "```rust",
+                "fn main() {}",
+                "```",
+                "",
+                "- **term**: definition",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn page_to_markdown_should_rewrite_links_and_transclusions() {
+        let vault = ObsidianVault::new();
+        let text = concat!(
+            "[[other|Other]] [[/root#Section]] [[#Local]] ",
+            "[[diary:2021-05-27]] [[https://example.com/a|site]] ",
+            "{{images/logo.png|logo}} {{https://example.com/a.png}}",
+        );
+
+        assert_eq!(
+            convert(&vault, "notes/page", text),
+            concat!(
+                "[[notes/other|Other]] [[root#Section]] [[#Local]] ",
+                "[[Daily Notes/2021-05-27]] [site](https://example.com/a) ",
+                "![[images/logo.png|logo]] ![](https://example.com/a.png)\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use super::LinkGraph;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Represents options used when analyzing the pages of a graph
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalysisOptions {
+    /// Id of the page from which all other pages should be reachable
+    #[serde(default = "AnalysisOptions::default_index")]
+    pub index: String,
+
+    /// Pages with fewer bytes of content than this are considered stubs
+    #[serde(default = "AnalysisOptions::default_stub_threshold")]
+    pub stub_threshold: usize,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        Self {
+            index: Self::default_index(),
+            stub_threshold: Self::default_stub_threshold(),
+        }
+    }
+}
+
+impl AnalysisOptions {
+    #[inline]
+    pub fn default_index() -> String {
+        String::from("index")
+    }
+
+    #[inline]
+    pub const fn default_stub_threshold() -> usize {
+        100
+    }
+}
+
+/// Represents pages of a graph that likely need attention
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageAnalysis {
+    /// Pages (other than the index) that no other page links to
+    pub orphans: Vec<String>,
+
+    /// Pages whose content is smaller than the stub threshold
+    pub stubs: Vec<String>,
+
+    /// Pages that cannot be reached by following links from the index, which
+    /// is every page if the index does not exist
+    pub unreachable: Vec<String>,
+}
+
+impl PageAnalysis {
+    /// Returns true if no pages were flagged
+    pub fn is_empty(&self) -> bool {
+        self.orphans.is_empty()
+            && self.stubs.is_empty()
+            && self.unreachable.is_empty()
+    }
+}
+
+impl LinkGraph {
+    /// Analyzes the pages that exist within the graph, reporting orphan,
+    /// stub, and unreachable pages sorted by id
+    pub fn analyze(&self, options: &AnalysisOptions) -> PageAnalysis {
+        let mut outgoing: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut has_incoming: HashSet<&str> = HashSet::new();
+        for edge in self.edges.iter().filter(|e| e.source != e.target) {
+            outgoing
+                .entry(edge.source.as_str())
+                .or_default()
+                .push(edge.target.as_str());
+            has_incoming.insert(edge.target.as_str());
+        }
+
+        // Walk all links starting at the index to find reachable pages
+        let mut reachable: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<&str> = Vec::new();
+        if self.nodes.get(&options.index).is_some_and(|n| n.exists) {
+            stack.push(options.index.as_str());
+        }
+        while let Some(id) = stack.pop() {
+            if reachable.insert(id) {
+                if let Some(targets) = outgoing.get(id) {
+                    stack.extend(targets.iter().copied());
+                }
+            }
+        }
+
+        let mut analysis = PageAnalysis::default();
+        for (id, node) in self.nodes.iter().filter(|(_, n)| n.exists) {
+            if *id != options.index && !has_incoming.contains(id.as_str()) {
+                analysis.orphans.push(id.to_string());
+            }
+
+            if node.size < options.stub_threshold {
+                analysis.stubs.push(id.to_string());
+            }
+
+            if !reachable.contains(id.as_str()) {
+                analysis.unreachable.push(id.to_string());
+            }
+        }
+
+        analysis
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, Page};
+
+    fn add(graph: &mut LinkGraph, name: &str, text: &str) {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        graph.add_page(None, name, &page);
+    }
+
+    fn make_graph() -> LinkGraph {
+        let mut graph = LinkGraph::new();
+        add(&mut graph, "index", "[[a]] [[missing]] and some more text");
+        add(
+            &mut graph,
+            "a",
+            "[[index]] with plenty of text to not be a stub",
+        );
+        add(
+            &mut graph,
+            "b",
+            "[[c]] with plenty of text to not be a stub",
+        );
+        add(&mut graph, "c", "[[b]] [[c]]");
+        add(&mut graph, "d", "short");
+        graph
+    }
+
+    #[test]
+    fn analyze_should_report_orphan_stub_and_unreachable_pages() {
+        let analysis = make_graph().analyze(&AnalysisOptions {
+            stub_threshold: 20,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            analysis,
+            PageAnalysis {
+                orphans: vec![String::from("d")],
+                stubs: vec![String::from("c"), String::from("d")],
+                unreachable: vec![
+                    String::from("b"),
+                    String::from("c"),
+                    String::from("d"),
+                ],
+            }
+        );
+        assert!(!analysis.is_empty());
+    }
+
+    #[test]
+    fn analyze_should_treat_all_pages_as_unreachable_if_index_missing() {
+        let analysis = make_graph().analyze(&AnalysisOptions {
+            index: String::from("missing"),
+            stub_threshold: 0,
+        });
+
+        assert_eq!(analysis.orphans, ["d"]);
+        assert!(analysis.stubs.is_empty());
+        assert_eq!(analysis.unreachable, ["a", "b", "c", "d", "index"]);
+    }
+}
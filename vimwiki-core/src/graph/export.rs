@@ -0,0 +1,155 @@
+use super::{LinkGraph, LinkKind};
+use std::fmt::Write;
+
+impl LinkGraph {
+    /// Produces a representation of the graph in the DOT language used by
+    /// graphviz, where pages that only exist as link targets are dashed
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph vimwiki {\n");
+
+        for (id, node) in self.nodes.iter() {
+            if node.exists {
+                let _ = writeln!(dot, "    {};", dot_quote(id));
+            } else {
+                let _ = writeln!(dot, "    {} [style=dashed];", dot_quote(id));
+            }
+        }
+
+        for edge in self.edges.iter() {
+            let _ = write!(
+                dot,
+                "    {} -> {}",
+                dot_quote(&edge.source),
+                dot_quote(&edge.target)
+            );
+
+            let mut attrs = Vec::new();
+            if edge.kind != LinkKind::Wiki {
+                attrs.push(format!(
+                    "label={}",
+                    dot_quote(&edge.kind.to_string())
+                ));
+            }
+            if edge.count > 1 {
+                attrs.push(format!("weight={}", edge.count));
+            }
+
+            if attrs.is_empty() {
+                dot.push_str(";\n");
+            } else {
+                let _ = writeln!(dot, " [{}];", attrs.join(", "));
+            }
+        }
+
+        dot.push('}');
+        dot
+    }
+
+    /// Produces a representation of the graph in the GraphML format
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"exists\" for=\"node\" attr.name=\"exists\" attr.type=\"boolean\"/>\n",
+            "  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <key id=\"count\" for=\"edge\" attr.name=\"count\" attr.type=\"int\"/>\n",
+            "  <graph id=\"vimwiki\" edgedefault=\"directed\">\n",
+        ));
+
+        for (id, node) in self.nodes.iter() {
+            let _ = writeln!(
+                xml,
+                "    <node id=\"{}\"><data key=\"exists\">{}</data></node>",
+                xml_escape(id),
+                node.exists
+            );
+        }
+
+        for edge in self.edges.iter() {
+            let _ = writeln!(
+                xml,
+                concat!(
+                    "    <edge source=\"{}\" target=\"{}\">",
+                    "<data key=\"kind\">{}</data>",
+                    "<data key=\"count\">{}</data></edge>"
+                ),
+                xml_escape(&edge.source),
+                xml_escape(&edge.target),
+                edge.kind,
+                edge.count
+            );
+        }
+
+        xml.push_str("  </graph>\n</graphml>");
+        xml
+    }
+}
+
+/// Wraps text in double quotes, escaping it for use as a DOT identifier
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Escapes text for use within XML attributes and content
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, Page};
+
+    fn make_graph() -> LinkGraph {
+        let mut graph = LinkGraph::new();
+        let page: Page = Language::from_vimwiki_str(
+            "[[a \"b\"]] [[a \"b\"]] [[diary:2021-01-02]]",
+        )
+        .parse()
+        .unwrap();
+        graph.add_page(None, "index", &page);
+        graph
+    }
+
+    #[test]
+    fn to_dot_should_produce_digraph() {
+        assert_eq!(
+            make_graph().to_dot(),
+            [
+                "digraph vimwiki {",
+                "    \"a \\\"b\\\"\" [style=dashed];",
+                "    \"diary/2021-01-02\" [style=dashed];",
+                "    \"index\";",
+                "    \"index\" -> \"a \\\"b\\\"\" [weight=2];",
+                "    \"index\" -> \"diary/2021-01-02\" [label=\"diary\"];",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn to_graphml_should_escape_ids() {
+        let xml = make_graph().to_graphml();
+        assert!(xml.contains(
+            "<node id=\"a &quot;b&quot;\"><data key=\"exists\">false</data></node>"
+        ));
+        assert!(xml.contains(concat!(
+            "<edge source=\"index\" target=\"diary/2021-01-02\">",
+            "<data key=\"kind\">diary</data>",
+            "<data key=\"count\">1</data></edge>"
+        )));
+        assert!(xml.ends_with("</graph>\n</graphml>"));
+    }
+}
@@ -0,0 +1,590 @@
+use crate::{
+    resolve::LinkSpaces, Element, InlineElement, Link, LinkData, Located, Page,
+};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::Path,
+};
+
+mod analysis;
+pub use analysis::*;
+
+mod export;
+
+/// Represents the kind of link that connects two pages
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    /// Link to another page within the same wiki
+    #[display(fmt = "wiki")]
+    Wiki,
+
+    /// Link to a page within a different wiki
+    #[display(fmt = "interwiki")]
+    InterWiki,
+
+    /// Link to a diary entry
+    #[display(fmt = "diary")]
+    Diary,
+}
+
+/// Represents a page within the graph
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// Whether or not the page was added to the graph, as opposed to only
+    /// being the target of a link
+    pub exists: bool,
+
+    /// Total bytes of content within the page, or 0 if the page does not
+    /// exist
+    pub size: usize,
+}
+
+/// Represents one or more links from one page to another
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    /// Id of the page containing the links
+    pub source: String,
+
+    /// Id of the page being linked
+    pub target: String,
+
+    /// Kind of link connecting the pages
+    pub kind: LinkKind,
+
+    /// Total links of this kind from the source to the target
+    pub count: usize,
+}
+
+/// Represents the number of distinct pages that link to some page
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkCount {
+    /// Id of the page being linked
+    pub id: String,
+
+    /// Total distinct pages that link to the page
+    pub count: usize,
+}
+
+/// Represents basic metrics about a graph
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphMetrics {
+    /// Pages that exist but have no incoming links from other pages
+    pub orphans: Vec<String>,
+
+    /// Pages with the most incoming links, most linked first
+    pub most_linked: Vec<LinkCount>,
+}
+
+/// Represents a directed graph of pages connected by the links between them
+///
+/// Each page is identified by its path relative to the root of its wiki
+/// without an extension (e.g. `projects/index`). Pages from wikis other than
+/// the primary wiki are prefixed with `wiki{index}:` and pages within named
+/// wikis that cannot be mapped to an index are prefixed with `wn.{name}:`,
+/// mirroring the syntax of interwiki links.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkGraph {
+    nodes: BTreeMap<String, GraphNode>,
+    edges: Vec<GraphEdge>,
+
+    #[serde(skip)]
+    edge_indexes: HashMap<(String, String, LinkKind), usize>,
+
+    #[serde(skip)]
+    diary_rel_path: String,
+
+    #[serde(skip)]
+    link_spaces: LinkSpaces,
+
+    #[serde(skip)]
+    primary_wiki: Option<u32>,
+
+    #[serde(skip)]
+    wiki_names: HashMap<String, u32>,
+}
+
+impl Default for LinkGraph {
+    fn default() -> Self {
+        Self {
+            nodes: BTreeMap::new(),
+            edges: Vec::new(),
+            edge_indexes: HashMap::new(),
+            diary_rel_path: String::from("diary"),
+            link_spaces: LinkSpaces::default(),
+            primary_wiki: None,
+            wiki_names: HashMap::new(),
+        }
+    }
+}
+
+impl LinkGraph {
+    /// Creates an empty graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the path (relative to the wiki root) where diary pages live,
+    /// defaulting to `diary`
+    pub fn with_diary_rel_path(mut self, path: impl Into<String>) -> Self {
+        self.diary_rel_path = path.into().trim_matches('/').to_string();
+        self
+    }
+
+    /// Sets how spaces within links to pages appear within the names of the
+    /// pages, so that links match the pages they target
+    pub fn with_link_spaces(mut self, link_spaces: LinkSpaces) -> Self {
+        self.link_spaces = link_spaces;
+        self
+    }
+
+    /// Sets the wiki whose pages are identified without a prefix
+    pub fn with_primary_wiki(mut self, index: u32) -> Self {
+        self.primary_wiki = Some(index);
+        self
+    }
+
+    /// Associates the name of a wiki with its index so named interwiki links
+    /// resolve to the same pages as indexed interwiki links
+    pub fn with_wiki_name(
+        mut self,
+        name: impl Into<String>,
+        index: u32,
+    ) -> Self {
+        self.wiki_names.insert(name.into(), index);
+        self
+    }
+
+    /// Returns the pages within the graph keyed by id
+    pub fn nodes(&self) -> &BTreeMap<String, GraphNode> {
+        &self.nodes
+    }
+
+    /// Returns the connections between pages within the graph
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    /// Adds a page and all of the pages it links to into the graph, where
+    /// `wiki` is the index of the wiki containing the page (or None for the
+    /// primary wiki) and `name` is the path of the page relative to the root
+    /// of the wiki without an extension
+    pub fn add_page(&mut self, wiki: Option<u32>, name: &str, page: &Page) {
+        let name = normalize_path(name.split('/'));
+        let source = self.qualify(wiki, &name);
+        let node = self.nodes.entry(source.clone()).or_default();
+        node.exists = true;
+        node.size = page
+            .elements()
+            .iter()
+            .map(|x| x.region().offset() + x.region().len())
+            .max()
+            .unwrap_or_default();
+
+        for link in collect_links(page) {
+            if let Some((target, kind)) = self.resolve(wiki, &name, &link) {
+                self.add_edge(&source, target, kind);
+            }
+        }
+    }
+
+    /// Produces the id of the page with the given name within the given
+    /// wiki, matching the ids of the nodes of the graph
+    pub fn page_id(&self, wiki: Option<u32>, name: &str) -> String {
+        self.qualify(wiki, &normalize_path(name.split('/')))
+    }
+
+    /// Resolves the id of the page targeted by a link found within the page
+    /// with the given name and wiki, or None if the link does not target a
+    /// page
+    pub fn resolve_link(
+        &self,
+        wiki: Option<u32>,
+        name: &str,
+        link: &Link,
+    ) -> Option<String> {
+        let name = normalize_path(name.split('/'));
+        self.resolve(wiki, &name, link).map(|(id, _)| id)
+    }
+
+    fn add_edge(&mut self, source: &str, target: String, kind: LinkKind) {
+        self.nodes.entry(target.clone()).or_default();
+
+        let key = (source.to_string(), target, kind);
+        match self.edge_indexes.get(&key) {
+            Some(idx) => self.edges[*idx].count += 1,
+            None => {
+                self.edges.push(GraphEdge {
+                    source: key.0.clone(),
+                    target: key.1.clone(),
+                    kind,
+                    count: 1,
+                });
+                self.edge_indexes.insert(key, self.edges.len() - 1);
+            }
+        }
+    }
+
+    /// Produces the id of a page within the given wiki
+    fn qualify(&self, wiki: Option<u32>, name: &str) -> String {
+        match wiki {
+            Some(index) if Some(index) != self.primary_wiki => {
+                format!("wiki{}:{}", index, name)
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    /// Resolves the id of the page targeted by a link found within the page
+    /// `name` of the given wiki, or None if the link does not target a page
+    fn resolve(
+        &self,
+        wiki: Option<u32>,
+        name: &str,
+        link: &Link,
+    ) -> Option<(String, LinkKind)> {
+        let data = link.data();
+        let is_page = data.scheme().is_none()
+            && !data.is_local_anchor()
+            && !data.is_path_dir();
+        let segments = || {
+            data.uri_ref
+                .path_segments()
+                .map(LinkData::decode_uri)
+                .map(|s| self.link_spaces.to_name(&s).into_owned())
+                .collect::<Vec<String>>()
+        };
+
+        match link {
+            Link::Wiki { .. } if is_page => {
+                let path = if data.uri_ref.is_path_absolute() {
+                    normalize_path(segments().iter().map(String::as_str))
+                } else {
+                    let parent = name.rsplit_once('/').map_or("", |x| x.0);
+                    let segments = segments();
+                    normalize_path(
+                        parent
+                            .split('/')
+                            .chain(segments.iter().map(String::as_str)),
+                    )
+                };
+                Some((self.qualify(wiki, &path), LinkKind::Wiki))
+            }
+            Link::IndexedInterWiki { index, .. } if is_page => {
+                let path =
+                    normalize_path(segments().iter().map(String::as_str));
+                Some((self.qualify(Some(*index), &path), LinkKind::InterWiki))
+            }
+            Link::NamedInterWiki { name, .. } if is_page => {
+                let path =
+                    normalize_path(segments().iter().map(String::as_str));
+                let id = match self.wiki_names.get(name.as_ref()) {
+                    Some(index) => self.qualify(Some(*index), &path),
+                    None => format!("wn.{}:{}", name, path),
+                };
+                Some((id, LinkKind::InterWiki))
+            }
+            Link::Diary { date, .. } => {
+                let path = normalize_path(
+                    self.diary_rel_path
+                        .split('/')
+                        .chain(std::iter::once(date.to_string().as_str())),
+                );
+                Some((self.qualify(wiki, &path), LinkKind::Diary))
+            }
+            _ => None,
+        }
+    }
+
+    /// Calculates metrics for the graph, limiting the most-linked pages to
+    /// the top `max_most_linked` pages
+    pub fn metrics(&self, max_most_linked: usize) -> GraphMetrics {
+        let mut incoming: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for edge in self.edges.iter().filter(|e| e.source != e.target) {
+            incoming
+                .entry(edge.target.as_str())
+                .or_default()
+                .insert(edge.source.as_str());
+        }
+
+        let orphans = self
+            .nodes
+            .iter()
+            .filter(|(id, node)| {
+                node.exists && !incoming.contains_key(id.as_str())
+            })
+            .map(|(id, _)| id.to_string())
+            .collect();
+
+        let mut most_linked: Vec<LinkCount> = incoming
+            .into_iter()
+            .map(|(id, sources)| LinkCount {
+                id: id.to_string(),
+                count: sources.len(),
+            })
+            .collect();
+        most_linked.sort_by_key(|x| Reverse(x.count));
+        most_linked.truncate(max_most_linked);
+
+        GraphMetrics {
+            orphans,
+            most_linked,
+        }
+    }
+}
+
+/// Produces the name of the page at `path` within the wiki at `root`, being
+/// the path relative to the root without an extension and using `/` between
+/// its components (e.g. `projects/index`), which is how pages are identified
+/// within a [`LinkGraph`]
+///
+/// Returns none if the path is not within the root
+pub fn page_name(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?.with_extension("");
+    Some(
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<String>>()
+            .join("/"),
+    )
+}
+
+/// Joins the segments of a path using `/`, resolving `.` and `..` and
+/// removing empty segments
+pub(crate) fn normalize_path<'a>(
+    segments: impl Iterator<Item = &'a str>,
+) -> String {
+    let mut path: Vec<&str> = Vec::new();
+    for segment in segments {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                path.pop();
+            }
+            x => path.push(x),
+        }
+    }
+    path.join("/")
+}
+
+/// Collects all links found anywhere within the page in the order that they
+/// appear, along with the region of each link
+pub fn collect_links<'a>(page: &'a Page<'a>) -> Vec<Located<Link<'a>>> {
+    page.descendants()
+        .filter_map(|x| {
+            let region = x.region();
+            match x.into_inner() {
+                Element::Inline(InlineElement::Link(link)) => {
+                    Some(Located::new(link, region))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn add(graph: &mut LinkGraph, wiki: Option<u32>, name: &str, text: &str) {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        graph.add_page(wiki, name, &page);
+    }
+
+    fn edges(graph: &LinkGraph) -> Vec<(&str, &str, LinkKind, usize)> {
+        let mut edges: Vec<(&str, &str, LinkKind, usize)> = graph
+            .edges()
+            .iter()
+            .map(|e| (e.source.as_str(), e.target.as_str(), e.kind, e.count))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn add_page_should_resolve_wiki_links_relative_to_page() {
+        let mut graph = LinkGraph::new();
+        add(
+            &mut graph,
+            None,
+            "projects/index",
+            "[[todo]] [[../notes]] [[/journal|desc]] [[todo#anchor]]",
+        );
+
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("projects/index", "journal", LinkKind::Wiki, 1),
+                ("projects/index", "notes", LinkKind::Wiki, 1),
+                ("projects/index", "projects/todo", LinkKind::Wiki, 2),
+            ]
+        );
+        assert!(graph.nodes()["projects/index"].exists);
+        assert!(!graph.nodes()["journal"].exists);
+    }
+
+    #[test]
+    fn resolve_link_should_produce_ids_of_targeted_pages() {
+        let graph = LinkGraph::new().with_primary_wiki(0);
+        let page: Page =
+            Language::from_vimwiki_str("[[todo]] [[wiki1:notes]] [[#anchor]]")
+                .parse()
+                .unwrap();
+        let ids: Vec<Option<String>> = collect_links(&page)
+            .iter()
+            .map(|link| graph.resolve_link(Some(0), "projects/index", link))
+            .collect();
+
+        assert_eq!(
+            ids,
+            vec![
+                Some(String::from("projects/todo")),
+                Some(String::from("wiki1:notes")),
+                None,
+            ]
+        );
+        assert_eq!(graph.page_id(Some(1), "notes"), "wiki1:notes");
+    }
+
+    #[test]
+    fn add_page_should_ignore_links_that_do_not_target_pages() {
+        let mut graph = LinkGraph::new();
+        add(
+            &mut graph,
+            None,
+            "index",
+            "[[#anchor]] [[dir/]] [[https://example.com]] https://example.com {{img.png}}",
+        );
+
+        assert!(graph.edges().is_empty());
+    }
+
+    #[test]
+    fn add_page_should_resolve_interwiki_and_diary_links() {
+        let mut graph = LinkGraph::new()
+            .with_primary_wiki(0)
+            .with_wiki_name("work", 2)
+            .with_diary_rel_path("journal/");
+        add(
+            &mut graph,
+            Some(0),
+            "index",
+            "[[wiki1:page]] [[wn.work:todo]] [[wn.other:x]] [[wiki0:self]] [[diary:2021-05-27]]",
+        );
+        add(&mut graph, Some(1), "page", "[[sub/other]]");
+
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("index", "journal/2021-05-27", LinkKind::Diary, 1),
+                ("index", "self", LinkKind::InterWiki, 1),
+                ("index", "wiki1:page", LinkKind::InterWiki, 1),
+                ("index", "wiki2:todo", LinkKind::InterWiki, 1),
+                ("index", "wn.other:x", LinkKind::InterWiki, 1),
+                ("wiki1:page", "wiki1:sub/other", LinkKind::Wiki, 1),
+            ]
+        );
+        assert!(graph.nodes()["wiki1:page"].exists);
+    }
+
+    #[test]
+    fn add_page_should_apply_link_spaces_to_targets() {
+        let mut graph =
+            LinkGraph::new().with_link_spaces(LinkSpaces::Underscore);
+        add(&mut graph, None, "index", "[[some page]] [[some_page]]");
+
+        assert_eq!(
+            edges(&graph),
+            vec![("index", "some_page", LinkKind::Wiki, 2)]
+        );
+    }
+
+    #[test]
+    fn add_page_should_find_links_nested_within_other_elements() {
+        let mut graph = LinkGraph::new();
+        add(
+            &mut graph,
+            None,
+            "index",
+            "= [[a]] =\n- *[[b]]*\n|[[c]]|\nterm:: [[d]]",
+        );
+
+        let targets: Vec<&str> =
+            edges(&graph).into_iter().map(|(_, t, _, _)| t).collect();
+        assert_eq!(targets, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn page_name_should_be_path_relative_to_root_without_extension() {
+        let root = Path::new("/wiki");
+        assert_eq!(
+            page_name(root, Path::new("/wiki/projects/index.wiki")).as_deref(),
+            Some("projects/index")
+        );
+        assert_eq!(page_name(root, Path::new("/other/page.wiki")), None);
+    }
+
+    #[test]
+    fn metrics_should_count_each_linking_page_once() {
+        let mut graph = LinkGraph::new();
+        add(
+            &mut graph,
+            None,
+            "index",
+            "[[diary/2021-01-01]] [[diary:2021-01-01]] [[diary/2021-01-01]]",
+        );
+        add(&mut graph, None, "other", "[[diary:2021-01-01]]");
+
+        let metrics = graph.metrics(1);
+        assert_eq!(
+            metrics.most_linked,
+            vec![LinkCount {
+                id: String::from("diary/2021-01-01"),
+                count: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn metrics_should_report_orphans_and_most_linked_pages() {
+        let mut graph = LinkGraph::new();
+        add(&mut graph, None, "index", "[[a]] [[b]] [[index]]");
+        add(&mut graph, None, "a", "[[b]] [[b]]");
+        add(&mut graph, None, "b", "[[missing]]");
+        add(&mut graph, None, "lonely", "[[lonely]]");
+
+        let metrics = graph.metrics(2);
+        assert_eq!(metrics.orphans, vec!["index", "lonely"]);
+        assert_eq!(
+            metrics.most_linked,
+            vec![
+                LinkCount {
+                    id: String::from("b"),
+                    count: 2
+                },
+                LinkCount {
+                    id: String::from("a"),
+                    count: 1
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use crate::{
+    BlockElement, DecoratedText, Element, InlineBlockElement, InlineElement,
+    Link, Page, Region,
+};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Represents the classification of a span of text used for highlighting
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightKind {
+    #[display(fmt = "header")]
+    Header,
+
+    #[display(fmt = "bold")]
+    Bold,
+
+    #[display(fmt = "italic")]
+    Italic,
+
+    #[display(fmt = "strikeout")]
+    Strikeout,
+
+    #[display(fmt = "superscript")]
+    Superscript,
+
+    #[display(fmt = "subscript")]
+    Subscript,
+
+    #[display(fmt = "keyword")]
+    Keyword,
+
+    /// Target of a link, such as `page` within `[[page|description]]`
+    #[display(fmt = "link_path")]
+    LinkPath,
+
+    /// Description of a link, such as `description` within
+    /// `[[page|description]]`
+    #[display(fmt = "link_description")]
+    LinkDescription,
+
+    #[display(fmt = "tag")]
+    Tag,
+
+    #[display(fmt = "math")]
+    Math,
+
+    #[display(fmt = "code")]
+    Code,
+
+    /// Status of a task, such as `[X]` within `- [X] task`
+    #[display(fmt = "todo_marker")]
+    TodoMarker,
+
+    #[display(fmt = "comment")]
+    Comment,
+}
+
+/// Represents a classified span of text within a page
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HighlightSpan {
+    /// Classification of the text
+    pub kind: HighlightKind,
+
+    /// Region of the text within the page
+    pub region: Region,
+}
+
+/// Classifies the spans of text within a page, given the text that the page
+/// was parsed from, sorted by offset with enclosing spans before the spans
+/// nested within them (e.g. a header before bold text within the header)
+pub fn highlight(text: &str, page: &Page) -> Vec<HighlightSpan> {
+    let mut spans = Vec::new();
+
+    for element in page.descendants() {
+        let region = element.region();
+        let kind = match element.as_inner() {
+            Element::Block(BlockElement::Header(_)) => HighlightKind::Header,
+            Element::Block(BlockElement::MathBlock(_)) => HighlightKind::Math,
+            Element::Block(BlockElement::CodeBlock(_)) => HighlightKind::Code,
+            Element::InlineBlock(InlineBlockElement::ListItem(x)) => {
+                if x.attributes.todo_status.is_some() {
+                    spans.extend(todo_marker_region(text, region).map(
+                        |region| HighlightSpan {
+                            kind: HighlightKind::TodoMarker,
+                            region,
+                        },
+                    ));
+                }
+                continue;
+            }
+            Element::Inline(InlineElement::DecoratedText(x)) => match x {
+                DecoratedText::Bold(_) => HighlightKind::Bold,
+                DecoratedText::Italic(_) => HighlightKind::Italic,
+                DecoratedText::Strikeout(_) => HighlightKind::Strikeout,
+                DecoratedText::Superscript(_) => HighlightKind::Superscript,
+                DecoratedText::Subscript(_) => HighlightKind::Subscript,
+            },
+            Element::Inline(InlineElement::Keyword(_)) => {
+                HighlightKind::Keyword
+            }
+            Element::Inline(InlineElement::Link(x)) => {
+                spans.extend(link_spans(text, region, x));
+                continue;
+            }
+            Element::Inline(InlineElement::Tags(_)) => HighlightKind::Tag,
+            Element::Inline(InlineElement::Math(_)) => HighlightKind::Math,
+            Element::Inline(InlineElement::Code(_)) => HighlightKind::Code,
+            Element::Inline(InlineElement::Comment(_)) => {
+                HighlightKind::Comment
+            }
+            _ => continue,
+        };
+
+        spans.push(HighlightSpan { kind, region });
+    }
+
+    spans.sort_by(|a, b| {
+        a.region
+            .offset()
+            .cmp(&b.region.offset())
+            .then_with(|| b.region.len().cmp(&a.region.len()))
+    });
+    spans
+}
+
+/// Classifies the spans of text within a page that overlap the given range
+/// of lines, where lines are counted from zero
+pub fn highlight_lines(
+    text: &str,
+    page: &Page,
+    lines: Range<usize>,
+) -> Vec<HighlightSpan> {
+    let start = line_offset(text, lines.start);
+    let end = line_offset(text, lines.end);
+
+    highlight(text, page)
+        .into_iter()
+        .filter(|span| {
+            let offset = span.region.offset();
+            offset < end && offset + span.region.len().max(1) > start
+        })
+        .collect()
+}
+
+/// Returns the byte offset where the line (counted from zero) begins, or the
+/// length of the text if it has fewer lines
+fn line_offset(text: &str, line: usize) -> usize {
+    if line == 0 {
+        return 0;
+    }
+
+    text.match_indices('\n')
+        .nth(line - 1)
+        .map(|(idx, _)| idx + 1)
+        .unwrap_or(text.len())
+}
+
+/// Splits a link into the spans of its path and description
+///
+/// Links wrapped in `[[...]]` or `{{...}}` are split at the first `|`, where
+/// the description of a transclusion ends at the following `|`. Any other
+/// link (such as a raw url) is entirely its path.
+fn link_spans(text: &str, region: Region, link: &Link) -> Vec<HighlightSpan> {
+    let start = region.offset();
+    let raw = match text.get(start..start + region.len()) {
+        Some(raw) => raw,
+        None => return Vec::new(),
+    };
+
+    let inner = match link {
+        Link::Raw { .. } => None,
+        _ if raw.starts_with("[[") && raw.ends_with("]]") => {
+            Some(&raw[2..raw.len() - 2])
+        }
+        _ if raw.starts_with("{{") && raw.ends_with("}}") => {
+            Some(&raw[2..raw.len() - 2])
+        }
+        _ => None,
+    };
+
+    let inner = match inner {
+        Some(inner) => inner,
+        None => {
+            return vec![HighlightSpan {
+                kind: HighlightKind::LinkPath,
+                region,
+            }]
+        }
+    };
+
+    let (path, description) = match inner.split_once('|') {
+        Some((path, description)) => (path, Some(description)),
+        None => (inner, None),
+    };
+
+    let mut spans = vec![HighlightSpan {
+        kind: HighlightKind::LinkPath,
+        region: Region::new(start + 2, path.len()),
+    }];
+
+    if let Some(description) = description {
+        let description = match link {
+            Link::Transclusion { .. } => {
+                description.split('|').next().unwrap_or(description)
+            }
+            _ => description,
+        };
+
+        if !description.is_empty() {
+            spans.push(HighlightSpan {
+                kind: HighlightKind::LinkDescription,
+                region: Region::new(start + 3 + path.len(), description.len()),
+            });
+        }
+    }
+
+    spans
+}
+
+/// Finds the region of the TODO status (e.g. `[X]`) on the first line of a
+/// list item
+fn todo_marker_region(text: &str, region: Region) -> Option<Region> {
+    let start = region.offset();
+    let line = text.get(start..start + region.len())?.lines().next()?;
+
+    // NOTE: The TODO status is the first bracket pair of the item,
+    //       following its prefix such as - or 1.
+    let idx = line.find('[')?;
+    let status = line[idx + 1..].chars().next()?;
+    let close = idx + 1 + status.len_utf8();
+    if line[close..].starts_with(']') {
+        Some(Region::new(start + idx, close + 1 - idx))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+
+    fn spans(text: &str, lines: Option<Range<usize>>) -> Vec<(String, &str)> {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let spans = match lines {
+            Some(lines) => highlight_lines(text, &page, lines),
+            None => highlight(text, &page),
+        };
+
+        spans
+            .into_iter()
+            .map(|x| {
+                let start = x.region.offset();
+                (x.kind.to_string(), &text[start..start + x.region.len()])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn highlight_should_classify_spans_in_order() {
+        let text = indoc! {"
+            = *Bold* header =
+            See [[page|the page]] and {{img.png|image|style}} :tag:
+            - [X] done `code` $math$ %%comment
+        "};
+
+        let spans = spans(text, None);
+        let spans: Vec<(&str, &str)> =
+            spans.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+        assert_eq!(
+            spans,
+            [
+                ("header", "= *Bold* header =\n"),
+                ("bold", "*Bold*"),
+                ("link_path", "page"),
+                ("link_description", "the page"),
+                ("link_path", "img.png"),
+                ("link_description", "image"),
+                ("tag", ":tag:"),
+                ("todo_marker", "[X]"),
+                ("code", "`code`"),
+                ("math", "$math$"),
+                ("comment", "%%comment"),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlight_lines_should_only_include_spans_overlapping_lines() {
+        let text = "*one*\n_two_\n`three`\n";
+        let kinds: Vec<String> =
+            spans(text, Some(1..2)).into_iter().map(|x| x.0).collect();
+        assert_eq!(kinds, ["italic"]);
+
+        let kinds: Vec<String> =
+            spans(text, Some(1..5)).into_iter().map(|x| x.0).collect();
+        assert_eq!(kinds, ["italic", "code"]);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    tasks::Task, BlockElement, ListItemTodoStatus, Page, Placeholder,
+    PlainConfig, ToPlainText,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Represents a brief summary of a page, used to preview the page targeted
+/// by a link
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageSummary {
+    /// Title of the page from its `%title` placeholder, otherwise the text
+    /// of its first header
+    pub title: Option<String>,
+
+    /// Plain text of the first paragraph of the page, joined into one line
+    pub first_paragraph: Option<String>,
+}
+
+impl PageSummary {
+    /// Summarizes the page using its top-level elements
+    pub fn from_page(page: &Page) -> Self {
+        let mut placeholder_title = None;
+        let mut header_title = None;
+        let mut first_paragraph = None;
+
+        for element in page.elements().iter() {
+            match element.as_inner() {
+                BlockElement::Placeholder(Placeholder::Title(x))
+                    if placeholder_title.is_none() =>
+                {
+                    placeholder_title = Some(x.trim().to_string());
+                }
+                BlockElement::Header(x) if header_title.is_none() => {
+                    header_title =
+                        Some(x.content.to_string().trim().to_string());
+                }
+                BlockElement::Paragraph(x)
+                    if first_paragraph.is_none() && !x.is_blank() =>
+                {
+                    first_paragraph =
+                        x.to_plain_text(PlainConfig::default()).ok().map(|x| {
+                            x.split_whitespace().collect::<Vec<_>>().join(" ")
+                        });
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            title: placeholder_title.or(header_title).filter(|x| !x.is_empty()),
+            first_paragraph: first_paragraph.filter(|x| !x.is_empty()),
+        }
+    }
+
+    /// Returns true if the summary has neither a title nor a paragraph
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.first_paragraph.is_none()
+    }
+}
+
+impl fmt::Display for PageSummary {
+    /// Writes the title followed by the first paragraph, separated by a
+    /// blank line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.title.as_deref(), self.first_paragraph.as_deref()) {
+            (Some(title), Some(paragraph)) => {
+                write!(f, "{}\n\n{}", title, paragraph)
+            }
+            (Some(x), None) | (None, Some(x)) => write!(f, "{}", x),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Describes a TODO status in words (e.g. `partially complete (34-66%)`)
+pub fn describe_todo_status(status: ListItemTodoStatus) -> &'static str {
+    match status {
+        ListItemTodoStatus::Incomplete => "incomplete",
+        ListItemTodoStatus::PartiallyComplete1 => "partially complete (1-33%)",
+        ListItemTodoStatus::PartiallyComplete2 => "partially complete (34-66%)",
+        ListItemTodoStatus::PartiallyComplete3 => "partially complete (67-99%)",
+        ListItemTodoStatus::Complete => "complete",
+        ListItemTodoStatus::Rejected => "rejected",
+    }
+}
+
+/// Summarizes a task by its status and description
+pub fn summarize_task(task: &Task) -> String {
+    format!(
+        "Task ({}): {}",
+        describe_todo_status(task.todo_status),
+        task.description
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tasks::find_tasks, Language};
+    use indoc::indoc;
+
+    fn parse(text: &str) -> Page<'_> {
+        Language::from_vimwiki_str(text).parse().unwrap()
+    }
+
+    #[test]
+    fn from_page_should_use_title_and_first_paragraph() {
+        let page = parse(indoc! {"
+            = Header =
+            %title Some Title
+
+            First *bold* line
+            second line
+
+            Another paragraph
+        "});
+        let summary = PageSummary::from_page(&page);
+        assert_eq!(summary.title.as_deref(), Some("Some Title"));
+        assert_eq!(
+            summary.first_paragraph.as_deref(),
+            Some("First bold line second line")
+        );
+        assert_eq!(
+            summary.to_string(),
+            "Some Title\n\nFirst bold line second line"
+        );
+
+        let summary = PageSummary::from_page(&parse("= Header =\n"));
+        assert_eq!(summary.to_string(), "Header");
+        assert!(PageSummary::from_page(&parse("")).is_empty());
+    }
+
+    #[test]
+    fn summarize_task_should_include_status_and_description() {
+        let page = parse("- [.] partial task\n");
+        let task = find_tasks(&page).into_iter().next().unwrap();
+        assert_eq!(
+            summarize_task(&task),
+            "Task (partially complete (1-33%)): partial task"
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{
+    date::{Timestamp, WikiDate},
+    ids::Fnv1a,
+    tasks::DatedTask,
+    BlockElement, Page, Placeholder,
+};
+use std::{collections::HashMap, fmt::Write};
+
+/// Maximum length of a content line in octets, excluding the line break
+const MAX_LINE_LEN: usize = 75;
+
+/// Represents a single all-day event within a calendar
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CalendarEvent {
+    uid: String,
+    date: WikiDate,
+    summary: String,
+    category: &'static str,
+}
+
+/// Represents an iCalendar feed of diary entries and dated tasks, each of
+/// which is exported as an all-day event
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Calendar {
+    name: String,
+    timestamp: Timestamp,
+    events: Vec<CalendarEvent>,
+    uids: HashMap<String, usize>,
+}
+
+impl Calendar {
+    /// Creates an empty calendar whose events are stamped with the given
+    /// timestamp, which should be the time the calendar is produced
+    pub fn new(timestamp: Timestamp) -> Self {
+        Self {
+            name: String::from("vimwiki"),
+            timestamp,
+            events: Vec::new(),
+            uids: HashMap::new(),
+        }
+    }
+
+    /// Sets the name of the calendar shown by calendar applications
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Returns total events within the calendar
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if the calendar has no events
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Adds an event for the diary page of the given date, using the page's
+    /// title placeholder or first header as the summary
+    ///
+    /// The wiki identifies the wiki containing the diary (e.g. its path) so
+    /// that entries of the same date from different wikis have distinct uids
+    pub fn add_diary_page(&mut self, wiki: &str, date: WikiDate, page: &Page) {
+        let summary = page_title(page)
+            .unwrap_or_else(|| format!("Diary {}", date.format("%Y-%m-%d")));
+        let date_str = date.format("%Y%m%d").to_string();
+        let uid = self.make_uid(
+            &format!("diary-{}", date_str),
+            &[wiki, date_str.as_str()],
+        );
+
+        self.events.push(CalendarEvent {
+            uid,
+            date,
+            summary,
+            category: "Diary",
+        });
+    }
+
+    /// Adds an event on the due date of the task, ignoring tasks that have
+    /// been completed or rejected
+    ///
+    /// The uid of the event is derived from the wiki containing the task
+    /// (e.g. its path), the name of its page, and its description, so that
+    /// it stays the same when text elsewhere within the page is edited
+    pub fn add_task(&mut self, wiki: &str, task: &DatedTask) {
+        if task.is_done() {
+            return;
+        }
+
+        let uid = self.make_uid(
+            "task",
+            &[wiki, task.page.as_str(), task.description.as_str()],
+        );
+        self.events.push(CalendarEvent {
+            uid,
+            date: task.due,
+            summary: task.description.clone(),
+            category: "Task",
+        });
+    }
+
+    /// Produces a uid from the prefix and a hash of the parts, adding a
+    /// count to uids that were already produced for earlier events (such as
+    /// two identical tasks within the same page)
+    fn make_uid(&mut self, prefix: &str, parts: &[&str]) -> String {
+        let mut hasher = Fnv1a::default();
+        for part in parts {
+            hasher.write(part.as_bytes());
+            hasher.write(&[0]);
+        }
+
+        let uid = format!("{}-{:016x}", prefix, hasher.0);
+        let count = self.uids.entry(uid.clone()).or_default();
+        *count += 1;
+        if *count > 1 {
+            format!("{}-{}@vimwiki", uid, count)
+        } else {
+            format!("{}@vimwiki", uid)
+        }
+    }
+
+    /// Produces the calendar in the iCalendar format, using CRLF line
+    /// endings and folding lines longer than 75 octets
+    pub fn to_ics_string(&self) -> String {
+        let mut lines = vec![
+            String::from("BEGIN:VCALENDAR"),
+            String::from("VERSION:2.0"),
+            String::from("PRODID:-//vimwiki//vimwiki-rs//EN"),
+            String::from("CALSCALE:GREGORIAN"),
+            format!("X-WR-CALNAME:{}", escape_text(&self.name)),
+        ];
+
+        let stamp = format!(
+            "{}T{:02}{:02}{:02}Z",
+            self.timestamp.date().format("%Y%m%d"),
+            self.timestamp.hour(),
+            self.timestamp.minute(),
+            self.timestamp.second()
+        );
+        for event in self.events.iter() {
+            lines.push(String::from("BEGIN:VEVENT"));
+            lines.push(format!("UID:{}", escape_text(&event.uid)));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!(
+                "DTSTART;VALUE=DATE:{}",
+                event.date.format("%Y%m%d")
+            ));
+            lines.push(format!(
+                "DTEND;VALUE=DATE:{}",
+                event.date.add_days(1).format("%Y%m%d")
+            ));
+            lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+            lines.push(format!("CATEGORIES:{}", event.category));
+            lines.push(String::from("END:VEVENT"));
+        }
+
+        lines.push(String::from("END:VCALENDAR"));
+
+        let mut ics = String::new();
+        for line in lines {
+            let _ = write!(ics, "{}\r\n", fold_line(&line));
+        }
+        ics
+    }
+}
+
+/// Finds the title of a page from its title placeholder, falling back to
+/// the text of its first header
+fn page_title(page: &Page) -> Option<String> {
+    let placeholder = page.elements().iter().find_map(|x| match x.as_inner() {
+        BlockElement::Placeholder(Placeholder::Title(title)) => {
+            Some(title.to_string())
+        }
+        _ => None,
+    });
+
+    placeholder.or_else(|| {
+        page.elements().iter().find_map(|x| match x.as_inner() {
+            BlockElement::Header(header) => Some(header.to_string()),
+            _ => None,
+        })
+    })
+}
+
+/// Escapes text for use as a TEXT property value
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Splits a content line into multiple lines no longer than 75 octets, where
+/// each continuation line begins with a single space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > MAX_LINE_LEN {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        folded.push(c);
+        len += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tasks::TaskIndex, Language};
+    fn timestamp() -> Timestamp {
+        "2024-05-01T12:30:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn to_ics_string_should_include_diary_entries_and_pending_tasks() {
+        let mut calendar = Calendar::new(timestamp()).with_name("My wiki");
+
+        let page: Page =
+            Language::from_vimwiki_str("= Trip, day 1 =\nsome text")
+                .parse()
+                .unwrap();
+        calendar.add_diary_page("/wiki", WikiDate::from_ymd(2024, 5, 2), &page);
+
+        let page: Page = Language::from_vimwiki_str(
+            "- [ ] pay bills <2024-05-03>\n- [X] done <2024-05-04>",
+        )
+        .parse()
+        .unwrap();
+        let mut index = TaskIndex::new();
+        index.add_page("sub/todo", &page);
+        for task in index.tasks() {
+            calendar.add_task("/wiki", task);
+        }
+
+        let diary_uid = calendar.events[0].uid.clone();
+        let task_uid = calendar.events[1].uid.clone();
+        assert_eq!(calendar.len(), 2);
+        assert_eq!(
+            calendar.to_ics_string(),
+            [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//vimwiki//vimwiki-rs//EN",
+                "CALSCALE:GREGORIAN",
+                "X-WR-CALNAME:My wiki",
+                "BEGIN:VEVENT",
+                &format!("UID:{}", diary_uid),
+                "DTSTAMP:20240501T123000Z",
+                "DTSTART;VALUE=DATE:20240502",
+                "DTEND;VALUE=DATE:20240503",
+                "SUMMARY:Trip\\, day 1",
+                "CATEGORIES:Diary",
+                "END:VEVENT",
+                "BEGIN:VEVENT",
+                &format!("UID:{}", task_uid),
+                "DTSTAMP:20240501T123000Z",
+                "DTSTART;VALUE=DATE:20240503",
+                "DTEND;VALUE=DATE:20240504",
+                "SUMMARY:pay bills <2024-05-03>",
+                "CATEGORIES:Task",
+                "END:VEVENT",
+                "END:VCALENDAR",
+                "",
+            ]
+            .join("\r\n")
+        );
+    }
+
+    #[test]
+    fn add_diary_page_should_fall_back_to_date_without_title() {
+        let mut calendar = Calendar::new(timestamp());
+        let page: Page =
+            Language::from_vimwiki_str("some text").parse().unwrap();
+        calendar.add_diary_page("/wiki", WikiDate::from_ymd(2024, 5, 2), &page);
+        assert!(calendar
+            .to_ics_string()
+            .contains("\r\nSUMMARY:Diary 2024-05-02\r\n"));
+    }
+
+    fn uids(calendar: &Calendar) -> Vec<String> {
+        calendar.events.iter().map(|x| x.uid.clone()).collect()
+    }
+
+    fn add_tasks(calendar: &mut Calendar, wiki: &str, text: &str) {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let mut index = TaskIndex::new();
+        index.add_page("todo", &page);
+        for task in index.tasks() {
+            calendar.add_task(wiki, task);
+        }
+    }
+
+    #[test]
+    fn add_diary_page_should_give_entries_of_different_wikis_distinct_uids() {
+        let mut calendar = Calendar::new(timestamp());
+        let page: Page =
+            Language::from_vimwiki_str("some text").parse().unwrap();
+        let date = WikiDate::from_ymd(2024, 5, 2);
+        calendar.add_diary_page("/wiki1", date, &page);
+        calendar.add_diary_page("/wiki2", date, &page);
+
+        let uids = uids(&calendar);
+        assert_ne!(uids[0], uids[1]);
+        assert!(uids.iter().all(|x| x.starts_with("diary-20240502-")));
+    }
+
+    #[test]
+    fn add_task_should_keep_uid_when_text_above_task_changes() {
+        let mut before = Calendar::new(timestamp());
+        add_tasks(&mut before, "/wiki", "- [ ] pay bills <2024-05-03>");
+
+        let mut after = Calendar::new(timestamp());
+        add_tasks(
+            &mut after,
+            "/wiki",
+            "= Header =\n\n- [ ] pay bills <2024-05-03>",
+        );
+
+        assert_eq!(uids(&before), uids(&after));
+    }
+
+    #[test]
+    fn add_task_should_give_identical_tasks_distinct_uids() {
+        let mut calendar = Calendar::new(timestamp());
+        add_tasks(
+            &mut calendar,
+            "/wiki",
+            "- [ ] pay bills <2024-05-03>\n- [ ] pay bills <2024-05-03>",
+        );
+        add_tasks(&mut calendar, "/other", "- [ ] pay bills <2024-05-03>");
+
+        let uids = uids(&calendar);
+        assert_eq!(uids.len(), 3);
+        assert_eq!(uids[1], uids[0].replace("@vimwiki", "-2@vimwiki"));
+        assert_ne!(uids[0], uids[2]);
+    }
+
+    #[test]
+    fn fold_line_should_split_long_lines_on_char_boundaries() {
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        let folded = fold_line(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.len() <= MAX_LINE_LEN));
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{Element, Located, Page, Region};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+/// Represents an id of an element derived from the path of its page, its
+/// type, and its region, so that the same element is given the same id every
+/// time its page is parsed and ids can be kept as durable references
+///
+/// NOTE: Ids are only unique within a page if no two elements of the same
+///       type share a region, which holds for every element produced by
+///       the vimwiki parser
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[display(fmt = "{:016x}", _0)]
+#[serde(transparent)]
+pub struct ElementId(u64);
+
+impl ElementId {
+    /// Derives the id of an element of the given type and region within the
+    /// page at the path
+    pub fn new(path: &str, kind: &str, region: Region) -> Self {
+        let mut hasher = Fnv1a::default();
+        hasher.write(path.as_bytes());
+        hasher.write(&[0]);
+        hasher.write(kind.as_bytes());
+        hasher.write(&[0]);
+        hasher.write(&(region.offset() as u64).to_le_bytes());
+        hasher.write(&(region.len() as u64).to_le_bytes());
+        Self(hasher.0)
+    }
+
+    /// Derives the id of the element within the page at the path
+    pub fn of(path: &str, element: &Located<Element>) -> Self {
+        Self::new(path, element.as_inner().kind_name(), element.region())
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+/// 64-bit FNV-1a hash, used instead of the standard hasher as its output is
+/// not guaranteed to be the same across releases of Rust
+pub(crate) struct Fnv1a(pub(crate) u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Derives the id of every element within the page at the path, in
+/// document order
+pub fn element_ids<'a>(
+    path: &str,
+    page: &'a Page<'a>,
+) -> Vec<(ElementId, Located<Element<'a>>)> {
+    page.descendants()
+        .map(|x| (ElementId::of(path, &x), x))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use std::collections::HashSet;
+
+    #[test]
+    fn element_ids_should_be_the_same_across_parses() {
+        let text = "= Header =\n- one *two*\n- three\n";
+        let ids = |text: &str| -> Vec<ElementId> {
+            let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+            element_ids("index.wiki", &page)
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect()
+        };
+
+        let first = ids(text);
+        assert_eq!(first, ids(text));
+        assert_eq!(
+            first.iter().collect::<HashSet<_>>().len(),
+            first.len(),
+            "Ids are not unique within the page"
+        );
+    }
+
+    #[test]
+    fn element_id_should_depend_on_path_type_and_region() {
+        let region = Region::new(3, 5);
+        let id = ElementId::new("a.wiki", "header", region);
+        assert_eq!(id, ElementId::new("a.wiki", "header", region));
+        assert_ne!(id, ElementId::new("b.wiki", "header", region));
+        assert_ne!(id, ElementId::new("a.wiki", "paragraph", region));
+        assert_ne!(id, ElementId::new("a.wiki", "header", Region::new(3, 6)));
+        assert_eq!(id.to_string().len(), 16);
+    }
+}
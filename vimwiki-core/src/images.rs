@@ -0,0 +1,131 @@
+use crate::{
+    graph::collect_links,
+    resolve::{LinkResolver, LinkTarget},
+    Description, Link, LinkData, Page, Region,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+/// Represents an image transcluded within a page, either by itself as in
+/// `{{img.png}}` or as the description of a link as in `[[page|{{img.png}}]]`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Image {
+    /// Source of the image as it was written
+    pub src: String,
+
+    /// Concrete location of the image, or None if it could not be resolved
+    pub target: Option<LinkTarget>,
+
+    /// Alternate text of the image from its description
+    pub alt: Option<String>,
+
+    /// Attributes of the image (e.g. `style` or `width`) sorted by name
+    pub properties: BTreeMap<String, String>,
+
+    /// Region of the link containing the image within its page
+    pub region: Region,
+}
+
+impl Image {
+    fn new(data: &LinkData, region: Region) -> Self {
+        Self {
+            src: data.uri_ref.to_string(),
+            target: None,
+            alt: match data.description.as_ref() {
+                Some(Description::Text(x)) => Some(x.to_string()),
+                _ => None,
+            },
+            properties: data
+                .properties
+                .iter()
+                .flatten()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            region,
+        }
+    }
+}
+
+/// Finds every image of a page in order alongside a transclusion link to it
+fn find_images<'a>(page: &'a Page<'a>) -> Vec<(Image, Link<'a>)> {
+    collect_links(page)
+        .into_iter()
+        .filter_map(|link| {
+            let region = link.region();
+            let data = match link.into_inner() {
+                Link::Transclusion { data } => data,
+                x => match x.into_data().description {
+                    Some(Description::TransclusionLink(data)) => *data,
+                    _ => return None,
+                },
+            };
+            let image = Image::new(&data, region);
+            Some((image, Link::Transclusion { data }))
+        })
+        .collect()
+}
+
+/// Collects every image of a page in order without resolving their targets
+pub fn collect_images(page: &Page) -> Vec<Image> {
+    find_images(page)
+        .into_iter()
+        .map(|(image, _)| image)
+        .collect()
+}
+
+/// Collects every image of the page of the file at the given path, resolving
+/// the location of each image relative to the file
+pub fn page_images(
+    resolver: &LinkResolver,
+    src: &Path,
+    page: &Page,
+) -> Vec<Image> {
+    find_images(page)
+        .into_iter()
+        .map(|(mut image, link)| {
+            image.target = resolver.resolve(src, &link).ok();
+            image
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{resolve::ResolverWiki, Language};
+    use std::path::PathBuf;
+
+    #[test]
+    fn page_images_should_include_images_within_link_descriptions() {
+        let text = concat!(
+            "{{img/a.png|logo|style=\"width:150px\" class=\"x\"}}\n",
+            "[[page|{{b.png}}]] [[other]]\n",
+        );
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let resolver =
+            LinkResolver::new().with_wiki(ResolverWiki::new("/wiki"));
+        let images =
+            page_images(&resolver, Path::new("/wiki/sub/page.wiki"), &page);
+
+        assert_eq!(images.len(), 2);
+        assert_eq!(images[0].src, "img/a.png");
+        assert_eq!(images[0].alt.as_deref(), Some("logo"));
+        assert_eq!(
+            images[0].properties.iter().collect::<Vec<_>>(),
+            [
+                (&"class".to_string(), &"x".to_string()),
+                (&"style".to_string(), &"width:150px".to_string()),
+            ]
+        );
+        assert_eq!(
+            images[0].target.as_ref().and_then(LinkTarget::path),
+            Some(PathBuf::from("/wiki/sub/img/a.png").as_path())
+        );
+
+        assert_eq!(images[1].src, "b.png");
+        assert_eq!(images[1].alt, None);
+        let offset = text.find("[[page").unwrap();
+        assert_eq!(images[1].region.offset(), offset);
+        assert_eq!(images[1].region.len(), "[[page|{{b.png}}]]".len());
+    }
+}
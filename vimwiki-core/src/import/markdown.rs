@@ -0,0 +1,826 @@
+use super::{indent_width, strip_indent, table_separator};
+
+/// Converts CommonMark text (with common extensions such as tables, task
+/// lists, strikethrough, math, YAML front matter, and `[[wiki links]]`) into
+/// vimwiki text
+///
+/// Constructs without a vimwiki equivalent, such as reference-style links
+/// and raw HTML, are kept as plain text.
+pub fn markdown_to_vimwiki(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut in_list = false;
+    let mut i = 0;
+
+    if lines.first().map(|x| x.trim_end()) == Some("---") {
+        if let Some(end) = lines.iter().skip(1).position(|x| x.trim() == "---")
+        {
+            convert_front_matter(&lines[1..=end], &mut out);
+            i = end + 2;
+        }
+    }
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        let indent = indent_width(line);
+        let prefix = &line[..line.len() - line.trim_start().len()];
+        let follows_blank = out.last().is_none_or(|x| x.is_empty());
+
+        if trimmed.is_empty() {
+            out.push(String::new());
+            i += 1;
+        } else if let Some(fence) = Fence::parse(trimmed) {
+            out.push(format!("{}{{{{{{{}", prefix, fence.language));
+            i += 1;
+            while i < lines.len() && !fence.is_closed_by(lines[i].trim()) {
+                out.push(format!(
+                    "{}{}",
+                    prefix,
+                    strip_indent(lines[i], indent)
+                ));
+                i += 1;
+            }
+            out.push(format!("{}}}}}}}", prefix));
+            i += 1;
+        } else if trimmed.starts_with("$$") {
+            i = convert_math_block(&lines, i, prefix, &mut out);
+        } else if indent >= 4 && !in_list && follows_blank {
+            out.push(String::from("{{{"));
+            while i < lines.len()
+                && (lines[i].trim().is_empty() || indent_width(lines[i]) >= 4)
+            {
+                out.push(strip_indent(lines[i], 4).to_string());
+                i += 1;
+            }
+            while out.last().is_some_and(|x| x.is_empty()) {
+                out.pop();
+            }
+            out.push(String::from("}}}"));
+        } else if trimmed.starts_with("<!--") {
+            i = convert_comment(&lines, i, &mut out);
+        } else if let Some((level, content)) = parse_heading(trimmed) {
+            let eq = "=".repeat(level);
+            out.push(format!("{} {} {}", eq, convert_inline(content), eq));
+            in_list = false;
+            i += 1;
+        } else if is_thematic_break(trimmed) {
+            out.push(String::from("----"));
+            in_list = false;
+            i += 1;
+        } else if let Some(alignments) = lines
+            .get(i + 1)
+            .filter(|_| trimmed.contains('|'))
+            .and_then(|x| parse_table_separator(x))
+        {
+            out.push(convert_table_row(trimmed));
+            out.push(table_separator(&alignments));
+            i += 2;
+            while i < lines.len()
+                && lines[i].contains('|')
+                && !lines[i].trim().is_empty()
+            {
+                out.push(convert_table_row(lines[i].trim()));
+                i += 1;
+            }
+        } else if let Some(content) = trimmed.strip_prefix('>') {
+            let content = content.trim_start_matches('>');
+            let content = content.strip_prefix(' ').unwrap_or(content);
+            out.push(format!("> {}", convert_inline(content)));
+            i += 1;
+        } else if let Some((marker, content)) = parse_list_item(trimmed) {
+            out.push(format!(
+                "{}{} {}",
+                prefix,
+                marker,
+                convert_inline(content)
+            ));
+            in_list = true;
+            i += 1;
+        } else {
+            if indent == 0 && follows_blank {
+                in_list = false;
+            }
+
+            let next = lines.get(i + 1).map(|x| x.trim()).unwrap_or_default();
+            let setext = if !next.is_empty() && next.chars().all(|c| c == '=') {
+                Some(1)
+            } else if !next.is_empty() && next.chars().all(|c| c == '-') {
+                Some(2)
+            } else {
+                None
+            };
+
+            match setext {
+                Some(level) if indent < 4 => {
+                    let eq = "=".repeat(level);
+                    out.push(format!(
+                        "{} {} {}",
+                        eq,
+                        convert_inline(trimmed),
+                        eq
+                    ));
+                    i += 2;
+                }
+                _ => {
+                    let content = trimmed.strip_suffix('\\').unwrap_or(trimmed);
+                    out.push(format!("{}{}", prefix, convert_inline(content)));
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    let mut vimwiki = out.join("\n");
+    if text.ends_with('\n') {
+        vimwiki.push('\n');
+    }
+    vimwiki
+}
+
+/// Represents the opening line of a fenced code block
+struct Fence<'a> {
+    marker: char,
+    len: usize,
+    language: &'a str,
+}
+
+impl<'a> Fence<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+        let len = line.chars().take_while(|c| *c == marker).count();
+        let info = line[len..].trim();
+        if len < 3 || (marker == '`' && info.contains('`')) {
+            return None;
+        }
+
+        Some(Self {
+            marker,
+            len,
+            language: info.split_whitespace().next().unwrap_or_default(),
+        })
+    }
+
+    fn is_closed_by(&self, line: &str) -> bool {
+        let len = line.chars().take_while(|c| *c == self.marker).count();
+        len >= self.len && line[len..].trim().is_empty()
+    }
+}
+
+/// Converts the `key: value` lines of YAML front matter into placeholders,
+/// dropping keys without an equivalent
+fn convert_front_matter(lines: &[&str], out: &mut Vec<String>) {
+    for line in lines {
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+
+        match key {
+            "title" => out.push(format!("%title {}", value)),
+            "date" => out.push(format!("%date {}", value)),
+            "tags" => {
+                let tags: Vec<&str> = value
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .map(|x| x.trim_matches(|c| c == '"' || c == '\''))
+                    .filter(|x| !x.is_empty())
+                    .collect();
+                if !tags.is_empty() {
+                    out.push(format!(":{}:", tags.join(":")));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Converts a `$$` math block starting at the given line, returning the
+/// index of the line following the block
+fn convert_math_block(
+    lines: &[&str],
+    start: usize,
+    prefix: &str,
+    out: &mut Vec<String>,
+) -> usize {
+    let first = lines[start].trim()[2..].trim();
+    out.push(format!("{}{{{{$", prefix));
+
+    // Support the entire block being on a single line
+    if let Some(math) = first.strip_suffix("$$") {
+        out.push(format!("{}{}", prefix, math.trim()));
+        out.push(format!("{}}}}}$", prefix));
+        return start + 1;
+    } else if !first.is_empty() {
+        out.push(format!("{}{}", prefix, first));
+    }
+
+    let mut i = start + 1;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        i += 1;
+        if let Some(math) = line.strip_suffix("$$") {
+            if !math.trim().is_empty() {
+                out.push(format!("{}{}", prefix, math.trim()));
+            }
+            break;
+        }
+        out.push(format!("{}{}", prefix, line));
+    }
+    out.push(format!("{}}}}}$", prefix));
+    i
+}
+
+/// Converts an HTML comment starting at the given line into a vimwiki
+/// comment, returning the index of the line following the comment
+fn convert_comment(
+    lines: &[&str],
+    start: usize,
+    out: &mut Vec<String>,
+) -> usize {
+    let first = lines[start].trim()["<!--".len()..].trim();
+    if let Some(comment) = first.strip_suffix("-->") {
+        out.push(format!("%% {}", comment.trim()));
+        return start + 1;
+    }
+
+    out.push(format!("%%+ {}", first));
+    let mut i = start + 1;
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+        if let Some(comment) = line.trim_end().strip_suffix("-->") {
+            out.push(format!("{} +%%", comment.trim_end()));
+            return i;
+        }
+        out.push(line.to_string());
+    }
+    out.push(String::from("+%%"));
+    i
+}
+
+/// Parses an ATX heading into its level and content
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[level..];
+    if level == 0 || level > 6 || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    // Remove an optional closing sequence of #
+    let content = rest.trim();
+    let stripped = content.trim_end_matches('#');
+    if stripped.is_empty() || stripped.ends_with(' ') {
+        Some((level, stripped.trim_end()))
+    } else {
+        Some((level, content))
+    }
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    let mut chars = line.chars().filter(|c| !c.is_whitespace());
+    match chars.next() {
+        Some(c) if c == '-' || c == '*' || c == '_' => {
+            chars.clone().all(|x| x == c) && chars.count() >= 2
+        }
+        _ => false,
+    }
+}
+
+/// Parses a list item into its vimwiki marker (including any checkbox) and
+/// its content
+fn parse_list_item(line: &str) -> Option<(String, &str)> {
+    let (marker, rest) = match line.chars().next()? {
+        c @ ('-' | '*' | '+') => {
+            (String::from(if c == '+' { '-' } else { c }), &line[1..])
+        }
+        c if c.is_ascii_digit() => {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            let suffix = line[digits..].chars().next()?;
+            if digits > 9 || (suffix != '.' && suffix != ')') {
+                return None;
+            }
+            (line[..=digits].to_string(), &line[digits + 1..])
+        }
+        _ => return None,
+    };
+
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    let rest = rest.trim_start();
+
+    for (checkbox, todo) in [("[ ]", "[ ]"), ("[x]", "[X]"), ("[X]", "[X]")] {
+        if let Some(content) = rest.strip_prefix(checkbox) {
+            if content.is_empty() || content.starts_with(' ') {
+                return Some((format!("{} {}", marker, todo), content.trim()));
+            }
+        }
+    }
+
+    Some((marker, rest))
+}
+
+/// Parses a table separator row (e.g. `| --- | :-: |`) into the alignment
+/// of each column
+fn parse_table_separator(line: &str) -> Option<Vec<(bool, bool)>> {
+    let cells = split_table_row(line.trim());
+    if !line.contains('-') || cells.is_empty() {
+        return None;
+    }
+
+    cells
+        .into_iter()
+        .map(|cell| {
+            let cell = cell.trim();
+            let inner = cell.trim_start_matches(':').trim_end_matches(':');
+            if !inner.is_empty() && inner.chars().all(|c| c == '-') {
+                Some((cell.starts_with(':'), cell.ends_with(':')))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn convert_table_row(line: &str) -> String {
+    let cells: Vec<String> = split_table_row(line)
+        .into_iter()
+        .map(|cell| format!(" {} ", convert_inline(cell.trim())))
+        .collect();
+    format!("|{}|", cells.join("|"))
+}
+
+/// Splits a table row into its cells, ignoring pipes within code spans or
+/// escaped with a backslash
+fn split_table_row(line: &str) -> Vec<&str> {
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(x) if !x.ends_with('\\') => x,
+        _ => line,
+    };
+
+    let mut cells = Vec::new();
+    let mut start = 0;
+    let mut in_code = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '`' => in_code = !in_code,
+            '|' if !in_code => {
+                cells.push(&line[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    cells.push(&line[start..]);
+    cells
+}
+
+/// Converts the inline markup of a line of markdown into vimwiki
+fn convert_inline(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        match c {
+            '\\' if next.is_some_and(|x| x.is_ascii_punctuation()) => {
+                out.extend(next);
+                i += 2;
+            }
+            '`' => {
+                let n = run_len(&chars, i, '`');
+                match find_run(&chars, i + n, '`', n) {
+                    Some(end) => {
+                        let code: String = chars[i + n..end].iter().collect();
+                        out.push('`');
+                        out.push_str(code.trim());
+                        out.push('`');
+                        i = end + n;
+                    }
+                    None => {
+                        out.extend(&chars[i..i + n]);
+                        i += n;
+                    }
+                }
+            }
+            '$' if next.is_some_and(|x| x != '$' && !x.is_whitespace()) => {
+                match find_run(&chars, i + 1, '$', 1)
+                    .filter(|end| !chars[end - 1].is_whitespace())
+                {
+                    Some(end) => {
+                        out.extend(&chars[i..=end]);
+                        i = end + 1;
+                    }
+                    None => {
+                        out.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            '!' if next == Some('[') && chars.get(i + 2) == Some(&'[') => {
+                match find_str(&chars, i + 3, "]]") {
+                    Some(end) => {
+                        let target: String = chars[i + 3..end].iter().collect();
+                        out.push_str(&format!("{{{{{}}}}}", target));
+                        i = end + 2;
+                    }
+                    None => {
+                        out.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            '!' if next == Some('[') => match parse_link(&chars, i + 1) {
+                Some((alt, src, end)) => {
+                    if alt.is_empty() {
+                        out.push_str(&format!("{{{{{}}}}}", src));
+                    } else {
+                        out.push_str(&format!("{{{{{}|{}}}}}", src, alt));
+                    }
+                    i = end;
+                }
+                None => {
+                    out.push(c);
+                    i += 1;
+                }
+            },
+            '[' if next == Some('[') => match find_str(&chars, i + 2, "]]") {
+                Some(end) => {
+                    out.extend(&chars[i..end + 2]);
+                    i = end + 2;
+                }
+                None => {
+                    out.push(c);
+                    i += 1;
+                }
+            },
+            '[' => match parse_link(&chars, i) {
+                Some((description, dest, end)) => {
+                    let target = link_target(&dest);
+                    let description = convert_inline(&description);
+                    if description.is_empty() || description == target {
+                        out.push_str(&format!("[[{}]]", target));
+                    } else {
+                        out.push_str(&format!(
+                            "[[{}|{}]]",
+                            target, description
+                        ));
+                    }
+                    i = end;
+                }
+                None => {
+                    out.push(c);
+                    i += 1;
+                }
+            },
+            '<' => {
+                let end = chars[i..].iter().position(|x| *x == '>');
+                match end
+                    .map(|end| chars[i + 1..i + end].iter().collect::<String>())
+                {
+                    Some(url) if is_autolink(&url) => {
+                        out.push_str(url.trim_start_matches("mailto:"));
+                        i += end.unwrap_or_default() + 1;
+                    }
+                    _ => {
+                        out.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            '~' if next == Some('~') => match find_run(&chars, i + 2, '~', 2) {
+                Some(end) => {
+                    let inner: String = chars[i + 2..end].iter().collect();
+                    out.push_str(&format!("~~{}~~", convert_inline(&inner)));
+                    i = end + 2;
+                }
+                None => {
+                    out.push_str("~~");
+                    i += 2;
+                }
+            },
+            '*' | '_' => {
+                let n = run_len(&chars, i, c).min(3);
+                let intraword = c == '_'
+                    && i > 0
+                    && chars[i - 1].is_alphanumeric()
+                    && next.is_some_and(|x| x.is_alphanumeric() || x == '_');
+                let opens =
+                    chars.get(i + n).is_some_and(|x| !x.is_whitespace());
+                let end = if intraword || !opens {
+                    None
+                } else {
+                    find_emphasis_end(&chars, i + n, c, n)
+                };
+
+                match end {
+                    Some(end) => {
+                        let inner: String = chars[i + n..end].iter().collect();
+                        let inner = convert_inline(&inner);
+                        match n {
+                            1 => out.push_str(&format!("_{}_", inner)),
+                            2 => out.push_str(&format!("*{}*", inner)),
+                            _ => out.push_str(&format!("_*{}*_", inner)),
+                        }
+                        i = end + n;
+                    }
+                    None => {
+                        out.extend(&chars[i..i + n]);
+                        i += n;
+                    }
+                }
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Returns the total consecutive occurrences of the character starting at
+/// the position
+fn run_len(chars: &[char], start: usize, c: char) -> usize {
+    chars[start..].iter().take_while(|x| **x == c).count()
+}
+
+/// Finds the position of the next run of exactly `n` of the character
+fn find_run(chars: &[char], start: usize, c: char, n: usize) -> Option<usize> {
+    let mut i = start;
+    while i < chars.len() {
+        if chars[i] == c {
+            let len = run_len(chars, i, c);
+            if len == n {
+                return Some(i);
+            }
+            i += len;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+fn find_str(chars: &[char], start: usize, s: &str) -> Option<usize> {
+    let s: Vec<char> = s.chars().collect();
+    (start..chars.len()).find(|i| chars[*i..].starts_with(&s))
+}
+
+/// Finds the closing delimiter of emphasis, which must follow a
+/// non-whitespace character and, for underscores, not precede a word
+fn find_emphasis_end(
+    chars: &[char],
+    start: usize,
+    c: char,
+    n: usize,
+) -> Option<usize> {
+    let mut i = start;
+    while let Some(end) = find_run(chars, i, c, n) {
+        let closes = end > start
+            && !chars[end - 1].is_whitespace()
+            && (c != '_'
+                || chars.get(end + n).is_none_or(|x| !x.is_alphanumeric()));
+        if closes {
+            return Some(end);
+        }
+        i = end + n;
+    }
+    None
+}
+
+/// Parses an inline link of the form `[text](destination "title")` starting
+/// at the opening bracket, returning the text, destination, and position
+/// following the link
+fn parse_link(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+    let mut depth = 0;
+    let mut close = None;
+    for (i, c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let close = close?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+
+    let mut depth = 0;
+    let end = (close + 1..chars.len()).find(|i| {
+        match chars[*i] {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        depth == 0
+    })?;
+
+    let text: String = chars[start + 1..close].iter().collect();
+    let dest: String = chars[close + 2..end].iter().collect();
+    let dest = dest.trim();
+    let dest = match dest.strip_prefix('<') {
+        Some(x) => x.split('>').next().unwrap_or_default(),
+        None => dest.split_whitespace().next().unwrap_or_default(),
+    };
+
+    Some((text, dest.to_string(), end + 1))
+}
+
+/// Converts a markdown link destination into a vimwiki link target, turning
+/// links to other markdown files into links to wiki pages
+fn link_target(dest: &str) -> String {
+    if dest.contains("://") || dest.starts_with("mailto:") {
+        return dest.to_string();
+    }
+
+    let (path, anchor) = match dest.split_once('#') {
+        Some((path, anchor)) => (path, Some(anchor)),
+        None => (dest, None),
+    };
+    let path = path.replace("%20", " ");
+
+    let ext = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase());
+    let path = match ext.as_deref() {
+        Some("md") | Some("markdown") => {
+            path[..path.rfind('.').unwrap_or(path.len())].to_string()
+        }
+        Some(ext) if !ext.contains('/') => format!("file:{}", path),
+        _ => path,
+    };
+
+    match anchor {
+        Some(anchor) => format!("{}#{}", path, anchor),
+        None => path,
+    }
+}
+
+fn is_autolink(text: &str) -> bool {
+    !text.contains(char::is_whitespace)
+        && (text.contains("://") || text.starts_with("mailto:"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, Page};
+
+    fn block_names(text: &str) -> Vec<&'static str> {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        page.elements()
+            .iter()
+            .map(|x| x.as_inner().kind_name())
+            .collect()
+    }
+
+    #[test]
+    fn markdown_to_vimwiki_should_convert_blocks() {
+        let markdown = [
+            "# Title",
+            "",
+            "Some **bold** and *italic* text with `code`.",
+            "",
+            "Subtitle",
+            "--------",
+            "",
+            "- [ ] todo",
+            "- [x] done",
+            "  + nested",
+            "1. first",
+            "",
+            "```rust",
+            "fn main() {}",
+            "```",
+            "",
+            "| Name | Age |",
+            "|:-----|----:|",
+            "| Bob  | 3   |",
+            "",
+            "> quoted *text*",
+            "",
+            "***",
+            "",
+        ]
+        .join("\n");
+
+        let vimwiki = markdown_to_vimwiki(&markdown);
+        assert_eq!(
+            vimwiki,
+            [
+                "= Title =",
+                "",
+                "Some *bold* and _italic_ text with `code`.",
+                "",
+                "== Subtitle ==",
+                "",
+                "- [ ] todo",
+                "- [X] done",
+                "  - nested",
+                "1. first",
+                "",
+                "{{{rust",
+                "fn main() {}",
+                "}}}",
+                "",
+                "| Name | Age |",
+                "|:---|---:|",
+                "| Bob | 3 |",
+                "",
+                "> quoted _text_",
+                "",
+                "----",
+                "",
+            ]
+            .join("\n")
+        );
+
+        assert_eq!(
+            block_names(&vimwiki),
+            [
+                "header",
+                "paragraph",
+                "header",
+                "list",
+                "code_block",
+                "table",
+                "blockquote",
+                "divider"
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_to_vimwiki_should_convert_links_and_images() {
+        assert_eq!(
+            convert_inline("[docs](https://example.com \"Docs\")"),
+            "[[https://example.com|docs]]"
+        );
+        assert_eq!(
+            convert_inline("[Other](other%20page.md#intro)"),
+            "[[other page#intro|Other]]"
+        );
+        assert_eq!(convert_inline("[a.pdf](a.pdf)"), "[[file:a.pdf|a.pdf]]");
+        assert_eq!(convert_inline("[[Obsidian|link]]"), "[[Obsidian|link]]");
+        assert_eq!(
+            convert_inline("![logo](img/logo.png)"),
+            "{{img/logo.png|logo}}"
+        );
+        assert_eq!(
+            convert_inline("<https://example.com>"),
+            "https://example.com"
+        );
+        assert_eq!(convert_inline("snake_case_name"), "snake_case_name");
+        assert_eq!(
+            convert_inline("~~gone~~ \\*literal\\*"),
+            "~~gone~~ *literal*"
+        );
+    }
+
+    #[test]
+    fn markdown_to_vimwiki_should_convert_front_matter_and_math() {
+        let markdown = [
+            "---",
+            "title: My Page",
+            "tags: [one, two]",
+            "aliases: ignored",
+            "---",
+            "$$",
+            "x^2",
+            "$$",
+            "<!-- note -->",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            markdown_to_vimwiki(&markdown),
+            [
+                "%title My Page",
+                ":one:two:",
+                "{{$",
+                "x^2",
+                "}}$",
+                "%% note"
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,79 @@
+mod markdown;
+pub use markdown::markdown_to_vimwiki;
+
+mod org;
+pub use org::org_to_vimwiki;
+
+/// Represents a format of text that can be converted into vimwiki
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ImportFormat {
+    Markdown,
+    Org,
+}
+
+impl ImportFormat {
+    /// Determines the format from a file extension, if it is supported
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "org" => Some(Self::Org),
+            _ => None,
+        }
+    }
+
+    /// Converts text in this format into vimwiki text
+    pub fn to_vimwiki(self, text: &str) -> String {
+        match self {
+            Self::Markdown => markdown_to_vimwiki(text),
+            Self::Org => org_to_vimwiki(text),
+        }
+    }
+}
+
+impl std::str::FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "org" => Ok(Self::Org),
+            x => Err(format!("Unknown import format: {}", x)),
+        }
+    }
+}
+
+/// Returns the number of leading spaces of a line, treating tabs as four
+/// spaces
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Removes up to `width` columns of leading whitespace from a line
+fn strip_indent(line: &str, width: usize) -> &str {
+    let mut removed = 0;
+    for (i, c) in line.char_indices() {
+        if removed >= width || (c != ' ' && c != '\t') {
+            return &line[i..];
+        }
+        removed += if c == '\t' { 4 } else { 1 };
+    }
+    ""
+}
+
+/// Produces a table separator row for the given column alignments, where
+/// each alignment is a pair of whether the column is aligned left and right
+fn table_separator(alignments: &[(bool, bool)]) -> String {
+    let cells: Vec<&str> = alignments
+        .iter()
+        .map(|alignment| match alignment {
+            (true, true) => ":---:",
+            (true, false) => ":---",
+            (false, true) => "---:",
+            (false, false) => "---",
+        })
+        .collect();
+    format!("|{}|", cells.join("|"))
+}
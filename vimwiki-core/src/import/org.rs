@@ -0,0 +1,548 @@
+use super::{indent_width, strip_indent, table_separator};
+
+/// Mapping of org TODO keywords to the closest vimwiki keyword
+const TODO_KEYWORDS: &[(&str, &str)] = &[
+    ("TODO", "TODO"),
+    ("DONE", "DONE"),
+    ("NEXT", "STARTED"),
+    ("STARTED", "STARTED"),
+    ("DOING", "STARTED"),
+    ("WAITING", "TODO"),
+    ("HOLD", "TODO"),
+    ("CANCELLED", "DONE"),
+    ("CANCELED", "DONE"),
+];
+
+/// Converts org-mode text into vimwiki text, mapping headlines (including
+/// TODO keywords and tags), plain and description lists, tables, src and
+/// example blocks, quotes, and the title, date, and file tags keywords
+///
+/// Property drawers, planning lines, and other keywords have no vimwiki
+/// equivalent and are dropped.
+pub fn org_to_vimwiki(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        let indent = indent_width(line);
+        let prefix = &line[..line.len() - line.trim_start().len()];
+        let upper = trimmed.to_uppercase();
+
+        if trimmed.is_empty() {
+            out.push(String::new());
+            i += 1;
+        } else if let Some(level) = headline_level(line) {
+            convert_headline(level, &line[level..], &mut out);
+            i += 1;
+        } else if let Some(name) = upper.strip_prefix("#+BEGIN_") {
+            let name = name.split_whitespace().next().unwrap_or_default();
+            let end = format!("#+END_{}", name);
+            let block_end = (i + 1..lines.len())
+                .find(|j| lines[*j].trim().to_uppercase().starts_with(&end))
+                .unwrap_or(lines.len());
+            let contents = &lines[i + 1..block_end];
+
+            match name {
+                "SRC" | "EXAMPLE" | "EXPORT" => {
+                    let language = if name == "SRC" {
+                        trimmed.split_whitespace().nth(1).unwrap_or_default()
+                    } else {
+                        ""
+                    };
+                    out.push(format!("{}{{{{{{{}", prefix, language));
+                    for line in contents {
+                        out.push(format!(
+                            "{}{}",
+                            prefix,
+                            strip_indent(line, indent)
+                        ));
+                    }
+                    out.push(format!("{}}}}}}}", prefix));
+                }
+                "QUOTE" => {
+                    for line in contents {
+                        out.push(format!("> {}", convert_inline(line.trim())));
+                    }
+                }
+                _ => out.push(org_to_vimwiki(&contents.join("\n"))),
+            }
+            i = block_end + 1;
+        } else if trimmed.starts_with("#+") {
+            convert_keyword(trimmed, &mut out);
+            i += 1;
+        } else if trimmed == "#" || trimmed.starts_with("# ") {
+            out.push(format!("%%{}", &trimmed[1..]));
+            i += 1;
+        } else if is_drawer_start(trimmed) {
+            i += 1;
+            while i < lines.len()
+                && !lines[i].trim().eq_ignore_ascii_case(":END:")
+            {
+                i += 1;
+            }
+            i += 1;
+        } else if trimmed == ":" || trimmed.starts_with(": ") {
+            out.push(format!("{}{{{{{{", prefix));
+            while i < lines.len()
+                && (lines[i].trim() == ":" || lines[i].trim().starts_with(": "))
+            {
+                out.push(format!(
+                    "{}{}",
+                    prefix,
+                    lines[i].trim().get(2..).unwrap_or_default()
+                ));
+                i += 1;
+            }
+            out.push(format!("{}}}}}}}", prefix));
+        } else if ["SCHEDULED:", "DEADLINE:", "CLOSED:"]
+            .iter()
+            .any(|x| trimmed.starts_with(x))
+        {
+            i += 1;
+        } else if trimmed.len() >= 5 && trimmed.chars().all(|c| c == '-') {
+            out.push(String::from("----"));
+            i += 1;
+        } else if trimmed.starts_with('|') {
+            let start = i;
+            while i < lines.len() && lines[i].trim().starts_with('|') {
+                i += 1;
+            }
+            convert_table(&lines[start..i], &mut out);
+        } else if let Some((marker, content)) = parse_list_item(trimmed, indent)
+        {
+            match content.split_once(" :: ") {
+                Some((term, definition)) if !marker.contains('[') => {
+                    out.push(format!(
+                        "{}{}:: {}",
+                        prefix,
+                        convert_inline(term.trim()),
+                        convert_inline(definition.trim())
+                    ));
+                }
+                _ => out.push(format!(
+                    "{}{} {}",
+                    prefix,
+                    marker,
+                    convert_inline(content)
+                )),
+            }
+            i += 1;
+        } else {
+            out.push(format!("{}{}", prefix, convert_inline(trimmed)));
+            i += 1;
+        }
+    }
+
+    let mut vimwiki = out.join("\n");
+    if text.ends_with('\n') {
+        vimwiki.push('\n');
+    }
+    vimwiki
+}
+
+/// Returns the level of the headline if the line is one, meaning that it
+/// starts with one or more stars followed by a space
+fn headline_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '*').count();
+    let rest = &line[level..];
+    if level > 0 && (rest.is_empty() || rest.starts_with(' ')) {
+        Some(level)
+    } else {
+        None
+    }
+}
+
+/// Converts the contents of a headline following its stars into a header,
+/// keeping its TODO keyword and placing its tags on the following line
+fn convert_headline(level: usize, rest: &str, out: &mut Vec<String>) {
+    let mut words: Vec<&str> = rest.split_whitespace().collect();
+
+    let keyword = words.first().and_then(|word| {
+        TODO_KEYWORDS
+            .iter()
+            .find(|(org, _)| org == word)
+            .map(|(_, vimwiki)| *vimwiki)
+    });
+    if keyword.is_some() {
+        words.remove(0);
+    }
+
+    // Remove the priority cookie as vimwiki has no equivalent
+    if words
+        .first()
+        .is_some_and(|x| x.starts_with("[#") && x.ends_with(']'))
+    {
+        words.remove(0);
+    }
+
+    let tags = words
+        .last()
+        .filter(|x| x.len() > 2 && x.starts_with(':') && x.ends_with(':'))
+        .map(|x| x.to_string());
+    if tags.is_some() {
+        words.pop();
+    }
+
+    let title = convert_inline(&words.join(" "));
+    let eq = "=".repeat(level.min(6));
+    out.push(match keyword {
+        Some(keyword) => format!("{} {} {} {}", eq, keyword, title, eq),
+        None => format!("{} {} {}", eq, title, eq),
+    });
+    out.extend(tags);
+}
+
+/// Converts an in-buffer keyword (e.g. `#+TITLE: text`) into a placeholder
+/// or tags, dropping keywords without an equivalent
+fn convert_keyword(line: &str, out: &mut Vec<String>) {
+    let (key, value) = match line[2..].split_once(':') {
+        Some((key, value)) => (key.to_uppercase(), value.trim()),
+        None => return,
+    };
+
+    match key.as_str() {
+        "TITLE" => out.push(format!("%title {}", value)),
+        "DATE" => out.push(format!(
+            "%date {}",
+            value
+                .trim_matches(|c| c == '<' || c == '>' || c == '[' || c == ']')
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+        )),
+        "FILETAGS" if !value.is_empty() => {
+            let tags: Vec<&str> =
+                value.split(':').filter(|x| !x.trim().is_empty()).collect();
+            out.push(format!(":{}:", tags.join(":")));
+        }
+        _ => {}
+    }
+}
+
+fn is_drawer_start(line: &str) -> bool {
+    line.len() > 2
+        && line.starts_with(':')
+        && line.ends_with(':')
+        && line[1..line.len() - 1]
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Converts the rows of a table, keeping the first horizontal rule following
+/// a row as the separator of the header and dropping all others
+fn convert_table(lines: &[&str], out: &mut Vec<String>) {
+    let mut has_header = false;
+    let mut has_row = false;
+
+    for line in lines {
+        let line = line.trim();
+        if line.starts_with("|-") {
+            if has_row && !has_header {
+                let columns = line.matches('+').count() + 1;
+                out.push(table_separator(&vec![(false, false); columns]));
+                has_header = true;
+            }
+            continue;
+        }
+
+        let cells: Vec<String> = line
+            .trim_start_matches('|')
+            .trim_end_matches('|')
+            .split('|')
+            .map(|cell| format!(" {} ", convert_inline(cell.trim())))
+            .collect();
+        out.push(format!("|{}|", cells.join("|")));
+        has_row = true;
+    }
+}
+
+/// Parses a plain list item into its vimwiki marker (including any checkbox)
+/// and its content, where unindented stars are headlines instead of items
+fn parse_list_item(line: &str, indent: usize) -> Option<(String, &str)> {
+    let (marker, rest) = match line.chars().next()? {
+        '-' | '+' => (String::from("-"), &line[1..]),
+        '*' if indent > 0 => (String::from("*"), &line[1..]),
+        c if c.is_ascii_digit() => {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            let suffix = line[digits..].chars().next()?;
+            if suffix != '.' && suffix != ')' {
+                return None;
+            }
+            (line[..=digits].to_string(), &line[digits + 1..])
+        }
+        _ => return None,
+    };
+
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    let rest = rest.trim_start();
+
+    for (checkbox, todo) in [("[ ]", "[ ]"), ("[X]", "[X]"), ("[-]", "[o]")] {
+        if let Some(content) = rest.strip_prefix(checkbox) {
+            return Some((format!("{} {}", marker, todo), content.trim()));
+        }
+    }
+
+    Some((marker, rest))
+}
+
+/// Converts the inline markup of a line of org text into vimwiki
+fn convert_inline(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '[' && chars.get(i + 1) == Some(&'[') {
+            if let Some((link, end)) = convert_link(&chars, i) {
+                out.push_str(&link);
+                i = end;
+                continue;
+            }
+        }
+
+        if c == '<' {
+            if let Some((date, end)) = parse_timestamp(&chars, i) {
+                out.push_str(&format!("<{}>", date));
+                i = end;
+                continue;
+            }
+        }
+
+        if "*/_+=~".contains(c) {
+            if let Some(end) = find_markup_end(&chars, i) {
+                let inner: String = chars[i + 1..end].iter().collect();
+                match c {
+                    '*' => {
+                        out.push_str(&format!("*{}*", convert_inline(&inner)))
+                    }
+                    '/' => {
+                        out.push_str(&format!("_{}_", convert_inline(&inner)))
+                    }
+                    '+' => {
+                        out.push_str(&format!("~~{}~~", convert_inline(&inner)))
+                    }
+                    '=' | '~' => out.push_str(&format!("`{}`", inner)),
+
+                    // Vimwiki has no underline, so keep only the text
+                    _ => out.push_str(&convert_inline(&inner)),
+                }
+                i = end + 1;
+                continue;
+            }
+        }
+
+        out.push(c);
+        i += 1;
+    }
+
+    out
+}
+
+/// Finds the closing marker of emphasis starting at the given position,
+/// following org's rules that markup starts after whitespace or an opening
+/// character and ends before whitespace or punctuation
+fn find_markup_end(chars: &[char], start: usize) -> Option<usize> {
+    let marker = chars[start];
+    let pre_ok = start == 0
+        || chars[start - 1].is_whitespace()
+        || "-({'\"".contains(chars[start - 1]);
+    let opens = chars.get(start + 1).is_some_and(|x| !x.is_whitespace());
+    if !pre_ok || !opens {
+        return None;
+    }
+
+    (start + 2..chars.len()).find(|i| {
+        chars[*i] == marker
+            && !chars[i - 1].is_whitespace()
+            && chars.get(i + 1).is_none_or(|x| {
+                x.is_whitespace() || "-.,:!?;'\")}[".contains(*x)
+            })
+    })
+}
+
+/// Converts a link of the form `[[target][description]]` or `[[target]]`
+/// starting at the given position, returning the link and the position
+/// following it
+fn convert_link(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let text: String = chars[start..].iter().collect();
+    let end = text.find("]]")?;
+    let inner = &text[2..end];
+    let len = text[..end + 2].chars().count();
+
+    let (target, description) = match inner.split_once("][") {
+        Some((target, description)) => (target, Some(description)),
+        None => (inner, None),
+    };
+
+    let is_image = [".png", ".jpg", ".jpeg", ".gif", ".svg"]
+        .iter()
+        .any(|ext| target.to_lowercase().ends_with(ext));
+    let target = link_target(target);
+
+    let link = match (is_image, description) {
+        (true, Some(description)) => {
+            format!("{{{{{}|{}}}}}", target, description)
+        }
+        (true, None) => format!("{{{{{}}}}}", target),
+        (false, Some(description)) => {
+            format!("[[{}|{}]]", target, convert_inline(description))
+        }
+        (false, None) => format!("[[{}]]", target),
+    };
+
+    Some((link, start + len))
+}
+
+/// Converts an org link target into a vimwiki link target, turning links to
+/// other org files into links to wiki pages and links to headlines into
+/// anchors
+fn link_target(target: &str) -> String {
+    if target.contains("://") || target.starts_with("mailto:") {
+        return target.to_string();
+    }
+
+    if let Some(headline) = target.strip_prefix('*') {
+        return format!("#{}", headline);
+    }
+
+    let path = target.strip_prefix("file:").unwrap_or(target);
+    let (path, search) = match path.split_once("::") {
+        Some((path, search)) => (path, Some(search)),
+        None => (path, None),
+    };
+
+    let path = match path.strip_suffix(".org") {
+        Some(page) => page.to_string(),
+        None if path.contains('.') => format!("file:{}", path),
+        None => path.to_string(),
+    };
+
+    match search.map(|x| x.trim_start_matches('*')) {
+        Some(anchor) => format!("{}#{}", path, anchor),
+        None => path,
+    }
+}
+
+/// Parses an active timestamp (e.g. `<2024-05-02 Thu 10:00>`) starting at
+/// the given position into its date, returning the date and the position
+/// following the timestamp
+fn parse_timestamp(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let end = (start..chars.len()).find(|i| chars[*i] == '>')?;
+    let inner: String = chars[start + 1..end].iter().collect();
+    let date = inner.split_whitespace().next()?;
+
+    let is_date = date.len() == 10
+        && date.chars().enumerate().all(|(i, c)| match i {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        });
+    if is_date {
+        Some((date.to_string(), end + 1))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Language, Page};
+
+    #[test]
+    fn org_to_vimwiki_should_convert_blocks() {
+        let org = [
+            "#+TITLE: My Notes",
+            "#+FILETAGS: :work:notes:",
+            "* TODO [#A] Write report :urgent:",
+            "  DEADLINE: <2024-05-03 Fri>",
+            "  :PROPERTIES:",
+            "  :ID: abc",
+            "  :END:",
+            "** Details",
+            "- [ ] draft <2024-05-02 Thu>",
+            "- [X] outline",
+            "  + nested",
+            "- term :: meaning",
+            "",
+            "| a | b |",
+            "|---+---|",
+            "| 1 | 2 |",
+            "",
+            "#+begin_src rust",
+            "fn main() {}",
+            "#+end_src",
+            "",
+            "# a comment",
+            "-----",
+        ]
+        .join("\n");
+
+        let vimwiki = org_to_vimwiki(&org);
+        assert_eq!(
+            vimwiki,
+            [
+                "%title My Notes",
+                ":work:notes:",
+                "= TODO Write report =",
+                ":urgent:",
+                "== Details ==",
+                "- [ ] draft <2024-05-02>",
+                "- [X] outline",
+                "  - nested",
+                "term:: meaning",
+                "",
+                "| a | b |",
+                "|---|---|",
+                "| 1 | 2 |",
+                "",
+                "{{{rust",
+                "fn main() {}",
+                "}}}",
+                "",
+                "%% a comment",
+                "----",
+            ]
+            .join("\n")
+        );
+
+        let page: Page = Language::from_vimwiki_str(&vimwiki).parse().unwrap();
+        let blocks: Vec<_> = page
+            .elements()
+            .iter()
+            .map(|x| x.as_inner().kind_name())
+            .collect();
+        assert!(blocks.contains(&"list"));
+        assert!(blocks.contains(&"table"));
+        assert!(blocks.contains(&"code_block"));
+        assert!(blocks.contains(&"definition_list"));
+    }
+
+    #[test]
+    fn org_to_vimwiki_should_convert_inline_markup_and_links() {
+        assert_eq!(
+            convert_inline("*bold* /italic/ +gone+ =verbatim= ~code~"),
+            "*bold* _italic_ ~~gone~~ `verbatim` `code`"
+        );
+        assert_eq!(convert_inline("a/b/c and 2*3*4"), "a/b/c and 2*3*4");
+        assert_eq!(
+            convert_inline("[[https://example.com][site]]"),
+            "[[https://example.com|site]]"
+        );
+        assert_eq!(
+            convert_inline("[[file:other.org::*Intro][Other]]"),
+            "[[other#Intro|Other]]"
+        );
+        assert_eq!(convert_inline("[[*Heading]]"), "[[#Heading]]");
+        assert_eq!(
+            convert_inline("[[file:img/logo.png]]"),
+            "{{file:img/logo.png}}"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::HashMap, sync::Arc};
+
+/// Represents a string interned by an [`Interner`], which is cheap to copy
+/// and compare in place of the string itself
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(transparent)]
+pub struct Symbol(u32);
+
+/// Represents a collection of distinct strings where each string is stored
+/// once no matter how many times it is interned, used to share highly
+/// repeated content such as tag and page names across an index
+///
+/// Serializes as the list of its strings in the order they were interned so
+/// that symbols remain valid once deserialized.
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    strings: Vec<Arc<str>>,
+    symbols: HashMap<Arc<str>, Symbol>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the symbol of the string, interning it if it has not been
+    /// seen before
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(s) {
+            return *symbol;
+        }
+
+        let symbol = Symbol(self.strings.len() as u32);
+        let s: Arc<str> = Arc::from(s);
+        self.strings.push(Arc::clone(&s));
+        self.symbols.insert(s, symbol);
+        symbol
+    }
+
+    /// Returns the symbol of the string if it has been interned
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.symbols.get(s).copied()
+    }
+
+    /// Returns the string of the symbol
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the symbol was not produced by this interner
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    /// Returns a shared reference to the string of the symbol, or None if the
+    /// symbol was not produced by this interner
+    pub fn resolve_shared(&self, symbol: Symbol) -> Option<Arc<str>> {
+        self.strings.get(symbol.0 as usize).cloned()
+    }
+
+    /// Returns each symbol alongside its string in the order they were
+    /// interned
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.strings
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (Symbol(i as u32), s.as_ref()))
+    }
+
+    /// Total distinct strings that have been interned
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether or not no strings have been interned
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+impl PartialEq for Interner {
+    fn eq(&self, other: &Self) -> bool {
+        self.strings == other.strings
+    }
+}
+
+impl Eq for Interner {}
+
+impl Serialize for Interner {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.strings.iter().map(AsRef::<str>::as_ref))
+    }
+}
+
+impl<'de> Deserialize<'de> for Interner {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let mut interner = Self::new();
+        for s in Vec::<String>::deserialize(deserializer)? {
+            interner.intern(&s);
+        }
+        Ok(interner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_should_share_repeated_strings() {
+        let mut interner = Interner::new();
+        let a = interner.intern("project");
+        let b = interner.intern("task");
+        assert_eq!(interner.intern("project"), a);
+        assert_ne!(a, b);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.get("task"), Some(b));
+        assert_eq!(interner.get("other"), None);
+        assert_eq!(interner.resolve(a), "project");
+        assert!(Arc::ptr_eq(
+            &interner.resolve_shared(a).unwrap(),
+            &interner.resolve_shared(a).unwrap()
+        ));
+    }
+
+    #[test]
+    fn interner_should_keep_symbols_when_serialized() {
+        let mut interner = Interner::new();
+        let a = interner.intern("one");
+        let b = interner.intern("two");
+
+        let json = serde_json::to_string(&interner).unwrap();
+        assert_eq!(json, r#"["one","two"]"#);
+
+        let interner: Interner = serde_json::from_str(&json).unwrap();
+        assert_eq!(interner.resolve(a), "one");
+        assert_eq!(interner.resolve(b), "two");
+    }
+}
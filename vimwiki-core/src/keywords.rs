@@ -0,0 +1,112 @@
+use crate::{
+    tasks::describe, BlockElement, Element, InlineBlockElement, InlineElement,
+    Keyword, Page, Region,
+};
+use serde::{Deserialize, Serialize};
+
+/// Represents a keyword (TODO, FIXME, XXX, etc) found within a page alongside
+/// the elements that contain it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeywordOccurrence {
+    /// The keyword that was found
+    pub keyword: Keyword,
+
+    /// Region of the keyword within its page
+    pub region: Region,
+
+    /// Text of the header whose section contains the keyword, which is the
+    /// header itself if the keyword is within a header
+    pub header: Option<String>,
+
+    /// Text of the first line of content within the innermost list item
+    /// containing the keyword
+    pub list_item: Option<String>,
+}
+
+/// Collects every keyword within the page, including those within
+/// decorations, list items, and tables, in document order
+pub fn find_keywords(page: &Page) -> Vec<KeywordOccurrence> {
+    let mut occurrences = Vec::new();
+    let mut header = None;
+
+    // Descriptions of the list items containing the current element
+    // alongside their depth, with the innermost last
+    let mut list_items: Vec<(usize, String)> = Vec::new();
+
+    for (depth, element) in page.descendants().with_depth() {
+        while list_items.last().is_some_and(|(d, _)| *d >= depth) {
+            list_items.pop();
+        }
+
+        match element.as_inner() {
+            Element::Block(BlockElement::Header(x)) if depth == 0 => {
+                header = Some(x.content.to_string().trim().to_string());
+            }
+            Element::InlineBlock(InlineBlockElement::ListItem(x)) => {
+                list_items.push((depth, describe(x)));
+            }
+            Element::Inline(InlineElement::Keyword(keyword)) => {
+                occurrences.push(KeywordOccurrence {
+                    keyword: keyword.clone(),
+                    region: element.region(),
+                    header: header.clone(),
+                    list_item: list_items.last().map(|(_, x)| x.clone()),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    occurrences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+
+    #[test]
+    fn find_keywords_should_include_containing_header_and_list_item() {
+        let text = indoc! {"
+            TODO before any header
+
+            = Plans FIXME =
+            - [ ] write *XXX* docs
+                - nested DONE item
+            - plain
+
+            == Later ==
+            |STARTED|cell|
+        "};
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let occurrences = find_keywords(&page);
+
+        let summary: Vec<(Keyword, Option<&str>, Option<&str>)> = occurrences
+            .iter()
+            .map(|x| {
+                (
+                    x.keyword.clone(),
+                    x.header.as_deref(),
+                    x.list_item.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Keyword::Todo, None, None),
+                (Keyword::Fixme, Some("Plans FIXME"), None),
+                (Keyword::Xxx, Some("Plans FIXME"), Some("write XXX docs")),
+                (Keyword::Done, Some("Plans FIXME"), Some("nested DONE item")),
+                (Keyword::Started, Some("Later"), None),
+            ]
+        );
+
+        for x in occurrences {
+            let start = x.region.offset();
+            let end = start + x.region.len();
+            assert_eq!(&text[start..end], x.keyword.to_string());
+        }
+    }
+}
@@ -1,10 +1,9 @@
 use crate::StrictEq;
-use derive_more::{Constructor, Index, IndexMut, IntoIterator};
+use derive_more::{Index, IndexMut, IntoIterator};
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, fmt, iter::FromIterator};
 
 #[derive(
-    Constructor,
     Clone,
     Debug,
     Eq,
@@ -23,9 +22,82 @@ pub struct Blockquote<'a> {
     #[index_mut]
     #[into_iterator(owned, ref, ref_mut)]
     pub lines: Vec<Cow<'a, str>>,
+
+    /// Represents the nesting depth of each line, where 1 is a line of the
+    /// outermost quote (e.g. `> text`), 2 is a line of a quote nested within
+    /// it (e.g. `>> text` or `> > text`), and so on; blank lines have a depth
+    /// of 0
+    #[serde(default)]
+    pub depths: Vec<usize>,
 }
 
 impl<'a> Blockquote<'a> {
+    /// Creates a blockquote whose lines all belong to the outermost quote
+    pub fn new(lines: Vec<Cow<'a, str>>) -> Self {
+        let depths = lines
+            .iter()
+            .map(|line| if line.is_empty() { 0 } else { 1 })
+            .collect();
+        Self { lines, depths }
+    }
+
+    /// Creates a blockquote whose lines have the given nesting depths
+    pub fn with_depths(lines: Vec<Cow<'a, str>>, depths: Vec<usize>) -> Self {
+        Self { lines, depths }
+    }
+
+    /// Returns the nesting depth of the line at the given index, where 1 is
+    /// the outermost quote and blank lines have a depth of 0
+    pub fn line_depth(&self, idx: usize) -> usize {
+        match self.depths.get(idx) {
+            Some(depth) => *depth,
+            None => match self.lines.get(idx) {
+                Some(line) if !line.is_empty() => 1,
+                _ => 0,
+            },
+        }
+    }
+
+    /// Returns an iterator over groups of lines along with the nesting depth
+    /// of each group, where groups are separated by blank lines or by a
+    /// change in depth
+    pub fn depth_groups(
+        &self,
+    ) -> impl Iterator<Item = (usize, &[Cow<'a, str>])> {
+        let mut groups = Vec::new();
+        let mut start = 0;
+        for idx in 0..=self.lines.len() {
+            let ends_group = idx == self.lines.len()
+                || self.lines[idx].is_empty()
+                || (idx > start
+                    && self.line_depth(idx) != self.line_depth(start));
+            if ends_group {
+                if idx > start {
+                    groups.push((
+                        self.line_depth(start),
+                        &self.lines[start..idx],
+                    ));
+                }
+                start = if idx < self.lines.len() && self.lines[idx].is_empty()
+                {
+                    idx + 1
+                } else {
+                    idx
+                };
+            }
+        }
+        groups.into_iter()
+    }
+
+    /// Returns the deepest nesting depth of any line, which is 1 unless the
+    /// blockquote contains nested quotes
+    pub fn max_depth(&self) -> usize {
+        (0..self.lines.len())
+            .map(|idx| self.line_depth(idx))
+            .max()
+            .unwrap_or_default()
+    }
+
     /// Returns total line groups available
     pub fn line_group_cnt(&self) -> usize {
         self.line_groups().count()
@@ -52,21 +124,28 @@ impl Blockquote<'_> {
     pub fn to_borrowed(&self) -> Blockquote {
         use self::Cow::*;
 
-        self.lines
-            .iter()
-            .map(|x| {
-                Cow::Borrowed(match x {
-                    Borrowed(x) => *x,
-                    Owned(x) => x.as_str(),
+        Blockquote::with_depths(
+            self.lines
+                .iter()
+                .map(|x| {
+                    Cow::Borrowed(match x {
+                        Borrowed(x) => *x,
+                        Owned(x) => x.as_str(),
+                    })
                 })
-            })
-            .collect()
+                .collect(),
+            self.depths.clone(),
+        )
     }
 
     pub fn into_owned(self) -> Blockquote<'static> {
-        self.into_iter()
-            .map(|x| Cow::from(x.into_owned()))
-            .collect()
+        Blockquote::with_depths(
+            self.lines
+                .into_iter()
+                .map(|x| Cow::from(x.into_owned()))
+                .collect(),
+            self.depths,
+        )
     }
 }
 
@@ -83,25 +162,19 @@ impl<'a> fmt::Display for Blockquote<'a> {
 
 impl<'a> FromIterator<&'a str> for Blockquote<'a> {
     fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
-        Self {
-            lines: iter.into_iter().map(Cow::Borrowed).collect(),
-        }
+        Self::new(iter.into_iter().map(Cow::Borrowed).collect())
     }
 }
 
 impl FromIterator<String> for Blockquote<'static> {
     fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
-        Self {
-            lines: iter.into_iter().map(Cow::Owned).collect(),
-        }
+        Self::new(iter.into_iter().map(Cow::Owned).collect())
     }
 }
 
 impl<'a> FromIterator<Cow<'a, str>> for Blockquote<'a> {
     fn from_iter<I: IntoIterator<Item = Cow<'a, str>>>(iter: I) -> Self {
-        Self {
-            lines: iter.into_iter().collect(),
-        }
+        Self::new(iter.into_iter().collect())
     }
 }
 
@@ -1,10 +1,9 @@
 use crate::StrictEq;
-use derive_more::{Constructor, Index, IndexMut, IntoIterator};
+use derive_more::{Index, IndexMut, IntoIterator};
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, collections::HashMap, fmt, iter::FromIterator};
 
 #[derive(
-    Constructor,
     Clone,
     Debug,
     Eq,
@@ -28,18 +27,118 @@ pub struct CodeBlock<'a> {
     #[index_mut]
     #[into_iterator(owned, ref, ref_mut)]
     pub lines: Vec<Cow<'a, str>>,
+
+    /// Represents the whitespace preceding the opening `{{{` of the block
+    #[serde(default)]
+    pub start_indent: Cow<'a, str>,
+
+    /// Represents the whitespace preceding the closing `}}}` of the block
+    #[serde(default)]
+    pub end_indent: Cow<'a, str>,
+
+    /// Represents the leading whitespace removed from each line to place it
+    /// relative to the fences, which is empty for lines indented no further
+    /// than the shallower of the two fences; when missing, nothing was removed
+    #[serde(default)]
+    pub line_indents: Vec<Cow<'a, str>>,
 }
 
 impl<'a> CodeBlock<'a> {
+    /// Constructs a code block whose fences and lines have no indentation
+    /// removed
+    pub fn new(
+        language: Option<Cow<'a, str>>,
+        metadata: HashMap<Cow<'a, str>, Cow<'a, str>>,
+        lines: Vec<Cow<'a, str>>,
+    ) -> Self {
+        Self {
+            language,
+            metadata,
+            lines,
+            start_indent: Cow::Borrowed(""),
+            end_indent: Cow::Borrowed(""),
+            line_indents: Vec::new(),
+        }
+    }
+
+    /// Sets the indentation of the fences and the leading whitespace that
+    /// was removed from each line
+    pub fn with_indents(
+        mut self,
+        start_indent: Cow<'a, str>,
+        end_indent: Cow<'a, str>,
+        line_indents: Vec<Cow<'a, str>>,
+    ) -> Self {
+        self.start_indent = start_indent;
+        self.end_indent = end_indent;
+        self.line_indents = line_indents;
+        self
+    }
+
+    /// Returns the lines exactly as written within the fences, including
+    /// the leading whitespace removed relative to the fences
+    pub fn raw_lines(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.lines.iter().enumerate().map(move |(idx, line)| {
+            match self.line_indents.get(idx).filter(|x| !x.is_empty()) {
+                Some(indent) => Cow::Owned(format!("{}{}", indent, line)),
+                None => Cow::Borrowed(line.as_ref()),
+            }
+        })
+    }
+
+    /// Returns the content exactly as written within the fences, separated
+    /// by line feed
+    pub fn raw_content(&self) -> String {
+        self.raw_lines().collect::<Vec<_>>().join("\n")
+    }
+
+    /// Returns the lines with the leading whitespace common to all non-blank
+    /// lines removed, such that the least indented line starts at the
+    /// beginning; blank lines become empty
+    pub fn dedented_lines(&self) -> impl Iterator<Item = &str> {
+        let indent_len = self
+            .lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let len =
+                    line.len() - line.trim_start_matches([' ', '\t']).len();
+                &line[..len]
+            })
+            .reduce(|common, indent| {
+                let len = common
+                    .bytes()
+                    .zip(indent.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                &common[..len]
+            })
+            .map(str::len)
+            .unwrap_or_default();
+
+        self.lines.iter().map(move |line| {
+            if line.trim().is_empty() {
+                ""
+            } else {
+                &line[indent_len..]
+            }
+        })
+    }
+
+    /// Returns the dedented lines separated by line feed
+    pub fn dedented_content(&self) -> String {
+        self.dedented_lines().collect::<Vec<_>>().join("\n")
+    }
+
     /// Constructs a code block with the provided lines using no language or metadata
     pub fn from_lines<I: IntoIterator<Item = L>, L: Into<Cow<'a, str>>>(
         iter: I,
     ) -> Self {
-        Self {
-            language: None,
-            metadata: HashMap::new(),
-            lines: iter.into_iter().map(Into::into).collect(),
-        }
+        Self::new(
+            None,
+            HashMap::new(),
+            iter.into_iter().map(Into::into).collect(),
+        )
     }
 }
 
@@ -80,6 +179,13 @@ impl CodeBlock<'_> {
                     })
                 })
                 .collect(),
+            start_indent: Cow::Borrowed(self.start_indent.as_ref()),
+            end_indent: Cow::Borrowed(self.end_indent.as_ref()),
+            line_indents: self
+                .line_indents
+                .iter()
+                .map(|x| Cow::Borrowed(x.as_ref()))
+                .collect(),
         }
     }
 
@@ -98,6 +204,13 @@ impl CodeBlock<'_> {
                 .into_iter()
                 .map(|x| Cow::from(x.into_owned()))
                 .collect(),
+            start_indent: Cow::from(self.start_indent.into_owned()),
+            end_indent: Cow::from(self.end_indent.into_owned()),
+            line_indents: self
+                .line_indents
+                .into_iter()
+                .map(|x| Cow::from(x.into_owned()))
+                .collect(),
         }
     }
 }
@@ -1,3 +1,7 @@
+// Terms are hashed by their text, while links within them only cache the
+// parsed parts of their uri, so they are safe to use as keys
+#![allow(clippy::mutable_key_type)]
+
 use crate::{
     lang::elements::{
         InlineBlockElement, InlineElement, InlineElementContainer,
@@ -1,11 +1,74 @@
 use crate::StrictEq;
-use derive_more::Constructor;
 use serde::{Deserialize, Serialize};
 
-#[derive(
-    Constructor, Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize,
-)]
-pub struct Divider;
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Divider {
+    /// Represents the total hyphens making up the divider, which is at least
+    /// four (e.g. `----`)
+    #[serde(default = "Divider::default_len")]
+    pub len: usize,
+
+    /// Represents the total blank lines immediately preceding the divider
+    #[serde(default = "Divider::default_blank_lines")]
+    pub blank_lines_before: usize,
+
+    /// Represents the total blank lines immediately following the divider
+    #[serde(default = "Divider::default_blank_lines")]
+    pub blank_lines_after: usize,
+}
+
+impl Default for Divider {
+    fn default() -> Self {
+        Self {
+            len: Self::default_len(),
+            blank_lines_before: Self::default_blank_lines(),
+            blank_lines_after: Self::default_blank_lines(),
+        }
+    }
+}
+
+impl Divider {
+    /// Minimum total hyphens needed to form a divider
+    pub const MIN_LEN: usize = 4;
+
+    pub const fn new(
+        len: usize,
+        blank_lines_before: usize,
+        blank_lines_after: usize,
+    ) -> Self {
+        Self {
+            len,
+            blank_lines_before,
+            blank_lines_after,
+        }
+    }
+
+    #[inline]
+    pub const fn default_len() -> usize {
+        Self::MIN_LEN
+    }
+
+    #[inline]
+    pub const fn default_blank_lines() -> usize {
+        1
+    }
+
+    /// Returns the normalized form of the divider used when formatting,
+    /// which has exactly four hyphens and no more than one blank line on
+    /// either side
+    pub fn normalized(&self) -> Self {
+        Self {
+            len: Self::MIN_LEN,
+            blank_lines_before: self.blank_lines_before.min(1),
+            blank_lines_after: self.blank_lines_after.min(1),
+        }
+    }
+
+    /// Returns true if the divider is already in its normalized form
+    pub fn is_normalized(&self) -> bool {
+        *self == self.normalized()
+    }
+}
 
 impl StrictEq for Divider {
     /// Same as PartialEq
@@ -0,0 +1,83 @@
+use crate::{
+    lang::elements::{
+        InlineElement, InlineElementContainer, IntoChildren, Located, Region,
+    },
+    StrictEq,
+};
+use derive_more::Constructor;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fmt};
+
+/// Represents the definition of a footnote such as `[^note]: Some text`,
+/// which is written on its own line (typically at the bottom of a page)
+#[derive(
+    Constructor, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
+pub struct FootnoteDefinition<'a> {
+    /// Represents the label of the footnote being defined
+    pub label: Cow<'a, str>,
+
+    /// Represents the content of the footnote
+    pub contents: InlineElementContainer<'a>,
+}
+
+impl FootnoteDefinition<'_> {
+    pub fn to_borrowed(&self) -> FootnoteDefinition<'_> {
+        use self::Cow::*;
+
+        let label = Cow::Borrowed(match &self.label {
+            Borrowed(x) => *x,
+            Owned(x) => x.as_str(),
+        });
+
+        FootnoteDefinition::new(label, self.contents.to_borrowed())
+    }
+
+    pub fn into_owned(self) -> FootnoteDefinition<'static> {
+        FootnoteDefinition::new(
+            Cow::from(self.label.into_owned()),
+            self.contents.into_owned(),
+        )
+    }
+}
+
+impl<'a> fmt::Display for FootnoteDefinition<'a> {
+    /// Writes out the contents of the footnote using their underlying
+    /// display impl
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.contents)
+    }
+}
+
+impl<'a> IntoChildren for FootnoteDefinition<'a> {
+    type Child = Located<InlineElement<'a>>;
+
+    fn into_children(self) -> Vec<Self::Child> {
+        self.contents.into_children()
+    }
+}
+
+impl<'a> StrictEq for FootnoteDefinition<'a> {
+    /// Performs strict_eq on label and contents
+    fn strict_eq(&self, other: &Self) -> bool {
+        self.label == other.label && self.contents.strict_eq(&other.contents)
+    }
+}
+
+/// Represents a footnote of a page, pairing its definition with every
+/// reference to it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Footnote<'a> {
+    /// Label of the footnote
+    pub label: Cow<'a, str>,
+
+    /// Number of the footnote starting at 1, ordered by the first reference
+    /// to each footnote followed by footnotes that are never referenced
+    pub number: usize,
+
+    /// Definition of the footnote, if the page has one
+    pub definition: Option<Located<FootnoteDefinition<'a>>>,
+
+    /// Regions of the references to the footnote in the order they appear
+    pub references: Vec<Region>,
+}
@@ -4,12 +4,11 @@ use crate::{
     },
     StrictEq,
 };
-use derive_more::{Constructor, Index, IndexMut, IntoIterator};
+use derive_more::{Index, IndexMut, IntoIterator};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(
-    Constructor,
     Clone,
     Debug,
     Eq,
@@ -35,12 +34,34 @@ pub struct Header<'a> {
     pub centered: bool,
 }
 
+impl<'a> Located<Header<'a>> {
+    /// Returns the inline elements of the header's content with regions
+    /// relative to the start of the header
+    pub fn local_inline_elements(&self) -> Vec<Located<&InlineElement<'a>>> {
+        self.content
+            .iter_relative_to(self.region().offset())
+            .collect()
+    }
+}
+
 impl<'a> Header<'a> {
     /// Represents the smallest a header's level can be
     pub const MIN_LEVEL: usize = 1;
 
     /// Represents teh largest a header's level can be
     pub const MAX_LEVEL: usize = 6;
+
+    pub const fn new(
+        content: InlineElementContainer<'a>,
+        level: usize,
+        centered: bool,
+    ) -> Self {
+        Self {
+            content,
+            level,
+            centered,
+        }
+    }
 }
 
 impl Header<'_> {
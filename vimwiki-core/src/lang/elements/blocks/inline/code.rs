@@ -1,11 +1,10 @@
 use crate::StrictEq;
-use derive_more::{AsRef, Constructor, Display, Into};
+use derive_more::{AsRef, Display, Into};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
 #[derive(
     AsRef,
-    Constructor,
     Clone,
     Debug,
     Display,
@@ -23,6 +22,17 @@ pub struct CodeInline<'a>(
 );
 
 impl<'a> CodeInline<'a> {
+    /// Constructs inline code from its contents
+    pub const fn new(code: Cow<'a, str>) -> Self {
+        Self(code)
+    }
+
+    /// Constructs inline code borrowing the string slice, for use within
+    /// constants and statics
+    pub const fn borrowed(code: &'a str) -> Self {
+        Self(Cow::Borrowed(code))
+    }
+
     /// Extracts a string slice containing the entire code snippet
     ///
     /// # Examples
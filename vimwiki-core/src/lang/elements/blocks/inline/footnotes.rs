@@ -0,0 +1,81 @@
+use crate::StrictEq;
+use derive_more::{AsRef, Constructor, Display};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Represents a reference to a footnote such as `[^note]`, which is
+/// displayed using the label of the footnote
+#[derive(
+    AsRef,
+    Constructor,
+    Clone,
+    Debug,
+    Display,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[as_ref(forward)]
+pub struct FootnoteReference<'a>(
+    /// Represents the label of the referenced footnote
+    Cow<'a, str>,
+);
+
+impl<'a> FootnoteReference<'a> {
+    /// Extracts a string slice containing the label of the footnote
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use std::borrow::Cow;
+    /// # use vimwiki_core::FootnoteReference;
+    /// let reference = FootnoteReference::new(Cow::Borrowed("note"));
+    /// assert_eq!(reference.as_str(), "note");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl FootnoteReference<'_> {
+    pub fn as_borrowed(&self) -> FootnoteReference<'_> {
+        use self::Cow::*;
+
+        let label = Cow::Borrowed(match &self.0 {
+            Borrowed(x) => *x,
+            Owned(x) => x.as_str(),
+        });
+
+        FootnoteReference::new(label)
+    }
+
+    pub fn into_owned(self) -> FootnoteReference<'static> {
+        let label = Cow::from(self.0.into_owned());
+
+        FootnoteReference::new(label)
+    }
+}
+
+impl<'a> From<&'a str> for FootnoteReference<'a> {
+    fn from(s: &'a str) -> Self {
+        Self::new(Cow::Borrowed(s))
+    }
+}
+
+impl From<String> for FootnoteReference<'static> {
+    fn from(s: String) -> Self {
+        Self::new(Cow::Owned(s))
+    }
+}
+
+impl<'a> StrictEq for FootnoteReference<'a> {
+    /// Same as PartialEq
+    #[inline]
+    fn strict_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+}
@@ -1,5 +1,8 @@
 use super::{Anchor, Description};
-use crate::StrictEq;
+use crate::{
+    uri::{WikiUri, WikiUriError},
+    StrictEq,
+};
 use derive_more::Constructor;
 use percent_encoding::{percent_decode, percent_encode, AsciiSet, CONTROLS};
 use serde::{Deserialize, Serialize};
@@ -11,13 +14,12 @@ use std::{
     hash::{Hash, Hasher},
     path::PathBuf,
 };
-use uriparse::{Fragment, Scheme, URIReference, URIReferenceError};
 
 /// Represents data for a link to some content, described through a combination
 /// of a URI reference and some arbitrary description
 #[derive(Constructor, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct LinkData<'a> {
-    pub uri_ref: URIReference<'a>,
+    pub uri_ref: WikiUri<'a>,
     pub description: Option<Description<'a>>,
     pub properties: Option<HashMap<Cow<'a, str>, Cow<'a, str>>>,
 }
@@ -26,7 +28,7 @@ impl LinkData<'_> {
     pub fn to_borrowed(&self) -> LinkData {
         use self::Cow::*;
 
-        let uri_ref = uri_ref_to_borrowed(&self.uri_ref);
+        let uri_ref = self.uri_ref.to_borrowed();
         let description =
             self.description.as_ref().map(Description::to_borrowed);
         let properties = self.properties.as_ref().map(|properties| {
@@ -112,13 +114,7 @@ impl<'a> LinkData<'a> {
     pub fn is_local_anchor(&self) -> bool {
         self.uri_ref.scheme().is_none()
             && self.uri_ref.authority().is_none()
-            && (self.uri_ref.path().segments().is_empty()
-                || self
-                    .uri_ref
-                    .path()
-                    .segments()
-                    .iter()
-                    .all(|s| s.as_str().is_empty()))
+            && self.uri_ref.path_segments().all(str::is_empty)
             && self.uri_ref.query().is_none()
             && self.has_anchor()
     }
@@ -131,20 +127,18 @@ impl<'a> LinkData<'a> {
         //       end with a / there is one final segment that is completely
         //       empty
         self.uri_ref
-            .path()
-            .segments()
+            .path_segments()
             .last()
-            .map_or(false, |s| s.as_str().is_empty())
+            .is_some_and(str::is_empty)
     }
 
     /// Whether or not the associated URI is local to the current system
     pub fn is_local(&self) -> bool {
         // If we have no scheme, have a file: scheme, or have our custom
         // local: scheme, then the uri's path is local
-        self.uri_ref.scheme().map_or(true, |scheme| match scheme {
-            Scheme::File => true,
-            Scheme::Unregistered(x) if x == "local" => true,
-            _ => false,
+        self.uri_ref.scheme().is_none_or(|scheme| {
+            scheme.eq_ignore_ascii_case("file")
+                || scheme.eq_ignore_ascii_case("local")
         })
     }
 
@@ -154,17 +148,99 @@ impl<'a> LinkData<'a> {
         !self.is_local()
     }
 
+    /// Converts a Windows path to a file (`C:\notes\file` or
+    /// `\\server\share\file`), optionally following a `file:` or `local:`
+    /// scheme, into the uri of that file (`file:///C:/notes/file` or
+    /// `file://server/share/file`), returning None if not a Windows path
+    pub fn normalize_windows_path(text: &str) -> Option<String> {
+        let (scheme, path) = match text.split_once(':') {
+            Some((scheme, path))
+                if scheme.eq_ignore_ascii_case("file")
+                    || scheme.eq_ignore_ascii_case("local") =>
+            {
+                (scheme, path)
+            }
+            _ => ("file", text),
+        };
+
+        let bytes = path.as_bytes();
+        if let Some(path) = path.strip_prefix(r"\\") {
+            Some(format!("{}://{}", scheme, path.replace('\\', "/")))
+        } else if bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'\\' | b'/')
+        {
+            Some(format!("{}:///{}", scheme, path.replace('\\', "/")))
+        } else {
+            None
+        }
+    }
+
+    /// Produces the root of the Windows path to the local file targeted by
+    /// the link, being either its drive (`C:\` for `file:///C:/notes`) or its
+    /// network share (`\\server\share\` for `file://server/share/doc`),
+    /// which is followed by the segments of the uri's path after the first
+    pub fn to_windows_root(&self) -> Option<PathBuf> {
+        if self.scheme().is_none()
+            || !self.is_local()
+            || !self.uri_ref.is_path_absolute()
+        {
+            return None;
+        }
+
+        const SEP: char = std::path::MAIN_SEPARATOR;
+        let first = self.uri_ref.path_segments().find(|s| !s.is_empty())?;
+        match self.uri_ref.host() {
+            Some(host) if !host.is_empty() && host != "localhost" => {
+                let share = Self::decode_uri(first);
+                Some(PathBuf::from(format!(
+                    "{0}{0}{1}{0}{2}{0}",
+                    SEP, host, share
+                )))
+            }
+            _ => {
+                let bytes = first.as_bytes();
+                let is_drive = bytes.len() == 2
+                    && bytes[0].is_ascii_alphabetic()
+                    && bytes[1] == b':';
+                if is_drive {
+                    Some(PathBuf::from(format!("{}{}", first, SEP)))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Whether or not the link targets a local file through an absolute
+    /// Windows path, either on a drive or a network share
+    #[inline]
+    pub fn is_windows_path(&self) -> bool {
+        self.to_windows_root().is_some()
+    }
+
     /// Produces a `PathBuf` from the path of the link's uri, using the
-    /// system's separator as the start if absolute
+    /// system's separator as the start if absolute or the drive or network
+    /// share if a Windows path
     pub fn to_path_buf(&self) -> PathBuf {
+        if let Some(root) = self.to_windows_root() {
+            return self
+                .uri_ref
+                .path_segments()
+                .filter(|s| !s.is_empty())
+                .skip(1)
+                .fold(root, |path, seg| path.join(seg));
+        }
+
         let mut path = PathBuf::new();
 
-        for seg in self.uri_ref.path().segments() {
-            path.push(seg.as_str());
+        for seg in self.uri_ref.path_segments() {
+            path.push(seg);
         }
 
         // If absolute, we need to make the path absolute
-        if self.uri_ref.path().is_absolute() {
+        if self.uri_ref.is_path_absolute() {
             std::path::Path::new(&std::path::Component::RootDir).join(path)
 
         // Otherwise, return the relative path as it is
@@ -176,7 +252,7 @@ impl<'a> LinkData<'a> {
     /// Returns a reference to the fragment portion of the link's uri (after
     /// the first # sign)
     pub fn fragment_str(&self) -> Option<&str> {
-        self.uri_ref.fragment().map(Fragment::as_str)
+        self.uri_ref.fragment()
     }
 
     /// Returns true if the link's uri contains an anchor (#something)
@@ -199,7 +275,7 @@ impl<'a> LinkData<'a> {
     }
 
     /// Returns reference to the scheme of the link's uri if it exists
-    pub fn scheme(&self) -> Option<&Scheme<'_>> {
+    pub fn scheme(&self) -> Option<&str> {
         self.uri_ref.scheme()
     }
 }
@@ -235,27 +311,27 @@ impl<'a> fmt::Display for LinkData<'a> {
 }
 
 impl TryFrom<String> for LinkData<'static> {
-    type Error = URIReferenceError;
+    type Error = WikiUriError;
 
-    /// Converts String into a link by parsing the String as a `uriparse::URIReference`
+    /// Converts String into a link by parsing the String as a `WikiUri`
     fn try_from(s: String) -> Result<Self, Self::Error> {
-        let uri_ref = URIReference::try_from(s.as_str())?.into_owned();
+        let uri_ref = WikiUri::try_from(s)?;
         Ok(Self::new(uri_ref, None, None))
     }
 }
 
 impl<'a> TryFrom<&'a str> for LinkData<'a> {
-    type Error = URIReferenceError;
+    type Error = WikiUriError;
 
-    /// Converts str into a link by parsing the str as a `uriparse::URIReference`
+    /// Converts str into a link by parsing the str as a `WikiUri`
     fn try_from(s: &'a str) -> Result<Self, Self::Error> {
-        let uri_ref = URIReference::try_from(s)?;
+        let uri_ref = WikiUri::parse(s)?;
         Ok(Self::new(uri_ref, None, None))
     }
 }
 
-impl<'a> From<URIReference<'a>> for LinkData<'a> {
-    fn from(uri_ref: URIReference<'a>) -> Self {
+impl<'a> From<WikiUri<'a>> for LinkData<'a> {
+    fn from(uri_ref: WikiUri<'a>) -> Self {
         Self::new(uri_ref, None, None)
     }
 }
@@ -274,21 +350,6 @@ impl<'a> StrictEq for LinkData<'a> {
     }
 }
 
-/// Helper function to borrow a `URIReference` similar to our other approaches as the
-/// functionality is not available directly in the `uriparse` crate
-fn uri_ref_to_borrowed<'a>(uri_ref: &'a URIReference<'a>) -> URIReference<'a> {
-    let scheme = uri_ref.scheme().map(|x| x.as_borrowed());
-    let authority = uri_ref.authority().map(|x| x.as_borrowed());
-    let query = uri_ref.query().map(|x| x.as_borrowed());
-    let fragment = uri_ref.fragment().map(|x| x.as_borrowed());
-
-    // NOTE: Requires an allocation of a new Vec of borrowed elements
-    let path = uri_ref.path().to_borrowed();
-
-    URIReference::from_parts(scheme, authority, path, query, fragment)
-        .expect("URI failed to borrow itself")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -304,7 +365,8 @@ mod tests {
     fn try_from_str_should_succeed_for_anchor_only() {
         let data = LinkData::try_from("#some-anchor")
             .expect("Failed to parse str as link data");
-        assert_eq!(data.uri_ref.path(), "#some-anchor");
+        assert_eq!(data.uri_ref.path(), "");
+        assert_eq!(data.uri_ref.fragment(), Some("some-anchor"));
     }
 
     #[test]
@@ -325,10 +387,7 @@ mod tests {
     fn try_from_str_should_succeed_for_network_path() {
         let data = LinkData::try_from("//network/path")
             .expect("Failed to parse str as link data");
-        assert_eq!(
-            data.uri_ref.host().map(ToString::to_string),
-            Some("network".to_string())
-        );
+        assert_eq!(data.uri_ref.host(), Some("network".to_string()));
         assert_eq!(data.uri_ref.path(), "/path");
     }
 
@@ -355,6 +414,58 @@ mod tests {
         assert_eq!(data.to_path_buf(), expected);
     }
 
+    #[test]
+    fn normalize_windows_path_should_convert_drive_and_share_paths_to_uris() {
+        assert_eq!(
+            LinkData::normalize_windows_path(r"C:\notes\file.txt").as_deref(),
+            Some("file:///C:/notes/file.txt")
+        );
+        assert_eq!(
+            LinkData::normalize_windows_path(r"local:d:/notes").as_deref(),
+            Some("local:///d:/notes")
+        );
+        assert_eq!(
+            LinkData::normalize_windows_path(r"file:\\server\share\doc.pdf")
+                .as_deref(),
+            Some("file://server/share/doc.pdf")
+        );
+        assert_eq!(LinkData::normalize_windows_path("file:///C:/notes"), None);
+        assert_eq!(LinkData::normalize_windows_path("https://a.com"), None);
+        assert_eq!(LinkData::normalize_windows_path("some/page"), None);
+    }
+
+    #[test]
+    fn to_path_buf_should_start_windows_paths_at_their_drive_or_share() {
+        const SEP: char = std::path::MAIN_SEPARATOR;
+
+        let data = LinkData::try_from("file:///C:/notes/file.txt").unwrap();
+        assert!(data.is_windows_path());
+        assert_eq!(
+            data.to_path_buf(),
+            PathBuf::from(format!("C:{}", SEP))
+                .join("notes")
+                .join("file.txt")
+        );
+
+        let data = LinkData::try_from("file://server/share/doc.pdf").unwrap();
+        assert!(data.is_windows_path());
+        assert_eq!(
+            data.to_path_buf(),
+            PathBuf::from(format!("{0}{0}server{0}share{0}", SEP))
+                .join("doc.pdf")
+        );
+
+        assert!(!LinkData::try_from("file:///notes")
+            .unwrap()
+            .is_windows_path());
+        assert!(!LinkData::try_from("//server/share")
+            .unwrap()
+            .is_windows_path());
+        assert!(!LinkData::try_from("https://a.com/C:")
+            .unwrap()
+            .is_windows_path());
+    }
+
     #[test]
     fn is_local_anchor_should_return_true_if_link_only_has_anchor() {
         let data = LinkData::try_from("#some-fragment")
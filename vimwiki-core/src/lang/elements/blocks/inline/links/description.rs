@@ -1,9 +1,11 @@
 use super::LinkData;
-use crate::StrictEq;
+use crate::{
+    uri::{WikiUri, WikiUriError},
+    StrictEq,
+};
 use derive_more::{Display, From, IsVariant, TryInto};
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, convert::TryFrom};
-use uriparse::{URIReference, URIReferenceError};
 
 /// Represents a description for a link
 #[derive(
@@ -25,7 +27,7 @@ pub enum Description<'a> {
 }
 
 impl<'a> Description<'a> {
-    pub fn into_uri_ref(self) -> Option<URIReference<'a>> {
+    pub fn into_uri_ref(self) -> Option<WikiUri<'a>> {
         match self {
             Self::TransclusionLink(x) => Some(x.uri_ref),
             _ => None,
@@ -34,7 +36,7 @@ impl<'a> Description<'a> {
 
     pub fn try_from_uri_ref_str(
         s: &'a str,
-    ) -> Result<Description<'a>, URIReferenceError> {
+    ) -> Result<Description<'a>, WikiUriError> {
         Ok(Description::TransclusionLink(Box::new(LinkData::try_from(
             s,
         )?)))
@@ -74,8 +76,8 @@ impl From<String> for Description<'static> {
     }
 }
 
-impl<'a> From<URIReference<'a>> for Description<'a> {
-    fn from(uri_ref: URIReference<'a>) -> Self {
+impl<'a> From<WikiUri<'a>> for Description<'a> {
+    fn from(uri_ref: WikiUri<'a>) -> Self {
         Self::from(LinkData::from(uri_ref))
     }
 }
@@ -1,5 +1,8 @@
-use crate::StrictEq;
-use chrono::NaiveDate;
+use crate::{
+    date::WikiDate,
+    uri::{WikiUri, WikiUriError},
+    StrictEq,
+};
 use derive_more::{Display, IsVariant};
 use percent_encoding::percent_decode;
 use serde::{Deserialize, Serialize};
@@ -8,7 +11,6 @@ use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
 };
-use uriparse::{Scheme, URIReference, URIReferenceError};
 
 mod anchor;
 pub use anchor::Anchor;
@@ -49,9 +51,11 @@ pub enum Link<'a> {
 
     /// Represents a link to a diary entry within a wiki
     #[display(fmt = "{}", "data.description.as_ref().map(ToString::to_string).unwrap_or_else(|| date.to_string())")]
-    Diary { date: NaiveDate, data: LinkData<'a> },
+    Diary { date: WikiDate, data: LinkData<'a> },
 
-    /// Represents a raw link without any frills (should only have URI)
+    /// Represents a raw link without any frills, which only has a URI unless
+    /// it was written as other text that implies the URI, such as a bare
+    /// email address, in which case the text is its description
     #[display(fmt = "{}", data)]
     Raw { data: LinkData<'a> },
 
@@ -64,7 +68,7 @@ pub enum Link<'a> {
 impl<'a> Link<'a> {
     /// Creates a new wiki link
     pub fn new_wiki_link<
-        U: Into<URIReference<'a>>,
+        U: Into<WikiUri<'a>>,
         D: Into<Option<Description<'a>>>,
     >(
         uri_ref: U,
@@ -77,18 +81,18 @@ impl<'a> Link<'a> {
 
     /// Tries to create a new wiki link
     pub fn try_new_wiki_link<
-        U: TryInto<URIReference<'a>, Error = URIReferenceError>,
+        U: TryInto<WikiUri<'a>, Error = WikiUriError>,
         D: Into<Option<Description<'a>>>,
     >(
         uri_ref: U,
         description: D,
-    ) -> Result<Self, URIReferenceError> {
+    ) -> Result<Self, WikiUriError> {
         Ok(Self::new_wiki_link(uri_ref.try_into()?, description))
     }
 
     /// Creates a new indexed interwiki link
     pub fn new_indexed_interwiki_link<
-        U: Into<URIReference<'a>>,
+        U: Into<WikiUri<'a>>,
         D: Into<Option<Description<'a>>>,
     >(
         index: u32,
@@ -103,13 +107,13 @@ impl<'a> Link<'a> {
 
     /// Tries to create a new indexed interwiki link
     pub fn try_new_indexed_interwiki_link<
-        U: TryInto<URIReference<'a>, Error = URIReferenceError>,
+        U: TryInto<WikiUri<'a>, Error = WikiUriError>,
         D: Into<Option<Description<'a>>>,
     >(
         index: u32,
         uri_ref: U,
         description: D,
-    ) -> Result<Self, URIReferenceError> {
+    ) -> Result<Self, WikiUriError> {
         Ok(Self::new_indexed_interwiki_link(
             index,
             uri_ref.try_into()?,
@@ -120,7 +124,7 @@ impl<'a> Link<'a> {
     /// Creates a new named interwiki link
     pub fn new_named_interwiki_link<
         S: Into<Cow<'a, str>>,
-        U: Into<URIReference<'a>>,
+        U: Into<WikiUri<'a>>,
         D: Into<Option<Description<'a>>>,
     >(
         name: S,
@@ -136,13 +140,13 @@ impl<'a> Link<'a> {
     /// Trys to create a new named interwiki link
     pub fn try_new_named_interwiki_link<
         S: Into<Cow<'a, str>>,
-        U: TryInto<URIReference<'a>, Error = URIReferenceError>,
+        U: TryInto<WikiUri<'a>, Error = WikiUriError>,
         D: Into<Option<Description<'a>>>,
     >(
         name: S,
         uri_ref: U,
         description: D,
-    ) -> Result<Self, URIReferenceError> {
+    ) -> Result<Self, WikiUriError> {
         Ok(Self::new_named_interwiki_link(
             name,
             uri_ref.try_into()?,
@@ -155,20 +159,18 @@ impl<'a> Link<'a> {
         D: Into<Option<Description<'a>>>,
         A: Into<Option<Anchor<'a>>>,
     >(
-        date: NaiveDate,
+        date: WikiDate,
         description: D,
         anchor: A,
     ) -> Self {
         // NOTE: Based on provided anchor, we produce a URI-compatible anchor
-        let empty_uri_ref = URIReference::try_from(
+        let empty_uri_ref = WikiUri::try_from(
             anchor
                 .into()
                 .as_ref()
-                .map_or_else(String::new, Anchor::to_encoded_uri_fragment)
-                .as_str(),
+                .map_or_else(String::new, Anchor::to_encoded_uri_fragment),
         )
-        .unwrap()
-        .into_owned();
+        .unwrap();
 
         Self::Diary {
             date,
@@ -177,24 +179,37 @@ impl<'a> Link<'a> {
     }
 
     /// Creates a new raw link
-    pub fn new_raw_link<U: Into<URIReference<'a>>>(uri_ref: U) -> Self {
+    pub fn new_raw_link<U: Into<WikiUri<'a>>>(uri_ref: U) -> Self {
         Self::Raw {
             data: LinkData::from(uri_ref.into()),
         }
     }
 
+    /// Creates a new raw link of a bare email address, linking to the
+    /// address through the `mailto` scheme while keeping the address as
+    /// written as its description
+    pub fn new_email_link<S: Into<Cow<'a, str>>>(address: S) -> Self {
+        let address = address.into();
+        let uri_ref = WikiUri::lazy(format!("mailto:{}", address));
+        Self::Raw {
+            data: LinkData::new(
+                uri_ref,
+                Some(Description::Text(address)),
+                None,
+            ),
+        }
+    }
+
     /// Tries to create a new raw link
-    pub fn try_new_raw_link<
-        U: TryInto<URIReference<'a>, Error = URIReferenceError>,
-    >(
+    pub fn try_new_raw_link<U: TryInto<WikiUri<'a>, Error = WikiUriError>>(
         uri_ref: U,
-    ) -> Result<Self, URIReferenceError> {
+    ) -> Result<Self, WikiUriError> {
         Ok(Self::new_raw_link(uri_ref.try_into()?))
     }
 
     /// Creates a new transclusion link
     pub fn new_transclusion_link<
-        U: Into<URIReference<'a>>,
+        U: Into<WikiUri<'a>>,
         D: Into<Option<Description<'a>>>,
         P: Into<Option<HashMap<Cow<'a, str>, Cow<'a, str>>>>,
     >(
@@ -213,14 +228,14 @@ impl<'a> Link<'a> {
 
     /// Tries to create a new transclusion link
     pub fn try_new_transclusion_link<
-        U: TryInto<URIReference<'a>, Error = URIReferenceError>,
+        U: TryInto<WikiUri<'a>, Error = WikiUriError>,
         D: Into<Option<Description<'a>>>,
         P: Into<Option<HashMap<Cow<'a, str>, Cow<'a, str>>>>,
     >(
         uri_ref: U,
         description: D,
         properties: P,
-    ) -> Result<Self, URIReferenceError> {
+    ) -> Result<Self, WikiUriError> {
         Ok(Self::new_transclusion_link(
             uri_ref.try_into()?,
             description,
@@ -313,13 +328,13 @@ impl<'a> Link<'a> {
     }
 
     /// Returns reference to the scheme of the link's uri if it exists
-    pub fn scheme(&self) -> Option<&Scheme<'_>> {
+    pub fn scheme(&self) -> Option<&str> {
         self.data().scheme()
     }
 
     /// Returns a copy of the date associated with the link if it exists
     /// (only occurs when is a diary link)
-    pub fn date(&self) -> Option<NaiveDate> {
+    pub fn date(&self) -> Option<WikiDate> {
         match self {
             Self::Diary { date, .. } => Some(*date),
             _ => None,
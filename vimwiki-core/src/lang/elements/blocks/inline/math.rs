@@ -1,19 +1,10 @@
 use crate::StrictEq;
-use derive_more::{AsRef, Constructor, Display};
+use derive_more::{AsRef, Display};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
 #[derive(
-    AsRef,
-    Constructor,
-    Clone,
-    Debug,
-    Display,
-    Eq,
-    PartialEq,
-    Hash,
-    Serialize,
-    Deserialize,
+    AsRef, Clone, Debug, Display, Eq, PartialEq, Hash, Serialize, Deserialize,
 )]
 #[as_ref(forward)]
 pub struct MathInline<'a>(
@@ -22,6 +13,17 @@ pub struct MathInline<'a>(
 );
 
 impl<'a> MathInline<'a> {
+    /// Constructs inline math from its contents
+    pub const fn new(formula: Cow<'a, str>) -> Self {
+        Self(formula)
+    }
+
+    /// Constructs inline math borrowing the string slice, for use within
+    /// constants and statics
+    pub const fn borrowed(formula: &'a str) -> Self {
+        Self(Cow::Borrowed(formula))
+    }
+
     /// Extracts a string slice containing the entire math snippet
     ///
     /// # Examples
@@ -2,9 +2,7 @@ use crate::{
     lang::elements::{IntoChildren, Located},
     StrictEq,
 };
-use derive_more::{
-    Constructor, Display, From, Index, IndexMut, Into, IntoIterator,
-};
+use derive_more::{Display, From, Index, IndexMut, Into, IntoIterator};
 use serde::{Deserialize, Serialize};
 use std::{fmt, iter::FromIterator};
 
@@ -12,6 +10,8 @@ mod code;
 pub use code::*;
 mod comments;
 pub use comments::*;
+mod footnotes;
+pub use footnotes::*;
 mod links;
 pub use links::*;
 mod math;
@@ -33,6 +33,7 @@ pub enum InlineElement<'a> {
     Tags(Tags<'a>),
     Code(CodeInline<'a>),
     Math(MathInline<'a>),
+    FootnoteReference(FootnoteReference<'a>),
 
     /// Comments exist as inline elements, but do not show up when displaying
     /// an inline element enum
@@ -45,11 +46,12 @@ impl InlineElement<'_> {
         match self {
             Self::Text(x) => InlineElement::from(x.as_borrowed()),
             Self::DecoratedText(x) => InlineElement::from(x.to_borrowed()),
-            Self::Keyword(x) => InlineElement::from(*x),
+            Self::Keyword(x) => InlineElement::from(x.clone()),
             Self::Link(x) => InlineElement::from(x.to_borrowed()),
             Self::Tags(x) => InlineElement::from(x.to_borrowed()),
             Self::Code(x) => InlineElement::from(x.as_borrowed()),
             Self::Math(x) => InlineElement::from(x.as_borrowed()),
+            Self::FootnoteReference(x) => InlineElement::from(x.as_borrowed()),
             Self::Comment(x) => InlineElement::from(x.to_borrowed()),
         }
     }
@@ -63,11 +65,251 @@ impl InlineElement<'_> {
             Self::Tags(x) => InlineElement::from(x.into_owned()),
             Self::Code(x) => InlineElement::from(x.into_owned()),
             Self::Math(x) => InlineElement::from(x.into_owned()),
+            Self::FootnoteReference(x) => InlineElement::from(x.into_owned()),
             Self::Comment(x) => InlineElement::from(x.into_owned()),
         }
     }
+
+    /// Returns the name of the kind of element (e.g. `code_inline`)
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "text",
+            Self::DecoratedText(_) => "decorated_text",
+            Self::Keyword(_) => "keyword",
+            Self::Link(_) => "link",
+            Self::Tags(_) => "tags",
+            Self::Code(_) => "code_inline",
+            Self::Math(_) => "math_inline",
+            Self::FootnoteReference(_) => "footnote_reference",
+            Self::Comment(_) => "comment",
+        }
+    }
 }
 
+impl<'a> InlineElement<'a> {
+    pub fn as_text(&self) -> Option<&Text<'a>> {
+        match self {
+            Self::Text(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_text(&mut self) -> Option<&mut Text<'a>> {
+        match self {
+            Self::Text(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_text(self) -> Option<Text<'a>> {
+        match self {
+            Self::Text(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_decorated_text(&self) -> Option<&DecoratedText<'a>> {
+        match self {
+            Self::DecoratedText(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_decorated_text(&mut self) -> Option<&mut DecoratedText<'a>> {
+        match self {
+            Self::DecoratedText(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_decorated_text(self) -> Option<DecoratedText<'a>> {
+        match self {
+            Self::DecoratedText(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_keyword(&self) -> Option<&Keyword> {
+        match self {
+            Self::Keyword(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_keyword(&mut self) -> Option<&mut Keyword> {
+        match self {
+            Self::Keyword(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_keyword(self) -> Option<Keyword> {
+        match self {
+            Self::Keyword(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_link(&self) -> Option<&Link<'a>> {
+        match self {
+            Self::Link(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_link(&mut self) -> Option<&mut Link<'a>> {
+        match self {
+            Self::Link(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_link(self) -> Option<Link<'a>> {
+        match self {
+            Self::Link(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_tags(&self) -> Option<&Tags<'a>> {
+        match self {
+            Self::Tags(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_tags(&mut self) -> Option<&mut Tags<'a>> {
+        match self {
+            Self::Tags(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_tags(self) -> Option<Tags<'a>> {
+        match self {
+            Self::Tags(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_code(&self) -> Option<&CodeInline<'a>> {
+        match self {
+            Self::Code(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_code(&mut self) -> Option<&mut CodeInline<'a>> {
+        match self {
+            Self::Code(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_code(self) -> Option<CodeInline<'a>> {
+        match self {
+            Self::Code(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_math(&self) -> Option<&MathInline<'a>> {
+        match self {
+            Self::Math(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_math(&mut self) -> Option<&mut MathInline<'a>> {
+        match self {
+            Self::Math(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_math(self) -> Option<MathInline<'a>> {
+        match self {
+            Self::Math(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_footnote_reference(&self) -> Option<&FootnoteReference<'a>> {
+        match self {
+            Self::FootnoteReference(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_footnote_reference(
+        &mut self,
+    ) -> Option<&mut FootnoteReference<'a>> {
+        match self {
+            Self::FootnoteReference(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_footnote_reference(self) -> Option<FootnoteReference<'a>> {
+        match self {
+            Self::FootnoteReference(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_comment(&self) -> Option<&Comment<'a>> {
+        match self {
+            Self::Comment(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_comment(&mut self) -> Option<&mut Comment<'a>> {
+        match self {
+            Self::Comment(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_comment(self) -> Option<Comment<'a>> {
+        match self {
+            Self::Comment(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+/// Represents a type of inline element that can be borrowed from an
+/// [`InlineElement`], used to extract inline elements of a specific type
+/// without matching on every variant
+pub trait InlineElementType<'a> {
+    fn from_inline_element<'b>(
+        element: &'b InlineElement<'a>,
+    ) -> Option<&'b Self>;
+}
+
+macro_rules! impl_inline_element_type {
+    ($t:ty, $f:ident) => {
+        impl<'a> InlineElementType<'a> for $t {
+            fn from_inline_element<'b>(
+                element: &'b InlineElement<'a>,
+            ) -> Option<&'b Self> {
+                element.$f()
+            }
+        }
+    };
+}
+
+impl_inline_element_type!(Text<'a>, as_text);
+impl_inline_element_type!(DecoratedText<'a>, as_decorated_text);
+impl_inline_element_type!(Keyword, as_keyword);
+impl_inline_element_type!(Link<'a>, as_link);
+impl_inline_element_type!(Tags<'a>, as_tags);
+impl_inline_element_type!(CodeInline<'a>, as_code);
+impl_inline_element_type!(MathInline<'a>, as_math);
+impl_inline_element_type!(FootnoteReference<'a>, as_footnote_reference);
+impl_inline_element_type!(Comment<'a>, as_comment);
+
 impl<'a> IntoChildren for InlineElement<'a> {
     type Child = Located<InlineElement<'a>>;
 
@@ -90,6 +332,9 @@ impl<'a> StrictEq for InlineElement<'a> {
             (Self::Tags(x), Self::Tags(y)) => x.strict_eq(y),
             (Self::Code(x), Self::Code(y)) => x.strict_eq(y),
             (Self::Math(x), Self::Math(y)) => x.strict_eq(y),
+            (Self::FootnoteReference(x), Self::FootnoteReference(y)) => {
+                x.strict_eq(y)
+            }
             (Self::Comment(x), Self::Comment(y)) => x.strict_eq(y),
             _ => false,
         }
@@ -98,7 +343,6 @@ impl<'a> StrictEq for InlineElement<'a> {
 
 /// Represents a convenience wrapper around a series of inline elements
 #[derive(
-    Constructor,
     Clone,
     Debug,
     Index,
@@ -115,11 +359,27 @@ impl<'a> StrictEq for InlineElement<'a> {
 pub struct InlineElementContainer<'a>(Vec<Located<InlineElement<'a>>>);
 
 impl<'a> InlineElementContainer<'a> {
+    pub const fn new(elements: Vec<Located<InlineElement<'a>>>) -> Self {
+        Self(elements)
+    }
+
     /// Returns iterator over references to elements
     pub fn iter(&self) -> impl Iterator<Item = &Located<InlineElement<'a>>> {
         self.into_iter()
     }
 
+    /// Returns iterator over references to elements whose regions are
+    /// relative to the given offset, such as the start of the block
+    /// containing them
+    pub fn iter_relative_to(
+        &self,
+        offset: usize,
+    ) -> impl Iterator<Item = Located<&InlineElement<'a>>> + '_ {
+        self.iter().map(move |x| {
+            Located::new(x.as_inner(), x.region().relative_to(offset))
+        })
+    }
+
     /// Returns iterator over mutable references to elements
     pub fn iter_mut(
         &mut self,
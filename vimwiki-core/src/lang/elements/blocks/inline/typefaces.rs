@@ -1,18 +1,17 @@
 use crate::{
     lang::elements::{
-        AsChildrenMutSlice, AsChildrenSlice, InlineElement, IntoChildren, Link,
-        Located,
+        AsChildrenMutSlice, AsChildrenSlice, CodeInline, FootnoteReference,
+        InlineElement, IntoChildren, Link, Located, MathInline,
     },
     StrictEq,
 };
-use derive_more::{AsRef, Constructor, Display, From, Into, IsVariant};
+use derive_more::{AsRef, Display, From, Into, IsVariant};
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, fmt};
 
 /// Represents plain text with no decorations or inline elements
 #[derive(
     AsRef,
-    Constructor,
     Clone,
     Debug,
     Display,
@@ -26,6 +25,17 @@ use std::{borrow::Cow, fmt};
 pub struct Text<'a>(Cow<'a, str>);
 
 impl<'a> Text<'a> {
+    /// Constructs text from its contents
+    pub const fn new(text: Cow<'a, str>) -> Self {
+        Self(text)
+    }
+
+    /// Constructs text borrowing the string slice, which unlike
+    /// [`From<&str>`] can be used to define constants and statics
+    pub const fn borrowed(text: &'a str) -> Self {
+        Self(Cow::Borrowed(text))
+    }
+
     /// Extracts a string slice containing the entire text snippet
     ///
     /// # Examples
@@ -82,7 +92,8 @@ impl<'a> StrictEq for Text<'a> {
     }
 }
 
-/// Represents content that can be contained within a decoration
+/// Represents content that can be contained within a decoration, including
+/// other decorations to support nesting such as `*bold _italic_ bold*`
 #[derive(
     Clone,
     Debug,
@@ -100,6 +111,9 @@ pub enum DecoratedTextContent<'a> {
     DecoratedText(DecoratedText<'a>),
     Keyword(Keyword),
     Link(Link<'a>),
+    Code(CodeInline<'a>),
+    Math(MathInline<'a>),
+    FootnoteReference(FootnoteReference<'a>),
 }
 
 impl DecoratedTextContent<'_> {
@@ -109,8 +123,13 @@ impl DecoratedTextContent<'_> {
             Self::DecoratedText(x) => {
                 DecoratedTextContent::from(x.to_borrowed())
             }
-            Self::Keyword(x) => DecoratedTextContent::from(*x),
+            Self::Keyword(x) => DecoratedTextContent::from(x.clone()),
             Self::Link(x) => DecoratedTextContent::from(x.to_borrowed()),
+            Self::Code(x) => DecoratedTextContent::from(x.as_borrowed()),
+            Self::Math(x) => DecoratedTextContent::from(x.as_borrowed()),
+            Self::FootnoteReference(x) => {
+                DecoratedTextContent::from(x.as_borrowed())
+            }
         }
     }
 
@@ -122,6 +141,11 @@ impl DecoratedTextContent<'_> {
             }
             Self::Keyword(x) => DecoratedTextContent::from(x),
             Self::Link(x) => DecoratedTextContent::from(x.into_owned()),
+            Self::Code(x) => DecoratedTextContent::from(x.into_owned()),
+            Self::Math(x) => DecoratedTextContent::from(x.into_owned()),
+            Self::FootnoteReference(x) => {
+                DecoratedTextContent::from(x.into_owned())
+            }
         }
     }
 }
@@ -132,8 +156,11 @@ impl<'a> DecoratedTextContent<'a> {
         match self {
             Self::Text(ref x) => x.as_borrowed().into(),
             Self::DecoratedText(ref x) => x.to_borrowed().into(),
-            Self::Keyword(x) => (*x).into(),
+            Self::Keyword(x) => x.clone().into(),
             Self::Link(ref x) => x.to_borrowed().into(),
+            Self::Code(ref x) => x.as_borrowed().into(),
+            Self::Math(ref x) => x.as_borrowed().into(),
+            Self::FootnoteReference(ref x) => x.as_borrowed().into(),
         }
     }
 
@@ -144,6 +171,9 @@ impl<'a> DecoratedTextContent<'a> {
             Self::DecoratedText(x) => x.into(),
             Self::Keyword(x) => x.into(),
             Self::Link(x) => x.into(),
+            Self::Code(x) => x.into(),
+            Self::Math(x) => x.into(),
+            Self::FootnoteReference(x) => x.into(),
         }
     }
 }
@@ -189,6 +219,11 @@ impl<'a> StrictEq for DecoratedTextContent<'a> {
             (Self::DecoratedText(x), Self::DecoratedText(y)) => x.strict_eq(y),
             (Self::Keyword(x), Self::Keyword(y)) => x.strict_eq(y),
             (Self::Link(x), Self::Link(y)) => x.strict_eq(y),
+            (Self::Code(x), Self::Code(y)) => x.strict_eq(y),
+            (Self::Math(x), Self::Math(y)) => x.strict_eq(y),
+            (Self::FootnoteReference(x), Self::FootnoteReference(y)) => {
+                x.strict_eq(y)
+            }
             _ => false,
         }
     }
@@ -408,7 +443,7 @@ impl<'a> StrictEq for DecoratedText<'a> {
 
 /// Represents special keywords that have unique syntax highlighting
 #[derive(
-    Copy, Clone, Debug, Display, Eq, PartialEq, Hash, Serialize, Deserialize,
+    Clone, Debug, Display, Eq, PartialEq, Hash, Serialize, Deserialize,
 )]
 pub enum Keyword {
     #[display(fmt = "TODO")]
@@ -423,6 +458,56 @@ pub enum Keyword {
     Fixed,
     #[display(fmt = "XXX")]
     Xxx,
+
+    /// Keyword recognized through the parser config (e.g. `WAITING`)
+    #[display(fmt = "{}", _0)]
+    Other(String),
+}
+
+impl Keyword {
+    /// Names of the keywords recognized when parsing unless configured
+    /// otherwise
+    pub const BUILTIN: [&'static str; 6] =
+        ["TODO", "DONE", "STARTED", "FIXME", "FIXED", "XXX"];
+
+    /// Returns the keyword with the given name, which is one of the builtin
+    /// keywords if the name matches one and otherwise [`Keyword::Other`]
+    ///
+    /// ### Examples
+    ///
+    /// ```rust
+    /// use vimwiki::Keyword;
+    ///
+    /// assert_eq!(Keyword::from_name("TODO"), Keyword::Todo);
+    /// assert_eq!(
+    ///     Keyword::from_name("WAITING"),
+    ///     Keyword::Other(String::from("WAITING")),
+    /// );
+    /// ```
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "TODO" => Self::Todo,
+            "DONE" => Self::Done,
+            "STARTED" => Self::Started,
+            "FIXME" => Self::Fixme,
+            "FIXED" => Self::Fixed,
+            "XXX" => Self::Xxx,
+            x => Self::Other(x.to_string()),
+        }
+    }
+
+    /// Returns the name of the keyword as it appears in text
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Todo => "TODO",
+            Self::Done => "DONE",
+            Self::Started => "STARTED",
+            Self::Fixme => "FIXME",
+            Self::Fixed => "FIXED",
+            Self::Xxx => "XXX",
+            Self::Other(x) => x,
+        }
+    }
 }
 
 impl StrictEq for Keyword {
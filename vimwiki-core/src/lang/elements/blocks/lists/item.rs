@@ -1,6 +1,8 @@
+use crate::date::WikiDate;
 use crate::{
     lang::elements::{
-        BlockElement, Element, IntoChildren, ListItemContents, Located,
+        BlockElement, Element, InlineElement, IntoChildren, ListItemContents,
+        Located,
     },
     StrictEq,
 };
@@ -84,6 +86,20 @@ impl<'a> StrictEq for ListItem<'a> {
     }
 }
 
+impl<'a> Located<ListItem<'a>> {
+    /// Returns the inline elements of the paragraphs directly within the
+    /// item with regions relative to the start of the item, excluding those
+    /// of nested lists
+    pub fn local_inline_elements(&self) -> Vec<Located<&InlineElement<'a>>> {
+        let offset = self.region().offset();
+        self.contents
+            .iter()
+            .filter_map(|x| x.as_inner().as_paragraph())
+            .flat_map(|x| x.inline_elements_relative_to(offset))
+            .collect()
+    }
+}
+
 impl<'a> ListItem<'a> {
     /// Indicates whether or not this list item represents an unordered item
     pub fn is_unordered(&self) -> bool {
@@ -105,6 +121,11 @@ impl<'a> ListItem<'a> {
         self.attributes.todo_status.is_some()
     }
 
+    /// Returns the date by which this list item is due, if it has one
+    pub fn due_date(&self) -> Option<WikiDate> {
+        self.attributes.due
+    }
+
     /// Returns percent complete in form of 0.0 == 0% and 1.0 == 100%. This
     /// is a calculated percentage based on the sublist items (if there are
     /// any) or the item itself.
@@ -438,6 +459,29 @@ impl OrderedListItemType {
 
         base
     }
+
+    /// Returns the value of the `type` attribute of an HTML ordered list
+    /// that numbers its items in the same style, or None for the default
+    /// numbering
+    pub fn to_html_type(self) -> Option<&'static str> {
+        match self {
+            Self::Number | Self::Pound => None,
+            Self::LowercaseAlphabet => Some("a"),
+            Self::UppercaseAlphabet => Some("A"),
+            Self::LowercaseRoman => Some("i"),
+            Self::UppercaseRoman => Some("I"),
+        }
+    }
+
+    /// Returns the alphabetic type with the same case as a roman numeral
+    /// type, or None if not a roman numeral type
+    pub fn to_alphabetic(self) -> Option<Self> {
+        match self {
+            Self::LowercaseRoman => Some(Self::LowercaseAlphabet),
+            Self::UppercaseRoman => Some(Self::UppercaseAlphabet),
+            _ => None,
+        }
+    }
 }
 
 impl Default for OrderedListItemType {
@@ -518,6 +562,10 @@ impl StrictEq for ListItemTodoStatus {
 pub struct ListItemAttributes {
     /// The TODO status for a list item, if it has been associated with TODO
     pub todo_status: Option<ListItemTodoStatus>,
+
+    /// The date by which the list item is due, if annotated with one such as
+    /// `(due: 2024-05-01)` or `<2024-05-01>`
+    pub due: Option<WikiDate>,
 }
 
 impl StrictEq for ListItemAttributes {
@@ -673,6 +721,7 @@ mod tests {
                 ))]),
                 ListItemAttributes {
                     todo_status: Some(ListItemTodoStatus::$type),
+                    due: None,
                 }
             )
         };
@@ -82,6 +82,15 @@ impl<'a> List<'a> {
             .map_or(false, |item| item.ty.is_ordered())
     }
 
+    /// Returns the type of the list's ordered items (e.g. roman numerals)
+    /// based on the first list item, or None if the list is not ordered
+    pub fn ordered_type(&self) -> Option<OrderedListItemType> {
+        match self.iter().next().map(|item| &item.ty) {
+            Some(ListItemType::Ordered(x)) => Some(*x),
+            _ => None,
+        }
+    }
+
     /// Returns whether or not the list represents an unordered list based on
     /// the first list item; if there are no items then this would return false
     pub fn is_unordered(&self) -> bool {
@@ -110,10 +119,19 @@ impl<'a> List<'a> {
     pub(crate) fn normalize(&mut self) -> &mut Self {
         // If we have items, we want to go through and normalize their types
         if let [head, tail @ ..] = &mut self.items[..] {
-            // TODO: Need to support special case where not all item types are
-            //       roman numeral but the first one is, as this can happen with
-            //       alphabetic lists if for some reason starting with i and moving
-            //       on to other letters like j and k
+            // NOTE: An alphabetic list can start with a letter that is also a
+            //       roman numeral (e.g. i followed by j and k), so the list is
+            //       alphabetic if any other item is a letter of the same case
+            if let ListItemType::Ordered(ty) = head.ty {
+                let alphabetic = ty.to_alphabetic().filter(|x| {
+                    let alphabetic = ListItemType::Ordered(*x);
+                    tail.iter().any(|item| item.ty == alphabetic)
+                });
+                if let Some(ty) = alphabetic {
+                    head.ty = ListItemType::Ordered(ty);
+                }
+            }
+
             for item in tail {
                 item.ty = head.ty.clone();
             }
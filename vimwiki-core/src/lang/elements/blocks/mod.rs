@@ -13,6 +13,8 @@ mod definitions;
 pub use definitions::*;
 mod dividers;
 pub use dividers::*;
+mod footnotes;
+pub use footnotes::*;
 mod headers;
 pub use headers::*;
 mod inline;
@@ -37,6 +39,7 @@ pub enum BlockElement<'a> {
     CodeBlock(CodeBlock<'a>),
     DefinitionList(DefinitionList<'a>),
     Divider(Divider),
+    FootnoteDefinition(FootnoteDefinition<'a>),
     Header(Header<'a>),
     List(List<'a>),
     MathBlock(MathBlock<'a>),
@@ -52,6 +55,7 @@ impl BlockElement<'_> {
             Self::CodeBlock(x) => BlockElement::from(x.to_borrowed()),
             Self::DefinitionList(x) => BlockElement::from(x.to_borrowed()),
             Self::Divider(x) => BlockElement::from(*x),
+            Self::FootnoteDefinition(x) => BlockElement::from(x.to_borrowed()),
             Self::Header(x) => BlockElement::from(x.to_borrowed()),
             Self::List(x) => BlockElement::from(x.to_borrowed()),
             Self::MathBlock(x) => BlockElement::from(x.to_borrowed()),
@@ -69,6 +73,9 @@ impl BlockElement<'_> {
                 BlockElement::DefinitionList(x.into_owned())
             }
             Self::Divider(x) => BlockElement::Divider(x),
+            Self::FootnoteDefinition(x) => {
+                BlockElement::FootnoteDefinition(x.into_owned())
+            }
             Self::Header(x) => BlockElement::Header(x.into_owned()),
             Self::List(x) => BlockElement::List(x.into_owned()),
             Self::MathBlock(x) => BlockElement::MathBlock(x.into_owned()),
@@ -77,6 +84,23 @@ impl BlockElement<'_> {
             Self::Table(x) => BlockElement::Table(x.into_owned()),
         }
     }
+
+    /// Returns the name of the kind of element (e.g. `header`)
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Self::Blockquote(_) => "blockquote",
+            Self::CodeBlock(_) => "code_block",
+            Self::DefinitionList(_) => "definition_list",
+            Self::Divider(_) => "divider",
+            Self::FootnoteDefinition(_) => "footnote_definition",
+            Self::Header(_) => "header",
+            Self::List(_) => "list",
+            Self::MathBlock(_) => "math_block",
+            Self::Paragraph(_) => "paragraph",
+            Self::Placeholder(_) => "placeholder",
+            Self::Table(_) => "table",
+        }
+    }
 }
 
 impl<'a> BlockElement<'a> {
@@ -166,6 +190,29 @@ impl<'a> BlockElement<'a> {
         }
     }
 
+    pub fn as_footnote_definition(&self) -> Option<&FootnoteDefinition<'a>> {
+        match self {
+            Self::FootnoteDefinition(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_mut_footnote_definition(
+        &mut self,
+    ) -> Option<&mut FootnoteDefinition<'a>> {
+        match self {
+            Self::FootnoteDefinition(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn into_footnote_definition(self) -> Option<FootnoteDefinition<'a>> {
+        match self {
+            Self::FootnoteDefinition(x) => Some(x),
+            _ => None,
+        }
+    }
+
     pub fn as_header(&self) -> Option<&Header<'a>> {
         match self {
             Self::Header(x) => Some(x),
@@ -293,6 +340,39 @@ impl<'a> BlockElement<'a> {
     }
 }
 
+/// Represents a type of block element that can be borrowed from a
+/// [`BlockElement`], used to extract blocks of a specific type without
+/// matching on every variant
+pub trait BlockElementType<'a> {
+    fn from_block_element<'b>(
+        element: &'b BlockElement<'a>,
+    ) -> Option<&'b Self>;
+}
+
+macro_rules! impl_block_element_type {
+    ($t:ty, $f:ident) => {
+        impl<'a> BlockElementType<'a> for $t {
+            fn from_block_element<'b>(
+                element: &'b BlockElement<'a>,
+            ) -> Option<&'b Self> {
+                element.$f()
+            }
+        }
+    };
+}
+
+impl_block_element_type!(Blockquote<'a>, as_blockquote);
+impl_block_element_type!(CodeBlock<'a>, as_code_block);
+impl_block_element_type!(DefinitionList<'a>, as_definition_list);
+impl_block_element_type!(Divider, as_divider);
+impl_block_element_type!(FootnoteDefinition<'a>, as_footnote_definition);
+impl_block_element_type!(Header<'a>, as_header);
+impl_block_element_type!(List<'a>, as_list);
+impl_block_element_type!(MathBlock<'a>, as_math_block);
+impl_block_element_type!(Paragraph<'a>, as_paragraph);
+impl_block_element_type!(Placeholder<'a>, as_placeholder);
+impl_block_element_type!(Table<'a>, as_table);
+
 impl<'a> IntoChildren for BlockElement<'a> {
     type Child = Located<Element<'a>>;
 
@@ -303,6 +383,11 @@ impl<'a> IntoChildren for BlockElement<'a> {
                 .into_iter()
                 .map(|x| x.map(Element::from))
                 .collect(),
+            Self::FootnoteDefinition(x) => x
+                .into_children()
+                .into_iter()
+                .map(|x| x.map(Element::from))
+                .collect(),
             Self::Header(x) => x
                 .into_children()
                 .into_iter()
@@ -338,6 +423,9 @@ impl<'a> StrictEq for BlockElement<'a> {
                 x.strict_eq(y)
             }
             (Self::Divider(x), Self::Divider(y)) => x.strict_eq(y),
+            (Self::FootnoteDefinition(x), Self::FootnoteDefinition(y)) => {
+                x.strict_eq(y)
+            }
             (Self::Header(x), Self::Header(y)) => x.strict_eq(y),
             (Self::List(x), Self::List(y)) => x.strict_eq(y),
             (Self::MathBlock(x), Self::MathBlock(y)) => x.strict_eq(y),
@@ -368,4 +456,5 @@ le_mapping!(CodeBlock<'a>);
 le_mapping!(MathBlock<'a>);
 le_mapping!(Blockquote<'a>);
 le_mapping!(Divider);
+le_mapping!(FootnoteDefinition<'a>);
 le_mapping!(Placeholder<'a>);
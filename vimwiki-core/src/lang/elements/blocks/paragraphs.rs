@@ -1,15 +1,14 @@
 use crate::{
     lang::elements::{
-        InlineElement, InlineElementContainer, IntoChildren, Located,
+        InlineElement, InlineElementContainer, IntoChildren, Located, Region,
     },
     StrictEq,
 };
-use derive_more::{Constructor, Index, IndexMut, IntoIterator};
+use derive_more::{Index, IndexMut, IntoIterator};
 use serde::{Deserialize, Serialize};
 use std::{fmt, iter::FromIterator};
 
 #[derive(
-    Constructor,
     Clone,
     Debug,
     Eq,
@@ -28,7 +27,19 @@ pub struct Paragraph<'a> {
     pub lines: Vec<InlineElementContainer<'a>>,
 }
 
+impl<'a> Located<Paragraph<'a>> {
+    /// Returns the inline elements of the paragraph with regions relative to
+    /// the start of the paragraph
+    pub fn local_inline_elements(&self) -> Vec<Located<&InlineElement<'a>>> {
+        self.inline_elements_relative_to(self.region().offset())
+    }
+}
+
 impl<'a> Paragraph<'a> {
+    pub const fn new(lines: Vec<InlineElementContainer<'a>>) -> Self {
+        Self { lines }
+    }
+
     /// Returns true if the paragraph only contains blank lines (or has no
     /// lines at all)
     pub fn is_blank(&self) -> bool {
@@ -45,6 +56,37 @@ impl<'a> Paragraph<'a> {
                 .any(|e| !matches!(e.as_inner(), InlineElement::Comment(_)))
         })
     }
+
+    /// Returns the inline elements of every line with regions relative to
+    /// the given offset, which is typically the start of the paragraph
+    pub fn inline_elements_relative_to(
+        &self,
+        offset: usize,
+    ) -> Vec<Located<&InlineElement<'a>>> {
+        self.lines
+            .iter()
+            .flat_map(|line| line.iter_relative_to(offset))
+            .collect()
+    }
+
+    /// Returns the regions of the line breaks separating the lines of the
+    /// paragraph, each spanning from the end of a line's content to the
+    /// start of the next line's content
+    ///
+    /// Renderers use these to decide whether a break is kept as a hard
+    /// break or joined into a soft wrap. Lines without content do not
+    /// have a known position and are skipped.
+    pub fn line_breaks(&self) -> Vec<Region> {
+        self.lines
+            .windows(2)
+            .filter_map(|lines| {
+                let last = lines[0].iter().last()?.region();
+                let end = last.offset() + last.len();
+                let start = lines[1].get(0)?.region().offset();
+                Some(Region::new(end, start.saturating_sub(end)))
+            })
+            .collect()
+    }
 }
 
 impl Paragraph<'_> {
@@ -1,5 +1,5 @@
+use crate::date::WikiDate;
 use crate::StrictEq;
-use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
@@ -8,7 +8,7 @@ pub enum Placeholder<'a> {
     Title(Cow<'a, str>),
     NoHtml,
     Template(Cow<'a, str>),
-    Date(NaiveDate),
+    Date(WikiDate),
     Other {
         name: Cow<'a, str>,
         value: Cow<'a, str>,
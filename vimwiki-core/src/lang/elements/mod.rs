@@ -1,9 +1,9 @@
 #![allow(clippy::large_enum_variant)]
 
-use crate::StrictEq;
-use derive_more::{Constructor, From, Index, IndexMut, IntoIterator};
+use crate::{tree::PageTree, StrictEq};
+use derive_more::{From, Index, IntoIterator};
 use serde::{Deserialize, Serialize};
-use std::iter::FromIterator;
+use std::{borrow::Cow, iter::FromIterator, ops::IndexMut, sync::OnceLock};
 
 mod blocks;
 pub use blocks::*;
@@ -12,16 +12,60 @@ pub use utils::{
     AsChildrenMutSlice, AsChildrenSlice, IntoChildren, Located, Region,
 };
 
+/// Represents the style of line endings used by some text
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub enum LineEnding {
+    /// Lines end with a line feed (`\n`)
+    #[default]
+    Lf,
+
+    /// Lines end with a carriage return and line feed (`\r\n`)
+    CrLf,
+}
+
+impl LineEnding {
+    /// Determines the style of line endings used by the text based on its
+    /// first line ending, defaulting to line feed if it has no lines
+    pub fn detect(text: &str) -> Self {
+        match text.find('\n') {
+            Some(idx) if text[..idx].ends_with('\r') => Self::CrLf,
+            _ => Self::Lf,
+        }
+    }
+
+    /// Returns the characters that end a line
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+
+    /// Converts every line ending within the text to this style
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        let has_other = match self {
+            Self::Lf => text.contains("\r\n"),
+            Self::CrLf => text.replace("\r\n", "").contains('\n'),
+        };
+
+        if has_other {
+            Cow::Owned(text.replace("\r\n", "\n").replace('\n', self.as_str()))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+}
+
 /// Represents a full page containing different elements
 #[derive(
-    Constructor,
     Clone,
     Debug,
     Default,
     Eq,
     PartialEq,
     Index,
-    IndexMut,
     IntoIterator,
     Serialize,
     Deserialize,
@@ -29,21 +73,249 @@ pub use utils::{
 pub struct Page<'a> {
     /// Comprised of the elements within a page
     #[index]
-    #[index_mut]
-    #[into_iterator(owned, ref, ref_mut)]
-    pub elements: Vec<Located<BlockElement<'a>>>,
+    #[into_iterator(owned, ref)]
+    elements: Vec<Located<BlockElement<'a>>>,
+
+    /// Style of line endings used by the original text of the page
+    #[serde(default)]
+    pub line_ending: LineEnding,
+
+    /// Whether or not the original text of the page began with a UTF-8 byte
+    /// order mark
+    #[serde(default)]
+    pub has_bom: bool,
+
+    /// Tree of the elements built on first request, cleared whenever the
+    /// elements are mutably borrowed
+    #[serde(skip)]
+    #[index(ignore)]
+    #[into_iterator(ignore)]
+    tree: TreeCache,
 }
 
+/// Holds the tree of a page once built, never affecting the comparison of
+/// pages
+#[derive(Clone, Debug, Default)]
+struct TreeCache(OnceLock<PageTree>);
+
+impl PartialEq for TreeCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for TreeCache {}
+
 impl<'a> Page<'a> {
+    /// UTF-8 byte order mark that can begin the text of a page
+    pub const BOM: &'static str = "\u{FEFF}";
+
+    /// Creates a page with line feed endings and no byte order mark
+    pub fn new(elements: Vec<Located<BlockElement<'a>>>) -> Self {
+        Self {
+            elements,
+            line_ending: LineEnding::default(),
+            has_bom: false,
+            tree: TreeCache::default(),
+        }
+    }
+
+    /// Sets the line ending style and byte order mark presence of the
+    /// original text of the page
+    pub fn with_format(
+        mut self,
+        line_ending: LineEnding,
+        has_bom: bool,
+    ) -> Self {
+        self.line_ending = line_ending;
+        self.has_bom = has_bom;
+        self
+    }
+
     /// Returns elements within the page
     pub fn elements(&self) -> &[Located<BlockElement<'a>>] {
         &self.elements
     }
 
+    /// Returns mutable elements within the page, clearing the tree of the
+    /// page as the elements may change
+    pub fn elements_mut(&mut self) -> &mut Vec<Located<BlockElement<'a>>> {
+        self.tree = TreeCache::default();
+        &mut self.elements
+    }
+
+    /// Returns the tree of all elements within the page, building it on the
+    /// first call and reusing it until the elements are mutably borrowed
+    ///
+    /// Ids of elements within the tree are derived without a path, so use
+    /// [`PageTree::new`] for ids that are unique across pages.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use vimwiki::{Language, Page};
+    ///
+    /// let page: Page = Language::from_vimwiki_str("= One =\n= Two =\n")
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(page.to_tree().roots.len(), 2);
+    /// ```
+    pub fn to_tree(&self) -> &PageTree {
+        self.tree.0.get_or_init(|| PageTree::new("", self))
+    }
+
+    /// Returns the top-level blocks of the given type in the order they
+    /// appear within the page
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use vimwiki::{Header, Language, Page};
+    ///
+    /// let page: Page = Language::from_vimwiki_str("= One =\ntext\n= Two =\n")
+    ///     .parse()
+    ///     .unwrap();
+    /// let headers: Vec<String> = page
+    ///     .iter_blocks_of::<Header>()
+    ///     .map(|x| x.content.to_string())
+    ///     .collect();
+    /// assert_eq!(headers, ["One", "Two"]);
+    /// ```
+    pub fn iter_blocks_of<'b, T: BlockElementType<'a> + 'b>(
+        &'b self,
+    ) -> Box<dyn Iterator<Item = Located<&'b T>> + 'b> {
+        Box::new(self.elements.iter().filter_map(|x| {
+            T::from_block_element(x.as_inner())
+                .map(|t| Located::new(t, x.region()))
+        }))
+    }
+
     /// Consumes the page and returns the elements within
     pub fn into_elements(self) -> Vec<Located<BlockElement<'a>>> {
         self.elements
     }
+
+    /// Returns the footnotes of the page, numbered in the order that they
+    /// are first referenced with footnotes that are defined but never
+    /// referenced numbered last
+    pub fn footnotes(&self) -> Vec<Footnote<'_>> {
+        let mut footnotes: Vec<Footnote> = Vec::new();
+        let mut definitions = Vec::new();
+
+        for element in self.descendants() {
+            let region = element.region();
+            match element.into_inner() {
+                Element::Block(BlockElement::FootnoteDefinition(x)) => {
+                    definitions.push(Located::new(x, region));
+                }
+                Element::Inline(InlineElement::FootnoteReference(x)) => {
+                    match footnotes.iter_mut().find(|f| f.label == x.as_str()) {
+                        Some(footnote) => footnote.references.push(region),
+                        None => footnotes.push(Footnote {
+                            label: Cow::Owned(x.as_str().to_string()),
+                            number: footnotes.len() + 1,
+                            definition: None,
+                            references: vec![region],
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for definition in definitions {
+            let label = definition.label.clone();
+            match footnotes.iter_mut().find(|f| f.label == label) {
+                Some(footnote) if footnote.definition.is_none() => {
+                    footnote.label = label;
+                    footnote.definition = Some(definition);
+                }
+                Some(_) => {}
+                None => footnotes.push(Footnote {
+                    label,
+                    number: footnotes.len() + 1,
+                    definition: Some(definition),
+                    references: Vec::new(),
+                }),
+            }
+        }
+
+        footnotes
+    }
+
+    /// Returns all inline code found anywhere within the page in the order
+    /// that it appears, such as for tooling that indexes commands mentioned
+    /// within pages
+    pub fn code_inlines(&self) -> Vec<Located<CodeInline<'_>>> {
+        self.descendants()
+            .filter_map(|x| {
+                let region = x.region();
+                match x.into_inner() {
+                    Element::Inline(InlineElement::Code(x)) => {
+                        Some(Located::new(x, region))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns every element within the page, including those nested within
+    /// other elements, where each element comes before its children and
+    /// elements are otherwise in the order that they appear within the page
+    pub fn descendants(&self) -> Descendants<'_> {
+        Descendants {
+            stack: self
+                .elements
+                .iter()
+                .rev()
+                .map(|x| {
+                    (0, x.as_ref().map(|x| Element::from(x.to_borrowed())))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Iterator over every element within a page, created by
+/// [`Page::descendants`]
+#[derive(Clone, Debug)]
+pub struct Descendants<'a> {
+    /// Elements yet to be visited alongside their depth, where the next
+    /// element to visit is last
+    stack: Vec<(usize, Located<Element<'a>>)>,
+}
+
+impl<'a> Descendants<'a> {
+    /// Pairs each element with its depth, where the top-level elements of
+    /// the page have a depth of 0 and children are one deeper than their
+    /// parent
+    pub fn with_depth(
+        mut self,
+    ) -> impl Iterator<Item = (usize, Located<Element<'a>>)> {
+        std::iter::from_fn(move || self.next_with_depth())
+    }
+
+    fn next_with_depth(&mut self) -> Option<(usize, Located<Element<'a>>)> {
+        let (depth, next) = self.stack.pop()?;
+
+        // Children are visited by region so that elements are always in the
+        // order they appear within the page
+        let mut children = next.as_inner().clone().into_children();
+        children.sort_by_key(|x| x.region().offset());
+        self.stack
+            .extend(children.into_iter().rev().map(|x| (depth + 1, x)));
+
+        Some((depth, next))
+    }
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = Located<Element<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_depth().map(|(_, x)| x)
+    }
 }
 
 impl Page<'_> {
@@ -54,7 +326,7 @@ impl Page<'_> {
             .map(|x| x.as_ref().map(BlockElement::to_borrowed))
             .collect();
 
-        Page { elements }
+        Page::new(elements).with_format(self.line_ending, self.has_bom)
     }
 
     pub fn into_owned(self) -> Page<'static> {
@@ -64,7 +336,22 @@ impl Page<'_> {
             .map(|x| x.map(BlockElement::into_owned))
             .collect();
 
-        Page { elements }
+        Page::new(elements).with_format(self.line_ending, self.has_bom)
+    }
+}
+
+impl<'a> IndexMut<usize> for Page<'a> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.elements_mut()[index]
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b mut Page<'a> {
+    type Item = &'b mut Located<BlockElement<'a>>;
+    type IntoIter = std::slice::IterMut<'b, Located<BlockElement<'a>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements_mut().iter_mut()
     }
 }
 
@@ -80,9 +367,7 @@ impl<'a> FromIterator<Located<BlockElement<'a>>> for Page<'a> {
     fn from_iter<I: IntoIterator<Item = Located<BlockElement<'a>>>>(
         iter: I,
     ) -> Self {
-        Self {
-            elements: iter.into_iter().collect(),
-        }
+        Self::new(iter.into_iter().collect())
     }
 }
 
@@ -123,6 +408,17 @@ impl Element<'_> {
             Self::InlineBlock(x) => Element::InlineBlock(x.into_owned()),
         }
     }
+
+    /// Returns the name of the kind of element (e.g. `header`), which is the
+    /// same name used for the kind of the element by stats, diffs, trees,
+    /// and ids
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Self::Block(x) => x.kind_name(),
+            Self::Inline(x) => x.kind_name(),
+            Self::InlineBlock(x) => x.kind_name(),
+        }
+    }
 }
 
 impl<'a> IntoChildren for Element<'a> {
@@ -194,6 +490,50 @@ impl<'a> Element<'a> {
             _ => None,
         }
     }
+
+    /// Returns the block element of the given type, if this is one
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use vimwiki::{BlockElement, Element, Header, Language, Located};
+    ///
+    /// let block: Located<BlockElement> =
+    ///     Language::from_vimwiki_str("= Title =").parse().unwrap();
+    /// let element = Element::from(block.into_inner());
+    /// assert!(element.as_block_of::<Header>().is_some());
+    /// ```
+    pub fn as_block_of<T: BlockElementType<'a>>(&self) -> Option<&T> {
+        self.as_block_element().and_then(T::from_block_element)
+    }
+
+    /// Returns the inline element of the given type, if this is one
+    pub fn as_inline_of<T: InlineElementType<'a>>(&self) -> Option<&T> {
+        self.as_inline_element().and_then(T::from_inline_element)
+    }
+
+    pub fn as_header(&self) -> Option<&Header<'a>> {
+        self.as_block_of()
+    }
+
+    pub fn as_list(&self) -> Option<&List<'a>> {
+        self.as_block_of()
+    }
+
+    pub fn as_table(&self) -> Option<&Table<'a>> {
+        self.as_block_of()
+    }
+
+    pub fn as_link(&self) -> Option<&Link<'a>> {
+        self.as_inline_of()
+    }
+
+    pub fn as_list_item(&self) -> Option<&ListItem<'a>> {
+        match self {
+            Self::InlineBlock(InlineBlockElement::ListItem(x)) => Some(x),
+            _ => None,
+        }
+    }
 }
 
 /// Represents a some element that is a descendant of a `BlockElement`, but
@@ -231,6 +571,15 @@ impl InlineBlockElement<'_> {
             }
         }
     }
+
+    /// Returns the name of the kind of element (e.g. `list_item`)
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Self::ListItem(_) => "list_item",
+            Self::Term(_) => "term",
+            Self::Definition(_) => "definition",
+        }
+    }
 }
 
 impl<'a> IntoChildren for InlineBlockElement<'a> {
@@ -277,6 +626,7 @@ macro_rules! element_impl_from {
 element_impl_from!(Blockquote<'a>, BlockElement);
 element_impl_from!(DefinitionList<'a>, BlockElement);
 element_impl_from!(Divider, BlockElement);
+element_impl_from!(FootnoteDefinition<'a>, BlockElement);
 element_impl_from!(Header<'a>, BlockElement);
 element_impl_from!(List<'a>, BlockElement);
 element_impl_from!(MathBlock<'a>, BlockElement);
@@ -292,5 +642,159 @@ element_impl_from!(Link<'a>, InlineElement);
 element_impl_from!(Tags<'a>, InlineElement);
 element_impl_from!(CodeInline<'a>, InlineElement);
 element_impl_from!(MathInline<'a>, InlineElement);
+element_impl_from!(FootnoteReference<'a>, InlineElement);
 
 element_impl_from!(ListItem<'a>, InlineBlockElement);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn parse_page(text: &str) -> Page<'_> {
+        Language::from_vimwiki_str(text).parse().unwrap()
+    }
+
+    #[test]
+    fn code_inlines_should_return_inline_code_throughout_page_in_order() {
+        let page = parse_page(
+            "= `header` =\n\n- item with `ls -la`\n\n*run `make`* then `make install`\n\n{{{\nnot `inline`\n}}}\n",
+        );
+        let code_inlines = page.code_inlines();
+        let code: Vec<&str> = code_inlines.iter().map(|x| x.as_str()).collect();
+        assert_eq!(code, vec!["header", "ls -la", "make", "make install"]);
+    }
+
+    #[test]
+    fn descendants_should_visit_elements_before_children_in_page_order() {
+        let page = parse_page("= *One* =\n\n- two\n  - three\n");
+        let elements: Vec<(usize, &str, usize)> = page
+            .descendants()
+            .with_depth()
+            .map(|(depth, x)| {
+                (depth, x.as_inner().kind_name(), x.region().offset())
+            })
+            .collect();
+        assert_eq!(
+            elements,
+            vec![
+                (0, "header", 0),
+                (1, "decorated_text", 2),
+                (2, "text", 3),
+                (0, "list", 11),
+                (1, "list_item", 11),
+                (2, "paragraph", 13),
+                (3, "text", 13),
+                (2, "list", 17),
+                (3, "list_item", 19),
+                (4, "paragraph", 21),
+                (5, "text", 21),
+            ]
+        );
+    }
+
+    #[test]
+    fn footnotes_should_number_by_first_reference() {
+        let page = parse_page(
+            "text[^b] and[^a] again[^b]\n\n[^a]: first\n[^b]: second\n[^c]: unused\n",
+        );
+        let footnotes = page.footnotes();
+        let footnotes: Vec<(&str, usize, bool, usize)> = footnotes
+            .iter()
+            .map(|x| {
+                (
+                    x.label.as_ref(),
+                    x.number,
+                    x.definition.is_some(),
+                    x.references.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            footnotes,
+            vec![("b", 1, true, 2), ("a", 2, true, 1), ("c", 3, true, 0)]
+        );
+    }
+
+    #[test]
+    fn typed_accessors_should_extract_elements_without_matching() {
+        let page = parse_page("= Title =\n- [[link]] TODO\n|a|\n");
+        assert_eq!(page.iter_blocks_of::<Header>().count(), 1);
+        assert_eq!(page.iter_blocks_of::<List>().count(), 1);
+        assert_eq!(page.iter_blocks_of::<Table>().count(), 1);
+        assert_eq!(page.iter_blocks_of::<Paragraph>().count(), 0);
+
+        let mut elements: Vec<Element> = Vec::new();
+        let mut stack: Vec<Element> = page
+            .elements()
+            .iter()
+            .map(|x| Element::from(x.as_inner().to_borrowed()))
+            .collect();
+        while let Some(element) = stack.pop() {
+            elements.push(element.clone());
+            stack.extend(
+                element.into_children().into_iter().map(Located::into_inner),
+            );
+        }
+
+        assert!(elements.iter().any(|x| x.as_header().is_some()));
+        assert!(elements.iter().any(|x| x.as_list().is_some()));
+        assert!(elements.iter().any(|x| x.as_table().is_some()));
+        assert!(elements.iter().any(|x| x.as_list_item().is_some()));
+
+        let inlines: Vec<&InlineElement> = elements
+            .iter()
+            .filter_map(Element::as_inline_element)
+            .collect();
+        assert!(inlines.iter().any(|x| x.as_link().is_some()));
+        assert_eq!(
+            inlines.iter().find_map(|x| x.as_keyword()),
+            Some(&Keyword::Todo)
+        );
+        assert!(elements
+            .iter()
+            .any(|x| x.as_inline_of::<Keyword>().is_some()));
+    }
+
+    #[test]
+    fn local_inline_elements_should_be_relative_to_their_block() {
+        let text = "intro\n\n= *Bold* title =\n\nsome text\nwith [[link]]\n\n- item [[other]]\n";
+        let page = parse_page(text);
+        let block_text = |region: Region| {
+            &text[region.offset()..region.offset() + region.len()]
+        };
+        let local = |elements: Vec<Located<&InlineElement>>, block: &str| {
+            elements
+                .iter()
+                .map(|x| {
+                    let r = x.region();
+                    block[r.offset()..r.offset() + r.len()].to_string()
+                })
+                .collect::<Vec<String>>()
+        };
+
+        let header = page.elements()[1]
+            .as_ref()
+            .map(|x| x.as_header().unwrap().clone());
+        assert_eq!(
+            local(header.local_inline_elements(), block_text(header.region())),
+            ["*Bold*", " title"]
+        );
+
+        let paragraph = page.elements()[2]
+            .as_ref()
+            .map(|x| x.as_paragraph().unwrap().clone());
+        let paragraph_text = block_text(paragraph.region());
+        assert_eq!(
+            local(paragraph.local_inline_elements(), paragraph_text),
+            ["some text", "with ", "[[link]]"]
+        );
+
+        let list = page.elements()[3].as_inner().as_list().unwrap();
+        let item = list.items[0].clone();
+        assert_eq!(
+            local(item.local_inline_elements(), block_text(item.region())),
+            ["item ", "[[other]]"]
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::StrictEq;
-use derive_more::{Constructor, Deref, DerefMut, Display, Index, IndexMut};
+use derive_more::{Deref, DerefMut, Display, Index, IndexMut};
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 
@@ -39,7 +39,6 @@ pub trait IntoChildren {
 /// Represents an encapsulation of a language element and its location
 /// within some string/file
 #[derive(
-    Constructor,
     Copy,
     Clone,
     Debug,
@@ -63,6 +62,11 @@ pub struct Located<T> {
 }
 
 impl<T> Located<T> {
+    /// Constructs a new element located at the region
+    pub const fn new(inner: T, region: Region) -> Self {
+        Self { inner, region }
+    }
+
     /// Maps a `Located<T>` to `Located<U>` by applying a
     /// function to the underlying element. Useful when upleveling the
     /// element (such as wrapping a Header1) while the region remains
@@ -96,7 +100,7 @@ impl<T> Located<T> {
     }
 
     /// Converts from `&Located<T>` to `&T`
-    pub fn as_inner(&self) -> &T {
+    pub const fn as_inner(&self) -> &T {
         &self.inner
     }
 
@@ -111,12 +115,12 @@ impl<T> Located<T> {
     }
 
     /// Returns depth of the inner value among other Located objects
-    pub fn depth(&self) -> u16 {
+    pub const fn depth(&self) -> u16 {
         self.region.depth()
     }
 
     /// Returns a copy of the region associated with the inner value
-    pub fn region(&self) -> Region {
+    pub const fn region(&self) -> Region {
         self.region
     }
 }
@@ -184,6 +188,33 @@ mod tests {
     use super::*;
     use std::collections::HashSet;
 
+    #[test]
+    fn new_should_be_usable_within_statics() {
+        use crate::{
+            CodeInline, Divider, Header, InlineElement, InlineElementContainer,
+            Text,
+        };
+
+        static HEADER: Located<Header> = Located::new(
+            Header::new(InlineElementContainer::new(Vec::new()), 2, false),
+            Region::new(0, 7),
+        );
+        static TEXT: Located<Text> =
+            Located::new(Text::borrowed("some text"), Region::new(3, 9));
+        static CODE: InlineElement =
+            InlineElement::Code(CodeInline::borrowed("x"));
+        const DIVIDER: Located<Divider> = Located::new(
+            Divider::new(Divider::MIN_LEN, 1, 1),
+            Region::new_at_depth(0, 4, 1),
+        );
+
+        assert_eq!(HEADER.level, 2);
+        assert_eq!(TEXT.as_str(), "some text");
+        assert_eq!(TEXT.region().offset(), 3);
+        assert_eq!(CODE.to_string(), "x");
+        assert_eq!(DIVIDER.depth(), 1);
+    }
+
     #[test]
     fn map_should_transform_inner_value_and_keep_region() {
         let le = Located::new(3, Region::new(1, 4));
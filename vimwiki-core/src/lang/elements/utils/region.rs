@@ -22,7 +22,7 @@ pub struct Region {
 impl Region {
     /// Constructs a new region with the given offset and length, containing
     /// no extra information
-    pub fn new(offset: usize, len: usize) -> Self {
+    pub const fn new(offset: usize, len: usize) -> Self {
         Self {
             offset,
             len,
@@ -31,42 +31,53 @@ impl Region {
     }
 
     /// Constructs a new region with the given offset and length and depth
-    pub fn new_at_depth(offset: usize, len: usize, depth: u16) -> Self {
+    pub const fn new_at_depth(offset: usize, len: usize, depth: u16) -> Self {
         Self { offset, len, depth }
     }
 
+    /// Constructs a copy of a region whose offset is relative to the given
+    /// offset rather than the start of the input, such as to refer to a
+    /// position within the text of a block instead of its page
+    pub const fn relative_to(&self, offset: usize) -> Self {
+        Self::new_at_depth(
+            self.offset.saturating_sub(offset),
+            self.len,
+            self.depth,
+        )
+    }
+
     /// Constructs a copy of a region with set to specified depth
-    pub fn with_depth(&self, depth: u16) -> Self {
+    pub const fn with_depth(&self, depth: u16) -> Self {
         Self::new_at_depth(self.offset, self.len, depth)
     }
 
     /// Checks if a position is contained within this region
     #[inline]
-    pub fn contains(&self, offset: usize) -> bool {
+    pub const fn contains(&self, offset: usize) -> bool {
         offset >= self.offset && offset < (self.offset + self.len)
     }
 
     /// The offset of the region relative to some span of input
     #[inline]
-    pub fn offset(&self) -> usize {
+    pub const fn offset(&self) -> usize {
         self.offset
     }
 
     /// The length of the region
     #[inline]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.len
     }
 
     /// The depth of the region
     #[inline]
-    pub fn depth(&self) -> u16 {
+    pub const fn depth(&self) -> u16 {
         self.depth
     }
 
     /// Returns true if the length of the region is zero
     #[inline]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.len == 0
     }
 }
@@ -4,7 +4,7 @@ pub mod parsers;
 
 use derive_more::Display;
 use elements::*;
-use parsers::{vimwiki, IResult, Span};
+use parsers::{vimwiki, IResult, ParserConfig, Span};
 
 /// Parse a value from a `Language`
 pub trait FromLanguage<'a>: Sized {
@@ -84,6 +84,30 @@ impl<'a> Language<'a> {
     pub fn parse<F: FromLanguage<'a>>(&self) -> Result<F, F::Error> {
         FromLanguage::from_language(*self)
     }
+
+    /// Borrows this language and parses it into another type using the
+    /// given config, such as to recognize additional keywords
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use vimwiki::{keywords::find_keywords, Keyword, Language, Page, ParserConfig};
+    ///
+    /// let config = ParserConfig::default().with_extra_keywords(["WAITING"]);
+    /// let page: Page = Language::from_vimwiki_str("WAITING on review")
+    ///     .parse_with_config(&config)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     find_keywords(&page)[0].keyword,
+    ///     Keyword::Other(String::from("WAITING")),
+    /// );
+    /// ```
+    pub fn parse_with_config<F: FromLanguage<'a>>(
+        &self,
+        config: &ParserConfig,
+    ) -> Result<F, F::Error> {
+        parsers::config_scope(config, || self.parse())
+    }
 }
 
 macro_rules! impl_from_language {
@@ -153,6 +177,16 @@ impl_from_language!(
 // Dividers
 impl_from_language!(Located<Divider>, vimwiki::blocks::dividers::divider);
 
+// Footnotes
+impl_from_language!(
+    Located<FootnoteDefinition<'a>>,
+    vimwiki::blocks::footnotes::footnote_definition
+);
+impl_from_language!(
+    Located<FootnoteReference<'a>>,
+    vimwiki::blocks::inline::footnotes::footnote_reference
+);
+
 // Headers
 impl_from_language!(Located<Header<'a>>, vimwiki::blocks::headers::header);
 
@@ -295,6 +329,20 @@ mod tests {
                 input.parse().expect("Failed to parse");
         }
 
+        #[test]
+        fn parse_to_located_footnote_definition() {
+            let input = Language::from_vimwiki_str("[^1]: some note");
+            let _result: Located<FootnoteDefinition> =
+                input.parse().expect("Failed to parse");
+        }
+
+        #[test]
+        fn parse_to_located_footnote_reference() {
+            let input = Language::from_vimwiki_str("[^1]");
+            let _result: Located<FootnoteReference> =
+                input.parse().expect("Failed to parse");
+        }
+
         #[test]
         fn parse_to_located_header() {
             let input = Language::from_vimwiki_str("= header =");
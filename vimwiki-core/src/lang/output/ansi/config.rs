@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents configuration properties for ANSI terminal output that are
+/// separate from the running state during conversion
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AnsiConfig {
+    /// Configuration settings that apply across entire pages
+    #[serde(default)]
+    pub page: AnsiPageConfig,
+
+    /// Configuration settings that apply specifically to headers
+    #[serde(default)]
+    pub header: AnsiHeaderConfig,
+
+    /// Configuration settings that apply specifically to links
+    #[serde(default)]
+    pub link: AnsiLinkConfig,
+
+    /// Configuration settings that apply specifically to tables
+    #[serde(default)]
+    pub table: AnsiTableConfig,
+}
+
+/// Represents one of the eight standard colors of a terminal
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnsiColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl AnsiColor {
+    /// Returns the SGR code that sets the foreground to this color
+    pub fn to_fg_code(self) -> u8 {
+        match self {
+            Self::Black => 30,
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::White => 37,
+        }
+    }
+}
+
+/// Represents configuration options related to entire pages
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnsiPageConfig {
+    /// Represents the string to use when indenting (e.g. four spaces or a tab)
+    #[serde(default = "AnsiPageConfig::default_indent_str")]
+    pub indent_str: String,
+
+    /// If true, will add an extra line between each block element at the page level
+    #[serde(default = "AnsiPageConfig::default_separate_block_elements")]
+    pub separate_block_elements: bool,
+
+    /// Total characters used to draw a divider
+    #[serde(default = "AnsiPageConfig::default_divider_width")]
+    pub divider_width: usize,
+}
+
+impl Default for AnsiPageConfig {
+    fn default() -> Self {
+        Self {
+            indent_str: Self::default_indent_str(),
+            separate_block_elements: Self::default_separate_block_elements(),
+            divider_width: Self::default_divider_width(),
+        }
+    }
+}
+
+impl AnsiPageConfig {
+    #[inline]
+    pub fn default_separate_block_elements() -> bool {
+        true
+    }
+
+    #[inline]
+    pub fn default_indent_str() -> String {
+        String::from("    ")
+    }
+
+    #[inline]
+    pub fn default_divider_width() -> usize {
+        40
+    }
+}
+
+/// Represents configuration options related to headers
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnsiHeaderConfig {
+    /// Colors of headers by level, where the first color is used for level 1
+    /// headers and the last color is used for all levels beyond the total
+    /// colors
+    #[serde(default = "AnsiHeaderConfig::default_colors")]
+    pub colors: Vec<AnsiColor>,
+}
+
+impl Default for AnsiHeaderConfig {
+    fn default() -> Self {
+        Self {
+            colors: Self::default_colors(),
+        }
+    }
+}
+
+impl AnsiHeaderConfig {
+    #[inline]
+    pub fn default_colors() -> Vec<AnsiColor> {
+        vec![
+            AnsiColor::Magenta,
+            AnsiColor::Blue,
+            AnsiColor::Cyan,
+            AnsiColor::Green,
+            AnsiColor::Yellow,
+            AnsiColor::Red,
+        ]
+    }
+
+    /// Returns the color of headers of the given level, if any colors exist
+    pub fn color_for_level(&self, level: usize) -> Option<AnsiColor> {
+        self.colors
+            .get(level.saturating_sub(1))
+            .or_else(|| self.colors.last())
+            .copied()
+    }
+}
+
+/// Represents configuration options related to links
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnsiLinkConfig {
+    /// If true, will write the target of a link in parentheses after its
+    /// description; links without a description always write their target
+    #[serde(default)]
+    pub include_target: bool,
+
+    /// Color of links
+    #[serde(default = "AnsiLinkConfig::default_color")]
+    pub color: AnsiColor,
+}
+
+impl Default for AnsiLinkConfig {
+    fn default() -> Self {
+        Self {
+            include_target: false,
+            color: Self::default_color(),
+        }
+    }
+}
+
+impl AnsiLinkConfig {
+    #[inline]
+    pub fn default_color() -> AnsiColor {
+        AnsiColor::Blue
+    }
+}
+
+/// Represents configuration options related to tables
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnsiTableConfig {
+    /// Represents the string placed between aligned columns
+    #[serde(default = "AnsiTableConfig::default_column_separator")]
+    pub column_separator: String,
+}
+
+impl Default for AnsiTableConfig {
+    fn default() -> Self {
+        Self {
+            column_separator: Self::default_column_separator(),
+        }
+    }
+}
+
+impl AnsiTableConfig {
+    #[inline]
+    pub fn default_column_separator() -> String {
+        String::from(" │ ")
+    }
+}
@@ -0,0 +1,47 @@
+use super::{AnsiConfig, AnsiFormatter, AnsiOutputError, Output};
+
+pub trait ToAnsiString {
+    fn to_ansi_string(
+        &self,
+        config: AnsiConfig,
+    ) -> Result<String, AnsiOutputError>;
+}
+
+impl<T: Output<AnsiFormatter>> ToAnsiString for T {
+    fn to_ansi_string(
+        &self,
+        config: AnsiConfig,
+    ) -> Result<String, AnsiOutputError> {
+        let mut formatter = AnsiFormatter::new(config);
+        self.fmt(&mut formatter)?;
+        Ok(formatter.into_content())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnsiOutputResult;
+
+    struct TestOutput<F: Fn(&mut AnsiFormatter) -> AnsiOutputResult>(F);
+    impl<F: Fn(&mut AnsiFormatter) -> AnsiOutputResult> Output<AnsiFormatter>
+        for TestOutput<F>
+    {
+        fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+            self.0(f)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_ansi_string_should_produce_a_string_representing_only_the_ansi_of_the_output(
+    ) {
+        let output = TestOutput(|f: &mut AnsiFormatter| {
+            use std::fmt::Write;
+            write!(f, "\x1b[1mI am some ansi output\x1b[22m")?;
+            Ok(())
+        });
+        let result = output.to_ansi_string(AnsiConfig::default()).unwrap();
+        assert_eq!(result, "\x1b[1mI am some ansi output\x1b[22m");
+    }
+}
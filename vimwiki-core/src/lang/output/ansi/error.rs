@@ -0,0 +1,11 @@
+use derive_more::{Display, Error, From};
+
+pub type AnsiOutputResult = Result<(), AnsiOutputError>;
+
+#[derive(Debug, From, Display, Error)]
+pub enum AnsiOutputError {
+    Fmt {
+        #[error(source)]
+        source: std::fmt::Error,
+    },
+}
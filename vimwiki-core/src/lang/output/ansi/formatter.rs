@@ -0,0 +1,174 @@
+use super::{AnsiColor, AnsiConfig, AnsiOutputError, OutputFormatter};
+use std::fmt::{self, Write};
+
+/// Represents a style that can be applied to text written to a terminal
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AnsiStyle {
+    Bold,
+    Dim,
+    Italic,
+    Underline,
+    Strikeout,
+    Fg(AnsiColor),
+}
+
+impl AnsiStyle {
+    /// Returns the SGR code that enables the style
+    pub fn to_on_code(self) -> u8 {
+        match self {
+            Self::Bold => 1,
+            Self::Dim => 2,
+            Self::Italic => 3,
+            Self::Underline => 4,
+            Self::Strikeout => 9,
+            Self::Fg(color) => color.to_fg_code(),
+        }
+    }
+
+    /// Returns the SGR code that disables the style without resetting
+    /// any other style
+    pub fn to_off_code(self) -> u8 {
+        match self {
+            Self::Bold | Self::Dim => 22,
+            Self::Italic => 23,
+            Self::Underline => 24,
+            Self::Strikeout => 29,
+            Self::Fg(_) => 39,
+        }
+    }
+}
+
+/// Represents the formatter to use to write ANSI terminal output that includes
+/// various options that can be set as well as a context for use when writing
+/// output
+#[derive(Clone, Default)]
+pub struct AnsiFormatter {
+    /// Represents the configuration associated with the formatter
+    config: AnsiConfig,
+
+    /// Contains the text written so far
+    content: String,
+
+    /// Current level of indentation to use when writing elements that care
+    /// about indentation
+    pub indent_level: usize,
+}
+
+impl OutputFormatter for AnsiFormatter {
+    type Error = AnsiOutputError;
+}
+
+impl Write for AnsiFormatter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.content.write_str(s)
+    }
+}
+
+impl AnsiFormatter {
+    pub fn new(config: AnsiConfig) -> Self {
+        Self {
+            config,
+            content: String::new(),
+            indent_level: 0,
+        }
+    }
+
+    pub fn clone_without_content(&self) -> Self {
+        Self {
+            content: String::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Writes a string representing the indentation for the current level,
+    /// only if the current content is either empty or the very last character
+    /// was a linefeed
+    pub fn write_indent(&mut self) -> Result<(), AnsiOutputError> {
+        if self.content.is_empty() || self.content.ends_with('\n') {
+            let indent_str = self.config.page.indent_str.to_string();
+
+            for _ in 0..self.indent_level {
+                write!(self, "{}", indent_str)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter where the indentation level has been incremented by 1 and
+    /// will be decremented at the end of the function call
+    pub fn and_indent<F>(&mut self, f: F) -> Result<(), AnsiOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), AnsiOutputError>,
+    {
+        self.indent_level += 1;
+        let result = f(self);
+        self.indent_level -= 1;
+        result
+    }
+
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter where everything written is wrapped in the given style
+    pub fn and_style<F>(
+        &mut self,
+        style: AnsiStyle,
+        f: F,
+    ) -> Result<(), AnsiOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), AnsiOutputError>,
+    {
+        write!(self, "\x1b[{}m", style.to_on_code())?;
+        let result = f(self);
+        write!(self, "\x1b[{}m", style.to_off_code())?;
+        result
+    }
+
+    /// Invokes the given function with a copy of this formatter without
+    /// content, returning the trimmed text that it produced
+    pub fn to_trimmed_string<F>(&self, f: F) -> Result<String, AnsiOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), AnsiOutputError>,
+    {
+        let mut formatter = self.clone_without_content();
+        formatter.indent_level = 0;
+        f(&mut formatter)?;
+        Ok(formatter.content.trim().to_string())
+    }
+
+    /// Represents the config contained within the formatter
+    #[inline]
+    pub fn config(&self) -> &AnsiConfig {
+        &self.config
+    }
+
+    pub fn get_content(&self) -> &str {
+        self.content.as_str()
+    }
+
+    pub fn into_content(self) -> String {
+        self.content
+    }
+}
+
+/// Returns the total characters of the text as displayed by a terminal,
+/// excluding escape sequences
+pub fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip everything up to and including the end of the sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+
+    width
+}
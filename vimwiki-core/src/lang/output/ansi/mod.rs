@@ -0,0 +1,590 @@
+mod config;
+pub use config::*;
+
+mod formatter;
+pub use formatter::{visible_width, AnsiFormatter, AnsiStyle};
+
+mod convert;
+pub use convert::ToAnsiString;
+
+mod error;
+pub use error::{AnsiOutputError, AnsiOutputResult};
+
+use crate::lang::{
+    elements::*,
+    output::{Output, OutputFormatter},
+};
+use std::fmt::Write;
+
+impl<'a> Output<AnsiFormatter> for Page<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        let AnsiPageConfig {
+            separate_block_elements,
+            ..
+        } = f.config().page;
+
+        for element in self.elements().iter() {
+            // NOTE: Some elements like placeholders produce no text, so we
+            //       render each element first to avoid adding separators for
+            //       elements that were skipped
+            let mut formatter = f.clone_without_content();
+            element.fmt(&mut formatter)?;
+            let text = formatter.into_content();
+
+            if !text.is_empty() {
+                if separate_block_elements && !f.get_content().is_empty() {
+                    writeln!(f)?;
+                }
+                write!(f, "{}", text)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Element<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        match self {
+            Self::Block(x) => x.fmt(f),
+            Self::Inline(x) => x.fmt(f),
+            Self::InlineBlock(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for InlineBlockElement<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        match self {
+            Self::ListItem(x) => x.fmt(f),
+            Self::Term(x) => x.fmt(f),
+            Self::Definition(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for BlockElement<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        match self {
+            Self::Blockquote(x) => x.fmt(f),
+            Self::DefinitionList(x) => x.fmt(f),
+            Self::Divider(x) => x.fmt(f),
+            Self::FootnoteDefinition(x) => x.fmt(f),
+            Self::Header(x) => x.fmt(f),
+            Self::List(x) => x.fmt(f),
+            Self::MathBlock(x) => x.fmt(f),
+            Self::Paragraph(x) => x.fmt(f),
+            Self::Placeholder(x) => x.fmt(f),
+            Self::CodeBlock(x) => x.fmt(f),
+            Self::Table(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Blockquote<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        for line in self {
+            f.write_indent()?;
+            f.and_style(AnsiStyle::Dim, |f| {
+                write!(f, "│")?;
+                Ok(())
+            })?;
+            f.and_style(AnsiStyle::Italic, |f| {
+                write!(f, " {}", line.trim())?;
+                Ok(())
+            })?;
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for DefinitionList<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        for (term, defs) in self {
+            f.write_indent()?;
+            f.and_style(AnsiStyle::Bold, |f| term.fmt(f))?;
+            writeln!(f)?;
+
+            // Definitions are placed on their own lines beneath their term
+            f.and_indent(|f| {
+                for def in defs {
+                    f.write_indent()?;
+                    def.fmt(f)?;
+                    writeln!(f)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for DefinitionListValue<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        self.as_inner().fmt(f)
+    }
+}
+
+impl Output<AnsiFormatter> for Divider {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        let width = f.config().page.divider_width;
+
+        f.write_indent()?;
+        f.and_style(AnsiStyle::Dim, |f| {
+            write!(f, "{}", "─".repeat(width))?;
+            Ok(())
+        })?;
+        writeln!(f)?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for FootnoteDefinition<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        f.write_indent()?;
+        f.and_style(AnsiStyle::Dim, |f| {
+            write!(f, "[{}]", self.label)?;
+            Ok(())
+        })?;
+        write!(f, " ")?;
+        self.contents.fmt(f)?;
+        writeln!(f)?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Header<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        let color = f.config().header.color_for_level(self.level);
+        let text = f.to_trimmed_string(|f| self.content.fmt(f))?;
+
+        f.write_indent()?;
+        f.and_style(AnsiStyle::Bold, |f| match color {
+            Some(color) => f.and_style(AnsiStyle::Fg(color), |f| {
+                write!(f, "{}", text)?;
+                Ok(())
+            }),
+            None => {
+                write!(f, "{}", text)?;
+                Ok(())
+            }
+        })?;
+        writeln!(f)?;
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for List<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        for item in self {
+            item.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for ListItem<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        // Write the prefix such as 1. or - as well as the todo status,
+        // leaving the first line of content to follow on the same line
+        f.write_indent()?;
+        write!(f, "{} ", self.to_prefix())?;
+
+        if let Some(todo_status) = self.attributes.todo_status.as_ref().copied()
+        {
+            todo_status.fmt(f)?;
+            write!(f, " ")?;
+        }
+
+        // Write content at next indentation level
+        for content in self.contents.iter() {
+            f.and_indent(|f| content.fmt(f))?;
+        }
+
+        // If the item had no content, we still need to end its line
+        if !f.get_content().ends_with('\n') {
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Output<AnsiFormatter> for ListItemTodoStatus {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        let (style, c) = match self {
+            Self::Incomplete => (AnsiStyle::Fg(AnsiColor::Yellow), ' '),
+            Self::PartiallyComplete1 => (AnsiStyle::Fg(AnsiColor::Yellow), '.'),
+            Self::PartiallyComplete2 => (AnsiStyle::Fg(AnsiColor::Yellow), 'o'),
+            Self::PartiallyComplete3 => (AnsiStyle::Fg(AnsiColor::Yellow), 'O'),
+            Self::Complete => (AnsiStyle::Fg(AnsiColor::Green), 'X'),
+            Self::Rejected => (AnsiStyle::Dim, '-'),
+        };
+
+        f.and_style(style, |f| {
+            write!(f, "[{}]", c)?;
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for MathBlock<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        for line in self {
+            f.write_indent()?;
+            f.and_style(AnsiStyle::Fg(AnsiColor::Cyan), |f| {
+                write!(f, "{}", line)?;
+                Ok(())
+            })?;
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Placeholder<'a> {
+    fn fmt(&self, _f: &mut AnsiFormatter) -> AnsiOutputResult {
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for CodeBlock<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        for line in self {
+            f.write_indent()?;
+            f.and_style(AnsiStyle::Fg(AnsiColor::Yellow), |f| {
+                write!(f, "{}", line)?;
+                Ok(())
+            })?;
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Paragraph<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        for line in self {
+            let text = f.to_trimmed_string(|f| line.fmt(f))?;
+            if !text.is_empty() {
+                f.write_indent()?;
+                writeln!(f, "{}", text)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Table<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        let AnsiTableConfig { column_separator } = f.config().table.clone();
+
+        // Rows before the divider row (if there is one) form the header
+        let header_row_cnt = self.get_divider_row_index().unwrap_or_default();
+
+        // First, we calculate the styled text of each cell, leaving out
+        // divider rows and writing spanned cells as empty
+        let mut rows: Vec<(bool, Vec<String>)> = Vec::new();
+        for (idx, row) in self.rows().enumerate() {
+            if row.is_divider_row() {
+                continue;
+            }
+
+            let is_header = idx < header_row_cnt;
+            rows.push((
+                is_header,
+                row.map(|cell| match cell.as_inner() {
+                    // NOTE: Trim before styling so that padding within the
+                    //       cell is not included in the style
+                    Cell::Content(x) if is_header => {
+                        let text = f.to_trimmed_string(|f| x.fmt(f))?;
+                        f.to_trimmed_string(|f| {
+                            f.and_style(AnsiStyle::Bold, |f| {
+                                write!(f, "{}", text)?;
+                                Ok(())
+                            })
+                        })
+                    }
+                    Cell::Content(x) => f.to_trimmed_string(|f| x.fmt(f)),
+                    _ => Ok(String::new()),
+                })
+                .collect::<Result<Vec<String>, AnsiOutputError>>()?,
+            ));
+        }
+
+        // Second, we calculate the largest cell in each column as displayed,
+        // which excludes the escape sequences used for styles
+        let mut max_column_sizes = vec![0; self.col_cnt()];
+        for (_, row) in rows.iter() {
+            for (col, text) in row.iter().enumerate() {
+                let size = visible_width(text);
+                if size > max_column_sizes[col] {
+                    max_column_sizes[col] = size;
+                }
+            }
+        }
+
+        // Third, we write each row with its cells padded to align columns
+        for (_, row) in rows {
+            f.write_indent()?;
+            for (col, text) in row.iter().enumerate() {
+                if col > 0 {
+                    f.and_style(AnsiStyle::Dim, |f| {
+                        write!(f, "{}", column_separator)?;
+                        Ok(())
+                    })?;
+                }
+                write!(f, "{}", text)?;
+
+                // NOTE: Skip padding the last column to avoid trailing spaces
+                if col + 1 < row.len() {
+                    write!(
+                        f,
+                        "{}",
+                        " ".repeat(max_column_sizes[col] - visible_width(text))
+                    )?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for InlineElementContainer<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        for element in self {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for InlineElement<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Tags(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::FootnoteReference(x) => x.fmt(f),
+            Self::Comment(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Text<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        write!(f, "{}", self.as_str())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for DecoratedText<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        let write_contents = |f: &mut AnsiFormatter| {
+            for content in self.as_contents_slice() {
+                content.fmt(f)?;
+            }
+            Ok(())
+        };
+
+        // NOTE: Terminals have no way to raise or lower text, so superscript
+        //       and subscript are written without a style
+        match self {
+            Self::Bold(_) => f.and_style(AnsiStyle::Bold, write_contents),
+            Self::Italic(_) => f.and_style(AnsiStyle::Italic, write_contents),
+            Self::Strikeout(_) => {
+                f.and_style(AnsiStyle::Strikeout, write_contents)
+            }
+            Self::Superscript(_) | Self::Subscript(_) => write_contents(f),
+        }
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for DecoratedTextContent<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::FootnoteReference(x) => x.fmt(f),
+        }
+    }
+}
+
+impl Output<AnsiFormatter> for Keyword {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        f.and_style(AnsiStyle::Bold, |f| {
+            f.and_style(AnsiStyle::Fg(AnsiColor::Red), |f| {
+                write!(f, "{}", self)?;
+                Ok(())
+            })
+        })
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Link<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        let AnsiLinkConfig {
+            include_target,
+            color,
+        } = f.config().link;
+
+        let target = match self {
+            Self::Diary { date, data } => format!(
+                "{}{}",
+                date,
+                data.to_anchor().map(|x| x.to_string()).unwrap_or_default()
+            ),
+            Self::Raw { data } => data.uri_ref.to_string(),
+            _ => self.data().to_decoded_uri_string(),
+        };
+
+        let description = match self.description() {
+            Some(Description::Text(x)) => Some(x.to_string()),
+            Some(Description::TransclusionLink(x)) => {
+                Some(x.to_decoded_uri_string())
+            }
+            None => None,
+        };
+
+        f.and_style(AnsiStyle::Underline, |f| {
+            f.and_style(AnsiStyle::Fg(color), |f| {
+                write!(
+                    f,
+                    "{}",
+                    description.as_deref().unwrap_or(target.as_str())
+                )?;
+                Ok(())
+            })
+        })?;
+
+        if include_target && description.is_some() {
+            write!(f, " ")?;
+            f.and_style(AnsiStyle::Dim, |f| {
+                write!(f, "({})", target)?;
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Tags<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        f.and_style(AnsiStyle::Fg(AnsiColor::Green), |f| {
+            write!(f, ":")?;
+            for tag in self {
+                write!(f, "{}:", tag)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for CodeInline<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        f.and_style(AnsiStyle::Fg(AnsiColor::Yellow), |f| {
+            write!(f, "{}", self)?;
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for MathInline<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        f.and_style(AnsiStyle::Fg(AnsiColor::Cyan), |f| {
+            write!(f, "{}", self)?;
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for FootnoteReference<'a> {
+    fn fmt(&self, f: &mut AnsiFormatter) -> AnsiOutputResult {
+        f.and_style(AnsiStyle::Dim, |f| {
+            write!(f, "[{}]", self)?;
+            Ok(())
+        })
+    }
+}
+
+impl<'a> Output<AnsiFormatter> for Comment<'a> {
+    fn fmt(&self, _f: &mut AnsiFormatter) -> AnsiOutputResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+    use similar_asserts::assert_str_eq;
+
+    fn to_ansi_string(text: &str) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        page.to_ansi_string(AnsiConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn page_should_style_headers_and_decorations() {
+        let text = to_ansi_string(indoc! {"
+            == Some header ==
+            A *bold* and _italic_ [[link|description]] %% hidden
+        "});
+
+        assert_str_eq!(
+            text,
+            concat!(
+                "\x1b[1m\x1b[34mSome header\x1b[39m\x1b[22m\n",
+                "\n",
+                "A \x1b[1mbold\x1b[22m and \x1b[3mitalic\x1b[23m ",
+                "\x1b[4m\x1b[34mdescription\x1b[39m\x1b[24m\n",
+            )
+        );
+    }
+
+    #[test]
+    fn page_should_align_tables_by_visible_width() {
+        let text = to_ansi_string(indoc! {"
+            | Name | Value |
+            |------|-------|
+            | *a*  | b     |
+        "});
+
+        assert_str_eq!(
+            text,
+            concat!(
+                "\x1b[1mName\x1b[22m\x1b[2m │ \x1b[22m\x1b[1mValue\x1b[22m\n",
+                "\x1b[1ma\x1b[22m   \x1b[2m │ \x1b[22mb\n",
+            )
+        );
+    }
+
+    #[test]
+    fn visible_width_should_exclude_escape_sequences() {
+        assert_eq!(visible_width("\x1b[1m\x1b[34mabc\x1b[39m\x1b[22m"), 3);
+        assert_eq!(visible_width("│ é"), 3);
+    }
+}
@@ -1,11 +1,20 @@
-use super::utils::{deserialize_absolute_path, make_path_relative};
+use super::utils::{
+    deserialize_absolute_path, encode_url_path, make_path_relative,
+};
+use crate::{
+    resolve::LinkSpaces,
+    schemes::{self, SchemeTemplate},
+    uri::WikiUri,
+    Keyword,
+};
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 use serde::{Deserialize, Serialize};
 use std::{
-    convert::TryFrom,
+    borrow::Cow,
+    collections::BTreeMap,
     path::{Component, Path, PathBuf},
+    sync::Arc,
 };
-use uriparse::URI;
 
 /// Represents some data with an associated index
 #[derive(Copy, Clone, Debug, PartialEq, Eq, AsRef, AsMut, Deref, DerefMut)]
@@ -69,9 +78,17 @@ pub struct HtmlConfig {
     #[serde(default)]
     pub comment: HtmlCommentConfig,
 
+    /// Configuration settings that apply specifically to keywords
+    #[serde(default)]
+    pub keyword: HtmlKeywordConfig,
+
     /// Configuration settings that apply specifically to templates
     #[serde(default)]
     pub template: HtmlTemplateConfig,
+
+    /// Configuration settings that apply specifically to site navigation
+    #[serde(default)]
+    pub navigation: HtmlNavigationConfig,
 }
 
 impl HtmlConfig {
@@ -270,6 +287,16 @@ pub struct HtmlRuntimeConfig {
 
     /// Path to the page's file that is being processed
     pub page: PathBuf,
+
+    /// If true, block elements and list items are annotated with the
+    /// regions they were parsed from via `data-region` attributes, which are
+    /// also collected into the formatter's source map
+    pub source_map: bool,
+
+    /// Paths to the files of every page of the wiki being processed, used to
+    /// generate navigation between pages and shared by the configs of every
+    /// page so that cloning a config stays cheap
+    pub pages: Arc<[PathBuf]>,
 }
 
 impl HtmlRuntimeConfig {
@@ -296,6 +323,10 @@ impl Default for HtmlRuntimeConfig {
             //       being included, otherwise trying to map the runtime
             //       page (default) to a tmp wiki (default) will fail
             page: HtmlWikiConfig::default_path().join("index.wiki"),
+
+            source_map: false,
+
+            pages: Arc::default(),
         }
     }
 }
@@ -331,9 +362,23 @@ pub struct HtmlWikiConfig {
     #[serde(default = "HtmlWikiConfig::default_ext")]
     pub ext: String,
 
+    /// Name of the page (without extension) that a link to a directory
+    /// within the wiki targets
+    #[serde(default = "HtmlWikiConfig::default_index")]
+    pub index: String,
+
     /// Path for diary directory relative to this wiki's path
     #[serde(default = "HtmlWikiConfig::default_diary_rel_path")]
     pub diary_rel_path: PathBuf,
+
+    /// How spaces within links to pages appear within the names of the
+    /// wiki's files
+    #[serde(default)]
+    pub link_spaces: LinkSpaces,
+
+    /// How the pages of this wiki map to output paths and urls
+    #[serde(default)]
+    pub output: HtmlOutputConfig,
 }
 
 impl Default for HtmlWikiConfig {
@@ -344,7 +389,10 @@ impl Default for HtmlWikiConfig {
             name: Self::default_name(),
             css_name: Self::default_css_name(),
             ext: Self::default_ext(),
+            index: Self::default_index(),
             diary_rel_path: Self::default_diary_rel_path(),
+            link_spaces: LinkSpaces::default(),
+            output: HtmlOutputConfig::default(),
         }
     }
 }
@@ -356,15 +404,17 @@ impl HtmlWikiConfig {
         self.path.as_path()
     }
 
-    /// Given an input path, will return a relative path from the input path
-    /// to get back to the root of the wiki in the form of `../..`, or None
-    /// if the input path does not fall within the wiki
+    /// Given an input path, will return a relative path from the output of
+    /// the page at the input path to get back to the root of the wiki's
+    /// output in the form of `../..`, or None if the input path does not fall
+    /// within the wiki
     pub fn path_to_root<P: AsRef<Path>>(&self, path: P) -> Option<PathBuf> {
-        self.path_within(path.as_ref()).and_then(|path| {
-            // Determine how many hops back we need to make, with a path
-            // like path/to/file.wiki yielding 2 to get back to root
+        self.page_name(path.as_ref()).and_then(|name| {
+            // Determine how many hops back we need to make, with an output
+            // path like path/to/file.html yielding 2 to get back to root
             // of the wiki
-            let hops_back = path.components().count();
+            let hops_back =
+                self.page_output_rel_path(&name).components().count();
 
             // Our actual total hops is 1 less than what is calculated as the
             // above includes the file itself. We only want to process if we
@@ -427,6 +477,122 @@ impl HtmlWikiConfig {
         self.path_html.join(input).with_extension(ext)
     }
 
+    /// Produce an absolute path to the output destination of the page at the
+    /// given input path, which is figured out the same way as
+    /// [`Self::make_output_path`] before being mapped using the output config
+    /// of the wiki
+    pub fn make_page_output_path(&self, input: &Path) -> PathBuf {
+        let input =
+            if !input.has_root() || !input.starts_with(self.path.as_path()) {
+                self.path.join(make_path_relative(input))
+            } else {
+                input.to_path_buf()
+            };
+
+        match self.page_name(&input) {
+            Some(name) => self.path_html.join(self.page_output_rel_path(&name)),
+            None => self.path_html.clone(),
+        }
+    }
+
+    /// Returns the name of the page at the given path, which is its path
+    /// relative to the wiki's root without an extension and using `/` as the
+    /// separator (e.g. `projects/todo`), or None if the path does not fall
+    /// within the wiki
+    pub fn page_name(&self, path: &Path) -> Option<String> {
+        let path = self.path_within(path)?.with_extension("");
+        Some(
+            path.components()
+                .map(|x| x.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        )
+    }
+
+    /// Returns the path of the output of the page with the given name
+    /// relative to the output directory of the wiki
+    pub fn page_output_rel_path(&self, name: &str) -> PathBuf {
+        let (stem, is_dir) = self.page_output_stem(name);
+        let file = if is_dir {
+            format!("{}index", stem)
+        } else {
+            stem
+        };
+
+        let mut path: PathBuf = file.split('/').collect();
+        if !self.output.ext.is_empty() {
+            path.set_extension(&self.output.ext);
+        }
+        path
+    }
+
+    /// Returns the url of the page with the given name relative to the root
+    /// of the wiki's output (e.g. `projects/todo.html`), which is empty for
+    /// the index page of the wiki when using pretty urls
+    ///
+    /// ### Examples
+    ///
+    /// ```rust
+    /// use vimwiki::{HtmlOutputConfig, HtmlWikiConfig};
+    ///
+    /// let wiki = HtmlWikiConfig::default();
+    /// assert_eq!(wiki.page_url("projects/todo"), "projects/todo.html");
+    ///
+    /// let wiki = HtmlWikiConfig {
+    ///     output: HtmlOutputConfig {
+    ///         pretty_urls: true,
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(wiki.page_url("projects/todo"), "projects/todo/");
+    /// assert_eq!(wiki.page_url("projects/index"), "projects/");
+    /// ```
+    pub fn page_url(&self, name: &str) -> String {
+        let (stem, is_dir) = self.page_output_stem(name);
+        let url = if is_dir || self.output.ext.is_empty() {
+            stem
+        } else {
+            format!("{}.{}", stem, self.output.ext)
+        };
+        encode_url_path(&url, self.output.link_spaces)
+    }
+
+    /// Returns the mapped name of the page's output, and whether the page is
+    /// written as the index file of a directory, in which case the name is
+    /// the directory (ending in `/` unless it is the root)
+    fn page_output_stem(&self, name: &str) -> (String, bool) {
+        let output = &self.output;
+        let parts: Vec<Cow<'_, str>> = name
+            .split('/')
+            .filter(|x| !x.is_empty())
+            .map(|x| output.link_spaces.to_name(x))
+            .collect();
+
+        if output.flatten {
+            let stem = parts.join(&output.flatten_separator);
+            if !output.pretty_urls {
+                (stem, false)
+            } else if stem == self.index {
+                (String::new(), true)
+            } else {
+                (format!("{}/", stem), true)
+            }
+        } else if !output.pretty_urls {
+            (parts.join("/"), false)
+        } else {
+            let parts = match parts.split_last() {
+                Some((last, rest)) if *last == self.index => rest,
+                _ => &parts,
+            };
+            let mut stem = parts.join("/");
+            if !stem.is_empty() {
+                stem.push('/');
+            }
+            (stem, true)
+        }
+    }
+
     #[inline]
     pub fn default_path() -> PathBuf {
         // NOTE: For wasm, home directory will always return None, but we don't
@@ -468,12 +634,83 @@ impl HtmlWikiConfig {
         String::from("wiki")
     }
 
+    #[inline]
+    pub fn default_index() -> String {
+        String::from("index")
+    }
+
     #[inline]
     pub fn default_diary_rel_path() -> PathBuf {
         PathBuf::from("diary")
     }
 }
 
+/// Represents how the pages of a wiki map to output paths and urls, which
+/// applies to the files written for pages as well as to every link to them
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HtmlOutputConfig {
+    /// Extension of the output of each page (e.g. html), or empty to write
+    /// pages without an extension
+    #[serde(default = "HtmlOutputConfig::default_ext")]
+    pub ext: String,
+
+    /// If true, pages within directories are written to the root of the
+    /// output with their directories joined into their names using the
+    /// flatten separator (e.g. `projects/todo` becomes `projects-todo.html`)
+    #[serde(default = "HtmlOutputConfig::default_flatten")]
+    pub flatten: bool,
+
+    /// Separator placed between directories when flattening
+    #[serde(default = "HtmlOutputConfig::default_flatten_separator")]
+    pub flatten_separator: String,
+
+    /// If true, each page is written as the index file of a directory named
+    /// after the page and linked by a url ending in `/` (e.g. `projects/todo`
+    /// becomes `projects/todo/index.html` linked as `projects/todo/`), where
+    /// the index page of a directory becomes the index of that directory
+    #[serde(default = "HtmlOutputConfig::default_pretty_urls")]
+    pub pretty_urls: bool,
+
+    /// How spaces within the names of pages appear within the names of
+    /// their output and within the urls linking to them
+    #[serde(default)]
+    pub link_spaces: LinkSpaces,
+}
+
+impl Default for HtmlOutputConfig {
+    fn default() -> Self {
+        Self {
+            ext: Self::default_ext(),
+            flatten: Self::default_flatten(),
+            flatten_separator: Self::default_flatten_separator(),
+            pretty_urls: Self::default_pretty_urls(),
+            link_spaces: LinkSpaces::default(),
+        }
+    }
+}
+
+impl HtmlOutputConfig {
+    #[inline]
+    pub fn default_ext() -> String {
+        String::from("html")
+    }
+
+    #[inline]
+    pub const fn default_flatten() -> bool {
+        false
+    }
+
+    #[inline]
+    pub fn default_flatten_separator() -> String {
+        String::from("-")
+    }
+
+    #[inline]
+    pub const fn default_pretty_urls() -> bool {
+        false
+    }
+}
+
 /// Represents configuration options related to lists
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HtmlListConfig {
@@ -527,9 +764,10 @@ impl HtmlParagraphConfig {
 /// Represents configuration options related to links
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HtmlLinkConfig {
-    /// Represents the base url used when forming absolute links
+    /// Represents the base url used when forming absolute links, which must
+    /// be absolute
     #[serde(default = "HtmlLinkConfig::default_base_url", with = "uri")]
-    pub base_url: URI<'static>,
+    pub base_url: WikiUri<'static>,
 
     /// If true, all relative links (path/to/file.html or even /wiki/path/to/file.html)
     /// will be canonicalized using the base_url, otherwise they are kept as
@@ -541,16 +779,23 @@ pub struct HtmlLinkConfig {
     /// instead of the pretty form of `example.com/urls/`
     #[serde(default = "HtmlLinkConfig::default_use_ugly_urls")]
     pub use_ugly_urls: bool,
+
+    /// Custom uri schemes (e.g. `jira:PROJ-123`) that are recognized as raw
+    /// links and rendered using templates
+    #[serde(default)]
+    pub schemes: Vec<SchemeTemplate>,
 }
 
-/// Module that provides serialize/deserialize of URI to a string type
+/// Module that provides serialize/deserialize of an absolute URI to a string
+/// type
 mod uri {
+    use crate::uri::WikiUri;
     use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
     use std::convert::TryFrom;
-    use uriparse::URI;
+    use uriparse::{URIReference, URI};
 
     pub fn serialize<S>(
-        data: &URI<'_>,
+        data: &WikiUri<'_>,
         serializer: S,
     ) -> Result<S::Ok, S::Error>
     where
@@ -561,17 +806,19 @@ mod uri {
 
     pub fn deserialize<'de, D>(
         deserializer: D,
-    ) -> Result<URI<'static>, D::Error>
+    ) -> Result<WikiUri<'static>, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        URI::try_from(s.as_str()).map(URI::into_owned).map_err(|x| {
-            de::Error::invalid_value(
-                de::Unexpected::Str(&s),
-                &x.to_string().as_str(),
-            )
-        })
+        URI::try_from(s.as_str())
+            .map(|x| WikiUri::from(URIReference::from(x)).into_owned())
+            .map_err(|x| {
+                de::Error::invalid_value(
+                    de::Unexpected::Str(&s),
+                    &x.to_string().as_str(),
+                )
+            })
     }
 }
 
@@ -581,14 +828,15 @@ impl Default for HtmlLinkConfig {
             base_url: Self::default_base_url(),
             canonicalize: Self::default_canonicalize(),
             use_ugly_urls: Self::default_use_ugly_urls(),
+            schemes: Vec::new(),
         }
     }
 }
 
 impl HtmlLinkConfig {
     #[inline]
-    pub fn default_base_url() -> URI<'static> {
-        URI::try_from("https://localhost").unwrap().into_owned()
+    pub fn default_base_url() -> WikiUri<'static> {
+        WikiUri::parse("https://localhost").unwrap().into_owned()
     }
 
     #[inline]
@@ -600,6 +848,14 @@ impl HtmlLinkConfig {
     pub const fn default_use_ugly_urls() -> bool {
         false
     }
+
+    /// Registers a handler for each of the custom uri schemes so that they
+    /// are recognized when parsing and rendering raw links
+    pub fn register_schemes(&self) {
+        for template in self.schemes.iter() {
+            schemes::register(template.clone());
+        }
+    }
 }
 
 /// Represents configuration options related to headers
@@ -608,12 +864,25 @@ pub struct HtmlHeaderConfig {
     /// Represents the text that a header could have to be marked as the ToC
     #[serde(default = "HtmlHeaderConfig::default_table_of_contents")]
     pub table_of_contents: String,
+
+    /// Represents the level of headers where numbering starts, prefixing
+    /// headers of that level and deeper with their number relative to it
+    /// (e.g. `1.2`); 0 disables numbering
+    #[serde(default = "HtmlHeaderConfig::default_numbering")]
+    pub numbering: usize,
+
+    /// Represents the symbol placed after the number of a numbered header,
+    /// such as `.` to produce `1.2.`
+    #[serde(default = "HtmlHeaderConfig::default_numbering_sym")]
+    pub numbering_sym: String,
 }
 
 impl Default for HtmlHeaderConfig {
     fn default() -> Self {
         Self {
             table_of_contents: Self::default_table_of_contents(),
+            numbering: Self::default_numbering(),
+            numbering_sym: Self::default_numbering_sym(),
         }
     }
 }
@@ -623,6 +892,16 @@ impl HtmlHeaderConfig {
     pub fn default_table_of_contents() -> String {
         String::from("Contents")
     }
+
+    #[inline]
+    pub const fn default_numbering() -> usize {
+        0
+    }
+
+    #[inline]
+    pub fn default_numbering_sym() -> String {
+        String::new()
+    }
 }
 
 /// Represents configuration options related to code
@@ -706,10 +985,64 @@ impl HtmlCommentConfig {
     }
 }
 
+/// Represents configuration options related to keywords (TODO, DONE, etc)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HtmlKeywordConfig {
+    /// Mapping of keyword names to the css class of the span wrapping each
+    /// keyword in HTML output as `<span class="{class}">{keyword}</span>`,
+    /// where keywords without a class are written as plain text
+    #[serde(default = "HtmlKeywordConfig::default_classes")]
+    pub classes: BTreeMap<String, String>,
+}
+
+impl Default for HtmlKeywordConfig {
+    fn default() -> Self {
+        Self {
+            classes: Self::default_classes(),
+        }
+    }
+}
+
+impl HtmlKeywordConfig {
+    /// Returns the css class of the given keyword, if it has one
+    pub fn class_of(&self, keyword: &Keyword) -> Option<&str> {
+        self.classes.get(keyword.as_str()).map(String::as_str)
+    }
+
+    #[inline]
+    pub fn default_classes() -> BTreeMap<String, String> {
+        vec![(String::from("TODO"), String::from("todo"))]
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Represents configuration options related to navigation between pages
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HtmlNavigationConfig {
+    /// If true, will generate breadcrumbs from the root of the wiki to each
+    /// page based on its directory, placed before the content unless the
+    /// template includes `%breadcrumbs%`
+    #[serde(default)]
+    pub breadcrumbs: bool,
+
+    /// If true, will generate links to the previous and next entries of each
+    /// diary page, placed after the content unless the template includes
+    /// `%diary_nav%`
+    #[serde(default)]
+    pub diary: bool,
+
+    /// If true, will generate a tree of every page of the wiki, placed before
+    /// the content unless the template includes `%sidebar%`
+    #[serde(default)]
+    pub sidebar: bool,
+}
+
 /// Represents configuration options related to templates
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HtmlTemplateConfig {
-    /// Represents the name of the default template to use (e.g. default)
+    /// Represents the name of the default template to use (e.g. default),
+    /// which is loaded from the template directory if it exists there
     #[serde(default = "HtmlTemplateConfig::default_name")]
     pub name: String,
 
@@ -723,7 +1056,7 @@ pub struct HtmlTemplateConfig {
     pub dir: PathBuf,
 
     /// Represents the text to use for the template if no explicit template
-    /// is specified
+    /// is specified and the default template does not exist
     #[serde(default = "HtmlTemplateConfig::default_text")]
     pub text: String,
 }
@@ -1,5 +1,14 @@
-use super::{utils, HtmlConfig, HtmlFormatter, HtmlOutputError, Output};
-use chrono::Local;
+use super::{
+    navigation,
+    template::{self, HtmlTemplate},
+    utils, HtmlConfig, HtmlFormatter, HtmlOutputError, HtmlTemplateConfig,
+    Output,
+};
+use crate::{BlockElement, Page, Placeholder, WikiDate};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 pub trait ToHtmlString {
     /// Converts to individual HTML dom string
@@ -50,69 +59,183 @@ impl<T: Output<HtmlFormatter>> ToHtmlPage for T {
         });
 
         // Leverage the provided date, falling back to the current, local date
-        let date = formatter
-            .take_date()
-            .unwrap_or_else(|| Local::now().naive_local().date());
-
-        // Attempts to find and load template in {TEMPLATE_DIR}/{TEMPLATE},
-        // defaulting to vimwiki's standard template
-        let template = formatter
-            .take_template()
-            .map(|p| formatter.config().template.dir.join(p))
-            .map(std::fs::read_to_string)
-            .transpose()
-            .map_err(|source| HtmlOutputError::TemplateNotLoaded { source })?
-            .unwrap_or_else(|| formatter.config().template.text.to_string());
-
-        // Fill in template variables
-        // NOTE: Content is filled in last so we don't replace parts of content
-        //       with template variable contents as template variables only
-        //       apply to the template and not the content itself
-        let template = template
-            .replace("%title%", &title)
-            .replace("%date%", &date.to_string())
-            .replace(
-                "%root_path%",
-                {
-                    let path_str = utils::path_to_uri_string(
-                        formatter
-                            .config()
-                            .to_active_page_path_to_wiki_root()
-                            .as_path(),
-                    );
-
-                    if path_str.is_empty() {
-                        String::new()
-                    } else {
-                        format!("{}/", path_str)
-                    }
-                }
-                .as_str(),
-            )
-            .replace(
-                "%wiki_path%",
-                &utils::path_to_uri_string(
-                    formatter.config().as_active_page_path_within_wiki(),
-                ),
-            )
-            .replace(
-                "%css%",
-                formatter.config().to_current_wiki().css_name.as_str(),
-            )
-            .replace("%encoding%", "utf-8")
-            .replace("%content%", formatter.get_content());
+        let date = formatter.take_date().unwrap_or_else(WikiDate::today);
+
+        // Attempts to find and load the template of the page, falling back
+        // to the default template of the template directory and finally to
+        // vimwiki's standard template
+        let page_template = formatter.take_template();
+        let template_config = &formatter.config().template;
+        let template = match page_template {
+            Some(p) => Some(template_path(template_config, &p)),
+            None => Some(default_template_path(template_config))
+                .filter(|p| p.is_file()),
+        }
+        .map(std::fs::read_to_string)
+        .transpose()
+        .map_err(|source| HtmlOutputError::TemplateNotLoaded { source })?
+        .map(HtmlTemplate::new)
+        .unwrap_or_else(|| HtmlTemplate::new(template_config.text.as_str()));
+
+        let root_path = {
+            let path_str = utils::path_to_uri_string(
+                formatter
+                    .config()
+                    .to_active_page_path_to_wiki_root()
+                    .as_path(),
+            );
+
+            if path_str.is_empty() {
+                String::new()
+            } else {
+                format!("{}/", path_str)
+            }
+        };
+        let wiki_path = utils::path_to_uri_string(
+            formatter.config().as_active_page_path_within_wiki(),
+        );
+        let wiki = formatter.config().to_current_wiki();
+        let name = utils::path_to_uri_string(
+            &formatter
+                .config()
+                .as_active_page_path_within_wiki()
+                .with_extension(""),
+        );
+        let pages: Vec<String> = formatter
+            .config()
+            .runtime
+            .pages
+            .iter()
+            .filter_map(|p| wiki.path_within(p))
+            .map(|p| utils::path_to_uri_string(&p.with_extension("")))
+            .collect();
+        let breadcrumbs =
+            navigation::breadcrumbs_to_html(&name, &root_path, &wiki);
+        let diary_nav =
+            navigation::diary_nav_to_html(&name, &root_path, &wiki, &pages);
+        let sidebar =
+            navigation::sidebar_to_html(&name, &root_path, &wiki, &pages);
+
+        // Place enabled navigation around the content when the template does
+        // not position it explicitly
+        let navigation_config = &formatter.config().navigation;
+        let missing =
+            |name: &str| !template.as_str().contains(&format!("%{}%", name));
+        let mut content = String::new();
+        if navigation_config.breadcrumbs && missing("breadcrumbs") {
+            content.push_str(&breadcrumbs);
+        }
+        if navigation_config.sidebar && missing("sidebar") {
+            content.push_str(&sidebar);
+        }
+        content.push_str(formatter.get_content());
+        if navigation_config.diary && missing("diary_nav") {
+            content.push_str(&diary_nav);
+        }
+
+        // Fill in template variables and partials
+        // NOTE: Variables (including content) are never scanned for more
+        //       placeholders as template variables only apply to the
+        //       template and not the content itself
+        let mut variables = HashMap::new();
+        variables.insert("title", title);
+        variables.insert("date", date.to_string());
+        variables.insert("root_path", root_path);
+        variables.insert("wiki_path", wiki_path);
+        variables.insert("css", wiki.css_name.to_string());
+        variables.insert("encoding", String::from("utf-8"));
+        variables.insert("breadcrumbs", breadcrumbs);
+        variables.insert("diary_nav", diary_nav);
+        variables.insert("sidebar", sidebar);
+        variables.insert(
+            "toc",
+            template::toc_to_html(&formatter.table_of_contents()),
+        );
+        variables.insert("content", content);
+
+        let template = template.render(&variables, |name| {
+            std::fs::read_to_string(template_path(
+                template_config,
+                Path::new(name),
+            ))
+            .map_err(|source| HtmlOutputError::TemplateNotLoaded { source })
+        })?;
 
         Ok(template)
     }
 }
 
+/// Returns paths to the template and partials used to produce the html page
+/// of the page, which are the files besides the page itself whose contents
+/// affect its html
+///
+/// The default template is included even when it does not exist as creating
+/// it changes the html of the page.
+pub fn html_page_dependencies(
+    page: &Page,
+    config: &HtmlConfig,
+) -> Vec<PathBuf> {
+    let template_config = &config.template;
+    // NOTE: The last template placeholder of a page is the one used
+    let page_template =
+        page.elements()
+            .iter()
+            .rev()
+            .find_map(|x| match x.as_inner() {
+                BlockElement::Placeholder(Placeholder::Template(x)) => {
+                    Some(Path::new(x.as_ref()))
+                }
+                _ => None,
+            });
+
+    let mut dependencies = vec![match page_template {
+        Some(p) => template_path(template_config, p),
+        None => default_template_path(template_config),
+    }];
+
+    // Follow partials included by the template and other partials, where
+    // a partial only needs to be visited once
+    let mut i = 0;
+    while i < dependencies.len() {
+        if let Ok(text) = std::fs::read_to_string(&dependencies[i]) {
+            for name in HtmlTemplate::new(text).partials() {
+                let path = template_path(template_config, Path::new(name));
+                if !dependencies.contains(&path) {
+                    dependencies.push(path);
+                }
+            }
+        }
+        i += 1;
+    }
+
+    dependencies
+}
+
+/// Returns the path to the default template within the template directory
+fn default_template_path(config: &HtmlTemplateConfig) -> PathBuf {
+    template_path(config, Path::new(&config.name))
+}
+
+/// Returns the path to the template (or partial) with the given name within
+/// the template directory, adding the template extension if the name has no
+/// extension of its own
+fn template_path(config: &HtmlTemplateConfig, name: &Path) -> PathBuf {
+    let path = config.dir.join(name);
+    if path.extension().is_none() && !config.ext.is_empty() {
+        path.with_extension(&config.ext)
+    } else {
+        path
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::date::WikiDate;
     use crate::{
-        HtmlOutputResult, HtmlRuntimeConfig, HtmlTemplateConfig, HtmlWikiConfig,
+        HtmlNavigationConfig, HtmlOutputResult, HtmlRuntimeConfig,
+        HtmlTemplateConfig, HtmlWikiConfig,
     };
-    use chrono::NaiveDate;
     use std::path::PathBuf;
 
     struct TestOutput<F: Fn(&mut HtmlFormatter) -> HtmlOutputResult>(F);
@@ -194,7 +317,7 @@ mod tests {
     #[test]
     fn to_html_page_should_replace_date_placeholder_with_provided_date() {
         let output = TestOutput(|f| {
-            f.set_date(&NaiveDate::from_ymd(2003, 11, 27));
+            f.set_date(&WikiDate::from_ymd(2003, 11, 27));
             Ok(())
         });
         let template = HtmlTemplateConfig::from_text("<html>%date%</html>");
@@ -218,10 +341,7 @@ mod tests {
         };
 
         let result = output.to_html_page(config).unwrap();
-        assert_eq!(
-            result,
-            format!("<html>{}</html>", Local::now().naive_local().date())
-        );
+        assert_eq!(result, format!("<html>{}</html>", WikiDate::today()));
     }
 
     #[test]
@@ -241,6 +361,7 @@ mod tests {
             runtime: HtmlRuntimeConfig {
                 wiki_index: Some(0),
                 page: ["some", "path", "to", "a", "file.wiki"].iter().collect(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -257,6 +378,7 @@ mod tests {
             runtime: HtmlRuntimeConfig {
                 wiki_index: Some(0),
                 page: ["some", "path", "to", "file.wiki"].iter().collect(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -273,6 +395,7 @@ mod tests {
             runtime: HtmlRuntimeConfig {
                 wiki_index: Some(0),
                 page: ["some", "path", "file.wiki"].iter().collect(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -294,6 +417,7 @@ mod tests {
             runtime: HtmlRuntimeConfig {
                 wiki_index: Some(0),
                 page: ["some", "path", "to", "a", "file.wiki"].iter().collect(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -302,6 +426,57 @@ mod tests {
         assert_eq!(result, "<html>to/a/file.wiki</html>");
     }
 
+    #[test]
+    fn to_html_page_should_place_enabled_navigation_around_content() {
+        let output = TestOutput(_text("<p>x</p>"));
+        let wiki: PathBuf = ["some", "path"].iter().collect();
+        let config = |text: &str| HtmlConfig {
+            template: HtmlTemplateConfig::from_text(text),
+            wikis: vec![HtmlWikiConfig {
+                path: wiki.clone(),
+                ..Default::default()
+            }],
+            runtime: HtmlRuntimeConfig {
+                wiki_index: Some(0),
+                page: wiki.join("diary").join("2021-04-05.wiki"),
+                pages: vec![
+                    wiki.join("diary").join("2021-04-01.wiki"),
+                    wiki.join("diary").join("2021-04-05.wiki"),
+                ]
+                .into(),
+                ..Default::default()
+            },
+            navigation: HtmlNavigationConfig {
+                breadcrumbs: true,
+                diary: true,
+                sidebar: false,
+            },
+            ..Default::default()
+        };
+
+        let result = output.to_html_page(config("%content%")).unwrap();
+        assert_eq!(
+            result,
+            concat!(
+                "<nav class=\"breadcrumbs\">",
+                "<a href=\"../index.html\">index</a> / ",
+                "<a href=\"../diary/index.html\">diary</a> / 2021-04-05</nav>",
+                "<p>x</p>",
+                "<nav class=\"diary-nav\">",
+                "<a class=\"previous\" href=\"../diary/2021-04-01.html\">",
+                "&larr; 2021-04-01</a></nav>",
+            )
+        );
+
+        // Navigation positioned by the template is not placed again
+        let result = output
+            .to_html_page(config("%content%|%breadcrumbs%"))
+            .unwrap();
+        assert!(result.starts_with("<p>x</p><nav class=\"diary-nav\">"));
+        assert!(result.ends_with("2021-04-05</nav>"));
+        assert_eq!(result.matches("class=\"breadcrumbs\"").count(), 1);
+    }
+
     #[test]
     fn to_html_page_should_replace_css_placeholder_with_provided_css_name() {
         let output = TestOutput(_text(""));
@@ -365,4 +540,65 @@ mod tests {
         let result = output.to_html_page(config).unwrap();
         assert_eq!(result, "<html>some output content</html>");
     }
+
+    #[test]
+    fn to_html_page_should_use_default_template_and_partials_from_template_dir()
+    {
+        let dir = std::env::temp_dir()
+            .join(format!("vimwiki-html-templates-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("default.tpl"),
+            "%partial:header%<main>%content%</main>",
+        )
+        .unwrap();
+        std::fs::write(dir.join("header.tpl"), "<h1>%title%</h1>").unwrap();
+
+        let output = TestOutput(|f: &mut HtmlFormatter| {
+            f.set_title("Page");
+            Ok(())
+        });
+        let config = HtmlConfig {
+            template: HtmlTemplateConfig {
+                dir: dir.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = output.to_html_page(config);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(result.unwrap(), "<h1>Page</h1><main></main>");
+    }
+
+    #[test]
+    fn html_page_dependencies_should_include_template_and_nested_partials() {
+        let dir = std::env::temp_dir()
+            .join(format!("vimwiki-html-dependencies-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("custom.tpl"), "%partial:a%%content%").unwrap();
+        std::fs::write(dir.join("a.tpl"), "%partial:b%%partial:a%").unwrap();
+
+        let config = HtmlConfig {
+            template: HtmlTemplateConfig {
+                dir: dir.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let page: Page = crate::Language::from_vimwiki_str("%template custom")
+            .parse()
+            .unwrap();
+        let custom = html_page_dependencies(&page, &config);
+        let page: Page =
+            crate::Language::from_vimwiki_str("text").parse().unwrap();
+        let default = html_page_dependencies(&page, &config);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            custom,
+            vec![dir.join("custom.tpl"), dir.join("a.tpl"), dir.join("b.tpl")]
+        );
+        assert_eq!(default, vec![dir.join("default.tpl")]);
+    }
 }
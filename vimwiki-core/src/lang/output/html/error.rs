@@ -37,6 +37,8 @@ pub enum HtmlOutputError {
 
     MissingWikiWithName(#[error(not(source))] String),
 
+    DirectiveFailed(#[error(not(source))] String),
+
     TemplateNotLoaded {
         #[error(source)]
         source: std::io::Error,
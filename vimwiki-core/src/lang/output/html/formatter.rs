@@ -1,5 +1,8 @@
-use super::{HtmlConfig, HtmlOutputError, OutputFormatter};
-use chrono::NaiveDate;
+use super::{HtmlConfig, HtmlOutputError, OutputFormatter, SourceMap};
+use crate::date::WikiDate;
+use crate::lang::elements::Region;
+use crate::resolve::LinkResolver;
+use crate::toc::{HeaderNumber, HeaderNumbering, TableOfContents, TocEntry};
 use std::{
     borrow::Cow,
     collections::HashMap,
@@ -14,17 +17,35 @@ pub struct HtmlFormatter {
     /// Represents the configuration associated with the formatter
     config: HtmlConfig,
 
+    /// Resolves the files targeted by links, built once from the config
+    link_resolver: LinkResolver,
+
     /// Mapping of header level -> text (with details stripped)
     last_seen_headers: HashMap<usize, String>,
 
     /// Cache of all ids used and total times used thus far
     id_cache: HashMap<String, usize>,
 
+    /// Mapping of footnote label -> number displayed for the footnote
+    footnote_numbers: HashMap<String, usize>,
+
+    /// Counter used to number headers as they are written
+    header_numbering: HeaderNumbering,
+
+    /// Headers written thus far that belong in a table of contents
+    toc_entries: Vec<TocEntry>,
+
+    /// Map of output written thus far back to the regions of elements
+    source_map: SourceMap,
+
+    /// Total list items that content is currently being written within
+    list_item_depth: usize,
+
     /// Contains the title to be used for the page
     title: Option<String>,
 
     /// Contains the date to be used for the page
-    date: Option<NaiveDate>,
+    date: Option<WikiDate>,
 
     /// Contains the template to be used for the page
     template: Option<PathBuf>,
@@ -46,9 +67,15 @@ impl Write for HtmlFormatter {
 impl HtmlFormatter {
     pub fn new(config: HtmlConfig) -> Self {
         Self {
+            link_resolver: LinkResolver::from(&config),
             config,
             last_seen_headers: HashMap::new(),
             id_cache: HashMap::new(),
+            footnote_numbers: HashMap::new(),
+            header_numbering: HeaderNumbering::new(),
+            toc_entries: Vec::new(),
+            source_map: SourceMap::new(),
+            list_item_depth: 0,
             title: None,
             date: None,
             template: None,
@@ -62,6 +89,13 @@ impl HtmlFormatter {
         &self.config
     }
 
+    /// Represents the resolver of links built from the config contained
+    /// within the formatter
+    #[inline]
+    pub fn link_resolver(&self) -> &LinkResolver {
+        &self.link_resolver
+    }
+
     /// Inserts text for the header at the given level to be remembered when
     /// keeping track of the last header seen at a given level
     pub fn insert_header_text<Text: Into<String>>(
@@ -82,6 +116,21 @@ impl HtmlFormatter {
         self.last_seen_headers.keys().max().copied()
     }
 
+    /// Counts a header of the given level, returning its hierarchical number
+    pub fn next_header_number(&mut self, level: usize) -> HeaderNumber {
+        self.header_numbering.next(level)
+    }
+
+    /// Adds a header to the table of contents of the page being written
+    pub fn insert_toc_entry(&mut self, entry: TocEntry) {
+        self.toc_entries.push(entry);
+    }
+
+    /// Returns the table of contents of the headers written thus far
+    pub fn table_of_contents(&self) -> TableOfContents {
+        TableOfContents::new(self.toc_entries.clone())
+    }
+
     /// Given some input id, will output an id that is guaranteed to be unique
     /// through a format of {ID}-{NUMBER}
     pub fn ensure_unique_id<'a>(&mut self, id: &'a str) -> Cow<'a, str> {
@@ -108,6 +157,40 @@ impl HtmlFormatter {
         unique_id
     }
 
+    /// Assigns the number displayed for the footnote with the given label
+    pub fn insert_footnote_number<Label: Into<String>>(
+        &mut self,
+        label: Label,
+        number: usize,
+    ) {
+        self.footnote_numbers.insert(label.into(), number);
+    }
+
+    /// Returns the number displayed for the footnote with the given label,
+    /// assigning the next available number if the footnote has none
+    pub fn footnote_number(&mut self, label: &str) -> usize {
+        let next = self.footnote_numbers.len() + 1;
+        *self
+            .footnote_numbers
+            .entry(label.to_string())
+            .or_insert(next)
+    }
+
+    /// Marks the start of writing the contents of a list item
+    pub fn begin_list_item(&mut self) {
+        self.list_item_depth += 1;
+    }
+
+    /// Marks the end of writing the contents of a list item
+    pub fn end_list_item(&mut self) {
+        self.list_item_depth = self.list_item_depth.saturating_sub(1);
+    }
+
+    /// Returns true if content is being written within a list item
+    pub fn is_in_list_item(&self) -> bool {
+        self.list_item_depth > 0
+    }
+
     pub fn set_title(&mut self, title: &str) {
         self.title = Some(title.to_string());
     }
@@ -120,15 +203,15 @@ impl HtmlFormatter {
         self.title.take()
     }
 
-    pub fn set_date(&mut self, date: &NaiveDate) {
+    pub fn set_date(&mut self, date: &WikiDate) {
         self.date = Some(*date);
     }
 
-    pub fn get_date(&self) -> Option<&NaiveDate> {
+    pub fn get_date(&self) -> Option<&WikiDate> {
         self.date.as_ref()
     }
 
-    pub fn take_date(&mut self) -> Option<NaiveDate> {
+    pub fn take_date(&mut self) -> Option<WikiDate> {
         self.date.take()
     }
 
@@ -144,6 +227,42 @@ impl HtmlFormatter {
         self.template.take()
     }
 
+    /// Returns the map of output written thus far back to the regions of
+    /// the elements that produced it
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    /// Maps all content written since the byte offset to the region, adding
+    /// a `data-region="{offset},{len}"` attribute to the first tag within
+    /// that content
+    pub fn map_content_to_region(&mut self, start: usize, region: Region) {
+        let bytes = self.content.as_bytes();
+        let tag_start = (start..bytes.len().saturating_sub(1))
+            .find(|i| bytes[*i] == b'<' && bytes[*i + 1].is_ascii_alphabetic());
+
+        if let Some(i) = tag_start {
+            let pos = (i + 1..bytes.len())
+                .find(|i| !bytes[*i].is_ascii_alphanumeric())
+                .unwrap_or(bytes.len());
+            let attr = format!(
+                r#" data-region="{},{}""#,
+                region.offset(),
+                region.len()
+            );
+            self.content.insert_str(pos, &attr);
+            self.source_map.shift(pos, attr.len());
+        }
+
+        if self.content.len() > start {
+            self.source_map.insert_from(
+                Some(self.config.runtime.page.to_path_buf()),
+                region,
+                start..self.content.len(),
+            );
+        }
+    }
+
     pub fn get_content(&self) -> &str {
         self.content.as_str()
     }
@@ -5,17 +5,35 @@ mod formatter;
 pub use formatter::HtmlFormatter;
 
 mod convert;
-pub use convert::{ToHtmlPage, ToHtmlString};
+pub use convert::{html_page_dependencies, ToHtmlPage, ToHtmlString};
 
 mod error;
 pub use error::{HtmlOutputError, HtmlOutputResult};
 
+mod navigation;
+
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "parallel")]
+pub use parallel::{render_html_pages, HtmlRenderProgress};
+
+mod source_map;
+pub use source_map::{SourceMap, SourceMapEntry};
+
+mod template;
+pub use template::HtmlTemplate;
+
 mod utils;
 pub use utils::LinkResolutionError;
 
-use crate::lang::{
-    elements::*,
-    output::{Output, OutputFormatter},
+use crate::{
+    directives,
+    lang::{
+        elements::*,
+        output::{Output, OutputFormatter},
+    },
+    schemes,
+    toc::TocEntry,
 };
 use lazy_static::lazy_static;
 use std::{borrow::Cow, collections::HashMap, fmt::Write};
@@ -25,7 +43,6 @@ use syntect::{
     html::{self, IncludeBackground},
     parsing::SyntaxSet,
 };
-use uriparse::URIReference;
 use voca_rs::escape;
 
 lazy_static! {
@@ -38,12 +55,58 @@ lazy_static! {
 }
 
 impl<'a> Output<HtmlFormatter> for Page<'a> {
+    /// Writes a page in HTML, where footnote definitions are collected into
+    /// a section at the end of the page ordered by their number
+    ///
+    /// ### Example
+    ///
+    /// ```html
+    /// <p>Some text<sup class="footnote-ref"><a href="#fn-note" id="fnref-note">1</a></sup></p>
+    /// <section class="footnotes">
+    /// <ol>
+    /// <li id="fn-note" value="1">Note text <a href="#fnref-note" class="footnote-backref">&#8617;</a></li>
+    /// </ol>
+    /// </section>
+    /// ```
+    ///
+    /// ### With a source map
+    ///
+    /// The first tag produced by each block element and list item is
+    /// annotated with the region of the element
+    ///
+    /// ```html
+    /// <p data-region="0,10">Some text</p>
+    /// ```
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
-        for element in self.elements.iter() {
-            element.fmt(f)?;
+        let footnotes = self.footnotes();
+        for footnote in footnotes.iter() {
+            f.insert_footnote_number(footnote.label.as_ref(), footnote.number);
+        }
+
+        for element in self.elements().iter() {
+            if element.is_footnote_definition() {
+                continue;
+            }
+
+            fmt_located(element, f)?;
             writeln!(f)?;
         }
 
+        let definitions: Vec<_> = footnotes
+            .iter()
+            .filter_map(|x| x.definition.as_ref())
+            .collect();
+        if !definitions.is_empty() {
+            writeln!(f, "<section class=\"footnotes\">")?;
+            writeln!(f, "<ol>")?;
+            for definition in definitions {
+                fmt_located(definition, f)?;
+                writeln!(f)?;
+            }
+            writeln!(f, "</ol>")?;
+            writeln!(f, "</section>")?;
+        }
+
         Ok(())
     }
 }
@@ -74,6 +137,7 @@ impl<'a> Output<HtmlFormatter> for BlockElement<'a> {
             Self::Blockquote(x) => x.fmt(f),
             Self::DefinitionList(x) => x.fmt(f),
             Self::Divider(x) => x.fmt(f),
+            Self::FootnoteDefinition(x) => x.fmt(f),
             Self::Header(x) => x.fmt(f),
             Self::List(x) => x.fmt(f),
             Self::MathBlock(x) => x.fmt(f),
@@ -97,24 +161,55 @@ impl<'a> Output<HtmlFormatter> for Blockquote<'a> {
     /// </blockquote>
     /// ```
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
+        fn write_group(
+            f: &mut HtmlFormatter,
+            lines: &[Cow<'_, str>],
+        ) -> HtmlOutputResult {
+            writeln!(
+                f,
+                "<p>{}</p>",
+                escape::escape_html(
+                    lines
+                        .iter()
+                        .map(|line| line.trim())
+                        .collect::<Vec<&str>>()
+                        .join(" ")
+                        .as_str()
+                )
+            )?;
+            Ok(())
+        }
+
         writeln!(f, "<blockquote>")?;
 
+        // If we have nested quotes, then we want to open and close a
+        // blockquote for each change in depth with a paragraph wrapping
+        // each group
+        if self.max_depth() > 1 {
+            let mut depth = 1;
+            for (group_depth, lines) in self.depth_groups() {
+                let group_depth = group_depth.max(1);
+                while depth < group_depth {
+                    writeln!(f, "<blockquote>")?;
+                    depth += 1;
+                }
+                while depth > group_depth {
+                    writeln!(f, "</blockquote>")?;
+                    depth -= 1;
+                }
+                write_group(f, lines)?;
+            }
+
+            while depth > 1 {
+                writeln!(f, "</blockquote>")?;
+                depth -= 1;
+            }
+
         // If we have more than one group of lines, then we want a paragraph
         // wrapping each group
-        if self.line_groups().count() > 1 {
+        } else if self.line_groups().count() > 1 {
             for lines in self.line_groups() {
-                writeln!(
-                    f,
-                    "<p>{}</p>",
-                    escape::escape_html(
-                        lines
-                            .iter()
-                            .map(|line| line.trim())
-                            .collect::<Vec<&str>>()
-                            .join(" ")
-                            .as_str()
-                    )
-                )?;
+                write_group(f, lines)?;
             }
 
         // Otherwise, we want to just drop in the lines verbatim
@@ -183,6 +278,29 @@ impl Output<HtmlFormatter> for Divider {
     }
 }
 
+impl<'a> Output<HtmlFormatter> for FootnoteDefinition<'a> {
+    /// Writes a footnote definition in HTML as an item of the list of
+    /// footnotes, linking back to the first reference of the footnote
+    ///
+    /// ```html
+    /// <li id="fn-note" value="1">Note text <a href="#fnref-note" class="footnote-backref">&#8617;</a></li>
+    /// ```
+    fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
+        let id = utils::normalize_id(&self.label);
+        let number = f.footnote_number(&self.label);
+
+        write!(f, "<li id=\"fn-{}\" value=\"{}\">", id, number)?;
+        self.contents.fmt(f)?;
+        write!(
+            f,
+            " <a href=\"#fnref-{}\" class=\"footnote-backref\">&#8617;</a></li>",
+            id
+        )?;
+
+        Ok(())
+    }
+}
+
 impl<'a> Output<HtmlFormatter> for Header<'a> {
     /// Writes a header in HTML
     ///
@@ -198,6 +316,17 @@ impl<'a> Output<HtmlFormatter> for Header<'a> {
     /// </div>
     /// ```
     ///
+    /// ### Numbered header
+    ///
+    /// When numbering is enabled, headers at or below the level where it
+    /// starts are prefixed with their number and the numbering symbol
+    ///
+    /// ```html
+    /// <h2 id="{second level text}" class="header">
+    ///     <a href="#{id-from-above-div}">1.2. <!-- header text --></a>
+    /// </h2>
+    /// ```
+    ///
     /// ### Table of Contents
     ///
     /// ```html
@@ -251,6 +380,24 @@ impl<'a> Output<HtmlFormatter> for Header<'a> {
             //       we are a top-level header then this would be the same
             //       as unique_header_id
             write!(f, r##"<a href="#{}">"##, unique_complete_header_id)?;
+
+            // Prefix the header with its number relative to the level where
+            // numbering starts, if enabled and the header is deep enough
+            let number = f.next_header_number(self.level);
+            let numbering = f.config().header.numbering;
+            if numbering > 0 {
+                if let Some(x) = number.from_level(numbering) {
+                    let sym = f.config().header.numbering_sym.to_string();
+                    write!(f, "{}{} ", x, sym)?;
+                }
+            }
+
+            f.insert_toc_entry(TocEntry {
+                level: self.level,
+                number,
+                text: raw_content.trim().to_string(),
+                id: unique_complete_header_id.to_string(),
+            });
             self.content.fmt(f)?;
             write!(f, "</a></h{}>", self.level)?;
 
@@ -285,13 +432,23 @@ impl<'a> Output<HtmlFormatter> for List<'a> {
     ///     <li>...</li>
     /// </ol>
     /// ```
+    ///
+    /// ### Ordered list using letters or roman numerals
+    ///
+    /// ```html
+    /// <ol type="i">
+    ///     <li>...</li>
+    ///     <li>...</li>
+    /// </ol>
+    /// ```
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
-        // TODO: This should be used for list items... how?
-        let _ignore_newlines = f.config().list.ignore_newline;
-
-        // If the list is ordered, we use an ordered HTML list
+        // If the list is ordered, we use an ordered HTML list numbered in
+        // the same style as the list's items
         if self.is_ordered() {
-            writeln!(f, "<ol>")?;
+            match self.ordered_type().and_then(|x| x.to_html_type()) {
+                Some(ty) => writeln!(f, "<ol type=\"{}\">", ty)?,
+                None => writeln!(f, "<ol>")?,
+            }
 
         // Otherwise, if the list is unordered (or has nothing) we use
         // an unordered HTML list
@@ -300,7 +457,7 @@ impl<'a> Output<HtmlFormatter> for List<'a> {
         }
 
         for item in self {
-            item.fmt(f)?;
+            fmt_located(item, f)?;
             writeln!(f)?;
         }
 
@@ -349,9 +506,6 @@ impl<'a> Output<HtmlFormatter> for ListItem<'a> {
     /// <li class="rejected">...</li>
     /// ```
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
-        // TODO: This should be used for list items... how?
-        let _ignore_newlines = f.config().list.ignore_newline;
-
         // First, figure out what class we should be using
         let todo_class = if self.is_todo_incomplete() {
             "done0"
@@ -376,7 +530,11 @@ impl<'a> Output<HtmlFormatter> for ListItem<'a> {
             write!(f, "<li>")?;
         }
 
-        self.contents.fmt(f)?;
+        // Paragraphs within the item follow the line break handling of lists
+        f.begin_list_item();
+        let result = self.contents.fmt(f);
+        f.end_list_item();
+        result?;
 
         write!(f, "</li>")?;
 
@@ -388,7 +546,7 @@ impl<'a> Output<HtmlFormatter> for ListItemContents<'a> {
     /// Writes a list item's contents in HTML
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
         for content in self {
-            content.fmt(f)?;
+            fmt_located(content, f)?;
         }
 
         Ok(())
@@ -441,13 +599,27 @@ impl<'a> Output<HtmlFormatter> for Placeholder<'a> {
     /// Note that this doesn't actually do any writing, but instead updates
     /// settings in the formatter with specific details such as a title, date,
     /// or alternative template to use
-    /// ```
+    ///
+    /// The exception is any other placeholder with a directive handler
+    /// registered for its name (such as the built-in `%plainhtml`), which
+    /// writes the HTML produced by the handler
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
         match self {
             Self::Title(x) => f.set_title(x),
             Self::Date(x) => f.set_date(x),
             Self::Template(x) => f.set_template(x.as_ref()),
-            _ => {}
+            Self::Other { name, value } => {
+                if let Some(handler) = directives::find(name) {
+                    let html = handler.html(value).map_err(|x| {
+                        HtmlOutputError::DirectiveFailed(format!(
+                            "%{}: {}",
+                            name, x
+                        ))
+                    })?;
+                    write!(f, "{}", html)?;
+                }
+            }
+            Self::NoHtml => {}
         }
 
         Ok(())
@@ -626,8 +798,15 @@ impl<'a> Output<HtmlFormatter> for Paragraph<'a> {
     /// ```html
     /// <p>Some paragraph text<br />on multiple lines</p>
     /// ```
+    ///
+    /// Paragraphs within list items follow the list config rather than the
+    /// paragraph config, mirroring vimwiki's separate options for each
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
-        let ignore_newlines = f.config().paragraph.ignore_newline;
+        let ignore_newlines = if f.is_in_list_item() {
+            f.config().list.ignore_newline
+        } else {
+            f.config().paragraph.ignore_newline
+        };
         let is_blank = self.is_blank();
 
         // TODO: CHIP CHIP CHIP -- need to handle situation where a paragraph
@@ -848,6 +1027,7 @@ impl<'a> Output<HtmlFormatter> for InlineElement<'a> {
             Self::Tags(x) => x.fmt(f),
             Self::Code(x) => x.fmt(f),
             Self::Math(x) => x.fmt(f),
+            Self::FootnoteReference(x) => x.fmt(f),
             Self::Comment(x) => x.fmt(f),
         }
     }
@@ -938,6 +1118,9 @@ impl<'a> Output<HtmlFormatter> for DecoratedTextContent<'a> {
             Self::DecoratedText(x) => x.fmt(f),
             Self::Keyword(x) => x.fmt(f),
             Self::Link(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::FootnoteReference(x) => x.fmt(f),
         }
     }
 }
@@ -948,15 +1131,18 @@ impl Output<HtmlFormatter> for Keyword {
     /// Unable to be implemented via Output<HtmlFormatter> trait as generic associated types
     /// would be required.
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
-        // For all keywords other than todo, they are treated as plain output
-        // for HTML. For todo, it is wrapped in a span with a todo class
-        match self {
-            Self::Todo => write!(f, "<span class=\"todo\">TODO</span>")?,
-            Self::Done => write!(f, "DONE")?,
-            Self::Started => write!(f, "STARTED")?,
-            Self::Fixme => write!(f, "FIXME")?,
-            Self::Fixed => write!(f, "FIXED")?,
-            Self::Xxx => write!(f, "XXX")?,
+        // Keywords with a configured css class (by default only todo) are
+        // wrapped in a span with that class while all others are treated as
+        // plain output
+        let class = f.config().keyword.class_of(self).map(escape::escape_html);
+        match class {
+            Some(class) => write!(
+                f,
+                "<span class=\"{}\">{}</span>",
+                class,
+                escape::escape_html(self.as_str())
+            )?,
+            None => write!(f, "{}", escape::escape_html(self.as_str()))?,
         }
 
         Ok(())
@@ -1051,13 +1237,63 @@ impl<'a> Output<HtmlFormatter> for Link<'a> {
     /// <img src="path/to/img.png" alt="descr" />
     /// <img src="path/to/img.png" alt="descr" style="A" />
     /// ```
+    ///
+    /// Properties are written as attributes sorted by name.
+    ///
+    /// ### Raw link with a registered scheme handler
+    ///
+    /// For `jira:PROJ-123` with a handler for the `jira` scheme:
+    ///
+    /// ```html
+    /// <a href="https://jira.example.com/browse/PROJ-123">PROJ-123</a>
+    /// ```
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
+        // Raw links of custom schemes are rendered entirely by their handler
+        if let Self::Raw { data } = self {
+            let handler = data.scheme().and_then(schemes::find);
+            if let Some(handler) = handler {
+                write!(
+                    f,
+                    "<a href=\"{}\">{}</a>",
+                    escape::escape_html(&handler.href(&data.uri_ref)),
+                    escape::escape_html(&handler.label(&data.uri_ref)),
+                )?;
+                return Ok(());
+            }
+        }
+
+        // Writes properties as attributes sorted by name so that output is
+        // stable, skipping names that are not valid html attribute names
+        fn write_properties(
+            f: &mut HtmlFormatter,
+            properties: Option<&HashMap<Cow<'_, str>, Cow<'_, str>>>,
+        ) -> HtmlOutputResult {
+            let mut properties: Vec<_> = properties
+                .into_iter()
+                .flatten()
+                .filter(|(k, _)| {
+                    !k.is_empty()
+                        && k.chars().all(|c| {
+                            c.is_ascii_alphanumeric()
+                                || matches!(c, '-' | '_' | ':')
+                        })
+                })
+                .collect();
+            properties.sort();
+
+            for (k, v) in properties {
+                write!(f, " {}=\"{}\"", k, escape::escape_html(v))?;
+            }
+
+            Ok(())
+        }
+
         // Produces a link tag of <a href=".." ...>link/description</a>
         // based on the link data and a given base url representing the root
         // of the wiki if needed
         fn write_link(
             f: &mut HtmlFormatter,
-            href: &URIReference<'_>,
+            href: &str,
             description: Option<&Description>,
             properties: Option<&HashMap<Cow<'_, str>, Cow<'_, str>>>,
             use_img_tag: bool,
@@ -1065,30 +1301,17 @@ impl<'a> Output<HtmlFormatter> for Link<'a> {
             if use_img_tag {
                 write!(f, "<img src=\"{}\"", href)?;
 
-                if let Some(desc) = description {
-                    write!(
-                        f,
-                        " alt=\"{}\"",
-                        escape::escape_html(desc.to_string().as_str())
-                    )?;
-                }
-
-                if let Some(properties) = properties {
-                    for (k, v) in properties.iter() {
-                        write!(f, " {}=\"{}\"", k, escape::escape_html(v))?;
-                    }
+                // NOTE: Only a textual description is alternate text, as the
+                //       description of an image cannot be another image
+                if let Some(Description::Text(desc)) = description {
+                    write!(f, " alt=\"{}\"", escape::escape_html(desc))?;
                 }
 
+                write_properties(f, properties)?;
                 write!(f, " />")?;
             } else {
                 write!(f, "<a href=\"{}\"", href)?;
-
-                if let Some(properties) = properties {
-                    for (k, v) in properties.iter() {
-                        write!(f, " {}=\"{}\"", k, escape::escape_html(v))?;
-                    }
-                }
-
+                write_properties(f, properties)?;
                 write!(f, ">")?;
 
                 match description {
@@ -1114,8 +1337,9 @@ impl<'a> Output<HtmlFormatter> for Link<'a> {
             Ok(())
         }
 
-        let uri_ref = utils::resolve_link(
+        let href = utils::resolve_link_href(
             f.config(),
+            f.link_resolver(),
             &f.config().to_current_wiki(),
             f.config().as_active_page_path_within_wiki(),
             &self,
@@ -1124,7 +1348,7 @@ impl<'a> Output<HtmlFormatter> for Link<'a> {
 
         write_link(
             f,
-            &uri_ref,
+            &href,
             self.to_description_or_fallback().as_ref(),
             self.properties(),
             matches!(self, Self::Transclusion { .. }),
@@ -1199,6 +1423,30 @@ impl<'a> Output<HtmlFormatter> for MathInline<'a> {
     }
 }
 
+impl<'a> Output<HtmlFormatter> for FootnoteReference<'a> {
+    /// Writes a footnote reference in HTML as the number of the footnote
+    /// linking to its definition
+    ///
+    /// ### Example
+    ///
+    /// ```html
+    /// <sup class="footnote-ref"><a href="#fn-note" id="fnref-note">1</a></sup>
+    /// ```
+    fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
+        let id = utils::normalize_id(self.as_str());
+        let number = f.footnote_number(self.as_str());
+        let ref_id = f.ensure_unique_id(&format!("fnref-{}", id)).to_string();
+
+        write!(
+            f,
+            "<sup class=\"footnote-ref\"><a href=\"#fn-{}\" id=\"{}\">{}</a></sup>",
+            id, ref_id, number
+        )?;
+
+        Ok(())
+    }
+}
+
 impl<'a> Output<HtmlFormatter> for Comment<'a> {
     /// Writes a comment in HTML
     fn fmt(&self, f: &mut HtmlFormatter) -> HtmlOutputResult {
@@ -1254,6 +1502,22 @@ impl<'a> Output<HtmlFormatter> for MultiLineComment<'a> {
     }
 }
 
+/// Writes an element, mapping its output back to the region of the element
+/// if the source map is enabled
+fn fmt_located<T: Output<HtmlFormatter>>(
+    element: &Located<T>,
+    f: &mut HtmlFormatter,
+) -> HtmlOutputResult {
+    let start = f.get_content().len();
+    element.as_inner().fmt(f)?;
+
+    if f.config().runtime.source_map {
+        f.map_content_to_region(start, element.region());
+    }
+
+    Ok(())
+}
+
 fn build_complete_id(
     f: &mut HtmlFormatter,
     max_level: usize,
@@ -1276,7 +1540,8 @@ fn build_complete_id(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use crate::WikiUri;
+    use crate::{date::WikiDate, resolve::LinkSpaces};
     use indoc::indoc;
     use similar_asserts::{assert_eq, assert_str_eq};
     use std::{
@@ -1285,7 +1550,6 @@ mod tests {
         convert::TryFrom,
         path::{Path, PathBuf},
     };
-    use uriparse::URIReference;
 
     /// Produces an html config with a singular wiki for some test page
     /// provided
@@ -1308,6 +1572,7 @@ mod tests {
                     wiki.as_ref(),
                     page.as_ref(),
                 ]),
+                ..Default::default()
             },
             ..Default::default()
         }
@@ -1372,6 +1637,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn page_should_map_block_elements_to_regions_if_source_map_enabled() {
+        let page: Page =
+            crate::Language::from_vimwiki_str("Hello\n\n- one\n- two\n")
+                .parse()
+                .unwrap();
+
+        let mut f = HtmlFormatter::new(HtmlConfig {
+            runtime: HtmlRuntimeConfig {
+                source_map: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        page.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            indoc! {r#"
+                <p data-region="0,6">Hello</p>
+                <ul data-region="7,12">
+                <li data-region="7,6"><p data-region="9,4">one</p></li>
+                <li data-region="13,6"><p data-region="15,4">two</p></li>
+                </ul>
+            "#}
+        );
+
+        // Looking up output within the second item yields its paragraph
+        let offset = f.get_content().find("two").unwrap();
+        assert_eq!(
+            f.source_map().find_by_output_offset(offset),
+            Some(Region::new_at_depth(15, 4, 2))
+        );
+    }
+
+    #[test]
+    fn page_should_number_footnotes_by_reference_and_list_them_at_the_end() {
+        let page = Page::new(vec![
+            Located::from(BlockElement::from(FootnoteDefinition::new(
+                Cow::from("a"),
+                text_to_inline_element_container("first"),
+            ))),
+            Located::from(BlockElement::from(Paragraph::new(vec![
+                InlineElementContainer::new(vec![
+                    Located::from(InlineElement::from(Text::from("see"))),
+                    Located::from(InlineElement::from(
+                        FootnoteReference::from("b"),
+                    )),
+                    Located::from(InlineElement::from(
+                        FootnoteReference::from("a"),
+                    )),
+                ]),
+            ]))),
+            Located::from(BlockElement::from(FootnoteDefinition::new(
+                Cow::from("b"),
+                text_to_inline_element_container("second"),
+            ))),
+        ]);
+
+        let mut f = HtmlFormatter::default();
+        page.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            indoc! {r##"
+                <p>see<sup class="footnote-ref"><a href="#fn-b" id="fnref-b">1</a></sup><sup class="footnote-ref"><a href="#fn-a" id="fnref-a">2</a></sup></p>
+                <section class="footnotes">
+                <ol>
+                <li id="fn-b" value="1">second <a href="#fnref-b" class="footnote-backref">&#8617;</a></li>
+                <li id="fn-a" value="2">first <a href="#fnref-a" class="footnote-backref">&#8617;</a></li>
+                </ol>
+                </section>
+            "##}
+        );
+    }
+
     #[test]
     fn blockquote_with_multiple_line_groups_should_output_blockquote_tag_with_paragraph_for_each_group_of_lines(
     ) {
@@ -1420,6 +1761,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn blockquote_with_nested_quotes_should_output_nested_blockquote_tags() {
+        let blockquote = Blockquote::with_depths(
+            vec![
+                Cow::from("outer"),
+                Cow::from("inner1"),
+                Cow::from("inner2"),
+                Cow::from("outer again"),
+            ],
+            vec![1, 2, 2, 1],
+        );
+        let mut f = HtmlFormatter::default();
+        blockquote.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            indoc! {"
+                <blockquote>
+                <p>outer</p>
+                <blockquote>
+                <p>inner1 inner2</p>
+                </blockquote>
+                <p>outer again</p>
+                </blockquote>
+            "}
+            .trim(),
+        );
+    }
+
     #[test]
     fn blockquote_should_escape_html_in_each_line_of_a_singular_line_group() {
         let blockquote = Blockquote::new(vec![
@@ -1541,7 +1911,7 @@ mod tests {
 
     #[test]
     fn divider_should_output_hr_tag() {
-        let divider = Divider;
+        let divider = Divider::default();
 
         let mut f = HtmlFormatter::default();
         divider.fmt(&mut f).unwrap();
@@ -1625,6 +1995,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn header_should_be_prefixed_with_number_if_configured() {
+        let mut f = HtmlFormatter::new(HtmlConfig {
+            header: HtmlHeaderConfig {
+                numbering: 2,
+                numbering_sym: String::from("."),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        for (text, level) in [("a", 1), ("b", 2), ("c", 3), ("d", 2)] {
+            Header::new(text_to_inline_element_container(text), level, false)
+                .fmt(&mut f)
+                .unwrap();
+        }
+
+        assert_str_eq!(
+            f.get_content(),
+            [
+                "<h1 id=\"a\" class=\"header\"><a href=\"#a\">a</a></h1>",
+                "<div id=\"a-b\"><h2 id=\"b\" class=\"header\">",
+                "<a href=\"#a-b\">1. b</a></h2></div>",
+                "<div id=\"a-b-c\"><h3 id=\"c\" class=\"header\">",
+                "<a href=\"#a-b-c\">1.1. c</a></h3></div>",
+                "<div id=\"a-d\"><h2 id=\"d\" class=\"header\">",
+                "<a href=\"#a-d\">2. d</a></h2></div>",
+            ]
+            .join(""),
+        );
+
+        let toc = f.table_of_contents();
+        let numbers: Vec<String> =
+            toc.entries().iter().map(|x| x.number.to_string()).collect();
+        assert_eq!(numbers, vec!["1", "1.1", "1.1.1", "1.2"]);
+    }
+
     #[test]
     fn header_should_escape_html_in_ids_for_toc() {
         let header =
@@ -1635,6 +2042,7 @@ mod tests {
         let mut f = HtmlFormatter::new(HtmlConfig {
             header: HtmlHeaderConfig {
                 table_of_contents: String::from("<test>"),
+                ..Default::default()
             },
             ..Default::default()
         });
@@ -1783,6 +2191,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn list_should_output_ordered_list_type_if_not_numbered() {
+        let list = List::new(vec![Located::from(ListItem::new(
+            ListItemType::Ordered(OrderedListItemType::UppercaseRoman),
+            ListItemSuffix::Paren,
+            0,
+            ListItemContents::new(vec![Located::from(BlockElement::from(
+                Paragraph::new(vec![text_to_inline_element_container(
+                    "some list item",
+                )]),
+            ))]),
+            ListItemAttributes::default(),
+        ))]);
+        let mut f = HtmlFormatter::default();
+        list.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            indoc! {r#"
+                <ol type="I">
+                <li><p>some list item</p></li>
+                </ol>
+            "#}
+            .trim(),
+        );
+    }
+
     #[test]
     fn list_should_output_unordered_list_if_unordered_type() {
         let list = List::new(vec![Located::from(ListItem::new(
@@ -1829,6 +2264,49 @@ mod tests {
         assert_str_eq!(f.get_content(), "<li><p>some list item</p></li>");
     }
 
+    #[test]
+    fn list_item_should_break_lines_based_on_list_config() {
+        let item = ListItem::new(
+            ListItemType::Unordered(UnorderedListItemType::Hyphen),
+            ListItemSuffix::None,
+            0,
+            ListItemContents::new(vec![Located::from(BlockElement::from(
+                Paragraph::new(vec![
+                    text_to_inline_element_container("some list item"),
+                    text_to_inline_element_container("on two lines"),
+                ]),
+            ))]),
+            ListItemAttributes::default(),
+        );
+        let mut f = HtmlFormatter::new(HtmlConfig {
+            list: HtmlListConfig {
+                ignore_newline: false,
+            },
+            ..Default::default()
+        });
+        item.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            "<li><p>some list item<br />on two lines</p></li>"
+        );
+
+        // Paragraphs outside of the list item follow the paragraph config
+        let paragraph = Paragraph::new(vec![
+            text_to_inline_element_container("some text"),
+            text_to_inline_element_container("and more text"),
+        ]);
+        let mut f = HtmlFormatter::new(HtmlConfig {
+            list: HtmlListConfig {
+                ignore_newline: false,
+            },
+            ..Default::default()
+        });
+        paragraph.fmt(&mut f).unwrap();
+
+        assert_str_eq!(f.get_content(), "<p>some text and more text</p>");
+    }
+
     #[test]
     fn list_item_should_support_adding_class_based_on_todo_status() {
         let mut item = ListItem::new(
@@ -1945,11 +2423,11 @@ mod tests {
 
     #[test]
     fn placeholder_should_set_date_if_specified() {
-        let placeholder = Placeholder::Date(NaiveDate::from_ymd(2021, 4, 27));
+        let placeholder = Placeholder::Date(WikiDate::from_ymd(2021, 4, 27));
         let mut f = HtmlFormatter::default();
         placeholder.fmt(&mut f).unwrap();
 
-        assert_eq!(f.get_date(), Some(&NaiveDate::from_ymd(2021, 4, 27)));
+        assert_eq!(f.get_date(), Some(&WikiDate::from_ymd(2021, 4, 27)));
     }
 
     #[test]
@@ -1961,6 +2439,56 @@ mod tests {
         assert_eq!(f.get_template(), Some(Path::new("template file")));
     }
 
+    #[test]
+    fn placeholder_should_write_plain_html_as_is() {
+        let placeholder =
+            Placeholder::other_from_str("plainhtml", "<hr class=\"x\" />");
+        let mut f = HtmlFormatter::default();
+        placeholder.fmt(&mut f).unwrap();
+
+        assert_str_eq!(f.get_content(), "<hr class=\"x\" />");
+    }
+
+    #[test]
+    fn placeholder_should_use_registered_directive_handlers() {
+        struct Badge;
+
+        impl directives::DirectiveHandler for Badge {
+            fn name(&self) -> &str {
+                "badge"
+            }
+
+            fn html(
+                &self,
+                value: &str,
+            ) -> Result<String, directives::DirectiveError> {
+                if value.is_empty() {
+                    Err("missing text".into())
+                } else {
+                    Ok(format!("<span class=\"badge\">{}</span>", value))
+                }
+            }
+        }
+
+        // Unknown placeholders produce nothing
+        let placeholder = Placeholder::other_from_str("badge", "new");
+        let mut f = HtmlFormatter::default();
+        placeholder.fmt(&mut f).unwrap();
+        assert_str_eq!(f.get_content(), "");
+
+        directives::register(Badge);
+
+        let mut f = HtmlFormatter::default();
+        placeholder.fmt(&mut f).unwrap();
+        assert_str_eq!(f.get_content(), "<span class=\"badge\">new</span>");
+
+        let result = Placeholder::other_from_str("badge", "")
+            .fmt(&mut HtmlFormatter::default());
+        assert!(matches!(result, Err(HtmlOutputError::DirectiveFailed(_))));
+
+        directives::unregister("badge");
+    }
+
     #[test]
     fn code_block_should_output_pre_code_tags_for_clientside_render() {
         let code = CodeBlock::from_lines(vec!["some lines", "of code"]);
@@ -2467,6 +2995,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decorated_text_should_nest_tags_of_nested_content() {
+        let decorated_text = DecoratedText::Bold(vec![
+            Located::from(DecoratedTextContent::Text(Text::from("a "))),
+            Located::from(DecoratedTextContent::DecoratedText(
+                DecoratedText::Italic(vec![
+                    Located::from(DecoratedTextContent::Text(Text::from("b "))),
+                    Located::from(DecoratedTextContent::Code(
+                        CodeInline::from("c"),
+                    )),
+                ]),
+            )),
+            Located::from(DecoratedTextContent::Text(Text::from(" e"))),
+        ]);
+        let mut f = HtmlFormatter::default();
+        decorated_text.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            r#"<strong id="a-b-c-e">a <em>b <code>c</code></em> e</strong>"#
+        );
+    }
+
     #[test]
     fn keyword_should_output_span_with_class_for_todo() {
         let keyword = Keyword::Todo;
@@ -2477,6 +3028,25 @@ mod tests {
         assert_str_eq!(f.get_content(), r#"<span class="todo">TODO</span>"#);
     }
 
+    #[test]
+    fn keyword_should_output_span_with_configured_class() {
+        let mut config = HtmlConfig::default();
+        config
+            .keyword
+            .classes
+            .insert(String::from("WAITING"), String::from("waiting"));
+        config.keyword.classes.remove("TODO");
+
+        let mut f = HtmlFormatter::new(config);
+        Keyword::Other(String::from("WAITING")).fmt(&mut f).unwrap();
+        Keyword::Todo.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            r#"<span class="waiting">WAITING</span>TODO"#
+        );
+    }
+
     #[test]
     fn keyword_should_output_self_in_all_caps() {
         let keyword = Keyword::Done;
@@ -2489,10 +3059,8 @@ mod tests {
 
     #[test]
     fn wiki_link_should_output_a_tag() {
-        let link = Link::new_wiki_link(
-            URIReference::try_from("some/page").unwrap(),
-            None,
-        );
+        let link =
+            Link::new_wiki_link(WikiUri::try_from("some/page").unwrap(), None);
         let mut f = HtmlFormatter::new(test_html_config("wiki", "test.wiki"));
         link.fmt(&mut f).unwrap();
 
@@ -2505,7 +3073,7 @@ mod tests {
     #[test]
     fn wiki_link_should_support_anchors() {
         let link = Link::new_wiki_link(
-            URIReference::try_from("some/page#some-anchor").unwrap(),
+            WikiUri::try_from("some/page#some-anchor").unwrap(),
             None,
         );
         let mut f = HtmlFormatter::new(test_html_config("wiki", "test.wiki"));
@@ -2517,10 +3085,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn wiki_link_should_follow_pretty_urls_of_wiki() {
+        let link =
+            Link::new_wiki_link(WikiUri::try_from("/some/page").unwrap(), None);
+        let mut config = test_html_config("wiki", "a/test.wiki");
+        config.wikis[0].output.pretty_urls = true;
+        let mut f = HtmlFormatter::new(config);
+        link.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            r#"<a href="../../some/page/">/some/page</a>"#
+        );
+    }
+
+    #[test]
+    fn wiki_link_should_follow_flattened_output_of_wiki() {
+        let link = Link::new_wiki_link(
+            WikiUri::try_from("/some/page#anchor").unwrap(),
+            None,
+        );
+        let mut config = test_html_config("wiki", "a/test.wiki");
+        config.wikis[0].output.flatten = true;
+        config.wikis[0].output.ext = String::from("htm");
+        let mut f = HtmlFormatter::new(config);
+        link.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            r#"<a href="some-page.htm#anchor">/some/page#anchor</a>"#
+        );
+    }
+
+    #[test]
+    fn wiki_link_should_follow_link_spaces_of_wiki() {
+        let link = Link::new_wiki_link(
+            WikiUri::try_from("some%20page#top").unwrap(),
+            None,
+        );
+        let href = |config: HtmlConfig| {
+            let mut f = HtmlFormatter::new(config);
+            link.fmt(&mut f).unwrap();
+            f.get_content().to_string()
+        };
+
+        let config = test_html_config("wiki", "test.wiki");
+        assert_str_eq!(
+            href(config),
+            r#"<a href="some%20page.html#top">some page#top</a>"#
+        );
+
+        let mut config = test_html_config("wiki", "test.wiki");
+        config.wikis[0].output.link_spaces = LinkSpaces::Space;
+        assert_str_eq!(
+            href(config),
+            r#"<a href="some page.html#top">some page#top</a>"#
+        );
+
+        let mut config = test_html_config("wiki", "test.wiki");
+        config.wikis[0].output.link_spaces = LinkSpaces::Underscore;
+        assert_str_eq!(
+            href(config),
+            r#"<a href="some_page.html#top">some page#top</a>"#
+        );
+
+        // Pages whose files use underscores keep them within their output
+        let mut config = test_html_config("wiki", "test.wiki");
+        config.wikis[0].link_spaces = LinkSpaces::Underscore;
+        assert_str_eq!(
+            href(config),
+            r#"<a href="some_page.html#top">some page#top</a>"#
+        );
+    }
+
     #[test]
     fn wiki_link_should_support_standalone_anchors() {
         let link = Link::new_wiki_link(
-            URIReference::try_from("#some-anchor").unwrap(),
+            WikiUri::try_from("#some-anchor").unwrap(),
             None,
         );
         let mut f = HtmlFormatter::new(test_html_config("wiki", "test.wiki"));
@@ -2535,7 +3177,7 @@ mod tests {
     #[test]
     fn wiki_link_should_support_text_description() {
         let link = Link::new_wiki_link(
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Description::from("some description"),
         );
         let mut f = HtmlFormatter::new(test_html_config("wiki", "test.wiki"));
@@ -2550,7 +3192,7 @@ mod tests {
     #[test]
     fn wiki_link_should_support_transclusion_link_description() {
         let link = Link::new_wiki_link(
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Description::try_from_uri_ref_str("some/img.png").unwrap(),
         );
         let mut f = HtmlFormatter::new(test_html_config("wiki", "test.wiki"));
@@ -2566,7 +3208,7 @@ mod tests {
     fn indexed_inter_wiki_link_should_output_a_tag() {
         let link = Link::new_indexed_interwiki_link(
             1,
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             None,
         );
 
@@ -2587,7 +3229,7 @@ mod tests {
     fn indexed_inter_wiki_link_should_support_anchors() {
         let link = Link::new_indexed_interwiki_link(
             1,
-            URIReference::try_from("some/page#some-anchor").unwrap(),
+            WikiUri::try_from("some/page#some-anchor").unwrap(),
             None,
         );
 
@@ -2608,7 +3250,7 @@ mod tests {
     fn indexed_inter_wiki_link_should_support_text_description() {
         let link = Link::new_indexed_interwiki_link(
             1,
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Description::from("some description"),
         );
 
@@ -2629,7 +3271,7 @@ mod tests {
     fn indexed_inter_wiki_link_should_support_transclusion_link_description() {
         let link = Link::new_indexed_interwiki_link(
             1,
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Description::try_from_uri_ref_str("some/img.png").unwrap(),
         );
 
@@ -2650,7 +3292,7 @@ mod tests {
     fn named_inter_wiki_link_should_output_a_tag() {
         let link = Link::new_named_interwiki_link(
             "my-wiki",
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             None,
         );
 
@@ -2671,7 +3313,7 @@ mod tests {
     fn named_inter_wiki_link_should_support_anchors() {
         let link = Link::new_named_interwiki_link(
             "my-wiki",
-            URIReference::try_from("some/page#some-anchor").unwrap(),
+            WikiUri::try_from("some/page#some-anchor").unwrap(),
             None,
         );
 
@@ -2692,7 +3334,7 @@ mod tests {
     fn named_inter_wiki_link_should_support_text_description() {
         let link = Link::new_named_interwiki_link(
             "my-wiki",
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Description::from("some description"),
         );
 
@@ -2713,7 +3355,7 @@ mod tests {
     fn named_inter_wiki_link_should_support_transclusion_link_description() {
         let link = Link::new_named_interwiki_link(
             "my-wiki",
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Description::try_from_uri_ref_str("some/img.png").unwrap(),
         );
 
@@ -2733,7 +3375,7 @@ mod tests {
     #[test]
     fn diary_link_should_output_a_tag() {
         let link =
-            Link::new_diary_link(NaiveDate::from_ymd(2021, 5, 27), None, None);
+            Link::new_diary_link(WikiDate::from_ymd(2021, 5, 27), None, None);
         let mut f = HtmlFormatter::new(test_html_config("wiki", "test.wiki"));
         link.fmt(&mut f).unwrap();
 
@@ -2746,7 +3388,7 @@ mod tests {
     #[test]
     fn diary_link_should_support_text_description() {
         let link = Link::new_diary_link(
-            NaiveDate::from_ymd(2021, 5, 27),
+            WikiDate::from_ymd(2021, 5, 27),
             Description::from("some description"),
             None,
         );
@@ -2762,7 +3404,7 @@ mod tests {
     #[test]
     fn diary_link_should_support_transclusion_link_description() {
         let link = Link::new_diary_link(
-            NaiveDate::from_ymd(2021, 5, 27),
+            WikiDate::from_ymd(2021, 5, 27),
             Description::try_from_uri_ref_str("some/img.png").unwrap(),
             None,
         );
@@ -2778,7 +3420,7 @@ mod tests {
     #[test]
     fn raw_link_should_output_a_tag() {
         let link = Link::new_raw_link(
-            URIReference::try_from("https://example.com").unwrap(),
+            WikiUri::try_from("https://example.com").unwrap(),
         );
 
         let mut f = HtmlFormatter::default();
@@ -2790,10 +3432,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn email_link_should_output_a_tag_with_mailto_href() {
+        let link = Link::new_email_link("user@example.com");
+
+        let mut f = HtmlFormatter::default();
+        link.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            r#"<a href="mailto:user@example.com">user@example.com</a>"#
+        );
+    }
+
+    #[test]
+    fn raw_link_should_be_rendered_by_registered_scheme_handler() {
+        schemes::register(
+            schemes::SchemeTemplate::new(
+                "jira",
+                "https://jira.example.com/browse/{value}",
+            )
+            .with_label("{value}"),
+        );
+
+        let link =
+            Link::new_raw_link(WikiUri::try_from("jira:PROJ-123").unwrap());
+
+        let mut f = HtmlFormatter::default();
+        link.fmt(&mut f).unwrap();
+        schemes::unregister("jira");
+
+        assert_str_eq!(
+            f.get_content(),
+            r#"<a href="https://jira.example.com/browse/PROJ-123">PROJ-123</a>"#
+        );
+    }
+
     #[test]
     fn transclusion_link_should_output_img_tag() {
         let link = Link::new_transclusion_link(
-            URIReference::try_from("https://example.com/img.jpg").unwrap(),
+            WikiUri::try_from("https://example.com/img.jpg").unwrap(),
             None,
             None,
         );
@@ -2810,7 +3488,7 @@ mod tests {
     #[test]
     fn transclusion_link_should_support_local_uris() {
         let link = Link::new_transclusion_link(
-            URIReference::try_from("img/pic.png").unwrap(),
+            WikiUri::try_from("img/pic.png").unwrap(),
             None,
             None,
         );
@@ -2821,10 +3499,27 @@ mod tests {
         assert_str_eq!(f.get_content(), r#"<img src="img/pic.png" />"#);
     }
 
+    #[test]
+    fn transclusion_link_should_keep_uris_of_windows_paths() {
+        let link = Link::new_transclusion_link(
+            WikiUri::try_from("file:///C:/img/pic.png").unwrap(),
+            None,
+            None,
+        );
+
+        let mut f = HtmlFormatter::new(test_html_config("wiki", "test.wiki"));
+        link.fmt(&mut f).unwrap();
+
+        assert_str_eq!(
+            f.get_content(),
+            r#"<img src="file:///C:/img/pic.png" />"#
+        );
+    }
+
     #[test]
     fn transclusion_link_should_use_description_as_alt_text() {
         let link = Link::new_transclusion_link(
-            URIReference::try_from("https://example.com/img.jpg").unwrap(),
+            WikiUri::try_from("https://example.com/img.jpg").unwrap(),
             Some(Description::from("some description")),
             None,
         );
@@ -2845,7 +3540,7 @@ mod tests {
         properties.insert(Cow::from("key2"), Cow::from("value2"));
 
         let link = Link::new_transclusion_link(
-            URIReference::try_from("https://example.com/img.jpg").unwrap(),
+            WikiUri::try_from("https://example.com/img.jpg").unwrap(),
             Some(Description::from("some description")),
             properties,
         );
@@ -2853,13 +3548,28 @@ mod tests {
         let mut f = HtmlFormatter::default();
         link.fmt(&mut f).unwrap();
 
-        // NOTE: The order of properties isn't guaranteed, so we have to check
-        //       both possibilities
-        let equal1 = f.get_content()
-            == r#"<img src="https://example.com/img.jpg" alt="some description" key1="value1" key2="value2" />"#;
-        let equal2 = f.get_content()
-            == r#"<img src="https://example.com/img.jpg" alt="some description" key2="value2" key1="value1" />"#;
-        assert!(equal1 || equal2);
+        assert_str_eq!(
+            f.get_content(),
+            r#"<img src="https://example.com/img.jpg" alt="some description" key1="value1" key2="value2" />"#
+        );
+    }
+
+    #[test]
+    fn transclusion_link_should_skip_invalid_attr_names_on_img() {
+        let mut properties: HashMap<Cow<str>, Cow<str>> = HashMap::new();
+        properties.insert(Cow::from("width"), Cow::from("150"));
+        properties.insert(Cow::from("on\"x"), Cow::from("y"));
+
+        let link = Link::new_transclusion_link(
+            WikiUri::try_from("img.png").unwrap(),
+            None,
+            properties,
+        );
+
+        let mut f = HtmlFormatter::default();
+        link.fmt(&mut f).unwrap();
+
+        assert_str_eq!(f.get_content(), r#"<img src="img.png" width="150" />"#);
     }
 
     #[test]
@@ -2868,8 +3578,7 @@ mod tests {
         properties.insert(Cow::from("key1"), Cow::from("<test>value1</test>"));
 
         let link = Link::new_transclusion_link(
-            URIReference::try_from("https://example.com/img.jpg?a=b&c=d")
-                .unwrap(),
+            WikiUri::try_from("https://example.com/img.jpg?a=b&c=d").unwrap(),
             Some(Description::from("<test>some description</test>")),
             properties,
         );
@@ -0,0 +1,301 @@
+use super::{utils, HtmlWikiConfig};
+use crate::date::WikiDate;
+use std::{collections::BTreeMap, fmt::Write};
+use voca_rs::escape;
+
+/// Produces the link to the page with the given name from a page whose path
+/// to the root of the wiki is `root_path`
+fn href(name: &str, root_path: &str, wiki: &HtmlWikiConfig) -> String {
+    let href = format!("{}{}", root_path, wiki.page_url(name));
+    if href.is_empty() {
+        String::from("./")
+    } else {
+        href
+    }
+}
+
+/// Produces html navigation from the root of a wiki to the page with the
+/// given name (e.g. `projects/todo`), linking to the index page of each
+/// directory along the way
+///
+/// `root_path` is the path from the page to the root of the wiki, ending in
+/// `/` unless the page is at the root.
+pub fn breadcrumbs_to_html(
+    name: &str,
+    root_path: &str,
+    wiki: &HtmlWikiConfig,
+) -> String {
+    let index = wiki.index.as_str();
+    let mut parts: Vec<&str> =
+        name.split('/').filter(|x| !x.is_empty()).collect();
+
+    // NOTE: The index page of the wiki is already the first breadcrumb
+    if parts == [index] {
+        parts.clear();
+    }
+
+    let mut html = String::from("<nav class=\"breadcrumbs\">");
+    let _ = write!(
+        html,
+        "<a href=\"{}\">{}</a>",
+        href(index, root_path, wiki),
+        escape::escape_html(index)
+    );
+
+    let mut dir = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        html.push_str(" / ");
+        if i + 1 == parts.len() {
+            html.push_str(&escape::escape_html(part));
+        } else {
+            dir.push_str(part);
+            dir.push('/');
+            let _ = write!(
+                html,
+                "<a href=\"{}\">{}</a>",
+                href(&format!("{}{}", dir, index), root_path, wiki),
+                escape::escape_html(part)
+            );
+        }
+    }
+
+    html.push_str("</nav>");
+    html
+}
+
+/// Returns the date of the diary page with the given name, or None if the
+/// page is not directly within the diary directory or not named by a date
+fn diary_date(name: &str, diary_dir: &str) -> Option<WikiDate> {
+    let (dir, stem) = name.rsplit_once('/').unwrap_or(("", name));
+    if dir != diary_dir.trim_matches('/') {
+        return None;
+    }
+    WikiDate::parse_from_str(stem, "%Y-%m-%d").ok()
+}
+
+/// Produces html links to the previous and next entries of the diary page
+/// with the given name among the names of all pages, or an empty string if
+/// the page is not a diary entry
+///
+/// `root_path` is the path from the page to the root of the wiki, ending in
+/// `/` unless the page is at the root.
+pub fn diary_nav_to_html(
+    name: &str,
+    root_path: &str,
+    wiki: &HtmlWikiConfig,
+    pages: &[String],
+) -> String {
+    let diary_dir = utils::path_to_uri_string(&wiki.diary_rel_path);
+    let diary_dir = diary_dir.as_str();
+    let date = match diary_date(name, diary_dir) {
+        Some(date) => date,
+        None => return String::new(),
+    };
+
+    let dates: Vec<WikiDate> = pages
+        .iter()
+        .filter_map(|x| diary_date(x, diary_dir))
+        .collect();
+    let previous = dates.iter().filter(|x| **x < date).max();
+    let next = dates.iter().filter(|x| **x > date).min();
+
+    let mut html = String::from("<nav class=\"diary-nav\">");
+    let diary_href = |date: &WikiDate| {
+        let name = format!("{}/{}", diary_dir.trim_matches('/'), date);
+        href(name.trim_start_matches('/'), root_path, wiki)
+    };
+    if let Some(date) = previous {
+        let _ = write!(
+            html,
+            "<a class=\"previous\" href=\"{}\">&larr; {}</a>",
+            diary_href(date),
+            date.format("%Y-%m-%d")
+        );
+    }
+    if let Some(date) = next {
+        let _ = write!(
+            html,
+            "<a class=\"next\" href=\"{}\">{} &rarr;</a>",
+            diary_href(date),
+            date.format("%Y-%m-%d")
+        );
+    }
+    html.push_str("</nav>");
+    html
+}
+
+/// Represents a directory of pages within the sidebar
+#[derive(Default)]
+struct PageTree<'a> {
+    pages: Vec<(&'a str, &'a str)>,
+    dirs: BTreeMap<&'a str, PageTree<'a>>,
+}
+
+impl<'a> PageTree<'a> {
+    fn insert(&mut self, name: &'a str, rest: &'a str) {
+        match rest.split_once('/') {
+            Some((dir, rest)) => {
+                self.dirs.entry(dir).or_default().insert(name, rest)
+            }
+            None => self.pages.push((name, rest)),
+        }
+    }
+
+    fn write(
+        &self,
+        html: &mut String,
+        current: &str,
+        root_path: &str,
+        wiki: &HtmlWikiConfig,
+    ) {
+        html.push_str("<ul>");
+        for (name, label) in self.pages.iter() {
+            let class = if *name == current {
+                " class=\"current\""
+            } else {
+                ""
+            };
+            let _ = write!(
+                html,
+                "<li><a{} href=\"{}\">{}</a></li>",
+                class,
+                href(name, root_path, wiki),
+                escape::escape_html(label)
+            );
+        }
+        for (dir, tree) in self.dirs.iter() {
+            let _ = write!(html, "<li>{}", escape::escape_html(dir));
+            tree.write(html, current, root_path, wiki);
+            html.push_str("</li>");
+        }
+        html.push_str("</ul>");
+    }
+}
+
+/// Produces an html tree of links to every page of a wiki grouped by
+/// directory, marking the link to the page with the given name as current
+///
+/// `root_path` is the path from the page to the root of the wiki, ending in
+/// `/` unless the page is at the root.
+pub fn sidebar_to_html(
+    name: &str,
+    root_path: &str,
+    wiki: &HtmlWikiConfig,
+    pages: &[String],
+) -> String {
+    let mut pages: Vec<&str> = pages.iter().map(String::as_str).collect();
+    pages.sort_unstable();
+    pages.dedup();
+
+    let mut tree = PageTree::default();
+    for page in pages {
+        tree.insert(page, page);
+    }
+
+    let mut html = String::from("<nav class=\"sidebar\">");
+    tree.write(&mut html, name, root_path, wiki);
+    html.push_str("</nav>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HtmlOutputConfig;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    fn wiki() -> HtmlWikiConfig {
+        HtmlWikiConfig::default()
+    }
+
+    #[test]
+    fn breadcrumbs_to_html_should_link_to_each_directory_index() {
+        assert_eq!(
+            breadcrumbs_to_html("a/b/page", "../../", &wiki()),
+            concat!(
+                "<nav class=\"breadcrumbs\">",
+                "<a href=\"../../index.html\">index</a> / ",
+                "<a href=\"../../a/index.html\">a</a> / ",
+                "<a href=\"../../a/b/index.html\">b</a> / page",
+                "</nav>",
+            )
+        );
+        assert_eq!(
+            breadcrumbs_to_html("index", "", &wiki()),
+            "<nav class=\"breadcrumbs\"><a href=\"index.html\">index</a></nav>"
+        );
+    }
+
+    #[test]
+    fn diary_nav_to_html_should_link_to_adjacent_entries() {
+        let pages = names(&[
+            "diary/2021-04-01",
+            "diary/2021-04-09",
+            "diary/2021-04-05",
+            "diary/diary",
+            "2021-04-07",
+        ]);
+
+        assert_eq!(
+            diary_nav_to_html("diary/2021-04-05", "../", &wiki(), &pages),
+            concat!(
+                "<nav class=\"diary-nav\">",
+                "<a class=\"previous\" href=\"../diary/2021-04-01.html\">&larr; 2021-04-01</a>",
+                "<a class=\"next\" href=\"../diary/2021-04-09.html\">2021-04-09 &rarr;</a>",
+                "</nav>",
+            )
+        );
+        assert_eq!(diary_nav_to_html("2021-04-07", "", &wiki(), &pages), "");
+    }
+
+    #[test]
+    fn sidebar_to_html_should_group_pages_by_directory() {
+        let pages = names(&["projects/b", "index", "projects/a", "z"]);
+        assert_eq!(
+            sidebar_to_html("projects/a", "../", &wiki(), &pages),
+            concat!(
+                "<nav class=\"sidebar\"><ul>",
+                "<li><a href=\"../index.html\">index</a></li>",
+                "<li><a href=\"../z.html\">z</a></li>",
+                "<li>projects<ul>",
+                "<li><a class=\"current\" href=\"../projects/a.html\">a</a></li>",
+                "<li><a href=\"../projects/b.html\">b</a></li>",
+                "</ul></li>",
+                "</ul></nav>",
+            )
+        );
+    }
+
+    #[test]
+    fn navigation_should_follow_output_mapping_of_wiki() {
+        let wiki = HtmlWikiConfig {
+            output: HtmlOutputConfig {
+                pretty_urls: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            breadcrumbs_to_html("a/page", "../../", &wiki),
+            concat!(
+                "<nav class=\"breadcrumbs\">",
+                "<a href=\"../../\">index</a> / ",
+                "<a href=\"../../a/\">a</a> / page",
+                "</nav>",
+            )
+        );
+        assert_eq!(
+            sidebar_to_html("index", "", &wiki, &names(&["index", "a"])),
+            concat!(
+                "<nav class=\"sidebar\"><ul>",
+                "<li><a href=\"a/\">a</a></li>",
+                "<li><a class=\"current\" href=\"./\">index</a></li>",
+                "</ul></nav>",
+            )
+        );
+    }
+}
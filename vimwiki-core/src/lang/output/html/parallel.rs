@@ -0,0 +1,111 @@
+use super::{HtmlConfig, HtmlFormatter, HtmlOutputError, Output, ToHtmlPage};
+use rayon::prelude::*;
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Represents how many pages of a batch have been rendered
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HtmlRenderProgress {
+    /// Total pages rendered so far, including those that failed to render
+    pub completed: usize,
+
+    /// Total pages within the batch
+    pub total: usize,
+}
+
+/// Renders each page of the batch, paired with the path to its file, to an
+/// html page in parallel using a work-stealing pool
+///
+/// The config is shared by every page, with the runtime page and wiki of the
+/// config set for each page before it is rendered. Results are returned in
+/// the same order as the pages, and `on_progress` is invoked from the pool
+/// after each page is rendered, so pages may complete out of order.
+///
+/// Pages are rendered using rayon's global pool, whose size can be changed
+/// with the `RAYON_NUM_THREADS` environment variable.
+pub fn render_html_pages<T, F>(
+    pages: &[(PathBuf, &T)],
+    config: &HtmlConfig,
+    on_progress: F,
+) -> Vec<Result<String, HtmlOutputError>>
+where
+    T: Output<HtmlFormatter> + Sync,
+    F: Fn(HtmlRenderProgress) + Sync,
+{
+    let total = pages.len();
+    let completed = AtomicUsize::new(0);
+
+    pages
+        .par_iter()
+        .map(|(path, page)| {
+            let mut config = config.clone();
+            let wiki_index = config.find_wiki_index_by_path(path);
+            config.map_runtime(|mut rt| {
+                rt.page = path.to_path_buf();
+                rt.wiki_index = wiki_index;
+                rt
+            });
+
+            let result = page.to_html_page(config);
+            on_progress(HtmlRenderProgress {
+                completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                total,
+            });
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HtmlTemplateConfig, Language, Page};
+    use std::sync::Mutex;
+
+    #[test]
+    fn render_html_pages_should_render_every_page_in_order() {
+        let pages: Vec<(PathBuf, Page)> = (0..50)
+            .map(|i| {
+                let text = format!("page {}", i);
+                let page: Page =
+                    Language::from_vimwiki_str(&text).parse().unwrap();
+                (
+                    PathBuf::from(format!("/wiki/{}.wiki", i)),
+                    page.into_owned(),
+                )
+            })
+            .collect();
+        let config = HtmlConfig {
+            template: HtmlTemplateConfig::from_text("%title%|%content%"),
+            ..Default::default()
+        };
+
+        let pages: Vec<(PathBuf, &Page)> = pages
+            .iter()
+            .map(|(path, page)| (path.clone(), page))
+            .collect();
+        let progress = Mutex::new(Vec::new());
+        let results = render_html_pages(&pages, &config, |x| {
+            progress.lock().unwrap().push(x);
+        });
+
+        assert_eq!(results.len(), 50);
+        for (i, result) in results.into_iter().enumerate() {
+            assert_eq!(result.unwrap(), format!("{}|<p>page {}</p>\n", i, i));
+        }
+
+        let mut progress = progress.into_inner().unwrap();
+        progress.sort_unstable_by_key(|x| x.completed);
+        assert_eq!(
+            progress,
+            (1..=50)
+                .map(|completed| HtmlRenderProgress {
+                    completed,
+                    total: 50
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+}
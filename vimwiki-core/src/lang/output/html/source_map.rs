@@ -0,0 +1,164 @@
+use crate::lang::elements::Region;
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, path::PathBuf};
+
+/// Represents the mapping of a portion of HTML output back to the region of
+/// the element that produced it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceMapEntry {
+    /// Path to the file of the page containing the element, or None if not
+    /// known (such as when rendering text that is not from a file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+
+    /// Region of the element within its page
+    pub region: Region,
+
+    /// Byte range of the HTML produced by the element
+    pub output: Range<usize>,
+}
+
+/// Represents a map from HTML output back to the regions of the elements
+/// that produced it, which can be used to find the source of some content
+/// within rendered HTML (e.g. to jump from a preview to an editor)
+///
+/// Entries are ordered by when the element finished being written, meaning
+/// that nested elements come before the elements that contain them. As the
+/// HTML of several pages can be composed into one output, each entry keeps
+/// the file of its element so it points back to the file it came from.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceMap {
+    entries: Vec<SourceMapEntry>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all entries of the map
+    pub fn entries(&self) -> &[SourceMapEntry] {
+        &self.entries
+    }
+
+    /// Returns true if the map has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds an entry mapping the range of output to the region of an
+    /// element whose file is not known
+    pub fn insert(&mut self, region: Region, output: Range<usize>) {
+        self.insert_from(None, region, output);
+    }
+
+    /// Adds an entry mapping the range of output to the region of an element
+    /// within the file at the path
+    pub fn insert_from(
+        &mut self,
+        path: Option<PathBuf>,
+        region: Region,
+        output: Range<usize>,
+    ) {
+        self.entries.push(SourceMapEntry {
+            path,
+            region,
+            output,
+        });
+    }
+
+    /// Appends the entries of another map whose output was placed at the
+    /// byte offset within the output of this map, such as when the HTML of
+    /// one page is embedded within that of another, where each appended
+    /// entry keeps the file of its element
+    pub fn append(&mut self, other: SourceMap, offset: usize) {
+        self.entries
+            .extend(other.entries.into_iter().map(|mut entry| {
+                entry.output =
+                    entry.output.start + offset..entry.output.end + offset;
+                entry
+            }));
+    }
+
+    /// Finds the entry of the innermost element whose output contains the
+    /// byte offset
+    pub fn find_entry_by_output_offset(
+        &self,
+        offset: usize,
+    ) -> Option<&SourceMapEntry> {
+        self.entries
+            .iter()
+            .filter(|x| x.output.contains(&offset))
+            .min_by_key(|x| x.output.len())
+    }
+
+    /// Finds the region of the innermost element whose output contains the
+    /// byte offset
+    pub fn find_by_output_offset(&self, offset: usize) -> Option<Region> {
+        self.find_entry_by_output_offset(offset).map(|x| x.region)
+    }
+
+    /// Updates entries to account for `len` bytes inserted into the output
+    /// at the byte offset
+    pub(super) fn shift(&mut self, offset: usize, len: usize) {
+        for entry in self.entries.iter_mut() {
+            if entry.output.start >= offset {
+                entry.output.start += len;
+            }
+            if entry.output.end > offset {
+                entry.output.end += len;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_by_output_offset_should_return_innermost_region() {
+        let mut map = SourceMap::new();
+        map.insert(Region::new(2, 3), 4..10);
+        map.insert(Region::new(0, 10), 0..20);
+
+        assert_eq!(map.find_by_output_offset(5), Some(Region::new(2, 3)));
+        assert_eq!(map.find_by_output_offset(15), Some(Region::new(0, 10)));
+        assert_eq!(map.find_by_output_offset(20), None);
+    }
+
+    #[test]
+    fn shift_should_move_entries_after_inserted_bytes() {
+        let mut map = SourceMap::new();
+        map.insert(Region::new(2, 3), 4..10);
+        map.shift(2, 5);
+
+        assert_eq!(map.entries()[0].output, 9..15);
+    }
+
+    #[test]
+    fn append_should_keep_files_of_entries_and_offset_their_output() {
+        let mut map = SourceMap::new();
+        map.insert_from(
+            Some(PathBuf::from("a.wiki")),
+            Region::new(0, 5),
+            0..10,
+        );
+
+        let mut other = SourceMap::new();
+        other.insert_from(
+            Some(PathBuf::from("b.wiki")),
+            Region::new(3, 4),
+            0..8,
+        );
+        map.append(other, 10);
+
+        let entry = map.find_entry_by_output_offset(12).unwrap();
+        assert_eq!(entry.path.as_deref(), Some(std::path::Path::new("b.wiki")));
+        assert_eq!(entry.region, Region::new(3, 4));
+        assert_eq!(entry.output, 10..18);
+
+        let entry = map.find_entry_by_output_offset(2).unwrap();
+        assert_eq!(entry.path.as_deref(), Some(std::path::Path::new("a.wiki")));
+    }
+}
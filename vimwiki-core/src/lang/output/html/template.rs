@@ -0,0 +1,231 @@
+use super::HtmlOutputError;
+use crate::toc::TableOfContents;
+use std::{collections::HashMap, fmt::Write};
+use voca_rs::escape;
+
+/// Prefix of a placeholder that is replaced with the contents of a partial,
+/// such as `%partial:header%`
+const PARTIAL_PREFIX: &str = "partial:";
+
+/// Maximum depth of partials including other partials, which guards against
+/// a partial that includes itself
+const MAX_PARTIAL_DEPTH: usize = 8;
+
+/// Represents the template of an html page
+///
+/// Within a template, `%name%` is replaced with the value of the variable
+/// `name` and `%partial:name%` with the rendered contents of the partial
+/// `name`. Placeholders without a matching variable are left as they are,
+/// and the values of variables are never scanned for placeholders, so the
+/// content of a page cannot be mistaken for part of its template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HtmlTemplate {
+    text: String,
+}
+
+impl HtmlTemplate {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+
+    /// Returns the text of the template
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the names of the partials included directly by the template in
+    /// the order they first appear
+    pub fn partials(&self) -> Vec<&str> {
+        let mut partials = Vec::new();
+        let mut rest = self.text.as_str();
+        while let Some(start) = rest.find('%') {
+            rest = &rest[start..];
+            let name = placeholder_name(rest);
+            match name.and_then(|x| x.strip_prefix(PARTIAL_PREFIX)) {
+                Some(partial) => {
+                    if !partials.contains(&partial) {
+                        partials.push(partial);
+                    }
+                    rest = &rest[name.unwrap_or_default().len() + 2..];
+                }
+                None => rest = &rest[1..],
+            }
+        }
+        partials
+    }
+
+    /// Renders the template by filling in variables and partials, where
+    /// partials are loaded by name using the given function
+    pub fn render<F>(
+        &self,
+        variables: &HashMap<&str, String>,
+        load_partial: F,
+    ) -> Result<String, HtmlOutputError>
+    where
+        F: Fn(&str) -> Result<String, HtmlOutputError>,
+    {
+        let mut out = String::with_capacity(self.text.len());
+        render_into(&mut out, &self.text, variables, &load_partial, 0)?;
+        Ok(out)
+    }
+}
+
+fn render_into<F>(
+    out: &mut String,
+    text: &str,
+    variables: &HashMap<&str, String>,
+    load_partial: &F,
+    depth: usize,
+) -> Result<(), HtmlOutputError>
+where
+    F: Fn(&str) -> Result<String, HtmlOutputError>,
+{
+    let mut rest = text;
+    while let Some(start) = rest.find('%') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let name = placeholder_name(rest);
+        match name.and_then(|x| x.strip_prefix(PARTIAL_PREFIX)) {
+            Some(partial) if depth < MAX_PARTIAL_DEPTH => {
+                let text = load_partial(partial)?;
+                render_into(out, &text, variables, load_partial, depth + 1)?;
+                rest = &rest[name.unwrap_or_default().len() + 2..];
+            }
+            _ => match name.and_then(|x| variables.get(x).map(|v| (x, v))) {
+                Some((name, value)) => {
+                    out.push_str(value);
+                    rest = &rest[name.len() + 2..];
+                }
+                None => {
+                    out.push('%');
+                    rest = &rest[1..];
+                }
+            },
+        }
+    }
+    out.push_str(rest);
+
+    Ok(())
+}
+
+/// Returns the name of the placeholder at the start of the text, where the
+/// text begins with `%`
+fn placeholder_name(text: &str) -> Option<&str> {
+    let inner = &text[1..];
+    let end = inner.find(|c: char| {
+        !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':' | '/'))
+    })?;
+
+    if end > 0 && inner[end..].starts_with('%') {
+        Some(&inner[..end])
+    } else {
+        None
+    }
+}
+
+/// Produces a nested html list of links to each header of a table of
+/// contents, or an empty string if there are no headers
+pub fn toc_to_html(toc: &TableOfContents) -> String {
+    let mut html = String::new();
+    let mut depth = 0;
+
+    for entry in toc.entries() {
+        let level = entry.level.max(1);
+        if level > depth {
+            for _ in depth..level {
+                html.push_str("<ul><li>");
+            }
+        } else {
+            for _ in level..depth {
+                html.push_str("</li></ul>");
+            }
+            html.push_str("</li><li>");
+        }
+        depth = level;
+
+        let _ = write!(
+            html,
+            "<a href=\"#{}\">{}</a>",
+            entry.id,
+            escape::escape_html(&entry.text)
+        );
+    }
+
+    for _ in 0..depth {
+        html.push_str("</li></ul>");
+    }
+
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::toc::{HeaderNumbering, TocEntry};
+
+    fn render(text: &str, variables: &[(&'static str, &str)]) -> String {
+        let variables: HashMap<&str, String> =
+            variables.iter().map(|(k, v)| (*k, v.to_string())).collect();
+        HtmlTemplate::new(text)
+            .render(&variables, |name| match name {
+                "header" => Ok(String::from("<h1>%title%</h1>")),
+                "loop" => Ok(String::from("x%partial:loop%")),
+                _ => Err(HtmlOutputError::TemplateNotLoaded {
+                    source: std::io::Error::from(std::io::ErrorKind::NotFound),
+                }),
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn render_should_fill_in_variables_and_partials() {
+        assert_eq!(
+            render(
+                "%partial:header%<p>%content%</p> 50% %missing%",
+                &[("title", "Hi"), ("content", "%title%")]
+            ),
+            "<h1>Hi</h1><p>%title%</p> 50% %missing%"
+        );
+
+        // Partials that include themselves stop at a maximum depth
+        assert_eq!(
+            render("%partial:loop%", &[]),
+            format!("{}%partial:loop%", "x".repeat(MAX_PARTIAL_DEPTH))
+        );
+    }
+
+    #[test]
+    fn partials_should_list_each_partial_once() {
+        let template = HtmlTemplate::new(
+            "%partial:header% 50% %title% %partial:footer%%partial:header%",
+        );
+        assert_eq!(template.partials(), ["header", "footer"]);
+    }
+
+    #[test]
+    fn toc_to_html_should_nest_entries_by_level() {
+        let mut numbering = HeaderNumbering::new();
+        let mut entry = |level: usize, text: &str| TocEntry {
+            level,
+            number: numbering.next(level),
+            text: text.to_string(),
+            id: text.to_string(),
+        };
+        let toc = TableOfContents::new(vec![
+            entry(1, "a"),
+            entry(2, "b"),
+            entry(1, "c"),
+        ]);
+
+        assert_eq!(
+            toc_to_html(&toc),
+            concat!(
+                "<ul><li><a href=\"#a\">a</a>",
+                "<ul><li><a href=\"#b\">b</a></li></ul>",
+                "</li><li><a href=\"#c\">c</a></li></ul>",
+            )
+        );
+        assert_eq!(toc_to_html(&TableOfContents::default()), "");
+    }
+}
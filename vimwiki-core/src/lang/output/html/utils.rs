@@ -1,7 +1,12 @@
 use super::{HtmlConfig, HtmlWikiConfig};
-use crate::Link;
-use chrono::NaiveDate;
+use crate::{
+    resolve::{
+        LinkResolver, LinkSpaces, LinkTarget, ResolveError, ResolverWiki,
+    },
+    Link,
+};
 use derive_more::{Display, Error};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use relative_path::RelativePathBuf;
 use serde::{de, Deserialize};
 use std::{
@@ -151,213 +156,144 @@ pub enum LinkResolutionError {
     },
 }
 
-/// Performs link resolution to figure out the resulting URI or relative path
-/// based on the file containing the link, the destination wiki, and the
-/// outgoing link
-pub fn resolve_link(
+impl From<ResolveError> for LinkResolutionError {
+    fn from(x: ResolveError) -> Self {
+        match x {
+            ResolveError::MissingWikiWithIndex { index } => {
+                Self::MissingWikiWithIndex { index }
+            }
+            ResolveError::MissingWikiWithName { name } => {
+                Self::MissingWikiWithName { name }
+            }
+        }
+    }
+}
+
+/// Performs link resolution to figure out the href of the link based on the
+/// file containing the link, the destination wiki, and the outgoing link,
+/// where spaces within links to pages are kept as-is when the output of the
+/// wiki being targeted calls for it
+pub fn resolve_link_href(
     config: &HtmlConfig,
+    resolver: &LinkResolver,
     src_wiki: &HtmlWikiConfig,
     src: &Path,
     target: &Link<'_>,
-) -> Result<URIReference<'static>, LinkResolutionError> {
-    let ext = "html";
-    let src_out = src_wiki.make_output_path(src, ext);
-
-    // We want to figure out if the target uri is a directory to ensure that
-    // certain links account for that
-    let target_is_dir = is_directory_uri(&target.data().uri_ref);
-
-    // First, build our raw uri WITHOUT anchors
-    let uri_ref = match target {
-        Link::Wiki { data } => {
-            if data.is_local() && !data.is_local_anchor() {
-                // TODO: Support alternative directory file name
-                // NOTE: Don't need to provide extension as will be replaced in
-                //       the absolute output path anyway
-                let mut path = data.to_path_buf();
-                if target_is_dir {
-                    path.push("index");
-                }
-
-                // Build our output path
-                //
-                // 1. If absolute (starts with /), then we want to place the
-                //    path relative to the root of the current wiki
-                // 2. If relative, then we want to place the path relative to
-                //    the current file's directory
-                let target_out = if data.uri_ref.path().is_absolute() {
-                    src_wiki.make_output_path(path.as_path(), ext)
-                } else {
-                    src_wiki.make_output_path(
-                        src.parent()
-                            .map(Path::to_path_buf)
-                            .unwrap_or_default()
-                            .join(path.as_path())
-                            .as_path(),
-                        ext,
-                    )
-                };
-
-                let mut uri_ref = make_relative_link(src_out, target_out)
-                    .map(URIReference::from)
-                    .map_err(|source| {
-                        LinkResolutionError::RelativeReference { source }
-                    })?;
+) -> Result<String, LinkResolutionError> {
+    let (uri_ref, target_wiki) =
+        resolve_link(config, resolver, src_wiki, src, target)?;
+    let href = uri_ref.to_string();
+
+    // NOTE: Urls cannot contain spaces, so they are encoded when resolved
+    //       and only decoded once the link is written
+    match target_wiki {
+        Some(wiki) if wiki.output.link_spaces == LinkSpaces::Space => {
+            Ok(href.replace("%20", " "))
+        }
+        _ => Ok(href),
+    }
+}
 
-                if let Some(anchor) = data.to_anchor() {
-                    uri_ref.map_fragment(|_| Fragment::try_from(anchor).ok());
-                }
+/// Resolves the link into the resulting URI or relative path, also returning
+/// the wiki whose page is targeted if the link was resolved to the output of
+/// a page
+///
+/// The file targeted by a local link is determined by the [`LinkResolver`]
+/// built from the config, which is then mapped to its html output path
+fn resolve_link<'a>(
+    config: &'a HtmlConfig,
+    resolver: &LinkResolver,
+    src_wiki: &'a HtmlWikiConfig,
+    src: &Path,
+    target: &Link<'_>,
+) -> Result<
+    (URIReference<'static>, Option<&'a HtmlWikiConfig>),
+    LinkResolutionError,
+> {
+    let src_out = src_wiki.make_page_output_path(src);
+    let data = target.data();
+
+    // Raw and remote links, anchors to the current page, and files outside
+    // of any wiki by their Windows path are passed back as-is
+    if matches!(target, Link::Raw { .. })
+        || data.is_remote()
+        || data.is_local_anchor()
+        || data.is_windows_path()
+    {
+        return Ok((
+            URIReference::from(data.uri_ref.clone()).into_owned(),
+            None,
+        ));
+    }
 
-                uri_ref
-            } else {
-                data.uri_ref.clone()
-            }
-        }
-        Link::IndexedInterWiki { index, data } => {
+    // Figure out which wiki contains the target so we can use its output
+    // path, failing if the wiki being targeted does not exist
+    let target_wiki = match target {
+        Link::IndexedInterWiki { index, .. } => {
             let index = *index as usize;
-            let wiki = config.find_wiki_by_index(index).ok_or({
+            config.find_wiki_by_index(index).ok_or({
                 LinkResolutionError::MissingWikiWithIndex { index }
-            })?;
-
-            // TODO: Support alternative directory file name
-            // NOTE: Don't need to provide extension as will be replaced in
-            //       the absolute output path anyway
-            let mut path = data.to_path_buf();
-            if target_is_dir {
-                path.push("index");
-            }
-
-            // Take the path of the target from the uri reference and make it
-            // a relative path as it will always be added to the path of the
-            // specified wiki
-            let target_out =
-                wiki.make_output_path(data.to_path_buf().as_path(), ext);
-
-            let mut uri_ref = make_relative_link(src_out, target_out)
-                .map(URIReference::from)
-                .map_err(|source| LinkResolutionError::RelativeReference {
-                    source,
-                })?;
-
-            if let Some(anchor) = data.to_anchor() {
-                uri_ref.map_fragment(|_| Fragment::try_from(anchor).ok());
-            }
-
-            uri_ref
+            })?
         }
-        Link::NamedInterWiki { name, data } => {
-            let wiki = config.find_wiki_by_name(name).ok_or_else(|| {
-                LinkResolutionError::MissingWikiWithName {
-                    name: name.to_string(),
-                }
-            })?;
-
-            // TODO: Support alternative directory file name
-            // NOTE: Don't need to provide extension as will be replaced in
-            //       the absolute output path anyway
-            let mut path = data.to_path_buf();
-            if target_is_dir {
-                path.push("index");
-            }
-
-            // Take the path of the target from the uri reference and make it
-            // a relative path as it will always be added to the path of the
-            // specified wiki
-            let target_out =
-                wiki.make_output_path(data.to_path_buf().as_path(), ext);
-
-            let mut uri_ref = make_relative_link(src_out, target_out)
-                .map(URIReference::from)
-                .map_err(|source| LinkResolutionError::RelativeReference {
-                    source,
-                })?;
-
-            if let Some(anchor) = data.to_anchor() {
-                uri_ref.map_fragment(|_| Fragment::try_from(anchor).ok());
-            }
+        Link::NamedInterWiki { name, .. } => config
+            .find_wiki_by_name(name)
+            .ok_or_else(|| LinkResolutionError::MissingWikiWithName {
+                name: name.to_string(),
+            })?,
+        _ => src_wiki,
+    };
 
-            uri_ref
+    let path = match resolver.resolve_within(
+        &ResolverWiki::from(src_wiki),
+        src,
+        target,
+    )? {
+        LinkTarget::File { path, .. } => path,
+        LinkTarget::Uri(_) => {
+            return Ok((
+                URIReference::from(data.uri_ref.clone()).into_owned(),
+                None,
+            ))
         }
-        Link::Diary { date, data } => {
-            let diary_out =
-                make_diary_absolute_output_path(src_wiki, *date, ext);
-
-            let mut uri_ref = make_relative_link(src_out, diary_out)
-                .map(URIReference::from)
-                .map_err(|source| LinkResolutionError::RelativeReference {
-                    source,
-                })?;
-
-            if let Some(anchor) = data.to_anchor() {
-                uri_ref.map_fragment(|_| Fragment::try_from(anchor).ok());
-            }
+    };
 
-            uri_ref
+    // Pages are output using the mapping of their wiki while transclusions
+    // reuse the path and extension of the file they include
+    let mut uri_ref = match target {
+        Link::Transclusion { .. } => {
+            let ext = path.extension().and_then(OsStr::to_str).unwrap_or("");
+            make_relative_link(
+                src_out,
+                target_wiki.make_output_path(path.as_path(), ext),
+            )
         }
-        Link::Raw { data } => data.uri_ref.clone(),
-        Link::Transclusion { data } => {
-            // If target is a local link, then we need to process it the same
-            // as any wiki link
-            if data.is_local() {
-                let path = data.to_path_buf();
-
-                // We want to reuse the extension if there is one rather than
-                // modifying it, so pull out the extension
-                let ext =
-                    path.extension().and_then(OsStr::to_str).unwrap_or("");
-
-                // Build our output path
-                //
-                // 1. If absolute (starts with /), then we want to place the
-                //    path relative to the root of the current wiki
-                // 2. If relative, then we want to place the path relative to
-                //    the current file's directory
-                let target_out = if data.uri_ref.path().is_absolute() {
-                    src_wiki.make_output_path(path.as_path(), ext)
+        _ => {
+            let target_out = target_wiki.make_page_output_path(path.as_path());
+            make_relative_link(src_out, target_out).and_then(|x| {
+                let is_dir = target_wiki
+                    .page_name(path.as_path())
+                    .map(|name| target_wiki.page_url(&name))
+                    .is_some_and(|url| url.is_empty() || url.ends_with('/'));
+                if is_dir {
+                    to_directory_link(x)
                 } else {
-                    src_wiki.make_output_path(
-                        src.parent()
-                            .map(Path::to_path_buf)
-                            .unwrap_or_default()
-                            .join(path.as_path())
-                            .as_path(),
-                        ext,
-                    )
-                };
-
-                make_relative_link(src_out, target_out)
-                    .map(URIReference::from)
-                    .map_err(|source| {
-                        LinkResolutionError::RelativeReference { source }
-                    })?
-
-            // Otherwise, we can just pass back the link as-is
-            } else {
-                data.uri_ref.clone()
-            }
+                    Ok(x)
+                }
+            })
         }
-    };
+    }
+    .map(URIReference::from)
+    .map_err(|source| LinkResolutionError::RelativeReference { source })?;
 
-    Ok(uri_ref.into_owned())
-}
+    if matches!(target, Link::Transclusion { .. }) {
+        return Ok((uri_ref.into_owned(), None));
+    }
 
-/// Produces an output path for a diary file
-fn make_diary_absolute_output_path(
-    config: &HtmlWikiConfig,
-    date: NaiveDate,
-    ext: &str,
-) -> PathBuf {
-    // Make our input path relative to wiki root
-    //
-    // {WIKI-ROOT}/{DIARY-REL-PATH}/{DATE}
-    //
-    // NOTE: The extension of our input doesn't matter (don't even need one)
-    //       as we are replacing it with the provided extension
-    let input = config
-        .path
-        .join(config.diary_rel_path.as_path())
-        .join(date.format("%Y-%m-%d").to_string());
-    config.make_output_path(input.as_path(), ext)
+    if let Some(anchor) = data.to_anchor() {
+        uri_ref.map_fragment(|_| Fragment::try_from(anchor).ok());
+    }
+
+    Ok((uri_ref.into_owned(), Some(target_wiki)))
 }
 
 /// Given a src and target path, creates a relative reference
@@ -374,15 +310,59 @@ fn make_relative_link<P1: AsRef<Path>, P2: AsRef<Path>>(
     // NOTE: a relative path of a/b -> a/c would yield ../c, but in the case
     //       of the web, we just want c as referencing from the same directory
     //       is fine; this means that we remove the first .. in the path
+    //
+    // NOTE: Paths are decoded when resolved, so we need to encode them
+    //       again to be valid within a uri
     let relative_path = src_rel.relative(target_rel);
-    let res = RelativeReference::try_from(
+    let relative_path = encode_url_path(
         relative_path
             .strip_prefix("..")
             .unwrap_or(&relative_path)
             .as_str(),
-    )
-    .map(RelativeReference::into_owned);
-    res
+        LinkSpaces::Percent,
+    );
+    RelativeReference::try_from(relative_path.as_str())
+        .map(RelativeReference::into_owned)
+}
+
+/// Characters of a path that are percent encoded within a url, other than
+/// spaces which depend on the [`LinkSpaces`] in use
+const PATH: &AsciiSet = &CONTROLS
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Characters of a path that are percent encoded within a url, including
+/// spaces
+const PATH_AND_SPACES: &AsciiSet = &PATH.add(b' ');
+
+/// Percent encodes a path for use within a url, where spaces are only
+/// encoded (as `%20`) when the given [`LinkSpaces`] calls for it
+pub fn encode_url_path(path: &str, link_spaces: LinkSpaces) -> String {
+    if link_spaces.is_percent_encoded() {
+        utf8_percent_encode(path, PATH_AND_SPACES).to_string()
+    } else {
+        utf8_percent_encode(path, PATH).to_string()
+    }
+}
+
+/// Converts a relative link to the index file of a directory into a link to
+/// the directory itself (e.g. `a/index.html` into `a/`)
+fn to_directory_link(
+    link: RelativeReference<'static>,
+) -> Result<RelativeReference<'static>, RelativeReferenceError> {
+    let link = link.to_string();
+    let dir = match link.rfind('/') {
+        Some(i) => &link[..=i],
+        None => "./",
+    };
+    RelativeReference::try_from(dir).map(RelativeReference::into_owned)
 }
 
 /// Makes a path relative by stripping it of absolute/root starting elements
@@ -397,15 +377,3 @@ pub fn make_path_relative<P: AsRef<Path>>(path: P) -> PathBuf {
         })
         .collect()
 }
-
-/// Checks if a URI reference's path represents a directory
-fn is_directory_uri(uri_ref: &URIReference<'_>) -> bool {
-    // NOTE: URI Reference breaks up segments by /, which means that if we
-    //       end with a / there is one final segment that is completely
-    //       empty
-    uri_ref
-        .path()
-        .segments()
-        .last()
-        .map_or(false, |s| s.as_str().is_empty())
-}
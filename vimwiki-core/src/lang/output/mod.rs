@@ -3,6 +3,12 @@ mod html;
 #[cfg(feature = "html")]
 pub use html::*;
 
+mod ansi;
+pub use self::ansi::*;
+
+mod plain;
+pub use self::plain::*;
+
 mod vimwiki;
 pub use self::vimwiki::*;
 
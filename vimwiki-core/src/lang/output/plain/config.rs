@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents configuration properties for plain text output that are
+/// separate from the running state during conversion
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlainConfig {
+    /// Configuration settings that apply across entire pages
+    #[serde(default)]
+    pub page: PlainPageConfig,
+
+    /// Configuration settings that apply specifically to links
+    #[serde(default)]
+    pub link: PlainLinkConfig,
+
+    /// Configuration settings that apply specifically to lists
+    #[serde(default)]
+    pub list: PlainListConfig,
+
+    /// Configuration settings that apply specifically to tables
+    #[serde(default)]
+    pub table: PlainTableConfig,
+}
+
+/// Represents configuration options related to entire pages
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlainPageConfig {
+    /// Represents the string to use when indenting (e.g. four spaces or a tab)
+    #[serde(default = "PlainPageConfig::default_indent_str")]
+    pub indent_str: String,
+
+    /// If true, will add an extra line between each block element at the page level
+    #[serde(default = "PlainPageConfig::default_separate_block_elements")]
+    pub separate_block_elements: bool,
+}
+
+impl Default for PlainPageConfig {
+    fn default() -> Self {
+        Self {
+            indent_str: Self::default_indent_str(),
+            separate_block_elements: Self::default_separate_block_elements(),
+        }
+    }
+}
+
+impl PlainPageConfig {
+    #[inline]
+    pub fn default_separate_block_elements() -> bool {
+        true
+    }
+
+    #[inline]
+    pub fn default_indent_str() -> String {
+        String::from("    ")
+    }
+}
+
+/// Represents configuration options related to links
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlainLinkConfig {
+    /// If true, will write the target of a link in parentheses after its
+    /// description; links without a description always write their target
+    #[serde(default)]
+    pub include_target: bool,
+}
+
+/// Represents configuration options related to lists
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlainListConfig {
+    /// If true, will write the todo status of list items (e.g. `[X]`) after
+    /// their bullet
+    #[serde(default = "PlainListConfig::default_include_todo_status")]
+    pub include_todo_status: bool,
+}
+
+impl Default for PlainListConfig {
+    fn default() -> Self {
+        Self {
+            include_todo_status: Self::default_include_todo_status(),
+        }
+    }
+}
+
+impl PlainListConfig {
+    #[inline]
+    pub fn default_include_todo_status() -> bool {
+        true
+    }
+}
+
+/// Represents configuration options related to tables
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlainTableConfig {
+    /// Represents the string placed between aligned columns
+    #[serde(default = "PlainTableConfig::default_column_separator")]
+    pub column_separator: String,
+}
+
+impl Default for PlainTableConfig {
+    fn default() -> Self {
+        Self {
+            column_separator: Self::default_column_separator(),
+        }
+    }
+}
+
+impl PlainTableConfig {
+    #[inline]
+    pub fn default_column_separator() -> String {
+        String::from("  ")
+    }
+}
@@ -0,0 +1,47 @@
+use super::{Output, PlainConfig, PlainFormatter, PlainOutputError};
+
+pub trait ToPlainText {
+    fn to_plain_text(
+        &self,
+        config: PlainConfig,
+    ) -> Result<String, PlainOutputError>;
+}
+
+impl<T: Output<PlainFormatter>> ToPlainText for T {
+    fn to_plain_text(
+        &self,
+        config: PlainConfig,
+    ) -> Result<String, PlainOutputError> {
+        let mut formatter = PlainFormatter::new(config);
+        self.fmt(&mut formatter)?;
+        Ok(formatter.into_content())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlainOutputResult;
+
+    struct TestOutput<F: Fn(&mut PlainFormatter) -> PlainOutputResult>(F);
+    impl<F: Fn(&mut PlainFormatter) -> PlainOutputResult> Output<PlainFormatter>
+        for TestOutput<F>
+    {
+        fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+            self.0(f)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_plain_text_should_produce_a_string_representing_only_the_text_of_the_output(
+    ) {
+        let output = TestOutput(|f: &mut PlainFormatter| {
+            use std::fmt::Write;
+            write!(f, "I am some plain output")?;
+            Ok(())
+        });
+        let result = output.to_plain_text(PlainConfig::default()).unwrap();
+        assert_eq!(result, "I am some plain output");
+    }
+}
@@ -0,0 +1,11 @@
+use derive_more::{Display, Error, From};
+
+pub type PlainOutputResult = Result<(), PlainOutputError>;
+
+#[derive(Debug, From, Display, Error)]
+pub enum PlainOutputError {
+    Fmt {
+        #[error(source)]
+        source: std::fmt::Error,
+    },
+}
@@ -0,0 +1,99 @@
+use super::{OutputFormatter, PlainConfig, PlainOutputError};
+use std::fmt::{self, Write};
+
+/// Represents the formatter to use to write plain text output that includes
+/// various options that can be set as well as a context for use when writing
+/// output
+#[derive(Clone, Default)]
+pub struct PlainFormatter {
+    /// Represents the configuration associated with the formatter
+    config: PlainConfig,
+
+    /// Contains the text written so far
+    content: String,
+
+    /// Current level of indentation to use when writing elements that care
+    /// about indentation
+    pub indent_level: usize,
+}
+
+impl OutputFormatter for PlainFormatter {
+    type Error = PlainOutputError;
+}
+
+impl Write for PlainFormatter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.content.write_str(s)
+    }
+}
+
+impl PlainFormatter {
+    pub fn new(config: PlainConfig) -> Self {
+        Self {
+            config,
+            content: String::new(),
+            indent_level: 0,
+        }
+    }
+
+    pub fn clone_without_content(&self) -> Self {
+        Self {
+            content: String::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Writes a string representing the indentation for the current level,
+    /// only if the current content is either empty or the very last character
+    /// was a linefeed
+    pub fn write_indent(&mut self) -> Result<(), PlainOutputError> {
+        if self.content.is_empty() || self.content.ends_with('\n') {
+            let indent_str = self.config.page.indent_str.to_string();
+
+            for _ in 0..self.indent_level {
+                write!(self, "{}", indent_str)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Invokes the given function, passing it a mutable reference to this
+    /// formatter where the indentation level has been incremented by 1 and
+    /// will be decremented at the end of the function call
+    pub fn and_indent<F>(&mut self, f: F) -> Result<(), PlainOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), PlainOutputError>,
+    {
+        self.indent_level += 1;
+        let result = f(self);
+        self.indent_level -= 1;
+        result
+    }
+
+    /// Invokes the given function with a copy of this formatter without
+    /// content, returning the trimmed text that it produced
+    pub fn to_trimmed_string<F>(&self, f: F) -> Result<String, PlainOutputError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), PlainOutputError>,
+    {
+        let mut formatter = self.clone_without_content();
+        formatter.indent_level = 0;
+        f(&mut formatter)?;
+        Ok(formatter.content.trim().to_string())
+    }
+
+    /// Represents the config contained within the formatter
+    #[inline]
+    pub fn config(&self) -> &PlainConfig {
+        &self.config
+    }
+
+    pub fn get_content(&self) -> &str {
+        self.content.as_str()
+    }
+
+    pub fn into_content(self) -> String {
+        self.content
+    }
+}
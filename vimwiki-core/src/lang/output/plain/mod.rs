@@ -0,0 +1,548 @@
+mod config;
+pub use config::*;
+
+mod formatter;
+pub use formatter::PlainFormatter;
+
+mod convert;
+pub use convert::ToPlainText;
+
+mod error;
+pub use error::{PlainOutputError, PlainOutputResult};
+
+use crate::lang::{
+    elements::*,
+    output::{Output, OutputFormatter},
+};
+use std::fmt::Write;
+
+/// Writes the text as a single line at the current indentation, skipping
+/// text that is empty
+fn write_line(f: &mut PlainFormatter, text: &str) -> PlainOutputResult {
+    if !text.is_empty() {
+        f.write_indent()?;
+        writeln!(f, "{}", text)?;
+    }
+
+    Ok(())
+}
+
+impl<'a> Output<PlainFormatter> for Page<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        let PlainPageConfig {
+            separate_block_elements,
+            ..
+        } = f.config().page;
+
+        for element in self.elements().iter() {
+            // NOTE: Some elements like placeholders and dividers produce no
+            //       text, so we render each element first to avoid adding
+            //       separators for elements that were skipped
+            let mut formatter = f.clone_without_content();
+            element.fmt(&mut formatter)?;
+            let text = formatter.into_content();
+
+            if !text.is_empty() {
+                if separate_block_elements && !f.get_content().is_empty() {
+                    writeln!(f)?;
+                }
+                write!(f, "{}", text)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Element<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        match self {
+            Self::Block(x) => x.fmt(f),
+            Self::Inline(x) => x.fmt(f),
+            Self::InlineBlock(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<PlainFormatter> for InlineBlockElement<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        match self {
+            Self::ListItem(x) => x.fmt(f),
+            Self::Term(x) => x.fmt(f),
+            Self::Definition(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<PlainFormatter> for BlockElement<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        match self {
+            Self::Blockquote(x) => x.fmt(f),
+            Self::DefinitionList(x) => x.fmt(f),
+            Self::Divider(x) => x.fmt(f),
+            Self::FootnoteDefinition(x) => x.fmt(f),
+            Self::Header(x) => x.fmt(f),
+            Self::List(x) => x.fmt(f),
+            Self::MathBlock(x) => x.fmt(f),
+            Self::Paragraph(x) => x.fmt(f),
+            Self::Placeholder(x) => x.fmt(f),
+            Self::CodeBlock(x) => x.fmt(f),
+            Self::Table(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Blockquote<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        for line in self {
+            f.write_indent()?;
+            writeln!(f, "{}", line.trim())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for DefinitionList<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        for (term, defs) in self {
+            let text = f.to_trimmed_string(|f| term.fmt(f))?;
+            write_line(f, &text)?;
+
+            // Definitions are placed on their own lines beneath their term
+            f.and_indent(|f| {
+                for def in defs {
+                    let text = f.to_trimmed_string(|f| def.fmt(f))?;
+                    write_line(f, &text)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for DefinitionListValue<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        self.as_inner().fmt(f)
+    }
+}
+
+impl Output<PlainFormatter> for Divider {
+    fn fmt(&self, _f: &mut PlainFormatter) -> PlainOutputResult {
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for FootnoteDefinition<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        let text = f.to_trimmed_string(|f| self.contents.fmt(f))?;
+        write_line(f, format!("[{}] {}", self.label, text).trim_end())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Header<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        let text = f.to_trimmed_string(|f| self.content.fmt(f))?;
+        write_line(f, &text)
+    }
+}
+
+impl<'a> Output<PlainFormatter> for List<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        for item in self {
+            item.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for ListItem<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        let PlainListConfig {
+            include_todo_status,
+        } = f.config().list;
+
+        // Write the prefix such as 1. or - as well as the todo status,
+        // leaving the first line of content to follow on the same line
+        f.write_indent()?;
+        write!(f, "{} ", self.to_prefix())?;
+
+        if include_todo_status {
+            if let Some(todo_status) =
+                self.attributes.todo_status.as_ref().copied()
+            {
+                write!(f, "[")?;
+                todo_status.fmt(f)?;
+                write!(f, "] ")?;
+            }
+        }
+
+        // Write content at next indentation level
+        for content in self.contents.iter() {
+            f.and_indent(|f| content.fmt(f))?;
+        }
+
+        // If the item had no content, we still need to end its line
+        if !f.get_content().ends_with('\n') {
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Output<PlainFormatter> for ListItemTodoStatus {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        match self {
+            Self::Incomplete => write!(f, " ")?,
+            Self::PartiallyComplete1 => write!(f, ".")?,
+            Self::PartiallyComplete2 => write!(f, "o")?,
+            Self::PartiallyComplete3 => write!(f, "O")?,
+            Self::Complete => write!(f, "X")?,
+            Self::Rejected => write!(f, "-")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for MathBlock<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        for line in self {
+            f.write_indent()?;
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Placeholder<'a> {
+    fn fmt(&self, _f: &mut PlainFormatter) -> PlainOutputResult {
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for CodeBlock<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        for line in self {
+            f.write_indent()?;
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Paragraph<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        for line in self {
+            let text = f.to_trimmed_string(|f| line.fmt(f))?;
+            write_line(f, &text)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Table<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        let PlainTableConfig { column_separator } = f.config().table.clone();
+
+        // First, we calculate the text of each cell, leaving out divider rows
+        // and writing spanned cells as empty
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        for row in self.rows().filter(|row| !row.is_divider_row()) {
+            rows.push(
+                row.map(|cell| match cell.as_inner() {
+                    Cell::Content(x) => f.to_trimmed_string(|f| x.fmt(f)),
+                    _ => Ok(String::new()),
+                })
+                .collect::<Result<Vec<String>, PlainOutputError>>()?,
+            );
+        }
+
+        // Second, we calculate the largest cell in each column
+        let mut max_column_sizes = vec![0; self.col_cnt()];
+        for row in rows.iter() {
+            for (col, text) in row.iter().enumerate() {
+                let size = text.chars().count();
+                if size > max_column_sizes[col] {
+                    max_column_sizes[col] = size;
+                }
+            }
+        }
+
+        // Third, we write each row with its cells padded to align columns
+        for row in rows {
+            let mut line = String::new();
+            for (col, text) in row.iter().enumerate() {
+                if col > 0 {
+                    line.push_str(&column_separator);
+                }
+                line.push_str(text);
+                line.push_str(
+                    &" ".repeat(max_column_sizes[col] - text.chars().count()),
+                );
+            }
+
+            f.write_indent()?;
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for InlineElementContainer<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        for element in self {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for InlineElement<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Tags(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::FootnoteReference(x) => x.fmt(f),
+            Self::Comment(x) => x.fmt(f),
+        }
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Text<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        write!(f, "{}", self.as_str())?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for DecoratedText<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        match self {
+            Self::Bold(contents)
+            | Self::Italic(contents)
+            | Self::Strikeout(contents)
+            | Self::Superscript(contents)
+            | Self::Subscript(contents) => {
+                for content in contents {
+                    content.fmt(f)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for DecoratedTextContent<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        match self {
+            Self::Text(x) => x.fmt(f),
+            Self::DecoratedText(x) => x.fmt(f),
+            Self::Keyword(x) => x.fmt(f),
+            Self::Link(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::FootnoteReference(x) => x.fmt(f),
+        }
+    }
+}
+
+impl Output<PlainFormatter> for Keyword {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        write!(f, "{}", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Link<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        let PlainLinkConfig { include_target } = f.config().link;
+
+        let target = match self {
+            Self::Diary { date, data } => format!(
+                "{}{}",
+                date,
+                data.to_anchor().map(|x| x.to_string()).unwrap_or_default()
+            ),
+            Self::Raw { data } => data.uri_ref.to_string(),
+            _ => self.data().to_decoded_uri_string(),
+        };
+
+        // NOTE: A transcluded description (e.g. an image) is written using
+        //       its own description or target
+        let description = match self.description() {
+            Some(Description::Text(x)) => Some(x.to_string()),
+            Some(Description::TransclusionLink(x)) => {
+                Some(f.to_trimmed_string(|f| {
+                    Link::Transclusion { data: *x.clone() }.fmt(f)
+                })?)
+            }
+            None => None,
+        };
+
+        match description {
+            Some(description) if include_target => {
+                write!(f, "{} ({})", description, target)?
+            }
+            Some(description) => write!(f, "{}", description)?,
+            None => write!(f, "{}", target)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Tags<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        for (idx, tag) in self.into_iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", tag)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for CodeInline<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        write!(f, "{}", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for MathInline<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        write!(f, "{}", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for FootnoteReference<'a> {
+    fn fmt(&self, f: &mut PlainFormatter) -> PlainOutputResult {
+        write!(f, "[{}]", self)?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<PlainFormatter> for Comment<'a> {
+    fn fmt(&self, _f: &mut PlainFormatter) -> PlainOutputResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+    use similar_asserts::assert_str_eq;
+
+    fn to_plain_text(text: &str) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        page.to_plain_text(PlainConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn page_should_strip_markup_from_inline_elements() {
+        let text = to_plain_text(indoc! {"
+            = *Some* header =
+            A _decorated_ paragraph with [[link|a description]], `code`, %% hidden
+            [[page]] and [[diary:2021-03-05]] and https://example.com/path
+            :tag1:tag2:
+        "});
+
+        assert_str_eq!(
+            text,
+            indoc! {"
+                Some header
+
+                A decorated paragraph with a description, code,
+                page and 2021-03-05 and https://example.com/path
+                tag1 tag2
+            "}
+        );
+    }
+
+    #[test]
+    fn page_should_keep_bullets_of_lists() {
+        let text = to_plain_text(indoc! {"
+            - [ ] first *item*
+                1. nested item
+            - [X] second item
+        "});
+
+        assert_str_eq!(
+            text,
+            indoc! {"
+                - [ ] first item
+                    1. nested item
+                - [X] second item
+            "}
+        );
+    }
+
+    #[test]
+    fn page_should_align_columns_of_tables() {
+        let text = to_plain_text(indoc! {"
+            | Name | *Description* |
+            |------|---------------|
+            | a    | first         |
+            | bcd  | >             |
+        "});
+
+        assert_str_eq!(
+            text,
+            indoc! {"
+                Name  Description
+                a     first
+                bcd
+            "}
+        );
+    }
+
+    #[test]
+    fn page_should_skip_elements_without_text() {
+        let text = to_plain_text(indoc! {"
+            %title Some title
+            first paragraph
+            ----
+            second paragraph
+        "});
+
+        assert_str_eq!(text, "first paragraph\n\nsecond paragraph\n");
+    }
+
+    #[test]
+    fn link_should_include_target_if_configured() {
+        let page: Page = Language::from_vimwiki_str("[[page|description]]\n")
+            .parse()
+            .unwrap();
+        let text = page
+            .to_plain_text(PlainConfig {
+                link: PlainLinkConfig {
+                    include_target: true,
+                },
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_str_eq!(text, "description (page)\n");
+    }
+}
@@ -20,6 +20,10 @@ pub struct VimwikiConfig {
     #[serde(default)]
     pub definition_list: VimwikiDefinitionListConfig,
 
+    /// Configuration settings that apply specifically to dividers
+    #[serde(default)]
+    pub divider: VimwikiDividerConfig,
+
     /// Configuration settings that apply specifically to headers
     #[serde(default)]
     pub header: VimwikiHeaderConfig,
@@ -187,6 +191,31 @@ impl VimwikiDefinitionListConfig {
     }
 }
 
+/// Represents configuration options related to dividers
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VimwikiDividerConfig {
+    /// If true, will write each divider in its normalized form of four
+    /// hyphens with at most one blank line on either side, rather than
+    /// reproducing its original hyphens and surrounding blank lines
+    #[serde(default = "VimwikiDividerConfig::default_normalize")]
+    pub normalize: bool,
+}
+
+impl Default for VimwikiDividerConfig {
+    fn default() -> Self {
+        Self {
+            normalize: Self::default_normalize(),
+        }
+    }
+}
+
+impl VimwikiDividerConfig {
+    #[inline]
+    pub fn default_normalize() -> bool {
+        false
+    }
+}
+
 /// Represents configuration options related to headers
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VimwikiHeaderConfig {
@@ -23,16 +23,46 @@ impl<'a> Output<VimwikiFormatter> for Page<'a> {
             ..
         } = f.config().page;
 
-        for (idx, element) in self.elements.iter().enumerate() {
-            element.fmt(f)?;
+        let normalize_dividers = f.config().divider.normalize;
+        let divider = |idx: usize| {
+            self.elements()
+                .get(idx)
+                .and_then(|x| x.as_inner().as_divider())
+                .map(|x| {
+                    if normalize_dividers {
+                        x.normalized()
+                    } else {
+                        *x
+                    }
+                })
+        };
 
-            // If specified, add an additional linefeed after each element
-            // except for the very last one
-            if separate_block_elements && idx < self.elements.len() - 1 {
-                writeln!(f)?;
+        // NOTE: Elements always write line feeds, so we write them separately
+        //       to convert to the line endings of the original page
+        let mut formatter = f.clone_without_content();
+        for (idx, element) in self.elements().iter().enumerate() {
+            element.fmt(&mut formatter)?;
+
+            if idx + 1 < self.elements().len() {
+                // Dividers keep the blank lines that surrounded them,
+                // otherwise if specified, add an additional linefeed after
+                // each element except for the very last one
+                let blank_lines = match (divider(idx), divider(idx + 1)) {
+                    (Some(x), _) => x.blank_lines_after,
+                    (None, Some(x)) => x.blank_lines_before,
+                    (None, None) => usize::from(separate_block_elements),
+                };
+                for _ in 0..blank_lines {
+                    writeln!(formatter)?;
+                }
             }
         }
 
+        if self.has_bom {
+            write!(f, "{}", Page::BOM)?;
+        }
+        write!(f, "{}", self.line_ending.apply(formatter.get_content()))?;
+
         Ok(())
     }
 }
@@ -63,6 +93,7 @@ impl<'a> Output<VimwikiFormatter> for BlockElement<'a> {
             Self::Blockquote(x) => x.fmt(f),
             Self::DefinitionList(x) => x.fmt(f),
             Self::Divider(x) => x.fmt(f),
+            Self::FootnoteDefinition(x) => x.fmt(f),
             Self::Header(x) => x.fmt(f),
             Self::List(x) => x.fmt(f),
             Self::MathBlock(x) => x.fmt(f),
@@ -81,17 +112,17 @@ impl<'a> Output<VimwikiFormatter> for Blockquote<'a> {
             trim_lines,
         } = f.config().blockquote;
 
-        for line in self {
+        for (idx, line) in self.lines.iter().enumerate() {
             f.write_indent()?;
 
             // TODO: Support determining when to use each type of blockquote
             //       as default instead of forcing one type or another
-            // TODO: Support spacing on multiple >>> for nested blockquotes
-            //       once those are implemented
-            if prefer_indented_blockquote {
+            // NOTE: Indented blockquotes cannot express nesting, so nested
+            //       quotes always use one > per level of depth
+            if prefer_indented_blockquote && self.max_depth() <= 1 {
                 write!(f, "    ")?;
             } else {
-                write!(f, "> ")?;
+                write!(f, "{} ", ">".repeat(self.line_depth(idx).max(1)))?;
             }
 
             if trim_lines {
@@ -157,7 +188,21 @@ impl<'a> Output<VimwikiFormatter> for DefinitionListValue<'a> {
 
 impl Output<VimwikiFormatter> for Divider {
     fn fmt(&self, f: &mut VimwikiFormatter) -> VimwikiOutputResult {
-        writeln!(f, "----")?;
+        let len = if f.config().divider.normalize {
+            Divider::MIN_LEN
+        } else {
+            self.len.max(Divider::MIN_LEN)
+        };
+        writeln!(f, "{}", "-".repeat(len))?;
+        Ok(())
+    }
+}
+
+impl<'a> Output<VimwikiFormatter> for FootnoteDefinition<'a> {
+    fn fmt(&self, f: &mut VimwikiFormatter) -> VimwikiOutputResult {
+        write!(f, "[^{}]: ", self.label)?;
+        self.contents.fmt(f)?;
+        writeln!(f)?;
         Ok(())
     }
 }
@@ -562,6 +607,7 @@ impl<'a> Output<VimwikiFormatter> for InlineElement<'a> {
             Self::Tags(x) => x.fmt(f),
             Self::Code(x) => x.fmt(f),
             Self::Math(x) => x.fmt(f),
+            Self::FootnoteReference(x) => x.fmt(f),
             Self::Comment(x) => x.fmt(f),
         }
     }
@@ -625,6 +671,9 @@ impl<'a> Output<VimwikiFormatter> for DecoratedTextContent<'a> {
             Self::DecoratedText(x) => x.fmt(f),
             Self::Keyword(x) => x.fmt(f),
             Self::Link(x) => x.fmt(f),
+            Self::Code(x) => x.fmt(f),
+            Self::Math(x) => x.fmt(f),
+            Self::FootnoteReference(x) => x.fmt(f),
         }
     }
 }
@@ -696,9 +745,10 @@ impl<'a> Output<VimwikiFormatter> for Link<'a> {
                 }
                 write!(f, "]]")?;
             }
-            Self::Raw { data } => {
-                write!(f, "{}", data.uri_ref.to_string())?;
-            }
+            Self::Raw { data } => match data.description.as_ref() {
+                Some(Description::Text(x)) => write!(f, "{}", x)?,
+                _ => write!(f, "{}", data.uri_ref)?,
+            },
             Self::Transclusion { data } => {
                 write!(f, "{{{{")?;
                 write!(f, "{}", data.to_decoded_uri_string())?;
@@ -774,6 +824,13 @@ impl<'a> Output<VimwikiFormatter> for MathInline<'a> {
     }
 }
 
+impl<'a> Output<VimwikiFormatter> for FootnoteReference<'a> {
+    fn fmt(&self, f: &mut VimwikiFormatter) -> VimwikiOutputResult {
+        write!(f, "[^{}]", self)?;
+        Ok(())
+    }
+}
+
 impl<'a> Output<VimwikiFormatter> for Comment<'a> {
     fn fmt(&self, f: &mut VimwikiFormatter) -> VimwikiOutputResult {
         match self {
@@ -849,11 +906,11 @@ impl<'a> Output<VimwikiFormatter> for MultiLineComment<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use crate::date::WikiDate;
+    use crate::WikiUri;
     use indoc::indoc;
     use similar_asserts::assert_str_eq;
     use std::{borrow::Cow, collections::HashMap, convert::TryFrom};
-    use uriparse::URIReference;
 
     fn text_to_inline_element_container(s: &str) -> InlineElementContainer {
         InlineElementContainer::new(vec![Located::from(InlineElement::Text(
@@ -931,6 +988,24 @@ mod tests {
         assert_str_eq!(f.get_content(), "    some lines\n    of text\n");
     }
 
+    #[test]
+    fn blockquote_should_use_arrow_per_depth_for_nested_quotes() {
+        let blockquote = Blockquote::with_depths(
+            vec![Cow::from("outer"), Cow::from("inner"), Cow::from("deepest")],
+            vec![1, 2, 3],
+        );
+        let mut f = VimwikiFormatter::new(VimwikiConfig {
+            blockquote: VimwikiBlockquoteConfig {
+                prefer_indented_blockquote: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        blockquote.fmt(&mut f).unwrap();
+
+        assert_str_eq!(f.get_content(), "> outer\n>> inner\n>>> deepest\n");
+    }
+
     #[test]
     fn blockquote_should_trim_lines_if_setting_enabled() {
         let blockquote = Blockquote::new(vec![
@@ -1251,7 +1326,7 @@ mod tests {
 
     #[test]
     fn divider_should_output_vimwiki_syntax() {
-        let divider = Divider;
+        let divider = Divider::default();
 
         let mut f = VimwikiFormatter::default();
         divider.fmt(&mut f).unwrap();
@@ -1259,9 +1334,54 @@ mod tests {
         assert_str_eq!(f.get_content(), "----\n");
     }
 
+    #[test]
+    fn divider_should_preserve_hyphens_unless_normalized() {
+        let divider = Divider::new(8, 1, 1);
+
+        let mut f = VimwikiFormatter::default();
+        divider.fmt(&mut f).unwrap();
+        assert_str_eq!(f.get_content(), "--------\n");
+
+        let mut f = VimwikiFormatter::new(VimwikiConfig {
+            divider: VimwikiDividerConfig { normalize: true },
+            ..Default::default()
+        });
+        divider.fmt(&mut f).unwrap();
+        assert_str_eq!(f.get_content(), "----\n");
+    }
+
+    #[test]
+    fn page_should_keep_blank_lines_around_dividers() {
+        let text = "some text\n------\n\n\nmore text\n";
+        let page: Page =
+            crate::Language::from_vimwiki_str(text).parse().unwrap();
+
+        let mut f = VimwikiFormatter::default();
+        page.fmt(&mut f).unwrap();
+        assert_str_eq!(f.get_content(), text);
+
+        let mut f = VimwikiFormatter::new(VimwikiConfig {
+            divider: VimwikiDividerConfig { normalize: true },
+            ..Default::default()
+        });
+        page.fmt(&mut f).unwrap();
+        assert_str_eq!(f.get_content(), "some text\n----\n\nmore text\n");
+    }
+
+    #[test]
+    fn page_should_reproduce_line_endings_and_byte_order_mark() {
+        let text = "\u{FEFF}= header =\r\n\r\nsome text\r\n";
+        let page: Page =
+            crate::Language::from_vimwiki_str(text).parse().unwrap();
+
+        let mut f = VimwikiFormatter::default();
+        page.fmt(&mut f).unwrap();
+        assert_str_eq!(f.get_content(), text);
+    }
+
     #[test]
     fn divider_should_not_support_indentation() {
-        let divider = Divider;
+        let divider = Divider::default();
 
         let mut f = VimwikiFormatter::default();
         f.and_indent(|f| divider.fmt(f)).unwrap();
@@ -1868,7 +1988,7 @@ mod tests {
 
     #[test]
     fn placeholder_should_support_date() {
-        let placeholder = Placeholder::Date(NaiveDate::from_ymd(2021, 6, 17));
+        let placeholder = Placeholder::Date(WikiDate::from_ymd(2021, 6, 17));
         let mut f = VimwikiFormatter::default();
         placeholder.fmt(&mut f).unwrap();
         assert_str_eq!(f.get_content(), "%date 2021-06-17\n");
@@ -2490,10 +2610,8 @@ mod tests {
 
     #[test]
     fn wiki_link_should_output_vimwiki() {
-        let link = Link::new_wiki_link(
-            URIReference::try_from("some/page").unwrap(),
-            None,
-        );
+        let link =
+            Link::new_wiki_link(WikiUri::try_from("some/page").unwrap(), None);
         let mut f = VimwikiFormatter::default();
         link.fmt(&mut f).unwrap();
 
@@ -2503,7 +2621,7 @@ mod tests {
     #[test]
     fn wiki_link_should_output_vimwiki_with_uri_percent_decoded() {
         let link = Link::new_wiki_link(
-            URIReference::try_from("some/page%20with%20spaces").unwrap(),
+            WikiUri::try_from("some/page%20with%20spaces").unwrap(),
             None,
         );
         let mut f = VimwikiFormatter::default();
@@ -2515,7 +2633,7 @@ mod tests {
     #[test]
     fn wiki_link_should_support_text_descriptions() {
         let link = Link::new_wiki_link(
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Some(Description::from("text description")),
         );
         let mut f = VimwikiFormatter::default();
@@ -2527,7 +2645,7 @@ mod tests {
     #[test]
     fn wiki_link_should_support_transclusion_descriptions() {
         let link = Link::new_wiki_link(
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Some(
                 Description::try_from_uri_ref_str(
                     "https://example.com/img.png",
@@ -2548,7 +2666,7 @@ mod tests {
     fn indexed_interwiki_link_should_output_vimwiki() {
         let link = Link::new_indexed_interwiki_link(
             123,
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             None,
         );
         let mut f = VimwikiFormatter::default();
@@ -2561,7 +2679,7 @@ mod tests {
     fn indexed_interwiki_link_should_output_vimwiki_with_uri_percent_decoded() {
         let link = Link::new_indexed_interwiki_link(
             123,
-            URIReference::try_from("some/page%20with%20spaces").unwrap(),
+            WikiUri::try_from("some/page%20with%20spaces").unwrap(),
             None,
         );
         let mut f = VimwikiFormatter::default();
@@ -2574,7 +2692,7 @@ mod tests {
     fn indexed_interwiki_link_should_support_text_descriptions() {
         let link = Link::new_indexed_interwiki_link(
             123,
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Some(Description::from("text description")),
         );
         let mut f = VimwikiFormatter::default();
@@ -2590,7 +2708,7 @@ mod tests {
     fn indexed_interwiki_link_should_support_transclusion_descriptions() {
         let link = Link::new_indexed_interwiki_link(
             123,
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Some(
                 Description::try_from_uri_ref_str(
                     "https://example.com/img.png",
@@ -2611,7 +2729,7 @@ mod tests {
     fn named_interwiki_link_should_output_vimwiki() {
         let link = Link::new_named_interwiki_link(
             "my wiki",
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             None,
         );
         let mut f = VimwikiFormatter::default();
@@ -2624,7 +2742,7 @@ mod tests {
     fn named_interwiki_link_should_output_vimwiki_with_uri_percent_decoded() {
         let link = Link::new_named_interwiki_link(
             "my wiki",
-            URIReference::try_from("some/page%20with%20spaces").unwrap(),
+            WikiUri::try_from("some/page%20with%20spaces").unwrap(),
             None,
         );
         let mut f = VimwikiFormatter::default();
@@ -2637,7 +2755,7 @@ mod tests {
     fn named_interwiki_link_should_support_text_descriptions() {
         let link = Link::new_named_interwiki_link(
             "my wiki",
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Some(Description::from("text description")),
         );
         let mut f = VimwikiFormatter::default();
@@ -2653,7 +2771,7 @@ mod tests {
     fn named_interwiki_link_should_support_transclusion_descriptions() {
         let link = Link::new_named_interwiki_link(
             "my wiki",
-            URIReference::try_from("some/page").unwrap(),
+            WikiUri::try_from("some/page").unwrap(),
             Some(
                 Description::try_from_uri_ref_str(
                     "https://example.com/img.png",
@@ -2673,7 +2791,7 @@ mod tests {
     #[test]
     fn diary_link_should_output_vimwiki() {
         let link =
-            Link::new_diary_link(NaiveDate::from_ymd(2021, 6, 17), None, None);
+            Link::new_diary_link(WikiDate::from_ymd(2021, 6, 17), None, None);
         let mut f = VimwikiFormatter::default();
         link.fmt(&mut f).unwrap();
 
@@ -2683,7 +2801,7 @@ mod tests {
     #[test]
     fn diary_link_should_support_text_descriptions() {
         let link = Link::new_diary_link(
-            NaiveDate::from_ymd(2021, 6, 17),
+            WikiDate::from_ymd(2021, 6, 17),
             Description::from("text description"),
             None,
         );
@@ -2699,7 +2817,7 @@ mod tests {
     #[test]
     fn diary_link_should_support_transclusion_descriptions() {
         let link = Link::new_diary_link(
-            NaiveDate::from_ymd(2021, 6, 17),
+            WikiDate::from_ymd(2021, 6, 17),
             Description::try_from_uri_ref_str("https://example.com/img.png")
                 .unwrap(),
             None,
@@ -2716,7 +2834,7 @@ mod tests {
     #[test]
     fn diary_link_should_support_anchors() {
         let link = Link::new_diary_link(
-            NaiveDate::from_ymd(2021, 6, 17),
+            WikiDate::from_ymd(2021, 6, 17),
             None,
             Anchor::from_uri_fragment("#one#two#three").unwrap(),
         );
@@ -2729,7 +2847,7 @@ mod tests {
     #[test]
     fn diary_link_should_support_anchors_and_descriptions_together() {
         let link = Link::new_diary_link(
-            NaiveDate::from_ymd(2021, 6, 17),
+            WikiDate::from_ymd(2021, 6, 17),
             Description::from("text description"),
             Anchor::from_uri_fragment("#one#two#three").unwrap(),
         );
@@ -2751,10 +2869,19 @@ mod tests {
         assert_str_eq!(f.get_content(), "https://example.com/");
     }
 
+    #[test]
+    fn email_link_should_output_vimwiki_as_bare_address() {
+        let link = Link::new_email_link("user@example.com");
+        let mut f = VimwikiFormatter::default();
+        link.fmt(&mut f).unwrap();
+
+        assert_str_eq!(f.get_content(), "user@example.com");
+    }
+
     #[test]
     fn transclusion_link_should_output_vimwiki() {
         let link = Link::new_transclusion_link(
-            URIReference::try_from("some/img.png").unwrap(),
+            WikiUri::try_from("some/img.png").unwrap(),
             None,
             None,
         );
@@ -2767,7 +2894,7 @@ mod tests {
     #[test]
     fn transclusion_link_should_output_with_uri_percent_decoded() {
         let link = Link::new_transclusion_link(
-            URIReference::try_from("some/img%20with%20spaces.png").unwrap(),
+            WikiUri::try_from("some/img%20with%20spaces.png").unwrap(),
             None,
             None,
         );
@@ -2780,7 +2907,7 @@ mod tests {
     #[test]
     fn transclusion_link_should_support_text_descriptions() {
         let link = Link::new_transclusion_link(
-            URIReference::try_from("some/img.png").unwrap(),
+            WikiUri::try_from("some/img.png").unwrap(),
             Some(Description::from("text description")),
             None,
         );
@@ -2793,7 +2920,7 @@ mod tests {
     #[test]
     fn transclusion_link_should_support_transclusion_descriptions() {
         let link = Link::new_transclusion_link(
-            URIReference::try_from("some/img.png").unwrap(),
+            WikiUri::try_from("some/img.png").unwrap(),
             Some(
                 Description::try_from_uri_ref_str(
                     "https://example.com/img.png",
@@ -2814,7 +2941,7 @@ mod tests {
     #[test]
     fn transclusion_link_should_support_properties() {
         let link = Link::new_transclusion_link(
-            URIReference::try_from("some/img.png").unwrap(),
+            WikiUri::try_from("some/img.png").unwrap(),
             None,
             vec![(Cow::Borrowed("key"), Cow::Borrowed("value"))]
                 .into_iter()
@@ -2829,7 +2956,7 @@ mod tests {
     #[test]
     fn transclusion_link_should_support_description_properties() {
         let link = Link::new_transclusion_link(
-            URIReference::try_from("some/img.png").unwrap(),
+            WikiUri::try_from("some/img.png").unwrap(),
             Description::from("text description"),
             vec![(Cow::Borrowed("key"), Cow::Borrowed("value"))]
                 .into_iter()
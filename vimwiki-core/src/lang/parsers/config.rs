@@ -0,0 +1,120 @@
+use crate::lang::elements::Keyword;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Reverse,
+    rc::Rc,
+};
+
+/// Represents configuration options that change what is recognized when
+/// parsing
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParserConfig {
+    /// Names of the keywords recognized within text (e.g. `TODO`), where
+    /// names other than those of the builtin keywords are parsed as
+    /// [`Keyword::Other`]
+    #[serde(default = "ParserConfig::default_keywords")]
+    pub keywords: Vec<String>,
+
+    /// If true, bare email addresses within text (e.g. `user@example.com`)
+    /// are parsed as raw links to their `mailto` uri
+    #[serde(default)]
+    pub email_autolinks: bool,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            keywords: Self::default_keywords(),
+            email_autolinks: false,
+        }
+    }
+}
+
+impl ParserConfig {
+    /// Replaces the recognized keywords with the given keywords
+    pub fn with_keywords<I, S>(mut self, keywords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.keywords = keywords.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Recognizes the given keywords in addition to those already recognized
+    pub fn with_extra_keywords<I, S>(mut self, keywords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for keyword in keywords.into_iter().map(Into::into) {
+            if !self.keywords.contains(&keyword) {
+                self.keywords.push(keyword);
+            }
+        }
+        self
+    }
+
+    /// Sets whether bare email addresses are parsed as raw links
+    pub fn with_email_autolinks(mut self, email_autolinks: bool) -> Self {
+        self.email_autolinks = email_autolinks;
+        self
+    }
+
+    #[inline]
+    pub fn default_keywords() -> Vec<String> {
+        Keyword::BUILTIN.iter().map(ToString::to_string).collect()
+    }
+}
+
+thread_local! {
+    /// Keywords recognized by the active config, ordered longest first so a
+    /// keyword is never cut short by another that is its prefix
+    static KEYWORDS: RefCell<Option<Rc<[String]>>> =
+        const { RefCell::new(None) };
+
+    /// Whether the active config parses bare email addresses as raw links
+    static EMAIL_AUTOLINKS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Evaluates the provided function with the given config applied to all
+/// parsing done within it, restoring whatever config was applied before once
+/// finished
+pub fn config_scope<T>(config: &ParserConfig, f: impl FnOnce() -> T) -> T {
+    struct ScopeGuard(Option<Rc<[String]>>, bool);
+
+    impl Drop for ScopeGuard {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            KEYWORDS.with(|x| *x.borrow_mut() = previous);
+            EMAIL_AUTOLINKS.with(|x| x.set(self.1));
+        }
+    }
+
+    let mut keywords: Vec<String> = config
+        .keywords
+        .iter()
+        .filter(|x| !x.is_empty())
+        .cloned()
+        .collect();
+    keywords.sort_by_key(|x| Reverse(x.len()));
+
+    let previous = KEYWORDS.with(|x| x.borrow_mut().replace(keywords.into()));
+    let previous_email_autolinks =
+        EMAIL_AUTOLINKS.with(|x| x.replace(config.email_autolinks));
+    let _guard = ScopeGuard(previous, previous_email_autolinks);
+    f()
+}
+
+/// Returns the keywords recognized by the active config, or None if parsing
+/// outside of a [`config_scope`] where the builtin keywords apply
+pub fn configured_keywords() -> Option<Rc<[String]>> {
+    KEYWORDS.with(|x| x.borrow().clone())
+}
+
+/// Returns true if the active config parses bare email addresses as raw
+/// links, which is never the case outside of a [`config_scope`]
+pub fn configured_email_autolinks() -> bool {
+    EMAIL_AUTOLINKS.with(Cell::get)
+}
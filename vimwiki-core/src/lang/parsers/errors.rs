@@ -68,6 +68,16 @@ impl<'a> LangParserError<'a> {
         }
     }
 
+    /// Returns the description of what failed to parse
+    pub fn context(&self) -> &str {
+        &self.ctx
+    }
+
+    /// Returns the byte offset within the parsed text where parsing failed
+    pub fn offset(&self) -> usize {
+        self.input.start_offset()
+    }
+
     pub fn from_ctx(input: &Span<'a>, ctx: &'static str) -> Self {
         Self {
             ctx: Cow::from(ctx),
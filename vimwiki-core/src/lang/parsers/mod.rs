@@ -1,3 +1,4 @@
+mod config;
 mod errors;
 mod span;
 mod utils;
@@ -8,6 +9,12 @@ pub mod vimwiki;
 /// Export the span used for input
 pub use span::Span;
 
+/// Export the config used to change what is recognized when parsing
+pub use config::ParserConfig;
+pub(crate) use config::{
+    config_scope, configured_email_autolinks, configured_keywords,
+};
+
 /// Alias to the type of error to use with parsing using nom
 pub use errors::LangParserError as Error;
 
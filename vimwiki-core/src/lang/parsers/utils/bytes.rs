@@ -111,6 +111,59 @@ pub fn surround_in_line1<'a>(
     context("Surround in Line", inner(left, right))
 }
 
+/// Parser that behaves like [`surround_in_line1`], but skips over any span
+/// opened and closed on the same line by one of the given delimiters (such as
+/// the backticks of inline code) when looking for the right side
+pub fn surround_in_line1_skipping<'a>(
+    left: &'static str,
+    right: &'static str,
+    delimiters: &'static [u8],
+) -> impl FnMut(Span<'a>) -> IResult<Span<'a>> {
+    fn inner<'a>(
+        left: &'static str,
+        right: &'static str,
+        delimiters: &'static [u8],
+    ) -> impl FnMut(Span<'a>) -> IResult<Span<'a>> {
+        move |input: Span| {
+            let (input, _) = tag(left)(input)?;
+            let input_bytes = input.as_bytes();
+            let line = match memchr(b'\n', input_bytes) {
+                Some(newline_pos) => &input_bytes[..newline_pos],
+                None => input_bytes,
+            };
+
+            let mut pos = 0;
+            while pos < line.len() {
+                // If we are at the start of a delimited span that is closed
+                // on this line, jump past the entire span
+                if delimiters.contains(&line[pos]) {
+                    if let Some(len) = memchr(line[pos], &line[pos + 1..]) {
+                        pos += len + 2;
+                        continue;
+                    }
+                }
+
+                // Only accept the right side if there would be something in
+                // the surroundings
+                if pos > 0 && line[pos..].starts_with(right.as_bytes()) {
+                    let (input, content) = input.take_split(pos);
+                    let (input, _) = input.take_split(right.len());
+                    return Ok((input, content));
+                }
+
+                pos += 1;
+            }
+
+            Err(nom::Err::Error(Error::from_ctx(
+                &input,
+                "right side not found",
+            )))
+        }
+    }
+
+    context("Surround in Line Skipping", inner(left, right, delimiters))
+}
+
 /// Parser that consumes input while the pattern succeeds or we reach the
 /// end of the line. Note that this does NOT consume the line termination.
 pub fn take_line_while<'a, T>(
@@ -509,6 +562,32 @@ mod tests {
         assert_eq!(result, "bb");
     }
 
+    #[test]
+    fn surround_in_line1_skipping_should_ignore_right_pattern_within_delimiters(
+    ) {
+        let input = Span::from("*a `b*c` d*e");
+        let (input, result) =
+            surround_in_line1_skipping("*", "*", b"`")(input).unwrap();
+        assert_eq!(input, "e");
+        assert_eq!(result, "a `b*c` d");
+    }
+
+    #[test]
+    fn surround_in_line1_skipping_should_treat_unclosed_delimiter_as_text() {
+        let input = Span::from("*a `b*c");
+        let (input, result) =
+            surround_in_line1_skipping("*", "*", b"`")(input).unwrap();
+        assert_eq!(input, "c");
+        assert_eq!(result, "a `b");
+    }
+
+    #[test]
+    fn surround_in_line1_skipping_should_fail_if_right_pattern_only_in_delimiters(
+    ) {
+        let input = Span::from("*a `b*c`\nd*");
+        assert!(surround_in_line1_skipping("*", "*", b"`")(input).is_err());
+    }
+
     #[test]
     fn take_line_while_should_yield_empty_if_empty_input() {
         let input = Span::from("");
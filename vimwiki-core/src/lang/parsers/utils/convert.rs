@@ -1,22 +1,37 @@
 use super::{context, single_multispace};
-use crate::lang::{
-    elements::{Located, Region},
-    parsers::{Captured, IResult, Span},
+use crate::{
+    lang::{
+        elements::{Located, Region},
+        parsers::{Captured, Error, IResult, Span},
+    },
+    uri::{is_uri_text, WikiUri},
 };
 use nom::{
     character::complete::anychar,
-    combinator::{map_res, not, recognize},
+    combinator::{map_opt, not, recognize},
     multi::many1,
     sequence::pair,
 };
-use std::{borrow::Cow, convert::TryFrom};
-use uriparse::URIReference;
+use std::borrow::Cow;
 
-/// Parser that wraps a span in a deeper depth
+/// Maximum depth that elements can be nested within one another, which keeps
+/// deeply-nested input from overflowing the stack; anything nested deeper is
+/// left to the shallower parsers, which typically treat it as plain text
+pub const MAX_DEPTH: u16 = 48;
+
+/// Parser that wraps a span in a deeper depth, failing recoverably if the span
+/// is already at the maximum depth so that other parsers can be tried instead
 pub fn deeper<'a, T>(
     mut parser: impl FnMut(Span<'a>) -> IResult<T>,
 ) -> impl FnMut(Span<'a>) -> IResult<T> {
     context("Deeper", move |input: Span<'a>| {
+        if input.depth() >= MAX_DEPTH {
+            return Err(nom::Err::Error(Error::from_ctx(
+                &input,
+                "Maximum depth exceeded",
+            )));
+        }
+
         let (input, x) = parser(input.with_deeper_depth())?;
         Ok((input.with_shallower_depth(), x))
     })
@@ -72,14 +87,19 @@ pub fn cow_str<'a>(input: Span<'a>) -> IResult<Cow<'a, str>> {
 ///
 /// 1. www (www.example.com) -> (https://www.example.com)
 /// 2. // (//some/abs/path) -> (file:/some/abs/path)
-pub fn uri_ref<'a>(input: Span<'a>) -> IResult<URIReference<'a>> {
+pub fn uri_ref<'a>(input: Span<'a>) -> IResult<WikiUri<'a>> {
     // TODO: Support special cases, which involves allocating a new string
     //       or providing some alternative structure to a URI
     context(
         "Normal URI Reference",
-        map_res(
+        map_opt(
             recognize(many1(pair(not(single_multispace), anychar))),
-            URIReference::try_from,
+            |x: Span<'a>| {
+                x.map_remaining_into(std::str::from_utf8)
+                    .ok()
+                    .filter(|text| is_uri_text(text))
+                    .map(WikiUri::lazy)
+            },
         ),
     )(input)
 }
@@ -87,7 +107,26 @@ pub fn uri_ref<'a>(input: Span<'a>) -> IResult<URIReference<'a>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nom::bytes::complete::tag;
+    use nom::{bytes::complete::tag, combinator::map_res};
+
+    #[test]
+    fn deeper_should_increase_depth_for_parser_and_restore_it_after() {
+        let input = Span::from("abc").with_depth(3);
+        let (input, depth) = deeper(|input: Span| {
+            let depth = input.depth();
+            Ok((input, depth))
+        })(input)
+        .unwrap();
+        assert_eq!(depth, 4);
+        assert_eq!(input.depth(), 3);
+    }
+
+    #[test]
+    fn deeper_should_fail_recoverably_if_at_max_depth() {
+        let input = Span::from("abc").with_depth(MAX_DEPTH);
+        let result = deeper(cow_str)(input);
+        assert!(matches!(result, Err(nom::Err::Error(_))));
+    }
 
     #[test]
     fn locate_should_return_parser_result_with_consumed_input_location() {
@@ -155,7 +194,7 @@ mod tests {
         let (input, u) = uri_ref(input).expect("Failed to parse uri ref");
         assert!(input.is_empty());
         assert_eq!(u.scheme(), None);
-        assert_eq!(u.host().map(ToString::to_string), Some("some".to_string()));
+        assert_eq!(u.host(), Some("some".to_string()));
         assert_eq!(u.path(), "/network/path");
     }
 
@@ -1,5 +1,8 @@
 use super::{context, end_of_line_or_input, take_until_end_of_line_or_input};
-use crate::lang::parsers::{Error, IResult, Span};
+use crate::lang::{
+    elements::Page,
+    parsers::{Error, IResult, Span},
+};
 use nom::{
     branch::alt,
     character::complete::{line_ending, space0, space1},
@@ -12,9 +15,13 @@ pub fn beginning_of_line(input: Span) -> IResult<()> {
     fn inner(input: Span) -> IResult<()> {
         let l = input.consumed_len();
 
-        // If we have consumed nothing or the last consumed byte was a newline,
-        // we are at the beginning of the line now
-        if l == 0 || input.as_consumed()[l - 1] == b'\n' {
+        // If we have consumed nothing (or only a byte order mark) or the
+        // last consumed byte was a newline, we are at the beginning of the
+        // line now
+        if l == 0
+            || input.as_consumed()[l - 1] == b'\n'
+            || input.as_consumed() == Page::BOM.as_bytes()
+        {
             Ok((input, ()))
         } else {
             Err(nom::Err::Error(Error::from_ctx(
@@ -0,0 +1,215 @@
+use crate::lang::parsers::{Error, IResult, Span};
+use std::{cell::RefCell, collections::HashMap};
+
+/// Key used to look up the outcome of a parser, comprised of the parser's
+/// identifier, the address of the underlying input, and the start and end
+/// offsets of the span being parsed
+type MemoKey = (&'static str, usize, usize, usize);
+
+/// Represents a packrat-style cache of whether or not a parser succeeded
+/// at some position within the input
+#[derive(Default)]
+struct MemoTable {
+    /// Number of nested scopes currently active
+    scopes: usize,
+
+    /// Outcomes of parsers, true if succeeded and false if failed
+    outcomes: HashMap<MemoKey, bool>,
+}
+
+thread_local! {
+    static MEMO_TABLE: RefCell<MemoTable> = RefCell::new(MemoTable::default());
+}
+
+fn make_key(id: &'static str, input: &Span) -> MemoKey {
+    (
+        id,
+        input.as_inner().as_ptr() as usize,
+        input.start_offset(),
+        input.end_offset(),
+    )
+}
+
+fn lookup(id: &'static str, input: &Span) -> Option<bool> {
+    MEMO_TABLE.with(|table| {
+        let table = table.borrow();
+        if table.scopes > 0 {
+            table.outcomes.get(&make_key(id, input)).copied()
+        } else {
+            None
+        }
+    })
+}
+
+fn record(id: &'static str, input: &Span, succeeded: bool) {
+    MEMO_TABLE.with(|table| {
+        let mut table = table.borrow_mut();
+        if table.scopes > 0 {
+            table.outcomes.insert(make_key(id, input), succeeded);
+        }
+    })
+}
+
+/// Evaluates the provided function with memoization of parser outcomes
+/// enabled. Outcomes are only remembered while the outermost scope is active
+/// as the cache is tied to the address of the input being parsed and is
+/// therefore cleared once the outermost scope finishes.
+pub fn memo_scope<T>(f: impl FnOnce() -> T) -> T {
+    struct ScopeGuard;
+
+    impl Drop for ScopeGuard {
+        fn drop(&mut self) {
+            MEMO_TABLE.with(|table| {
+                let mut table = table.borrow_mut();
+                table.scopes -= 1;
+                if table.scopes == 0 {
+                    table.outcomes.clear();
+                }
+            })
+        }
+    }
+
+    MEMO_TABLE.with(|table| table.borrow_mut().scopes += 1);
+    let _guard = ScopeGuard;
+    f()
+}
+
+/// Wraps a parser such that whether or not it succeeds at some input is
+/// remembered while within a [`memo_scope`]. If the parser is known to have
+/// already failed at the given input, it will fail immediately without
+/// being evaluated again.
+pub fn memo<'a, T>(
+    id: &'static str,
+    mut f: impl FnMut(Span<'a>) -> IResult<'a, T>,
+) -> impl FnMut(Span<'a>) -> IResult<'a, T> {
+    move |input: Span<'a>| {
+        if lookup(id, &input) == Some(false) {
+            return Err(nom::Err::Error(Error::from_ctx(&input, id)));
+        }
+
+        let result = f(input);
+        match &result {
+            Ok(_) => record(id, &input, true),
+            Err(nom::Err::Error(_)) => record(id, &input, false),
+            _ => {}
+        }
+        result
+    }
+}
+
+/// Negative lookahead equivalent to `nom::combinator::not`, but will use the
+/// remembered outcome of the parser when within a [`memo_scope`] rather than
+/// evaluating the parser again
+pub fn memo_not<'a, T>(
+    id: &'static str,
+    mut f: impl FnMut(Span<'a>) -> IResult<'a, T>,
+) -> impl FnMut(Span<'a>) -> IResult<'a, ()> {
+    move |input: Span<'a>| {
+        let succeeded = match lookup(id, &input) {
+            Some(succeeded) => succeeded,
+            None => match f(input) {
+                Ok(_) => {
+                    record(id, &input, true);
+                    true
+                }
+                Err(nom::Err::Error(_)) => {
+                    record(id, &input, false);
+                    false
+                }
+                Err(x) => return Err(x),
+            },
+        };
+
+        if succeeded {
+            Err(nom::Err::Error(Error::from_ctx(&input, "Not")))
+        } else {
+            Ok((input, ()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::bytes::complete::tag;
+    use std::cell::Cell;
+
+    fn counting_tag<'a>(
+        t: &'static str,
+        counter: &'a Cell<usize>,
+    ) -> impl FnMut(Span<'a>) -> IResult<'a, Span<'a>> + 'a {
+        move |input: Span<'a>| {
+            counter.set(counter.get() + 1);
+            tag(t)(input)
+        }
+    }
+
+    #[test]
+    fn memo_should_evaluate_parser_every_time_outside_of_scope() {
+        let counter = Cell::new(0);
+        let input = Span::from("abc");
+        let mut parser = memo("test", counting_tag("b", &counter));
+
+        assert!(parser(input).is_err());
+        assert!(parser(input).is_err());
+        assert_eq!(counter.get(), 2);
+    }
+
+    #[test]
+    fn memo_should_skip_parser_that_already_failed_within_scope() {
+        let counter = Cell::new(0);
+        let input = Span::from("abc");
+        let mut parser = memo("test", counting_tag("b", &counter));
+
+        memo_scope(|| {
+            assert!(parser(input).is_err());
+            assert!(parser(input).is_err());
+        });
+        assert_eq!(counter.get(), 1);
+    }
+
+    #[test]
+    fn memo_should_evaluate_parser_that_succeeded_within_scope() {
+        let counter = Cell::new(0);
+        let input = Span::from("abc");
+        let mut parser = memo("test", counting_tag("a", &counter));
+
+        memo_scope(|| {
+            let (input, output) = parser(input).unwrap();
+            assert_eq!(input, "bc");
+            assert_eq!(output, "a");
+            assert!(parser(Span::from("abc")).is_ok());
+        });
+        assert_eq!(counter.get(), 2);
+    }
+
+    #[test]
+    fn memo_not_should_reuse_outcomes_within_scope() {
+        let counter = Cell::new(0);
+        let input = Span::from("abc");
+
+        memo_scope(|| {
+            let mut parser = memo("test", counting_tag("a", &counter));
+            assert!(parser(input).is_ok());
+
+            let mut not_parser = memo_not("test", counting_tag("a", &counter));
+            assert!(not_parser(input).is_err());
+
+            let mut not_parser = memo_not("other", counting_tag("b", &counter));
+            assert!(not_parser(input).is_ok());
+            assert!(not_parser(input).is_ok());
+        });
+        assert_eq!(counter.get(), 2);
+    }
+
+    #[test]
+    fn memo_scope_should_clear_outcomes_when_finished() {
+        let counter = Cell::new(0);
+        let input = Span::from("abc");
+        let mut parser = memo("test", counting_tag("b", &counter));
+
+        memo_scope(|| assert!(parser(input).is_err()));
+        memo_scope(|| assert!(parser(input).is_err()));
+        assert_eq!(counter.get(), 2);
+    }
+}
@@ -3,6 +3,7 @@ mod character;
 mod convert;
 mod error;
 mod line;
+mod memo;
 mod whitespace;
 
 pub use bytes::*;
@@ -10,4 +11,5 @@ pub use character::*;
 pub use convert::*;
 pub use error::*;
 pub use line::*;
+pub use memo::*;
 pub use whitespace::*;
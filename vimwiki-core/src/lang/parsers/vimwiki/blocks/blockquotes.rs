@@ -1,3 +1,4 @@
+use super::paragraphs::continue_paragraph;
 use crate::lang::{
     elements::{Blockquote, Located},
     parsers::{
@@ -9,11 +10,10 @@ use crate::lang::{
 };
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{not_line_ending, space0},
-    combinator::{map, map_parser, value, verify},
-    multi::{many0, many1},
-    sequence::pair,
+    character::complete::{char, not_line_ending, space0},
+    combinator::{map_parser, verify},
+    multi::{fold_many0, many1},
+    sequence::preceded,
 };
 use std::borrow::Cow;
 
@@ -45,42 +45,84 @@ fn indented_blockquote_line<'a>(input: Span<'a>) -> IResult<Cow<'a, str>> {
 
 pub fn arrow_blockquote(input: Span) -> IResult<Located<Blockquote>> {
     fn inner(input: Span) -> IResult<Blockquote> {
-        // NOTE: > blockquotes allow blank lines inbetween
-        let (input, lines) = map(
-            pair(
-                many1(arrow_blockquote_line),
-                map(
-                    many0(pair(
-                        many0(value(Cow::from(""), blank_line)),
-                        arrow_blockquote_line,
-                    )),
-                    |pairs| {
-                        pairs
-                            .into_iter()
-                            .flat_map(|(mut blanks, bq)| {
-                                blanks.push(bq);
-                                blanks
-                            })
-                            .collect()
-                    },
-                ),
-            ),
-            |(head, rest)| vec![head, rest].concat(),
-        )(input)?;
-        Ok((input, Blockquote::new(lines)))
+        let (mut input, (depth, line)) = arrow_blockquote_line(input)?;
+        let mut lines = vec![line];
+        let mut depths = vec![depth];
+
+        loop {
+            // Each line starting with > continues the blockquote
+            if let Ok((i, (depth, line))) = arrow_blockquote_line(input) {
+                input = i;
+                lines.push(line);
+                depths.push(depth);
+                continue;
+            }
+
+            // NOTE: A line following a non-blank line that does not start
+            //       another block element lazily continues the quote of the
+            //       line before it
+            let last_depth = depths.last().copied().unwrap_or_default();
+            if last_depth > 0 {
+                if let Ok((i, line)) = lazy_continuation_line(input) {
+                    input = i;
+                    lines.push(line);
+                    depths.push(last_depth);
+                    continue;
+                }
+            }
+
+            // NOTE: > blockquotes allow blank lines inbetween
+            if let Ok((i, blanks)) = many1(blank_line)(input) {
+                if arrow_blockquote_line(i).is_ok() {
+                    input = i;
+                    lines.extend(blanks.into_iter().map(|_| Cow::from("")));
+                    depths.resize(lines.len(), 0);
+                    continue;
+                }
+            }
+
+            break;
+        }
+
+        Ok((input, Blockquote::with_depths(lines, depths)))
     }
 
     context("Arrow Blockquote", locate(capture(inner)))(input)
 }
 
-/// Parses a blockquote line that begins with >
+/// Parses a blockquote line that begins with one or more >, where each
+/// additional > (e.g. `>> text` or `> > text`) nests the line one level
+/// deeper, returning the depth of the line alongside its text
 #[inline]
-fn arrow_blockquote_line<'a>(input: Span<'a>) -> IResult<Cow<'a, str>> {
+fn arrow_blockquote_line<'a>(
+    input: Span<'a>,
+) -> IResult<(usize, Cow<'a, str>)> {
     let (input, _) = space0(input)?;
-    let (input, _) = tag("> ")(input)?;
+    let (input, _) = char('>')(input)?;
+    let (input, depth) =
+        fold_many0(preceded(space0, char('>')), 1, |depth, _| depth + 1)(
+            input,
+        )?;
+    let (input, _) = char(' ')(input)?;
     let (input, text) = map_parser(not_line_ending, cow_str)(input)?;
     let (input, _) = end_of_line_or_input(input)?;
 
+    let depth = if text.is_empty() { 0 } else { depth };
+    Ok((input, (depth, text)))
+}
+
+/// Parses a line without a > prefix that continues the blockquote, which
+/// must not be blank or start another block element
+#[inline]
+fn lazy_continuation_line<'a>(input: Span<'a>) -> IResult<Cow<'a, str>> {
+    let (input, _) = continue_paragraph(input)?;
+    let (input, _) = space0(input)?;
+    let (input, text) = map_parser(
+        verify(not_line_ending, |s: &Span<'a>| !s.is_only_whitespace()),
+        cow_str,
+    )(input)?;
+    let (input, _) = end_of_line_or_input(input)?;
+
     Ok((input, text))
 }
 
@@ -206,4 +248,42 @@ mod tests {
         assert_eq!(bq[3], "This is another blockquote");
         assert_eq!(bq[4], "that is using prefixes");
     }
+
+    #[test]
+    fn blockquote_should_support_nested_angle_prefixes() {
+        let input = Span::from(indoc! {"
+        > outer
+        >> inner
+        > > also inner
+
+        >>> deepest
+        "});
+        let (input, bq) = blockquote(input).unwrap();
+        assert!(input.is_empty(), "Did not consume blockquote");
+
+        assert_eq!(
+            bq.lines,
+            vec!["outer", "inner", "also inner", "", "deepest"]
+        );
+        assert_eq!(bq.depths, vec![1, 2, 2, 0, 3]);
+        assert_eq!(bq.max_depth(), 3);
+    }
+
+    #[test]
+    fn blockquote_should_support_lazy_continuation_lines() {
+        let input = Span::from(indoc! {"
+        >> inner quote
+        continued lazily
+        > outer quote
+        = header =
+        "});
+        let (input, bq) = blockquote(input).unwrap();
+        assert_eq!(input.as_unsafe_remaining_str(), "= header =\n");
+
+        assert_eq!(
+            bq.lines,
+            vec!["inner quote", "continued lazily", "outer quote"]
+        );
+        assert_eq!(bq.depths, vec![2, 2, 1]);
+    }
 }
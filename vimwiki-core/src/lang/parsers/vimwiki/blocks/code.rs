@@ -2,8 +2,8 @@ use crate::lang::{
     elements::{CodeBlock, Located},
     parsers::{
         utils::{
-            any_line, capture, context, count_remaining_bytes, cow_str,
-            end_of_line_or_input, locate, take_line_until, take_line_until1,
+            any_line, capture, context, cow_str, end_of_line_or_input, locate,
+            take_line_until, take_line_until1,
         },
         IResult, Span,
     },
@@ -23,20 +23,21 @@ type Metadata<'a> = HashMap<Cow<'a, str>, Cow<'a, str>>;
 #[inline]
 pub fn code_block(input: Span) -> IResult<Located<CodeBlock>> {
     fn inner(input: Span) -> IResult<CodeBlock> {
-        let (input, (start_indent_size, maybe_lang, metadata)) =
+        let (input, (start_indent, maybe_lang, metadata)) =
             code_block_start(input)?;
         let (input, lines) = many0(preceded(
             not(code_block_end),
             map_parser(any_line, cow_str),
         ))(input)?;
-        let (input, end_indent_size) = code_block_end(input)?;
+        let (input, end_indent) = code_block_end(input)?;
 
         // We need to adjust the start of each line based on the indentation
-        // of the code block start/end and the space at the beginning of a line
-        let indent_size = std::cmp::min(start_indent_size, end_indent_size);
-        let lines = lines
+        // of the code block start/end and the space at the beginning of a
+        // line, remembering what was removed to reproduce the original
+        let indent_size = std::cmp::min(start_indent.len(), end_indent.len());
+        let (lines, mut line_indents): (Vec<_>, Vec<Cow<str>>) = lines
             .into_iter()
-            .map(|mut line| {
+            .map(|line| {
                 // Figure out total bytes of leading whitespace so we know if
                 // the line is at the same level of indentation, further, or
                 // earlier
@@ -44,17 +45,31 @@ pub fn code_block(input: Span) -> IResult<Located<CodeBlock>> {
                 let cnt_to_remove = std::cmp::min(cnt, indent_size);
 
                 match line {
-                    Cow::Borrowed(ref mut x) => *x = &x[cnt_to_remove..],
-                    Cow::Owned(ref mut x) => {
-                        *x = x[cnt_to_remove..].to_string()
-                    }
+                    Cow::Borrowed(x) => (
+                        Cow::Borrowed(&x[cnt_to_remove..]),
+                        Cow::Borrowed(&x[..cnt_to_remove]),
+                    ),
+                    Cow::Owned(x) => (
+                        Cow::Owned(x[cnt_to_remove..].to_string()),
+                        Cow::Owned(x[..cnt_to_remove].to_string()),
+                    ),
                 }
-
-                line
             })
-            .collect();
+            .unzip();
+
+        // NOTE: Lines that had nothing removed do not need to be remembered
+        if line_indents.iter().all(|x| x.is_empty()) {
+            line_indents.clear();
+        }
 
-        Ok((input, CodeBlock::new(maybe_lang, metadata, lines)))
+        Ok((
+            input,
+            CodeBlock::new(maybe_lang, metadata, lines).with_indents(
+                start_indent,
+                end_indent,
+                line_indents,
+            ),
+        ))
     }
 
     context("Preformatted Text", locate(capture(inner)))(input)
@@ -63,10 +78,9 @@ pub fn code_block(input: Span) -> IResult<Located<CodeBlock>> {
 #[inline]
 fn code_block_start<'a>(
     input: Span<'a>,
-) -> IResult<(usize, MaybeLang<'a>, Metadata<'a>)> {
+) -> IResult<(Cow<'a, str>, MaybeLang<'a>, Metadata<'a>)> {
     // First, verify we have the start of a block and consume it
-    let (input, indent_size) =
-        map_parser(space0, count_remaining_bytes)(input)?;
+    let (input, indent) = map_parser(space0, cow_str)(input)?;
     let (input, _) = tag("{{{")(input)?;
 
     // Second, look for optional language and consume it
@@ -102,21 +116,17 @@ fn code_block_start<'a>(
     let (input, _) = space0(input)?;
     let (input, _) = end_of_line_or_input(input)?;
 
-    Ok((
-        input,
-        (indent_size, maybe_lang, pairs.into_iter().collect()),
-    ))
+    Ok((input, (indent, maybe_lang, pairs.into_iter().collect())))
 }
 
 #[inline]
-fn code_block_end(input: Span) -> IResult<usize> {
-    let (input, indent_size) =
-        map_parser(space0, count_remaining_bytes)(input)?;
+fn code_block_end<'a>(input: Span<'a>) -> IResult<'a, Cow<'a, str>> {
+    let (input, indent) = map_parser(space0, cow_str)(input)?;
     let (input, _) = tag("}}}")(input)?;
     let (input, _) = space0(input)?;
     let (input, _) = end_of_line_or_input(input)?;
 
-    Ok((input, indent_size))
+    Ok((input, indent))
 }
 
 #[cfg(test)]
@@ -367,4 +377,41 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn code_block_should_retain_indentation_of_fences_and_lines() {
+        let input = Span::from(indoc! {"
+                {{{
+            one line
+                    two line
+              \tthree line
+              }}}
+        "});
+        let (input, p) = code_block(input).unwrap();
+        assert!(input.is_empty(), "Did not consume code block");
+        assert_eq!(p.start_indent, "    ");
+        assert_eq!(p.end_indent, "  ");
+        assert_eq!(p.line_indents, vec!["", "  ", "  "]);
+
+        // Raw content is exactly what was between the fences
+        assert_eq!(
+            p.raw_content(),
+            "one line\n        two line\n  \tthree line"
+        );
+
+        // Lines are relative to the fences
+        assert_eq!(p.to_string(), "one line\n      two line\n\tthree line");
+
+        // Dedented content removes indentation shared by all lines
+        let input = Span::from(indoc! {"
+            {{{
+                fn main() {
+                    body();
+
+                }
+            }}}
+        "});
+        let (_, p) = code_block(input).unwrap();
+        assert_eq!(p.dedented_content(), "fn main() {\n    body();\n\n}");
+    }
 }
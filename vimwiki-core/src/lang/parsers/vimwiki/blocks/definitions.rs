@@ -120,9 +120,9 @@ mod tests {
         DecoratedText, DecoratedTextContent, InlineElement,
         InlineElementContainer, Link, MathInline, Text,
     };
+    use crate::WikiUri;
     use indoc::indoc;
     use std::convert::TryFrom;
-    use uriparse::URIReference;
 
     /// Checks defs match those of a provided list in ANY order
     fn check_text_defs(defs: Vec<&Definition>, expected: Vec<&str>) {
@@ -392,7 +392,7 @@ mod tests {
             defs[0],
             &InlineElementContainer::new(vec![Located::from(
                 InlineElement::from(Link::new_wiki_link(
-                    URIReference::try_from("def%201").unwrap(),
+                    WikiUri::try_from("def%201").unwrap(),
                     None
                 ))
             )])
@@ -14,17 +14,37 @@ use nom::{character::complete::char, combinator::verify};
 pub fn divider(input: Span) -> IResult<Located<Divider>> {
     fn inner(input: Span) -> IResult<Divider> {
         let (input, _) = beginning_of_line(input)?;
-        let (input, _) = verify(take_line_while1(char('-')), |s: &Span| {
-            s.remaining_len() >= 4
-        })(input)?;
+        let blank_lines_before = count_blank_lines(
+            input.as_unsafe_consumed_str().split_inclusive('\n').rev(),
+        );
+        let (input, hyphens) =
+            verify(take_line_while1(char('-')), |s: &Span| {
+                s.remaining_len() >= Divider::MIN_LEN
+            })(input)?;
         let (input, _) = end_of_line_or_input(input)?;
+        let blank_lines_after = count_blank_lines(
+            input.as_unsafe_remaining_str().split_inclusive('\n'),
+        );
 
-        Ok((input, Divider))
+        Ok((
+            input,
+            Divider::new(
+                hyphens.remaining_len(),
+                blank_lines_before,
+                blank_lines_after,
+            ),
+        ))
     }
 
     context("Divider", locate(capture(inner)))(input)
 }
 
+/// Counts the lines that are blank before reaching a line that is not
+#[inline]
+fn count_blank_lines<'a>(lines: impl Iterator<Item = &'a str>) -> usize {
+    lines.take_while(|line| line.trim().is_empty()).count()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,6 +81,19 @@ mod tests {
         assert!(input.is_empty(), "Divider not consumed");
     }
 
+    #[test]
+    fn divider_should_record_hyphens_and_surrounding_blank_lines() {
+        let input = Span::from("text\n\n\n-------\n\nabcd");
+        let input = input.advance_start_by(7);
+        let (input, d) = divider(input).unwrap();
+        assert_eq!(input.as_unsafe_remaining_str(), "\nabcd");
+        assert_eq!(d.into_inner(), Divider::new(7, 2, 1));
+
+        let input = Span::from("----\r\n\r\n");
+        let (_, d) = divider(input).unwrap();
+        assert_eq!(d.into_inner(), Divider::new(4, 0, 1));
+    }
+
     #[test]
     fn divider_should_consume_end_of_line() {
         let input = Span::from("----\nabcd");
@@ -0,0 +1,82 @@
+use crate::lang::{
+    elements::{FootnoteDefinition, InlineElementContainer, Located},
+    parsers::{
+        utils::{
+            beginning_of_line, capture, context, end_of_line_or_input, locate,
+        },
+        vimwiki::blocks::inline::{
+            footnotes::footnote_label, inline_element_container,
+        },
+        IResult, Span,
+    },
+};
+use nom::{
+    character::complete::{char, space0},
+    combinator::{map, opt},
+};
+
+/// Parses a footnote definition in the form of `[^label]: contents`, which
+/// must start at the beginning of a line
+#[inline]
+pub fn footnote_definition(
+    input: Span,
+) -> IResult<Located<FootnoteDefinition>> {
+    fn inner(input: Span) -> IResult<FootnoteDefinition> {
+        let (input, _) = beginning_of_line(input)?;
+        let (input, label) = footnote_label(input)?;
+        let (input, _) = char(':')(input)?;
+        let (input, _) = space0(input)?;
+        let (input, contents) =
+            opt(map(inline_element_container, |c| c.into_inner()))(input)?;
+        let (input, _) = end_of_line_or_input(input)?;
+
+        Ok((
+            input,
+            FootnoteDefinition::new(
+                label,
+                contents.unwrap_or_else(|| InlineElementContainer::new(vec![])),
+            ),
+        ))
+    }
+
+    context("Footnote Definition", locate(capture(inner)))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::elements::{InlineElement, Text};
+
+    #[test]
+    fn footnote_definition_should_fail_if_not_at_beginning_of_line() {
+        let input = Span::from(" [^note]: text");
+        assert!(footnote_definition(input).is_err());
+    }
+
+    #[test]
+    fn footnote_definition_should_fail_without_colon_after_label() {
+        let input = Span::from("[^note] text");
+        assert!(footnote_definition(input).is_err());
+    }
+
+    #[test]
+    fn footnote_definition_should_parse_label_and_contents() {
+        let input = Span::from("[^note]: some *text*\nafter");
+        let (input, definition) = footnote_definition(input).unwrap();
+        assert_eq!(input, "after", "Unexpected input consumed");
+        assert_eq!(definition.label, "note");
+        assert_eq!(
+            definition.contents[0],
+            InlineElement::from(Text::from("some "))
+        );
+        assert_eq!(definition.contents.len(), 2);
+    }
+
+    #[test]
+    fn footnote_definition_should_support_empty_contents() {
+        let input = Span::from("[^note]:");
+        let (input, definition) = footnote_definition(input).unwrap();
+        assert!(input.is_empty(), "Did not consume all of input");
+        assert!(definition.contents.is_empty());
+    }
+}
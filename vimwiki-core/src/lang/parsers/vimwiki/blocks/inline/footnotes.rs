@@ -0,0 +1,61 @@
+use crate::lang::{
+    elements::{FootnoteReference, Located},
+    parsers::{
+        utils::{capture, context, cow_str, locate, take_line_while1},
+        IResult, Span,
+    },
+};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, none_of},
+    combinator::{map, map_parser},
+    sequence::delimited,
+};
+
+#[inline]
+pub fn footnote_reference(input: Span) -> IResult<Located<FootnoteReference>> {
+    fn inner(input: Span) -> IResult<FootnoteReference> {
+        map(footnote_label, FootnoteReference::new)(input)
+    }
+
+    context("Footnote Reference", locate(capture(inner)))(input)
+}
+
+/// Parses the label of a footnote in the form of `[^label]`, where the label
+/// cannot contain whitespace or brackets
+pub fn footnote_label(input: Span) -> IResult<std::borrow::Cow<str>> {
+    context(
+        "Footnote Label",
+        delimited(
+            tag("[^"),
+            map_parser(take_line_while1(none_of(" \t[]")), cow_str),
+            char(']'),
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn footnote_reference_should_fail_if_input_empty() {
+        let input = Span::from("");
+        assert!(footnote_reference(input).is_err());
+    }
+
+    #[test]
+    fn footnote_reference_should_fail_if_label_is_empty_or_has_whitespace() {
+        assert!(footnote_reference(Span::from("[^]")).is_err());
+        assert!(footnote_reference(Span::from("[^some note]")).is_err());
+        assert!(footnote_reference(Span::from("[^note")).is_err());
+    }
+
+    #[test]
+    fn footnote_reference_should_consume_label_within_brackets() {
+        let input = Span::from("[^note-1] after");
+        let (input, reference) = footnote_reference(input).unwrap();
+        assert_eq!(input, " after", "Unexpected input consumed");
+        assert_eq!(reference.as_str(), "note-1");
+    }
+}
@@ -1,4 +1,5 @@
 use super::{link_anchor, link_description};
+use crate::date::WikiDate;
 use crate::lang::{
     elements::{Link, Located},
     parsers::{
@@ -9,7 +10,6 @@ use crate::lang::{
         IResult, Span,
     },
 };
-use chrono::NaiveDate;
 use nom::{
     bytes::complete::tag,
     combinator::{map_parser, map_res, opt},
@@ -24,7 +24,7 @@ pub fn diary_link(input: Span) -> IResult<Located<Link>> {
         // end of a link, start of anchor, or start of a description
         let (input, date) =
             map_res(take_line_until_one_of_two1("|", "#"), |span| {
-                NaiveDate::parse_from_str(
+                WikiDate::parse_from_str(
                     span.as_unsafe_remaining_str(),
                     "%Y-%m-%d",
                 )
@@ -77,7 +77,7 @@ mod tests {
         // Link should be consumed
         assert!(input.is_empty());
 
-        assert_eq!(link.date(), Some(NaiveDate::from_ymd(2012, 3, 5)));
+        assert_eq!(link.date(), Some(WikiDate::from_ymd(2012, 3, 5)));
         assert_eq!(link.description(), None);
         assert_eq!(link.to_anchor(), None);
     }
@@ -91,7 +91,7 @@ mod tests {
         // Link should be consumed
         assert!(input.is_empty());
 
-        assert_eq!(link.date(), Some(NaiveDate::from_ymd(2012, 3, 5)));
+        assert_eq!(link.date(), Some(WikiDate::from_ymd(2012, 3, 5)));
         assert_eq!(
             link.description(),
             Some(&Description::from("some description"))
@@ -108,7 +108,7 @@ mod tests {
         // Link should be consumed
         assert!(input.is_empty());
 
-        assert_eq!(link.date(), Some(NaiveDate::from_ymd(2012, 3, 5)));
+        assert_eq!(link.date(), Some(WikiDate::from_ymd(2012, 3, 5)));
         assert_eq!(link.description(), None);
         assert_eq!(link.to_anchor(), Some(Anchor::from("Tomorrow")));
     }
@@ -123,7 +123,7 @@ mod tests {
         // Link should be consumed
         assert!(input.is_empty());
 
-        assert_eq!(link.date(), Some(NaiveDate::from_ymd(2012, 3, 5)));
+        assert_eq!(link.date(), Some(WikiDate::from_ymd(2012, 3, 5)));
         assert_eq!(
             link.description(),
             Some(&Description::from("Tasks for tomorrow"))
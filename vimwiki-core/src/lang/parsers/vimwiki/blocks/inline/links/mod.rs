@@ -1,12 +1,15 @@
-use crate::lang::{
-    elements::{Anchor, Description, Link, LinkData, Located},
-    parsers::{
-        utils::{
-            context, cow_str, take_line_until, take_line_until1,
-            take_line_until_one_of_three1,
+use crate::{
+    lang::{
+        elements::{Anchor, Description, Link, LinkData, Located},
+        parsers::{
+            utils::{
+                context, cow_str, take_line_until, take_line_until1,
+                take_line_until_one_of_three1,
+            },
+            Error, IResult, Span,
         },
-        Error, IResult, Span,
     },
+    uri::{is_uri_text, WikiUri},
 };
 use nom::{
     branch::alt,
@@ -16,8 +19,7 @@ use nom::{
     multi::separated_list1,
     sequence::{delimited, separated_pair},
 };
-use std::{borrow::Cow, collections::HashMap, convert::TryFrom};
-use uriparse::URIReference;
+use std::{borrow::Cow, collections::HashMap};
 
 mod diary;
 mod interwiki;
@@ -26,7 +28,7 @@ mod transclusion;
 mod wiki;
 
 // Exporting for optimization in text(...)
-pub(crate) use raw::raw_link;
+pub(crate) use raw::{is_email_local_char, raw_link};
 
 /// Inspecting vimwiki source code, there are a couple of link utils
 ///
@@ -96,27 +98,37 @@ fn link_data<'a>(input: Span<'a>) -> IResult<LinkData<'a>> {
 /// %20 encoded characters
 ///
 /// Can either be a text description OR an embeded {{...}} transclusion link
-fn link_uri_ref<'a>(input: Span<'a>) -> IResult<URIReference<'a>> {
+fn link_uri_ref<'a>(input: Span<'a>) -> IResult<WikiUri<'a>> {
     let (input, uri_span) =
         take_line_until_one_of_three1("|", "]]", "}}")(input)?;
 
-    match URIReference::try_from(uri_span) {
-        Ok(uri_ref) => Ok((input, uri_ref)),
-        Err(_) => {
-            // NOTE: We encode our string, but need to repair the first #
-            //       which signals the fragment
-            let encoded_uri_str = LinkData::encode_uri(uri_span.as_remaining());
-            let uri_ref = URIReference::try_from(encoded_uri_str.as_str())
-                .map_err(|x| {
-                    use nom::error::FromExternalError;
-                    nom::Err::Error(Error::from_external_error(
-                        uri_span,
-                        nom::error::ErrorKind::MapRes,
-                        x,
-                    ))
-                })?
-                .into_owned();
-            Ok((input, uri_ref))
+    // NOTE: Windows paths are converted into the uri of their file, and the
+    //       uri is only checked to be made of uri characters here as it is
+    //       parsed into its parts the first time that they are needed
+    let text: Option<Cow<'a, str>> = uri_span
+        .map_remaining_into(std::str::from_utf8)
+        .ok()
+        .map(|text| {
+            LinkData::normalize_windows_path(text)
+                .map_or(Cow::Borrowed(text), Cow::Owned)
+        });
+
+    match text {
+        Some(text) if is_uri_text(&text) => Ok((input, WikiUri::lazy(text))),
+        text => {
+            let encoded_uri_str = LinkData::encode_uri(
+                text.as_deref()
+                    .map_or(uri_span.as_remaining(), str::as_bytes),
+            );
+            if is_uri_text(&encoded_uri_str) {
+                Ok((input, WikiUri::lazy(encoded_uri_str)))
+            } else {
+                use nom::error::ParseError;
+                Err(nom::Err::Error(Error::from_error_kind(
+                    uri_span,
+                    nom::error::ErrorKind::MapRes,
+                )))
+            }
         }
     }
 }
@@ -1,31 +1,87 @@
-use crate::lang::{
-    elements::{Link, Located},
-    parsers::{
-        utils::{capture, context, locate, uri_ref},
-        IResult, Span,
+use crate::{
+    lang::{
+        elements::{Link, Located},
+        parsers::{
+            configured_email_autolinks,
+            utils::{capture, context, cow_str, locate, uri_ref},
+            Error, IResult, Span,
+        },
     },
+    schemes::is_valid_raw_link,
+};
+use nom::{
+    branch::alt,
+    bytes::complete::take_while1,
+    character::complete::char,
+    combinator::{map, map_parser, recognize, verify},
+    multi::many1,
+    sequence::{pair, preceded, tuple},
 };
-use nom::combinator::verify;
 
 pub fn raw_link(input: Span) -> IResult<Located<Link>> {
-    fn inner(input: Span) -> IResult<Link> {
+    fn uri_link(input: Span) -> IResult<Link> {
         // This will match any URI, but we only want to allow a certain set
-        // to ensure that we don't mistake some text preceding a tag
+        // to ensure that we don't mistake some text preceding a tag, being
+        // the built-in schemes and any with a registered handler
         //
         // NOTE: We don't use link_uri_ref because we don't want to auto-escape
         //       spaces or other characters. For raw links, that is up to the
         //       user to do so
-        let (input, uri_ref) = verify(uri_ref, |uri_ref| {
-            uri_ref.scheme().map_or(false, |scheme| {
-                ["http", "https", "ftp", "file", "local", "mailto"]
-                    .contains(&scheme.as_str())
-            })
-        })(input)?;
+        let (input, uri_ref) = verify(uri_ref, is_valid_raw_link)(input)?;
 
         Ok((input, Link::new_raw_link(uri_ref)))
     }
 
-    context("Raw Link", locate(capture(inner)))(input)
+    context("Raw Link", locate(capture(alt((uri_link, email_link)))))(input)
+}
+
+/// Parses a bare email address (e.g. `user@example.com`) as a raw link to its
+/// `mailto` uri, which is only done when enabled by the active config
+fn email_link(input: Span) -> IResult<Link> {
+    if !configured_email_autolinks() {
+        return Err(nom::Err::Error(Error::from_ctx(
+            &input,
+            "Email autolinks disabled",
+        )));
+    }
+
+    context(
+        "Email Link",
+        map(map_parser(email_address, cow_str), Link::new_email_link),
+    )(input)
+}
+
+/// Recognizes an email address, being a local part of letters, digits, and
+/// `._%+-` that neither starts nor ends with a dot, followed by `@` and a
+/// domain of at least two labels separated by dots
+fn email_address(input: Span) -> IResult<Span> {
+    fn local_part(input: Span) -> IResult<Span> {
+        verify(take_while1(is_email_local_char), |s: &Span| {
+            let bytes = s.as_remaining();
+            bytes[0] != b'.' && bytes[bytes.len() - 1] != b'.'
+        })(input)
+    }
+
+    fn label(input: Span) -> IResult<Span> {
+        verify(
+            take_while1(|b: u8| b.is_ascii_alphanumeric() || b == b'-'),
+            |s: &Span| {
+                let bytes = s.as_remaining();
+                bytes[0] != b'-' && bytes[bytes.len() - 1] != b'-'
+            },
+        )(input)
+    }
+
+    recognize(tuple((
+        local_part,
+        char('@'),
+        pair(label, many1(preceded(char('.'), label))),
+    )))(input)
+}
+
+/// Returns true if the byte can be part of the local part of an email address
+pub(crate) fn is_email_local_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'%' | b'+' | b'-')
 }
 
 #[cfg(test)]
@@ -128,4 +184,58 @@ mod tests {
         assert_eq!(link.scheme().unwrap(), "mailto");
         assert_eq!(link.data().uri_ref.path(), "person@example.com");
     }
+
+    #[test]
+    fn raw_link_should_support_schemes_with_registered_handlers() {
+        use crate::schemes::{self, SchemeTemplate};
+
+        let input = Span::from("zotero:select/items/ABC123");
+        assert!(raw_link(input).is_err(), "Unregistered scheme was parsed");
+
+        schemes::register(SchemeTemplate::new("zotero", "zotero://{value}"));
+        let (input, link) = raw_link(input).expect("Failed to parse uri");
+
+        // Link should be consumed
+        assert!(input.is_empty());
+
+        assert_eq!(link.scheme().unwrap(), "zotero");
+        assert_eq!(link.data().uri_ref.path(), "select/items/ABC123");
+
+        // Handlers can still reject links of their scheme
+        assert!(raw_link(Span::from("zotero:")).is_err());
+        schemes::unregister("zotero");
+    }
+
+    #[test]
+    fn raw_link_should_support_email_addresses_if_configured() {
+        use crate::lang::{
+            elements::Description,
+            parsers::{config_scope, ParserConfig},
+        };
+
+        let input = Span::from("first.last+tag@mail.example.com.");
+        assert!(raw_link(input).is_err(), "Email parsed while disabled");
+
+        let config = ParserConfig::default().with_email_autolinks(true);
+        config_scope(&config, || {
+            let (input, link) = raw_link(input).expect("Failed to parse email");
+
+            // Trailing dot is not part of the address
+            assert_eq!(input, ".");
+
+            assert_eq!(link.scheme().unwrap(), "mailto");
+            assert_eq!(
+                link.data().uri_ref.to_string(),
+                "mailto:first.last+tag@mail.example.com"
+            );
+            assert_eq!(
+                link.description(),
+                Some(&Description::from("first.last+tag@mail.example.com"))
+            );
+
+            assert!(raw_link(Span::from("user@localhost")).is_err());
+            assert!(raw_link(Span::from(".user@example.com")).is_err());
+            assert!(raw_link(Span::from("user@-example.com")).is_err());
+        });
+    }
 }
@@ -176,4 +176,26 @@ mod tests {
         );
         assert_eq!(link.data().get_property_str("style"), Some("something"));
     }
+
+    #[test]
+    fn transclusion_link_should_normalize_windows_paths() {
+        let input = Span::from(r"{{C:\My Images\logo.png|logo}}");
+        let (input, link) = transclusion_link(input).unwrap();
+        assert!(input.is_empty(), "Did not consume link");
+        assert_eq!(link.scheme().unwrap(), "file");
+        assert_eq!(
+            link.data().uri_ref.to_string(),
+            "file:///C:/My%20Images/logo.png"
+        );
+        assert_eq!(link.description(), Some(&Description::from("logo")));
+
+        let input = Span::from(r"{{file:\\server\share\logo.png}}");
+        let (input, link) = transclusion_link(input).unwrap();
+        assert!(input.is_empty(), "Did not consume link");
+        assert_eq!(
+            link.data().uri_ref.to_string(),
+            "file://server/share/logo.png"
+        );
+        assert_eq!(link.data().uri_ref.host().as_deref(), Some("server"));
+    }
 }
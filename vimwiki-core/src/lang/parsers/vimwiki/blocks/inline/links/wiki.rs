@@ -28,8 +28,8 @@ pub fn wiki_link(input: Span) -> IResult<Located<Link>> {
 mod tests {
     use super::*;
     use crate::lang::elements::{Anchor, Description};
+    use crate::WikiUri;
     use std::{borrow::Cow, convert::TryFrom};
-    use uriparse::URIReference;
 
     #[test]
     fn wiki_link_should_fail_if_does_not_have_proper_prefix() {
@@ -96,7 +96,7 @@ mod tests {
         assert_eq!(
             link.description(),
             Some(&Description::from(
-                URIReference::try_from("https://example.com/img.jpg")
+                WikiUri::try_from("https://example.com/img.jpg")
                     .unwrap()
                     .into_owned()
             ))
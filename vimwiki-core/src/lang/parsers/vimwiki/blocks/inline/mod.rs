@@ -9,6 +9,7 @@ use nom::{branch::alt, combinator::map, multi::many1};
 
 pub mod code;
 pub mod comments;
+pub mod footnotes;
 pub mod links;
 pub mod math;
 pub mod tags;
@@ -44,6 +45,9 @@ pub fn inline_element(input: Span) -> IResult<Located<InlineElement>> {
             map(math::math_inline, |c| c.map(InlineElement::from)),
             map(code::code_inline, |c| c.map(InlineElement::from)),
             map(tags::tags, |c| c.map(InlineElement::from)),
+            map(footnotes::footnote_reference, |c| {
+                c.map(InlineElement::from)
+            }),
             map(links::link, |c| c.map(InlineElement::from)),
             map(typefaces::decorated_text, |c| c.map(InlineElement::from)),
             map(typefaces::keyword, |c| c.map(InlineElement::from)),
@@ -63,8 +67,8 @@ mod tests {
         },
         parsers::Span,
     };
+    use crate::WikiUri;
     use std::convert::TryFrom;
-    use uriparse::URIReference;
 
     #[test]
     fn inline_element_container_should_prioritize_comments_over_bold_text() {
@@ -224,7 +228,7 @@ mod tests {
                 ],)),
                 InlineElement::Text(Text::from(" has a ")),
                 InlineElement::Link(Link::new_wiki_link(
-                    URIReference::try_from("link").unwrap(),
+                    WikiUri::try_from("link").unwrap(),
                     None
                 )),
                 InlineElement::Text(Text::from(" with ")),
@@ -1,19 +1,21 @@
 use super::{
     code::code_inline,
     comments::comment,
-    links::{link, raw_link},
+    footnotes::footnote_reference,
+    links::{is_email_local_char, link, raw_link},
     math::math_inline,
     tags::tags,
 };
 use crate::lang::{
     elements::{
-        DecoratedText, DecoratedTextContent, InlineElement, Keyword, Link,
-        Located, Text,
+        CodeInline, DecoratedText, DecoratedTextContent, FootnoteReference,
+        InlineElement, Keyword, Link, Located, MathInline, Text,
     },
     parsers::{
+        configured_email_autolinks, configured_keywords,
         utils::{
             capture, context, cow_str, deeper, locate, not_contains,
-            surround_in_line1,
+            surround_in_line1_skipping,
         },
         Error, IResult, Span,
     },
@@ -23,7 +25,7 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::char,
-    combinator::{map, map_parser, peek},
+    combinator::{all_consuming, map, map_parser, peek, verify},
     multi::many1,
     sequence::preceded,
 };
@@ -45,6 +47,9 @@ pub fn text(input: Span) -> IResult<Located<Text>> {
             map(preceded(peek(char(':')), tags), |x| {
                 x.map(InlineElement::from)
             }),
+            map(preceded(peek(char('[')), footnote_reference), |x| {
+                x.map(InlineElement::from)
+            }),
             map(preceded(peek(alt((char('['), char('{')))), link), |x| {
                 x.map(InlineElement::from)
             }),
@@ -85,6 +90,31 @@ pub fn text(input: Span) -> IResult<Located<Text>> {
                 }),
                 |x| x.map(InlineElement::from),
             ),
+            // Special case for email addresses as @ signifies a possible
+            // address where we need to backtrack to the start of its local
+            // part to use as the span
+            map(
+                preceded(
+                    peek(verify(char('@'), |_| configured_email_autolinks())),
+                    |input: Span<'a>| {
+                        let consumed_len = input.consumed_len();
+                        let consumed = input.as_consumed();
+
+                        let mut neg_offset = 0;
+                        while consumed_len > neg_offset
+                            && is_email_local_char(
+                                consumed[consumed_len - neg_offset - 1],
+                            )
+                        {
+                            neg_offset += 1;
+                        }
+
+                        let input = input.backtrack_start_by(neg_offset);
+                        raw_link(input)
+                    },
+                ),
+                |x| x.map(InlineElement::from),
+            ),
         ))(input)
     }
 
@@ -146,7 +176,7 @@ fn italic_text(input: Span) -> IResult<DecoratedText> {
         "Italic Decorated Text",
         map(
             map_parser(
-                not_contains("%%", surround_in_line1("_", "_")),
+                not_contains("%%", decoration("_", "_")),
                 deeper(decorated_text_contents),
             ),
             DecoratedText::Italic,
@@ -159,7 +189,7 @@ fn bold_text(input: Span) -> IResult<DecoratedText> {
         "Bold Decorated Text",
         map(
             map_parser(
-                not_contains("%%", surround_in_line1("*", "*")),
+                not_contains("%%", decoration("*", "*")),
                 deeper(decorated_text_contents),
             ),
             DecoratedText::Bold,
@@ -172,7 +202,7 @@ fn strikeout_text(input: Span) -> IResult<DecoratedText> {
         "Strikeout Decorated Text",
         map(
             map_parser(
-                not_contains("%%", surround_in_line1("~~", "~~")),
+                not_contains("%%", decoration("~~", "~~")),
                 deeper(decorated_text_contents),
             ),
             DecoratedText::Strikeout,
@@ -185,7 +215,7 @@ fn superscript_text(input: Span) -> IResult<DecoratedText> {
         "Superscript Decorated Text",
         map(
             map_parser(
-                not_contains("%%", surround_in_line1("^", "^")),
+                not_contains("%%", decoration("^", "^")),
                 deeper(decorated_text_contents),
             ),
             DecoratedText::Superscript,
@@ -198,7 +228,7 @@ fn subscript_text(input: Span) -> IResult<DecoratedText> {
         "Subscript Decorated Text",
         map(
             map_parser(
-                not_contains("%%", surround_in_line1(",,", ",,")),
+                not_contains("%%", decoration(",,", ",,")),
                 deeper(decorated_text_contents),
             ),
             DecoratedText::Subscript,
@@ -206,11 +236,30 @@ fn subscript_text(input: Span) -> IResult<DecoratedText> {
     )(input)
 }
 
+/// Parser that consumes the content between the left and right sides of a
+/// decoration, where the right side is not matched within inline code or math
+/// so that `*a `b*c` d*` is bold text containing code
+fn decoration<'a>(
+    left: &'static str,
+    right: &'static str,
+) -> impl FnMut(Span<'a>) -> IResult<Span<'a>> {
+    surround_in_line1_skipping(left, right, b"`$")
+}
+
 fn decorated_text_contents<'a>(
     input: Span<'a>,
 ) -> IResult<Vec<Located<DecoratedTextContent<'a>>>> {
     fn inner(input: Span) -> IResult<Vec<Located<DecoratedTextContent>>> {
         many1(alt((
+            map(code_inline, |l: Located<CodeInline>| {
+                l.map(DecoratedTextContent::from)
+            }),
+            map(math_inline, |l: Located<MathInline>| {
+                l.map(DecoratedTextContent::from)
+            }),
+            map(footnote_reference, |l: Located<FootnoteReference>| {
+                l.map(DecoratedTextContent::from)
+            }),
             map(link, |l: Located<Link>| l.map(DecoratedTextContent::from)),
             map(keyword, |l: Located<Keyword>| {
                 l.map(DecoratedTextContent::from)
@@ -222,35 +271,54 @@ fn decorated_text_contents<'a>(
         )))(input)
     }
 
-    context("Decorated Text Contents", inner)(input)
+    // NOTE: All content must be consumed, otherwise content such as tags
+    //       that cannot be decorated would be silently dropped
+    context("Decorated Text Contents", all_consuming(inner))(input)
 }
 
 #[inline]
 pub fn keyword(input: Span) -> IResult<Located<Keyword>> {
-    // TODO: Generate using strum to iterate over all keyword items,
-    //       forming a tag based on the string version and parsing the
-    //       string back into the keyword in a map (or possibly using
-    //       the keyword enum variant directly if we can iterate over
-    //       the variants themselves)
-    context(
-        "Keyword",
-        locate(capture(alt((
+    fn configured_keyword(input: Span) -> IResult<Keyword> {
+        let keywords = match configured_keywords() {
+            Some(keywords) => keywords,
+            None => return builtin_keyword(input),
+        };
+
+        // NOTE: Keywords are ordered longest first so that the first match
+        //       is never the prefix of a longer keyword
+        let remaining = input.as_remaining();
+        match keywords
+            .iter()
+            .find(|x| remaining.starts_with(x.as_bytes()))
+        {
+            Some(x) => {
+                Ok((input.advance_start_by(x.len()), Keyword::from_name(x)))
+            }
+            None => Err(nom::Err::Error(Error::from_ctx(&input, "Keyword"))),
+        }
+    }
+
+    fn builtin_keyword(input: Span) -> IResult<Keyword> {
+        alt((
             map(tag("DONE"), |_| Keyword::Done),
             map(tag("FIXED"), |_| Keyword::Fixed),
             map(tag("FIXME"), |_| Keyword::Fixme),
             map(tag("STARTED"), |_| Keyword::Started),
             map(tag("TODO"), |_| Keyword::Todo),
             map(tag("XXX"), |_| Keyword::Xxx),
-        )))),
-    )(input)
+        ))(input)
+    }
+
+    context("Keyword", locate(capture(configured_keyword)))(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::lang::elements::Link;
+    use crate::lang::parsers::{config_scope, ParserConfig};
+    use crate::WikiUri;
     use std::convert::TryFrom;
-    use uriparse::URIReference;
 
     #[test]
     fn text_should_fail_if_input_empty() {
@@ -324,6 +392,29 @@ mod tests {
         assert_eq!(t.into_inner(), Text::from("abc123 "));
     }
 
+    #[test]
+    fn text_should_consume_until_encountering_an_email_address_if_configured() {
+        let input = Span::from("mail (user.name@example.com) now");
+        let (input, t) = text(input).unwrap();
+        assert!(input.is_empty(), "Email parsed while disabled");
+        assert_eq!(
+            t.into_inner(),
+            Text::from("mail (user.name@example.com) now")
+        );
+
+        let config = ParserConfig::default().with_email_autolinks(true);
+        config_scope(&config, || {
+            let input = Span::from("mail (user.name@example.com) now");
+            let (input, t) = text(input).unwrap();
+            assert_eq!(
+                input.as_unsafe_remaining_str(),
+                "user.name@example.com) now",
+                "Unexpected input consumption"
+            );
+            assert_eq!(t.into_inner(), Text::from("mail ("));
+        });
+    }
+
     #[test]
     fn text_should_consume_until_encountering_decorated_text() {
         let input = Span::from("abc123*bold text*");
@@ -469,7 +560,7 @@ mod tests {
             dt.into_inner(),
             DecoratedText::Bold(vec![Located::from(
                 DecoratedTextContent::from(Link::new_wiki_link(
-                    URIReference::try_from("some%20link").unwrap(),
+                    WikiUri::try_from("some%20link").unwrap(),
                     None
                 ))
             )])
@@ -507,6 +598,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decorated_text_should_support_decorated_text_nested_within_content() {
+        let input = Span::from("*bold _italic_ bold*");
+        let (input, dt) = decorated_text(input).unwrap();
+        assert!(input.is_empty(), "Did not consume decorated text");
+        assert_eq!(
+            dt.into_inner(),
+            DecoratedText::Bold(vec![
+                Located::from(DecoratedTextContent::from(Text::from("bold "))),
+                Located::from(DecoratedTextContent::from(
+                    DecoratedText::Italic(vec![Located::from(
+                        DecoratedTextContent::from(Text::from("italic"))
+                    )])
+                )),
+                Located::from(DecoratedTextContent::from(Text::from(" bold"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn decorated_text_should_support_inline_code_and_math() {
+        let input = Span::from("*a `code` $math$ b*");
+        let (input, dt) = decorated_text(input).unwrap();
+        assert!(input.is_empty(), "Did not consume decorated text");
+        assert_eq!(
+            dt.into_inner(),
+            DecoratedText::Bold(vec![
+                Located::from(DecoratedTextContent::from(Text::from("a "))),
+                Located::from(DecoratedTextContent::from(CodeInline::from(
+                    "code"
+                ))),
+                Located::from(DecoratedTextContent::from(Text::from(" "))),
+                Located::from(DecoratedTextContent::from(MathInline::from(
+                    "math"
+                ))),
+                Located::from(DecoratedTextContent::from(Text::from(" b"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn decorated_text_should_not_end_within_inline_code() {
+        let input = Span::from("*a `b*c` d* e");
+        let (input, dt) = decorated_text(input).unwrap();
+        assert_eq!(input.as_unsafe_remaining_str(), " e");
+        assert_eq!(
+            dt.into_inner(),
+            DecoratedText::Bold(vec![
+                Located::from(DecoratedTextContent::from(Text::from("a "))),
+                Located::from(DecoratedTextContent::from(CodeInline::from(
+                    "b*c"
+                ))),
+                Located::from(DecoratedTextContent::from(Text::from(" d"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn decorated_text_should_support_footnote_references() {
+        let input = Span::from("*important[^1]*");
+        let (input, dt) = decorated_text(input).unwrap();
+        assert!(input.is_empty(), "Did not consume decorated text");
+        assert_eq!(
+            dt.into_inner(),
+            DecoratedText::Bold(vec![
+                Located::from(DecoratedTextContent::from(Text::from(
+                    "important"
+                ))),
+                Located::from(DecoratedTextContent::from(
+                    FootnoteReference::from("1")
+                )),
+            ])
+        );
+    }
+
+    #[test]
+    fn decorated_text_should_fail_rather_than_drop_undecoratable_content() {
+        let input = Span::from("*some :tag: here*");
+        assert!(decorated_text(input).is_err());
+    }
+
     #[test]
     fn decorated_text_should_properly_adjust_depth_for_content() {
         let input = Span::from(
@@ -562,4 +734,29 @@ mod tests {
         let (_, k) = keyword(input).unwrap();
         assert_eq!(k.into_inner(), Keyword::Xxx);
     }
+
+    #[test]
+    fn keyword_should_consume_configured_keywords() {
+        let config =
+            ParserConfig::default().with_keywords(["WAIT", "WAITING", "TODO"]);
+        config_scope(&config, || {
+            let input = Span::from("WAITING on review");
+            let (input, k) = keyword(input).unwrap();
+            assert_eq!(input, " on review", "Wrong input consumed");
+            assert_eq!(k.into_inner(), Keyword::Other(String::from("WAITING")));
+
+            let input = Span::from("TODO");
+            let (_, k) = keyword(input).unwrap();
+            assert_eq!(k.into_inner(), Keyword::Todo);
+
+            // Builtin keywords that were replaced are no longer recognized
+            let input = Span::from("DONE");
+            assert!(keyword(input).is_err());
+        });
+
+        // Once the scope ends, the builtin keywords are recognized again
+        let input = Span::from("DONE");
+        let (_, k) = keyword(input).unwrap();
+        assert_eq!(k.into_inner(), Keyword::Done);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::date::WikiDate;
 use crate::lang::{
     elements::{
         BlockElement, List, ListItem, ListItemAttributes, ListItemContents,
@@ -7,6 +8,7 @@ use crate::lang::{
     parsers::{
         utils::{
             beginning_of_line, capture, context, deeper, locate, rest_of_line,
+            take_until_end_of_line_or_input,
         },
         vimwiki::blocks::nested_block_element,
         IResult, Span,
@@ -120,13 +122,17 @@ fn list_item_tail(
         // 4. Check if we have a todo status attribute
         let (input, maybe_todo_status) = opt(todo_status)(input)?;
 
-        // 5. Parse the rest of the current line
+        // 5. Check if the current line contains a due date annotation, which
+        //    remains as part of the content
+        let (input, maybe_due) = peek(due_date)(input)?;
+
+        // 6. Parse the rest of the current line
         let (input, content) =
             map(deeper(nested_block_element), |c| c.map(BlockElement::from))(
                 input,
             )?;
 
-        // 6. Continue parsing additional lines as content for the
+        // 7. Continue parsing additional lines as content for the
         //    current list item as long as the following are met:
         //
         //    a. The indentation must be GREATER than that of the current item,
@@ -148,6 +154,7 @@ fn list_item_tail(
             (
                 ListItemAttributes {
                     todo_status: maybe_todo_status,
+                    due: maybe_due,
                 },
                 contents.into_iter().collect(),
             ),
@@ -183,6 +190,46 @@ fn todo_status(input: Span) -> IResult<ListItemTodoStatus> {
     Ok((input, attr))
 }
 
+/// Parser that scans the rest of the current line for a due date annotation
+/// in the form of `(due: YYYY-MM-DD)` or `<YYYY-MM-DD>`, yielding the first
+/// one found
+///
+/// ### Example
+///
+/// - [ ] Pay bills (due: 2024-05-01)
+/// - [ ] Renew passport <2024-06-15>
+///
+#[inline]
+fn due_date(input: Span) -> IResult<Option<WikiDate>> {
+    fn parse_date(s: &str) -> Option<(WikiDate, &str)> {
+        let date = s.get(..10)?;
+        WikiDate::parse_from_str(date, "%Y-%m-%d")
+            .ok()
+            .map(|x| (x, &s[10..]))
+    }
+
+    fn find_due_date(line: &str) -> Option<WikiDate> {
+        line.char_indices().find_map(|(i, c)| {
+            let s = &line[i + c.len_utf8()..];
+            match c {
+                '(' => {
+                    let s = s.strip_prefix("due:")?.trim_start();
+                    let (date, s) = parse_date(s)?;
+                    s.trim_start().starts_with(')').then_some(date)
+                }
+                '<' => {
+                    let (date, s) = parse_date(s)?;
+                    s.starts_with('>').then_some(date)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    let (input, line) = take_until_end_of_line_or_input(input)?;
+    Ok((input, find_due_date(line.as_unsafe_remaining_str())))
+}
+
 #[inline]
 fn list_item_prefix(input: Span) -> IResult<(ListItemType, ListItemSuffix)> {
     alt((
@@ -324,7 +371,7 @@ fn ordered_list_item_type_upper_roman(
 ) -> IResult<OrderedListItemType> {
     value(
         OrderedListItemType::UppercaseRoman,
-        many1(one_of("IVXLEDM")),
+        many1(one_of("IVXLCDM")),
     )(input)
 }
 
@@ -347,9 +394,9 @@ fn list_item_suffix_none(input: Span) -> IResult<ListItemSuffix> {
 mod tests {
     use super::*;
     use crate::lang::elements::*;
+    use crate::WikiUri;
     use indoc::indoc;
-    use std::convert::TryFrom;
-    use uriparse::URIReference;
+    use std::{borrow::Cow, convert::TryFrom};
 
     fn check_single_line_list_item(
         l: &List,
@@ -549,6 +596,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn list_should_distinguish_roman_numerals_from_letters() {
+        fn ty(text: &str) -> ListItemType<'static> {
+            let (_, l) = list(Span::from(text)).unwrap();
+            let types: Vec<_> = l.iter().map(|x| x.ty.clone()).collect();
+            assert!(types.iter().all(|x| *x == types[0]));
+            types[0].clone().into_owned()
+        }
+
+        assert_eq!(
+            ty("XC) list item 1"),
+            ListItemType::from(OrderedListItemType::UppercaseRoman)
+        );
+        assert_eq!(
+            ty("E) list item 1"),
+            ListItemType::from(OrderedListItemType::UppercaseAlphabet)
+        );
+        assert_eq!(
+            ty("i. one\nii. two\niii. three\n"),
+            ListItemType::from(OrderedListItemType::LowercaseRoman)
+        );
+
+        // Letters that are also roman numerals belong to an alphabetic list
+        // when any item is not a roman numeral
+        assert_eq!(
+            ty("i) one\nj) two\nk) three\n"),
+            ListItemType::from(OrderedListItemType::LowercaseAlphabet)
+        );
+        assert_eq!(
+            ty("C) one\nD) two\nE) three\n"),
+            ListItemType::from(OrderedListItemType::UppercaseAlphabet)
+        );
+    }
+
     #[test]
     fn list_should_support_list_item_with_paragraph_on_same_line() {
         let input = Span::from(indoc! {r#"
@@ -569,7 +650,7 @@ mod tests {
                 )),
                 Located::from(InlineElement::Text(Text::from(" has a "))),
                 Located::from(InlineElement::Link(Link::new_wiki_link(
-                    URIReference::try_from("link").unwrap(),
+                    WikiUri::try_from("link").unwrap(),
                     None
                 ))),
                 Located::from(InlineElement::Text(Text::from(" with "))),
@@ -646,7 +727,11 @@ mod tests {
         assert_eq!(l[0][0].as_paragraph().unwrap().to_string(), "list item");
         assert_eq!(
             l[0][1].as_code_block().unwrap(),
-            &CodeBlock::from_lines(vec!["some code"]),
+            &CodeBlock::from_lines(vec!["some code"]).with_indents(
+                Cow::from("  "),
+                Cow::from("  "),
+                vec![Cow::from("  ")]
+            ),
         );
     }
 
@@ -987,4 +1072,33 @@ mod tests {
         assert!(l[5].is_todo_rejected());
         assert_eq!(l[5][0].as_paragraph().unwrap().to_string(), "list item 6");
     }
+
+    #[test]
+    fn list_should_support_due_dates_on_list_items() {
+        let input = Span::from(indoc! {"
+            - [ ] pay bills (due: 2024-05-01)
+            - [X] renew passport <2024-06-15>
+            - (due:2024-07-04) and <2024-08-01>
+            - not a date (due: 2024-13-01) <2024-05-01 >
+              <2024-05-01>
+        "});
+        let (input, l) = list(input).unwrap();
+        assert!(input.is_empty(), "Did not consume list");
+        assert_eq!(l.len(), 4, "Unexpected number of list items");
+
+        assert_eq!(l[0].attributes.due, Some(WikiDate::from_ymd(2024, 5, 1)));
+        assert_eq!(
+            l[0][0].as_paragraph().unwrap().to_string(),
+            "pay bills (due: 2024-05-01)"
+        );
+        assert!(l[0].is_todo_incomplete());
+
+        assert_eq!(l[1].attributes.due, Some(WikiDate::from_ymd(2024, 6, 15)));
+        assert!(l[1].is_todo_complete());
+
+        assert_eq!(l[2].attributes.due, Some(WikiDate::from_ymd(2024, 7, 4)));
+        assert!(!l[2].is_todo());
+
+        assert_eq!(l[3].attributes.due, None);
+    }
 }
@@ -1,6 +1,10 @@
 use crate::lang::{
     elements::{BlockElement, Located},
-    parsers::{utils::context, IResult, Span},
+    parsers::{
+        utils::{context, memo},
+        vimwiki::classify::{classified, LineKinds},
+        IResult, Span,
+    },
 };
 use nom::{branch::alt, combinator::map};
 
@@ -8,6 +12,7 @@ pub mod blockquotes;
 pub mod code;
 pub mod definitions;
 pub mod dividers;
+pub mod footnotes;
 pub mod headers;
 pub mod inline;
 pub mod lists;
@@ -25,19 +30,37 @@ pub mod tables;
 /// 2. Indented Blockquotes
 /// 3. Placeholders
 /// 4. Dividers
+/// 5. Footnote Definitions
 pub fn top_level_block_element(input: Span) -> IResult<Located<BlockElement>> {
     context(
         "Top Level Block Element",
         alt((
-            map(headers::header, |c| c.map(BlockElement::from)),
-            map(definitions::definition_list, |c| c.map(BlockElement::from)),
-            map(lists::list, |c| c.map(BlockElement::from)),
-            map(tables::table, |c| c.map(BlockElement::from)),
-            map(code::code_block, |c| c.map(BlockElement::from)),
-            map(math::math_block, |c| c.map(BlockElement::from)),
-            map(blockquotes::blockquote, |c| c.map(BlockElement::from)),
-            map(dividers::divider, |c| c.map(BlockElement::from)),
-            map(placeholders::placeholder, |c| c.map(BlockElement::from)),
+            block("headers::header", LineKinds::HEADER, headers::header),
+            block(
+                "definitions::definition_list",
+                LineKinds::DEFINITION,
+                definitions::definition_list,
+            ),
+            block("lists::list", LineKinds::LIST, lists::list),
+            block("tables::table", LineKinds::TABLE, tables::table),
+            block("code::code_block", LineKinds::CODE, code::code_block),
+            block("math::math_block", LineKinds::MATH, math::math_block),
+            block(
+                "blockquotes::blockquote",
+                LineKinds::BLOCKQUOTE,
+                blockquotes::blockquote,
+            ),
+            block("dividers::divider", LineKinds::DIVIDER, dividers::divider),
+            block(
+                "placeholders::placeholder",
+                LineKinds::PLACEHOLDER,
+                placeholders::placeholder,
+            ),
+            block(
+                "footnotes::footnote_definition",
+                LineKinds::FOOTNOTE,
+                footnotes::footnote_definition,
+            ),
             // NOTE: Final type because will match literally anything in a line
             map(paragraphs::paragraph, |c| c.map(BlockElement::from)),
         )),
@@ -50,14 +73,42 @@ pub fn nested_block_element(input: Span) -> IResult<Located<BlockElement>> {
     context(
         "Block Element",
         alt((
-            map(definitions::definition_list, |c| c.map(BlockElement::from)),
-            map(lists::list, |c| c.map(BlockElement::from)),
-            map(tables::nested_table, |c| c.map(BlockElement::from)),
-            map(code::code_block, |c| c.map(BlockElement::from)),
-            map(math::math_block, |c| c.map(BlockElement::from)),
-            map(blockquotes::arrow_blockquote, |c| c.map(BlockElement::from)),
+            block(
+                "definitions::definition_list",
+                LineKinds::DEFINITION,
+                definitions::definition_list,
+            ),
+            block("lists::list", LineKinds::LIST, lists::list),
+            block(
+                "tables::nested_table",
+                LineKinds::TABLE,
+                tables::nested_table,
+            ),
+            block("code::code_block", LineKinds::CODE, code::code_block),
+            block("math::math_block", LineKinds::MATH, math::math_block),
+            block(
+                "blockquotes::arrow_blockquote",
+                LineKinds::ARROW_BLOCKQUOTE,
+                blockquotes::arrow_blockquote,
+            ),
             // NOTE: Final type because will match literally anything in a line
             map(paragraphs::paragraph, |c| c.map(BlockElement::from)),
         )),
     )(input)
 }
+
+/// Wraps a block parser such that it is skipped when the line being parsed
+/// cannot start the given kinds of block elements and its outcome is
+/// remembered for later lookaheads (such as where a paragraph ends)
+fn block<'a, T>(
+    id: &'static str,
+    kinds: LineKinds,
+    f: impl FnMut(Span<'a>) -> IResult<'a, Located<T>>,
+) -> impl FnMut(Span<'a>) -> IResult<'a, Located<BlockElement<'a>>>
+where
+    BlockElement<'a>: From<T>,
+{
+    map(memo(id, classified(kinds, f)), |c| {
+        c.map(BlockElement::from)
+    })
+}
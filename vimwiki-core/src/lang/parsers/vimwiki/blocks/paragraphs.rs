@@ -1,13 +1,18 @@
 use super::{
     blockquotes::arrow_blockquote, code::code_block,
-    definitions::definition_list, dividers::divider, headers::header,
+    definitions::definition_list, dividers::divider,
+    footnotes::footnote_definition, headers::header,
     inline::inline_element_container, lists::list, math::math_block,
     placeholders::placeholder, tables::table,
 };
 use crate::lang::{
     elements::{InlineElementContainer, Located, Paragraph},
     parsers::{
-        utils::{blank_line, capture, context, end_of_line_or_input, locate},
+        utils::{
+            blank_line, capture, context, end_of_line_or_input, locate,
+            memo_not,
+        },
+        vimwiki::classify::{classified, LineKinds},
         IResult, Span,
     },
 };
@@ -51,33 +56,65 @@ fn paragraph_line(input: Span) -> IResult<InlineElementContainer> {
     )(input)
 }
 
-// TODO: Optimize by adjusting paragraph parser to be a tuple that
-//       includes an Option<BlockElement> so that we don't waste
-//       the processing spent
-fn continue_paragraph(input: Span) -> IResult<()> {
-    let (input, _) = not(header)(input)?;
-    let (input, _) = not(definition_list)(input)?;
-    let (input, _) = not(list)(input)?;
-    let (input, _) = not(table)(input)?;
-    let (input, _) = not(code_block)(input)?;
-    let (input, _) = not(math_block)(input)?;
+/// Checks that the input does not start another type of block element; the
+/// outcome of each check is remembered such that the same block parsers
+/// tried by [`super::top_level_block_element`] are not evaluated again
+pub(super) fn continue_paragraph(input: Span) -> IResult<()> {
+    let (input, _) =
+        not_block("headers::header", LineKinds::HEADER, header)(input)?;
+    let (input, _) = not_block(
+        "definitions::definition_list",
+        LineKinds::DEFINITION,
+        definition_list,
+    )(input)?;
+    let (input, _) = not_block("lists::list", LineKinds::LIST, list)(input)?;
+    let (input, _) =
+        not_block("tables::table", LineKinds::TABLE, table)(input)?;
+    let (input, _) =
+        not_block("code::code_block", LineKinds::CODE, code_block)(input)?;
+    let (input, _) =
+        not_block("math::math_block", LineKinds::MATH, math_block)(input)?;
     let (input, _) = not(blank_line)(input)?;
-    let (input, _) = not(arrow_blockquote)(input)?;
-    let (input, _) = not(divider)(input)?;
-    let (input, _) = not(placeholder)(input)?;
+    let (input, _) = not_block(
+        "blockquotes::arrow_blockquote",
+        LineKinds::ARROW_BLOCKQUOTE,
+        arrow_blockquote,
+    )(input)?;
+    let (input, _) =
+        not_block("dividers::divider", LineKinds::DIVIDER, divider)(input)?;
+    let (input, _) = not_block(
+        "placeholders::placeholder",
+        LineKinds::PLACEHOLDER,
+        placeholder,
+    )(input)?;
+    let (input, _) = not_block(
+        "footnotes::footnote_definition",
+        LineKinds::FOOTNOTE,
+        footnote_definition,
+    )(input)?;
     Ok((input, ()))
 }
 
+/// Succeeds if the block parser fails, skipping the parser entirely when the
+/// line cannot start the given kinds of block elements
+fn not_block<'a, T>(
+    id: &'static str,
+    kinds: LineKinds,
+    f: impl FnMut(Span<'a>) -> IResult<'a, T>,
+) -> impl FnMut(Span<'a>) -> IResult<'a, ()> {
+    memo_not(id, classified(kinds, f))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::lang::elements::{
         DecoratedText, DecoratedTextContent, InlineElement, Link, MathInline,
-        Text,
+        Region, Text,
     };
+    use crate::WikiUri;
     use indoc::indoc;
     use std::convert::TryFrom;
-    use uriparse::URIReference;
 
     #[test]
     fn paragraph_should_fail_if_on_blank_line() {
@@ -106,7 +143,7 @@ mod tests {
                 ])),
                 InlineElement::Text(Text::from(", ")),
                 InlineElement::Link(Link::new_wiki_link(
-                    URIReference::try_from("links").unwrap(),
+                    WikiUri::try_from("links").unwrap(),
                     None
                 )),
                 InlineElement::Text(Text::from(", ")),
@@ -146,7 +183,7 @@ mod tests {
                 .collect::<Vec<InlineElement>>(),
             vec![
                 InlineElement::Link(Link::new_wiki_link(
-                    URIReference::try_from("links").unwrap(),
+                    WikiUri::try_from("links").unwrap(),
                     None
                 )),
                 InlineElement::Text(Text::from(", ")),
@@ -187,7 +224,7 @@ mod tests {
                 .collect::<Vec<InlineElement>>(),
             vec![
                 InlineElement::Link(Link::new_wiki_link(
-                    URIReference::try_from("links").unwrap(),
+                    WikiUri::try_from("links").unwrap(),
                     None
                 )),
                 InlineElement::Text(Text::from(", ")),
@@ -197,6 +234,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn paragraph_should_record_positions_of_line_breaks() {
+        let input = Span::from("first line\n  second line\nthird line");
+        let (input, p) = paragraph(input).unwrap();
+        assert!(input.is_empty(), "Did not consume paragraph");
+
+        // Breaks span the newline and any indentation of the following line
+        assert_eq!(
+            p.line_breaks(),
+            vec![Region::new(10, 3), Region::new(24, 1)]
+        );
+    }
+
     #[test]
     fn paragraph_should_stop_at_a_blank_line() {
         let input = Span::from(indoc! {"
@@ -233,7 +283,7 @@ mod tests {
                 .collect::<Vec<InlineElement>>(),
             vec![
                 InlineElement::Link(Link::new_wiki_link(
-                    URIReference::try_from("links").unwrap(),
+                    WikiUri::try_from("links").unwrap(),
                     None
                 )),
                 InlineElement::Text(Text::from(", ")),
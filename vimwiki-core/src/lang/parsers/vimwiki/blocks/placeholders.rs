@@ -1,3 +1,4 @@
+use crate::date::WikiDate;
 use crate::lang::{
     elements::{Located, Placeholder},
     parsers::{
@@ -9,7 +10,6 @@ use crate::lang::{
         IResult, Span,
     },
 };
-use chrono::NaiveDate;
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -83,7 +83,7 @@ fn placeholder_date(input: Span) -> IResult<Placeholder> {
         let (input, _) = space1(input)?;
         let (input, date) =
             map_res(take_until_end_of_line_or_input, |s: Span| {
-                NaiveDate::parse_from_str(
+                WikiDate::parse_from_str(
                     s.as_unsafe_remaining_str(),
                     "%Y-%m-%d",
                 )
@@ -196,7 +196,7 @@ mod tests {
         assert!(input.is_empty(), "Did not consume placeholder");
         assert_eq!(
             placeholder.into_inner(),
-            Placeholder::Date(NaiveDate::from_ymd(2012, 3, 5)),
+            Placeholder::Date(WikiDate::from_ymd(2012, 3, 5)),
         );
     }
 
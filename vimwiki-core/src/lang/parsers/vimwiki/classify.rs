@@ -0,0 +1,320 @@
+use crate::lang::parsers::{Error, IResult, Span};
+use memchr::{memchr, memchr_iter};
+use std::{
+    cell::RefCell,
+    ops::{BitOr, BitOrAssign},
+};
+
+/// Represents the kinds of block elements that could begin on a line based
+/// purely on the leading characters of the line. Classification is
+/// conservative, meaning that a kind being present does not guarantee that
+/// the associated parser will succeed, but a kind being absent guarantees
+/// that the associated parser will fail.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LineKinds(u16);
+
+impl LineKinds {
+    pub const NONE: Self = Self(0);
+    pub const BLANK: Self = Self(1);
+    pub const HEADER: Self = Self(1 << 1);
+    pub const DEFINITION: Self = Self(1 << 2);
+    pub const LIST: Self = Self(1 << 3);
+    pub const TABLE: Self = Self(1 << 4);
+    pub const CODE: Self = Self(1 << 5);
+    pub const MATH: Self = Self(1 << 6);
+    pub const INDENTED_BLOCKQUOTE: Self = Self(1 << 7);
+    pub const ARROW_BLOCKQUOTE: Self = Self(1 << 8);
+    pub const DIVIDER: Self = Self(1 << 9);
+    pub const PLACEHOLDER: Self = Self(1 << 10);
+    pub const FOOTNOTE: Self = Self(1 << 11);
+    pub const BLOCKQUOTE: Self =
+        Self(Self::INDENTED_BLOCKQUOTE.0 | Self::ARROW_BLOCKQUOTE.0);
+
+    /// Returns true if any of the kinds within other are also in self
+    #[inline]
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for LineKinds {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for LineKinds {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Returns true if the line contains a `::` anywhere within it
+fn contains_double_colon(line: &[u8]) -> bool {
+    memchr_iter(b':', line).any(|pos| line.get(pos + 1) == Some(&b':'))
+}
+
+/// Classifies a line (excluding its line termination) based on the block
+/// elements that could start with it
+pub fn classify_line(line: &[u8]) -> LineKinds {
+    let mut kinds = LineKinds::NONE;
+
+    let indent = line
+        .iter()
+        .take_while(|b| **b == b' ' || **b == b'\t')
+        .count();
+    let rest = &line[indent..];
+
+    if rest.is_empty() || rest == b"\r" {
+        return LineKinds::BLANK;
+    }
+
+    if indent >= 4 {
+        kinds |= LineKinds::INDENTED_BLOCKQUOTE;
+    }
+
+    if contains_double_colon(line) {
+        kinds |= LineKinds::DEFINITION;
+    }
+
+    match rest[0] {
+        b'=' => kinds |= LineKinds::HEADER,
+        b'|' => kinds |= LineKinds::TABLE,
+        b'>' if matches!(rest.get(1), Some(b' ' | b'>')) => {
+            kinds |= LineKinds::ARROW_BLOCKQUOTE
+        }
+        b'{' if rest.starts_with(b"{{{") => kinds |= LineKinds::CODE,
+        b'{' if rest.starts_with(b"{{$") => kinds |= LineKinds::MATH,
+        b'-' | b'*' if rest.get(1) == Some(&b' ') => kinds |= LineKinds::LIST,
+        b'#' => kinds |= LineKinds::LIST,
+        b if b.is_ascii_alphanumeric() => {
+            let len = rest
+                .iter()
+                .take_while(|b| b.is_ascii_alphanumeric())
+                .count();
+            if matches!(rest.get(len), Some(b'.') | Some(b')')) {
+                kinds |= LineKinds::LIST;
+            }
+        }
+        _ => {}
+    }
+
+    // Dividers and placeholders must start at the very beginning of a line
+    if line.starts_with(b"----") {
+        kinds |= LineKinds::DIVIDER;
+    }
+
+    if line.first() == Some(&b'%') {
+        kinds |= LineKinds::PLACEHOLDER;
+    }
+
+    if line.starts_with(b"[^") {
+        kinds |= LineKinds::FOOTNOTE;
+    }
+
+    kinds
+}
+
+/// Represents the classification of every line within some input, produced
+/// by scanning the input a single time
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LineClassifier {
+    /// Address of the input that was scanned
+    addr: usize,
+
+    /// Starting offset of each line alongside its classification
+    lines: Vec<(usize, LineKinds)>,
+}
+
+impl LineClassifier {
+    /// Scans the entire input, classifying each line within it
+    pub fn scan(input: &[u8]) -> Self {
+        let mut lines = Vec::new();
+        let mut start = 0;
+
+        for end in memchr_iter(b'\n', input) {
+            lines.push((start, classify_line(&input[start..end])));
+            start = end + 1;
+        }
+
+        if start < input.len() {
+            lines.push((start, classify_line(&input[start..])));
+        }
+
+        Self {
+            addr: input.as_ptr() as usize,
+            lines,
+        }
+    }
+
+    /// Returns the classification of the line that starts at the given
+    /// offset, or None if no line starts at the offset
+    pub fn kinds_at(&self, offset: usize) -> Option<LineKinds> {
+        self.lines
+            .binary_search_by_key(&offset, |(start, _)| *start)
+            .ok()
+            .map(|idx| self.lines[idx].1)
+    }
+}
+
+thread_local! {
+    static CLASSIFIER: RefCell<Option<LineClassifier>> = const { RefCell::new(None) };
+}
+
+/// Evaluates the provided function with the classification of each line in
+/// the input available to [`classified`] parsers
+pub fn classify_scope<T>(input: Span, f: impl FnOnce() -> T) -> T {
+    struct ScopeGuard(Option<LineClassifier>);
+
+    impl Drop for ScopeGuard {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            CLASSIFIER.with(|c| *c.borrow_mut() = previous);
+        }
+    }
+
+    let classifier = LineClassifier::scan(input.as_inner());
+    let previous = CLASSIFIER.with(|c| c.borrow_mut().replace(classifier));
+    let _guard = ScopeGuard(previous);
+    f()
+}
+
+/// Classifies the line starting at the beginning of the input, using the
+/// pre-scanned classification if available
+fn classify_input(input: &Span) -> LineKinds {
+    let offset = input.start_offset();
+    let scanned = CLASSIFIER.with(|c| {
+        c.borrow().as_ref().and_then(|c| {
+            if c.addr == input.as_inner().as_ptr() as usize {
+                c.kinds_at(offset)
+            } else {
+                None
+            }
+        })
+    });
+
+    scanned.unwrap_or_else(|| {
+        let inner = input.as_inner();
+        let end = memchr(b'\n', &inner[offset..])
+            .map(|pos| offset + pos)
+            .unwrap_or_else(|| inner.len());
+        classify_line(&inner[offset..end])
+    })
+}
+
+/// Wraps a parser such that it fails immediately if the line at the start of
+/// the input cannot begin any of the given kinds of block elements
+pub fn classified<'a, T>(
+    kinds: LineKinds,
+    mut f: impl FnMut(Span<'a>) -> IResult<'a, T>,
+) -> impl FnMut(Span<'a>) -> IResult<'a, T> {
+    move |input: Span<'a>| {
+        if classify_input(&input).intersects(kinds) {
+            f(input)
+        } else {
+            Err(nom::Err::Error(Error::from_ctx(
+                &input,
+                "Line Kind Mismatch",
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::bytes::complete::tag;
+
+    #[test]
+    fn classify_line_should_detect_blank_lines() {
+        assert_eq!(classify_line(b""), LineKinds::BLANK);
+        assert_eq!(classify_line(b"  \t "), LineKinds::BLANK);
+        assert_eq!(classify_line(b"  \r"), LineKinds::BLANK);
+    }
+
+    #[test]
+    fn classify_line_should_detect_headers() {
+        assert!(classify_line(b"= header =").intersects(LineKinds::HEADER));
+        assert!(classify_line(b"  == header ==").intersects(LineKinds::HEADER));
+        assert!(!classify_line(b"header =").intersects(LineKinds::HEADER));
+    }
+
+    #[test]
+    fn classify_line_should_detect_lists() {
+        assert!(classify_line(b"- item").intersects(LineKinds::LIST));
+        assert!(classify_line(b"  * item").intersects(LineKinds::LIST));
+        assert!(classify_line(b"# item").intersects(LineKinds::LIST));
+        assert!(classify_line(b"12. item").intersects(LineKinds::LIST));
+        assert!(classify_line(b"iv) item").intersects(LineKinds::LIST));
+        assert!(!classify_line(b"-item").intersects(LineKinds::LIST));
+        assert!(!classify_line(b"some text").intersects(LineKinds::LIST));
+    }
+
+    #[test]
+    fn classify_line_should_detect_other_block_starts() {
+        assert!(classify_line(b"|a|b|").intersects(LineKinds::TABLE));
+        assert!(classify_line(b"  {{{rust").intersects(LineKinds::CODE));
+        assert!(classify_line(b"{{$%align%").intersects(LineKinds::MATH));
+        assert!(classify_line(b"term:: def").intersects(LineKinds::DEFINITION));
+        assert!(classify_line(b":: def").intersects(LineKinds::DEFINITION));
+        assert!(
+            classify_line(b"> quote").intersects(LineKinds::ARROW_BLOCKQUOTE)
+        );
+        assert!(classify_line(b"    quote")
+            .intersects(LineKinds::INDENTED_BLOCKQUOTE));
+        assert!(classify_line(b"-----").intersects(LineKinds::DIVIDER));
+        assert!(classify_line(b"%title a").intersects(LineKinds::PLACEHOLDER));
+        assert!(classify_line(b"[^1]: note").intersects(LineKinds::FOOTNOTE));
+        assert!(!classify_line(b" ----").intersects(LineKinds::DIVIDER));
+        assert!(!classify_line(b" %title").intersects(LineKinds::PLACEHOLDER));
+    }
+
+    #[test]
+    fn classify_line_should_yield_nothing_for_plain_text() {
+        assert_eq!(classify_line(b"some plain text"), LineKinds::NONE);
+    }
+
+    #[test]
+    fn scan_should_classify_each_line_in_a_single_pass() {
+        let classifier = LineClassifier::scan(b"= header =\n\n- item\ntext");
+        assert_eq!(
+            classifier
+                .lines
+                .iter()
+                .map(|(_, kinds)| *kinds)
+                .collect::<Vec<LineKinds>>(),
+            vec![
+                LineKinds::HEADER,
+                LineKinds::BLANK,
+                LineKinds::LIST,
+                LineKinds::NONE
+            ]
+        );
+        assert_eq!(classifier.kinds_at(12), Some(LineKinds::LIST));
+        assert_eq!(classifier.kinds_at(13), None);
+    }
+
+    #[test]
+    fn classified_should_fail_without_evaluating_parser_if_kind_missing() {
+        let input = Span::from("some text");
+        let mut evaluated = false;
+        let result = classified(LineKinds::HEADER, |input| {
+            evaluated = true;
+            tag("some")(input)
+        })(input);
+        assert!(result.is_err());
+        assert!(!evaluated);
+    }
+
+    #[test]
+    fn classified_should_evaluate_parser_if_kind_present() {
+        let input = Span::from("some text\n= header =");
+        let result = classify_scope(input, || {
+            let input = input.advance_start_by(10);
+            classified(LineKinds::HEADER, tag("="))(input)
+        });
+        assert!(result.is_ok());
+    }
+}
@@ -1,7 +1,8 @@
 use crate::lang::{
     elements::*,
     parsers::{
-        utils::{blank_line, context},
+        utils::{blank_line, context, memo_scope},
+        vimwiki::classify::classify_scope,
         IResult, Span,
     },
 };
@@ -12,6 +13,7 @@ use nom::{
 };
 
 pub mod blocks;
+pub mod classify;
 
 pub fn page<'a>(input: Span<'a>) -> IResult<Page<'a>> {
     fn inner<'a>(input: Span<'a>) -> IResult<Page<'a>> {
@@ -26,12 +28,29 @@ pub fn page<'a>(input: Span<'a>) -> IResult<Page<'a>> {
             ))(input)
         }
 
-        map(all_consuming(many0(maybe_block_element)), |mut elements| {
-            Page::new(elements.drain(..).flatten().collect())
-        })(input)
+        // NOTE: A byte order mark is skipped rather than removed so the
+        //       regions of elements remain relative to the original text
+        let has_bom = input.as_remaining().starts_with(Page::BOM.as_bytes());
+        let input = if has_bom {
+            input.advance_start_by(Page::BOM.len())
+        } else {
+            input
+        };
+        let line_ending = LineEnding::detect(input.as_unsafe_remaining_str());
+
+        map(
+            all_consuming(many0(maybe_block_element)),
+            move |mut elements| {
+                Page::new(elements.drain(..).flatten().collect())
+                    .with_format(line_ending, has_bom)
+            },
+        )(input)
     }
 
-    context("Page", inner)(input)
+    // Classify every line of the page up front so block parsers that cannot
+    // match a line are skipped, and remember the outcomes of block parsers
+    // so we avoid re-evaluating them when checking where a paragraph ends
+    classify_scope(input, || memo_scope(|| context("Page", inner)(input)))
 }
 
 #[cfg(test)]
@@ -44,6 +63,21 @@ mod tests {
         assert!(page.elements().is_empty());
     }
 
+    #[test]
+    fn page_should_record_line_endings_and_skip_byte_order_mark() {
+        let (_, p) =
+            page(Span::from("\u{FEFF}= header =\r\ntext\r\n")).unwrap();
+        assert_eq!(p.line_ending, LineEnding::CrLf);
+        assert!(p.has_bom, "Byte order mark not detected");
+        assert!(p[0].as_header().is_some(), "Header not parsed");
+        assert_eq!(p[0].region(), Region::new(3, 12));
+        assert!(p[1].as_paragraph().is_some(), "Paragraph not parsed");
+
+        let (_, p) = page(Span::from("text\n")).unwrap();
+        assert_eq!(p.line_ending, LineEnding::Lf);
+        assert!(!p.has_bom, "Byte order mark unexpectedly detected");
+    }
+
     #[test]
     fn page_should_parse_blocks() {
         let (_, page) = page(Span::from("some text with % signs")).unwrap();
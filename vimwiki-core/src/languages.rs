@@ -0,0 +1,228 @@
+use crate::{BlockElement, Page, Placeholder};
+use lazy_static::lazy_static;
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+lazy_static! {
+    static ref LANGUAGE_DETECTORS: RwLock<Vec<Arc<dyn LanguageDetector>>> =
+        RwLock::new(Vec::new());
+}
+
+/// Name of the placeholder (without the leading `%`) read by the built-in
+/// detector, as in `%lang en`
+pub const LANG_PLACEHOLDER: &str = "lang";
+
+/// Represents a detector of the natural language (e.g. `en` or `pt-BR`) of a
+/// page and its block elements, such as for spellchecking or per-language
+/// typography
+///
+/// Returning None from either method defers to the next detector.
+pub trait LanguageDetector: Send + Sync {
+    /// Returns the name of the detector
+    fn name(&self) -> &str;
+
+    /// Detects the language of the page as a whole
+    fn detect_page(&self, _page: &Page<'_>) -> Option<String> {
+        None
+    }
+
+    /// Detects the language of the top-level block element at the given
+    /// index within the page
+    fn detect_block(&self, _page: &Page<'_>, _idx: usize) -> Option<String> {
+        None
+    }
+}
+
+impl fmt::Debug for dyn LanguageDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LanguageDetector")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+/// Represents the built-in detector that reads `%lang` placeholders, where
+/// the first one is the language of the page and each one is the language of
+/// the block elements that follow it
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlaceholderLanguage;
+
+impl PlaceholderLanguage {
+    fn lang<'a>(element: &'a BlockElement<'_>) -> Option<&'a str> {
+        match element {
+            BlockElement::Placeholder(Placeholder::Other { name, value })
+                if name == LANG_PLACEHOLDER && !value.trim().is_empty() =>
+            {
+                Some(value.trim())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl LanguageDetector for PlaceholderLanguage {
+    fn name(&self) -> &str {
+        LANG_PLACEHOLDER
+    }
+
+    fn detect_page(&self, page: &Page<'_>) -> Option<String> {
+        page.elements()
+            .iter()
+            .find_map(|x| Self::lang(x.as_inner()))
+            .map(ToString::to_string)
+    }
+
+    fn detect_block(&self, page: &Page<'_>, idx: usize) -> Option<String> {
+        page.elements()
+            .get(..=idx)?
+            .iter()
+            .rev()
+            .find_map(|x| Self::lang(x.as_inner()))
+            .map(ToString::to_string)
+    }
+}
+
+/// Registers a detector, replacing any detector previously registered with
+/// the same name
+///
+/// Detectors are consulted in the order that they were registered, followed
+/// by the built-in detector of `%lang` placeholders.
+pub fn register(detector: impl LanguageDetector + 'static) {
+    if let Ok(mut x) = LANGUAGE_DETECTORS.write() {
+        let detector: Arc<dyn LanguageDetector> = Arc::new(detector);
+        match x.iter_mut().find(|x| x.name() == detector.name()) {
+            Some(existing) => *existing = detector,
+            None => x.push(detector),
+        }
+    }
+}
+
+/// Removes the detector of the name, returning true if one was registered
+pub fn unregister(name: &str) -> bool {
+    LANGUAGE_DETECTORS
+        .write()
+        .map(|mut x| {
+            let len = x.len();
+            x.retain(|x| x.name() != name);
+            x.len() != len
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the names of registered detectors in the order they are consulted
+pub fn registered() -> Vec<String> {
+    LANGUAGE_DETECTORS
+        .read()
+        .map(|x| x.iter().map(|x| x.name().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Returns the registered detectors followed by the built-in one
+fn detectors() -> Vec<Arc<dyn LanguageDetector>> {
+    let mut detectors = LANGUAGE_DETECTORS
+        .read()
+        .map(|x| x.clone())
+        .unwrap_or_default();
+    detectors.push(Arc::new(PlaceholderLanguage));
+    detectors
+}
+
+/// Detects the language of the page using the first detector that knows it
+pub fn page_language(page: &Page<'_>) -> Option<String> {
+    detectors().iter().find_map(|x| x.detect_page(page))
+}
+
+/// Detects the language of each top-level block element of the page, where
+/// a block without a language of its own has the language of the page
+pub fn block_languages(page: &Page<'_>) -> Vec<Option<String>> {
+    let detectors = detectors();
+    let page_language = detectors.iter().find_map(|x| x.detect_page(page));
+
+    (0..page.elements().len())
+        .map(|idx| {
+            detectors
+                .iter()
+                .find_map(|x| x.detect_block(page, idx))
+                .or_else(|| page_language.clone())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+
+    fn parse(text: &str) -> Page<'_> {
+        Language::from_vimwiki_str(text).parse().unwrap()
+    }
+
+    #[test]
+    fn placeholders_should_set_page_and_block_languages() {
+        let page = parse(indoc! {"
+            first paragraph
+
+            %lang en
+
+            english paragraph
+
+            %lang fr
+
+            paragraphe français
+        "});
+
+        assert_eq!(page_language(&page).as_deref(), Some("en"));
+        assert_eq!(
+            block_languages(&page),
+            vec![
+                Some("en".to_string()),
+                Some("en".to_string()),
+                Some("en".to_string()),
+                Some("fr".to_string()),
+                Some("fr".to_string()),
+            ]
+        );
+
+        assert_eq!(page_language(&parse("no language\n")), None);
+    }
+
+    struct CodeIsEnglish;
+
+    impl LanguageDetector for CodeIsEnglish {
+        fn name(&self) -> &str {
+            "code-is-english"
+        }
+
+        fn detect_block(&self, page: &Page<'_>, idx: usize) -> Option<String> {
+            match page.elements().get(idx)?.as_inner() {
+                BlockElement::CodeBlock(_) => Some("en".to_string()),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn registered_detectors_should_be_consulted_first() {
+        let page = parse(indoc! {"
+            %lang de
+
+            {{{
+            code
+            }}}
+        "});
+
+        register(CodeIsEnglish);
+        assert!(registered().contains(&"code-is-english".to_string()));
+        let languages = block_languages(&page);
+        assert!(unregister("code-is-english"));
+
+        assert_eq!(
+            languages,
+            vec![Some("de".to_string()), Some("en".to_string())]
+        );
+        assert_eq!(block_languages(&page)[1].as_deref(), Some("de"));
+    }
+}
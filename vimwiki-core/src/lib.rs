@@ -1,4 +1,40 @@
+pub mod check;
+pub mod completion;
+pub mod date;
+pub mod diary;
+pub mod diff;
+pub mod directives;
+pub mod dynamic;
+pub mod edit;
+pub mod export;
+pub mod graph;
+pub mod highlight;
+pub mod hover;
+pub mod ics;
+pub mod ids;
+pub mod images;
+pub mod import;
+pub mod intern;
+pub mod keywords;
 mod lang;
+pub mod languages;
+pub mod linking;
+pub mod lists;
+pub mod merge;
+pub mod outline;
+pub mod positions;
+pub mod reflow;
+pub mod resolve;
+pub mod schemes;
+pub mod site;
+pub mod sources;
+pub mod stats;
+pub mod tables;
+pub mod tags;
+pub mod tasks;
+pub mod toc;
+pub mod tree;
+pub mod uri;
 mod utils;
 
 // Export all elements at top level
@@ -10,18 +46,41 @@ pub use lang::output::*;
 // Export our parser error, which is used for language parsing
 pub use lang::parsers::Error as ParseError;
 
+// Export our parser config, which changes what is recognized when parsing
+pub use lang::parsers::ParserConfig;
+
 // Export our primary language structure and trait
 pub use lang::{FromLanguage, Language};
 
+// Export our own date and uri types, which wrap those of vendor libraries
+pub use date::{Timestamp, WikiDate};
+pub use uri::WikiUri;
+
 // Export our trait to do stronger comparsisons that include the region of elements
 pub use utils::StrictEq;
 
 // Re-export the vendor libraries so we're able to reconstruct their
 // structs from macros
 pub mod vendor {
+    #[cfg(feature = "chrono")]
     pub use chrono;
     pub use uriparse;
 }
 
+#[cfg(feature = "settings")]
+pub mod settings;
+
 #[cfg(feature = "timekeeper")]
 pub mod timekeeper;
+
+#[cfg(feature = "benchmarks")]
+pub mod benchmarks;
+
+#[cfg(feature = "snapshots")]
+pub mod snapshots;
+
+#[cfg(feature = "regressions")]
+pub mod regressions;
+
+#[cfg(feature = "compact")]
+pub mod compact;
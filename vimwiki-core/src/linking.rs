@@ -0,0 +1,183 @@
+use crate::{
+    date::WikiDate, edit::TextEdit, graph::collect_links, Page, Region,
+};
+use serde::{Deserialize, Serialize};
+
+/// Punctuation trimmed from the ends of a selection, so that selecting a word
+/// at the end of a sentence does not pull its period into the link
+const TRIMMED_PUNCTUATION: &[char] = &[
+    '.', ',', ';', ':', '!', '?', '"', '\'', '(', ')', '[', ']', '{', '}',
+];
+
+/// Represents a wiki link created in place of some text
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewLink {
+    /// Edit that replaces the text with the link
+    pub edit: TextEdit,
+
+    /// Page targeted by the link
+    pub target: String,
+
+    /// Text of the link shown in place of its target, if it differs from
+    /// the target
+    pub description: Option<String>,
+}
+
+/// Normalizes a selection to the text to turn into a link, or None if there
+/// is no such text
+///
+/// An empty selection expands to the word (text between whitespace) around
+/// its offset, like vimwiki's `<CR>` on a word. The selection is then
+/// trimmed of whitespace and surrounding punctuation, and must not span
+/// multiple lines.
+pub fn normalize_selection(text: &str, region: Region) -> Option<Region> {
+    let (mut start, mut end) =
+        (region.offset(), region.offset() + region.len());
+    if end > text.len() || !text.is_char_boundary(start) {
+        return None;
+    }
+
+    if start == end {
+        start = text[..start]
+            .rfind(char::is_whitespace)
+            .map(|idx| idx + text[idx..].chars().next().unwrap().len_utf8())
+            .unwrap_or(0);
+        end += text[end..]
+            .find(char::is_whitespace)
+            .unwrap_or(text.len() - end);
+    }
+
+    let is_trimmed =
+        |c: char| c.is_whitespace() || TRIMMED_PUNCTUATION.contains(&c);
+    let selected = text.get(start..end)?;
+    let trimmed = selected
+        .trim_start_matches(is_trimmed)
+        .trim_end_matches(is_trimmed);
+    if trimmed.is_empty() || trimmed.contains('\n') {
+        return None;
+    }
+
+    start += selected.len() - selected.trim_start_matches(is_trimmed).len();
+    Some(Region::new(start, trimmed.len()))
+}
+
+/// Turns the selected text of a page into a wiki link, returning None if the
+/// selection normalizes to nothing or overlaps an existing link
+///
+/// Without a target, the link is `[[selection]]`. With a target that differs
+/// from the selection, the selection becomes the link's description as in
+/// `[[target|selection]]`.
+pub fn make_link(
+    text: &str,
+    page: &Page,
+    region: Region,
+    target: Option<&str>,
+) -> Option<NewLink> {
+    let region = normalize_selection(text, region)?;
+    let (start, end) = (region.offset(), region.offset() + region.len());
+    let overlaps_link = collect_links(page).iter().any(|link| {
+        let r = link.region();
+        r.offset() < end && start < r.offset() + r.len()
+    });
+    if overlaps_link {
+        return None;
+    }
+
+    let selection = &text[start..end];
+    let target = target
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .unwrap_or(selection);
+    let description = Some(selection)
+        .filter(|x| *x != target)
+        .map(ToString::to_string);
+
+    let link = match description.as_deref() {
+        Some(description) => format!("[[{}|{}]]", target, description),
+        None => format!("[[{}]]", target),
+    };
+
+    Some(NewLink {
+        edit: TextEdit::new(region, link),
+        target: target.to_string(),
+        description,
+    })
+}
+
+/// Fills in a template for a new page, replacing every `{title}` with the
+/// title of the page and every `{date}` with the given date (as YYYY-MM-DD)
+pub fn render_page_template(
+    template: &str,
+    title: &str,
+    date: WikiDate,
+) -> String {
+    template
+        .replace("{title}", title)
+        .replace("{date}", &date.format("%Y-%m-%d").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{edit::apply_edits, Language};
+
+    fn link(
+        text: &str,
+        region: Region,
+        target: Option<&str>,
+    ) -> Option<String> {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let link = make_link(text, &page, region, target)?;
+        Some(apply_edits(text, &[link.edit]).unwrap())
+    }
+
+    #[test]
+    fn make_link_should_normalize_selection_to_word() {
+        // Cursor within a word expands to the word without punctuation
+        let text = "See the (Other Page). Then more\n";
+        let offset = text.find("ther").unwrap();
+        assert_eq!(
+            link(text, Region::new(offset, 0), None).as_deref(),
+            Some("See the ([[Other]] Page). Then more\n")
+        );
+
+        // Selection is trimmed of whitespace and punctuation
+        let start = text.find('(').unwrap();
+        assert_eq!(
+            link(text, Region::new(start, 13), None).as_deref(),
+            Some("See the ([[Other Page]]). Then more\n")
+        );
+
+        // Selections spanning lines or only whitespace are rejected
+        assert_eq!(link("a\nb\n", Region::new(0, 3), None), None);
+        assert_eq!(link("a   b\n", Region::new(2, 0), None), None);
+    }
+
+    #[test]
+    fn make_link_should_use_target_and_skip_existing_links() {
+        let text = "some words [[link]]\n";
+        assert_eq!(
+            link(text, Region::new(0, 10), Some("target")).as_deref(),
+            Some("[[target|some words]] [[link]]\n")
+        );
+        assert_eq!(
+            link(text, Region::new(0, 4), Some("some")).as_deref(),
+            Some("[[some]] words [[link]]\n")
+        );
+
+        let offset = text.find("link").unwrap();
+        assert_eq!(link(text, Region::new(offset, 0), None), None);
+    }
+
+    #[test]
+    fn render_page_template_should_fill_in_title_and_date() {
+        assert_eq!(
+            render_page_template(
+                "= {title} =\n%date {date}\n",
+                "Page",
+                WikiDate::from_ymd(2021, 4, 3)
+            ),
+            "= Page =\n%date 2021-04-03\n"
+        );
+    }
+}
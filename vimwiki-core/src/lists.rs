@@ -0,0 +1,513 @@
+use crate::{
+    edit::TextEdit,
+    tasks::{collect_list_items, describe},
+    BlockElement, Element, Language, List, ListItem, Located, Page, Region,
+};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+/// Represents a change to the nesting level of a list item
+#[derive(
+    Copy, Clone, Debug, Display, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelChange {
+    /// Nests the item one level deeper, making it a child of the item
+    /// before it
+    #[display(fmt = "increase")]
+    Increase,
+
+    /// Nests the item one level shallower, making it a sibling of its parent
+    #[display(fmt = "decrease")]
+    Decrease,
+}
+
+/// Produces the edits that change the nesting level of the innermost list
+/// item containing the given byte offset, the same way that vimwiki's
+/// Tab/Shift-Tab do, or no edits if there is no item at the offset or it
+/// cannot change level
+///
+/// The item and its sublists are shifted by `shiftwidth` spaces. The moved
+/// item adopts the marker of its new siblings, and the numbers of ordered
+/// items within the surrounding list are recalculated. Only the portions of
+/// lines that change are edited.
+pub fn change_level(
+    text: &str,
+    page: &Page,
+    offset: usize,
+    change: LevelChange,
+    shiftwidth: usize,
+) -> Vec<TextEdit> {
+    let item = match find_list_item_at_offset(page, offset) {
+        Some(item) => item,
+        None => return Vec::new(),
+    };
+
+    let old_starts = line_starts(text);
+    let region = item.region();
+    let first = line_of(&old_starts, region.offset());
+    let last = line_of(&old_starts, region.offset() + region.len().max(1) - 1);
+
+    let old_lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let mut new_lines: Vec<String> =
+        old_lines.iter().map(ToString::to_string).collect();
+
+    for (idx, line) in
+        new_lines.iter_mut().enumerate().take(last + 1).skip(first)
+    {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match change {
+            LevelChange::Increase => {
+                line.insert_str(0, &" ".repeat(shiftwidth))
+            }
+            LevelChange::Decrease => {
+                let removed = outdent_len(line, shiftwidth);
+                if removed == 0 && idx == first {
+                    return Vec::new();
+                }
+                line.replace_range(..removed, "");
+            }
+        }
+    }
+
+    // Reparse the shifted text to learn the new structure of the list, which
+    // has the same lines as the original text
+    let shifted = new_lines.concat();
+    let shifted_page: Page = match Language::from_vimwiki_str(&shifted).parse()
+    {
+        Ok(page) => page,
+        Err(_) => return Vec::new(),
+    };
+
+    let starts = line_starts(&shifted);
+    let moved_line = first;
+    for element in shifted_page.elements().iter() {
+        if let BlockElement::List(list) = element.as_inner() {
+            let region = element.region();
+            let list_lines = line_of(&starts, region.offset())
+                ..=line_of(&starts, region.offset() + region.len().max(1) - 1);
+            if list_lines.contains(&moved_line) {
+                relabel_list(list, &starts, moved_line, &mut new_lines);
+            }
+        }
+    }
+
+    old_lines
+        .iter()
+        .zip(new_lines.iter())
+        .enumerate()
+        .filter_map(|(idx, (old, new))| line_edit(old_starts[idx], old, new))
+        .collect()
+}
+
+/// Represents the result of pressing Enter within a list item
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListContinuation {
+    /// Edits to apply to the text, such as inserting a new item
+    pub edits: Vec<TextEdit>,
+
+    /// Byte offset of the cursor within the text once the edits are applied
+    pub cursor: usize,
+}
+
+/// Computes what pressing Enter at the given byte offset within a list item
+/// does, following vimwiki's rules, or None if the offset is not past the
+/// marker of a list item and a plain newline should be inserted instead
+///
+/// Within an item that has content, a new item is started at the cursor with
+/// the same indentation, the next marker (e.g. `3.` following `2.`), and an
+/// incomplete TODO status if the item has one, renumbering the ordered items
+/// that follow. Within an empty item, the marker is removed to end the list.
+/// An offset past the end of the text or within a character is never within
+/// an item.
+pub fn continue_list(
+    text: &str,
+    page: &Page,
+    offset: usize,
+) -> Option<ListContinuation> {
+    // An empty item, which is only a marker, ends the list by removing its
+    // marker and is not parsed as an item, so it is found within the line
+    let line_start =
+        text.get(..offset)?.rfind('\n').map(|x| x + 1).unwrap_or(0);
+    let line_end = text
+        .get(offset..)?
+        .find('\n')
+        .map(|x| offset + x)
+        .unwrap_or(text.len());
+    let line = &text[line_start..line_end];
+    if is_empty_item(line) {
+        let start = line_start + line.len() - line.trim_start().len();
+        return Some(ListContinuation {
+            edits: vec![TextEdit::new(
+                Region::from(start..line_end),
+                String::new(),
+            )],
+            cursor: start,
+        });
+    }
+
+    // NOTE: An item at the end of text without a trailing newline does not
+    //       contain the offset at the very end
+    let item =
+        find_list_item_at_offset(page, offset).or_else(|| {
+            match offset.checked_sub(1) {
+                Some(x) if offset == text.len() => {
+                    find_list_item_at_offset(page, x)
+                }
+                _ => None,
+            }
+        })?;
+    let region = item.region();
+    let start = region.offset();
+    let item_line_start = text[..start].rfind('\n').map(|x| x + 1).unwrap_or(0);
+    let indent = &text[item_line_start..start];
+
+    // Content of the item begins after its marker and TODO status
+    let marker_end = start + text[start..].find(char::is_whitespace)?;
+    let content_start = if item.is_todo() {
+        let rest = &text[marker_end..];
+        let trimmed = rest.trim_start();
+        marker_end + rest.len() - trimmed.len() + trimmed.find(']')? + 1
+    } else {
+        marker_end
+    };
+
+    if offset < content_start {
+        return None;
+    }
+
+    let mut inserted = format!(
+        "\n{}{} ",
+        indent,
+        item.ty.to_prefix(item.pos + 1, item.suffix)
+    );
+    if item.is_todo() {
+        inserted.push_str("[ ] ");
+    }
+
+    let mut edits =
+        vec![TextEdit::new(Region::new(offset, 0), inserted.clone())];
+
+    // Renumber the siblings that follow as they each move down a position
+    if let Some(list) = find_parent_list(page, region) {
+        for sibling in list.items.iter().skip(item.pos + 1) {
+            let old = sibling.to_prefix();
+            let new = sibling.ty.to_prefix(sibling.pos + 1, sibling.suffix);
+            if old != new {
+                edits.push(TextEdit::new(
+                    Region::new(sibling.region().offset(), old.len()),
+                    new,
+                ));
+            }
+        }
+    }
+
+    Some(ListContinuation {
+        edits,
+        cursor: offset + inserted.len(),
+    })
+}
+
+/// Returns true if the line is only the marker of a list item, optionally
+/// followed by a TODO status, such as `- ` or `1. [ ]`
+fn is_empty_item(line: &str) -> bool {
+    let line = line.trim();
+    if line.is_empty() {
+        return false;
+    }
+
+    // Probe the parser with placeholder content to recognize the marker
+    let probe = format!("{} x\n", line);
+    match Language::from_vimwiki_str(&probe).parse::<Page>() {
+        Ok(page) => match collect_list_items(&page).as_slice() {
+            [item] => describe(item.as_inner()) == "x",
+            _ => false,
+        },
+        Err(_) => false,
+    }
+}
+
+/// Finds the list within the page that directly contains the item at the
+/// given region
+fn find_parent_list<'a>(
+    page: &'a Page<'a>,
+    region: Region,
+) -> Option<List<'a>> {
+    page.descendants()
+        .find_map(|element| match element.into_inner() {
+            Element::Block(BlockElement::List(list))
+                if list.items.iter().any(|x| x.region() == region) =>
+            {
+                Some(list)
+            }
+            _ => None,
+        })
+}
+
+/// Finds the innermost list item within the page containing the given byte
+/// offset
+pub fn find_list_item_at_offset<'a>(
+    page: &'a Page<'a>,
+    offset: usize,
+) -> Option<Located<ListItem<'a>>> {
+    collect_list_items(page)
+        .into_iter()
+        .filter(|item| item.region().contains(offset))
+        .min_by_key(|item| item.region().len())
+}
+
+/// Rewrites the marker of each item within the list and its sublists so the
+/// item on the moved line matches the marker of its siblings and every
+/// ordered item is numbered by its position
+fn relabel_list(
+    list: &List,
+    starts: &[usize],
+    moved_line: usize,
+    lines: &mut [String],
+) {
+    for (idx, item) in list.items.iter().enumerate() {
+        let line = line_of(starts, item.region().offset());
+        let style = if line == moved_line {
+            idx.checked_sub(1)
+                .and_then(|idx| list.items.get(idx))
+                .or_else(|| list.items.get(idx + 1))
+                .unwrap_or(item)
+        } else {
+            item
+        };
+
+        let prefix = style.ty.to_prefix(item.pos, style.suffix);
+        replace_marker(&mut lines[line], &prefix);
+
+        for content in item.contents.iter() {
+            if let BlockElement::List(x) = content.as_inner() {
+                relabel_list(x, starts, moved_line, lines);
+            }
+        }
+    }
+}
+
+/// Replaces the marker (e.g. `-` or `1.`) following the indentation of a
+/// line with the given prefix
+fn replace_marker(line: &mut String, prefix: &str) {
+    let start = line.len() - line.trim_start().len();
+    let end = line[start..]
+        .find(char::is_whitespace)
+        .map(|idx| start + idx)
+        .unwrap_or(line.len());
+    line.replace_range(start..end, prefix);
+}
+
+/// Returns the total bytes of indentation to remove from the start of a line
+/// to outdent it by the given width, where a tab counts as the full width
+fn outdent_len(line: &str, shiftwidth: usize) -> usize {
+    if line.starts_with('\t') {
+        return 1;
+    }
+
+    line.chars()
+        .take(shiftwidth)
+        .take_while(|c| *c == ' ')
+        .count()
+}
+
+/// Returns the byte offset where each line of the text begins
+pub(crate) fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect()
+}
+
+/// Returns the index of the line containing the given byte offset
+pub(crate) fn line_of(starts: &[usize], offset: usize) -> usize {
+    match starts.binary_search(&offset) {
+        Ok(idx) => idx,
+        Err(idx) => idx.saturating_sub(1),
+    }
+}
+
+/// Produces the smallest edit that turns the old line starting at the given
+/// offset into the new line, or None if they are the same
+pub(crate) fn line_edit(
+    offset: usize,
+    old: &str,
+    new: &str,
+) -> Option<TextEdit> {
+    if old == new {
+        return None;
+    }
+
+    let prefix: usize = old
+        .chars()
+        .zip(new.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+    let suffix: usize = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+
+    Some(TextEdit::new(
+        Region::new(offset + prefix, old.len() - prefix - suffix),
+        new[prefix..new.len() - suffix].to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::apply_edits;
+    use indoc::indoc;
+
+    fn change(text: &str, offset: usize, change: LevelChange) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let edits = change_level(text, &page, offset, change, 4);
+        apply_edits(text, &edits).unwrap()
+    }
+
+    #[test]
+    fn change_level_should_shift_item_and_children() {
+        let text = indoc! {"
+            - one
+            - two
+                * child
+            - three
+        "};
+        let offset = text.find("two").unwrap();
+
+        assert_eq!(
+            change(text, offset, LevelChange::Increase),
+            indoc! {"
+                - one
+                    - two
+                        * child
+                - three
+            "}
+        );
+        assert_eq!(
+            change(text, offset, LevelChange::Decrease),
+            text,
+            "top-level item cannot be outdented"
+        );
+    }
+
+    #[test]
+    fn change_level_should_adopt_sibling_markers_and_renumber() {
+        let text = indoc! {"
+            1. one
+                a) sub
+            2. two
+            3. three
+        "};
+
+        // Indenting two joins the sublist of one, renumbering three
+        assert_eq!(
+            change(text, text.find("two").unwrap(), LevelChange::Increase),
+            indoc! {"
+                1. one
+                    a) sub
+                    b) two
+                2. three
+            "}
+        );
+
+        // Outdenting sub makes it a sibling of one
+        assert_eq!(
+            change(text, text.find("sub").unwrap(), LevelChange::Decrease),
+            indoc! {"
+                1. one
+                2. sub
+                3. two
+                4. three
+            "}
+        );
+    }
+
+    #[test]
+    fn change_level_should_produce_minimal_edits() {
+        let text = "1. one\n2. two\n3. three\n";
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let edits = change_level(
+            text,
+            &page,
+            text.find("two").unwrap(),
+            LevelChange::Increase,
+            2,
+        );
+
+        assert_eq!(
+            edits,
+            [
+                TextEdit::new(Region::new(7, 1), String::from("  1")),
+                TextEdit::new(Region::new(14, 1), String::from("2")),
+            ]
+        );
+    }
+
+    fn enter(text: &str, offset: usize) -> Option<(String, usize)> {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        continue_list(text, &page, offset)
+            .map(|x| (apply_edits(text, &x.edits).unwrap(), x.cursor))
+    }
+
+    #[test]
+    fn continue_list_should_start_next_item_and_renumber() {
+        let text = "  1. one\n  2. two\n  3. three\n";
+        let offset = text.find("one").unwrap() + 3;
+        let (result, cursor) = enter(text, offset).unwrap();
+        assert_eq!(result, "  1. one\n  2. \n  3. two\n  4. three\n");
+        assert_eq!(&result[cursor..], "\n  3. two\n  4. three\n");
+
+        // Text following the cursor moves into the new item
+        let text = "- [X] first second\n";
+        let (result, cursor) =
+            enter(text, text.find(" second").unwrap()).unwrap();
+        assert_eq!(result, "- [X] first\n- [ ]  second\n");
+        assert_eq!(&result[cursor..], " second\n");
+
+        // Letters and roman numerals keep their style when renumbered
+        let text = "a) one\nb) two\n";
+        let (result, _) = enter(text, text.find(" one").unwrap() + 4).unwrap();
+        assert_eq!(result, "a) one\nb) \nc) two\n");
+
+        let text = "I. one\nII. two\n";
+        let (result, _) = enter(text, text.find(" one").unwrap() + 4).unwrap();
+        assert_eq!(result, "I. one\nII. \nIII. two\n");
+    }
+
+    #[test]
+    fn continue_list_should_end_list_on_empty_item() {
+        let text = "- one\n    - [ ] \n";
+        let (result, cursor) = enter(text, text.len() - 1).unwrap();
+        assert_eq!(result, "- one\n    \n");
+        assert_eq!(cursor, 10);
+
+        // Item at the end of text is continued
+        let (result, _) = enter("- one", 5).unwrap();
+        assert_eq!(result, "- one\n- ");
+
+        // Cursor before the marker inserts a plain newline
+        assert_eq!(enter("- one\n", 0), None);
+        assert_eq!(enter("text\n", 2), None);
+    }
+
+    #[test]
+    fn continue_list_should_support_multibyte_characters_before_cursor() {
+        let text = "- café au lait\n";
+        let offset = text.find(" au").unwrap();
+        let (result, cursor) = enter(text, offset).unwrap();
+        assert_eq!(result, "- café\n-  au lait\n");
+        assert_eq!(&result[cursor..], " au lait\n");
+
+        // Offsets within a character or past the end are not within an item
+        assert_eq!(enter(text, text.find('é').unwrap() + 1), None);
+        assert_eq!(enter(text, text.len() + 1), None);
+    }
+}
@@ -0,0 +1,389 @@
+use crate::{
+    diff::{edit_script, Step},
+    BlockElement, Language, Located, Page, ParseError,
+};
+use std::ops::Range;
+
+/// Line that starts our side of a conflict
+const OURS_MARKER: &str = "<<<<<<< ours";
+
+/// Line that separates our side of a conflict from their side
+const SEPARATOR_MARKER: &str = "=======";
+
+/// Line that ends their side of a conflict
+const THEIRS_MARKER: &str = ">>>>>>> theirs";
+
+/// Represents the result of merging two versions of a page that descend from
+/// a common base
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Merge {
+    /// Text of the merged page, including conflict markers around any blocks
+    /// that were changed differently by both versions
+    pub text: String,
+
+    /// Total conflicts within the merged text
+    pub conflicts: usize,
+}
+
+impl Merge {
+    /// Returns true if the merged text contains conflicts
+    pub fn has_conflicts(&self) -> bool {
+        self.conflicts > 0
+    }
+}
+
+/// Represents a version of a page alongside the source text of each of its
+/// top-level blocks, where a block's source extends to the start of the next
+/// block so that joining all of them reproduces the original text
+struct Version<'a> {
+    id: usize,
+    page: Page<'a>,
+    chunks: Vec<&'a str>,
+}
+
+impl<'a> Version<'a> {
+    fn parse(id: usize, text: &'a str) -> Result<Self, ParseError<'a>> {
+        let page: Page = Language::from_vimwiki_str(text).parse()?;
+        let starts: Vec<usize> = page
+            .elements()
+            .iter()
+            .map(|x| x.region().offset())
+            .collect();
+
+        let chunks = (0..starts.len())
+            .map(|i| {
+                let start = if i == 0 { 0 } else { starts[i] };
+                let end = starts.get(i + 1).copied().unwrap_or(text.len());
+                &text[start..end]
+            })
+            .collect();
+
+        Ok(Self { id, page, chunks })
+    }
+
+    fn blocks(&self) -> &[Located<BlockElement<'a>>] {
+        self.page.elements()
+    }
+
+    /// Returns the runs of blocks of the base version that this version
+    /// replaced, ordered by their position
+    fn hunks(&self, base: &Version) -> Vec<Hunk> {
+        let mut hunks = Vec::new();
+        let mut start = None;
+        let (mut i, mut j) = (0, 0);
+
+        for step in edit_script(base.blocks(), self.blocks()) {
+            match step {
+                Step::Keep(..) => {
+                    if let Some((i0, j0)) = start.take() {
+                        hunks.push(Hunk::new(i0..i, j - j0));
+                    }
+                    i += 1;
+                    j += 1;
+                }
+                Step::Remove(_) => {
+                    start.get_or_insert((i, j));
+                    i += 1;
+                }
+                Step::Add(_) => {
+                    start.get_or_insert((i, j));
+                    j += 1;
+                }
+            }
+        }
+
+        if let Some((i0, j0)) = start {
+            hunks.push(Hunk::new(i0..i, j - j0));
+        }
+
+        hunks
+    }
+}
+
+/// Represents a run of blocks of the base version replaced by a version
+#[derive(Clone, Debug)]
+struct Hunk {
+    /// Blocks of the base version that were replaced
+    base: Range<usize>,
+
+    /// Total blocks put in place of the base blocks
+    len: usize,
+}
+
+impl Hunk {
+    fn new(base: Range<usize>, len: usize) -> Self {
+        Self { base, len }
+    }
+
+    /// Change in total blocks caused by the hunk
+    fn delta(&self) -> isize {
+        self.len as isize - self.base.len() as isize
+    }
+}
+
+/// Represents overlapping hunks of both versions that must be resolved
+/// together
+#[derive(Clone, Debug)]
+struct Group {
+    base: Range<usize>,
+    ours: Option<isize>,
+    theirs: Option<isize>,
+}
+
+impl Group {
+    /// Returns true if the hunk touches the same base blocks as the group,
+    /// or is inserted at the same position
+    fn overlaps(&self, hunk: &Hunk) -> bool {
+        hunk.base.start < self.base.end || hunk.base.start == self.base.start
+    }
+
+    fn add(&mut self, hunk: &Hunk, is_ours: bool) {
+        self.base.end = self.base.end.max(hunk.base.end);
+        let delta = if is_ours {
+            &mut self.ours
+        } else {
+            &mut self.theirs
+        };
+        *delta = Some(delta.unwrap_or_default() + hunk.delta());
+    }
+
+    /// Total blocks of the version within the group, given its change in
+    /// total blocks
+    fn len(&self, delta: Option<isize>) -> usize {
+        (self.base.len() as isize + delta.unwrap_or_default()) as usize
+    }
+}
+
+/// Merges the changes made by two versions of a page to their common base,
+/// comparing the pages block by block rather than line by line
+///
+/// Blocks changed by only one version, or changed identically by both, are
+/// taken as-is, keeping their original source text. Only blocks that both
+/// versions changed differently are surrounded by conflict markers.
+pub fn merge_pages<'a>(
+    base: &'a str,
+    ours: &'a str,
+    theirs: &'a str,
+) -> Result<Merge, ParseError<'a>> {
+    let base = Version::parse(0, base)?;
+    let ours = Version::parse(1, ours)?;
+    let theirs = Version::parse(2, theirs)?;
+
+    let mut hunks: Vec<(Hunk, bool)> = ours
+        .hunks(&base)
+        .into_iter()
+        .map(|h| (h, true))
+        .chain(theirs.hunks(&base).into_iter().map(|h| (h, false)))
+        .collect();
+    hunks.sort_by_key(|(h, _)| (h.base.start, h.base.end));
+
+    let mut groups: Vec<Group> = Vec::new();
+    for (hunk, is_ours) in hunks {
+        match groups.last_mut() {
+            Some(group) if group.overlaps(&hunk) => group.add(&hunk, is_ours),
+            _ => {
+                let mut group = Group {
+                    base: hunk.base.clone(),
+                    ours: None,
+                    theirs: None,
+                };
+                group.add(&hunk, is_ours);
+                groups.push(group);
+            }
+        }
+    }
+
+    let mut writer = Writer::default();
+    let (mut b, mut o, mut t) = (0, 0, 0);
+    for group in groups {
+        // Blocks prior to the group were left unchanged by both versions
+        let unchanged = group.base.start - b;
+        writer.push_run(&ours, o..o + unchanged);
+        o += unchanged;
+        t += unchanged;
+
+        let ours_range = o..o + group.len(group.ours);
+        let theirs_range = t..t + group.len(group.theirs);
+        match (group.ours, group.theirs) {
+            (Some(_), None) => writer.push_run(&ours, ours_range.clone()),
+            (None, _) => writer.push_run(&theirs, theirs_range.clone()),
+            _ if ours.blocks()[ours_range.clone()]
+                == theirs.blocks()[theirs_range.clone()] =>
+            {
+                writer.push_run(&ours, ours_range.clone())
+            }
+            _ => writer.push_conflict(
+                (&ours, ours_range.clone()),
+                (&theirs, theirs_range.clone()),
+            ),
+        }
+
+        b = group.base.end;
+        o = ours_range.end;
+        t = theirs_range.end;
+    }
+    writer.push_run(&ours, o..ours.blocks().len());
+
+    Ok(Merge {
+        text: writer.text,
+        conflicts: writer.conflicts,
+    })
+}
+
+/// Builds the merged text from the source of blocks, keeping blocks that
+/// were not adjacent within the same version separated by a blank line
+#[derive(Default)]
+struct Writer {
+    text: String,
+    conflicts: usize,
+    last: Option<(usize, usize)>,
+}
+
+impl Writer {
+    fn push_block(&mut self, version: &Version, idx: usize) {
+        self.end_line();
+
+        let is_separated = match self.last {
+            Some(last) => idx == 0 || last != (version.id, idx - 1),
+            None => false,
+        };
+        if is_separated && !self.text.ends_with("\n\n") {
+            self.text.push('\n');
+        }
+
+        self.text.push_str(version.chunks[idx]);
+        self.last = Some((version.id, idx));
+    }
+
+    fn push_run(&mut self, version: &Version, range: Range<usize>) {
+        for idx in range {
+            self.push_block(version, idx);
+        }
+    }
+
+    fn push_conflict(
+        &mut self,
+        (ours, ours_range): (&Version, Range<usize>),
+        (theirs, theirs_range): (&Version, Range<usize>),
+    ) {
+        self.end_line();
+        self.text.push_str(OURS_MARKER);
+        self.text.push('\n');
+        self.last = None;
+        self.push_run(ours, ours_range);
+        self.push_marker(SEPARATOR_MARKER);
+        self.push_run(theirs, theirs_range);
+        self.push_marker(THEIRS_MARKER);
+        self.text.push('\n');
+        self.conflicts += 1;
+    }
+
+    /// Writes a conflict marker on its own line following a side of the
+    /// conflict, dropping blank lines that separated it from the side
+    fn push_marker(&mut self, marker: &str) {
+        while self.text.ends_with("\n\n") {
+            self.text.pop();
+        }
+        self.end_line();
+        self.text.push_str(marker);
+        self.text.push('\n');
+
+        // The block following the marker is not separated from it
+        self.last = None;
+    }
+
+    /// Ensures the text ends with a line break unless it is empty
+    fn end_line(&mut self) {
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str =
+        "= Title =\n\nsome text\n\n- one\n- two\n\n|a|b|\n|1|2|\n";
+
+    #[test]
+    fn merge_pages_should_combine_changes_to_different_blocks() {
+        let ours =
+            "= Title =\n\nsome other text\n\n- one\n- two\n\n|a|b|\n|1|2|\n";
+        let theirs =
+            "= Title =\n\nsome text\n\n- one\n- two\n- three\n\n|a|b|\n|1|3|\n";
+        let merge = merge_pages(BASE, ours, theirs).unwrap();
+        assert!(!merge.has_conflicts());
+        assert_eq!(
+            merge.text,
+            concat!(
+                "= Title =\n\nsome other text\n\n",
+                "- one\n- two\n- three\n\n|a|b|\n|1|3|\n",
+            )
+        );
+    }
+
+    #[test]
+    fn merge_pages_should_accept_identical_changes_and_additions() {
+        let ours = "= Title =\n\nnew text\n\n- one\n- two\n\n|a|b|\n|1|2|\n";
+        let theirs =
+            "= Title =\n\nnew text\n\n- one\n- two\n\n|a|b|\n|1|2|\n\nmore";
+        let merge = merge_pages(BASE, ours, theirs).unwrap();
+        assert!(!merge.has_conflicts());
+        assert_eq!(
+            merge.text,
+            "= Title =\n\nnew text\n\n- one\n- two\n\n|a|b|\n|1|2|\n\nmore"
+        );
+    }
+
+    #[test]
+    fn merge_pages_should_mark_conflicting_changes_to_the_same_block() {
+        let ours = "= Title =\n\nour text\n\n- one\n- two\n\n|a|b|\n|1|2|\n";
+        let theirs =
+            "= Title =\n\ntheir text\n\n- one\n- two\n\n|a|b|\n|1|2|\n";
+        let merge = merge_pages(BASE, ours, theirs).unwrap();
+        assert_eq!(merge.conflicts, 1);
+        assert_eq!(
+            merge.text,
+            [
+                "= Title =",
+                "",
+                "<<<<<<< ours",
+                "our text",
+                "=======",
+                "their text",
+                ">>>>>>> theirs",
+                "",
+                "- one",
+                "- two",
+                "",
+                "|a|b|",
+                "|1|2|",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn merge_pages_should_combine_a_changed_block_with_a_new_neighbor() {
+        let ours = "= Title =\n\nsome text\n\n- one\n- two\n\n|a|b|\n|9|9|\n";
+        let theirs =
+            "= Title =\n\nsome text\n\n- one\n- two\n\n|a|b|\n|1|2|\nmore\n";
+        let merge = merge_pages(BASE, ours, theirs).unwrap();
+        assert!(!merge.has_conflicts());
+        assert_eq!(
+            merge.text,
+            "= Title =\n\nsome text\n\n- one\n- two\n\n|a|b|\n|9|9|\n\nmore\n"
+        );
+    }
+
+    #[test]
+    fn merge_pages_should_keep_removals_made_by_one_version() {
+        let ours = "= Title =\n\nsome text\n\n|a|b|\n|1|2|\n";
+        let merge = merge_pages(BASE, ours, BASE).unwrap();
+        assert!(!merge.has_conflicts());
+        assert_eq!(merge.text, ours);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{BlockElement, Page, Region};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+/// Represents the kind of element listed within an outline
+#[derive(
+    Copy, Clone, Debug, Display, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    #[display(fmt = "header")]
+    Header,
+
+    #[display(fmt = "list")]
+    List,
+
+    #[display(fmt = "table")]
+    Table,
+}
+
+impl SymbolKind {
+    /// Returns the numeric `SymbolKind` of the language server protocol that
+    /// best represents the element
+    pub fn to_lsp_kind(self) -> u32 {
+        match self {
+            // String
+            Self::Header => 15,
+            // Array
+            Self::List => 18,
+            // Struct
+            Self::Table => 23,
+        }
+    }
+}
+
+/// Represents an element within the outline of a page, shaped after the
+/// `DocumentSymbol` of the language server protocol
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutlineSymbol {
+    /// Text of a header, or a short label for other elements
+    pub name: String,
+
+    /// Additional details about the element, such as the number of items
+    /// within a list
+    pub detail: Option<String>,
+
+    /// Kind of element represented by the symbol
+    pub kind: SymbolKind,
+
+    /// Level of a header (1, 2, 3, etc), or none for other elements
+    pub level: Option<usize>,
+
+    /// Region spanning the element and everything it contains, which for a
+    /// header is its entire section up to the next header of the same or
+    /// higher level
+    pub region: Region,
+
+    /// Region of the element itself, which for a header excludes its section
+    pub selection_region: Region,
+
+    /// Symbols contained within the element, such as the headers of
+    /// subsections
+    pub children: Vec<OutlineSymbol>,
+}
+
+/// Represents options that control which elements are included within an
+/// outline beyond headers
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct OutlineOptions {
+    /// If true, top-level lists are included as symbols
+    pub include_lists: bool,
+
+    /// If true, top-level tables are included as symbols
+    pub include_tables: bool,
+}
+
+/// Builds the hierarchical outline of a page, where each header contains the
+/// headers of its subsections and, if enabled, the lists and tables within
+/// its section
+pub fn outline(page: &Page, options: OutlineOptions) -> Vec<OutlineSymbol> {
+    let end = page
+        .elements()
+        .iter()
+        .map(|x| x.region().offset() + x.region().len())
+        .max()
+        .unwrap_or_default();
+
+    let mut roots = Vec::new();
+    let mut stack: Vec<OutlineSymbol> = Vec::new();
+
+    for element in page.elements().iter() {
+        let region = element.region();
+        let symbol = match element.as_inner() {
+            BlockElement::Header(x) => {
+                // Close every open section at the same or deeper level as
+                // it ends where this header begins
+                while stack.last().and_then(|x| x.level) >= Some(x.level) {
+                    close_section(&mut stack, &mut roots, region.offset());
+                }
+
+                stack.push(OutlineSymbol {
+                    name: x.content.to_string().trim().to_string(),
+                    detail: None,
+                    kind: SymbolKind::Header,
+                    level: Some(x.level),
+                    region,
+                    selection_region: region,
+                    children: Vec::new(),
+                });
+                continue;
+            }
+            BlockElement::List(x) if options.include_lists => OutlineSymbol {
+                name: String::from(if x.is_ordered() {
+                    "ordered list"
+                } else {
+                    "list"
+                }),
+                detail: Some(format!("{} items", x.len())),
+                kind: SymbolKind::List,
+                level: None,
+                region,
+                selection_region: region,
+                children: Vec::new(),
+            },
+            BlockElement::Table(x) if options.include_tables => OutlineSymbol {
+                name: String::from("table"),
+                detail: Some(format!(
+                    "{} rows, {} columns",
+                    x.row_cnt(),
+                    x.col_cnt()
+                )),
+                kind: SymbolKind::Table,
+                level: None,
+                region,
+                selection_region: region,
+                children: Vec::new(),
+            },
+            _ => continue,
+        };
+
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(symbol),
+            None => roots.push(symbol),
+        }
+    }
+
+    while !stack.is_empty() {
+        close_section(&mut stack, &mut roots, end);
+    }
+
+    roots
+}
+
+/// Removes the innermost open section, extending its region to the given
+/// end offset, and adds it to its parent section or the roots
+fn close_section(
+    stack: &mut Vec<OutlineSymbol>,
+    roots: &mut Vec<OutlineSymbol>,
+    end: usize,
+) {
+    if let Some(mut symbol) = stack.pop() {
+        let offset = symbol.region.offset();
+        symbol.region = Region::new(offset, end.max(offset) - offset);
+
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(symbol),
+            None => roots.push(symbol),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+
+    fn names(symbols: &[OutlineSymbol]) -> Vec<&str> {
+        symbols.iter().map(|x| x.name.as_str()).collect()
+    }
+
+    #[test]
+    fn outline_should_nest_headers_by_level() {
+        let text = indoc! {"
+            = One =
+            == Two ==
+            text
+            === Three ===
+            = Four =
+            === Five ===
+        "};
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+
+        let symbols = outline(&page, OutlineOptions::default());
+        assert_eq!(names(&symbols), ["One", "Four"]);
+        assert_eq!(names(&symbols[0].children), ["Two"]);
+        assert_eq!(names(&symbols[0].children[0].children), ["Three"]);
+        assert_eq!(names(&symbols[1].children), ["Five"]);
+
+        // Section of a header runs up to the next header at the same level
+        let four = text.find("= Four").unwrap();
+        assert_eq!(symbols[0].region, Region::new(0, four));
+        assert_eq!(symbols[0].selection_region, Region::new(0, 8));
+        assert_eq!(symbols[0].children[0].region.offset(), 8);
+    }
+
+    #[test]
+    fn outline_should_optionally_include_lists_and_tables() {
+        let page: Page = Language::from_vimwiki_str(indoc! {"
+            - item
+            = Header =
+            1. one
+            2. two
+
+            |a|b|
+            |c|d|
+        "})
+        .parse()
+        .unwrap();
+
+        let symbols = outline(&page, OutlineOptions::default());
+        assert_eq!(names(&symbols), ["Header"]);
+        assert!(symbols[0].children.is_empty());
+
+        let symbols = outline(
+            &page,
+            OutlineOptions {
+                include_lists: true,
+                include_tables: true,
+            },
+        );
+        assert_eq!(names(&symbols), ["list", "Header"]);
+        assert_eq!(names(&symbols[1].children), ["ordered list", "table"]);
+        assert_eq!(symbols[1].children[0].detail.as_deref(), Some("2 items"));
+        assert_eq!(
+            symbols[1].children[1].detail.as_deref(),
+            Some("2 rows, 2 columns")
+        );
+        assert_eq!(symbols[1].children[1].kind.to_lsp_kind(), 23);
+    }
+}
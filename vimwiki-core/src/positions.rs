@@ -0,0 +1,254 @@
+use crate::{
+    lists::{line_of, line_starts},
+    Region,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Represents the unit used to count columns within a line
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnUnit {
+    /// Bytes of UTF-8, matching the offsets of regions
+    Byte,
+
+    /// Code units of UTF-16, as used by the language server protocol
+    Utf16,
+
+    /// Unicode scalar values (chars)
+    Char,
+
+    /// Extended grapheme clusters, matching what is displayed as a single
+    /// character for CJK text, emoji, and combining marks
+    Grapheme,
+}
+
+/// Represents a position within text as a line and a column within that
+/// line, both counted from zero
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+/// Converts between byte offsets of some text and line/column positions
+/// measured in a chosen [`ColumnUnit`]
+#[derive(Clone, Debug)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            starts: line_starts(text),
+        }
+    }
+
+    /// Returns the total lines of the text
+    pub fn line_cnt(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Returns the content of the line at the given index, excluding its
+    /// line ending (`\n` or `\r\n`)
+    pub fn line(&self, line: usize) -> Option<&'a str> {
+        let start = *self.starts.get(line)?;
+        let end = self
+            .starts
+            .get(line + 1)
+            .map(|x| x - 1)
+            .unwrap_or(self.text.len());
+        let line = &self.text[start..end];
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+
+    /// Converts a byte offset into a position, returning None if the offset
+    /// is beyond the text or not on a char boundary
+    ///
+    /// An offset within a grapheme cluster is reported at the column of
+    /// that cluster.
+    pub fn position(
+        &self,
+        offset: usize,
+        unit: ColumnUnit,
+    ) -> Option<Position> {
+        if !self.text.is_char_boundary(offset) {
+            return None;
+        }
+
+        let line = line_of(&self.starts, offset);
+        let start = self.starts[line];
+        let before = &self.text[start..offset];
+        let column = match unit {
+            ColumnUnit::Byte => before.len(),
+            ColumnUnit::Utf16 => before.encode_utf16().count(),
+            ColumnUnit::Char => before.chars().count(),
+            ColumnUnit::Grapheme => {
+                let line_text = self.line(line).unwrap_or_default();
+                line_text
+                    .grapheme_indices(true)
+                    .take_while(|(idx, g)| idx + g.len() <= before.len())
+                    .count()
+            }
+        };
+
+        Some(Position::new(line, column))
+    }
+
+    /// Converts a position into a byte offset, returning None if the line is
+    /// beyond the text; a column beyond the end of its line is clamped to
+    /// the end of the line
+    pub fn offset(
+        &self,
+        position: Position,
+        unit: ColumnUnit,
+    ) -> Option<usize> {
+        let start = *self.starts.get(position.line)?;
+        let line = self.line(position.line)?;
+        let within = match unit {
+            ColumnUnit::Byte => {
+                let mut len = position.column.min(line.len());
+                while !line.is_char_boundary(len) {
+                    len -= 1;
+                }
+                len
+            }
+            ColumnUnit::Utf16 => {
+                let mut units = 0;
+                line.char_indices()
+                    .find(|(_, c)| {
+                        units += c.len_utf16();
+                        units > position.column
+                    })
+                    .map(|(idx, _)| idx)
+                    .unwrap_or(line.len())
+            }
+            ColumnUnit::Char => line
+                .char_indices()
+                .nth(position.column)
+                .map(|(idx, _)| idx)
+                .unwrap_or(line.len()),
+            ColumnUnit::Grapheme => line
+                .grapheme_indices(true)
+                .nth(position.column)
+                .map(|(idx, _)| idx)
+                .unwrap_or(line.len()),
+        };
+
+        Some(start + within)
+    }
+
+    /// Converts a region into the range of positions from its start up to
+    /// (but not including) its end
+    pub fn range(
+        &self,
+        region: Region,
+        unit: ColumnUnit,
+    ) -> Option<Range<Position>> {
+        let start = self.position(region.offset(), unit)?;
+        let end = self.position(region.offset() + region.len(), unit)?;
+        Some(start..end)
+    }
+
+    /// Converts a range of positions into the region it covers
+    pub fn region(
+        &self,
+        range: Range<Position>,
+        unit: ColumnUnit,
+    ) -> Option<Region> {
+        let start = self.offset(range.start, unit)?;
+        let end = self.offset(range.end, unit)?;
+        Some(Region::from(start..end.max(start)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_should_count_columns_in_chosen_unit() {
+        // 日 is 3 bytes and 1 UTF-16 unit, 👍🏽 is 8 bytes, 4 UTF-16 units,
+        // 2 chars, and a single grapheme
+        let text = "= 日本 =\r\n👍🏽 [[link]]\n";
+        let index = LineIndex::new(text);
+        let offset = text.find("[[").unwrap();
+
+        assert_eq!(
+            index.position(offset, ColumnUnit::Byte),
+            Some(Position::new(1, 9))
+        );
+        assert_eq!(
+            index.position(offset, ColumnUnit::Utf16),
+            Some(Position::new(1, 5))
+        );
+        assert_eq!(
+            index.position(offset, ColumnUnit::Char),
+            Some(Position::new(1, 3))
+        );
+        assert_eq!(
+            index.position(offset, ColumnUnit::Grapheme),
+            Some(Position::new(1, 2))
+        );
+
+        // Within a grapheme cluster reports the cluster, but not within a char
+        let emoji = text.find('👍').unwrap();
+        assert_eq!(
+            index.position(emoji + 4, ColumnUnit::Grapheme),
+            Some(Position::new(1, 0))
+        );
+        assert_eq!(index.position(emoji + 1, ColumnUnit::Utf16), None);
+        assert_eq!(index.line(0), Some("= 日本 ="));
+    }
+
+    #[test]
+    fn offset_should_invert_position_and_clamp_to_line_end() {
+        let text = "= 日本 =\r\n👍🏽 [[link]]\n";
+        let index = LineIndex::new(text);
+        let offset = text.find("[[").unwrap();
+
+        for unit in [
+            ColumnUnit::Byte,
+            ColumnUnit::Utf16,
+            ColumnUnit::Char,
+            ColumnUnit::Grapheme,
+        ] {
+            let position = index.position(offset, unit).unwrap();
+            assert_eq!(index.offset(position, unit), Some(offset));
+        }
+
+        let end_of_header = text.find('\r').unwrap();
+        assert_eq!(
+            index.offset(Position::new(0, 100), ColumnUnit::Utf16),
+            Some(end_of_header)
+        );
+        assert_eq!(index.offset(Position::new(5, 0), ColumnUnit::Byte), None);
+
+        let region = Region::new(offset, "[[link]]".len());
+        let range = index.range(region, ColumnUnit::Utf16).unwrap();
+        assert_eq!(range, Position::new(1, 5)..Position::new(1, 13));
+        assert_eq!(index.region(range, ColumnUnit::Utf16), Some(region));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    edit::TextEdit, BlockElement, Comment, Element, InlineElement, Page, Region,
+};
+
+/// Rewraps every paragraph of a page overlapping the given region so that no
+/// line exceeds the width (in characters), like vim's `gq`, returning an edit
+/// per paragraph that changes
+///
+/// Lines are only broken at whitespace outside of links, inline code, math,
+/// and decorated text, so a long `[[link|with a description]]` is kept
+/// whole even when it exceeds the width by itself. Each paragraph keeps the
+/// indentation of its first line. Paragraphs containing a line comment are
+/// left alone, as joining lines would comment out the text that follows.
+///
+/// An empty region selects the paragraph containing its offset.
+pub fn reflow(
+    text: &str,
+    page: &Page,
+    region: Region,
+    width: usize,
+) -> Vec<TextEdit> {
+    let elements: Vec<_> = page.descendants().collect();
+    let selection_end = region.offset() + region.len();
+
+    page.elements()
+        .iter()
+        .filter(|x| matches!(x.as_inner(), BlockElement::Paragraph(_)))
+        .map(|x| x.region())
+        .filter(|x| {
+            if region.is_empty() {
+                x.contains(region.offset())
+            } else {
+                x.offset() < selection_end
+                    && region.offset() < x.offset() + x.len()
+            }
+        })
+        .filter_map(|paragraph| {
+            let end = paragraph.offset() + paragraph.len();
+            let within = |x: &Region| {
+                x.offset() >= paragraph.offset() && x.offset() < end
+            };
+
+            let mut protected = Vec::new();
+            for element in elements.iter().filter(|x| within(&x.region())) {
+                match element.as_inner() {
+                    Element::Inline(InlineElement::Comment(Comment::Line(
+                        _,
+                    ))) => return None,
+                    Element::Inline(
+                        InlineElement::Link(_)
+                        | InlineElement::Code(_)
+                        | InlineElement::Math(_)
+                        | InlineElement::DecoratedText(_),
+                    ) => protected.push(element.region()),
+                    _ => {}
+                }
+            }
+
+            let old = text.get(paragraph.offset()..end)?;
+            let new = wrap(old, paragraph.offset(), &protected, width);
+            if old == new {
+                None
+            } else {
+                Some(TextEdit::new(paragraph, new))
+            }
+        })
+        .collect()
+}
+
+/// Wraps the text of a paragraph starting at the given offset, never breaking
+/// at whitespace within a protected region
+fn wrap(
+    text: &str,
+    offset: usize,
+    protected: &[Region],
+    width: usize,
+) -> String {
+    let content = text.trim_end_matches('\n');
+    let indent_len =
+        content.len() - content.trim_start_matches([' ', '\t']).len();
+    let indent = &content[..indent_len];
+
+    // Split into words at whitespace that is not protected
+    let mut words = Vec::new();
+    let mut start = None;
+    for (idx, c) in content.char_indices().skip(indent_len) {
+        let is_break = c.is_whitespace()
+            && !protected.iter().any(|x| x.contains(offset + idx));
+        match (is_break, start) {
+            (true, Some(x)) => {
+                words.push(&content[x..idx]);
+                start = None;
+            }
+            (false, None) => start = Some(idx),
+            _ => {}
+        }
+    }
+    if let Some(x) = start {
+        words.push(&content[x..]);
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::from(indent);
+    let mut line_width = indent.chars().count();
+    let mut is_empty = true;
+    for word in words {
+        let word_width = word.chars().count();
+        if !is_empty && line_width + 1 + word_width > width {
+            lines.push(std::mem::replace(&mut line, String::from(indent)));
+            line_width = indent.chars().count();
+            is_empty = true;
+        }
+
+        if !is_empty {
+            line.push(' ');
+            line_width += 1;
+        }
+        line.push_str(word);
+        line_width += word_width;
+        is_empty = false;
+    }
+    lines.push(line);
+
+    let mut wrapped = lines.join("\n");
+    if text.ends_with('\n') {
+        wrapped.push('\n');
+    }
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{edit::apply_edits, Language};
+    use indoc::indoc;
+
+    fn reflow_text(text: &str, region: Region, width: usize) -> String {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        apply_edits(text, &reflow(text, &page, region, width)).unwrap()
+    }
+
+    #[test]
+    fn reflow_should_wrap_without_breaking_links_code_or_math() {
+        let text = indoc! {"
+            Some words then [[a long link|with a description]] and
+            `code with spaces` plus $x + y$ here
+        "};
+        assert_eq!(
+            reflow_text(text, Region::new(0, 0), 20),
+            indoc! {"
+                Some words then
+                [[a long link|with a description]]
+                and
+                `code with spaces`
+                plus $x + y$ here
+            "}
+        );
+    }
+
+    #[test]
+    fn reflow_should_only_change_selected_paragraphs() {
+        let text = indoc! {"
+            one two
+            three
+
+              four five
+            six
+
+            seven %% comment
+            eight
+        "};
+
+        // Second paragraph keeps its indentation while the others, including
+        // the paragraph with a line comment, are left alone
+        let offset = text.find("five").unwrap();
+        assert_eq!(
+            reflow_text(text, Region::new(offset, 0), 80),
+            indoc! {"
+                one two
+                three
+
+                  four five six
+
+                seven %% comment
+                eight
+            "}
+        );
+
+        assert_eq!(
+            reflow_text(text, Region::new(0, text.len()), 80),
+            indoc! {"
+                one two three
+
+                  four five six
+
+                seven %% comment
+                eight
+            "}
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use crate::{ids::Fnv1a, tree::PageTree, Language, Page, ParseError};
+use std::{
+    any::Any,
+    fmt, fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+/// Extension of files within a regression corpus
+pub const REGRESSION_FILE_EXT: &str = "wiki";
+
+/// Represents the parser misbehaving on some input, rather than merely
+/// rejecting it with a parse error
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// Parsing panicked with the given message
+    Panicked { message: String },
+
+    /// Parsing produced an element whose region is not within the input
+    OutOfBounds {
+        kind: String,
+        start: usize,
+        end: usize,
+    },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Panicked { message } => write!(f, "panicked: {}", message),
+            Self::OutOfBounds { kind, start, end } => {
+                write!(f, "{} at {}..{} is out of bounds", kind, start, end)
+            }
+        }
+    }
+}
+
+/// Parses the vimwiki text, returning how the parser misbehaved if it did
+///
+/// Panics are caught, but are still reported by the panic hook; see
+/// [`Repro::find`] to check without them being reported
+pub fn check(text: &str) -> Result<(), Failure> {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let page: Page = match Language::from_vimwiki_str(text).parse() {
+            Ok(page) => page,
+            Err(x) => {
+                let _: ParseError = x;
+                return Ok(());
+            }
+        };
+
+        let tree = PageTree::new("", &page);
+        for node in tree.nodes.iter() {
+            let start = node.region.offset();
+            let end = start + node.region.len();
+            if text.get(start..end).is_none() {
+                return Err(Failure::OutOfBounds {
+                    kind: node.kind.to_string(),
+                    start,
+                    end,
+                });
+            }
+        }
+        Ok(())
+    }));
+
+    match result {
+        Ok(x) => x,
+        Err(x) => Err(Failure::Panicked {
+            message: panic_message(x.as_ref()),
+        }),
+    }
+}
+
+/// Produces the message of the payload of a panic
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(x) = payload.downcast_ref::<&str>() {
+        x.to_string()
+    } else if let Some(x) = payload.downcast_ref::<String>() {
+        x.clone()
+    } else {
+        String::from("<unknown>")
+    }
+}
+
+/// Shrinks the text to a smaller one that still fails, first removing
+/// whole lines and then single characters until removing any more would
+/// make it pass
+pub fn minimize(text: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let lines: Vec<String> = text
+        .split_inclusive('\n')
+        .map(ToString::to_string)
+        .collect();
+    let lines = reduce(lines, &mut fails);
+
+    let chars: Vec<String> =
+        lines.concat().chars().map(|x| x.to_string()).collect();
+    reduce(chars, &mut fails).concat()
+}
+
+/// Removes chunks of the units while the remainder still fails, halving the
+/// size of the chunks whenever none can be removed (delta debugging)
+fn reduce(
+    mut units: Vec<String>,
+    fails: &mut impl FnMut(&str) -> bool,
+) -> Vec<String> {
+    let mut chunks = 2;
+    while units.len() >= 2 {
+        let size = units.len().div_ceil(chunks);
+        let mut removed = false;
+
+        let mut start = 0;
+        while start < units.len() {
+            let end = (start + size).min(units.len());
+            let candidate: String = units[..start]
+                .iter()
+                .chain(units[end..].iter())
+                .map(String::as_str)
+                .collect();
+            if fails(&candidate) {
+                units.drain(start..end);
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+
+        if removed {
+            chunks = (chunks - 1).max(2);
+        } else if chunks >= units.len() {
+            break;
+        } else {
+            chunks = (chunks * 2).min(units.len());
+        }
+    }
+    units
+}
+
+/// Represents a minimized input that makes the parser misbehave
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Repro {
+    /// Smallest input found that fails in the same way as the original
+    pub input: String,
+
+    /// How the parser misbehaves on the input
+    pub failure: Failure,
+}
+
+impl Repro {
+    /// Checks the vimwiki text, returning a minimized reproduction if the
+    /// parser misbehaves on it
+    ///
+    /// While checking, the panic hook is replaced so that the many panics
+    /// caused by minimizing are not reported
+    pub fn find(text: &str) -> Option<Self> {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+
+        let repro = check(text).err().map(|failure| {
+            let input = minimize(text, |x| {
+                check(x).err().is_some_and(|x| same_kind(&x, &failure))
+            });
+            let failure = check(&input).err().unwrap_or(failure);
+            Self { input, failure }
+        });
+
+        panic::set_hook(hook);
+        repro
+    }
+}
+
+/// Returns true if both failures are the same kind of misbehavior, as the
+/// exact message or region of a failure changes as its input shrinks
+fn same_kind(a: &Failure, b: &Failure) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+/// Represents an input of a regression corpus that made the parser
+/// misbehave
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Regression {
+    /// Path to the input within the corpus
+    pub file: PathBuf,
+
+    /// How the parser misbehaves on the input
+    pub failure: Failure,
+}
+
+/// Represents the results of checking every input of a regression corpus
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegressionReport {
+    /// Total inputs checked
+    pub checked: usize,
+
+    /// Inputs that made the parser misbehave, in order of path
+    pub regressions: Vec<Regression>,
+}
+
+impl RegressionReport {
+    /// Panics with every input that made the parser misbehave
+    pub fn assert_ok(&self) {
+        if !self.regressions.is_empty() {
+            let text: Vec<String> = self
+                .regressions
+                .iter()
+                .map(|x| format!("{:?}: {}", x.file, x.failure))
+                .collect();
+            panic!(
+                "{} of {} regression input(s) failed:\n\n{}",
+                self.regressions.len(),
+                self.checked,
+                text.join("\n")
+            );
+        }
+    }
+}
+
+/// Represents a directory of inputs that once made the parser misbehave,
+/// such as those found by fuzzing, which are checked so that they never
+/// do again
+///
+/// ## Examples
+///
+/// ```no_run
+/// use vimwiki::regressions::{RegressionCorpus, Repro};
+///
+/// let text = std::fs::read_to_string("crash.wiki").unwrap();
+/// if let Some(repro) = Repro::find(&text) {
+///     println!("{}: {:?}", repro.failure, repro.input);
+///     RegressionCorpus::bundled().record(&repro.input).unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegressionCorpus {
+    /// Directory containing the inputs
+    pub dir: PathBuf,
+}
+
+impl RegressionCorpus {
+    /// Creates a corpus of the inputs in the directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the corpus of regression inputs bundled with this crate
+    pub fn bundled() -> Self {
+        Self::new(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/regressions"),
+        )
+    }
+
+    /// Returns the paths of the inputs of the corpus, sorted
+    pub fn files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.is_file()
+                && path.extension().is_some_and(|x| x == REGRESSION_FILE_EXT)
+            {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Returns the path where the input is stored, named after a hash of
+    /// its contents so that recording the same input twice is harmless
+    pub fn path_of(&self, input: &str) -> PathBuf {
+        let mut hasher = Fnv1a::default();
+        hasher.write(input.as_bytes());
+        self.dir
+            .join(format!("{:016x}.{}", hasher.0, REGRESSION_FILE_EXT))
+    }
+
+    /// Adds the input to the corpus, returning the path where it is stored
+    pub fn record(&self, input: &str) -> io::Result<PathBuf> {
+        let path = self.path_of(input);
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&path, input)?;
+        Ok(path)
+    }
+
+    /// Checks every input of the corpus
+    pub fn check(&self) -> io::Result<RegressionReport> {
+        let mut report = RegressionReport::default();
+        for file in self.files()? {
+            report.checked += 1;
+            if let Err(failure) = check(&fs::read_to_string(&file)?) {
+                report.regressions.push(Regression { file, failure });
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn check_should_not_fail_on_parse_errors() {
+        assert_eq!(check("= Title =\n{{{\n"), Ok(()));
+        assert_eq!(check(""), Ok(()));
+    }
+
+    #[test]
+    fn minimize_should_shrink_input_while_it_still_fails() {
+        let text = "= Title =\nsome text\nhas bad [[link]]\nmore\n";
+        let input = minimize(text, |x| x.contains("bad"));
+        assert_eq!(input, "bad");
+
+        let input = minimize(text, |x| x.matches('\n').count() >= 2);
+        assert_eq!(input, "\n\n");
+    }
+
+    #[test]
+    fn record_should_store_inputs_by_hash() {
+        let dir = env::temp_dir().join("vimwiki-regressions-record");
+        let _ = fs::remove_dir_all(&dir);
+
+        let corpus = RegressionCorpus::new(&dir);
+        let path = corpus.record("[[").unwrap();
+        assert_eq!(corpus.record("[[").unwrap(), path);
+        assert_eq!(corpus.files().unwrap(), vec![path]);
+
+        let report = corpus.check().unwrap();
+        assert_eq!(report.checked, 1);
+        assert!(report.regressions.is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn bundled_regressions_should_not_fail() {
+        RegressionCorpus::bundled().check().unwrap().assert_ok();
+    }
+}
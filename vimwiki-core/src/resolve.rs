@@ -0,0 +1,722 @@
+use crate::{
+    date::WikiDate, graph::collect_links, Anchor, BlockElement, Element,
+    InlineElement, Link, LinkData, Page, Region,
+};
+use derive_more::{Display, Error};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+/// Represents how links to directories (e.g. `[[projects/]]`) are resolved
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectoryLinks {
+    /// Directory links resolve to the index file within the directory
+    #[default]
+    Index,
+
+    /// Directory links resolve to the directory itself
+    Directory,
+}
+
+/// Represents how spaces within the targets of links (e.g. `[[some page]]`)
+/// appear within the names of files and within urls, where a target written
+/// with spaces and one written with `%20` are always the same target
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSpaces {
+    /// Spaces are kept within names and urls (e.g. `some page.html`)
+    Space,
+
+    /// Spaces are kept within names and percent encoded within urls (e.g.
+    /// `some%20page.html`)
+    #[default]
+    Percent,
+
+    /// Spaces are replaced with underscores within names and urls, so a
+    /// target written with underscores is the same target (e.g.
+    /// `some_page.html`)
+    Underscore,
+}
+
+impl LinkSpaces {
+    /// Produces the name used for a decoded segment of a link target
+    pub fn to_name(self, segment: &str) -> Cow<'_, str> {
+        match self {
+            Self::Underscore if segment.contains(' ') => {
+                Cow::Owned(segment.replace(' ', "_"))
+            }
+            _ => Cow::Borrowed(segment),
+        }
+    }
+
+    /// Returns true if spaces are percent encoded within urls
+    pub fn is_percent_encoded(self) -> bool {
+        matches!(self, Self::Percent)
+    }
+}
+
+/// Represents the layout of a wiki used when resolving links into files
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolverWiki {
+    /// Path to the root of the wiki (must be absolute path)
+    pub path: PathBuf,
+
+    /// Optional name of the wiki used by named interwiki links
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Extension of the wiki's pages without the leading period
+    #[serde(default = "ResolverWiki::default_ext")]
+    pub ext: String,
+
+    /// Name of the index page of a directory without an extension
+    #[serde(default = "ResolverWiki::default_index")]
+    pub index: String,
+
+    /// Path for diary directory relative to the wiki's path
+    #[serde(default = "ResolverWiki::default_diary_rel_path")]
+    pub diary_rel_path: PathBuf,
+
+    /// How spaces within links to pages appear within the names of the
+    /// wiki's files
+    #[serde(default)]
+    pub link_spaces: LinkSpaces,
+}
+
+impl ResolverWiki {
+    /// Creates a wiki rooted at the given path using the default extension,
+    /// index, and diary location
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            name: None,
+            ext: Self::default_ext(),
+            index: Self::default_index(),
+            diary_rel_path: Self::default_diary_rel_path(),
+            link_spaces: LinkSpaces::default(),
+        }
+    }
+
+    /// Sets the name of the wiki used by named interwiki links
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the extension of the wiki's pages (e.g. `wiki` or `.md`)
+    pub fn with_ext(mut self, ext: impl Into<String>) -> Self {
+        self.ext = ext.into().trim_start_matches('.').to_string();
+        self
+    }
+
+    /// Sets the name of the index page of a directory, defaulting to `index`
+    pub fn with_index(mut self, index: impl Into<String>) -> Self {
+        self.index = index.into();
+        self
+    }
+
+    /// Sets the path (relative to the wiki root) where diary pages live,
+    /// defaulting to `diary`
+    pub fn with_diary_rel_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.diary_rel_path = path.into();
+        self
+    }
+
+    /// Sets how spaces within links to pages appear within the names of the
+    /// wiki's files, defaulting to keeping them as spaces
+    pub fn with_link_spaces(mut self, link_spaces: LinkSpaces) -> Self {
+        self.link_spaces = link_spaces;
+        self
+    }
+
+    /// Produces the path of the diary page for the given date
+    pub fn diary_path(&self, date: WikiDate) -> PathBuf {
+        self.page_file(
+            self.path
+                .join(self.diary_rel_path.as_path())
+                .join(date.format("%Y-%m-%d").to_string()),
+        )
+    }
+
+    /// Produces the path of the page file with the given name, adding the
+    /// wiki's extension unless the name already has it
+    fn page_file(&self, mut path: PathBuf) -> PathBuf {
+        let has_ext = path
+            .extension()
+            .is_some_and(|ext| ext.to_string_lossy() == self.ext);
+        if !has_ext && !self.ext.is_empty() {
+            let mut name = path.file_name().unwrap_or_default().to_os_string();
+            name.push(".");
+            name.push(self.ext.as_str());
+            path.set_file_name(name);
+        }
+        path
+    }
+
+    #[inline]
+    pub fn default_ext() -> String {
+        String::from("wiki")
+    }
+
+    #[inline]
+    pub fn default_index() -> String {
+        String::from("index")
+    }
+
+    #[inline]
+    pub fn default_diary_rel_path() -> PathBuf {
+        PathBuf::from("diary")
+    }
+}
+
+/// Represents the concrete target of a link
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkTarget {
+    /// Link targets a file (or directory) on the local machine, optionally
+    /// at an anchor within it
+    File {
+        path: PathBuf,
+        anchor: Option<Anchor<'static>>,
+    },
+
+    /// Link targets some remote resource
+    Uri(String),
+}
+
+impl LinkTarget {
+    /// Returns the path of the file targeted by the link, if it is local
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::File { path, .. } => Some(path.as_path()),
+            Self::Uri(_) => None,
+        }
+    }
+
+    /// Returns the anchor within the file targeted by the link, if it has one
+    pub fn anchor(&self) -> Option<&Anchor<'static>> {
+        match self {
+            Self::File { anchor, .. } => anchor.as_ref(),
+            Self::Uri(_) => None,
+        }
+    }
+}
+
+/// Represents an error encountered when resolving a link
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+pub enum ResolveError {
+    /// Link targets a wiki by an index that does not exist
+    #[display(fmt = "No wiki with index {}", index)]
+    MissingWikiWithIndex {
+        #[error(not(source))]
+        index: usize,
+    },
+
+    /// Link targets a wiki by a name that does not exist
+    #[display(fmt = "No wiki with name {}", name)]
+    MissingWikiWithName {
+        #[error(not(source))]
+        name: String,
+    },
+}
+
+/// Represents a link within a page whose target does not exist
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenLink {
+    /// Region of the link within the page
+    pub region: Region,
+
+    /// Target of the link as written within the page
+    pub link: String,
+
+    /// Description of why the link is broken
+    pub reason: String,
+}
+
+/// Resolves links into the files they target, following the same rules for
+/// every consumer (such as html output, link checking, and go-to-definition)
+///
+/// Links to pages are resolved relative to the directory of the file
+/// containing them, or relative to the root of the wiki if they start with
+/// `/`. The wiki's extension is added to pages that lack it, links to
+/// directories resolve based on [`DirectoryLinks`], and transclusions and
+/// links to other local files keep their paths as-is.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkResolver {
+    #[serde(default)]
+    wikis: Vec<ResolverWiki>,
+
+    #[serde(default)]
+    directory_links: DirectoryLinks,
+}
+
+impl LinkResolver {
+    /// Creates a resolver without any wikis
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a wiki, where the order that wikis are added determines their
+    /// index for indexed interwiki links
+    pub fn with_wiki(mut self, wiki: ResolverWiki) -> Self {
+        self.wikis.push(wiki);
+        self
+    }
+
+    /// Sets how links to directories are resolved, defaulting to the index
+    /// page within the directory
+    pub fn with_directory_links(
+        mut self,
+        directory_links: DirectoryLinks,
+    ) -> Self {
+        self.directory_links = directory_links;
+        self
+    }
+
+    /// Returns the wikis known to the resolver ordered by index
+    pub fn wikis(&self) -> &[ResolverWiki] {
+        &self.wikis
+    }
+
+    /// Finds the wiki that contains the file at the given path; if more than
+    /// one wiki would match, then the wiki at the deepest level is returned
+    pub fn find_wiki_by_path(&self, path: &Path) -> Option<&ResolverWiki> {
+        self.wikis
+            .iter()
+            .filter(|wiki| path.starts_with(wiki.path.as_path()))
+            .max_by_key(|wiki| wiki.path.components().count())
+    }
+
+    /// Resolves a link found within the file at the given absolute path
+    ///
+    /// If the file is not within any wiki, it is treated as the only file
+    /// of a wiki rooted at its directory.
+    pub fn resolve(
+        &self,
+        src: &Path,
+        link: &Link,
+    ) -> Result<LinkTarget, ResolveError> {
+        match self.find_wiki_by_path(src) {
+            Some(wiki) => self.resolve_within(wiki, src, link),
+            None => {
+                let mut wiki =
+                    ResolverWiki::new(src.parent().unwrap_or(Path::new("")));
+                if let Some(ext) = src.extension() {
+                    wiki = wiki.with_ext(ext.to_string_lossy());
+                }
+                self.resolve_within(&wiki, src, link)
+            }
+        }
+    }
+
+    /// Resolves a link found within the file at the given path that belongs
+    /// to the given wiki, where a relative path is treated as relative to
+    /// the root of the wiki
+    pub fn resolve_within(
+        &self,
+        wiki: &ResolverWiki,
+        src: &Path,
+        link: &Link,
+    ) -> Result<LinkTarget, ResolveError> {
+        let data = link.data();
+        if data.is_remote() {
+            return Ok(LinkTarget::Uri(data.uri_ref.to_string()));
+        }
+
+        let src_dir = src.parent().unwrap_or(Path::new(""));
+        let path = match link {
+            Link::Wiki { .. } if data.is_local_anchor() => src.to_path_buf(),
+            Link::Wiki { .. } if data.scheme().is_none() => {
+                let base = if data.uri_ref.is_path_absolute() {
+                    wiki.path.as_path()
+                } else {
+                    src_dir
+                };
+                self.page_path(wiki, base, data)
+            }
+            Link::IndexedInterWiki { index, .. } => {
+                let index = *index as usize;
+                let wiki = self
+                    .wikis
+                    .get(index)
+                    .ok_or(ResolveError::MissingWikiWithIndex { index })?;
+                self.page_path(wiki, wiki.path.as_path(), data)
+            }
+            Link::NamedInterWiki { name, .. } => {
+                let wiki = self
+                    .wikis
+                    .iter()
+                    .find(|w| w.name.as_deref() == Some(name.as_ref()))
+                    .ok_or_else(|| ResolveError::MissingWikiWithName {
+                        name: name.to_string(),
+                    })?;
+                self.page_path(wiki, wiki.path.as_path(), data)
+            }
+            Link::Diary { date, .. } => wiki.diary_path(*date),
+
+            // Transclusions, raw links, and links to local files (using
+            // file: or local:) point directly at a file without an extension
+            // being added, where Windows paths start at their drive or share
+            _ => {
+                let path = rel_path(data);
+                if let Some(root) = data.to_windows_root() {
+                    root.join(path.components().skip(1).collect::<PathBuf>())
+                } else if data.uri_ref.is_path_absolute()
+                    && data.scheme().is_some()
+                {
+                    Path::new(&std::path::Component::RootDir).join(path)
+                } else if data.uri_ref.is_path_absolute() {
+                    wiki.path.join(path)
+                } else {
+                    src_dir.join(path)
+                }
+            }
+        };
+
+        Ok(LinkTarget::File {
+            path,
+            anchor: data.to_anchor().map(Anchor::into_owned),
+        })
+    }
+
+    /// Finds links within the page of the file at the given path whose
+    /// targets do not exist on the local machine, ignoring remote links
+    /// and anchors
+    pub fn find_broken_links(
+        &self,
+        src: &Path,
+        page: &Page,
+    ) -> Vec<BrokenLink> {
+        let mut broken = Vec::new();
+
+        for link in collect_links(page) {
+            let reason = match self.resolve(src, link.as_inner()) {
+                Ok(LinkTarget::File { path, .. }) if !path.exists() => {
+                    format!("{} does not exist", path.to_string_lossy())
+                }
+                Ok(_) => continue,
+                Err(x) => x.to_string(),
+            };
+
+            broken.push(BrokenLink {
+                region: link.region(),
+                link: match link.as_inner() {
+                    Link::Diary { date, .. } => format!("diary:{}", date),
+                    Link::IndexedInterWiki { index, data } => format!(
+                        "wiki{}:{}",
+                        index,
+                        data.to_decoded_uri_string()
+                    ),
+                    Link::NamedInterWiki { name, data } => {
+                        format!("wn.{}:{}", name, data.to_decoded_uri_string())
+                    }
+                    x => x.data().to_decoded_uri_string(),
+                },
+                reason,
+            });
+        }
+
+        broken
+    }
+
+    /// Produces the path of the page targeted by a link relative to the
+    /// given base directory
+    fn page_path(
+        &self,
+        wiki: &ResolverWiki,
+        base: &Path,
+        data: &LinkData,
+    ) -> PathBuf {
+        let path = base.join(
+            data.uri_ref
+                .path_segments()
+                .filter(|s| !s.is_empty())
+                .map(LinkData::decode_uri)
+                .map(|s| wiki.link_spaces.to_name(&s).into_owned())
+                .collect::<PathBuf>(),
+        );
+        if data.is_path_dir() {
+            match self.directory_links {
+                DirectoryLinks::Index => {
+                    wiki.page_file(path.join(wiki.index.as_str()))
+                }
+                DirectoryLinks::Directory => path,
+            }
+        } else {
+            wiki.page_file(path)
+        }
+    }
+}
+
+#[cfg(feature = "html")]
+impl From<&crate::HtmlWikiConfig> for ResolverWiki {
+    fn from(config: &crate::HtmlWikiConfig) -> Self {
+        let wiki = Self::new(config.path.as_path())
+            .with_ext(config.ext.as_str())
+            .with_index(config.index.as_str())
+            .with_diary_rel_path(config.diary_rel_path.as_path())
+            .with_link_spaces(config.link_spaces);
+        match config.name.as_deref() {
+            Some(name) => wiki.with_name(name),
+            None => wiki,
+        }
+    }
+}
+
+#[cfg(feature = "html")]
+impl From<&crate::HtmlConfig> for LinkResolver {
+    fn from(config: &crate::HtmlConfig) -> Self {
+        config
+            .wikis
+            .iter()
+            .map(ResolverWiki::from)
+            .fold(Self::new(), Self::with_wiki)
+    }
+}
+
+/// Finds the region of the element within the page targeted by the anchor,
+/// or None if the anchor is empty or nothing within the page matches it
+///
+/// Each part of the anchor matches the text of a header or the name of a
+/// tag following the element matched by the part before it, so `#one#two`
+/// targets the first `two` found after `one`.
+pub fn find_anchor(page: &Page, anchor: &Anchor) -> Option<Region> {
+    let mut parts = anchor.into_iter().peekable();
+    let mut region = None;
+
+    for element in page.descendants() {
+        let part = match parts.peek() {
+            Some(part) => part.as_ref(),
+            None => break,
+        };
+
+        let is_match = match element.as_inner() {
+            Element::Block(BlockElement::Header(x)) => {
+                x.content.to_string().trim() == part
+            }
+            Element::Inline(InlineElement::Tags(x)) => {
+                x.into_iter().any(|tag| tag.as_str() == part)
+            }
+            _ => false,
+        };
+
+        if is_match {
+            region = Some(element.region());
+            parts.next();
+        }
+    }
+
+    match parts.peek() {
+        Some(_) => None,
+        None => region,
+    }
+}
+
+/// Produces the relative path represented by the decoded segments of the
+/// link's uri
+fn rel_path(data: &LinkData) -> PathBuf {
+    data.uri_ref
+        .path_segments()
+        .filter(|s| !s.is_empty())
+        .map(LinkData::decode_uri)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use crate::WikiUri;
+    use std::convert::TryFrom;
+
+    fn resolver() -> LinkResolver {
+        LinkResolver::new()
+            .with_wiki(ResolverWiki::new("/wiki"))
+            .with_wiki(
+                ResolverWiki::new("/notes")
+                    .with_name("notes")
+                    .with_ext(".md")
+                    .with_index("README"),
+            )
+    }
+
+    fn resolve(resolver: &LinkResolver, src: &str, link: &str) -> LinkTarget {
+        let page: Page = Language::from_vimwiki_str(link).parse().unwrap();
+        let link = collect_links(&page).remove(0).into_inner();
+        resolver.resolve(Path::new(src), &link).unwrap()
+    }
+
+    fn file(path: &str) -> LinkTarget {
+        LinkTarget::File {
+            path: PathBuf::from(path),
+            anchor: None,
+        }
+    }
+
+    #[test]
+    fn resolve_should_resolve_wiki_links_relative_to_the_page() {
+        let r = resolver();
+        assert_eq!(
+            resolve(&r, "/wiki/a/b.wiki", "[[c]]"),
+            file("/wiki/a/c.wiki")
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/a/b.wiki", "[[/c]]"),
+            file("/wiki/c.wiki")
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/a/b.wiki", "[[c.wiki]]"),
+            file("/wiki/a/c.wiki")
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "[[some%20page#one#two]]"),
+            LinkTarget::File {
+                path: PathBuf::from("/wiki/some page.wiki"),
+                anchor: Some(Anchor::new(vec!["one".into(), "two".into()])),
+            }
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "[[#top]]"),
+            LinkTarget::File {
+                path: PathBuf::from("/wiki/b.wiki"),
+                anchor: Some(Anchor::new(vec!["top".into()])),
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_should_use_the_layout_of_the_target_wiki() {
+        let r = resolver();
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "[[dir/]]"),
+            file("/wiki/dir/index.wiki")
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "[[wiki1:dir/]]"),
+            file("/notes/dir/README.md")
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "[[wn.notes:page]]"),
+            file("/notes/page.md")
+        );
+        assert_eq!(
+            resolve(&r, "/notes/b.md", "[[diary:2021-05-27]]"),
+            file("/notes/diary/2021-05-27.md")
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/a/b.wiki", "{{img.png}}"),
+            file("/wiki/a/img.png")
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/a/b.wiki", "[[https://example.com/x]]"),
+            LinkTarget::Uri(String::from("https://example.com/x"))
+        );
+
+        let r = resolver().with_directory_links(DirectoryLinks::Directory);
+        assert_eq!(resolve(&r, "/wiki/b.wiki", "[[dir/]]"), file("/wiki/dir"));
+
+        let link = Link::new_indexed_interwiki_link(
+            5,
+            WikiUri::try_from("page").unwrap(),
+            None,
+        );
+        assert_eq!(
+            r.resolve(Path::new("/wiki/b.wiki"), &link),
+            Err(ResolveError::MissingWikiWithIndex { index: 5 })
+        );
+    }
+
+    #[test]
+    fn resolve_should_apply_link_spaces_of_the_wiki_to_pages() {
+        let r = resolver();
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "[[some page]]"),
+            file("/wiki/some page.wiki")
+        );
+
+        let r = LinkResolver::new().with_wiki(
+            ResolverWiki::new("/wiki").with_link_spaces(LinkSpaces::Underscore),
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "[[some page]]"),
+            file("/wiki/some_page.wiki")
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "[[some%20page]]"),
+            file("/wiki/some_page.wiki")
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "[[some_page]]"),
+            file("/wiki/some_page.wiki")
+        );
+
+        // Files that are not pages keep their names
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", "{{some img.png}}"),
+            file("/wiki/some img.png")
+        );
+    }
+
+    #[test]
+    fn resolve_should_start_windows_paths_at_their_drive_or_share() {
+        const SEP: char = std::path::MAIN_SEPARATOR;
+        let r = resolver();
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", r"[[file:C:\My Notes\todo.txt]]"),
+            LinkTarget::File {
+                path: PathBuf::from(format!("C:{}", SEP))
+                    .join("My Notes")
+                    .join("todo.txt"),
+                anchor: None,
+            }
+        );
+        assert_eq!(
+            resolve(&r, "/wiki/b.wiki", r"{{\\server\share\img.png}}"),
+            LinkTarget::File {
+                path: PathBuf::from(format!("{0}{0}server{0}share{0}", SEP))
+                    .join("img.png"),
+                anchor: None,
+            }
+        );
+    }
+
+    #[test]
+    fn find_broken_links_should_report_links_to_missing_files() {
+        let dir = std::env::temp_dir().join("vimwiki_resolve_broken_links");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("exists.wiki"), "").unwrap();
+
+        let r = LinkResolver::new().with_wiki(ResolverWiki::new(&dir));
+        let page: Page =
+            Language::from_vimwiki_str("[[exists]] [[missing]] [[wiki3:x]]")
+                .parse()
+                .unwrap();
+        let broken = r.find_broken_links(&dir.join("index.wiki"), &page);
+
+        let links: Vec<&str> = broken.iter().map(|x| x.link.as_str()).collect();
+        assert_eq!(links, vec!["missing", "wiki3:x"]);
+        assert_eq!(broken[1].reason, "No wiki with index 3");
+    }
+
+    #[test]
+    fn find_anchor_should_match_headers_and_tags_in_order() {
+        let text = "= One =\n== Two ==\n= Three =\n== Two ==\n:tag:\n";
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let offset = |anchor: &str| {
+            find_anchor(&page, &Anchor::from_uri_fragment(anchor).unwrap())
+                .map(|x| x.offset())
+        };
+
+        assert_eq!(offset("#Two"), Some(8));
+        assert_eq!(offset("#Three#Two"), Some(28));
+        assert_eq!(offset("#tag"), Some(text.find(":tag:").unwrap()));
+        assert_eq!(offset("#Two#One"), None);
+        assert_eq!(offset("#Missing"), None);
+    }
+}
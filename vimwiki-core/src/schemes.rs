@@ -0,0 +1,215 @@
+use crate::uri::WikiUri;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+lazy_static! {
+    static ref SCHEME_HANDLERS: RwLock<HashMap<String, Arc<dyn SchemeHandler>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Schemes of raw links that are recognized without registering a handler
+pub const BUILTIN_SCHEMES: &[&str] =
+    &["http", "https", "ftp", "file", "local", "mailto"];
+
+/// Represents a handler for a custom uri scheme (e.g. `jira:PROJ-123`) that
+/// decides which raw links of the scheme are valid and how they are rendered
+pub trait SchemeHandler: Send + Sync {
+    /// Returns the scheme (without the trailing colon) that is handled
+    fn scheme(&self) -> &str;
+
+    /// Returns true if the uri is a valid raw link for the scheme, which
+    /// otherwise is left as plain text
+    fn is_valid(&self, _uri: &WikiUri<'_>) -> bool {
+        true
+    }
+
+    /// Produces the location that a rendered link points to
+    fn href(&self, uri: &WikiUri<'_>) -> String {
+        uri.to_string()
+    }
+
+    /// Produces the text displayed for a rendered link
+    fn label(&self, uri: &WikiUri<'_>) -> String {
+        uri.to_string()
+    }
+}
+
+impl fmt::Debug for dyn SchemeHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchemeHandler")
+            .field("scheme", &self.scheme())
+            .finish()
+    }
+}
+
+/// Registers a handler for raw links of its scheme, replacing any handler
+/// previously registered for the same scheme
+///
+/// Handlers are shared by every parse and render that follows, so they
+/// should be registered before any pages are parsed.
+pub fn register(handler: impl SchemeHandler + 'static) {
+    if let Ok(mut x) = SCHEME_HANDLERS.write() {
+        x.insert(handler.scheme().to_lowercase(), Arc::new(handler));
+    }
+}
+
+/// Removes the handler of the scheme, returning true if one was registered
+pub fn unregister(scheme: &str) -> bool {
+    SCHEME_HANDLERS
+        .write()
+        .map(|mut x| x.remove(&scheme.to_lowercase()).is_some())
+        .unwrap_or_default()
+}
+
+/// Finds the handler registered for the scheme
+pub fn find(scheme: &str) -> Option<Arc<dyn SchemeHandler>> {
+    SCHEME_HANDLERS
+        .read()
+        .ok()
+        .and_then(|x| x.get(&scheme.to_lowercase()).cloned())
+}
+
+/// Returns the schemes that have a registered handler in sorted order
+pub fn registered() -> Vec<String> {
+    let mut schemes: Vec<String> = SCHEME_HANDLERS
+        .read()
+        .map(|x| x.keys().cloned().collect())
+        .unwrap_or_default();
+    schemes.sort();
+    schemes
+}
+
+/// Returns true if the uri can be used as a raw link, either because its
+/// scheme is built-in or because the handler of its scheme accepts it
+pub fn is_valid_raw_link(uri: &WikiUri<'_>) -> bool {
+    match uri.scheme() {
+        Some(scheme) => match find(scheme) {
+            Some(handler) => handler.is_valid(uri),
+            None => BUILTIN_SCHEMES
+                .iter()
+                .any(|x| x.eq_ignore_ascii_case(scheme)),
+        },
+        None => false,
+    }
+}
+
+/// Represents a scheme handler defined by templates, suitable for loading
+/// from configuration
+///
+/// Templates can reference `{value}`, the portion of the uri following the
+/// scheme's colon, and `{uri}`, the entire uri. For example, a scheme of
+/// `jira` with an href of `https://jira.example.com/browse/{value}` turns
+/// `jira:PROJ-123` into a link to the issue.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemeTemplate {
+    /// Scheme (without the trailing colon) that is handled
+    pub scheme: String,
+
+    /// Template of the location that rendered links point to
+    pub href: String,
+
+    /// Template of the text displayed for rendered links, defaulting to the
+    /// entire uri
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl SchemeTemplate {
+    pub fn new(scheme: impl Into<String>, href: impl Into<String>) -> Self {
+        Self {
+            scheme: scheme.into(),
+            href: href.into(),
+            label: None,
+        }
+    }
+
+    /// Sets the template of the text displayed for rendered links
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Fills in the template using the given uri
+    fn fill(&self, template: &str, uri: &WikiUri<'_>) -> String {
+        let uri = uri.to_string();
+        let value = uri
+            .get(self.scheme.len() + 1..)
+            .unwrap_or_default()
+            .to_string();
+        template.replace("{value}", &value).replace("{uri}", &uri)
+    }
+}
+
+impl SchemeHandler for SchemeTemplate {
+    fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Requires some value to follow the scheme's colon
+    fn is_valid(&self, uri: &WikiUri<'_>) -> bool {
+        uri.to_string().len() > self.scheme.len() + 1
+    }
+
+    fn href(&self, uri: &WikiUri<'_>) -> String {
+        self.fill(&self.href, uri)
+    }
+
+    fn label(&self, uri: &WikiUri<'_>) -> String {
+        match self.label.as_deref() {
+            Some(label) => self.fill(label, uri),
+            None => uri.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    struct ManPages;
+
+    impl SchemeHandler for ManPages {
+        fn scheme(&self) -> &str {
+            "man"
+        }
+
+        fn is_valid(&self, uri: &WikiUri<'_>) -> bool {
+            uri.path().ends_with(')')
+        }
+    }
+
+    #[test]
+    fn is_valid_raw_link_should_defer_to_registered_handlers() {
+        let uri = |s: &'static str| WikiUri::try_from(s).unwrap();
+
+        assert!(is_valid_raw_link(&uri("https://example.com")));
+        assert!(!is_valid_raw_link(&uri("man:ls(1)")));
+
+        register(ManPages);
+        assert!(is_valid_raw_link(&uri("man:ls(1)")));
+        assert!(!is_valid_raw_link(&uri("man:ls")));
+        assert!(registered().contains(&String::from("man")));
+
+        assert!(unregister("man"));
+        assert!(!is_valid_raw_link(&uri("man:ls(1)")));
+    }
+
+    #[test]
+    fn scheme_template_should_fill_in_value_and_uri() {
+        let template =
+            SchemeTemplate::new("jira", "https://jira.example.com/{value}")
+                .with_label("{value} ({uri})");
+        let uri = WikiUri::try_from("jira:PROJ-123").unwrap();
+
+        assert!(template.is_valid(&uri));
+        assert_eq!(template.href(&uri), "https://jira.example.com/PROJ-123");
+        assert_eq!(template.label(&uri), "PROJ-123 (jira:PROJ-123)");
+        assert!(!template.is_valid(&WikiUri::try_from("jira:").unwrap()));
+    }
+}
@@ -0,0 +1,852 @@
+use crate::{
+    schemes::SchemeTemplate, HtmlConfig, HtmlWikiConfig, ParserConfig,
+    VimwikiConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs, io,
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+};
+
+/// Name of the file that settings are loaded from at each layer
+pub const SETTINGS_FILE_NAME: &str = "vimwiki.toml";
+
+/// Prefix of environment variables that override settings, where the rest
+/// of the variable is the section and key separated by `__` (e.g.
+/// `VIMWIKI_SERVER__PORT=9000` or `VIMWIKI_HTML__CODE__THEME=base16`)
+pub const ENV_PREFIX: &str = "VIMWIKI_";
+
+/// Separator between the sections and key of an environment variable
+const ENV_SEPARATOR: &str = "__";
+
+/// Source reported for problems with settings from environment variables
+const ENV_SOURCE: &str = "environment";
+
+/// Represents settings shared by the vimwiki tools, loaded from layers of
+/// `vimwiki.toml` files where later layers override earlier ones
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Settings {
+    /// Wikis to load, in addition to any defined within the html settings
+    #[serde(default)]
+    pub wikis: Vec<HtmlWikiConfig>,
+
+    /// Settings that change what is recognized when parsing, such as
+    /// custom keywords
+    #[serde(default)]
+    pub syntax: ParserConfig,
+
+    /// Settings used when rendering pages as HTML
+    #[serde(default)]
+    pub html: HtmlConfig,
+
+    /// Settings used when formatting pages as vimwiki
+    #[serde(default)]
+    pub format: VimwikiConfig,
+
+    /// Settings that apply to output reported by the tools
+    #[serde(default)]
+    pub output: OutputSettings,
+
+    /// Settings that apply to linting and checking pages
+    #[serde(default)]
+    pub lint: LintSettings,
+
+    /// Settings that apply to running the server
+    #[serde(default)]
+    pub server: ServerSettings,
+}
+
+/// Represents settings that apply to output reported by the tools
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputSettings {
+    /// Format of reported information (e.g. `text` or `json`)
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// Represents settings that apply to linting and checking pages, where any
+/// setting not provided falls back to the default of the tool
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintSettings {
+    /// Name of the page from which all other pages should be reachable
+    #[serde(default)]
+    pub index: Option<String>,
+
+    /// Pages with fewer bytes of content than this are reported as stubs
+    #[serde(default)]
+    pub stub_threshold: Option<usize>,
+
+    /// Whether or not links whose targets do not exist are reported
+    #[serde(default)]
+    pub links: Option<bool>,
+
+    /// Whether or not files that are not formatted are reported
+    #[serde(default)]
+    pub format: Option<bool>,
+}
+
+/// Represents settings that apply to running the server, where any setting
+/// not provided falls back to the default of the server
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerSettings {
+    /// Host/IP address of the server in http mode
+    #[serde(default)]
+    pub host: Option<IpAddr>,
+
+    /// Port of the server in http mode
+    #[serde(default)]
+    pub port: Option<u16>,
+
+    /// Whether or not the /graphiql endpoint is hosted
+    #[serde(default)]
+    pub graphiql: Option<bool>,
+
+    /// Directory where cache information for the server is stored
+    #[serde(default)]
+    pub cache: Option<PathBuf>,
+
+    /// Whether or not wikis loaded from vim/neovim are merged with the
+    /// wikis that are configured
+    #[serde(default)]
+    pub merge: Option<bool>,
+
+    /// Path of the unix socket the server listens on in socket mode
+    #[serde(default)]
+    pub socket: Option<PathBuf>,
+}
+
+impl ServerSettings {
+    /// Returns the path of the unix socket the server listens on in socket
+    /// mode, falling back to [`default_socket_path`] for the workspace
+    /// containing the given directory
+    pub fn socket_path(&self, dir: &Path) -> PathBuf {
+        self.socket
+            .clone()
+            .unwrap_or_else(|| default_socket_path(&workspace_dir(dir)))
+    }
+}
+
+/// Represents a problem found while validating settings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SettingsDiagnostic {
+    /// Where the setting came from, either the path of a settings file or
+    /// `environment`
+    pub source: String,
+
+    /// Dotted key of the setting (e.g. `server.port` or `wikis[0].path`), or
+    /// empty if the problem is not tied to a single setting
+    pub key: String,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for SettingsDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.key.is_empty() {
+            write!(f, "{}: {}", self.source, self.message)
+        } else {
+            write!(f, "{}: {}: {}", self.source, self.key, self.message)
+        }
+    }
+}
+
+/// Represents settings that failed validation, containing every problem
+/// that was found
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SettingsError {
+    pub diagnostics: Vec<SettingsDiagnostic>,
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid settings")?;
+        for diagnostic in self.diagnostics.iter() {
+            write!(f, "\n  {}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<SettingsError> for io::Error {
+    fn from(x: SettingsError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, x)
+    }
+}
+
+impl Settings {
+    /// Loads settings from the system, user, and project layers that exist,
+    /// where the project layer is the closest `vimwiki.toml` found within
+    /// the given directory or its ancestors, followed by overrides from
+    /// environment variables
+    ///
+    /// Fails with a [`SettingsError`] if any layer has unknown keys or
+    /// values of the wrong type, or if configured paths do not exist.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut layers = read_layers(layer_paths(dir))?;
+        if let Some(layer) = env_layer(std::env::vars()) {
+            layers.push((String::from(ENV_SOURCE), layer));
+        }
+        Ok(Self::from_sourced_layers(layers)?)
+    }
+
+    /// Loads settings from the files at the given paths, where each file
+    /// overrides the settings of the files before it
+    ///
+    /// Fails with a [`SettingsError`] if any file has unknown keys or values
+    /// of the wrong type, or if configured paths do not exist.
+    pub fn load_from<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+    ) -> io::Result<Self> {
+        Ok(Self::from_sourced_layers(read_layers(paths)?)?)
+    }
+
+    /// Produces settings from the given layers, each paired with its source,
+    /// validating every layer and reporting all problems found at once
+    pub fn from_sourced_layers(
+        layers: Vec<(String, toml::Value)>,
+    ) -> Result<Self, SettingsError> {
+        let schema = schema();
+        let mut diagnostics = Vec::new();
+        for (source, layer) in layers.iter() {
+            find_unknown_keys(&schema, layer, "", source, &mut diagnostics);
+            if let Err(x) = layer.clone().try_into::<Settings>() {
+                diagnostics.push(SettingsDiagnostic {
+                    source: source.to_string(),
+                    key: String::new(),
+                    message: x.to_string(),
+                });
+            }
+        }
+
+        if !diagnostics.is_empty() {
+            return Err(SettingsError { diagnostics });
+        }
+
+        let source_of = |section: &str| {
+            layers
+                .iter()
+                .rev()
+                .find(|(_, layer)| layer.get(section).is_some())
+                .map(|(source, _)| source.to_string())
+                .unwrap_or_default()
+        };
+
+        let mut raw = toml::Value::Table(Default::default());
+        for (_, layer) in layers.iter() {
+            merge_values(&mut raw, layer.clone());
+        }
+
+        let settings: Settings =
+            raw.clone().try_into().map_err(|x: toml::de::Error| {
+                SettingsError {
+                    diagnostics: vec![SettingsDiagnostic {
+                        source: layers
+                            .iter()
+                            .map(|(source, _)| source.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        key: String::new(),
+                        message: x.to_string(),
+                    }],
+                }
+            })?;
+
+        for (section, key, path) in settings.configured_paths(&raw) {
+            if !path.exists() {
+                diagnostics.push(SettingsDiagnostic {
+                    source: source_of(section),
+                    key,
+                    message: format!(
+                        "Path {} does not exist",
+                        path.to_string_lossy()
+                    ),
+                });
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(settings)
+        } else {
+            Err(SettingsError { diagnostics })
+        }
+    }
+
+    /// Returns the paths that are expected to exist, each with the section
+    /// and dotted key of the setting that configures it
+    fn configured_paths(
+        &self,
+        raw: &toml::Value,
+    ) -> Vec<(&'static str, String, PathBuf)> {
+        let mut paths = Vec::new();
+        for (idx, wiki) in self.wikis.iter().enumerate() {
+            let key = format!("wikis[{}].path", idx);
+            paths.push(("wikis", key, wiki.path.to_path_buf()));
+        }
+
+        for (idx, wiki) in self.html.wikis.iter().enumerate() {
+            let key = format!("html.wikis[{}].path", idx);
+            paths.push(("html", key, wiki.path.to_path_buf()));
+        }
+
+        if let Some(dir) = self.html.code.theme_dir.as_ref() {
+            let key = String::from("html.code.theme_dir");
+            paths.push(("html", key, dir.to_path_buf()));
+        }
+
+        if let Some(dir) = self.html.code.syntax_dir.as_ref() {
+            let key = String::from("html.code.syntax_dir");
+            paths.push(("html", key, dir.to_path_buf()));
+        }
+
+        // The template directory always has a default, which is only
+        // expected to exist if it was configured
+        let has_template_dir = raw
+            .get("html")
+            .and_then(|x| x.get("template"))
+            .and_then(|x| x.get("dir"))
+            .is_some();
+        if has_template_dir {
+            let key = String::from("html.template.dir");
+            paths.push(("html", key, self.html.template.dir.to_path_buf()));
+        }
+
+        paths
+    }
+
+    /// Produces settings from the given layers, where tables are merged key
+    /// by key and any other value of a later layer replaces the value of an
+    /// earlier layer
+    pub fn from_layers(
+        layers: impl IntoIterator<Item = toml::Value>,
+    ) -> io::Result<Self> {
+        let mut value = toml::Value::Table(Default::default());
+        for layer in layers {
+            merge_values(&mut value, layer);
+        }
+        value.try_into().map_err(|x: toml::de::Error| {
+            io::Error::new(io::ErrorKind::InvalidData, x)
+        })
+    }
+
+    /// Produces a config for rendering HTML from the html settings, where
+    /// the wikis of the settings follow those defined within the html
+    /// settings
+    pub fn to_html_config(&self) -> HtmlConfig {
+        let mut config = self.html.clone();
+        config.wikis.extend(self.wikis.iter().cloned());
+        config
+    }
+}
+
+/// Produces a layer of settings from the environment variables that start
+/// with [`ENV_PREFIX`] and name a section and key, returning none if there
+/// are no such variables
+///
+/// Each value is read as a TOML value if possible (e.g. `9000`, `true`, or
+/// `["a", "b"]`) and as a string otherwise.
+pub fn env_layer(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Option<toml::Value> {
+    let mut layer = toml::value::Table::new();
+    for (name, value) in vars {
+        let keys: Vec<String> = match name.strip_prefix(ENV_PREFIX) {
+            Some(rest) if rest.contains(ENV_SEPARATOR) => {
+                rest.split(ENV_SEPARATOR).map(str::to_lowercase).collect()
+            }
+            _ => continue,
+        };
+
+        let value =
+            toml::from_str::<toml::value::Table>(&format!("value = {}", value))
+                .ok()
+                .and_then(|mut x| x.remove("value"))
+                .unwrap_or(toml::Value::String(value));
+
+        let (last, sections) = match keys.split_last() {
+            Some(x) => x,
+            None => continue,
+        };
+        let mut table = &mut layer;
+        for key in sections {
+            let entry = table
+                .entry(key.to_string())
+                .or_insert_with(|| toml::Value::Table(Default::default()));
+            if !entry.is_table() {
+                *entry = toml::Value::Table(Default::default());
+            }
+            table = match entry {
+                toml::Value::Table(x) => x,
+                _ => unreachable!(),
+            };
+        }
+        table.insert(last.to_string(), value);
+    }
+
+    if layer.is_empty() {
+        None
+    } else {
+        Some(toml::Value::Table(layer))
+    }
+}
+
+/// Returns the path of the system settings file
+pub fn system_path() -> Option<PathBuf> {
+    if cfg!(unix) {
+        Some(Path::new("/etc/vimwiki").join(SETTINGS_FILE_NAME))
+    } else {
+        None
+    }
+}
+
+/// Returns the path of the settings file of the current user
+pub fn user_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("vimwiki").join(SETTINGS_FILE_NAME))
+}
+
+/// Returns the path of the closest settings file within the given directory
+/// or its ancestors
+pub fn project_path(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(SETTINGS_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// Returns the paths of the settings files that exist, ordered from the
+/// system layer to the project layer for the given directory
+pub fn layer_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    let candidates = vec![system_path(), user_path(), project_path(dir)];
+    for path in candidates.into_iter().flatten() {
+        if path.is_file() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Returns the workspace containing the given directory, which is the
+/// directory of the closest settings file or otherwise the directory itself
+pub fn workspace_dir(dir: &Path) -> PathBuf {
+    project_path(dir)
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| dir.to_path_buf())
+}
+
+/// Returns the path of the unix socket shared by every tool working within
+/// the given workspace, placed in the runtime directory of the user (or the
+/// temporary directory if there is none) and named after the workspace
+pub fn default_socket_path(workspace: &Path) -> PathBuf {
+    // FNV-1a, which unlike the std hasher is stable across builds so that
+    // separately-built tools agree on the path
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in workspace.to_string_lossy().bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(format!("vimwiki-{:016x}.sock", hash))
+}
+
+/// Returns the path of the lock file held by the server listening on the
+/// unix socket at the given path, which contains the id of its process
+pub fn socket_lock_path(socket: &Path) -> PathBuf {
+    socket.with_extension("lock")
+}
+
+/// Reads and parses the files at the given paths, pairing each with its path
+fn read_layers<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+) -> io::Result<Vec<(String, toml::Value)>> {
+    let mut layers = Vec::new();
+    for path in paths {
+        let path = path.as_ref();
+        let source = path.to_string_lossy().to_string();
+        let text = fs::read_to_string(path)?;
+        match toml::from_str(&text) {
+            Ok(layer) => layers.push((source, layer)),
+            Err(x) => {
+                return Err(SettingsError {
+                    diagnostics: vec![SettingsDiagnostic {
+                        source,
+                        key: String::new(),
+                        message: x.to_string(),
+                    }],
+                }
+                .into())
+            }
+        }
+    }
+    Ok(layers)
+}
+
+/// Produces settings where every optional setting is filled in and every
+/// list has one entry, so that serializing them reveals every known key
+fn schema() -> toml::Value {
+    let wiki = HtmlWikiConfig {
+        name: Some(String::new()),
+        ..Default::default()
+    };
+
+    let mut settings = Settings {
+        wikis: vec![wiki.clone()],
+        output: OutputSettings {
+            format: Some(String::new()),
+        },
+        lint: LintSettings {
+            index: Some(String::new()),
+            stub_threshold: Some(0),
+            links: Some(true),
+            format: Some(true),
+        },
+        server: ServerSettings {
+            host: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            port: Some(0),
+            graphiql: Some(true),
+            cache: Some(PathBuf::new()),
+            merge: Some(true),
+            socket: Some(PathBuf::new()),
+        },
+        ..Default::default()
+    };
+    settings.html.wikis = vec![wiki];
+    settings.html.link.schemes =
+        vec![SchemeTemplate::new("", "").with_label("")];
+    settings.html.code.theme_dir = Some(PathBuf::new());
+    settings.html.code.syntax_dir = Some(PathBuf::new());
+
+    // Classes are keyed by keyword, so any key is accepted
+    settings.html.keyword.classes.clear();
+
+    toml::Value::try_from(settings)
+        .unwrap_or_else(|_| toml::Value::Table(Default::default()))
+}
+
+/// Reports the keys of the value that are not within the schema, where
+/// empty tables within the schema accept any key
+fn find_unknown_keys(
+    schema: &toml::Value,
+    value: &toml::Value,
+    key: &str,
+    source: &str,
+    diagnostics: &mut Vec<SettingsDiagnostic>,
+) {
+    match (schema, value) {
+        (toml::Value::Table(schema), toml::Value::Table(value))
+            if !schema.is_empty() =>
+        {
+            for (name, value) in value.iter() {
+                let full_key = if key.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}.{}", key, name)
+                };
+
+                match schema.get(name) {
+                    Some(schema) => find_unknown_keys(
+                        schema,
+                        value,
+                        &full_key,
+                        source,
+                        diagnostics,
+                    ),
+                    None => diagnostics.push(SettingsDiagnostic {
+                        source: source.to_string(),
+                        key: full_key,
+                        message: match suggest(name, schema.keys()) {
+                            Some(x) => {
+                                format!("Unknown key, did you mean `{}`?", x)
+                            }
+                            None => String::from("Unknown key"),
+                        },
+                    }),
+                }
+            }
+        }
+        (toml::Value::Array(schema), toml::Value::Array(value)) => {
+            if let Some(schema) = schema.first() {
+                for (idx, value) in value.iter().enumerate() {
+                    let full_key = format!("{}[{}]", key, idx);
+                    find_unknown_keys(
+                        schema,
+                        value,
+                        &full_key,
+                        source,
+                        diagnostics,
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the known key closest to the given key if it is close enough to
+/// likely be a typo
+fn suggest<'a>(
+    key: &str,
+    known: impl IntoIterator<Item = &'a String>,
+) -> Option<&'a str> {
+    let max_distance = std::cmp::max(2, key.chars().count() / 3);
+    known
+        .into_iter()
+        .map(|x| (edit_distance(key, x), x))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, x)| x.as_str())
+}
+
+/// Returns the number of single character insertions, deletions, and
+/// substitutions needed to turn one string into another
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn merge_values(base: &mut toml::Value, layer: toml::Value) {
+    match (base, layer) {
+        (toml::Value::Table(base), toml::Value::Table(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(text: &str) -> toml::Value {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn from_layers_should_merge_tables_and_replace_other_values() {
+        let settings = Settings::from_layers(vec![
+            layer(
+                r#"
+                [[wikis]]
+                path = "/system/wiki"
+
+                [lint]
+                stub_threshold = 50
+                links = false
+
+                [server]
+                port = 9000
+                "#,
+            ),
+            layer(
+                r#"
+                [[wikis]]
+                path = "/project/wiki"
+
+                [lint]
+                stub_threshold = 200
+
+                [output]
+                format = "json"
+                "#,
+            ),
+        ])
+        .unwrap();
+
+        let paths: Vec<_> = settings.wikis.iter().map(|x| &x.path).collect();
+        assert_eq!(paths, [Path::new("/project/wiki")]);
+        assert_eq!(settings.lint.stub_threshold, Some(200));
+        assert_eq!(settings.lint.links, Some(false));
+        assert_eq!(settings.server.port, Some(9000));
+        assert_eq!(settings.output.format.as_deref(), Some("json"));
+    }
+
+    #[test]
+    fn from_layers_should_fail_if_a_value_has_the_wrong_type() {
+        let err = Settings::from_layers(vec![layer("[server]\nport = \"x\"")])
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    fn sourced(source: &str, text: &str) -> Vec<(String, toml::Value)> {
+        vec![(source.to_string(), layer(text))]
+    }
+
+    #[test]
+    fn env_layer_should_read_sections_and_typed_values() {
+        let layer = env_layer(vec![
+            (String::from("VIMWIKI_SERVER__PORT"), String::from("9000")),
+            (String::from("VIMWIKI_OUTPUT__FORMAT"), String::from("json")),
+            (String::from("VIMWIKI_HTML__CODE__THEME"), String::from("x")),
+            (String::from("VIMWIKI_PAGE_PATH"), String::from("ignored")),
+            (String::from("HOME"), String::from("ignored")),
+        ])
+        .unwrap();
+
+        let settings =
+            Settings::from_sourced_layers(vec![(String::new(), layer)])
+                .unwrap();
+        assert_eq!(settings.server.port, Some(9000));
+        assert_eq!(settings.output.format.as_deref(), Some("json"));
+        assert_eq!(settings.html.code.theme, "x");
+
+        assert_eq!(
+            env_layer(vec![(String::from("VIMWIKI_PAGE_PATH"), String::new())]),
+            None
+        );
+    }
+
+    #[test]
+    fn from_sourced_layers_should_report_unknown_keys_with_suggestions() {
+        let err = Settings::from_sourced_layers(sourced(
+            "vimwiki.toml",
+            r#"
+            [lint]
+            stub_treshold = 10
+
+            [[html.link.schemes]]
+            scheme = "jira"
+            href = "{value}"
+            lable = "x"
+
+            [html.keyword.classes]
+            anything = "goes"
+
+            [bogus]
+            "#,
+        ))
+        .unwrap_err();
+
+        let keys: Vec<_> =
+            err.diagnostics.iter().map(|x| x.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["bogus", "html.link.schemes[0].lable", "lint.stub_treshold"]
+        );
+        assert_eq!(err.diagnostics[0].message, "Unknown key");
+        assert_eq!(
+            err.diagnostics[2].to_string(),
+            "vimwiki.toml: lint.stub_treshold: Unknown key, did you mean \
+            `stub_threshold`?"
+        );
+    }
+
+    #[test]
+    fn from_sourced_layers_should_report_the_source_of_wrong_types() {
+        let mut layers = sourced("a.toml", "[server]\nport = 1");
+        layers
+            .push((String::from(ENV_SOURCE), layer("[server]\nport = \"x\"")));
+
+        let err = Settings::from_sourced_layers(layers).unwrap_err();
+        assert_eq!(err.diagnostics.len(), 1);
+        assert_eq!(err.diagnostics[0].source, ENV_SOURCE);
+    }
+
+    #[test]
+    fn from_sourced_layers_should_report_paths_that_do_not_exist() {
+        let dir = std::env::temp_dir();
+        let err = Settings::from_sourced_layers(sourced(
+            "user.toml",
+            &format!(
+                r#"
+                [[wikis]]
+                path = {:?}
+
+                [[wikis]]
+                path = "/missing/vimwiki"
+
+                [html.code]
+                theme_dir = "/missing/themes"
+                "#,
+                dir.to_string_lossy()
+            ),
+        ))
+        .unwrap_err();
+
+        let keys: Vec<_> =
+            err.diagnostics.iter().map(|x| x.key.as_str()).collect();
+        assert_eq!(keys, ["wikis[1].path", "html.code.theme_dir"]);
+        assert_eq!(err.diagnostics[0].source, "user.toml");
+        assert_eq!(
+            err.diagnostics[0].message,
+            "Path /missing/vimwiki does not exist"
+        );
+    }
+
+    #[test]
+    fn edit_distance_should_count_single_character_edits() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("port", "port"), 0);
+    }
+
+    #[test]
+    fn project_path_should_find_the_closest_settings_file() {
+        let root = std::env::temp_dir().join("vimwiki-settings-project");
+        let nested = root.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join(SETTINGS_FILE_NAME), "").unwrap();
+
+        assert_eq!(project_path(&nested), Some(root.join(SETTINGS_FILE_NAME)));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn socket_path_should_be_shared_within_a_workspace() {
+        let root = std::env::temp_dir().join("vimwiki-settings-socket");
+        let nested = root.join("a");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join(SETTINGS_FILE_NAME), "").unwrap();
+
+        let settings = ServerSettings::default();
+        let socket = settings.socket_path(&root);
+        assert_eq!(settings.socket_path(&nested), socket);
+        assert_ne!(default_socket_path(&nested), socket);
+        assert_eq!(socket_lock_path(&socket).extension().unwrap(), "lock");
+        fs::remove_dir_all(&root).unwrap();
+
+        let settings = ServerSettings {
+            socket: Some(PathBuf::from("/tmp/wiki.sock")),
+            ..Default::default()
+        };
+        assert_eq!(settings.socket_path(&nested), Path::new("/tmp/wiki.sock"));
+    }
+
+    #[test]
+    fn to_html_config_should_include_wikis_of_both_sections() {
+        let settings = Settings::from_layers(vec![layer(
+            r#"
+            [[wikis]]
+            path = "/b"
+
+            [[html.wikis]]
+            path = "/a"
+            "#,
+        )])
+        .unwrap();
+
+        let config = settings.to_html_config();
+        let paths: Vec<_> = config.wikis.iter().map(|x| &x.path).collect();
+        assert_eq!(paths, [Path::new("/a"), Path::new("/b")]);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::date::WikiDate;
+use crate::{hover::PageSummary, Page, PlainConfig, ToPlainText};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Represents a single page listed within a sitemap
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SitemapEntry {
+    url: String,
+    last_modified: Option<WikiDate>,
+}
+
+/// Represents a sitemap (sitemap.xml) of the pages of a statically exported
+/// wiki, letting search engines discover every page
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sitemap {
+    base_url: String,
+    entries: Vec<SitemapEntry>,
+}
+
+impl Sitemap {
+    /// Creates an empty sitemap whose pages are located relative to the
+    /// given base url (e.g. `https://example.com/wiki`)
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+
+        Self {
+            base_url,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns total pages within the sitemap
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the sitemap has no pages
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds the page at the url relative to the base url (e.g.
+    /// `diary/2021-04-05.html`), optionally with the date it last changed
+    pub fn add_page(&mut self, url: &str, last_modified: Option<WikiDate>) {
+        self.entries.push(SitemapEntry {
+            url: format!("{}{}", self.base_url, url.trim_start_matches('/')),
+            last_modified,
+        });
+    }
+
+    /// Produces the sitemap in the sitemaps.org xml format
+    pub fn to_xml_string(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        ));
+
+        for entry in self.entries.iter() {
+            let _ = write!(xml, "  <url><loc>{}</loc>", escape_xml(&entry.url));
+            if let Some(date) = entry.last_modified {
+                let _ = write!(
+                    xml,
+                    "<lastmod>{}</lastmod>",
+                    date.format("%Y-%m-%d")
+                );
+            }
+            xml.push_str("</url>\n");
+        }
+
+        xml.push_str("</urlset>\n");
+        xml
+    }
+}
+
+/// Escapes the characters of text that are special within xml
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Represents a single page within a search index
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchDocument {
+    /// Name of the page relative to its wiki (e.g. `projects/todo`)
+    pub id: String,
+
+    /// Url of the page's html relative to the root of the exported wiki
+    pub url: String,
+
+    /// Title of the page from its `%title` placeholder, otherwise the text
+    /// of its first header, otherwise its name
+    pub title: String,
+
+    /// Plain text of the page
+    pub body: String,
+}
+
+/// Represents a search index of the pages of a statically exported wiki,
+/// which serializes as a list of documents with `id`, `url`, `title`, and
+/// `body` fields that client-side search libraries such as lunr or
+/// elasticlunr can index in the browser
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SearchIndex {
+    documents: Vec<SearchDocument>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the documents of the index in the order they were added
+    pub fn documents(&self) -> &[SearchDocument] {
+        &self.documents
+    }
+
+    /// Returns total documents within the index
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Returns true if the index has no documents
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Adds the page with the given name whose html is found at the url,
+    /// using the plain text renderer to produce the searchable body
+    pub fn add_page(&mut self, name: &str, url: &str, page: &Page) {
+        let title = PageSummary::from_page(page)
+            .title
+            .unwrap_or_else(|| name.to_string());
+        let body = page
+            .to_plain_text(PlainConfig::default())
+            .unwrap_or_default();
+
+        self.documents.push(SearchDocument {
+            id: name.to_string(),
+            url: url.to_string(),
+            title,
+            body: body.trim().to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    #[test]
+    fn sitemap_should_list_escaped_urls_relative_to_base_url() {
+        let mut sitemap = Sitemap::new("https://example.com/wiki");
+        sitemap.add_page("index.html", WikiDate::from_ymd_opt(2021, 4, 5));
+        sitemap.add_page("/a&b.html", None);
+
+        assert_eq!(
+            sitemap.to_xml_string(),
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+                "  <url><loc>https://example.com/wiki/index.html</loc>",
+                "<lastmod>2021-04-05</lastmod></url>\n",
+                "  <url><loc>https://example.com/wiki/a&amp;b.html</loc></url>\n",
+                "</urlset>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn search_index_should_use_title_and_plain_text_of_pages() {
+        let mut index = SearchIndex::new();
+        let page: Page =
+            Language::from_vimwiki_str("= Hello =\nsome *bold* text\n")
+                .parse()
+                .unwrap();
+        index.add_page("projects/hello", "projects/hello.html", &page);
+
+        let page: Page =
+            Language::from_vimwiki_str("no header").parse().unwrap();
+        index.add_page("other", "other.html", &page);
+
+        let documents = index.documents();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0].id, "projects/hello");
+        assert_eq!(documents[0].url, "projects/hello.html");
+        assert_eq!(documents[0].title, "Hello");
+        assert!(documents[0].body.contains("some bold text"));
+        assert_eq!(documents[1].title, "other");
+        assert_eq!(documents[1].body, "no header");
+    }
+}
@@ -0,0 +1,424 @@
+use crate::{tree::PageTree, Language, Page, ParseError};
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable that decides how snapshots are updated, where
+/// `always` (or `1`) overwrites snapshots that differ, `no` never writes
+/// snapshots, and anything else only writes snapshots that do not exist
+pub const UPDATE_ENV_VAR: &str = "VIMWIKI_UPDATE_SNAPSHOTS";
+
+/// Extension of files within a corpus
+pub const CORPUS_FILE_EXT: &str = "wiki";
+
+/// Extension of snapshot files
+pub const SNAPSHOT_EXT: &str = "snap";
+
+/// Extension of files holding a snapshot that differs from the stored one,
+/// written next to the stored snapshot for review
+pub const NEW_SNAPSHOT_EXT: &str = "snap.new";
+
+/// Maximum characters of the text of an element shown within a snapshot
+const MAX_EXCERPT_LEN: usize = 40;
+
+/// Renders the element structure of the vimwiki text as a snapshot, where
+/// each element is a line with its kind, region, and the start of its text,
+/// indented beneath its parent
+pub fn render(text: &str) -> String {
+    let page: Page = match Language::from_vimwiki_str(text).parse() {
+        Ok(page) => page,
+        Err(x) => {
+            let x: ParseError = x;
+            return format!("error: {}\n", x);
+        }
+    };
+
+    let tree = PageTree::new("", &page);
+    let mut out = String::new();
+    let mut stack: Vec<(usize, usize)> =
+        tree.roots.iter().rev().map(|x| (*x, 0)).collect();
+    while let Some((position, depth)) = stack.pop() {
+        let node = &tree.nodes[position];
+        let start = node.region.offset();
+        let end = start + node.region.len();
+        out.push_str(&format!(
+            "{}{} {}..{} {}\n",
+            "  ".repeat(depth),
+            node.kind,
+            start,
+            end,
+            excerpt(text.get(start..end).unwrap_or_default()),
+        ));
+        stack.extend(node.children.iter().rev().map(|x| (*x, depth + 1)));
+    }
+
+    out
+}
+
+/// Produces a quoted excerpt of the first line of the text
+fn excerpt(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > MAX_EXCERPT_LEN {
+        let line: String = line.chars().take(MAX_EXCERPT_LEN).collect();
+        format!("{:?}...", line)
+    } else {
+        format!("{:?}", line)
+    }
+}
+
+/// Produces a reviewable diff of the lines of two snapshots, showing the
+/// lines between their common start and end as removed (`-`) and added
+/// (`+`), or None if they are the same
+pub fn diff(old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut out = format!(
+        "@@ line {} (-{} +{}) @@\n",
+        prefix + 1,
+        old.len() - prefix - suffix,
+        new.len() - prefix - suffix
+    );
+    for line in &old[prefix..old.len() - suffix] {
+        out.push_str(&format!("-{}\n", line));
+    }
+    for line in &new[prefix..new.len() - suffix] {
+        out.push_str(&format!("+{}\n", line));
+    }
+    Some(out)
+}
+
+/// Represents when snapshots are written to disk
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum UpdateMode {
+    /// Never write snapshots, failing on any that are missing
+    No,
+
+    /// Write snapshots that do not exist yet, failing on any that differ
+    #[default]
+    New,
+
+    /// Write every snapshot, overwriting any that differ
+    Always,
+}
+
+impl UpdateMode {
+    /// Loads the mode from [`UPDATE_ENV_VAR`]
+    pub fn from_env() -> Self {
+        match env::var(UPDATE_ENV_VAR).as_deref() {
+            Ok("always") | Ok("1") => Self::Always,
+            Ok("no") | Ok("0") => Self::No,
+            _ => Self::New,
+        }
+    }
+}
+
+/// Represents what happened when checking the snapshot of a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    /// Snapshot matched the stored one
+    Matched,
+
+    /// Snapshot did not exist and was written
+    Created,
+
+    /// Snapshot did not exist and was not written
+    Missing,
+
+    /// Snapshot differed from the stored one, which was overwritten
+    Updated { diff: String },
+
+    /// Snapshot differed from the stored one, and was written next to it
+    /// for review
+    Mismatched { diff: String },
+}
+
+impl SnapshotOutcome {
+    /// Returns true if the snapshot needs attention
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Missing | Self::Mismatched { .. })
+    }
+}
+
+/// Represents the result of checking the snapshot of a single file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotResult {
+    /// Path to the file within the corpus
+    pub file: PathBuf,
+
+    /// Path to the stored snapshot of the file
+    pub snapshot: PathBuf,
+
+    /// What happened when checking the snapshot
+    pub outcome: SnapshotOutcome,
+}
+
+impl fmt::Display for SnapshotResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.outcome {
+            SnapshotOutcome::Matched => write!(f, "{:?}: matched", self.file),
+            SnapshotOutcome::Created => {
+                write!(f, "{:?}: created {:?}", self.file, self.snapshot)
+            }
+            SnapshotOutcome::Missing => {
+                write!(f, "{:?}: missing {:?}", self.file, self.snapshot)
+            }
+            SnapshotOutcome::Updated { diff } => {
+                write!(
+                    f,
+                    "{:?}: updated {:?}\n{}",
+                    self.file, self.snapshot, diff
+                )
+            }
+            SnapshotOutcome::Mismatched { diff } => write!(
+                f,
+                "{:?}: differs from {:?}\n{}",
+                self.file, self.snapshot, diff
+            ),
+        }
+    }
+}
+
+/// Represents the results of checking every file of a corpus
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CorpusReport {
+    /// Result of each file, in order of path
+    pub results: Vec<SnapshotResult>,
+}
+
+impl CorpusReport {
+    /// Returns the results that need attention
+    pub fn failures(&self) -> Vec<&SnapshotResult> {
+        self.results
+            .iter()
+            .filter(|x| x.outcome.is_failure())
+            .collect()
+    }
+
+    /// Panics with the diff of each snapshot that needs attention
+    pub fn assert_ok(&self) {
+        let failures = self.failures();
+        if !failures.is_empty() {
+            let text: Vec<String> =
+                failures.iter().map(ToString::to_string).collect();
+            panic!(
+                "{} snapshot(s) need review (set {}=always to accept):\n\n{}",
+                failures.len(),
+                UPDATE_ENV_VAR,
+                text.join("\n")
+            );
+        }
+    }
+}
+
+/// Represents a directory of vimwiki files whose element structure is
+/// compared against stored snapshots
+///
+/// ## Examples
+///
+/// ```no_run
+/// use vimwiki::snapshots::{Corpus, UpdateMode};
+///
+/// // Snapshots of my-wiki/*.wiki are stored in my-wiki/snapshots/*.snap
+/// Corpus::new("my-wiki")
+///     .check(UpdateMode::from_env())
+///     .unwrap()
+///     .assert_ok();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Corpus {
+    /// Directory containing the files
+    pub dir: PathBuf,
+
+    /// Directory where the snapshots of the files are stored
+    pub snapshot_dir: PathBuf,
+}
+
+impl Corpus {
+    /// Creates a corpus of the files in the directory, storing snapshots
+    /// within its `snapshots` subdirectory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        let snapshot_dir = dir.join("snapshots");
+        Self { dir, snapshot_dir }
+    }
+
+    /// Stores snapshots within the given directory instead
+    pub fn with_snapshot_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.snapshot_dir = dir.into();
+        self
+    }
+
+    /// Returns the corpus of real-world wiki files bundled with this crate
+    pub fn bundled() -> Self {
+        Self::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus"))
+    }
+
+    /// Returns the paths of the files of the corpus, sorted
+    pub fn files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.is_file()
+                && path.extension().is_some_and(|x| x == CORPUS_FILE_EXT)
+            {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Returns the path of the stored snapshot of the file
+    pub fn snapshot_path(&self, file: &Path) -> PathBuf {
+        let name = file.file_stem().unwrap_or_default().to_string_lossy();
+        self.snapshot_dir.join(format!("{}.{}", name, SNAPSHOT_EXT))
+    }
+
+    /// Checks the snapshot of every file of the corpus, writing snapshots
+    /// as allowed by the mode
+    pub fn check(&self, mode: UpdateMode) -> io::Result<CorpusReport> {
+        let mut report = CorpusReport::default();
+        for file in self.files()? {
+            report.results.push(self.check_file(&file, mode)?);
+        }
+        Ok(report)
+    }
+
+    /// Checks the snapshot of a single file, writing it as allowed by the
+    /// mode
+    pub fn check_file(
+        &self,
+        file: &Path,
+        mode: UpdateMode,
+    ) -> io::Result<SnapshotResult> {
+        let snapshot = self.snapshot_path(file);
+        let new_snapshot = snapshot.with_extension(NEW_SNAPSHOT_EXT);
+        let actual = render(&fs::read_to_string(file)?);
+
+        let outcome = match fs::read_to_string(&snapshot) {
+            Ok(expected) => match diff(&expected, &actual) {
+                None => SnapshotOutcome::Matched,
+                Some(diff) if mode == UpdateMode::Always => {
+                    fs::write(&snapshot, &actual)?;
+                    SnapshotOutcome::Updated { diff }
+                }
+                Some(diff) => {
+                    if mode != UpdateMode::No {
+                        fs::write(&new_snapshot, &actual)?;
+                    }
+                    SnapshotOutcome::Mismatched { diff }
+                }
+            },
+            Err(x) if x.kind() == io::ErrorKind::NotFound => {
+                if mode == UpdateMode::No {
+                    SnapshotOutcome::Missing
+                } else {
+                    fs::create_dir_all(&self.snapshot_dir)?;
+                    fs::write(&snapshot, &actual)?;
+                    SnapshotOutcome::Created
+                }
+            }
+            Err(x) => return Err(x),
+        };
+
+        // Reviews left over from earlier runs no longer apply
+        if !matches!(outcome, SnapshotOutcome::Mismatched { .. })
+            && new_snapshot.exists()
+        {
+            fs::remove_file(&new_snapshot)?;
+        }
+
+        Ok(SnapshotResult {
+            file: file.to_path_buf(),
+            snapshot,
+            outcome,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_should_indent_children_beneath_their_parents() {
+        let text = "= Title =\n- [ ] *task*\n";
+        let snapshot = render(text);
+        let lines: Vec<&str> = snapshot.lines().collect();
+        assert_eq!(lines[0], "header 0..10 \"= Title =\"");
+        assert!(
+            lines[1].starts_with("  text 2..7 \"Title\""),
+            "{}",
+            snapshot
+        );
+        assert!(lines.iter().any(|x| x.starts_with("list 10..")));
+        assert!(lines.iter().any(|x| x.starts_with("      decorated_text")));
+    }
+
+    #[test]
+    fn diff_should_show_changed_lines_between_common_ones() {
+        assert_eq!(diff("a\nb\n", "a\nb\n"), None);
+        assert_eq!(
+            diff("a\nb\nc\n", "a\nx\ny\nc\n").unwrap(),
+            "@@ line 2 (-1 +2) @@\n-b\n+x\n+y\n"
+        );
+    }
+
+    #[test]
+    fn check_should_create_then_compare_snapshots() {
+        let dir = env::temp_dir().join("vimwiki-snapshots-check");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("page.wiki");
+        fs::write(&file, "= One =\n").unwrap();
+        fs::write(dir.join("ignored.txt"), "").unwrap();
+
+        let corpus = Corpus::new(&dir);
+        assert_eq!(corpus.files().unwrap(), vec![file.clone()]);
+
+        let missing = corpus.check(UpdateMode::No).unwrap();
+        assert_eq!(missing.failures().len(), 1);
+
+        let created = corpus.check(UpdateMode::New).unwrap();
+        assert_eq!(created.results[0].outcome, SnapshotOutcome::Created);
+        corpus.check(UpdateMode::No).unwrap().assert_ok();
+
+        fs::write(&file, "= Two =\n").unwrap();
+        let snapshot = corpus.snapshot_path(&file);
+        let result = corpus.check_file(&file, UpdateMode::New).unwrap();
+        assert!(result.outcome.is_failure());
+        assert!(snapshot.with_extension(NEW_SNAPSHOT_EXT).exists());
+
+        let result = corpus.check_file(&file, UpdateMode::Always).unwrap();
+        assert!(matches!(result.outcome, SnapshotOutcome::Updated { .. }));
+        assert!(!snapshot.with_extension(NEW_SNAPSHOT_EXT).exists());
+        corpus.check(UpdateMode::No).unwrap().assert_ok();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn bundled_corpus_should_match_snapshots() {
+        Corpus::bundled()
+            .check(UpdateMode::from_env())
+            .unwrap()
+            .assert_ok();
+    }
+}
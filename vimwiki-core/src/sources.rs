@@ -0,0 +1,242 @@
+use crate::{Language, Page, ParseError, ParserConfig};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+/// Represents the encoding detected for the bytes of some text
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Encoding {
+    /// UTF-8, optionally beginning with a byte order mark
+    Utf8 { bom: bool },
+
+    /// ISO-8859-1, used as a fallback for bytes that are not valid UTF-8 as
+    /// every byte maps to exactly one character
+    Latin1,
+}
+
+impl Encoding {
+    /// Detects the encoding of the bytes, preferring UTF-8 and falling back
+    /// to latin-1
+    pub fn detect(bytes: &[u8]) -> Self {
+        if std::str::from_utf8(bytes).is_ok() {
+            Self::Utf8 {
+                bom: bytes.starts_with(Page::BOM.as_bytes()),
+            }
+        } else {
+            Self::Latin1
+        }
+    }
+}
+
+/// Decodes the bytes into text, returning the text alongside the encoding
+/// that was detected
+///
+/// NOTE: A UTF-8 byte order mark is kept at the start of the text so that
+///       parsing records its presence within the page
+pub fn decode(bytes: Vec<u8>) -> (String, Encoding) {
+    match String::from_utf8(bytes) {
+        Ok(text) => {
+            let bom = text.starts_with(Page::BOM);
+            (text, Encoding::Utf8 { bom })
+        }
+        Err(x) => (
+            x.into_bytes().into_iter().map(char::from).collect(),
+            Encoding::Latin1,
+        ),
+    }
+}
+
+/// Reads the entirety of the reader and decodes it into text
+pub fn read_to_string<R: Read>(
+    mut reader: R,
+) -> io::Result<(String, Encoding)> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    Ok(decode(bytes))
+}
+
+/// Reads the entirety of the async reader and decodes it into text
+#[cfg(feature = "tokio")]
+pub async fn read_to_string_async<R>(
+    mut reader: R,
+) -> io::Result<(String, Encoding)>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    Ok(decode(bytes))
+}
+
+fn parse_owned(text: &str, config: &ParserConfig) -> io::Result<Page<'static>> {
+    Language::from_vimwiki_str(text)
+        .parse_with_config(config)
+        .map(Page::into_owned)
+        .map_err(|x: ParseError| {
+            io::Error::new(io::ErrorKind::InvalidData, x.to_string())
+        })
+}
+
+impl Page<'static> {
+    /// Reads and parses a vimwiki page from the reader, decoding its bytes
+    /// as UTF-8 (with or without a byte order mark) or latin-1
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use vimwiki::Page;
+    ///
+    /// // "caf\u{e9}" in latin-1
+    /// let page = Page::from_reader(&b"= caf\xe9 ="[..]).unwrap();
+    /// assert_eq!(page.elements().len(), 1);
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> io::Result<Self> {
+        Self::from_reader_with_config(reader, &ParserConfig::default())
+    }
+
+    /// Reads and parses a vimwiki page from the reader using the given
+    /// config
+    pub fn from_reader_with_config<R: Read>(
+        reader: R,
+        config: &ParserConfig,
+    ) -> io::Result<Self> {
+        let (text, _) = read_to_string(reader)?;
+        parse_owned(&text, config)
+    }
+
+    /// Reads and parses a vimwiki page from the async reader
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader<R>(reader: R) -> io::Result<Self>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        Self::from_async_reader_with_config(reader, &ParserConfig::default())
+            .await
+    }
+
+    /// Reads and parses a vimwiki page from the async reader using the given
+    /// config
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader_with_config<R>(
+        reader: R,
+        config: &ParserConfig,
+    ) -> io::Result<Self>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        let (text, _) = read_to_string_async(reader).await?;
+        parse_owned(&text, config)
+    }
+}
+
+/// Represents a page loaded from a file alongside where it was loaded and
+/// how its bytes were decoded
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadedPage {
+    pub path: PathBuf,
+    pub encoding: Encoding,
+    pub page: Page<'static>,
+}
+
+/// Loads and parses each file at the given paths as a vimwiki page, failing
+/// on the first file that cannot be read or parsed
+pub fn load_pages<I, P>(
+    paths: I,
+    config: &ParserConfig,
+) -> io::Result<Vec<LoadedPage>>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    paths
+        .into_iter()
+        .map(|path| {
+            let path = path.as_ref();
+            let reader = BufReader::new(fs::File::open(path)?);
+            let (text, encoding) = read_to_string(reader)?;
+            Ok(LoadedPage {
+                path: path.to_path_buf(),
+                encoding,
+                page: parse_owned(&text, config)?,
+            })
+        })
+        .collect()
+}
+
+/// Loads and parses each file at the given paths as a vimwiki page without
+/// blocking the async executor on file IO
+#[cfg(feature = "tokio")]
+pub async fn load_pages_async<I, P>(
+    paths: I,
+    config: &ParserConfig,
+) -> io::Result<Vec<LoadedPage>>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut pages = Vec::new();
+    for path in paths {
+        let path = path.as_ref();
+        let file =
+            tokio::io::BufReader::new(tokio::fs::File::open(path).await?);
+        let (text, encoding) = read_to_string_async(file).await?;
+        pages.push(LoadedPage {
+            path: path.to_path_buf(),
+            encoding,
+            page: parse_owned(&text, config)?,
+        });
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_should_detect_utf8_with_and_without_bom() {
+        let (text, encoding) = decode("caf\u{e9}".as_bytes().to_vec());
+        assert_eq!(text, "caf\u{e9}");
+        assert_eq!(encoding, Encoding::Utf8 { bom: false });
+
+        let (text, encoding) = decode(b"\xef\xbb\xbfcaf\xc3\xa9".to_vec());
+        assert_eq!(text, "\u{feff}caf\u{e9}");
+        assert_eq!(encoding, Encoding::Utf8 { bom: true });
+    }
+
+    #[test]
+    fn decode_should_fall_back_to_latin1_for_invalid_utf8() {
+        let (text, encoding) = decode(b"caf\xe9 \xff".to_vec());
+        assert_eq!(text, "caf\u{e9} \u{ff}");
+        assert_eq!(encoding, Encoding::Latin1);
+        assert_eq!(Encoding::detect(b"caf\xe9"), Encoding::Latin1);
+    }
+
+    #[test]
+    fn from_reader_should_record_bom_of_utf8_input() {
+        let page = Page::from_reader(&b"\xef\xbb\xbf= header =\n"[..]).unwrap();
+        assert!(page.has_bom);
+        assert_eq!(page.elements().len(), 1);
+    }
+
+    #[test]
+    fn load_pages_should_decode_each_file() {
+        let dir = std::env::temp_dir().join("vimwiki-core-load-pages");
+        fs::create_dir_all(&dir).unwrap();
+        let utf8 = dir.join("utf8.wiki");
+        let latin1 = dir.join("latin1.wiki");
+        fs::write(&utf8, "= caf\u{e9} =\n").unwrap();
+        fs::write(&latin1, b"= caf\xe9 =\n").unwrap();
+
+        let pages =
+            load_pages([&utf8, &latin1], &ParserConfig::default()).unwrap();
+        assert_eq!(pages[0].encoding, Encoding::Utf8 { bom: false });
+        assert_eq!(pages[1].encoding, Encoding::Latin1);
+        assert_eq!(pages[0].page, pages[1].page);
+    }
+}
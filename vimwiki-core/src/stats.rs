@@ -0,0 +1,207 @@
+use crate::{
+    Description, Element, InlineBlockElement, InlineElement,
+    ListItemTodoStatus, Page, Timestamp,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Average words read per minute, used to estimate reading time
+pub const WORDS_PER_MINUTE: usize = 200;
+
+/// Represents statistics about the content of one or more pages
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageStats {
+    /// Total words of text, including the descriptions of links but
+    /// excluding code, math, and comments
+    pub words: usize,
+
+    /// Mapping of element type (e.g. `paragraph`) -> total elements
+    pub elements: BTreeMap<String, usize>,
+
+    /// Total list items with a TODO status
+    pub tasks: usize,
+
+    /// Total list items that have been completed or rejected
+    pub completed_tasks: usize,
+
+    /// Total links of any kind
+    pub links: usize,
+
+    /// Total links to something not local to the system (e.g. a website)
+    pub external_links: usize,
+
+    /// When the page was last modified, if known
+    pub last_modified: Option<Timestamp>,
+}
+
+impl PageStats {
+    /// Computes the statistics of the content of a page
+    pub fn from_page(page: &Page) -> Self {
+        let mut stats = Self::default();
+
+        for element in page.descendants() {
+            let element = element.into_inner();
+            *stats
+                .elements
+                .entry(element.kind_name().to_string())
+                .or_default() += 1;
+
+            match element {
+                Element::Inline(InlineElement::Text(x)) => {
+                    stats.words += count_words(x.as_str());
+                }
+                Element::Inline(InlineElement::Link(x)) => {
+                    stats.links += 1;
+                    if !x.data().is_local() {
+                        stats.external_links += 1;
+                    }
+                    if let Some(Description::Text(x)) = x.description() {
+                        stats.words += count_words(x);
+                    }
+                }
+                Element::InlineBlock(InlineBlockElement::ListItem(x)) => {
+                    if let Some(status) = x.attributes.todo_status {
+                        stats.tasks += 1;
+                        if matches!(
+                            status,
+                            ListItemTodoStatus::Complete
+                                | ListItemTodoStatus::Rejected
+                        ) {
+                            stats.completed_tasks += 1;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        stats
+    }
+
+    /// Updates the stats to mark when the page was last modified
+    pub fn with_last_modified(
+        mut self,
+        last_modified: impl Into<Option<Timestamp>>,
+    ) -> Self {
+        self.last_modified = last_modified.into();
+        self
+    }
+
+    /// Estimated minutes to read the text, rounded up
+    pub fn reading_minutes(&self) -> usize {
+        match self.words {
+            0 => 0,
+            words => (words - 1) / WORDS_PER_MINUTE + 1,
+        }
+    }
+
+    /// Ratio of completed tasks to all tasks (0.0 to 1.0), or none if there
+    /// are no tasks
+    pub fn task_completion(&self) -> Option<f64> {
+        if self.tasks > 0 {
+            Some(self.completed_tasks as f64 / self.tasks as f64)
+        } else {
+            None
+        }
+    }
+
+    /// Adds the stats of another page to these stats, keeping the latest
+    /// time that either was modified
+    pub fn merge(&mut self, other: &Self) {
+        self.words += other.words;
+        for (ty, count) in other.elements.iter() {
+            *self.elements.entry(ty.to_string()).or_default() += count;
+        }
+        self.tasks += other.tasks;
+        self.completed_tasks += other.completed_tasks;
+        self.links += other.links;
+        self.external_links += other.external_links;
+        self.last_modified = self.last_modified.max(other.last_modified);
+    }
+}
+
+/// Represents statistics about every page of a wiki alongside their totals
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WikiStats {
+    total: PageStats,
+    pages: BTreeMap<String, PageStats>,
+}
+
+impl WikiStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the stats of the page with the given name, replacing the stats
+    /// of any page previously added with the same name
+    pub fn add_page(&mut self, name: &str, stats: PageStats) {
+        self.pages.insert(name.to_string(), stats);
+        self.total = PageStats::default();
+        for stats in self.pages.values() {
+            self.total.merge(stats);
+        }
+    }
+
+    /// Returns the combined stats of every page
+    pub fn total(&self) -> &PageStats {
+        &self.total
+    }
+
+    /// Returns the stats of each page by name in sorted order
+    pub fn pages(&self) -> &BTreeMap<String, PageStats> {
+        &self.pages
+    }
+}
+
+fn count_words(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+
+    #[test]
+    fn from_page_should_count_words_elements_tasks_and_links() {
+        let page: Page = Language::from_vimwiki_str(indoc! {"
+            = Some header =
+            A *bold* paragraph with [[link|two words]] and
+            [[https://example.com]] %% not counted
+            - [ ] first task
+            - [X] second task
+            - plain item
+        "})
+        .parse()
+        .unwrap();
+
+        let stats = PageStats::from_page(&page);
+        assert_eq!(stats.words, 15);
+        assert_eq!(stats.elements.get("header"), Some(&1));
+        assert_eq!(stats.elements.get("list_item"), Some(&3));
+        assert_eq!(stats.elements.get("link"), Some(&2));
+        assert_eq!((stats.tasks, stats.completed_tasks), (2, 1));
+        assert_eq!((stats.links, stats.external_links), (2, 1));
+        assert_eq!(stats.task_completion(), Some(0.5));
+        assert_eq!(stats.reading_minutes(), 1);
+    }
+
+    #[test]
+    fn wiki_stats_should_total_pages() {
+        let mut wiki = WikiStats::new();
+        let page = |text: &str| {
+            let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+            PageStats::from_page(&page)
+        };
+
+        wiki.add_page("one", page("one two three\n"));
+        wiki.add_page("two", page("- [X] four five\n"));
+        wiki.add_page("one", page("one two\n"));
+
+        assert_eq!(wiki.pages().len(), 2);
+        assert_eq!(wiki.total().words, 4);
+        assert_eq!(wiki.total().task_completion(), Some(1.0));
+        assert_eq!(wiki.total().elements.get("paragraph"), Some(&2));
+    }
+}
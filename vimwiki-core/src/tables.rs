@@ -0,0 +1,233 @@
+use crate::{
+    edit::{apply_edits, TextEdit},
+    lists::{line_of, line_starts},
+    BlockElement, Element, Language, Page, Region, ToVimwikiString,
+    VimwikiConfig,
+};
+use serde::{Deserialize, Serialize};
+
+/// Represents a table reformatted after an edit made within it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableFormat {
+    /// Edit to the original text that both applies the pending edit and
+    /// replaces the table with its reformatted version
+    pub edit: TextEdit,
+
+    /// Byte offset of the cursor once the edit is applied, kept at the same
+    /// position within the content of its cell
+    pub cursor: usize,
+}
+
+/// Applies a pending edit made with the cursor at the given byte offset and
+/// reformats the table containing the cursor afterwards, aligning its
+/// columns the way that vim-table-mode does, or None if the cursor is not
+/// within a table once the edit is applied
+///
+/// The cursor is given within the text before the edit and, when at or
+/// within the edit, is placed after the edit's new text as when typing a
+/// character. It then moves with the content of its cell as the columns are
+/// realigned.
+pub fn format_table_on_edit(
+    text: &str,
+    offset: usize,
+    edit: &TextEdit,
+) -> Option<TableFormat> {
+    let edited = apply_edits(text, std::slice::from_ref(edit)).ok()?;
+    let delta = edit.text.len() as isize - edit.region.len() as isize;
+    let edit_end = edit.region.offset() + edit.region.len();
+    let cursor = if offset >= edit_end {
+        (offset as isize + delta) as usize
+    } else if offset >= edit.region.offset() {
+        edit.region.offset() + edit.text.len()
+    } else {
+        offset
+    };
+
+    let page: Page = Language::from_vimwiki_str(&edited).parse().ok()?;
+    let (table, region) = page.descendants().find_map(|element| {
+        let region = element.region();
+        match element.into_inner() {
+            Element::Block(BlockElement::Table(x))
+                if region.contains(cursor)
+                    || region.offset() + region.len() == cursor =>
+            {
+                Some((x, region))
+            }
+            _ => None,
+        }
+    })?;
+
+    // Reformat the table, keeping the indentation of its first line
+    let starts = line_starts(&edited);
+    let first_line = line_of(&starts, region.offset());
+    let start = starts[first_line];
+    let end = region.offset() + region.len();
+    let indent = &edited[start..start + leading_whitespace(&edited[start..])];
+    let formatted: String = table
+        .to_vimwiki_string(VimwikiConfig::default())
+        .ok()?
+        .lines()
+        .map(|line| format!("{}{}\n", indent, line.trim_start()))
+        .collect();
+
+    // Locate the cell of the cursor and where it is within the cell's
+    // content to find the same place within the reformatted table
+    let line = line_of(&starts, cursor);
+    let line_text = &edited[starts[line]..];
+    let line_text =
+        &line_text[..line_text.find('\n').unwrap_or(line_text.len())];
+    let (col, within) = cell_position(line_text, cursor - starts[line]);
+
+    let formatted_starts = line_starts(&formatted);
+    let formatted_line = formatted_starts
+        .get(line - first_line)
+        .map(|x| &formatted[*x..])
+        .and_then(|x| x.lines().next())
+        .unwrap_or_default();
+    let new_cursor = start
+        + formatted_starts
+            .get(line - first_line)
+            .copied()
+            .unwrap_or(0)
+        + cell_offset(formatted_line, col, within);
+
+    // Produce a single edit against the original text, where the region of
+    // the table before the pending edit is found by undoing the edit's shift
+    // and is widened to include the entire pending edit
+    let original_start = start.min(edit.region.offset());
+    let original_end = if end >= edit.region.offset() + edit.text.len() {
+        (end as isize - delta) as usize
+    } else {
+        end
+    }
+    .max(edit_end);
+
+    Some(TableFormat {
+        edit: TextEdit::new(
+            Region::from(original_start..original_end),
+            formatted,
+        ),
+        cursor: new_cursor,
+    })
+}
+
+/// Returns the total bytes of whitespace at the start of the text, not
+/// including line breaks
+fn leading_whitespace(text: &str) -> usize {
+    text.len() - text.trim_start_matches([' ', '\t']).len()
+}
+
+/// Returns the column of the cell containing the byte offset within a row of
+/// a table, and the byte offset within the trimmed content of the cell
+fn cell_position(line: &str, offset: usize) -> (usize, usize) {
+    let bars: Vec<usize> =
+        line.match_indices('|').map(|(idx, _)| idx).collect();
+    let col = bars
+        .iter()
+        .filter(|x| **x < offset)
+        .count()
+        .saturating_sub(1);
+
+    let (content_start, content_end) = cell_content(line, &bars, col);
+    (
+        col,
+        offset.clamp(content_start, content_end.max(content_start))
+            - content_start,
+    )
+}
+
+/// Returns the byte offset within a row of a table of the given offset
+/// within the content of the cell at the given column
+fn cell_offset(line: &str, col: usize, within: usize) -> usize {
+    let bars: Vec<usize> =
+        line.match_indices('|').map(|(idx, _)| idx).collect();
+    let (content_start, content_end) = cell_content(line, &bars, col);
+
+    // NOTE: An empty cell still has padding, so place the cursor after the
+    //       padding's first space
+    if content_start >= content_end {
+        let bar = bars.get(col).copied().unwrap_or_default();
+        return (bar + 2).min(bars.get(col + 1).copied().unwrap_or(line.len()));
+    }
+
+    (content_start + within).min(content_end)
+}
+
+/// Returns the start and end byte offsets of the trimmed content of the cell
+/// at the given column within a row, given the offsets of the row's bars
+fn cell_content(line: &str, bars: &[usize], col: usize) -> (usize, usize) {
+    let start = bars.get(col).map(|x| x + 1).unwrap_or(0);
+    let end = bars.get(col + 1).copied().unwrap_or(line.len()).max(start);
+    let cell = &line[start..end];
+    let content_start = start + (cell.len() - cell.trim_start().len());
+    let content_end = start + cell.trim_end().len();
+    (content_start, content_end.max(content_start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn type_at(text: &str, offset: usize, typed: &str) -> (String, usize) {
+        let edit = TextEdit::new(Region::new(offset, 0), typed.to_string());
+        let format = format_table_on_edit(text, offset, &edit).unwrap();
+        (apply_edits(text, &[format.edit]).unwrap(), format.cursor)
+    }
+
+    #[test]
+    fn format_table_on_edit_should_realign_columns_and_move_cursor() {
+        let text = indoc! {"
+            Some text
+            | a | b |
+            |---|---|
+            | c | d |
+            More text
+        "};
+
+        // Typing at the end of the first cell widens the first column
+        let offset = text.find("a ").unwrap() + 1;
+        let (result, cursor) = type_at(text, offset, "bc");
+        assert_eq!(
+            result,
+            indoc! {"
+                Some text
+                | abc | b |
+                |-----|---|
+                | c   | d |
+                More text
+            "}
+        );
+        assert_eq!(&result[cursor..cursor + 4], " | b");
+
+        // Typing within the second row keeps the cursor within its cell
+        let offset = text.find("d ").unwrap();
+        let (result, cursor) = type_at(text, offset, "xyz");
+        assert_eq!(
+            result,
+            indoc! {"
+                Some text
+                | a | b    |
+                |---|------|
+                | c | xyzd |
+                More text
+            "}
+        );
+        assert_eq!(&result[cursor..], "d |\nMore text\n");
+    }
+
+    #[test]
+    fn format_table_on_edit_should_keep_indentation_and_ignore_other_text() {
+        let text = "    |a|bb|\n    |c|d|\n";
+        let edit = TextEdit::new(Region::new(7, 0), String::from("x"));
+        let format = format_table_on_edit(text, 7, &edit).unwrap();
+        assert_eq!(
+            apply_edits(text, &[format.edit]).unwrap(),
+            "    | a | xbb |\n    | c | d   |\n"
+        );
+
+        let text = "not a table\n";
+        let edit = TextEdit::new(Region::new(0, 0), String::from("x"));
+        assert_eq!(format_table_on_edit(text, 0, &edit), None);
+    }
+}
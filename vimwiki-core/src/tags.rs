@@ -0,0 +1,338 @@
+use crate::{
+    intern::{Interner, Symbol},
+    InlineElement, Located, Page, Region,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Represents the configuration of how tags are organized into a hierarchy
+/// and which tags act as aliases of others
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagConfig {
+    /// Separator between the parts of a hierarchical tag, where
+    /// `project/sub` is a child of `project` when using `/`
+    pub separator: String,
+
+    /// Mapping of alias -> tag, where an alias is replaced by its tag
+    /// whether it is used by itself or as the root of a hierarchical tag
+    /// (e.g. `proj/sub` becomes `project/sub` if `proj` is an alias of
+    /// `project`)
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl Default for TagConfig {
+    fn default() -> Self {
+        Self {
+            separator: Self::default_separator(),
+            aliases: BTreeMap::new(),
+        }
+    }
+}
+
+impl TagConfig {
+    #[inline]
+    pub fn default_separator() -> String {
+        String::from("/")
+    }
+}
+
+/// Represents a tag found within a page
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaggedPage {
+    /// Name of the page containing the tag
+    pub page: Symbol,
+
+    /// Tag with its alias (if any) expanded
+    pub tag: Symbol,
+
+    /// Region of the tags element containing the tag within its page
+    pub region: Region,
+}
+
+/// Represents a collection of tags gathered across pages, which can be
+/// queried by tag or by an entire subtree of hierarchical tags
+///
+/// Names of tags and pages are interned as they repeat across pages, so
+/// entries refer to them by [`Symbol`] and can be compared without comparing
+/// strings.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagIndex {
+    config: TagConfig,
+    interner: Interner,
+    entries: Vec<TaggedPage>,
+}
+
+impl TagIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the index to organize tags based on the config
+    pub fn with_config(mut self, config: TagConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Returns the config used to organize tags
+    pub fn config(&self) -> &TagConfig {
+        &self.config
+    }
+
+    /// Returns all tags in the order that they were added
+    pub fn entries(&self) -> &[TaggedPage] {
+        &self.entries
+    }
+
+    /// Returns the interner holding the names of tags and pages
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    /// Returns the name of the tag or page of the symbol
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.interner.resolve(symbol)
+    }
+
+    /// Returns the symbol of the tag with its alias (if any) expanded, or
+    /// None if the tag is not within the index
+    pub fn symbol(&self, tag: &str) -> Option<Symbol> {
+        self.interner.get(&self.expand(tag))
+    }
+
+    /// Interns a name alongside those of tags and pages, used by other
+    /// indexes to share names with this one
+    pub(crate) fn intern(&mut self, s: &str) -> Symbol {
+        self.interner.intern(s)
+    }
+
+    /// Adds all tags within the page as belonging to the page with the
+    /// given name
+    pub fn add_page(&mut self, name: &str, page: &Page) {
+        let name = self.interner.intern(name);
+        for tags in collect_tags(page) {
+            for tag in tags.as_inner() {
+                let tag = self.expand(tag);
+                self.entries.push(TaggedPage {
+                    page: name,
+                    tag: self.interner.intern(&tag),
+                    region: tags.region(),
+                });
+            }
+        }
+    }
+
+    /// Replaces an alias used by itself or as the root of a hierarchical
+    /// tag with the tag that it stands for
+    ///
+    /// Aliases are only expanded once, meaning that an alias of another
+    /// alias is not expanded further.
+    pub fn expand(&self, tag: &str) -> String {
+        if let Some(x) = self.config.aliases.get(tag) {
+            return x.to_string();
+        }
+
+        let sep = self.config.separator.as_str();
+        match tag.split_once(sep) {
+            Some((root, rest)) if !sep.is_empty() => {
+                match self.config.aliases.get(root) {
+                    Some(x) => format!("{}{}{}", x, sep, rest),
+                    None => tag.to_string(),
+                }
+            }
+            _ => tag.to_string(),
+        }
+    }
+
+    /// Returns every distinct tag in sorted order, including the ancestors
+    /// of hierarchical tags even if they are never used by themselves
+    pub fn tags(&self) -> BTreeSet<String> {
+        let sep = self.config.separator.as_str();
+        let mut tags = BTreeSet::new();
+
+        for entry in self.entries.iter() {
+            let tag = self.resolve(entry.tag);
+            if !sep.is_empty() {
+                for (i, _) in tag.match_indices(sep) {
+                    tags.insert(tag[..i].to_string());
+                }
+            }
+            tags.insert(tag.to_string());
+        }
+
+        tags
+    }
+
+    /// Returns the tags directly below the given tag within the hierarchy
+    /// in sorted order
+    pub fn children(&self, tag: &str) -> BTreeSet<String> {
+        let sep = self.config.separator.as_str();
+        let prefix = format!("{}{}", self.expand(tag), sep);
+
+        self.tags()
+            .into_iter()
+            .filter(|x| match x.strip_prefix(prefix.as_str()) {
+                Some(rest) => !rest.is_empty() && !rest.contains(sep),
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Finds all tags matching the query, where a query of `project` only
+    /// matches that tag and a query of `project/*` matches `project` and
+    /// every tag below it (`project/sub`, `project/sub/more`, etc)
+    ///
+    /// Aliases within the query are expanded prior to matching.
+    pub fn find(&self, query: &str) -> Vec<&TaggedPage> {
+        let sep = self.config.separator.as_str();
+        let wildcard = format!("{}*", sep);
+
+        match query.strip_suffix(wildcard.as_str()) {
+            Some(root) if !sep.is_empty() => {
+                let root = self.expand(root);
+                let prefix = format!("{}{}", root, sep);
+                self.entries
+                    .iter()
+                    .filter(|x| {
+                        let tag = self.resolve(x.tag);
+                        tag == root || tag.starts_with(&prefix)
+                    })
+                    .collect()
+            }
+            _ => match self.symbol(query) {
+                Some(tag) => {
+                    self.entries.iter().filter(|x| x.tag == tag).collect()
+                }
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// Returns the names of pages with tags matching the query in sorted
+    /// order, using the same form of query as [`TagIndex::find`]
+    pub fn pages(&self, query: &str) -> BTreeSet<&str> {
+        self.find(query)
+            .into_iter()
+            .map(|x| self.resolve(x.page))
+            .collect()
+    }
+}
+
+/// Collects all tags elements within the page, in document order
+fn collect_tags<'a>(page: &'a Page<'a>) -> Vec<Located<Vec<String>>> {
+    page.descendants()
+        .filter_map(|x| match x.as_inner().as_inline_element() {
+            Some(InlineElement::Tags(tags)) => Some(Located::new(
+                tags.iter().map(|t| t.as_str().to_string()).collect(),
+                x.region(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+
+    fn make_index() -> TagIndex {
+        let mut aliases = BTreeMap::new();
+        aliases.insert(String::from("proj"), String::from("project"));
+        aliases.insert(String::from("todo"), String::from("task"));
+
+        let mut index = TagIndex::new().with_config(TagConfig {
+            aliases,
+            ..Default::default()
+        });
+
+        let page: Page = Language::from_vimwiki_str(indoc! {"
+            :project/web:todo:
+            - item with :proj/web/frontend:
+        "})
+        .parse()
+        .unwrap();
+        index.add_page("one", &page);
+
+        let page: Page = Language::from_vimwiki_str(":project:projects:\n")
+            .parse()
+            .unwrap();
+        index.add_page("two", &page);
+
+        index
+    }
+
+    #[test]
+    fn add_page_should_expand_aliases() {
+        let index = make_index();
+        let tags: Vec<(&str, &str)> = index
+            .entries()
+            .iter()
+            .map(|x| (index.resolve(x.page), index.resolve(x.tag)))
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                ("one", "project/web"),
+                ("one", "task"),
+                ("one", "project/web/frontend"),
+                ("two", "project"),
+                ("two", "projects"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tags_should_include_ancestors_of_hierarchical_tags() {
+        let index = make_index();
+        let tags: Vec<String> = index.tags().into_iter().collect();
+        assert_eq!(
+            tags,
+            [
+                "project",
+                "project/web",
+                "project/web/frontend",
+                "projects",
+                "task"
+            ]
+        );
+
+        let children: Vec<String> =
+            index.children("proj").into_iter().collect();
+        assert_eq!(children, ["project/web"]);
+    }
+
+    #[test]
+    fn find_should_support_subtree_queries() {
+        let index = make_index();
+
+        let pages: Vec<&str> = index.pages("project").into_iter().collect();
+        assert_eq!(pages, ["two"]);
+
+        let pages: Vec<&str> = index.pages("project/*").into_iter().collect();
+        assert_eq!(pages, ["one", "two"]);
+
+        let tags: Vec<&str> = index
+            .find("proj/web/*")
+            .into_iter()
+            .map(|x| index.resolve(x.tag))
+            .collect();
+        assert_eq!(tags, ["project/web", "project/web/frontend"]);
+
+        assert!(index.find("web/*").is_empty());
+    }
+
+    #[test]
+    fn add_page_should_intern_repeated_names() {
+        let index = make_index();
+        let project = index.symbol("proj").unwrap();
+        assert_eq!(index.resolve(project), "project");
+        assert_eq!(index.symbol("missing"), None);
+
+        // Two page names and five distinct expanded tags
+        assert_eq!(index.interner().len(), 7);
+        assert_eq!(index.entries()[0].page, index.entries()[1].page);
+        assert_eq!(index.find("project")[0].tag, project);
+    }
+}
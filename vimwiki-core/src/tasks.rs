@@ -0,0 +1,310 @@
+use crate::date::WikiDate;
+use crate::{
+    edit::TextEdit, BlockElement, Element, InlineBlockElement, ListItem,
+    ListItemTodoStatus, Located, Page, Region,
+};
+use serde::{Deserialize, Serialize};
+
+/// Represents a list item annotated with a due date
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatedTask {
+    /// Name of the page containing the task
+    pub page: String,
+
+    /// Text of the first line of content within the task
+    pub description: String,
+
+    /// Date by which the task is due
+    pub due: WikiDate,
+
+    /// The TODO status of the task, if it has one
+    pub todo_status: Option<ListItemTodoStatus>,
+
+    /// Region of the list item within its page
+    pub region: Region,
+}
+
+impl DatedTask {
+    /// Whether or not the task no longer needs attention, meaning it has
+    /// been completed or rejected
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self.todo_status,
+            Some(ListItemTodoStatus::Complete)
+                | Some(ListItemTodoStatus::Rejected)
+        )
+    }
+
+    /// Whether or not the task is not done and was due before the given date
+    pub fn is_overdue(&self, today: WikiDate) -> bool {
+        !self.is_done() && self.due < today
+    }
+}
+
+/// Represents a collection of dated tasks gathered across pages
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskIndex {
+    tasks: Vec<DatedTask>,
+}
+
+impl TaskIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all tasks in the order that they were added
+    pub fn tasks(&self) -> &[DatedTask] {
+        &self.tasks
+    }
+
+    /// Adds all list items with a due date within the page, including those
+    /// within sublists, as tasks belonging to the page with the given name
+    pub fn add_page(&mut self, name: &str, page: &Page) {
+        for item in collect_list_items(page) {
+            let region = item.region();
+            let item = item.into_inner();
+
+            if let Some(due) = item.due_date() {
+                self.tasks.push(DatedTask {
+                    page: name.to_string(),
+                    description: describe(&item),
+                    due,
+                    todo_status: item.attributes.todo_status,
+                    region,
+                });
+            }
+        }
+    }
+
+    /// Returns tasks that are not done and were due before the given date,
+    /// sorted by due date
+    pub fn overdue(&self, today: WikiDate) -> Vec<&DatedTask> {
+        self.filter_sorted(|t| t.is_overdue(today))
+    }
+
+    /// Returns tasks that are not done and are due on the given date or
+    /// within the following number of days, sorted by due date
+    pub fn upcoming(&self, today: WikiDate, days: u32) -> Vec<&DatedTask> {
+        let until = today.add_days(days as i64);
+        self.filter_sorted(|t| !t.is_done() && t.due >= today && t.due <= until)
+    }
+
+    fn filter_sorted(&self, f: impl Fn(&DatedTask) -> bool) -> Vec<&DatedTask> {
+        let mut tasks: Vec<&DatedTask> =
+            self.tasks.iter().filter(|t| f(t)).collect();
+        tasks.sort_by_key(|t| t.due);
+        tasks
+    }
+}
+
+/// Represents a list item with a TODO status
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Task {
+    /// Text of the first line of content within the task
+    pub description: String,
+
+    /// The TODO status of the task
+    pub todo_status: ListItemTodoStatus,
+
+    /// Region of the list item within its page
+    pub region: Region,
+}
+
+impl Task {
+    /// Produces the edit to the text of the task's page that toggles the
+    /// task, marking a complete or rejected task as incomplete and any other
+    /// task as complete, or None if the text does not contain the task's
+    /// TODO status
+    pub fn to_toggle_edit(&self, text: &str) -> Option<TextEdit> {
+        let start = self.region.offset();
+        let end = start + self.region.len();
+        let line = text.get(start..end)?.lines().next()?;
+
+        // NOTE: The TODO status is the first bracket pair of the item,
+        //       following its prefix such as - or 1.
+        let idx = line.find('[')?;
+        let mut chars = line[idx + 1..].chars();
+        chars.next()?;
+        if chars.next()? != ']' {
+            return None;
+        }
+
+        let status = match self.todo_status {
+            ListItemTodoStatus::Complete | ListItemTodoStatus::Rejected => ' ',
+            _ => 'X',
+        };
+
+        let offset = start + idx + 1;
+        let len = line[idx + 1..].chars().next()?.len_utf8();
+        Some(TextEdit::new(Region::new(offset, len), status.to_string()))
+    }
+}
+
+/// Collects all list items with a TODO status within the page, including
+/// those within sublists, in document order
+pub fn find_tasks(page: &Page) -> Vec<Task> {
+    collect_list_items(page)
+        .into_iter()
+        .filter_map(|item| {
+            let region = item.region();
+            let item = item.into_inner();
+            item.attributes.todo_status.map(|todo_status| Task {
+                description: describe(&item),
+                todo_status,
+                region,
+            })
+        })
+        .collect()
+}
+
+/// Finds the innermost task within the page whose list item contains the
+/// given byte offset, or None if the offset is not within a task
+pub fn find_task_at_offset(page: &Page, offset: usize) -> Option<Task> {
+    find_tasks(page)
+        .into_iter()
+        .filter(|task| task.region.contains(offset))
+        .min_by_key(|task| task.region.len())
+}
+
+/// Produces a single-line description of a list item from its first content
+pub(crate) fn describe(item: &ListItem) -> String {
+    match item.contents.iter().next().map(|c| c.as_inner()) {
+        Some(BlockElement::Paragraph(x)) => x
+            .to_string()
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<&str>>()
+            .join(" "),
+        _ => String::new(),
+    }
+}
+
+/// Collects all list items within the page, in document order
+pub(crate) fn collect_list_items<'a>(
+    page: &'a Page<'a>,
+) -> Vec<Located<ListItem<'a>>> {
+    page.descendants()
+        .filter_map(|x| {
+            let region = x.region();
+            match x.into_inner() {
+                Element::InlineBlock(InlineBlockElement::ListItem(item)) => {
+                    Some(Located::new(item, region))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+    use indoc::indoc;
+
+    fn make_index() -> TaskIndex {
+        let mut index = TaskIndex::new();
+        let page: Page = Language::from_vimwiki_str(indoc! {"
+            - [ ] pay bills (due: 2024-05-01)
+            - [X] renew passport <2024-04-01>
+            - [ ] undated task
+            - plan trip <2024-05-10>
+                - [.] book hotel <2024-05-03>
+            - [ ] far away <2024-09-01>
+        "})
+        .parse()
+        .unwrap();
+        index.add_page("todo", &page);
+        index
+    }
+
+    #[test]
+    fn add_page_should_collect_list_items_with_due_dates() {
+        let index = make_index();
+        let descriptions: Vec<&str> = index
+            .tasks()
+            .iter()
+            .map(|t| t.description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            [
+                "pay bills (due: 2024-05-01)",
+                "renew passport <2024-04-01>",
+                "plan trip <2024-05-10>",
+                "book hotel <2024-05-03>",
+                "far away <2024-09-01>",
+            ]
+        );
+        assert!(index.tasks().iter().all(|t| t.page == "todo"));
+    }
+
+    #[test]
+    fn to_toggle_edit_should_flip_between_complete_and_incomplete() {
+        let text = "- [ ] first [[link]]\n    - [X] second\n- plain\n";
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        let tasks = find_tasks(&page);
+        assert_eq!(
+            tasks
+                .iter()
+                .map(|t| t.description.as_str())
+                .collect::<Vec<_>>(),
+            ["first link", "second"]
+        );
+
+        let edits: Vec<TextEdit> = tasks
+            .iter()
+            .filter_map(|t| t.to_toggle_edit(text))
+            .collect();
+        assert_eq!(
+            crate::edit::apply_edits(text, &edits).unwrap(),
+            "- [X] first [[link]]\n    - [ ] second\n- plain\n"
+        );
+    }
+
+    #[test]
+    fn find_task_at_offset_should_find_innermost_task() {
+        let text = "- [ ] first\n    - [X] second\n- plain\n";
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+
+        let task =
+            |offset| find_task_at_offset(&page, offset).map(|t| t.description);
+        assert_eq!(task(2).as_deref(), Some("first"));
+        assert_eq!(
+            task(text.find("second").unwrap()).as_deref(),
+            Some("second")
+        );
+        assert_eq!(task(text.find("plain").unwrap()), None);
+    }
+
+    #[test]
+    fn overdue_should_exclude_done_tasks() {
+        let index = make_index();
+        let overdue: Vec<&str> = index
+            .overdue(WikiDate::from_ymd(2024, 5, 5))
+            .into_iter()
+            .map(|t| t.description.as_str())
+            .collect();
+        assert_eq!(
+            overdue,
+            ["pay bills (due: 2024-05-01)", "book hotel <2024-05-03>"]
+        );
+    }
+
+    #[test]
+    fn upcoming_should_include_tasks_due_within_days() {
+        let index = make_index();
+        let upcoming: Vec<WikiDate> = index
+            .upcoming(WikiDate::from_ymd(2024, 5, 1), 7)
+            .into_iter()
+            .map(|t| t.due)
+            .collect();
+        assert_eq!(
+            upcoming,
+            [
+                WikiDate::from_ymd(2024, 5, 1),
+                WikiDate::from_ymd(2024, 5, 3)
+            ]
+        );
+    }
+}
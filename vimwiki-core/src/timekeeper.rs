@@ -1,14 +1,28 @@
 use crate::lang::parsers::Span;
 use lazy_static::lazy_static;
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Mutex,
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
 };
 
 lazy_static! {
     static ref TIMEKEEPER_ENABLED: AtomicBool = AtomicBool::new(false);
-    static ref TIMEKEEPER: Mutex<std::collections::HashMap<&'static str, (usize, u128)>> =
-        std::sync::Mutex::new(std::collections::HashMap::new());
+    static ref TIMEKEEPER: Mutex<HashMap<&'static str, (usize, u128)>> =
+        Mutex::new(HashMap::new());
+    static ref TIMEKEEPER_STACKS: Mutex<HashMap<Vec<&'static str>, (usize, u128)>> =
+        Mutex::new(HashMap::new());
+}
+
+thread_local! {
+    /// Contexts currently being evaluated on this thread, outermost first
+    static CONTEXT_STACK: RefCell<Vec<&'static str>> =
+        const { RefCell::new(Vec::new()) };
 }
 
 pub fn is_enabled() -> bool {
@@ -31,44 +45,201 @@ pub fn toggle_enabled() {
     }
 }
 
-/// Prints a report based on the timekeeper's memory
-pub fn print_report(clear_after_print: bool) {
-    let mut results: Vec<(&'static str, (usize, u128))> = TIMEKEEPER
-        .lock()
-        .unwrap()
-        .iter()
-        .map(|(k, v)| (*k, *v))
-        .collect();
-
-    // Sort with most expensive average item first
-    results.sort_unstable_by_key(|k| (k.1 .1 as f64 / k.1 .0 as f64) as u128);
-    results.reverse();
-
-    fn time_to_str(x: u128) -> String {
-        if x >= 10_u128.pow(9) {
-            format!("{}s", (x as f64) / 10_f64.powi(9))
-        } else if x >= 10_u128.pow(6) {
-            format!("{}ms", (x as f64) / 10_f64.powi(6))
-        } else if x >= 10_u128.pow(3) {
-            format!("{}μs", (x as f64) / 10_f64.powi(3))
+/// Represents a snapshot of the timekeeper's memory
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Statistics per context regardless of where the context was entered,
+    /// sorted with the most expensive average context first
+    pub contexts: Vec<ContextStats>,
+
+    /// Tree of contexts as they were nested during parsing
+    pub tree: Vec<ProfileNode>,
+}
+
+/// Represents the cumulative statistics of a single parser context
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextStats {
+    /// Label of the context
+    pub name: String,
+
+    /// Total number of times the context was entered
+    pub calls: usize,
+
+    /// Total time (in nanoseconds) spent within the context
+    pub total_nanos: u128,
+}
+
+impl ContextStats {
+    /// Average time (in nanoseconds) spent within the context per call
+    pub fn average_nanos(&self) -> u128 {
+        if self.calls > 0 {
+            self.total_nanos / self.calls as u128
         } else {
-            format!("{}ns", x)
+            0
         }
     }
+}
 
-    println!("====== TIMEKEEPER REPORT ======");
-    println!();
-    for (ctx, (cnt, nanos)) in results.drain(..) {
-        println!(
-            "- {}: ({} calls, total {}, average {})",
-            ctx,
-            cnt,
-            time_to_str(nanos),
-            time_to_str((nanos as f64 / cnt as f64) as u128),
-        );
+/// Represents a context within the tree of contexts, where its children are
+/// the contexts that were entered while it was being evaluated
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileNode {
+    /// Label of the context
+    pub name: String,
+
+    /// Total number of times the context was entered at this position
+    pub calls: usize,
+
+    /// Total time (in nanoseconds) spent within the context at this position,
+    /// including time spent within children
+    pub total_nanos: u128,
+
+    /// Contexts entered from within this context
+    pub children: Vec<ProfileNode>,
+}
+
+impl ProfileNode {
+    /// Time (in nanoseconds) spent within the context itself, excluding
+    /// time spent within children
+    pub fn self_nanos(&self) -> u128 {
+        let children: u128 = self.children.iter().map(|c| c.total_nanos).sum();
+        self.total_nanos.saturating_sub(children)
+    }
+}
+
+impl Profile {
+    /// Produces a report in the folded stack format consumed by flamegraph
+    /// tooling such as `inferno` and `flamegraph.pl`, where each line is a
+    /// semicolon-separated stack followed by the self time in nanoseconds
+    pub fn to_folded_stacks(&self) -> String {
+        fn fold(node: &ProfileNode, prefix: &str, lines: &mut Vec<String>) {
+            let stack = if prefix.is_empty() {
+                node.name.to_string()
+            } else {
+                format!("{};{}", prefix, node.name)
+            };
+
+            lines.push(format!("{} {}", stack, node.self_nanos()));
+            for child in node.children.iter() {
+                fold(child, &stack, lines);
+            }
+        }
+
+        let mut lines = Vec::new();
+        for node in self.tree.iter() {
+            fold(node, "", &mut lines);
+        }
+        lines.join("\n")
+    }
+
+    /// Builds a profile from flat context statistics and statistics keyed by
+    /// the stack of contexts active at the time
+    fn from_raw(
+        flat: &HashMap<&'static str, (usize, u128)>,
+        stacks: &HashMap<Vec<&'static str>, (usize, u128)>,
+    ) -> Self {
+        let mut contexts: Vec<ContextStats> = flat
+            .iter()
+            .map(|(name, (calls, total_nanos))| ContextStats {
+                name: name.to_string(),
+                calls: *calls,
+                total_nanos: *total_nanos,
+            })
+            .collect();
+
+        // Sort with most expensive average item first, using the name to
+        // keep the ordering stable between equivalent items
+        contexts.sort_unstable_by(|a, b| {
+            b.average_nanos()
+                .cmp(&a.average_nanos())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        // Sort stacks so the tree is always built in the same order
+        let mut stacks: Vec<(&Vec<&'static str>, &(usize, u128))> =
+            stacks.iter().collect();
+        stacks.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        fn insert(
+            nodes: &mut Vec<ProfileNode>,
+            stack: &[&'static str],
+            calls: usize,
+            total_nanos: u128,
+        ) {
+            if let Some((name, rest)) = stack.split_first() {
+                let idx = match nodes.iter().position(|n| n.name == *name) {
+                    Some(idx) => idx,
+                    None => {
+                        nodes.push(ProfileNode {
+                            name: name.to_string(),
+                            calls: 0,
+                            total_nanos: 0,
+                            children: Vec::new(),
+                        });
+                        nodes.len() - 1
+                    }
+                };
+
+                if rest.is_empty() {
+                    nodes[idx].calls += calls;
+                    nodes[idx].total_nanos += total_nanos;
+                } else {
+                    insert(&mut nodes[idx].children, rest, calls, total_nanos);
+                }
+            }
+        }
+
+        let mut tree = Vec::new();
+        for (stack, (calls, total_nanos)) in stacks {
+            insert(&mut tree, stack, *calls, *total_nanos);
+        }
+
+        Self { contexts, tree }
     }
-    println!();
-    println!("===============================");
+}
+
+impl fmt::Display for Profile {
+    /// Writes a human-readable report of the contexts within the profile
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn time_to_str(x: u128) -> String {
+            if x >= 10_u128.pow(9) {
+                format!("{}s", (x as f64) / 10_f64.powi(9))
+            } else if x >= 10_u128.pow(6) {
+                format!("{}ms", (x as f64) / 10_f64.powi(6))
+            } else if x >= 10_u128.pow(3) {
+                format!("{}μs", (x as f64) / 10_f64.powi(3))
+            } else {
+                format!("{}ns", x)
+            }
+        }
+
+        writeln!(f, "====== TIMEKEEPER REPORT ======")?;
+        writeln!(f)?;
+        for stats in self.contexts.iter() {
+            writeln!(
+                f,
+                "- {}: ({} calls, total {}, average {})",
+                stats.name,
+                stats.calls,
+                time_to_str(stats.total_nanos),
+                time_to_str(stats.average_nanos()),
+            )?;
+        }
+        writeln!(f)?;
+        write!(f, "===============================")
+    }
+}
+
+/// Returns a structured profile based on the timekeeper's memory
+pub fn profile() -> Profile {
+    let flat = TIMEKEEPER.lock().unwrap();
+    let stacks = TIMEKEEPER_STACKS.lock().unwrap();
+    Profile::from_raw(&flat, &stacks)
+}
+
+/// Prints a report based on the timekeeper's memory
+pub fn print_report(clear_after_print: bool) {
+    println!("{}", profile());
 
     if clear_after_print {
         clear();
@@ -78,6 +249,7 @@ pub fn print_report(clear_after_print: bool) {
 /// Clears the timekeeper's memory
 pub fn clear() {
     TIMEKEEPER.lock().unwrap().clear();
+    TIMEKEEPER_STACKS.lock().unwrap().clear();
 }
 
 /// Wraps a parser in a contextual label, which makes it easier to identify
@@ -96,8 +268,20 @@ pub mod parsers {
         mut f: impl FnMut(Span<'a>) -> IResult<T>,
     ) -> impl FnMut(Span<'a>) -> IResult<T> {
         move |input: Span| {
+            let enabled = is_enabled();
             let start = std::time::Instant::now();
 
+            // Track the stack of contexts so we can build a tree later
+            let stack = if enabled {
+                CONTEXT_STACK.with(|s| {
+                    let mut s = s.borrow_mut();
+                    s.push(ctx);
+                    Some(s.clone())
+                })
+            } else {
+                None
+            };
+
             // NOTE: Following is the code found in nom's context parser, but due
             //       to issues with wrapping a function like above in a parser,
             //       we have to explicitly call the f parser on its own
@@ -112,8 +296,10 @@ pub mod parsers {
                 }
             };
 
-            if is_enabled() {
+            if let Some(stack) = stack {
                 let x = start.elapsed().as_nanos();
+                CONTEXT_STACK.with(|s| s.borrow_mut().pop());
+
                 TIMEKEEPER
                     .lock()
                     .unwrap()
@@ -122,9 +308,82 @@ pub mod parsers {
                         *e = (e.0 + 1, e.1 + x);
                     })
                     .or_insert((1, x));
+
+                TIMEKEEPER_STACKS
+                    .lock()
+                    .unwrap()
+                    .entry(stack)
+                    .and_modify(move |e| {
+                        *e = (e.0 + 1, e.1 + x);
+                    })
+                    .or_insert((1, x));
             }
 
             result
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_profile() -> Profile {
+        let mut flat = HashMap::new();
+        flat.insert("Page", (1, 100));
+        flat.insert("Paragraph", (2, 60));
+        flat.insert("Text", (4, 20));
+
+        let mut stacks = HashMap::new();
+        stacks.insert(vec!["Page"], (1, 100));
+        stacks.insert(vec!["Page", "Paragraph"], (2, 60));
+        stacks.insert(vec!["Page", "Paragraph", "Text"], (3, 15));
+        stacks.insert(vec!["Page", "Text"], (1, 5));
+
+        Profile::from_raw(&flat, &stacks)
+    }
+
+    #[test]
+    fn profile_should_sort_contexts_by_most_expensive_average_first() {
+        let profile = make_profile();
+        let names: Vec<&str> =
+            profile.contexts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Page", "Paragraph", "Text"]);
+        assert_eq!(profile.contexts[1].average_nanos(), 30);
+    }
+
+    #[test]
+    fn profile_should_nest_contexts_based_on_stacks() {
+        let profile = make_profile();
+        assert_eq!(profile.tree.len(), 1);
+
+        let page = &profile.tree[0];
+        assert_eq!(page.name, "Page");
+        assert_eq!(page.calls, 1);
+        assert_eq!(page.total_nanos, 100);
+        assert_eq!(page.self_nanos(), 35);
+
+        let children: Vec<(&str, usize, u128)> = page
+            .children
+            .iter()
+            .map(|c| (c.name.as_str(), c.calls, c.total_nanos))
+            .collect();
+        assert_eq!(children, vec![("Paragraph", 2, 60), ("Text", 1, 5)]);
+        assert_eq!(page.children[0].children[0].total_nanos, 15);
+    }
+
+    #[test]
+    fn to_folded_stacks_should_emit_self_time_per_stack() {
+        let profile = make_profile();
+        assert_eq!(
+            profile.to_folded_stacks(),
+            [
+                "Page 35",
+                "Page;Paragraph 45",
+                "Page;Paragraph;Text 15",
+                "Page;Text 5",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,226 @@
+#[cfg(feature = "html")]
+use crate::{HtmlFormatter, HtmlOutputError, Output, Page};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Represents the hierarchical number of a header (e.g. `1.2.3`), where each
+/// part is the position of the header (or one of its ancestors) among its
+/// siblings
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub struct HeaderNumber(Vec<usize>);
+
+impl HeaderNumber {
+    /// Returns the parts of the number, one per level
+    pub fn as_slice(&self) -> &[usize] {
+        &self.0
+    }
+
+    /// Returns the level of the header that the number belongs to
+    pub fn level(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the number relative to headers of the given level, dropping
+    /// the parts of all levels above it (e.g. `1.2.3` from level 2 is
+    /// `2.3`), or none if the header is above the given level
+    pub fn from_level(&self, level: usize) -> Option<Self> {
+        let start = level.max(1) - 1;
+        if start < self.0.len() {
+            Some(Self(self.0[start..].to_vec()))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for HeaderNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> =
+            self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+/// Represents a counter of headers that produces the hierarchical number of
+/// each header as they are encountered in order
+///
+/// Levels skipped between a header and its parent are numbered as zero
+/// (e.g. a level 3 header directly below the first level 1 header is
+/// `1.0.1`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeaderNumbering {
+    counts: Vec<usize>,
+}
+
+impl HeaderNumbering {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a header of the given level, returning its number
+    pub fn next(&mut self, level: usize) -> HeaderNumber {
+        let level = level.max(1);
+        self.counts.resize(level, 0);
+        self.counts[level - 1] += 1;
+        HeaderNumber(self.counts.clone())
+    }
+}
+
+/// Represents a header listed within a table of contents
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TocEntry {
+    /// Level of the header (1, 2, 3, etc)
+    pub level: usize,
+
+    /// Hierarchical number of the header
+    pub number: HeaderNumber,
+
+    /// Text of the header
+    pub text: String,
+
+    /// Id of the anchor that links to the header within HTML output, which
+    /// is unique within its page
+    pub id: String,
+}
+
+/// Represents the table of contents of a page, listing its headers in order
+/// with the numbers and anchor ids that they are given in HTML output
+///
+/// The header acting as the table of contents itself is not listed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableOfContents {
+    entries: Vec<TocEntry>,
+}
+
+impl TableOfContents {
+    pub fn new(entries: Vec<TocEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Builds the table of contents of a page using the default HTML config
+    ///
+    /// Anchor ids are assigned by rendering the page in HTML, which ensures
+    /// that they are deduplicated against every other id of the page the
+    /// same way as within HTML output.
+    #[cfg(feature = "html")]
+    pub fn from_page(page: &Page) -> Result<Self, HtmlOutputError> {
+        let mut f = HtmlFormatter::default();
+        page.fmt(&mut f)?;
+        Ok(f.table_of_contents())
+    }
+
+    /// Returns all entries in the order that their headers appear
+    pub fn entries(&self) -> &[TocEntry] {
+        &self.entries
+    }
+
+    /// Returns true if the table of contents has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for TableOfContents {
+    /// Writes the table of contents as a vimwiki list of links to each
+    /// header, indented by level, where the anchor of a link includes the
+    /// text of the header's ancestors (e.g. `[[#Intro#Setup|Setup]]`)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ancestors: Vec<&str> = Vec::new();
+
+        for entry in self.entries.iter() {
+            let level = entry.level.max(1);
+            ancestors.truncate(level - 1);
+            ancestors.resize(level - 1, "");
+            ancestors.push(entry.text.as_str());
+
+            let anchor: Vec<&str> = ancestors
+                .iter()
+                .copied()
+                .filter(|x| !x.is_empty())
+                .collect();
+            writeln!(
+                f,
+                "{}- [[#{}|{}]]",
+                "  ".repeat(level - 1),
+                anchor.join("#"),
+                entry.text,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "html")]
+    use crate::Language;
+
+    #[test]
+    fn header_numbering_should_number_headers_hierarchically() {
+        let mut numbering = HeaderNumbering::new();
+        assert_eq!(numbering.next(1).to_string(), "1");
+        assert_eq!(numbering.next(2).to_string(), "1.1");
+        assert_eq!(numbering.next(2).to_string(), "1.2");
+        assert_eq!(numbering.next(3).to_string(), "1.2.1");
+        assert_eq!(numbering.next(1).to_string(), "2");
+        assert_eq!(numbering.next(3).to_string(), "2.0.1");
+    }
+
+    #[test]
+    fn header_number_should_support_dropping_levels_above() {
+        let mut numbering = HeaderNumbering::new();
+        numbering.next(1);
+        let number = numbering.next(2);
+
+        assert_eq!(number.from_level(1), Some(number.clone()));
+        assert_eq!(number.from_level(2).unwrap().to_string(), "1");
+        assert_eq!(number.from_level(3), None);
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn from_page_should_list_headers_with_unique_ids() {
+        let page: Page = Language::from_vimwiki_str(
+            "= Contents =\n= Intro =\n== Setup ==\n= Usage =\n== Setup ==\n= Intro =\n",
+        )
+        .parse()
+        .unwrap();
+
+        let toc = TableOfContents::from_page(&page).unwrap();
+        let entries: Vec<(String, &str, &str)> = toc
+            .entries()
+            .iter()
+            .map(|x| (x.number.to_string(), x.text.as_str(), x.id.as_str()))
+            .collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                (String::from("1"), "Intro", "intro"),
+                (String::from("1.1"), "Setup", "intro-setup"),
+                (String::from("2"), "Usage", "usage"),
+                (String::from("2.1"), "Setup", "usage-setup"),
+                (String::from("3"), "Intro", "intro-1"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn table_of_contents_should_display_as_list_of_links() {
+        let page: Page =
+            Language::from_vimwiki_str("= Intro =\n=== Setup ===\n= Usage =\n")
+                .parse()
+                .unwrap();
+
+        let toc = TableOfContents::from_page(&page).unwrap();
+        assert_eq!(
+            toc.to_string(),
+            "- [[#Intro|Intro]]\n    - [[#Intro#Setup|Setup]]\n- [[#Usage|Usage]]\n",
+        );
+    }
+}
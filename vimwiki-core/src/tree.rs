@@ -0,0 +1,241 @@
+use crate::{ids::ElementId, Page, Region};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::collections::VecDeque;
+
+/// Version of the serialized form of [`PageTree`], increased whenever the
+/// form changes so that cached trees of an older form are rejected
+pub const PAGE_TREE_VERSION: u32 = 1;
+
+/// Represents the elements of a page as a flat list of nodes in document
+/// order, where each node refers to its children by their position within
+/// the list, so that the same page always serializes the same way and trees
+/// can be cached and diffed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageTree {
+    /// Version of the serialized form of the tree
+    #[serde(deserialize_with = "deserialize_version")]
+    pub version: u32,
+
+    /// Positions of the top-level elements of the page within `nodes`
+    pub roots: Vec<usize>,
+
+    /// Every element of the page in document order
+    pub nodes: Vec<PageTreeNode>,
+}
+
+/// Represents a single element within a [`PageTree`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageTreeNode {
+    /// Stable id of the element
+    pub id: ElementId,
+
+    /// Name of the type of the element (e.g. `header`)
+    pub kind: String,
+
+    /// Region of the element within its page
+    pub region: Region,
+
+    /// Positions of the children of the element within the nodes of the
+    /// tree, in document order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<usize>,
+}
+
+impl PageTree {
+    /// Builds the tree of the page at the path, which is used to derive the
+    /// ids of its elements
+    pub fn new(path: &str, page: &Page) -> Self {
+        let mut tree = Self {
+            version: PAGE_TREE_VERSION,
+            roots: Vec::new(),
+            nodes: Vec::new(),
+        };
+
+        // Positions of the nodes containing the current element, where the
+        // node at each index is the ancestor at that depth
+        let mut ancestors: Vec<usize> = Vec::new();
+
+        for (depth, next) in page.descendants().with_depth() {
+            let position = tree.nodes.len();
+            ancestors.truncate(depth);
+            match ancestors.last() {
+                Some(parent) => tree.nodes[*parent].children.push(position),
+                None => tree.roots.push(position),
+            }
+            ancestors.push(position);
+
+            tree.nodes.push(PageTreeNode {
+                id: ElementId::of(path, &next),
+                kind: next.as_inner().kind_name().to_string(),
+                region: next.region(),
+                children: Vec::new(),
+            });
+        }
+
+        tree
+    }
+
+    /// Returns the node with the given id
+    pub fn find(&self, id: ElementId) -> Option<&PageTreeNode> {
+        self.nodes.iter().find(|x| x.id == id)
+    }
+
+    /// Returns the children of the node
+    pub fn children<'a>(
+        &'a self,
+        node: &'a PageTreeNode,
+    ) -> impl Iterator<Item = &'a PageTreeNode> {
+        node.children.iter().filter_map(move |i| self.nodes.get(*i))
+    }
+
+    /// Returns every node in document order, which is depth-first with
+    /// siblings ordered by region
+    pub fn iter(&self) -> impl Iterator<Item = &PageTreeNode> {
+        self.nodes.iter()
+    }
+
+    /// Returns every node below the node in document order
+    pub fn descendants<'a>(
+        &'a self,
+        node: &'a PageTreeNode,
+    ) -> impl Iterator<Item = &'a PageTreeNode> {
+        let mut stack: Vec<usize> =
+            node.children.iter().rev().copied().collect();
+        std::iter::from_fn(move || {
+            let next = self.nodes.get(stack.pop()?)?;
+            stack.extend(next.children.iter().rev());
+            Some(next)
+        })
+    }
+
+    /// Returns every node below the node a level at a time, starting with its
+    /// children
+    pub fn breadth_first<'a>(
+        &'a self,
+        node: &'a PageTreeNode,
+    ) -> impl Iterator<Item = &'a PageTreeNode> {
+        let mut queue: VecDeque<usize> =
+            node.children.iter().copied().collect();
+        std::iter::from_fn(move || {
+            let next = self.nodes.get(queue.pop_front()?)?;
+            queue.extend(next.children.iter());
+            Some(next)
+        })
+    }
+
+    /// Returns every node without children in document order, which for a
+    /// page are typically the pieces of text that make it up
+    pub fn leaves(&self) -> impl Iterator<Item = &PageTreeNode> {
+        self.nodes.iter().filter(|x| x.children.is_empty())
+    }
+}
+
+fn deserialize_version<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u32, D::Error> {
+    let version = u32::deserialize(deserializer)?;
+    if version == PAGE_TREE_VERSION {
+        Ok(version)
+    } else {
+        Err(de::Error::custom(format!(
+            "Unsupported page tree version {}, expected {}",
+            version, PAGE_TREE_VERSION
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Language;
+
+    fn make_tree(text: &str) -> PageTree {
+        let page: Page = Language::from_vimwiki_str(text).parse().unwrap();
+        PageTree::new("index.wiki", &page)
+    }
+
+    #[test]
+    fn new_should_link_children_in_document_order() {
+        let tree = make_tree("= Header =\n- one\n- two\n");
+        let kinds = |nodes: Vec<&PageTreeNode>| -> Vec<String> {
+            nodes.into_iter().map(|x| x.kind.clone()).collect()
+        };
+
+        let roots = tree.roots.iter().map(|i| &tree.nodes[*i]).collect();
+        assert_eq!(kinds(roots), ["header", "list"]);
+
+        let list = &tree.nodes[tree.roots[1]];
+        assert_eq!(
+            kinds(tree.children(list).collect()),
+            ["list_item", "list_item"]
+        );
+        assert_eq!(tree.find(list.id), Some(list));
+    }
+
+    #[test]
+    fn tree_should_serialize_the_same_way_every_time() {
+        let text = "= Header =\nsome *bold* text\n";
+        let json = serde_json::to_string(&make_tree(text)).unwrap();
+        assert_eq!(json, serde_json::to_string(&make_tree(text)).unwrap());
+
+        let tree: PageTree = serde_json::from_str(&json).unwrap();
+        assert_eq!(tree, make_tree(text));
+    }
+
+    #[test]
+    fn tree_should_reject_unsupported_versions() {
+        let json = r#"{"version":999,"roots":[],"nodes":[]}"#;
+        assert!(serde_json::from_str::<PageTree>(json).is_err());
+    }
+
+    #[test]
+    fn traversals_should_follow_document_order() {
+        let tree = make_tree(
+            "- one *two*
+  - three
+- four
+",
+        );
+        let list = &tree.nodes[tree.roots[0]];
+        let offsets = |nodes: Vec<&PageTreeNode>| -> Vec<usize> {
+            nodes.into_iter().map(|x| x.region.offset()).collect()
+        };
+
+        let descendants: Vec<&PageTreeNode> = tree.descendants(list).collect();
+        assert_eq!(descendants.len(), tree.nodes.len() - 1);
+        assert_eq!(descendants, tree.iter().skip(1).collect::<Vec<_>>());
+
+        let depth_first = offsets(descendants);
+        let mut sorted = depth_first.clone();
+        sorted.sort_unstable();
+        assert_eq!(depth_first, sorted);
+
+        let breadth_first: Vec<&str> = tree
+            .breadth_first(list)
+            .take(2)
+            .map(|x| x.kind.as_str())
+            .collect();
+        assert_eq!(breadth_first, ["list_item", "list_item"]);
+
+        let leaves = offsets(tree.leaves().collect());
+        assert!(!leaves.is_empty());
+        assert!(tree.leaves().all(|x| x.children.is_empty()));
+        let mut sorted = leaves.clone();
+        sorted.sort_unstable();
+        assert_eq!(leaves, sorted);
+    }
+
+    #[test]
+    fn page_should_reuse_its_tree_until_mutated() {
+        let mut page: Page = Language::from_vimwiki_str("= One =\n= Two =\n")
+            .parse()
+            .unwrap();
+        let tree = page.to_tree() as *const PageTree;
+        assert_eq!(page.to_tree() as *const PageTree, tree);
+        assert_eq!(page.to_tree().roots.len(), 2);
+        assert_eq!(page.clone(), page);
+
+        page.elements_mut().pop();
+        assert_eq!(page.to_tree().roots.len(), 1);
+    }
+}
@@ -0,0 +1,393 @@
+use crate::LinkData;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+    sync::OnceLock,
+};
+use uriparse::{Fragment, URIReference, URIReferenceError};
+
+/// Represents a failure to parse a URI reference from text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WikiUriError(URIReferenceError);
+
+impl fmt::Display for WikiUriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for WikiUriError {}
+
+impl From<URIReferenceError> for WikiUriError {
+    fn from(x: URIReferenceError) -> Self {
+        Self(x)
+    }
+}
+
+/// Represents a URI reference, such as the target of a link, which may be
+/// absolute (`https://example.com`) or relative (`some/page#anchor`)
+///
+/// The URI is owned by this crate so that the library implementing it can
+/// change without affecting the API; conversions to and from
+/// `uriparse::URIReference` are available through `From`
+///
+/// Only the text of the URI is stored, borrowed from the input when parsed
+/// as part of a page, as most URIs are only ever displayed. The text is
+/// parsed into its parts the first time that one of them is needed
+#[derive(Clone)]
+pub struct WikiUri<'a> {
+    /// Text of the URI as it was written
+    text: Cow<'a, str>,
+
+    /// Parts of the URI, parsed from the text on first use
+    parsed: OnceLock<Box<URIReference<'static>>>,
+}
+
+impl<'a> WikiUri<'a> {
+    /// Parses the text as a URI reference, failing if it is not valid
+    pub fn parse(s: &'a str) -> Result<Self, WikiUriError> {
+        URIReference::try_from(s)?;
+        Ok(Self::lazy(s))
+    }
+
+    /// Creates a URI from the text without checking that it is valid,
+    /// deferring parsing until its parts are first needed
+    ///
+    /// Text that turns out to not be a valid URI reference has its parts
+    /// taken from the text once percent encoded, but is still displayed as
+    /// it was written
+    pub fn lazy(text: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            text: text.into(),
+            parsed: OnceLock::new(),
+        }
+    }
+
+    /// Returns the text of the URI as it was written
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns true if the text of the URI has been parsed into its parts
+    pub fn is_parsed(&self) -> bool {
+        self.parsed.get().is_some()
+    }
+
+    /// Returns the parts of the URI, parsing them from the text if this is
+    /// the first time that they are needed
+    fn uri(&self) -> &URIReference<'static> {
+        self.parsed
+            .get_or_init(|| Box::new(parse_lossy(&self.text)))
+    }
+
+    /// Returns the scheme of the URI (e.g. `https`) as written if it has one,
+    /// which is found without parsing the rest of the URI
+    pub fn scheme(&self) -> Option<&str> {
+        let (scheme, _) = self.text.split_once(':')?;
+        let mut chars = scheme.chars();
+        let is_scheme = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && chars.all(|c| {
+                c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')
+            });
+        if is_scheme {
+            Some(scheme)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the host of the URI (e.g. `example.com`) if it has one
+    pub fn host(&self) -> Option<String> {
+        self.uri().host().map(ToString::to_string)
+    }
+
+    /// Returns the authority of the URI (e.g. `user@example.com:8080`) if
+    /// it has one, which is the host along with an optional user and port
+    pub fn authority(&self) -> Option<String> {
+        self.uri().authority().map(ToString::to_string)
+    }
+
+    /// Returns the username within the authority of the URI if it has one
+    pub fn username(&self) -> Option<&str> {
+        self.uri().username().map(|x| x.as_str())
+    }
+
+    /// Returns the password within the authority of the URI if it has one
+    pub fn password(&self) -> Option<&str> {
+        self.uri().password().map(|x| x.as_str())
+    }
+
+    /// Returns the port within the authority of the URI if it has one
+    pub fn port(&self) -> Option<u16> {
+        self.uri().port()
+    }
+
+    /// Returns the path of the URI, which is empty if it has none
+    pub fn path(&self) -> String {
+        self.uri().path().to_string()
+    }
+
+    /// Returns the segments of the URI's path, which are separated by `/`
+    /// and can be empty (e.g. the last segment of `some/dir/`)
+    pub fn path_segments(&self) -> impl Iterator<Item = &str> {
+        self.uri().path().segments().iter().map(|x| x.as_str())
+    }
+
+    /// Returns true if the URI's path starts with `/`
+    pub fn is_path_absolute(&self) -> bool {
+        self.uri().path().is_absolute()
+    }
+
+    /// Returns the query of the URI (after `?`) if it has one
+    pub fn query(&self) -> Option<&str> {
+        self.uri().query().map(|x| x.as_str())
+    }
+
+    /// Returns the fragment of the URI (after `#`) if it has one
+    pub fn fragment(&self) -> Option<&str> {
+        self.uri().fragment().map(Fragment::as_str)
+    }
+
+    /// Returns true if the URI has a fragment, even if it is empty
+    pub fn has_fragment(&self) -> bool {
+        self.uri().has_fragment()
+    }
+
+    /// Replaces the fragment of the URI, removing it if None or if the
+    /// text is not a valid fragment
+    pub fn map_fragment(&mut self, fragment: Option<&str>) {
+        let fragment = fragment
+            .and_then(|x| Fragment::try_from(x).ok().map(Fragment::into_owned));
+        let mut uri = self.uri().clone();
+        uri.map_fragment(|_| fragment);
+
+        self.text = Cow::Owned(uri.to_string());
+        self.parsed = OnceLock::from(Box::new(uri));
+    }
+
+    /// Borrows the text of the URI, which is parsed again if its parts are
+    /// needed
+    pub fn to_borrowed(&self) -> WikiUri<'_> {
+        WikiUri::lazy(self.text.as_ref())
+    }
+
+    /// Takes ownership of the text of the URI, keeping its parts if they
+    /// have already been parsed
+    pub fn into_owned(self) -> WikiUri<'static> {
+        WikiUri {
+            text: Cow::Owned(self.text.into_owned()),
+            parsed: self.parsed,
+        }
+    }
+}
+
+/// Parses the text as a URI reference, falling back to the text once
+/// percent encoded and finally to an empty reference if neither is valid
+fn parse_lossy(text: &str) -> URIReference<'static> {
+    URIReference::try_from(text)
+        .map(URIReference::into_owned)
+        .or_else(|_| {
+            URIReference::try_from(LinkData::encode_uri(text).as_str())
+                .map(URIReference::into_owned)
+        })
+        .unwrap_or_else(|_| {
+            URIReference::try_from("")
+                .expect("Empty URI reference is valid")
+                .into_owned()
+        })
+}
+
+/// Returns true if every character of the text can appear within a URI
+/// reference, which is a cheap check performed before creating a URI
+/// without parsing it
+pub(crate) fn is_uri_text(text: &str) -> bool {
+    !text.is_empty()
+        && text.bytes().all(|b| {
+            b.is_ascii_alphanumeric()
+                || matches!(
+                    b,
+                    b'-' | b'.'
+                        | b'_'
+                        | b'~'
+                        | b':'
+                        | b'/'
+                        | b'?'
+                        | b'#'
+                        | b'['
+                        | b']'
+                        | b'@'
+                        | b'!'
+                        | b'$'
+                        | b'&'
+                        | b'\''
+                        | b'('
+                        | b')'
+                        | b'*'
+                        | b'+'
+                        | b','
+                        | b';'
+                        | b'='
+                        | b'%'
+                )
+        })
+}
+
+impl PartialEq for WikiUri<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for WikiUri<'_> {}
+
+impl Hash for WikiUri<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+    }
+}
+
+impl fmt::Debug for WikiUri<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WikiUri").field(&self.text).finish()
+    }
+}
+
+impl fmt::Display for WikiUri<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Serialize for WikiUri<'_> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&self.text)
+    }
+}
+
+impl<'de, 'a> Deserialize<'de> for WikiUri<'a> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(d)?;
+        if text.is_empty() || is_uri_text(&text) {
+            Ok(Self::lazy(text))
+        } else {
+            Err(de::Error::invalid_value(
+                de::Unexpected::Str(&text),
+                &"a uri reference",
+            ))
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for WikiUri<'a> {
+    type Error = WikiUriError;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for WikiUri<'static> {
+    type Error = WikiUriError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        URIReference::try_from(s.as_str())?;
+        Ok(WikiUri::lazy(s))
+    }
+}
+
+impl<'a> From<URIReference<'a>> for WikiUri<'a> {
+    fn from(uri: URIReference<'a>) -> Self {
+        Self {
+            text: Cow::Owned(uri.to_string()),
+            parsed: OnceLock::from(Box::new(uri.into_owned())),
+        }
+    }
+}
+
+impl<'a> From<WikiUri<'a>> for URIReference<'a> {
+    fn from(uri: WikiUri<'a>) -> Self {
+        match uri.parsed.into_inner() {
+            Some(x) => *x,
+            None => parse_lossy(&uri.text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_should_expose_parts_of_uri() {
+        let uri =
+            WikiUri::parse("https://me@example.com:8080/some/path?x=1#top")
+                .unwrap();
+        assert_eq!(uri.scheme(), Some("https"));
+        assert_eq!(uri.authority().as_deref(), Some("me@example.com:8080"));
+        assert_eq!(uri.username(), Some("me"));
+        assert_eq!(uri.password(), None);
+        assert_eq!(uri.host().as_deref(), Some("example.com"));
+        assert_eq!(uri.port(), Some(8080));
+        assert_eq!(uri.path(), "/some/path");
+        assert!(uri.is_path_absolute());
+        assert_eq!(uri.path_segments().collect::<Vec<_>>(), ["some", "path"]);
+        assert_eq!(uri.query(), Some("x=1"));
+        assert_eq!(uri.fragment(), Some("top"));
+        assert_eq!(
+            uri.to_string(),
+            "https://me@example.com:8080/some/path?x=1#top"
+        );
+    }
+
+    #[test]
+    fn lazy_should_only_parse_when_parts_are_needed() {
+        let uri = WikiUri::lazy("https://example.com/page");
+        assert_eq!(uri.scheme(), Some("https"));
+        assert_eq!(uri.to_string(), "https://example.com/page");
+        assert!(!uri.is_parsed());
+
+        assert_eq!(uri.path(), "/page");
+        assert!(uri.is_parsed());
+    }
+
+    #[test]
+    fn lazy_should_fall_back_to_encoded_text_if_invalid() {
+        let uri = WikiUri::lazy("some page#top");
+        assert_eq!(uri.scheme(), None);
+        assert_eq!(uri.path(), "some%20page");
+        assert_eq!(uri.to_string(), "some page#top");
+        assert_eq!(uri.fragment(), Some("top"));
+    }
+
+    #[test]
+    fn scheme_should_require_valid_scheme_before_colon() {
+        assert_eq!(
+            WikiUri::lazy("mailto:me@example.com").scheme(),
+            Some("mailto")
+        );
+        assert_eq!(WikiUri::lazy("some/page:1").scheme(), None);
+        assert_eq!(WikiUri::lazy("1abc:def").scheme(), None);
+        assert_eq!(WikiUri::lazy("page").scheme(), None);
+    }
+
+    #[test]
+    fn map_fragment_should_replace_fragment() {
+        let mut uri = WikiUri::parse("some/page#top").unwrap();
+        uri.map_fragment(Some("bottom"));
+        assert_eq!(uri.to_string(), "some/page#bottom");
+
+        uri.map_fragment(None);
+        assert_eq!(uri.to_string(), "some/page");
+        assert!(!uri.has_fragment());
+    }
+
+    #[test]
+    fn should_convert_to_and_from_uri_reference() {
+        let uri = WikiUri::parse("some/page").unwrap();
+        let uri_ref = URIReference::from(uri.clone());
+        assert_eq!(WikiUri::from(uri_ref), uri);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::parser::fixtures::VimwikiFile;
 use similar_asserts::Diff;
 use std::convert::TryFrom;
-use vimwiki_core::{vendor::uriparse::URIReference, *};
+use vimwiki_core::*;
 
 #[test]
 fn test() {
@@ -131,7 +131,7 @@ fn test() {
                         Region::new_at_depth(112, 6, 1),
                     ),
                     Located::new(
-                        InlineElement::from(Link::new_wiki_link(URIReference::try_from("links").unwrap(), None)),
+                        InlineElement::from(Link::new_wiki_link(WikiUri::try_from("links").unwrap(), None)),
                         Region::new_at_depth(118, 9, 1),
                     ),
                     Located::new(
@@ -331,3 +331,41 @@ fn test() {
         Diff::from_debug(&page, &expected_page, "Actual Page", "Expected Page"),
     );
 }
+
+#[test]
+fn nesting_beyond_max_depth_should_parse_the_rest_as_text() {
+    let contents: String = (0..200)
+        .map(|i| format!("{}- item {}\n", " ".repeat(i * 2), i))
+        .collect();
+
+    let page: Page = Language::from_vimwiki_str(&contents).parse().unwrap();
+
+    // Every item nested within the maximum depth remains a list item, in
+    // order, with its text intact
+    let items = page
+        .descendants()
+        .filter(|e| e.kind_name() == "list_item")
+        .count();
+    let texts: Vec<String> = page
+        .descendants()
+        .filter_map(|e| {
+            e.as_inline_of::<Text>()
+                .map(|x| x.as_str().trim().to_string())
+        })
+        .collect();
+    assert!(items >= 20, "Only {} items were nested", items);
+    for (i, text) in texts.iter().take(items).enumerate() {
+        assert_eq!(text, &format!("item {}", i));
+    }
+
+    // Items nested beyond the maximum depth are kept as plain text
+    let rest = texts[items..].join(" ");
+    for i in items..200 {
+        assert!(
+            rest.contains(&format!("item {}", i)),
+            "Missing item {} in {:?}",
+            i,
+            rest
+        );
+    }
+}
@@ -139,7 +139,10 @@ fn test() {
         ))),
     );
 
-    assert_eq!(page[7], Located::from(BlockElement::from(Divider::new())),);
+    assert_eq!(
+        page[7],
+        Located::from(BlockElement::from(Divider::new(4, 1, 1))),
+    );
 
     assert_eq!(
         page[8],
@@ -0,0 +1,104 @@
+#ifndef VIMWIKI_H
+#define VIMWIKI_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque handle to a parsed page */
+typedef struct VimwikiPage vimwiki_page_t;
+
+/* Region of text (in bytes) that an element occupies */
+typedef struct {
+    size_t offset;
+    size_t len;
+    uint16_t depth;
+} vimwiki_region_t;
+
+/* Type of an element; discriminants are stable and only ever appended */
+typedef enum {
+    VIMWIKI_BLOCKQUOTE = 0,
+    VIMWIKI_CODE_BLOCK = 1,
+    VIMWIKI_DEFINITION_LIST = 2,
+    VIMWIKI_DIVIDER = 3,
+    VIMWIKI_HEADER = 4,
+    VIMWIKI_LIST = 5,
+    VIMWIKI_MATH_BLOCK = 6,
+    VIMWIKI_PARAGRAPH = 7,
+    VIMWIKI_PLACEHOLDER = 8,
+    VIMWIKI_TABLE = 9,
+    VIMWIKI_LIST_ITEM = 10,
+    VIMWIKI_TERM = 11,
+    VIMWIKI_DEFINITION = 12,
+    VIMWIKI_TEXT = 13,
+    VIMWIKI_DECORATED_TEXT = 14,
+    VIMWIKI_KEYWORD = 15,
+    VIMWIKI_LINK = 16,
+    VIMWIKI_TAGS = 17,
+    VIMWIKI_CODE_INLINE = 18,
+    VIMWIKI_MATH_INLINE = 19,
+    VIMWIKI_COMMENT = 20,
+    VIMWIKI_FOOTNOTE_DEFINITION = 21,
+    VIMWIKI_FOOTNOTE_REFERENCE = 22,
+} vimwiki_element_kind_t;
+
+/* Element within a page */
+typedef struct {
+    vimwiki_element_kind_t kind;
+    vimwiki_region_t region;
+} vimwiki_element_t;
+
+/*
+ * Parses len bytes of UTF-8 text, returning NULL on failure. If error is not
+ * NULL, it is set to a message on failure that must be freed with
+ * vimwiki_string_free. The page must be freed with vimwiki_page_free.
+ */
+vimwiki_page_t *vimwiki_parse(const char *text, size_t len, char **error);
+
+/* Frees a page; does nothing if NULL */
+void vimwiki_page_free(vimwiki_page_t *page);
+
+/* Returns the total top-level elements within the page */
+size_t vimwiki_page_element_count(const vimwiki_page_t *page);
+
+/* Writes the top-level element at idx into out, returning false if missing */
+bool vimwiki_page_element_at(
+    const vimwiki_page_t *page,
+    size_t idx,
+    vimwiki_element_t *out
+);
+
+/*
+ * Writes the most deeply-nested element containing the byte offset into out,
+ * returning false if no element exists at the offset
+ */
+bool vimwiki_page_element_at_offset(
+    const vimwiki_page_t *page,
+    size_t offset,
+    vimwiki_element_t *out
+);
+
+/*
+ * Renders the page as HTML, returning NULL on failure. The returned string
+ * must be freed with vimwiki_string_free.
+ */
+char *vimwiki_page_to_html(const vimwiki_page_t *page, char **error);
+
+/*
+ * Returns a static name for the kind that must not be freed, or NULL if the
+ * kind is unknown
+ */
+const char *vimwiki_element_kind_name(uint32_t kind);
+
+/* Frees a string produced by this library; does nothing if NULL */
+void vimwiki_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* VIMWIKI_H */
@@ -0,0 +1,207 @@
+use std::os::raw::c_char;
+use vimwiki::{
+    self as v, BlockElement, InlineBlockElement, InlineElement, IntoChildren,
+    Located,
+};
+
+/// Represents the region of text that an element occupies, mirroring the
+/// layout of `vimwiki_region_t` in the C header
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VimwikiRegion {
+    /// Byte offset within the parsed text where the region begins
+    pub offset: usize,
+
+    /// Total bytes from the offset that the region covers
+    pub len: usize,
+
+    /// How deeply nested the element is within the page
+    pub depth: u16,
+}
+
+impl From<v::Region> for VimwikiRegion {
+    fn from(region: v::Region) -> Self {
+        Self {
+            offset: region.offset(),
+            len: region.len(),
+            depth: region.depth(),
+        }
+    }
+}
+
+/// Represents the type of an element, mirroring `vimwiki_element_kind_t` in
+/// the C header; discriminants are fixed and new kinds are only ever appended
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VimwikiElementKind {
+    Blockquote = 0,
+    CodeBlock = 1,
+    DefinitionList = 2,
+    Divider = 3,
+    Header = 4,
+    List = 5,
+    MathBlock = 6,
+    Paragraph = 7,
+    Placeholder = 8,
+    Table = 9,
+    ListItem = 10,
+    Term = 11,
+    Definition = 12,
+    Text = 13,
+    DecoratedText = 14,
+    Keyword = 15,
+    Link = 16,
+    Tags = 17,
+    CodeInline = 18,
+    MathInline = 19,
+    Comment = 20,
+    FootnoteDefinition = 21,
+    FootnoteReference = 22,
+}
+
+impl VimwikiElementKind {
+    /// Returns the kind with the given discriminant, or none if the value
+    /// does not match any known kind
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Blockquote),
+            1 => Some(Self::CodeBlock),
+            2 => Some(Self::DefinitionList),
+            3 => Some(Self::Divider),
+            4 => Some(Self::Header),
+            5 => Some(Self::List),
+            6 => Some(Self::MathBlock),
+            7 => Some(Self::Paragraph),
+            8 => Some(Self::Placeholder),
+            9 => Some(Self::Table),
+            10 => Some(Self::ListItem),
+            11 => Some(Self::Term),
+            12 => Some(Self::Definition),
+            13 => Some(Self::Text),
+            14 => Some(Self::DecoratedText),
+            15 => Some(Self::Keyword),
+            16 => Some(Self::Link),
+            17 => Some(Self::Tags),
+            18 => Some(Self::CodeInline),
+            19 => Some(Self::MathInline),
+            20 => Some(Self::Comment),
+            21 => Some(Self::FootnoteDefinition),
+            22 => Some(Self::FootnoteReference),
+            _ => None,
+        }
+    }
+
+    /// Returns a static, NUL-terminated name of the kind
+    pub fn name(self) -> &'static [u8] {
+        match self {
+            Self::Blockquote => b"blockquote\0",
+            Self::CodeBlock => b"code_block\0",
+            Self::DefinitionList => b"definition_list\0",
+            Self::Divider => b"divider\0",
+            Self::Header => b"header\0",
+            Self::List => b"list\0",
+            Self::MathBlock => b"math_block\0",
+            Self::Paragraph => b"paragraph\0",
+            Self::Placeholder => b"placeholder\0",
+            Self::Table => b"table\0",
+            Self::ListItem => b"list_item\0",
+            Self::Term => b"term\0",
+            Self::Definition => b"definition\0",
+            Self::Text => b"text\0",
+            Self::DecoratedText => b"decorated_text\0",
+            Self::Keyword => b"keyword\0",
+            Self::Link => b"link\0",
+            Self::Tags => b"tags\0",
+            Self::CodeInline => b"code_inline\0",
+            Self::MathInline => b"math_inline\0",
+            Self::Comment => b"comment\0",
+            Self::FootnoteDefinition => b"footnote_definition\0",
+            Self::FootnoteReference => b"footnote_reference\0",
+        }
+    }
+
+    /// Returns a pointer to the static, NUL-terminated name of the kind
+    pub fn name_ptr(self) -> *const c_char {
+        self.name().as_ptr() as *const c_char
+    }
+}
+
+impl<'a> From<&v::Element<'a>> for VimwikiElementKind {
+    fn from(element: &v::Element<'a>) -> Self {
+        match element {
+            v::Element::Block(x) => match x {
+                BlockElement::Blockquote(_) => Self::Blockquote,
+                BlockElement::CodeBlock(_) => Self::CodeBlock,
+                BlockElement::DefinitionList(_) => Self::DefinitionList,
+                BlockElement::Divider(_) => Self::Divider,
+                BlockElement::Header(_) => Self::Header,
+                BlockElement::List(_) => Self::List,
+                BlockElement::MathBlock(_) => Self::MathBlock,
+                BlockElement::Paragraph(_) => Self::Paragraph,
+                BlockElement::Placeholder(_) => Self::Placeholder,
+                BlockElement::Table(_) => Self::Table,
+                BlockElement::FootnoteDefinition(_) => Self::FootnoteDefinition,
+            },
+            v::Element::InlineBlock(x) => match x {
+                InlineBlockElement::ListItem(_) => Self::ListItem,
+                InlineBlockElement::Term(_) => Self::Term,
+                InlineBlockElement::Definition(_) => Self::Definition,
+            },
+            v::Element::Inline(x) => match x {
+                InlineElement::Text(_) => Self::Text,
+                InlineElement::DecoratedText(_) => Self::DecoratedText,
+                InlineElement::Keyword(_) => Self::Keyword,
+                InlineElement::Link(_) => Self::Link,
+                InlineElement::Tags(_) => Self::Tags,
+                InlineElement::Code(_) => Self::CodeInline,
+                InlineElement::Math(_) => Self::MathInline,
+                InlineElement::Comment(_) => Self::Comment,
+                InlineElement::FootnoteReference(_) => Self::FootnoteReference,
+            },
+        }
+    }
+}
+
+/// Represents an element within a page, mirroring `vimwiki_element_t` in the
+/// C header
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VimwikiElement {
+    /// Type of the element
+    pub kind: VimwikiElementKind,
+
+    /// Region of text that the element occupies
+    pub region: VimwikiRegion,
+}
+
+impl<'a> From<&Located<v::Element<'a>>> for VimwikiElement {
+    fn from(element: &Located<v::Element<'a>>) -> Self {
+        Self {
+            kind: VimwikiElementKind::from(element.as_inner()),
+            region: VimwikiRegion::from(element.region()),
+        }
+    }
+}
+
+/// Finds the most deeply-nested element within the page whose region
+/// contains the given offset
+pub fn deepest_element_at_offset(
+    page: &v::Page,
+    offset: usize,
+) -> Option<VimwikiElement> {
+    let mut found = None;
+    let mut candidates: Vec<Located<v::Element>> = page
+        .elements()
+        .iter()
+        .map(|x| x.as_ref().map(|x| v::Element::from(x.to_borrowed())))
+        .collect();
+
+    while let Some(next) =
+        candidates.into_iter().find(|x| x.region().contains(offset))
+    {
+        found = Some(VimwikiElement::from(&next));
+        candidates = next.into_inner().into_children();
+    }
+
+    found
+}
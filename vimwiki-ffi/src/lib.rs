@@ -0,0 +1,311 @@
+use std::{
+    ffi::CString,
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice, str,
+};
+use vimwiki::{self as v, HtmlConfig, Language, ParseError, ToHtmlString};
+
+mod elements;
+pub use elements::*;
+
+mod utils;
+use utils::{clear_error, into_c_string, set_error};
+
+/// Opaque handle to a parsed page, mirroring `vimwiki_page_t` in the C header
+pub struct VimwikiPage(v::Page<'static>);
+
+/// Parses `len` bytes of UTF-8 vimwiki text into a page, returning null if
+/// the text could not be parsed. The returned page must be released using
+/// `vimwiki_page_free`.
+///
+/// # Safety
+///
+/// `text` must point to at least `len` readable bytes and `error` must either
+/// be null or point to writable memory for a pointer. If an error occurs, a
+/// message is written to `error` that must be released using
+/// `vimwiki_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn vimwiki_parse(
+    text: *const c_char,
+    len: usize,
+    error: *mut *mut c_char,
+) -> *mut VimwikiPage {
+    clear_error(error);
+
+    if text.is_null() {
+        set_error(error, "text is null");
+        return ptr::null_mut();
+    }
+
+    let bytes = slice::from_raw_parts(text as *const u8, len);
+    let text = match str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(x) => {
+            set_error(error, x.to_string());
+            return ptr::null_mut();
+        }
+    };
+
+    let result = panic::catch_unwind(|| {
+        let page: Result<v::Page, ParseError> =
+            Language::from_vimwiki_str(text).parse();
+        page.map(v::Page::into_owned).map_err(|x| x.to_string())
+    });
+
+    match result {
+        Ok(Ok(page)) => Box::into_raw(Box::new(VimwikiPage(page))),
+        Ok(Err(x)) => {
+            set_error(error, x);
+            ptr::null_mut()
+        }
+        Err(_) => {
+            set_error(error, "parser panicked");
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a page produced by `vimwiki_parse`; does nothing if null
+///
+/// # Safety
+///
+/// `page` must either be null or have been produced by `vimwiki_parse` and
+/// not yet released
+#[no_mangle]
+pub unsafe extern "C" fn vimwiki_page_free(page: *mut VimwikiPage) {
+    if !page.is_null() {
+        drop(Box::from_raw(page));
+    }
+}
+
+/// Returns the total top-level elements within the page, or 0 if null
+///
+/// # Safety
+///
+/// `page` must either be null or a valid page produced by `vimwiki_parse`
+#[no_mangle]
+pub unsafe extern "C" fn vimwiki_page_element_count(
+    page: *const VimwikiPage,
+) -> usize {
+    page.as_ref()
+        .map(|p| p.0.elements().len())
+        .unwrap_or_default()
+}
+
+/// Writes the top-level element at the given index into `out`, returning
+/// false if the page is null or the index is out of bounds
+///
+/// # Safety
+///
+/// `page` must either be null or a valid page produced by `vimwiki_parse`
+/// and `out` must either be null or point to writable memory for an element
+#[no_mangle]
+pub unsafe extern "C" fn vimwiki_page_element_at(
+    page: *const VimwikiPage,
+    idx: usize,
+    out: *mut VimwikiElement,
+) -> bool {
+    let element = page.as_ref().and_then(|p| {
+        p.0.elements().get(idx).map(|x| {
+            VimwikiElement::from(
+                &x.as_ref().map(|x| v::Element::from(x.to_borrowed())),
+            )
+        })
+    });
+
+    write_element(element, out)
+}
+
+/// Writes the most deeply-nested element whose region contains the byte
+/// offset into `out`, returning false if the page is null or no element
+/// exists at the offset
+///
+/// # Safety
+///
+/// `page` must either be null or a valid page produced by `vimwiki_parse`
+/// and `out` must either be null or point to writable memory for an element
+#[no_mangle]
+pub unsafe extern "C" fn vimwiki_page_element_at_offset(
+    page: *const VimwikiPage,
+    offset: usize,
+    out: *mut VimwikiElement,
+) -> bool {
+    let element = page
+        .as_ref()
+        .and_then(|p| deepest_element_at_offset(&p.0, offset));
+
+    write_element(element, out)
+}
+
+unsafe fn write_element(
+    element: Option<VimwikiElement>,
+    out: *mut VimwikiElement,
+) -> bool {
+    match element {
+        Some(element) if !out.is_null() => {
+            *out = element;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Renders the page as HTML using the default configuration, returning null
+/// if the page could not be rendered. The returned string must be released
+/// using `vimwiki_string_free`.
+///
+/// # Safety
+///
+/// `page` must either be null or a valid page produced by `vimwiki_parse`
+/// and `error` must either be null or point to writable memory for a pointer
+#[no_mangle]
+pub unsafe extern "C" fn vimwiki_page_to_html(
+    page: *const VimwikiPage,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    clear_error(error);
+
+    let page = match page.as_ref() {
+        Some(page) => page,
+        None => {
+            set_error(error, "page is null");
+            return ptr::null_mut();
+        }
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        page.0.to_html_string(HtmlConfig::default())
+    }));
+
+    match result {
+        Ok(Ok(html)) => into_c_string(html),
+        Ok(Err(x)) => {
+            set_error(error, x.to_string());
+            ptr::null_mut()
+        }
+        Err(_) => {
+            set_error(error, "html output panicked");
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns a static, NUL-terminated name for the element kind that must not
+/// be released, or null if the kind is unknown
+///
+/// The kind is taken as its integer value as C can pass any value in place
+/// of the enum, which would be undefined behavior for a Rust enum
+#[no_mangle]
+pub extern "C" fn vimwiki_element_kind_name(kind: u32) -> *const c_char {
+    VimwikiElementKind::from_u32(kind)
+        .map_or(ptr::null(), VimwikiElementKind::name_ptr)
+}
+
+/// Releases a string produced by this library; does nothing if null
+///
+/// # Safety
+///
+/// `s` must either be null or have been produced by this library and not yet
+/// released
+#[no_mangle]
+pub unsafe extern "C" fn vimwiki_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    unsafe fn parse(text: &str) -> *mut VimwikiPage {
+        vimwiki_parse(
+            text.as_ptr() as *const c_char,
+            text.len(),
+            ptr::null_mut(),
+        )
+    }
+
+    #[test]
+    fn parse_should_report_invalid_utf8_as_error() {
+        let bytes = [b'a', 0xFF, b'b'];
+        let mut error = ptr::null_mut();
+        unsafe {
+            let page = vimwiki_parse(
+                bytes.as_ptr() as *const c_char,
+                bytes.len(),
+                &mut error,
+            );
+            assert!(page.is_null());
+            assert!(!error.is_null());
+            vimwiki_string_free(error);
+        }
+    }
+
+    #[test]
+    fn page_element_at_should_yield_top_level_elements() {
+        unsafe {
+            let page = parse("= header =\n\nsome text");
+            assert_eq!(vimwiki_page_element_count(page), 2);
+
+            let mut element = VimwikiElement {
+                kind: VimwikiElementKind::Text,
+                region: VimwikiRegion::default(),
+            };
+            assert!(vimwiki_page_element_at(page, 1, &mut element));
+            assert_eq!(element.kind, VimwikiElementKind::Paragraph);
+            assert_eq!(element.region.offset, 12);
+            assert!(!vimwiki_page_element_at(page, 2, &mut element));
+
+            vimwiki_page_free(page);
+        }
+    }
+
+    #[test]
+    fn page_element_at_offset_should_yield_deepest_element() {
+        unsafe {
+            let page = parse("some *bold* text");
+            let mut element = VimwikiElement {
+                kind: VimwikiElementKind::Paragraph,
+                region: VimwikiRegion::default(),
+            };
+            assert!(vimwiki_page_element_at_offset(page, 6, &mut element));
+            assert_eq!(element.kind, VimwikiElementKind::Text);
+            assert!(vimwiki_page_element_at_offset(page, 5, &mut element));
+            assert_eq!(element.kind, VimwikiElementKind::DecoratedText);
+            assert!(!vimwiki_page_element_at_offset(page, 99, &mut element));
+
+            vimwiki_page_free(page);
+        }
+    }
+
+    #[test]
+    fn page_to_html_should_render_page() {
+        unsafe {
+            let page = parse("some text");
+            let html = vimwiki_page_to_html(page, ptr::null_mut());
+            assert_eq!(
+                CStr::from_ptr(html).to_str().unwrap(),
+                "<p>some text</p>\n"
+            );
+
+            vimwiki_string_free(html);
+            vimwiki_page_free(page);
+        }
+    }
+
+    #[test]
+    fn element_kind_name_should_be_nul_terminated() {
+        let name =
+            vimwiki_element_kind_name(VimwikiElementKind::CodeInline as u32);
+        let name = unsafe { CStr::from_ptr(name) };
+        assert_eq!(name.to_str().unwrap(), "code_inline");
+    }
+
+    #[test]
+    fn element_kind_name_should_be_null_for_unknown_kind() {
+        assert!(vimwiki_element_kind_name(u32::MAX).is_null());
+    }
+}
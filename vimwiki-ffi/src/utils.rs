@@ -0,0 +1,37 @@
+use std::{ffi::CString, os::raw::c_char, ptr};
+
+/// Converts text into a heap-allocated, NUL-terminated string owned by the
+/// caller, replacing any interior NUL bytes so the conversion cannot fail
+pub fn into_c_string(text: String) -> *mut c_char {
+    let text = if text.contains('\0') {
+        text.replace('\0', "\u{FFFD}")
+    } else {
+        text
+    };
+
+    CString::new(text)
+        .map(CString::into_raw)
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Writes the message into the error out-parameter if one was provided
+///
+/// # Safety
+///
+/// `error` must either be null or point to writable memory for a pointer
+pub unsafe fn set_error(error: *mut *mut c_char, msg: impl Into<String>) {
+    if !error.is_null() {
+        *error = into_c_string(msg.into());
+    }
+}
+
+/// Clears the error out-parameter if one was provided
+///
+/// # Safety
+///
+/// `error` must either be null or point to writable memory for a pointer
+pub unsafe fn clear_error(error: *mut *mut c_char) {
+    if !error.is_null() {
+        *error = ptr::null_mut();
+    }
+}
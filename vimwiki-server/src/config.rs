@@ -4,19 +4,63 @@ use std::{
     io,
     path::{Component, PathBuf},
 };
+use vimwiki::{
+    resolve::{LinkResolver, LinkSpaces, ResolverWiki},
+    schemes::SchemeTemplate,
+    settings::Settings,
+    HtmlConfig, HtmlOutputConfig, HtmlWikiConfig, ParserConfig,
+};
 
 /// Represents a config file that can be loaded and used by the server
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Config {
     /// Contains configs for individual wikis
     #[serde(default)]
     pub wikis: Vec<WikiConfig>,
+
+    /// Contains settings for integrating with git
+    #[serde(default)]
+    pub git: GitConfig,
+
+    /// Custom uri schemes (e.g. `jira:PROJ-123`) that are recognized as raw
+    /// links and rendered using templates
+    #[serde(default)]
+    pub schemes: Vec<SchemeTemplate>,
+
+    /// Settings for rendering HTML loaded from the vimwiki.toml files, onto
+    /// which the configured wikis are placed
+    #[serde(skip)]
+    pub html: HtmlConfig,
+
+    /// Settings that change what is recognized when parsing pages, loaded
+    /// from the vimwiki.toml files
+    #[serde(skip)]
+    pub syntax: ParserConfig,
 }
 
 impl Config {
-    /// Loads config using provided options
-    pub fn load(opt: &Opt) -> io::Result<Config> {
-        utils::load_config(opt.config.as_deref(), opt.merge)
+    /// Loads config using provided options, falling back to the wikis of
+    /// the settings if the config file defines none
+    pub fn load(opt: &Opt, settings: &Settings) -> io::Result<Config> {
+        utils::load_config(opt.config.as_deref(), settings, opt.merge)
+    }
+
+    /// Produces a resolver of links between the files of the configured
+    /// wikis
+    pub fn to_link_resolver(&self) -> LinkResolver {
+        self.wikis
+            .iter()
+            .map(ResolverWiki::from)
+            .fold(LinkResolver::new(), LinkResolver::with_wiki)
+    }
+
+    /// Produces a config for rendering the files of the configured wikis
+    /// as HTML
+    pub fn to_html_config(&self) -> HtmlConfig {
+        HtmlConfig {
+            wikis: self.wikis.iter().map(HtmlWikiConfig::from).collect(),
+            ..self.html.clone()
+        }
     }
 }
 
@@ -42,6 +86,20 @@ pub struct WikiConfig {
     /// File extension for files within a wiki to load and parse
     #[serde(default = "WikiConfig::default_ext")]
     pub ext: String,
+
+    /// Name of the page (without extension) that a link to a directory
+    /// within the wiki targets
+    #[serde(default = "WikiConfig::default_index")]
+    pub index: String,
+
+    /// How spaces within links to pages appear within the names of the
+    /// wiki's files
+    #[serde(default)]
+    pub link_spaces: LinkSpaces,
+
+    /// How the pages of this wiki map to output paths and urls
+    #[serde(default)]
+    pub output: HtmlOutputConfig,
 }
 
 impl Default for WikiConfig {
@@ -51,6 +109,52 @@ impl Default for WikiConfig {
             name: Self::default_name(),
             diary_rel_path: Self::default_diary_rel_path(),
             ext: Self::default_ext(),
+            index: Self::default_index(),
+            link_spaces: LinkSpaces::default(),
+            output: HtmlOutputConfig::default(),
+        }
+    }
+}
+
+impl From<&WikiConfig> for ResolverWiki {
+    fn from(config: &WikiConfig) -> Self {
+        let wiki = Self::new(config.path.as_path())
+            .with_ext(config.ext.as_str())
+            .with_index(config.index.as_str())
+            .with_diary_rel_path(config.diary_rel_path.as_path())
+            .with_link_spaces(config.link_spaces);
+        match config.name.as_deref() {
+            Some(name) => wiki.with_name(name),
+            None => wiki,
+        }
+    }
+}
+
+impl From<&WikiConfig> for HtmlWikiConfig {
+    fn from(config: &WikiConfig) -> Self {
+        Self {
+            path: config.path.to_path_buf(),
+            name: config.name.clone(),
+            diary_rel_path: config.diary_rel_path.to_path_buf(),
+            ext: config.ext.to_string(),
+            index: config.index.to_string(),
+            link_spaces: config.link_spaces,
+            output: config.output.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&HtmlWikiConfig> for WikiConfig {
+    fn from(config: &HtmlWikiConfig) -> Self {
+        Self {
+            path: config.path.to_path_buf(),
+            name: config.name.clone(),
+            diary_rel_path: config.diary_rel_path.to_path_buf(),
+            ext: config.ext.to_string(),
+            index: config.index.to_string(),
+            link_spaces: config.link_spaces,
+            output: config.output.clone(),
         }
     }
 }
@@ -82,4 +186,66 @@ impl WikiConfig {
     pub fn default_ext() -> String {
         String::from("wiki")
     }
+
+    #[inline]
+    pub fn default_index() -> String {
+        String::from("index")
+    }
+}
+
+/// Represents settings for integrating with git repositories containing
+/// wiki files, which are ignored unless the server is built with the `git`
+/// feature
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitConfig {
+    /// If true, edits applied by the server are committed to the git
+    /// repository containing the edited file
+    #[serde(default)]
+    pub auto_commit: bool,
+
+    /// Message used for automatic commits, where `{path}` and `{description}`
+    /// are replaced with the edited file's path and the edit's description
+    #[serde(default = "GitConfig::default_commit_message")]
+    pub commit_message: String,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            auto_commit: false,
+            commit_message: Self::default_commit_message(),
+        }
+    }
+}
+
+impl GitConfig {
+    #[inline]
+    pub fn default_commit_message() -> String {
+        String::from("Update {path}: {description}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wiki_config_should_keep_link_spaces_through_conversions() {
+        let mut html = HtmlWikiConfig {
+            link_spaces: LinkSpaces::Underscore,
+            ..Default::default()
+        };
+        html.output.link_spaces = LinkSpaces::Space;
+
+        let config = WikiConfig::from(&html);
+        assert_eq!(config.link_spaces, LinkSpaces::Underscore);
+        assert_eq!(config.output.link_spaces, LinkSpaces::Space);
+
+        let html = HtmlWikiConfig::from(&config);
+        assert_eq!(html.link_spaces, LinkSpaces::Underscore);
+        assert_eq!(html.output.link_spaces, LinkSpaces::Space);
+
+        let wiki = ResolverWiki::from(&config);
+        assert_eq!(wiki.link_spaces, LinkSpaces::Underscore);
+    }
 }
@@ -0,0 +1,24 @@
+use vimwiki::graph as g;
+
+/// Represents pages of a wiki that likely need attention
+#[derive(Clone, Debug, Default, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct PageAnalysis {
+    /// Pages (other than the index) that no other page links to
+    orphans: Vec<String>,
+
+    /// Pages whose content is smaller than the stub threshold
+    stubs: Vec<String>,
+
+    /// Pages that cannot be reached by following links from the index
+    unreachable: Vec<String>,
+}
+
+impl From<g::PageAnalysis> for PageAnalysis {
+    fn from(analysis: g::PageAnalysis) -> Self {
+        Self {
+            orphans: analysis.orphans,
+            stubs: analysis.stubs,
+            unreachable: analysis.unreachable,
+        }
+    }
+}
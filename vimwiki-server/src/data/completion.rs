@@ -0,0 +1,96 @@
+use super::{to_gql_error, Wiki};
+use crate::{database::gql_db, overlay};
+use entity::*;
+use std::path::Path;
+use vimwiki::completion as c;
+
+/// Represents the kind of item being completed
+#[derive(async_graphql::Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CompletionKind {
+    /// Name of a page, completed within a link such as `[[Proj`
+    Page,
+
+    /// Header within a page, completed within a link such as `[[page#Intr`
+    Anchor,
+
+    /// Tag, completed within tags such as `:wo`
+    Tag,
+}
+
+impl From<c::CompletionKind> for CompletionKind {
+    fn from(kind: c::CompletionKind) -> Self {
+        match kind {
+            c::CompletionKind::Page => Self::Page,
+            c::CompletionKind::Anchor => Self::Anchor,
+            c::CompletionKind::Tag => Self::Tag,
+        }
+    }
+}
+
+/// Represents a candidate to complete some partial input
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct Completion {
+    /// Kind of item being completed
+    kind: CompletionKind,
+
+    /// Text that replaces the partial input being completed
+    text: String,
+
+    /// How closely the candidate matches the partial input, where a higher
+    /// score is a better match
+    score: u32,
+}
+
+impl From<c::Completion> for Completion {
+    fn from(completion: c::Completion) -> Self {
+        Self {
+            kind: CompletionKind::from(completion.kind),
+            text: completion.text,
+            score: completion.score,
+        }
+    }
+}
+
+/// Produces up to `limit` completions for the end of the input, ranked from
+/// best to worst match
+///
+/// When given the path of the file being edited, only pages of the wiki
+/// containing the file are completed and pages are completed relative to
+/// the file. Otherwise, pages of every wiki are completed.
+pub async fn completions(
+    input: &str,
+    path: Option<&Path>,
+    limit: Option<usize>,
+) -> async_graphql::Result<Vec<Completion>> {
+    let path = match path {
+        Some(path) => Some(overlay::key(path).await),
+        None => None,
+    };
+
+    let all_wikis = gql_db()?
+        .find_all_typed::<Wiki>(Wiki::query().into())
+        .map_err(to_gql_error)?;
+    let (wikis, current): (Vec<Wiki>, Option<String>) =
+        match path.as_ref().and_then(|path| {
+            all_wikis
+                .iter()
+                .find_map(|w| w.page_name(path).map(|name| (w.clone(), name)))
+        }) {
+            Some((wiki, name)) => (vec![wiki], Some(name)),
+            None => (all_wikis, None),
+        };
+
+    let mut index = c::CompletionIndex::new();
+    for wiki in wikis {
+        for page in wiki.indexed_pages().await? {
+            index.add_page(&page.name, &page.indexed.page);
+        }
+    }
+
+    let mut completions = index.complete(input, current.as_deref());
+    if let Some(limit) = limit {
+        completions.truncate(limit);
+    }
+
+    Ok(completions.into_iter().map(Completion::from).collect())
+}
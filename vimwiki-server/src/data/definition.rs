@@ -0,0 +1,355 @@
+use super::{to_gql_error, ParsedFile, Region};
+use crate::{overlay, syntax};
+use lazy_static::lazy_static;
+use std::{path::Path, sync::RwLock};
+use vimwiki::{
+    graph::collect_links,
+    hover::{summarize_task, PageSummary},
+    images::page_images as collect_page_images,
+    linking::{make_link, render_page_template},
+    resolve::{
+        find_anchor, BrokenLink, LinkResolver, LinkTarget as ResolvedTarget,
+        ResolverWiki,
+    },
+    tasks::find_task_at_offset,
+    Link, Located, Page, WikiDate,
+};
+
+lazy_static! {
+    static ref LINK_RESOLVER: RwLock<LinkResolver> =
+        RwLock::new(LinkResolver::default());
+}
+
+/// Sets the resolver used by the server to find the targets of links
+pub fn set_link_resolver(resolver: LinkResolver) {
+    if let Ok(mut x) = LINK_RESOLVER.write() {
+        *x = resolver;
+    }
+}
+
+/// Represents the concrete target of a link within a page
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct LinkTarget {
+    /// Path to the file targeted by the link, if it is local
+    path: Option<String>,
+
+    /// Uri of the resource targeted by the link, if it is remote
+    uri: Option<String>,
+
+    /// Anchor within the targeted file (e.g. `#one#two`), if there is one
+    anchor: Option<String>,
+
+    /// Region of the header or tag within the targeted file matching the
+    /// anchor, if the file could be read and the anchor was found
+    region: Option<Region>,
+}
+
+impl From<ResolvedTarget> for LinkTarget {
+    fn from(target: ResolvedTarget) -> Self {
+        match target {
+            ResolvedTarget::File { path, anchor } => Self {
+                path: Some(path.to_string_lossy().to_string()),
+                uri: None,
+                anchor: anchor.map(|x| x.to_string()),
+                region: None,
+            },
+            ResolvedTarget::Uri(uri) => Self {
+                path: None,
+                uri: Some(uri),
+                anchor: None,
+                region: None,
+            },
+        }
+    }
+}
+
+/// Represents content describing the element at some position within a
+/// page, such as a preview of the page targeted by a link
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct Hover {
+    /// Plain text describing the element
+    contents: String,
+
+    /// Region of the element being described
+    region: Region,
+}
+
+/// Represents a wiki link created in place of text within a file
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct CreatedLink {
+    /// Region of the new link within the file
+    region: Region,
+
+    /// Concrete target of the new link
+    target: LinkTarget,
+
+    /// True if the page targeted by the link did not exist and was created
+    created_page: bool,
+}
+
+/// Represents an attribute of an image, such as its style or width
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct ImageProperty {
+    name: String,
+    value: String,
+}
+
+/// Represents an image transcluded within a page
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct PageImage {
+    /// Source of the image as it was written
+    src: String,
+
+    /// Concrete location of the image, if it could be resolved
+    target: Option<LinkTarget>,
+
+    /// Alternate text of the image from its description
+    alt: Option<String>,
+
+    /// Attributes of the image sorted by name
+    properties: Vec<ImageProperty>,
+
+    /// Region of the link containing the image
+    region: Region,
+}
+
+/// Reads and parses the file at the given path (or its overlay), passing the
+/// page to the function
+async fn with_page<T>(
+    path: &Path,
+    f: impl FnOnce(&Page) -> T,
+) -> async_graphql::Result<T> {
+    let text = overlay::read_to_string(path).await.map_err(to_gql_error)?;
+    let page: Page = syntax::parse_page(&text).map_err(to_gql_error)?;
+    Ok(f(&page))
+}
+
+/// Finds the link within the page containing the given byte offset
+fn link_at_offset(
+    page: &Page,
+    offset: usize,
+) -> Option<Located<Link<'static>>> {
+    collect_links(page)
+        .into_iter()
+        .find(|link| link.region().contains(offset))
+        .map(|link| link.map(|x| x.clone().into_owned()))
+}
+
+/// Resolves the target of a link within the file at the given path
+fn resolve(path: &Path, link: &Link) -> async_graphql::Result<ResolvedTarget> {
+    let resolver = LINK_RESOLVER.read().map_err(to_gql_error)?;
+    resolver.resolve(path, link).map_err(to_gql_error)
+}
+
+/// Resolves the target of the link found at the given byte offset within the
+/// file at the given path, returning none if there is no link at the offset
+///
+/// When the link targets an anchor within a file that can be read, the
+/// region of the header or tag matching the anchor is included.
+pub async fn link_target_at_offset(
+    path: impl AsRef<Path>,
+    offset: usize,
+) -> async_graphql::Result<Option<LinkTarget>> {
+    let path = overlay::key(path).await;
+    let link =
+        match with_page(&path, |page| link_at_offset(page, offset)).await? {
+            Some(link) => link,
+            None => return Ok(None),
+        };
+
+    let target = resolve(&path, link.as_inner())?;
+    let region = match (target.path(), target.anchor()) {
+        (Some(path), Some(anchor)) => {
+            with_page(path, |page| find_anchor(page, anchor).map(Region::from))
+                .await
+                .ok()
+                .flatten()
+        }
+        _ => None,
+    };
+
+    let mut target = LinkTarget::from(target);
+    target.region = region;
+    Ok(Some(target))
+}
+
+/// Describes the element found at the given byte offset within the file at
+/// the given path, returning none if there is nothing to describe
+///
+/// A link is described by the title and first paragraph of the page it
+/// targets (or by its target when the page cannot be read), and a task is
+/// described by its status and description.
+pub async fn hover_at_offset(
+    path: impl AsRef<Path>,
+    offset: usize,
+) -> async_graphql::Result<Option<Hover>> {
+    let path = overlay::key(path).await;
+    let (link, task) = with_page(&path, |page| {
+        (
+            link_at_offset(page, offset),
+            find_task_at_offset(page, offset),
+        )
+    })
+    .await?;
+
+    if let Some(link) = link {
+        let contents = match resolve(&path, link.as_inner())? {
+            ResolvedTarget::File { path, anchor } => {
+                let summary = with_page(&path, PageSummary::from_page)
+                    .await
+                    .ok()
+                    .filter(|x| !x.is_empty());
+                let target = format!(
+                    "{}{}",
+                    path.to_string_lossy(),
+                    anchor.map(|x| x.to_string()).unwrap_or_default()
+                );
+                match summary {
+                    Some(summary) => format!("{}\n\n{}", summary, target),
+                    None => target,
+                }
+            }
+            ResolvedTarget::Uri(uri) => uri,
+        };
+
+        return Ok(Some(Hover {
+            contents,
+            region: Region::from(link.region()),
+        }));
+    }
+
+    Ok(task.map(|task| Hover {
+        contents: summarize_task(&task),
+        region: Region::from(task.region),
+    }))
+}
+
+/// Finds the links within the page of the file at the given path whose
+/// targets do not exist
+pub(crate) fn find_broken_links(
+    path: &Path,
+    page: &Page,
+) -> async_graphql::Result<Vec<BrokenLink>> {
+    let resolver = LINK_RESOLVER.read().map_err(to_gql_error)?;
+    Ok(resolver.find_broken_links(path, page))
+}
+
+/// Returns the wiki at the given index known to the resolver
+pub(crate) fn resolver_wiki(
+    index: usize,
+) -> async_graphql::Result<ResolverWiki> {
+    let resolver = LINK_RESOLVER.read().map_err(to_gql_error)?;
+    resolver
+        .wikis()
+        .get(index)
+        .cloned()
+        .ok_or_else(|| to_gql_error(format!("No wiki with index {}", index)))
+}
+
+/// Creates the page at the given path, along with its missing parent
+/// directories, from the template where `{title}` and `{date}` are filled
+/// in, returning true if the page did not exist and was created
+pub(crate) async fn create_page_from_template(
+    path: &Path,
+    template: Option<&str>,
+    title: &str,
+    date: WikiDate,
+) -> async_graphql::Result<bool> {
+    if tokio::fs::metadata(path).await.is_ok() {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(to_gql_error)?;
+    }
+
+    let contents =
+        render_page_template(template.unwrap_or_default(), title, date);
+    ParsedFile::create(None, path, contents, false).await?;
+    Ok(true)
+}
+
+/// Replaces the text within the region of the file at the given path with a
+/// wiki link, returning none if the region has no text to link or overlaps
+/// an existing link
+///
+/// An empty region links the word around its offset. The link targets the
+/// given page (keeping the text as its description), otherwise the text
+/// itself. If the targeted page does not exist, it is created from the
+/// template where `{title}` and `{date}` are filled in.
+pub async fn create_link(
+    path: impl AsRef<Path>,
+    region: vimwiki::Region,
+    target: Option<String>,
+    template: Option<String>,
+) -> async_graphql::Result<Option<CreatedLink>> {
+    let path = overlay::key(path).await;
+    let text = overlay::read_to_string(&path).await.map_err(to_gql_error)?;
+    let page: Page = syntax::parse_page(&text).map_err(to_gql_error)?;
+    let new_link = match make_link(&text, &page, region, target.as_deref()) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    let link_page: Page =
+        syntax::parse_page(&new_link.edit.text).map_err(to_gql_error)?;
+    let link = link_at_offset(&link_page, 0)
+        .ok_or_else(|| to_gql_error("Failed to parse new link"))?;
+    let region = Region::from(vimwiki::Region::new(
+        new_link.edit.region.offset(),
+        new_link.edit.text.len(),
+    ));
+
+    ParsedFile::edit(&path, String::from("create link"), vec![new_link.edit])
+        .await?;
+
+    let target = resolve(&path, link.as_inner())?;
+    let created_page = match target.path() {
+        Some(target_path) => {
+            let title = new_link.target.rsplit('/').next().unwrap_or_default();
+            create_page_from_template(
+                target_path,
+                template.as_deref(),
+                title,
+                WikiDate::today(),
+            )
+            .await?
+        }
+        None => false,
+    };
+
+    Ok(Some(CreatedLink {
+        region,
+        target: LinkTarget::from(target),
+        created_page,
+    }))
+}
+
+/// Lists every image of the file at the given path, including images used as
+/// the descriptions of links, alongside their resolved locations
+pub async fn page_images(
+    path: impl AsRef<Path>,
+) -> async_graphql::Result<Vec<PageImage>> {
+    let path = overlay::key(path).await;
+    let resolver = LINK_RESOLVER.read().map_err(to_gql_error)?.clone();
+    let images =
+        with_page(&path, |page| collect_page_images(&resolver, &path, page))
+            .await?;
+
+    Ok(images
+        .into_iter()
+        .map(|x| PageImage {
+            src: x.src,
+            target: x.target.map(LinkTarget::from),
+            alt: x.alt,
+            properties: x
+                .properties
+                .into_iter()
+                .map(|(name, value)| ImageProperty { name, value })
+                .collect(),
+            region: Region::from(x.region),
+        })
+        .collect())
+}
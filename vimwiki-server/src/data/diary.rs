@@ -0,0 +1,98 @@
+use super::{create_page_from_template, resolver_wiki, to_gql_error};
+use vimwiki::{
+    diary::{diary_entries, next_entry, previous_entry},
+    resolve::ResolverWiki,
+    WikiDate,
+};
+
+/// Represents the direction to move from one diary entry to another
+#[derive(async_graphql::Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DiaryDirection {
+    /// Towards older entries
+    Previous,
+
+    /// Towards newer entries
+    Next,
+}
+
+/// Represents the diary page of a wiki for some date
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct DiaryEntry {
+    /// Date of the entry in YYYY-MM-DD format
+    date: String,
+
+    /// Path to the file of the entry
+    path: String,
+
+    /// True if the file of the entry exists
+    exists: bool,
+}
+
+impl DiaryEntry {
+    async fn new(wiki: &ResolverWiki, date: WikiDate) -> Self {
+        let path = wiki.diary_path(date);
+        Self {
+            date: date.format("%Y-%m-%d").to_string(),
+            exists: tokio::fs::metadata(&path).await.is_ok(),
+            path: path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// Parses a date in YYYY-MM-DD format, defaulting to today, and shifts it by
+/// the given number of days (e.g. -1 for yesterday)
+pub fn diary_date(
+    date: Option<&str>,
+    days: i64,
+) -> async_graphql::Result<WikiDate> {
+    let date = match date {
+        Some(x) => WikiDate::parse_from_str(x, "%Y-%m-%d")
+            .map_err(|x| to_gql_error(format!("Invalid date: {}", x)))?,
+        None => WikiDate::today(),
+    };
+    Ok(date.add_days(days))
+}
+
+/// Describes the diary entry for the date within the wiki at the given index
+pub async fn diary_entry(
+    wiki: usize,
+    date: WikiDate,
+) -> async_graphql::Result<DiaryEntry> {
+    let wiki = resolver_wiki(wiki)?;
+    Ok(DiaryEntry::new(&wiki, date).await)
+}
+
+/// Creates the diary entry for the date within the wiki at the given index
+/// from the template if it does not exist, like `:VimwikiMakeDiaryNote`,
+/// where `{title}` and `{date}` are both filled in with the date
+pub async fn make_diary_note(
+    wiki: usize,
+    date: WikiDate,
+    template: Option<&str>,
+) -> async_graphql::Result<DiaryEntry> {
+    let wiki = resolver_wiki(wiki)?;
+    let title = date.format("%Y-%m-%d").to_string();
+    create_page_from_template(&wiki.diary_path(date), template, &title, date)
+        .await?;
+    Ok(DiaryEntry::new(&wiki, date).await)
+}
+
+/// Finds the closest existing diary entry before or after the date within
+/// the wiki at the given index, skipping dates without an entry
+pub async fn adjacent_diary_entry(
+    wiki: usize,
+    date: WikiDate,
+    direction: DiaryDirection,
+) -> async_graphql::Result<Option<DiaryEntry>> {
+    let wiki = resolver_wiki(wiki)?;
+    let entries = diary_entries(&wiki).map_err(to_gql_error)?;
+    let date = match direction {
+        DiaryDirection::Previous => previous_entry(&entries, date),
+        DiaryDirection::Next => next_entry(&entries, date),
+    };
+
+    Ok(match date {
+        Some(date) => Some(DiaryEntry::new(&wiki, date).await),
+        None => None,
+    })
+}
@@ -0,0 +1,54 @@
+use super::{to_gql_error, ParsedFile, Wiki};
+use crate::{database::gql_db, overlay, syntax};
+use entity::*;
+use std::path::Path;
+use vimwiki::{dynamic, edit, Page};
+
+/// Builds an index of every file of every wiki, named by their paths
+/// relative to their wiki, to evaluate the queries of dynamic blocks
+async fn query_index() -> async_graphql::Result<dynamic::QueryIndex> {
+    let mut index = dynamic::QueryIndex::new();
+
+    for wiki in gql_db()?
+        .find_all_typed::<Wiki>(Wiki::query().into())
+        .map_err(to_gql_error)?
+    {
+        for page in wiki.indexed_pages().await? {
+            index.add_page(&page.name, &page.indexed.page);
+        }
+    }
+
+    Ok(index)
+}
+
+impl ParsedFile {
+    /// Regenerates the content of the dynamic blocks within the file at the
+    /// path, leaving the rest of the file untouched
+    ///
+    /// If the file has an overlay, the overlay is updated instead of the file
+    /// on disk so that the editor's unsaved buffer is not overwritten.
+    /// Otherwise, the edits are journaled so they can be reverted.
+    pub async fn refresh_dynamic_blocks(
+        path: impl AsRef<Path>,
+    ) -> async_graphql::Result<Self> {
+        let path = overlay::key(path).await;
+        let index = query_index().await?;
+
+        let text =
+            overlay::read_to_string(&path).await.map_err(to_gql_error)?;
+        let page: Page = syntax::parse_page(&text).map_err(to_gql_error)?;
+        let edits =
+            dynamic::refresh(&text, &page, &index).map_err(to_gql_error)?;
+
+        if edits.is_empty() {
+            Self::load(None, path).await
+        } else if overlay::contains(&path) {
+            let text =
+                edit::apply_edits(&text, &edits).map_err(to_gql_error)?;
+            Self::set_overlay(None, path, text).await
+        } else {
+            Self::edit(path, String::from("refresh dynamic blocks"), edits)
+                .await
+        }
+    }
+}
@@ -11,6 +11,15 @@ pub struct Divider {
     #[ent(field(graphql(filter_untyped)))]
     region: Region,
 
+    /// Total hyphens making up the divider
+    len: i32,
+
+    /// Total blank lines immediately preceding the divider
+    blank_lines_before: i32,
+
+    /// Total blank lines immediately following the divider
+    blank_lines_after: i32,
+
     /// Page containing the element
     #[ent(edge)]
     page: Page,
@@ -28,9 +37,14 @@ impl<'a> FromVimwikiElement<'a> for Divider {
         parent_id: Option<Id>,
         element: Self::Element,
     ) -> Result<Self, GraphqlDatabaseError> {
+        let region = Region::from(element.region());
+        let divider = element.into_inner();
         GraphqlDatabaseError::wrap(
             Self::build()
-                .region(Region::from(element.region()))
+                .region(region)
+                .len(divider.len as i32)
+                .blank_lines_before(divider.blank_lines_before as i32)
+                .blank_lines_after(divider.blank_lines_after as i32)
                 .page(page_id)
                 .parent(parent_id)
                 .finish_and_commit(),
@@ -47,11 +61,14 @@ mod tests {
     #[test]
     fn should_fully_populate_from_vimwiki_element() {
         global::with_db(InmemoryDatabase::default(), || {
-            let element = vimwiki_divider!("----");
+            let element = vimwiki_divider!("-----");
             let region = Region::from(element.region());
             let ent = Divider::from_vimwiki_element(999, Some(123), element)
                 .expect("Failed to convert from element");
 
+            assert_eq!(*ent.len(), 5);
+            assert_eq!(*ent.blank_lines_before(), 0);
+            assert_eq!(*ent.blank_lines_after(), 0);
             assert_eq!(ent.region(), &region);
             assert_eq!(ent.page_id(), 999);
             assert_eq!(ent.parent_id(), Some(123));
@@ -4,10 +4,9 @@ use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, fmt, str::FromStr};
 use vimwiki::{
     self as v,
-    vendor::{
-        chrono::{self, NaiveDate},
-        uriparse::{self, URIReference},
-    },
+    date::ParseDateError,
+    uri::{WikiUri, WikiUriError},
+    WikiDate,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -20,7 +19,7 @@ impl fmt::Display for Description {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Text(ref x) => write!(f, "{}", x),
-            Self::UriRef(ref x) => write!(f, "{}", x.to_string()),
+            Self::UriRef(ref x) => write!(f, "{}", x),
         }
     }
 }
@@ -115,19 +114,19 @@ impl<'a> From<v::Anchor<'a>> for Anchor {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, From, Serialize, Deserialize)]
-pub struct Date(NaiveDate);
+pub struct Date(WikiDate);
 
 impl fmt::Display for Date {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0.format("%Y-%m-%d").to_string())
+        write!(f, "{}", self.0.format("%Y-%m-%d"))
     }
 }
 
 impl FromStr for Date {
-    type Err = chrono::ParseError;
+    type Err = ParseDateError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        NaiveDate::parse_from_str(&s, "%Y-%m-%d").map(Date)
+        WikiDate::parse_from_str(s, "%Y-%m-%d").map(Date)
     }
 }
 
@@ -147,19 +146,19 @@ impl ValueLike for Date {
 async_graphql::scalar!(Date);
 
 #[derive(Constructor, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct UriRef(URIReference<'static>);
+pub struct UriRef(WikiUri<'static>);
 
 impl fmt::Display for UriRef {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0.to_string())
+        write!(f, "{}", self.0)
     }
 }
 
 impl FromStr for UriRef {
-    type Err = uriparse::URIReferenceError;
+    type Err = WikiUriError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        URIReference::try_from(s).map(|x| UriRef(x.into_owned()))
+        WikiUri::try_from(s).map(|x| UriRef(x.into_owned()))
     }
 }
 
@@ -176,8 +175,8 @@ impl ValueLike for UriRef {
     }
 }
 
-impl<'a> From<URIReference<'a>> for UriRef {
-    fn from(uri_ref: URIReference<'a>) -> Self {
+impl<'a> From<WikiUri<'a>> for UriRef {
+    fn from(uri_ref: WikiUri<'a>) -> Self {
         Self(uri_ref.into_owned())
     }
 }
@@ -187,7 +186,7 @@ impl<'a> From<URIReference<'a>> for UriRef {
 impl UriRef {
     /// The authority portion of the URI, if it exists
     async fn authority(&self) -> Option<String> {
-        self.0.authority().map(ToString::to_string)
+        self.0.authority()
     }
 
     /// The fragment portion of the URI, if it exists
@@ -197,7 +196,7 @@ impl UriRef {
 
     /// The host portion of the URI, if it exists
     async fn host(&self) -> Option<String> {
-        self.0.host().map(ToString::to_string)
+        self.0.host()
     }
 
     /// The password portion of the URI, if it exists
@@ -207,7 +206,7 @@ impl UriRef {
 
     /// The path of the URI
     async fn path(&self) -> String {
-        self.0.path().to_string()
+        self.0.path()
     }
 
     /// The port portion of the URI, if it exists
@@ -8,6 +8,8 @@ mod code;
 pub use code::*;
 mod comments;
 pub use comments::*;
+mod footnotes;
+pub use footnotes::*;
 mod links;
 pub use links::*;
 mod math;
@@ -21,6 +23,8 @@ pub use typefaces::*;
 #[derive(Debug, Display)]
 pub enum InlineElement {
     Text(Text),
+    #[ent(wrap)]
+    #[graphql(flatten)]
     DecoratedText(DecoratedText),
     Keyword(Keyword),
     #[ent(wrap)]
@@ -32,6 +36,7 @@ pub enum InlineElement {
     #[ent(wrap)]
     #[graphql(flatten)]
     Comment(Comment),
+    FootnoteReference(FootnoteReference),
 }
 
 impl InlineElement {
@@ -45,6 +50,7 @@ impl InlineElement {
             Self::Code(x) => x.page_id(),
             Self::Math(x) => x.page_id(),
             Self::Comment(x) => x.page_id(),
+            Self::FootnoteReference(x) => x.page_id(),
         }
     }
 
@@ -58,6 +64,7 @@ impl InlineElement {
             Self::Code(x) => x.parent_id(),
             Self::Math(x) => x.parent_id(),
             Self::Comment(x) => x.parent_id(),
+            Self::FootnoteReference(x) => x.parent_id(),
         }
     }
 }
@@ -128,6 +135,13 @@ impl<'a> FromVimwikiElement<'a> for InlineElement {
                     Located::new(x, region),
                 )?)
             }
+            v::InlineElement::FootnoteReference(x) => Self::FootnoteReference(
+                FootnoteReference::from_vimwiki_element(
+                    page_id,
+                    parent_id,
+                    Located::new(x, region),
+                )?,
+            ),
         })
     }
 }
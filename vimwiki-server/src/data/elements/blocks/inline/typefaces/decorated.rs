@@ -1,6 +1,7 @@
 use crate::data::{
-    Element, ElementQuery, FromVimwikiElement, GqlPageFilter,
-    GraphqlDatabaseError, Keyword, Link, Page, PageQuery, Region, Text,
+    CodeInline, Element, ElementQuery, FootnoteReference, FromVimwikiElement,
+    GqlPageFilter, GraphqlDatabaseError, Keyword, Link, MathInline, Page,
+    PageQuery, Region, Text,
 };
 use derive_more::Display;
 use entity::*;
@@ -9,15 +10,186 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use vimwiki::{self as v, Located};
 
+/// Represents text with a typeface decoration, where each decoration is a
+/// distinct type
 #[gql_ent]
-pub struct DecoratedText {
-    /// The segment of the document this decorated text is within
+#[derive(Debug, Display)]
+pub enum DecoratedText {
+    Bold(BoldText),
+    Italic(ItalicText),
+    Strikeout(StrikeoutText),
+    Superscript(SuperscriptText),
+    Subscript(SubscriptText),
+}
+
+impl DecoratedText {
+    pub fn region(&self) -> &Region {
+        match self {
+            Self::Bold(x) => x.region(),
+            Self::Italic(x) => x.region(),
+            Self::Strikeout(x) => x.region(),
+            Self::Superscript(x) => x.region(),
+            Self::Subscript(x) => x.region(),
+        }
+    }
+
+    /// Returns the decoration applied to the text
+    pub fn decoration(&self) -> Decoration {
+        match self {
+            Self::Bold(_) => Decoration::Bold,
+            Self::Italic(_) => Decoration::Italic,
+            Self::Strikeout(_) => Decoration::Strikeout,
+            Self::Superscript(_) => Decoration::Superscript,
+            Self::Subscript(_) => Decoration::Subscript,
+        }
+    }
+
+    /// Loads the content within the decoration
+    pub fn load_contents(&self) -> DatabaseResult<Vec<DecoratedTextContent>> {
+        match self {
+            Self::Bold(x) => x.load_contents(),
+            Self::Italic(x) => x.load_contents(),
+            Self::Strikeout(x) => x.load_contents(),
+            Self::Superscript(x) => x.load_contents(),
+            Self::Subscript(x) => x.load_contents(),
+        }
+    }
+
+    pub fn page_id(&self) -> Id {
+        match self {
+            Self::Bold(x) => x.page_id(),
+            Self::Italic(x) => x.page_id(),
+            Self::Strikeout(x) => x.page_id(),
+            Self::Superscript(x) => x.page_id(),
+            Self::Subscript(x) => x.page_id(),
+        }
+    }
+
+    pub fn parent_id(&self) -> Option<Id> {
+        match self {
+            Self::Bold(x) => x.parent_id(),
+            Self::Italic(x) => x.parent_id(),
+            Self::Strikeout(x) => x.parent_id(),
+            Self::Superscript(x) => x.parent_id(),
+            Self::Subscript(x) => x.parent_id(),
+        }
+    }
+}
+
+/// Builds an ent of the given decorated text type, creating all of the
+/// content within the text using the ent as the parent
+macro_rules! build_decorated_text {
+    ($type:ident, $page_id:expr, $parent_id:expr, $region:expr, $contents:expr) => {{
+        // First, we create the decorated text without content since we need
+        // this ent's id to pass along as parent
+        let mut ent = GraphqlDatabaseError::wrap(
+            $type::build()
+                .region($region)
+                .contents(Vec::new())
+                .page($page_id)
+                .parent($parent_id)
+                .finish_and_commit(),
+        )?;
+
+        // Second, we need to create all of the content contained within the
+        // text
+        let mut contents = Vec::new();
+        for content in $contents {
+            contents.push(
+                DecoratedTextContent::from_vimwiki_element(
+                    $page_id,
+                    Some(ent.id()),
+                    content,
+                )?
+                .id(),
+            );
+        }
+
+        ent.set_contents_ids(contents);
+        ent.commit().map_err(GraphqlDatabaseError::Database)?;
+
+        Self::from(ent)
+    }};
+}
+
+impl<'a> FromVimwikiElement<'a> for DecoratedText {
+    type Element = Located<v::DecoratedText<'a>>;
+
+    fn from_vimwiki_element(
+        page_id: Id,
+        parent_id: Option<Id>,
+        element: Self::Element,
+    ) -> Result<Self, GraphqlDatabaseError> {
+        let region = Region::from(element.region());
+        Ok(match element.into_inner() {
+            v::DecoratedText::Bold(x) => {
+                build_decorated_text!(BoldText, page_id, parent_id, region, x)
+            }
+            v::DecoratedText::Italic(x) => {
+                build_decorated_text!(ItalicText, page_id, parent_id, region, x)
+            }
+            v::DecoratedText::Strikeout(x) => build_decorated_text!(
+                StrikeoutText,
+                page_id,
+                parent_id,
+                region,
+                x
+            ),
+            v::DecoratedText::Superscript(x) => build_decorated_text!(
+                SuperscriptText,
+                page_id,
+                parent_id,
+                region,
+                x
+            ),
+            v::DecoratedText::Subscript(x) => build_decorated_text!(
+                SubscriptText,
+                page_id,
+                parent_id,
+                region,
+                x
+            ),
+        })
+    }
+}
+
+/// Represents a single document bold text
+#[gql_ent]
+pub struct BoldText {
+    /// The segment of the document this bold text is within
     #[ent(field(graphql(filter_untyped)))]
     region: Region,
 
-    /// Represents the decoration applied to some text
+    /// The content within the decoration as individual elements
+    #[ent(edge(policy = "deep", wrap, graphql(filter_untyped)))]
+    contents: Vec<DecoratedTextContent>,
+
+    /// The content within the decoration as it would be read by humans
+    /// without frills
+    #[ent(field(computed = "self.to_string()"))]
+    text: String,
+
+    /// The page containing this bold text
+    #[ent(edge)]
+    page: Page,
+
+    /// The parent element containing this bold text
+    #[ent(edge(policy = "shallow", wrap, graphql(filter_untyped)))]
+    parent: Option<Element>,
+}
+
+impl fmt::Display for BoldText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_contents(f, self.load_contents())
+    }
+}
+
+/// Represents a single document italic text
+#[gql_ent]
+pub struct ItalicText {
+    /// The segment of the document this italic text is within
     #[ent(field(graphql(filter_untyped)))]
-    decoration: Decoration,
+    region: Region,
 
     /// The content within the decoration as individual elements
     #[ent(edge(policy = "deep", wrap, graphql(filter_untyped)))]
@@ -28,84 +200,136 @@ pub struct DecoratedText {
     #[ent(field(computed = "self.to_string()"))]
     text: String,
 
-    /// The page containing this decorated text
+    /// The page containing this italic text
     #[ent(edge)]
     page: Page,
 
-    /// The parent element containing this decorated text
+    /// The parent element containing this italic text
     #[ent(edge(policy = "shallow", wrap, graphql(filter_untyped)))]
     parent: Option<Element>,
 }
 
-impl fmt::Display for DecoratedText {
+impl fmt::Display for ItalicText {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.load_contents() {
-            Ok(contents) => {
-                for content in contents {
-                    write!(f, "{}", content.to_string())?;
-                }
-                Ok(())
-            }
-            Err(x) => {
-                write!(f, "{}", x)?;
-                Ok(())
-            }
-        }
+        fmt_contents(f, self.load_contents())
     }
 }
 
-impl<'a> FromVimwikiElement<'a> for DecoratedText {
-    type Element = Located<v::DecoratedText<'a>>;
+/// Represents a single document strikeout text
+#[gql_ent]
+pub struct StrikeoutText {
+    /// The segment of the document this strikeout text is within
+    #[ent(field(graphql(filter_untyped)))]
+    region: Region,
 
-    fn from_vimwiki_element(
-        page_id: Id,
-        parent_id: Option<Id>,
-        element: Self::Element,
-    ) -> Result<Self, GraphqlDatabaseError> {
-        let region = Region::from(element.region());
+    /// The content within the decoration as individual elements
+    #[ent(edge(policy = "deep", wrap, graphql(filter_untyped)))]
+    contents: Vec<DecoratedTextContent>,
 
-        // First, figure out the type of decoration
-        let decoration = match element.as_inner() {
-            v::DecoratedText::Bold(_) => Decoration::Bold,
-            v::DecoratedText::Italic(_) => Decoration::Italic,
-            v::DecoratedText::Strikeout(_) => Decoration::Strikeout,
-            v::DecoratedText::Superscript(_) => Decoration::Superscript,
-            v::DecoratedText::Subscript(_) => Decoration::Subscript,
-        };
+    /// The content within the decoration as it would be read by humans
+    /// without frills
+    #[ent(field(computed = "self.to_string()"))]
+    text: String,
 
-        // Second, we create the decorated text without content since we need
-        // this ent's id to pass along as parent
-        let mut ent = GraphqlDatabaseError::wrap(
-            Self::build()
-                .region(region)
-                .decoration(decoration)
-                .contents(Vec::new())
-                .page(page_id)
-                .parent(parent_id)
-                .finish_and_commit(),
-        )?;
+    /// The page containing this strikeout text
+    #[ent(edge)]
+    page: Page,
 
-        // Third, we need to create all of the content contained within the text
-        let mut contents = Vec::new();
-        for content in element.into_inner() {
-            contents.push(
-                DecoratedTextContent::from_vimwiki_element(
-                    page_id,
-                    Some(ent.id()),
-                    content,
-                )?
-                .id(),
-            );
-        }
+    /// The parent element containing this strikeout text
+    #[ent(edge(policy = "shallow", wrap, graphql(filter_untyped)))]
+    parent: Option<Element>,
+}
 
-        ent.set_contents_ids(contents);
-        ent.commit().map_err(GraphqlDatabaseError::Database)?;
+impl fmt::Display for StrikeoutText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_contents(f, self.load_contents())
+    }
+}
+
+/// Represents a single document superscript text
+#[gql_ent]
+pub struct SuperscriptText {
+    /// The segment of the document this superscript text is within
+    #[ent(field(graphql(filter_untyped)))]
+    region: Region,
+
+    /// The content within the decoration as individual elements
+    #[ent(edge(policy = "deep", wrap, graphql(filter_untyped)))]
+    contents: Vec<DecoratedTextContent>,
+
+    /// The content within the decoration as it would be read by humans
+    /// without frills
+    #[ent(field(computed = "self.to_string()"))]
+    text: String,
+
+    /// The page containing this superscript text
+    #[ent(edge)]
+    page: Page,
+
+    /// The parent element containing this superscript text
+    #[ent(edge(policy = "shallow", wrap, graphql(filter_untyped)))]
+    parent: Option<Element>,
+}
+
+impl fmt::Display for SuperscriptText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_contents(f, self.load_contents())
+    }
+}
+
+/// Represents a single document subscript text
+#[gql_ent]
+pub struct SubscriptText {
+    /// The segment of the document this subscript text is within
+    #[ent(field(graphql(filter_untyped)))]
+    region: Region,
+
+    /// The content within the decoration as individual elements
+    #[ent(edge(policy = "deep", wrap, graphql(filter_untyped)))]
+    contents: Vec<DecoratedTextContent>,
+
+    /// The content within the decoration as it would be read by humans
+    /// without frills
+    #[ent(field(computed = "self.to_string()"))]
+    text: String,
+
+    /// The page containing this subscript text
+    #[ent(edge)]
+    page: Page,
 
-        Ok(ent)
+    /// The parent element containing this subscript text
+    #[ent(edge(policy = "shallow", wrap, graphql(filter_untyped)))]
+    parent: Option<Element>,
+}
+
+impl fmt::Display for SubscriptText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_contents(f, self.load_contents())
+    }
+}
+
+/// Writes out loaded contents of decorated text, or the error encountered
+/// when loading them
+fn fmt_contents(
+    f: &mut fmt::Formatter<'_>,
+    contents: DatabaseResult<Vec<DecoratedTextContent>>,
+) -> fmt::Result {
+    match contents {
+        Ok(contents) => {
+            for content in contents {
+                write!(f, "{}", content)?;
+            }
+            Ok(())
+        }
+        Err(x) => write!(f, "{}", x),
     }
 }
 
 /// Represents content that can be contained within a decoration
+///
+/// Nested decorations are listed by their distinct types rather than through
+/// [`DecoratedText`] as flattening a union that contains itself (through
+/// the contents of its decorations) fails to register with the schema.
 #[gql_ent]
 #[derive(Debug, Display)]
 pub enum DecoratedTextContent {
@@ -114,7 +338,14 @@ pub enum DecoratedTextContent {
     #[ent(wrap)]
     #[graphql(flatten)]
     Link(Link),
-    DecoratedText(DecoratedText),
+    Bold(BoldText),
+    Italic(ItalicText),
+    Strikeout(StrikeoutText),
+    Superscript(SuperscriptText),
+    Subscript(SubscriptText),
+    Code(CodeInline),
+    Math(MathInline),
+    FootnoteReference(FootnoteReference),
 }
 
 impl DecoratedTextContent {
@@ -123,7 +354,14 @@ impl DecoratedTextContent {
             Self::Text(x) => x.page_id(),
             Self::Keyword(x) => x.page_id(),
             Self::Link(x) => x.page_id(),
-            Self::DecoratedText(x) => x.page_id(),
+            Self::Bold(x) => x.page_id(),
+            Self::Italic(x) => x.page_id(),
+            Self::Strikeout(x) => x.page_id(),
+            Self::Superscript(x) => x.page_id(),
+            Self::Subscript(x) => x.page_id(),
+            Self::Code(x) => x.page_id(),
+            Self::Math(x) => x.page_id(),
+            Self::FootnoteReference(x) => x.page_id(),
         }
     }
 
@@ -132,7 +370,14 @@ impl DecoratedTextContent {
             Self::Text(x) => x.parent_id(),
             Self::Keyword(x) => x.parent_id(),
             Self::Link(x) => x.parent_id(),
-            Self::DecoratedText(x) => x.parent_id(),
+            Self::Bold(x) => x.parent_id(),
+            Self::Italic(x) => x.parent_id(),
+            Self::Strikeout(x) => x.parent_id(),
+            Self::Superscript(x) => x.parent_id(),
+            Self::Subscript(x) => x.parent_id(),
+            Self::Code(x) => x.parent_id(),
+            Self::Math(x) => x.parent_id(),
+            Self::FootnoteReference(x) => x.parent_id(),
         }
     }
 }
@@ -155,7 +400,7 @@ impl<'a> FromVimwikiElement<'a> for DecoratedTextContent {
                 )?)
             }
             v::DecoratedTextContent::DecoratedText(x) => {
-                Self::DecoratedText(DecoratedText::from_vimwiki_element(
+                Self::from(DecoratedText::from_vimwiki_element(
                     page_id,
                     parent_id,
                     Located::new(x, region),
@@ -175,21 +420,47 @@ impl<'a> FromVimwikiElement<'a> for DecoratedTextContent {
                     Located::new(x, region),
                 )?)
             }
+            v::DecoratedTextContent::Code(x) => {
+                Self::Code(CodeInline::from_vimwiki_element(
+                    page_id,
+                    parent_id,
+                    Located::new(x, region),
+                )?)
+            }
+            v::DecoratedTextContent::Math(x) => {
+                Self::Math(MathInline::from_vimwiki_element(
+                    page_id,
+                    parent_id,
+                    Located::new(x, region),
+                )?)
+            }
+            v::DecoratedTextContent::FootnoteReference(x) => {
+                Self::FootnoteReference(
+                    FootnoteReference::from_vimwiki_element(
+                        page_id,
+                        parent_id,
+                        Located::new(x, region),
+                    )?,
+                )
+            }
         })
     }
 }
 
+impl From<DecoratedText> for DecoratedTextContent {
+    fn from(x: DecoratedText) -> Self {
+        match x {
+            DecoratedText::Bold(x) => Self::Bold(x),
+            DecoratedText::Italic(x) => Self::Italic(x),
+            DecoratedText::Strikeout(x) => Self::Strikeout(x),
+            DecoratedText::Superscript(x) => Self::Superscript(x),
+            DecoratedText::Subscript(x) => Self::Subscript(x),
+        }
+    }
+}
+
 /// Represents the type of decoration to apply to some text
-#[derive(
-    async_graphql::Enum,
-    Copy,
-    Clone,
-    Debug,
-    Eq,
-    PartialEq,
-    Serialize,
-    Deserialize,
-)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Decoration {
     Bold,
     Italic,
@@ -198,32 +469,6 @@ pub enum Decoration {
     Subscript,
 }
 
-impl ValueLike for Decoration {
-    fn into_value(self) -> Value {
-        match self {
-            Self::Bold => Value::from("bold"),
-            Self::Italic => Value::from("italic"),
-            Self::Strikeout => Value::from("strikeout"),
-            Self::Superscript => Value::from("superscript"),
-            Self::Subscript => Value::from("subscript"),
-        }
-    }
-
-    fn try_from_value(value: Value) -> Result<Self, Value> {
-        match value {
-            Value::Text(x) => match x.as_str() {
-                "bold" => Ok(Self::Bold),
-                "italic" => Ok(Self::Italic),
-                "strikeout" => Ok(Self::Strikeout),
-                "superscript" => Ok(Self::Superscript),
-                "subscript" => Ok(Self::Subscript),
-                _ => Err(Value::Text(x)),
-            },
-            x => Err(x),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,6 +485,8 @@ mod tests {
                     .expect("Failed to convert from element");
 
             assert_eq!(ent.region(), &region);
+            assert_eq!(ent.decoration(), Decoration::Bold);
+            assert!(matches!(ent, DecoratedText::Bold(_)));
             assert_eq!(ent.page_id(), 999);
             assert_eq!(ent.parent_id(), Some(123));
             assert_eq!(ent.to_string(), "some text");
@@ -250,4 +497,29 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn should_produce_distinct_type_for_each_decoration() {
+        global::with_db(InmemoryDatabase::default(), || {
+            let convert = |element| {
+                DecoratedText::from_vimwiki_element(999, None, element)
+                    .expect("Failed to convert from element")
+            };
+
+            let ent = convert(vimwiki_decorated_text!(r#"_some text_"#));
+            assert!(matches!(ent, DecoratedText::Italic(_)));
+
+            let ent = convert(vimwiki_decorated_text!(r#"~~some text~~"#));
+            assert!(matches!(ent, DecoratedText::Strikeout(_)));
+            assert_eq!(ent.to_string(), "some text");
+
+            let ent = convert(vimwiki_decorated_text!(r#"^some text^"#));
+            assert!(matches!(ent, DecoratedText::Superscript(_)));
+            assert_eq!(ent.to_string(), "some text");
+
+            let ent = convert(vimwiki_decorated_text!(r#",,some text,,"#));
+            assert!(matches!(ent, DecoratedText::Subscript(_)));
+            assert_eq!(ent.to_string(), "some text");
+        });
+    }
 }
@@ -19,6 +19,10 @@ pub struct Keyword {
     #[ent(field(graphql(filter_untyped)))]
     ty: KeywordType,
 
+    /// Name of the keyword as it appears in text (e.g. `TODO`), which
+    /// distinguishes keywords of the other type
+    name: String,
+
     /// Page containing the element
     #[ent(edge)]
     page: Page,
@@ -30,7 +34,7 @@ pub struct Keyword {
 
 impl fmt::Display for Keyword {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.ty)
+        write!(f, "{}", self.name)
     }
 }
 
@@ -43,10 +47,12 @@ impl<'a> FromVimwikiElement<'a> for Keyword {
         element: Self::Element,
     ) -> Result<Self, GraphqlDatabaseError> {
         let region = Region::from(element.region());
+        let keyword = element.into_inner();
         GraphqlDatabaseError::wrap(
             Self::build()
                 .region(region)
-                .ty(KeywordType::from(element.into_inner()))
+                .name(keyword.to_string())
+                .ty(KeywordType::from(keyword))
                 .page(page_id)
                 .parent(parent_id)
                 .finish_and_commit(),
@@ -72,6 +78,9 @@ pub enum KeywordType {
     Fixme,
     Fixed,
     Xxx,
+
+    /// Keyword recognized through the parser config rather than builtin
+    Other,
 }
 
 impl fmt::Display for KeywordType {
@@ -86,6 +95,7 @@ impl fmt::Display for KeywordType {
                 Self::Fixme => "fixme",
                 Self::Fixed => "fixed",
                 Self::Xxx => "xxx",
+                Self::Other => "other",
             }
         )
     }
@@ -102,6 +112,7 @@ impl FromStr for KeywordType {
             "fixme" => Ok(Self::Fixme),
             "fixed" => Ok(Self::Fixed),
             "xxx" => Ok(Self::Xxx),
+            "other" => Ok(Self::Other),
             _ => Err(()),
         }
     }
@@ -116,6 +127,7 @@ impl From<v::Keyword> for KeywordType {
             v::Keyword::Fixme => KeywordType::Fixme,
             v::Keyword::Fixed => KeywordType::Fixed,
             v::Keyword::Xxx => KeywordType::Xxx,
+            v::Keyword::Other(_) => KeywordType::Other,
         }
     }
 }
@@ -149,6 +161,7 @@ mod tests {
 
             assert_eq!(ent.region(), &region);
             assert_eq!(*ent.ty(), KeywordType::Todo);
+            assert_eq!(ent.name(), "TODO");
             assert_eq!(ent.page_id(), 999);
             assert_eq!(ent.parent_id(), Some(123));
         });
@@ -1,7 +1,7 @@
 use crate::data::{
-    BlockElement, BlockElementQuery, Element, ElementQuery, FromVimwikiElement,
-    GqlPageFilter, GraphqlDatabaseError, InlineElement, InlineElementQuery,
-    Page, PageQuery, Region,
+    BlockElement, BlockElementQuery, Date, Element, ElementQuery,
+    FromVimwikiElement, GqlPageFilter, GraphqlDatabaseError, InlineElement,
+    InlineElementQuery, Page, PageQuery, Region,
 };
 use entity::*;
 use entity_async_graphql::*;
@@ -302,6 +302,10 @@ pub struct ListItemAttributes {
     #[ent(field(graphql(filter_untyped)))]
     todo_status: Option<ListItemTodoStatus>,
 
+    /// Date by which the list item is due
+    #[ent(field(graphql(filter_untyped)))]
+    due: Option<Date>,
+
     /// Page containing this list item attribute set
     #[ent(edge)]
     page: Page,
@@ -320,10 +324,12 @@ impl<'a> FromVimwikiElement<'a> for ListItemAttributes {
         element: Self::Element,
     ) -> Result<Self, GraphqlDatabaseError> {
         let todo_status = element.todo_status.map(ListItemTodoStatus::from);
+        let due = element.due.map(Date::from);
 
         GraphqlDatabaseError::wrap(
             Self::build()
                 .todo_status(todo_status)
+                .due(due)
                 .page(page_id)
                 .parent(parent_id)
                 .finish_and_commit(),
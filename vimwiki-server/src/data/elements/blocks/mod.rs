@@ -11,6 +11,8 @@ mod definitions;
 pub use definitions::*;
 mod dividers;
 pub use dividers::*;
+mod footnotes;
+pub use footnotes::*;
 mod headers;
 pub use headers::*;
 mod inline;
@@ -33,6 +35,7 @@ pub enum BlockElement {
     CodeBlock(CodeBlock),
     DefinitionList(DefinitionList),
     Divider(Divider),
+    FootnoteDefinition(FootnoteDefinition),
     Header(Header),
     List(List),
     Math(MathBlock),
@@ -50,6 +53,7 @@ impl BlockElement {
             Self::CodeBlock(x) => x.page_id(),
             Self::DefinitionList(x) => x.page_id(),
             Self::Divider(x) => x.page_id(),
+            Self::FootnoteDefinition(x) => x.page_id(),
             Self::Header(x) => x.page_id(),
             Self::List(x) => x.page_id(),
             Self::Math(x) => x.page_id(),
@@ -65,6 +69,7 @@ impl BlockElement {
             Self::CodeBlock(x) => x.parent_id(),
             Self::DefinitionList(x) => x.parent_id(),
             Self::Divider(x) => x.parent_id(),
+            Self::FootnoteDefinition(x) => x.parent_id(),
             Self::Header(x) => x.parent_id(),
             Self::List(x) => x.parent_id(),
             Self::Math(x) => x.parent_id(),
@@ -153,6 +158,13 @@ impl<'a> FromVimwikiElement<'a> for BlockElement {
                     Located::new(x, region),
                 )?)
             }
+            v::BlockElement::FootnoteDefinition(x) => {
+                Self::from(FootnoteDefinition::from_vimwiki_element(
+                    page_id,
+                    parent_id,
+                    Located::new(x, region),
+                )?)
+            }
         })
     }
 }
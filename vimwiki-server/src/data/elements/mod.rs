@@ -1,6 +1,9 @@
 mod blocks;
 pub use blocks::*;
 
+mod node;
+pub use node::*;
+
 mod utils;
 pub use utils::*;
 
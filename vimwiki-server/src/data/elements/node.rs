@@ -0,0 +1,142 @@
+use crate::data::*;
+
+/// Defines the [`Node`] interface over every element type, paired with the
+/// type of the vimwiki element it is built from, alongside the resolvers of
+/// its fields, which are shared by the objects of those types
+macro_rules! node_interface {
+    ($($name:ident => $kind:literal),+ $(,)?) => {
+        /// Represents any element within a page, exposing the fields that
+        /// every element has in common
+        #[derive(async_graphql::Interface)]
+        #[graphql(
+            field(name = "id", method = "node_id", type = "Id"),
+            field(name = "type", method = "node_type", type = "String"),
+            field(name = "region", method = "node_region", type = "Region"),
+            field(name = "id_for_page", method = "node_page_id", type = "Id"),
+            field(
+                name = "id_for_parent",
+                method = "node_parent_id",
+                type = "Option<Id>"
+            )
+        )]
+        pub enum Node {
+            $($name($name)),+
+        }
+
+        impl Node {
+            /// Returns the type of the vimwiki element represented by the
+            /// node (e.g. `header`), matching [`vimwiki::Element::kind_name`]
+            pub fn kind(&self) -> &'static str {
+                match self {
+                    $(Self::$name(_) => $kind),+
+                }
+            }
+
+            pub fn region(&self) -> Region {
+                match self {
+                    $(Self::$name(x) => *x.region()),+
+                }
+            }
+
+            pub fn page_id(&self) -> Id {
+                match self {
+                    $(Self::$name(x) => x.page_id()),+
+                }
+            }
+        }
+
+        impl EntWrapper for Node {
+            fn wrap_ent(ent: Box<dyn Ent>) -> Option<Self> {
+                $(
+                    if let Some(x) = ent.to_ent::<$name>() {
+                        return Some(Self::$name(x));
+                    }
+                )+
+                None
+            }
+
+            fn can_wrap_ent(ent: &dyn Ent) -> bool {
+                $(ent.as_ent::<$name>().is_some() ||)+ false
+            }
+        }
+
+        $(
+            impl $name {
+                async fn node_id(
+                    &self,
+                    _ctx: &async_graphql::Context<'_>,
+                ) -> async_graphql::Result<Id> {
+                    Ok(self.id())
+                }
+
+                async fn node_type(
+                    &self,
+                    _ctx: &async_graphql::Context<'_>,
+                ) -> async_graphql::Result<String> {
+                    Ok(self.r#type().to_string())
+                }
+
+                async fn node_region(
+                    &self,
+                    _ctx: &async_graphql::Context<'_>,
+                ) -> async_graphql::Result<Region> {
+                    Ok(*self.region())
+                }
+
+                async fn node_page_id(
+                    &self,
+                    _ctx: &async_graphql::Context<'_>,
+                ) -> async_graphql::Result<Id> {
+                    Ok(self.page_id())
+                }
+
+                async fn node_parent_id(
+                    &self,
+                    _ctx: &async_graphql::Context<'_>,
+                ) -> async_graphql::Result<Option<Id>> {
+                    Ok(self.parent_id())
+                }
+            }
+        )+
+    };
+}
+
+node_interface!(
+    Blockquote => "blockquote",
+    CodeBlock => "code_block",
+    DefinitionList => "definition_list",
+    Divider => "divider",
+    FootnoteDefinition => "footnote_definition",
+    Header => "header",
+    List => "list",
+    MathBlock => "math_block",
+    Paragraph => "paragraph",
+    PlaceholderTitle => "placeholder",
+    PlaceholderNoHtml => "placeholder",
+    PlaceholderTemplate => "placeholder",
+    PlaceholderDate => "placeholder",
+    PlaceholderOther => "placeholder",
+    Table => "table",
+    Text => "text",
+    BoldText => "decorated_text",
+    ItalicText => "decorated_text",
+    StrikeoutText => "decorated_text",
+    SuperscriptText => "decorated_text",
+    SubscriptText => "decorated_text",
+    Keyword => "keyword",
+    WikiLink => "link",
+    IndexedInterWikiLink => "link",
+    NamedInterWikiLink => "link",
+    DiaryLink => "link",
+    RawLink => "link",
+    TransclusionLink => "link",
+    Tags => "tags",
+    CodeInline => "code_inline",
+    MathInline => "math_inline",
+    LineComment => "comment",
+    MultiLineComment => "comment",
+    FootnoteReference => "footnote_reference",
+    ListItem => "list_item",
+    Term => "term",
+    Definition => "definition",
+);
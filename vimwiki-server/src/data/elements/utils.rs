@@ -26,6 +26,12 @@ pub struct Region {
     depth: u16,
 }
 
+impl From<Region> for v::Region {
+    fn from(region: Region) -> Self {
+        Self::new_at_depth(region.offset, region.len, region.depth)
+    }
+}
+
 impl From<v::Region> for Region {
     fn from(region: v::Region) -> Self {
         Self {
@@ -0,0 +1,152 @@
+use lazy_static::lazy_static;
+use std::{collections::VecDeque, path::Path, sync::Mutex};
+use vimwiki::Timestamp;
+
+/// Maximum number of events kept, dropping the oldest first
+const MAX_EVENTS: usize = 1000;
+
+lazy_static! {
+    static ref EVENTS: Mutex<Events> = Mutex::new(Events::default());
+}
+
+/// Represents the kind of something that happened to the index of the server
+#[derive(Copy, Clone, Debug, PartialEq, Eq, async_graphql::Enum)]
+pub enum EventKind {
+    /// File was parsed and added to the index for the first time
+    FileAdded,
+
+    /// File already in the index was parsed again after its contents changed
+    FileChanged,
+
+    /// File was removed from the index
+    FileRemoved,
+
+    /// File in the index was moved to a new path
+    FileRenamed,
+
+    /// File could not be parsed, leaving it out of the index
+    ParseFailed,
+
+    /// Edits were applied to a file by the server
+    EditApplied,
+
+    /// Edits previously applied to a file by the server were reverted
+    EditReverted,
+}
+
+/// Represents something that happened to the index of the server
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct Event {
+    /// Unique id of the event, increasing with every event
+    id: u64,
+
+    /// When the event happened, in RFC 3339 format
+    timestamp: String,
+
+    /// Kind of the event
+    kind: EventKind,
+
+    /// Path to the file the event concerns
+    path: String,
+
+    /// Additional details about the event, such as the error that occurred
+    message: Option<String>,
+}
+
+/// Represents an in-memory log of the most recent events, oldest first
+#[derive(Debug, Default)]
+struct Events {
+    next_id: u64,
+    events: VecDeque<Event>,
+}
+
+/// Records an event concerning the file at the path
+pub fn record_event(
+    kind: EventKind,
+    path: impl AsRef<Path>,
+    message: impl Into<Option<String>>,
+) {
+    if let Ok(mut x) = EVENTS.lock() {
+        x.next_id += 1;
+        let event = Event {
+            id: x.next_id,
+            timestamp: Timestamp::now().to_string(),
+            kind,
+            path: path.as_ref().to_string_lossy().to_string(),
+            message: message.into(),
+        };
+
+        x.events.push_back(event);
+        if x.events.len() > MAX_EVENTS {
+            x.events.pop_front();
+        }
+    }
+}
+
+/// Returns up to `limit` of the most recent events with an id greater than
+/// `since`, optionally only of the given kinds or concerning files within
+/// the given path, newest first
+pub fn events(
+    since: Option<u64>,
+    kinds: Option<&[EventKind]>,
+    path: Option<&Path>,
+    limit: usize,
+) -> Vec<Event> {
+    EVENTS
+        .lock()
+        .map(|x| {
+            x.events
+                .iter()
+                .rev()
+                .filter(|e| since.is_none_or(|id| e.id > id))
+                .filter(|e| kinds.is_none_or(|x| x.contains(&e.kind)))
+                .filter(|e| {
+                    path.is_none_or(|x| Path::new(&e.path).starts_with(x))
+                })
+                .take(limit)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_should_filter_newest_first() {
+        let start = events(None, None, None, 1).first().map_or(0, |e| e.id);
+        record_event(EventKind::FileAdded, "/events/a.wiki", None);
+        record_event(
+            EventKind::ParseFailed,
+            "/events/b.wiki",
+            Some(String::from("bad")),
+        );
+        record_event(EventKind::FileChanged, "/events/a.wiki", None);
+
+        let path = Some(Path::new("/events"));
+        let kinds: Vec<EventKind> = events(Some(start), None, path, 10)
+            .into_iter()
+            .map(|e| e.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::FileChanged,
+                EventKind::ParseFailed,
+                EventKind::FileAdded
+            ]
+        );
+
+        let failed =
+            events(Some(start), Some(&[EventKind::ParseFailed]), path, 10);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].message.as_deref(), Some("bad"));
+
+        let a_path = Some(Path::new("/events/a.wiki"));
+        let a = events(Some(start), None, a_path, 1);
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].kind, EventKind::FileChanged);
+    }
+}
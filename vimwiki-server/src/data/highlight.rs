@@ -0,0 +1,82 @@
+use super::{to_gql_error, Region};
+use crate::{overlay, syntax};
+use std::{ops::Range, path::Path};
+use vimwiki::{highlight as h, Page};
+
+/// Represents the classification of a span of text used for highlighting
+#[derive(async_graphql::Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HighlightKind {
+    Header,
+    Bold,
+    Italic,
+    Strikeout,
+    Superscript,
+    Subscript,
+    Keyword,
+    LinkPath,
+    LinkDescription,
+    Tag,
+    Math,
+    Code,
+    TodoMarker,
+    Comment,
+}
+
+impl From<h::HighlightKind> for HighlightKind {
+    fn from(kind: h::HighlightKind) -> Self {
+        match kind {
+            h::HighlightKind::Header => Self::Header,
+            h::HighlightKind::Bold => Self::Bold,
+            h::HighlightKind::Italic => Self::Italic,
+            h::HighlightKind::Strikeout => Self::Strikeout,
+            h::HighlightKind::Superscript => Self::Superscript,
+            h::HighlightKind::Subscript => Self::Subscript,
+            h::HighlightKind::Keyword => Self::Keyword,
+            h::HighlightKind::LinkPath => Self::LinkPath,
+            h::HighlightKind::LinkDescription => Self::LinkDescription,
+            h::HighlightKind::Tag => Self::Tag,
+            h::HighlightKind::Math => Self::Math,
+            h::HighlightKind::Code => Self::Code,
+            h::HighlightKind::TodoMarker => Self::TodoMarker,
+            h::HighlightKind::Comment => Self::Comment,
+        }
+    }
+}
+
+/// Represents a classified span of text within a page
+#[derive(Copy, Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct HighlightSpan {
+    /// Classification of the text
+    kind: HighlightKind,
+
+    /// Region of the text within the page
+    region: Region,
+}
+
+impl From<h::HighlightSpan> for HighlightSpan {
+    fn from(span: h::HighlightSpan) -> Self {
+        Self {
+            kind: HighlightKind::from(span.kind),
+            region: Region::from(span.region),
+        }
+    }
+}
+
+/// Classifies the spans of text within the file at the given path (or its
+/// overlay), limited to those overlapping the given range of lines (counted
+/// from zero) if provided
+pub async fn highlight_spans(
+    path: impl AsRef<Path>,
+    lines: Option<Range<usize>>,
+) -> async_graphql::Result<Vec<HighlightSpan>> {
+    let path = overlay::key(path).await;
+    let text = overlay::read_to_string(&path).await.map_err(to_gql_error)?;
+    let page: Page = syntax::parse_page(&text).map_err(to_gql_error)?;
+
+    let spans = match lines {
+        Some(lines) => h::highlight_lines(&text, &page, lines),
+        None => h::highlight(&text, &page),
+    };
+
+    Ok(spans.into_iter().map(HighlightSpan::from).collect())
+}
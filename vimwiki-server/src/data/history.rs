@@ -0,0 +1,209 @@
+use super::to_gql_error;
+#[cfg(feature = "git")]
+use crate::git;
+use crate::{overlay, syntax};
+use std::path::{Path, PathBuf};
+use vimwiki::{diff, Page};
+
+/// Represents a commit that changed a file
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct Commit {
+    /// Full hash of the commit
+    id: String,
+
+    /// Name of the author of the commit
+    author: String,
+
+    /// When the commit was authored, in ISO 8601 format
+    timestamp: String,
+
+    /// First line of the commit message
+    summary: String,
+}
+
+#[cfg(feature = "git")]
+impl From<git::CommitInfo> for Commit {
+    fn from(info: git::CommitInfo) -> Self {
+        Self {
+            id: info.id,
+            author: info.author,
+            timestamp: info.timestamp,
+            summary: info.summary,
+        }
+    }
+}
+
+/// Represents the kind of change made to a block or inline element
+#[derive(Copy, Clone, Debug, PartialEq, Eq, async_graphql::Enum)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Moved,
+    Modified,
+}
+
+impl From<diff::ChangeKind> for ChangeKind {
+    fn from(kind: diff::ChangeKind) -> Self {
+        match kind {
+            diff::ChangeKind::Added => Self::Added,
+            diff::ChangeKind::Removed => Self::Removed,
+            diff::ChangeKind::Moved => Self::Moved,
+            diff::ChangeKind::Modified => Self::Modified,
+        }
+    }
+}
+
+/// Represents an inline element added to or removed from a paragraph
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct InlineChange {
+    /// Kind of change made to the element
+    kind: ChangeKind,
+
+    /// Name of the type of element that changed
+    element: String,
+
+    /// Text of the element as it would be displayed
+    text: String,
+
+    /// Byte offset of the element within the old revision, if it exists there
+    old_offset: Option<usize>,
+
+    /// Byte offset of the element within the new revision, if it exists there
+    new_offset: Option<usize>,
+}
+
+impl From<diff::InlineChange> for InlineChange {
+    fn from(change: diff::InlineChange) -> Self {
+        Self {
+            kind: change.kind.into(),
+            element: change.element,
+            text: change.text,
+            old_offset: change.old_region.map(|x| x.offset()),
+            new_offset: change.new_region.map(|x| x.offset()),
+        }
+    }
+}
+
+/// Represents a change to a top-level block of a page between revisions
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct BlockChange {
+    /// Kind of change made to the block
+    kind: ChangeKind,
+
+    /// Name of the type of block that changed
+    block: String,
+
+    /// Byte offset of the block within the old revision, if it exists there
+    old_offset: Option<usize>,
+
+    /// Byte offset of the block within the new revision, if it exists there
+    new_offset: Option<usize>,
+
+    /// Total bytes of the block within the new revision, or the old revision
+    /// if it was removed
+    len: usize,
+
+    /// Inline elements added and removed within a modified paragraph
+    inline: Vec<InlineChange>,
+}
+
+impl From<diff::BlockChange> for BlockChange {
+    fn from(change: diff::BlockChange) -> Self {
+        let region = change.new_region.or(change.old_region);
+        Self {
+            kind: change.kind.into(),
+            block: change.block,
+            old_offset: change.old_region.map(|x| x.offset()),
+            new_offset: change.new_region.map(|x| x.offset()),
+            len: region.map(|x| x.len()).unwrap_or_default(),
+            inline: change.inline.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Returns up to `limit` commits that changed the file at the path, newest
+/// first
+pub async fn page_history(
+    path: impl AsRef<Path>,
+    limit: usize,
+) -> async_graphql::Result<Vec<Commit>> {
+    let c_path = tokio::fs::canonicalize(path).await.map_err(to_gql_error)?;
+    commits_of_file(c_path, limit).await
+}
+
+/// Compares the blocks of the file at the path between two revisions, where
+/// a missing `to` revision compares against the file's overlay or the file
+/// on disk
+pub async fn page_revision_diff(
+    path: impl AsRef<Path>,
+    from: &str,
+    to: Option<&str>,
+) -> async_graphql::Result<Vec<BlockChange>> {
+    let c_path = tokio::fs::canonicalize(path).await.map_err(to_gql_error)?;
+
+    let old_text = file_at_revision(c_path.clone(), from).await?;
+    let new_text = match to {
+        Some(rev) => file_at_revision(c_path.clone(), rev).await?,
+        None => overlay::read_to_string(&c_path)
+            .await
+            .map_err(to_gql_error)?,
+    };
+
+    let old: Page = syntax::parse_page(&old_text).map_err(to_gql_error)?;
+    let new: Page = syntax::parse_page(&new_text).map_err(to_gql_error)?;
+
+    Ok(diff::diff_pages(&old, &new)
+        .into_iter()
+        .map(BlockChange::from)
+        .collect())
+}
+
+/// Returns up to `limit` commits that changed the file at the canonical path,
+/// running git on a blocking thread so the executor can serve other requests
+#[cfg(feature = "git")]
+async fn commits_of_file(
+    path: PathBuf,
+    limit: usize,
+) -> async_graphql::Result<Vec<Commit>> {
+    let commits =
+        tokio::task::spawn_blocking(move || git::page_history(&path, limit))
+            .await
+            .map_err(to_gql_error)?
+            .map_err(to_gql_error)?;
+    Ok(commits.into_iter().map(Commit::from).collect())
+}
+
+#[cfg(not(feature = "git"))]
+async fn commits_of_file(
+    _path: PathBuf,
+    _limit: usize,
+) -> async_graphql::Result<Vec<Commit>> {
+    Err(git_disabled())
+}
+
+/// Returns the contents of the file at the canonical path as of the revision,
+/// running git on a blocking thread so the executor can serve other requests
+#[cfg(feature = "git")]
+async fn file_at_revision(
+    path: PathBuf,
+    rev: &str,
+) -> async_graphql::Result<String> {
+    let rev = rev.to_string();
+    tokio::task::spawn_blocking(move || git::file_at_revision(&path, &rev))
+        .await
+        .map_err(to_gql_error)?
+        .map_err(to_gql_error)
+}
+
+#[cfg(not(feature = "git"))]
+async fn file_at_revision(
+    _path: PathBuf,
+    _rev: &str,
+) -> async_graphql::Result<String> {
+    Err(git_disabled())
+}
+
+#[cfg(not(feature = "git"))]
+fn git_disabled() -> async_graphql::Error {
+    async_graphql::Error::new("vimwiki-server was built without git support")
+}
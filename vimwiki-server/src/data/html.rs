@@ -0,0 +1,171 @@
+use super::{to_gql_error, Node, Page};
+use crate::{database::gql_db, overlay, preview, syntax};
+use entity::*;
+use vimwiki::{
+    ids::{element_ids, ElementId},
+    HtmlConfig, HtmlFormatter, Output, Region,
+};
+
+/// Represents options that adjust how HTML is rendered, each of which falls
+/// back to the configuration of the server when not provided
+#[derive(Clone, Debug, Default, async_graphql::InputObject)]
+pub struct HtmlOptions {
+    /// Whether or not newlines within paragraphs and list items are ignored
+    /// rather than rendered as line breaks
+    ignore_newline: Option<bool>,
+
+    /// Whether or not comments are included as HTML comments
+    include_comments: Option<bool>,
+
+    /// Whether or not code blocks are highlighted by the server
+    highlight_code: Option<bool>,
+
+    /// Name of the theme used to highlight code blocks
+    code_theme: Option<String>,
+
+    /// Level of headers to start numbering from, or 0 to not number headers
+    header_numbering: Option<usize>,
+}
+
+impl HtmlOptions {
+    /// Applies the options on top of the given config
+    pub fn apply(self, config: &mut HtmlConfig) {
+        if let Some(x) = self.ignore_newline {
+            config.paragraph.ignore_newline = x;
+            config.list.ignore_newline = x;
+        }
+
+        if let Some(x) = self.include_comments {
+            config.comment.include = x;
+        }
+
+        if let Some(x) = self.highlight_code {
+            config.code.server_side = x;
+        }
+
+        if let Some(x) = self.code_theme {
+            config.code.theme = x;
+        }
+
+        if let Some(x) = self.header_numbering {
+            config.header.numbering = x;
+        }
+    }
+}
+
+/// Renders the page or element with the given id into an HTML fragment
+/// using the current contents of its file (or its overlay), returning none
+/// if there is no page or element with the id
+pub async fn render_html(
+    id: Id,
+    options: HtmlOptions,
+) -> async_graphql::Result<Option<String>> {
+    let ent = match gql_db()?.get(id).map_err(to_gql_error)? {
+        Some(ent) => ent,
+        None => return Ok(None),
+    };
+
+    let (page, target) = match ent.to_ent::<Page>() {
+        Some(page) => (page, None),
+        None => match Node::wrap_ent(ent) {
+            Some(node) => match gql_db()?
+                .get_typed::<Page>(node.page_id())
+                .map_err(to_gql_error)?
+            {
+                Some(page) => (page, Some(node)),
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        },
+    };
+
+    let file = page.load_file().map_err(to_gql_error)?;
+    let path = overlay::key(file.path()).await;
+    let text = overlay::read_to_string(&path).await.map_err(to_gql_error)?;
+
+    let mut config = preview::config_for_path(&preview::html_config(), &path);
+    options.apply(&mut config);
+
+    let page: vimwiki::Page =
+        syntax::parse_page(&text).map_err(to_gql_error)?;
+
+    render(
+        config,
+        &page,
+        target.map(|node| (node.kind(), Region::from(node.region()))),
+    )
+}
+
+/// Renders the page, or only its element of the given type and region if
+/// provided, returning none if the page has no such element
+pub(crate) fn render(
+    config: HtmlConfig,
+    page: &vimwiki::Page,
+    target: Option<(&str, Region)>,
+) -> async_graphql::Result<Option<String>> {
+    let mut f = HtmlFormatter::new(config);
+    match target {
+        Some((kind, region)) => {
+            let id = ElementId::new("", kind, region);
+            match element_ids("", page).into_iter().find(|(x, _)| *x == id) {
+                Some((_, element)) => {
+                    element.into_inner().fmt(&mut f).map_err(to_gql_error)?
+                }
+                None => return Ok(None),
+            }
+        }
+        None => page.fmt(&mut f).map_err(to_gql_error)?,
+    }
+
+    Ok(Some(f.into_content()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vimwiki::Language;
+
+    #[test]
+    fn render_should_only_render_the_targeted_element() {
+        let text = "= Header =\nsome *bold* text\n";
+        let page: vimwiki::Page =
+            Language::from_vimwiki_str(text).parse().unwrap();
+        let html = render(HtmlConfig::default(), &page, None).unwrap().unwrap();
+        assert!(html.contains("<h1"), "{}", html);
+        assert!(html.contains("<p>"), "{}", html);
+
+        let bold = render(
+            HtmlConfig::default(),
+            &page,
+            Some(("decorated_text", Region::new(16, 6))),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(bold.contains(">bold</strong>"), "{}", bold);
+        assert!(!bold.contains("<p>"), "{}", bold);
+
+        let missing = render(
+            HtmlConfig::default(),
+            &page,
+            Some(("header", Region::new(16, 6))),
+        )
+        .unwrap();
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn options_should_override_only_what_they_set() {
+        let mut config = HtmlConfig::default();
+        let theme = config.code.theme.clone();
+        HtmlOptions {
+            include_comments: Some(true),
+            header_numbering: Some(2),
+            ..Default::default()
+        }
+        .apply(&mut config);
+
+        assert!(config.comment.include);
+        assert_eq!(config.header.numbering, 2);
+        assert_eq!(config.code.theme, theme);
+    }
+}
@@ -0,0 +1,318 @@
+use super::{record_event, to_gql_error, EventKind, ParsedFile};
+#[cfg(feature = "git")]
+use crate::git;
+use crate::syntax;
+use lazy_static::lazy_static;
+use sha1::{Digest, Sha1};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use vimwiki::{
+    edit::{invert_edits, FileEditor, TextEdit},
+    tasks::find_task_at_offset,
+    Page, Region, Timestamp,
+};
+
+/// Maximum number of entries kept per file, dropping the oldest first
+const MAX_ENTRIES_PER_FILE: usize = 100;
+
+lazy_static! {
+    static ref JOURNAL: Mutex<Journal> = Mutex::new(Journal::default());
+}
+
+/// Represents a replacement of the text within a region of a file
+#[derive(Clone, Debug, async_graphql::InputObject)]
+pub struct TextEditInput {
+    /// Byte offset within the file where the replaced region starts
+    offset: usize,
+
+    /// Total bytes of the region being replaced
+    len: usize,
+
+    /// Text to put in place of the region
+    text: String,
+}
+
+impl From<TextEditInput> for TextEdit {
+    fn from(input: TextEditInput) -> Self {
+        TextEdit::new(Region::new(input.offset, input.len), input.text)
+    }
+}
+
+/// Represents a set of edits the server applied to a file
+#[derive(Clone, Debug, async_graphql::SimpleObject)]
+pub struct JournalEntry {
+    /// Unique id of the entry
+    id: u64,
+
+    /// When the edits were applied, in RFC 3339 format
+    timestamp: String,
+
+    /// Path to the file that was edited
+    path: String,
+
+    /// Description of the operation that produced the edits
+    description: String,
+
+    /// Total edits applied to the file
+    edit_count: usize,
+
+    /// Edits that restore the file to its state prior to the operation
+    #[graphql(skip)]
+    undo: Vec<TextEdit>,
+
+    /// Checksum of the file after the edits were applied
+    #[graphql(skip)]
+    checksum: String,
+}
+
+/// Represents an in-memory history of edits applied by the server, kept
+/// per file from oldest to newest. Nothing is persisted, so the history is
+/// lost when the server exits.
+#[derive(Debug, Default)]
+struct Journal {
+    next_id: u64,
+    entries: HashMap<PathBuf, Vec<JournalEntry>>,
+}
+
+impl Journal {
+    fn record(
+        &mut self,
+        path: &Path,
+        description: String,
+        edit_count: usize,
+        undo: Vec<TextEdit>,
+        checksum: String,
+    ) {
+        let entry = JournalEntry {
+            id: self.next_id,
+            timestamp: Timestamp::now().to_string(),
+            path: path.to_string_lossy().to_string(),
+            description,
+            edit_count,
+            undo,
+            checksum,
+        };
+        self.next_id += 1;
+
+        let entries = self.entries.entry(path.to_path_buf()).or_default();
+        entries.push(entry);
+        if entries.len() > MAX_ENTRIES_PER_FILE {
+            entries.remove(0);
+        }
+    }
+}
+
+/// Returns the journal entries of the file at the path, newest first
+pub fn journal_entries(path: &Path) -> Vec<JournalEntry> {
+    JOURNAL
+        .lock()
+        .map(|journal| {
+            journal
+                .entries
+                .get(path)
+                .map(|x| x.iter().rev().cloned().collect())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default()
+}
+
+fn checksum(text: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Sha1::digest(text.as_ref()))
+}
+
+/// Records the overwrite of the file at the canonical path with new contents
+/// in the journal so that its previous contents can be restored
+pub(super) fn record_overwrite(
+    path: &Path,
+    previous: String,
+    contents: &[u8],
+) -> async_graphql::Result<()> {
+    let undo = TextEdit::new(Region::new(0, contents.len()), previous);
+    JOURNAL.lock().map_err(to_gql_error)?.record(
+        path,
+        String::from("overwrite file"),
+        1,
+        vec![undo],
+        checksum(contents),
+    );
+    Ok(())
+}
+
+impl ParsedFile {
+    /// Applies edits to the file at the path, recording them in the journal
+    /// so they can be reverted, and reloads the file
+    pub async fn edit(
+        path: impl AsRef<Path>,
+        description: String,
+        edits: Vec<TextEdit>,
+    ) -> async_graphql::Result<Self> {
+        let c_path =
+            tokio::fs::canonicalize(path).await.map_err(to_gql_error)?;
+
+        let mut editor = FileEditor::open_async(&c_path)
+            .await
+            .map_err(to_gql_error)?;
+        let original = editor.text().to_string();
+        let applied = editor.apply(&edits).map_err(to_gql_error)?;
+        let undo = invert_edits(&original, &applied).map_err(to_gql_error)?;
+        editor.save_async().await.map_err(to_gql_error)?;
+
+        #[cfg(feature = "git")]
+        git::auto_commit_async(c_path.clone(), description.clone()).await;
+        record_event(
+            EventKind::EditApplied,
+            &c_path,
+            format!("{} ({} edit(s))", description, edits.len()),
+        );
+        JOURNAL.lock().map_err(to_gql_error)?.record(
+            &c_path,
+            description,
+            edits.len(),
+            undo,
+            checksum(editor.text()),
+        );
+
+        Self::load(None, c_path).await
+    }
+
+    /// Toggles the task whose list item contains the given byte offset of
+    /// the file at the path, marking a complete or rejected task as
+    /// incomplete and any other task as complete, as a journaled edit
+    pub async fn toggle_task(
+        path: impl AsRef<Path>,
+        offset: usize,
+    ) -> async_graphql::Result<Self> {
+        let text = tokio::fs::read_to_string(path.as_ref())
+            .await
+            .map_err(to_gql_error)?;
+        let page: Page = syntax::parse_page(&text).map_err(to_gql_error)?;
+        let edit = find_task_at_offset(&page, offset)
+            .and_then(|task| task.to_toggle_edit(&text))
+            .ok_or_else(|| {
+                async_graphql::Error::new(format!("No task at {}", offset))
+            })?;
+
+        Self::edit(path, String::from("toggle task"), vec![edit]).await
+    }
+
+    /// Reverts the last `count` journaled operations applied to the file at
+    /// the path, newest first, and reloads the file. Fails without reverting
+    /// further if the file was changed outside of the journal since the
+    /// operation being reverted.
+    pub async fn revert(
+        path: impl AsRef<Path>,
+        count: usize,
+    ) -> async_graphql::Result<Self> {
+        let c_path =
+            tokio::fs::canonicalize(path).await.map_err(to_gql_error)?;
+
+        let mut reverted = 0;
+        for _ in 0..count {
+            // NOTE: The journal is not locked while the file is being
+            //       reverted so that the lock is never held across an await
+            let entry = JOURNAL
+                .lock()
+                .map_err(to_gql_error)?
+                .entries
+                .get(&c_path)
+                .and_then(|entries| entries.last())
+                .cloned();
+            let entry = match entry {
+                Some(entry) => entry,
+                None => break,
+            };
+
+            let mut editor = FileEditor::open_async(&c_path)
+                .await
+                .map_err(to_gql_error)?;
+            if checksum(editor.text()) != entry.checksum {
+                return Err(async_graphql::Error::new(format!(
+                    "{:?} was modified since \"{}\" was applied",
+                    c_path, entry.description
+                )));
+            }
+
+            editor.apply(&entry.undo).map_err(to_gql_error)?;
+            editor.save_async().await.map_err(to_gql_error)?;
+            if let Some(entries) = JOURNAL
+                .lock()
+                .map_err(to_gql_error)?
+                .entries
+                .get_mut(&c_path)
+            {
+                entries.retain(|x| x.id != entry.id);
+            }
+            reverted += 1;
+        }
+
+        if reverted > 0 {
+            record_event(
+                EventKind::EditReverted,
+                &c_path,
+                format!("Reverted {} operation(s)", reverted),
+            );
+            #[cfg(feature = "git")]
+            git::auto_commit_async(
+                c_path.clone(),
+                format!("revert {} operation(s)", reverted),
+            )
+            .await;
+        }
+
+        Self::load(None, c_path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use entity::global;
+    use entity_inmemory::InmemoryDatabase;
+    use vimwiki::edit::apply_edits;
+
+    #[test]
+    fn record_overwrite_should_journal_restoring_previous_contents() {
+        let path = Path::new("/journal/test/overwrite.wiki");
+        record_overwrite(path, String::from("old contents"), b"new").unwrap();
+
+        let entry = journal_entries(path).remove(0);
+        assert_eq!(entry.description, "overwrite file");
+        assert_eq!(entry.checksum, checksum("new"));
+        assert_eq!(apply_edits("new", &entry.undo).unwrap(), "old contents");
+    }
+
+    #[test]
+    fn revert_should_restore_file_with_crlf_line_endings() {
+        let file =
+            std::env::temp_dir().join("vimwiki-server-journal-crlf.wiki");
+        std::fs::write(&file, "one\r\ntwo\r\n").unwrap();
+
+        global::with_db(InmemoryDatabase::default(), || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async {
+                let edit = TextEdit::new(
+                    Region::new(5, 0),
+                    String::from("- a\n- b\n"),
+                );
+                ParsedFile::edit(&file, String::from("add list"), vec![edit])
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    std::fs::read_to_string(&file).unwrap(),
+                    "one\r\n- a\r\n- b\r\ntwo\r\n"
+                );
+
+                ParsedFile::revert(&file, 1).await.unwrap();
+                assert_eq!(
+                    std::fs::read_to_string(&file).unwrap(),
+                    "one\r\ntwo\r\n"
+                );
+            })
+        });
+
+        std::fs::remove_file(&file).unwrap();
+    }
+}
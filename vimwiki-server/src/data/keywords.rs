@@ -0,0 +1,82 @@
+use super::{ColumnUnit, KeywordType, Region, TextPosition, Wiki};
+use vimwiki::{keywords as k, positions::LineIndex};
+
+/// Represents a keyword (TODO, FIXME, XXX, etc) found within a page of a
+/// wiki alongside the elements that contain it
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct KeywordOccurrence {
+    /// Index of the wiki containing the page
+    wiki: usize,
+
+    /// Path to the file of the page
+    path: String,
+
+    /// Name of the page relative to its wiki
+    page: String,
+
+    /// The type of keyword that was found
+    keyword: KeywordType,
+
+    /// Name of the keyword as it appears in text (e.g. `TODO`)
+    name: String,
+
+    /// Region of the keyword within its page
+    region: Region,
+
+    /// Position of the keyword within its page
+    position: Option<TextPosition>,
+
+    /// Line of text containing the keyword, trimmed of surrounding spaces
+    line: Option<String>,
+
+    /// Text of the header whose section contains the keyword, which is the
+    /// header itself if the keyword is within a header
+    header: Option<String>,
+
+    /// Text of the first line of content within the innermost list item
+    /// containing the keyword
+    list_item: Option<String>,
+}
+
+impl Wiki {
+    /// Finds every keyword within the pages of the wiki, optionally only
+    /// those of the given types, with positions whose columns are counted in
+    /// the given unit
+    pub async fn keyword_occurrences(
+        &self,
+        keywords: Option<&[KeywordType]>,
+        unit: ColumnUnit,
+    ) -> async_graphql::Result<Vec<KeywordOccurrence>> {
+        let mut occurrences = Vec::new();
+
+        for page in self.indexed_pages().await? {
+            let index = LineIndex::new(&page.indexed.text);
+
+            for x in k::find_keywords(&page.indexed.page) {
+                let name = x.keyword.to_string();
+                let keyword = KeywordType::from(x.keyword);
+                if !keywords.is_none_or(|k| k.contains(&keyword)) {
+                    continue;
+                }
+
+                let position = index.position(x.region.offset(), unit.into());
+                occurrences.push(KeywordOccurrence {
+                    wiki: *self.index(),
+                    path: page.path.clone(),
+                    page: page.name.clone(),
+                    keyword,
+                    name,
+                    region: Region::from(x.region),
+                    position: position.map(TextPosition::from),
+                    line: position
+                        .and_then(|p| index.line(p.line))
+                        .map(|x| x.trim().to_string()),
+                    header: x.header,
+                    list_item: x.list_item,
+                });
+            }
+        }
+
+        Ok(occurrences)
+    }
+}
@@ -1,29 +1,104 @@
 #![allow(clippy::large_enum_variant)]
 
-use crate::{database::gql_db, utils, Config};
+use crate::{
+    database::gql_db,
+    metrics, overlay,
+    pages::{self, IndexedPage},
+    preview, revisions, syntax, utils, Config,
+};
 use entity::{TypedPredicate as P, *};
 use entity_async_graphql::*;
+use log::warn;
 use sha1::{Digest, Sha1};
-use std::path::{Path, PathBuf};
-use vimwiki::{self as v, Language, ParseError};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+use vimwiki::{self as v, graph as g};
+
+mod analysis;
+pub use analysis::*;
+
+mod completion;
+pub use completion::*;
+
+mod definition;
+pub use definition::*;
+
+mod diary;
+pub use diary::*;
+
+mod dynamic;
 
 mod errors;
 pub use errors::*;
 
+mod events;
+pub use events::*;
+
+mod highlight;
+pub use highlight::*;
+
+mod history;
+pub use history::*;
+
+mod html;
+pub use html::*;
+
+mod journal;
+pub use journal::*;
+
+mod keywords;
+pub use keywords::*;
+
+mod outline;
+pub use outline::*;
+
+mod playground;
+pub use playground::*;
+
+mod positions;
+pub use positions::*;
+
+mod search;
+pub use search::*;
+
+mod snapshot;
+pub use snapshot::*;
+
+mod stats;
+pub use stats::*;
+
 mod elements;
 pub use elements::*;
 
+/// Converts any error into a GraphQL error using its message
+pub(crate) fn to_gql_error(x: impl ToString) -> async_graphql::Error {
+    async_graphql::Error::new(x.to_string())
+}
+
 #[gql_ent]
 pub struct Wiki {
     index: usize,
     name: Option<String>,
     path: String,
 
+    /// File extension of the pages of the wiki
+    ext: String,
+
+    /// Syntax of the pages of the wiki, which is always `vimwiki` as it is
+    /// the only syntax that can be parsed
+    syntax: String,
+
     #[ent(edge(policy = "deep"))]
     files: Vec<ParsedFile>,
 }
 
 impl Wiki {
+    /// Syntax of the pages of every wiki
+    pub const SYNTAX: &'static str = "vimwiki";
+
     pub async fn load_all_from_config<F1, F2, F3, R1>(
         config: &Config,
         before_loading_files: F1,
@@ -71,9 +146,19 @@ impl Wiki {
     ) -> async_graphql::Result<Self> {
         let c_path: PathBuf = tokio::fs::canonicalize(path.as_ref())
             .await
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
-
-        let paths = utils::walk_and_resolve_paths(c_path.as_path(), ext);
+            .map_err(to_gql_error)?;
+
+        // NOTE: Walking a large wiki touches every directory, so it is done
+        //       on a blocking thread rather than on the async executor
+        let paths = {
+            let c_path = c_path.clone();
+            let ext = ext.to_string();
+            tokio::task::spawn_blocking(move || {
+                utils::walk_and_resolve_paths(c_path.as_path(), &ext)
+            })
+            .await
+            .map_err(to_gql_error)?
+        };
         let tracker = before_loading_files(paths.len());
 
         // Check if the wiki already exists, otherwise create a new one
@@ -84,7 +169,7 @@ impl Wiki {
                     .where_path(P::equals(c_path.to_string_lossy().to_string()))
                     .into(),
             )
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?
+            .map_err(to_gql_error)?
             .into_iter()
             .next();
 
@@ -96,10 +181,12 @@ impl Wiki {
                     .index(index)
                     .name(name.map(|x| x.as_ref().to_string()))
                     .path(c_path.to_string_lossy().to_string())
+                    .ext(ext.to_string())
+                    .syntax(Self::SYNTAX.to_string())
                     .files(Vec::new())
                     .finish_and_commit(),
             )
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?
+            .map_err(to_gql_error)?
         };
 
         let mut file_ids = Vec::new();
@@ -115,12 +202,89 @@ impl Wiki {
 
         // Update the wiki's files edge
         wiki.set_files_ids(file_ids);
-        let _ = wiki
-            .commit()
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        let _ = wiki.commit().map_err(to_gql_error)?;
 
         Ok(wiki)
     }
+
+    /// Produces the name of the page at the path as its path relative to the
+    /// wiki, using `/` as the separator and excluding the extension, or none
+    /// if the path is not within the wiki
+    pub fn page_name(&self, path: impl AsRef<Path>) -> Option<String> {
+        g::page_name(Path::new(self.path()), path.as_ref())
+    }
+
+    /// Finds the file of the page with the given name within the wiki,
+    /// where the name is the path relative to the wiki without extension
+    pub fn find_page(
+        &self,
+        name: &str,
+    ) -> async_graphql::Result<Option<ParsedFile>> {
+        let name = name.trim_matches('/');
+        Ok(self
+            .load_files()
+            .map_err(to_gql_error)?
+            .into_iter()
+            .find(|x| self.page_name(x.path()).as_deref() == Some(name)))
+    }
+
+    /// Returns the name and indexed page of every file of the wiki, skipping
+    /// files outside of the wiki and files whose pages cannot be loaded
+    pub async fn indexed_pages(&self) -> async_graphql::Result<Vec<WikiPage>> {
+        let mut pages = Vec::new();
+
+        for file in self.load_files().map_err(to_gql_error)? {
+            let name = match self.page_name(file.path()) {
+                Some(name) => name,
+                None => continue,
+            };
+
+            if let Some(indexed) = file.try_indexed_page().await {
+                pages.push(WikiPage {
+                    path: file.path().to_string(),
+                    name,
+                    indexed,
+                });
+            }
+        }
+
+        Ok(pages)
+    }
+
+    /// Analyzes the pages of the wiki by following the links between the
+    /// indexed pages of its files
+    pub async fn analyze(
+        &self,
+        options: &g::AnalysisOptions,
+    ) -> async_graphql::Result<PageAnalysis> {
+        let link_spaces = resolver_wiki(*self.index())
+            .map(|x| x.link_spaces)
+            .unwrap_or_default();
+        let mut graph = g::LinkGraph::new()
+            .with_primary_wiki(*self.index() as u32)
+            .with_link_spaces(link_spaces);
+
+        // Map names of all wikis to their indexes so named interwiki links
+        // resolve to the same pages as indexed interwiki links
+        for wiki in gql_db()?
+            .find_all_typed::<Wiki>(Wiki::query().into())
+            .map_err(to_gql_error)?
+        {
+            if let Some(name) = wiki.name() {
+                graph = graph.with_wiki_name(name, *wiki.index() as u32);
+            }
+        }
+
+        for page in self.indexed_pages().await? {
+            graph.add_page(
+                Some(*self.index() as u32),
+                &page.name,
+                &page.indexed.page,
+            );
+        }
+
+        Ok(PageAnalysis::from(graph.analyze(options)))
+    }
 }
 
 #[gql_ent]
@@ -145,18 +309,34 @@ impl ParsedFile {
     ) -> async_graphql::Result<Self> {
         use tokio::io::AsyncWriteExt;
 
+        // NOTE: The contents being overwritten are kept so that the
+        //       overwrite can be reverted like any other journaled edit
+        let previous = if overwrite {
+            tokio::fs::read_to_string(path.as_ref()).await.ok()
+        } else {
+            None
+        };
+
         let mut file = tokio::fs::OpenOptions::new()
             .write(true)
             .create(true)
             .create_new(!overwrite)
+            .truncate(overwrite)
             .open(path.as_ref())
             .await
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            .map_err(to_gql_error)?;
 
         let _ = file
             .write_all(contents.as_ref())
             .await
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            .map_err(to_gql_error)?;
+
+        if let Some(previous) = previous {
+            let c_path = tokio::fs::canonicalize(path.as_ref())
+                .await
+                .map_err(to_gql_error)?;
+            journal::record_overwrite(&c_path, previous, contents.as_ref())?;
+        }
 
         Self::load(wiki_id, path).await
     }
@@ -176,9 +356,15 @@ impl ParsedFile {
         wiki_id: impl Into<Option<Id>>,
         path: impl AsRef<Path>,
     ) -> async_graphql::Result<Self> {
-        let c_path: PathBuf = tokio::fs::canonicalize(path)
-            .await
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        // NOTE: A file missing from disk can still be loaded from its overlay
+        let c_path: PathBuf = match tokio::fs::canonicalize(path.as_ref()).await
+        {
+            Ok(c_path) => c_path,
+            Err(_) if overlay::contains(path.as_ref()) => {
+                path.as_ref().to_path_buf()
+            }
+            Err(x) => return Err(async_graphql::Error::new(x.to_string())),
+        };
 
         // First, search for an existing ent at the specified path
         let maybe_ent = gql_db()?
@@ -187,20 +373,22 @@ impl ParsedFile {
                     .where_path(P::equals(c_path.to_string_lossy().to_string()))
                     .into(),
             )
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?
+            .map_err(to_gql_error)?
             .into_iter()
             .next();
 
-        // Second, load the contents of the file into memory
-        let text = tokio::fs::read_to_string(c_path.as_path())
+        // Second, load the contents of the file into memory, preferring the
+        // unsaved contents of an editor's buffer over those on disk
+        let text = overlay::read_to_string(c_path.as_path())
             .await
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            .map_err(to_gql_error)?;
         let checksum = format!("{:x}", Sha1::digest(text.as_bytes()));
 
         // Third, determine if the content has changed from what we know. If it
         // hasn't, we return the current ent; otherwise, we continue with the
         // intention of replacing the ent by returning its old wiki and removing
         // it from the database
+        let existed = maybe_ent.is_some();
         let old_wiki_id = if let Some(ent) = maybe_ent {
             if ent.checksum() == &checksum {
                 return Ok(ent);
@@ -213,10 +401,17 @@ impl ParsedFile {
             None
         };
 
-        // Fourth, convert file contents into a vimwiki page
-        let page: v::Page = Language::from_vimwiki_str(&text).parse().map_err(
-            |x: ParseError| async_graphql::Error::new(x.to_string()),
-        )?;
+        // Fourth, convert file contents into a vimwiki page on a blocking
+        // thread so that parsing a large page does not stall the executor,
+        // keeping the page in memory for queries that work on parsed pages
+        let indexed = parse_indexed_page(checksum.clone(), text)
+            .await?
+            .map_err(|x| {
+                record_event(EventKind::ParseFailed, &c_path, x.clone());
+                async_graphql::Error::new(x)
+            })?;
+        let page = indexed.page.clone();
+        pages::insert(c_path.as_path(), indexed);
 
         // Fifth, save the parsed file with a temporary page id
         let mut parsed_file = GraphqlDatabaseError::wrap(
@@ -227,7 +422,7 @@ impl ParsedFile {
                 .page(EPHEMERAL_ID)
                 .finish_and_commit(),
         )
-        .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        .map_err(to_gql_error)?;
 
         // Sixth, save the vimwiki page as a graphql page
         let page_id = Page::create_from_vimwiki(parsed_file.id(), page)?.id();
@@ -235,21 +430,78 @@ impl ParsedFile {
         // Seventh, update the parsed file's page id
         parsed_file.set_page_id(page_id);
         parsed_file.commit()?;
+        revisions::modified(c_path.as_path());
+        record_event(
+            if existed {
+                EventKind::FileChanged
+            } else {
+                EventKind::FileAdded
+            },
+            c_path,
+            None,
+        );
 
         Ok(parsed_file)
     }
 
+    /// Registers the unsaved contents of an editor's buffer for the file at
+    /// the path, reloading the file so that queries reflect the buffer
+    /// rather than the contents on disk
+    pub async fn set_overlay(
+        wiki_id: impl Into<Option<Id>>,
+        path: impl AsRef<Path>,
+        text: impl Into<String>,
+    ) -> async_graphql::Result<Self> {
+        let path = overlay::set(path, text).await;
+        let file = Self::load(wiki_id, path.as_path()).await?;
+        preview::notify_file_changed(path);
+        Ok(file)
+    }
+
+    /// Removes the overlay of the file at the path, reloading the file from
+    /// disk, or removing it if it only ever existed as an overlay
+    pub async fn clear_overlay(
+        path: impl AsRef<Path>,
+    ) -> async_graphql::Result<Option<Self>> {
+        let path = overlay::key(path).await;
+        let _ = overlay::clear(path.as_path()).await;
+
+        let file = if path.exists() {
+            Some(Self::load(None, path.as_path()).await?)
+        } else {
+            let ents = gql_db()?
+                .find_all_typed::<ParsedFile>(
+                    ParsedFile::query()
+                        .where_path(P::equals(
+                            path.to_string_lossy().to_string(),
+                        ))
+                        .into(),
+                )
+                .map_err(to_gql_error)?;
+            for ent in ents {
+                ent.remove().map_err(to_gql_error)?;
+                revisions::removed(path.as_path());
+                pages::remove(path.as_path());
+                record_event(EventKind::FileRemoved, path.as_path(), None);
+            }
+            None
+        };
+
+        preview::notify_file_changed(path);
+        Ok(file)
+    }
+
     pub async fn rename<P1: AsRef<Path>, P2: AsRef<Path>>(
         from_path: P1,
         to_path: P2,
     ) -> async_graphql::Result<()> {
         let c_from_path = tokio::fs::canonicalize(from_path)
             .await
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            .map_err(to_gql_error)?;
 
         let c_to_path = tokio::fs::canonicalize(to_path)
             .await
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            .map_err(to_gql_error)?;
 
         let ents = gql_db()?
             .find_all_typed::<ParsedFile>(
@@ -259,12 +511,37 @@ impl ParsedFile {
                     ))
                     .into(),
             )
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            .map_err(to_gql_error)?;
 
         for mut ent in ents {
             ent.set_path(c_to_path.to_string_lossy().to_string());
-            ent.commit()
-                .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            ent.commit().map_err(to_gql_error)?;
+            revisions::removed(c_from_path.as_path());
+            revisions::modified(c_to_path.as_path());
+            pages::rename(c_from_path.as_path(), c_to_path.as_path());
+            record_event(
+                EventKind::FileRenamed,
+                c_to_path.as_path(),
+                format!("Renamed from {}", c_from_path.to_string_lossy()),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Parses every loaded file again, such as after the syntax used to
+    /// parse them has changed, even if the contents of the file have not
+    pub async fn reparse_all() -> async_graphql::Result<()> {
+        let ents = gql_db()?
+            .find_all_typed::<ParsedFile>(ParsedFile::query().into())
+            .map_err(to_gql_error)?;
+
+        for ent in ents {
+            let path = PathBuf::from(ent.path());
+            let wiki_id = ent.wiki_id();
+            ent.remove().map_err(to_gql_error)?;
+            pages::remove(path.as_path());
+            Self::load(wiki_id, path.as_path()).await?;
         }
 
         Ok(())
@@ -281,9 +558,8 @@ impl ParsedFile {
     }
 
     pub async fn remove(path: impl AsRef<Path>) -> async_graphql::Result<()> {
-        let c_path = tokio::fs::canonicalize(path)
-            .await
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        let c_path =
+            tokio::fs::canonicalize(path).await.map_err(to_gql_error)?;
 
         let ents = gql_db()?
             .find_all_typed::<ParsedFile>(
@@ -291,13 +567,86 @@ impl ParsedFile {
                     .where_path(P::equals(c_path.to_string_lossy().to_string()))
                     .into(),
             )
-            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            .map_err(to_gql_error)?;
 
         for ent in ents {
-            ent.remove()
-                .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+            ent.remove().map_err(to_gql_error)?;
+            revisions::removed(c_path.as_path());
+            pages::remove(c_path.as_path());
+            record_event(EventKind::FileRemoved, c_path.as_path(), None);
         }
 
         Ok(())
     }
+
+    /// Returns the contents of the file and the page parsed from them when
+    /// the file was indexed, parsing the file again if the page is no longer
+    /// kept in memory
+    pub async fn indexed_page(
+        &self,
+    ) -> async_graphql::Result<Arc<IndexedPage>> {
+        if let Some(page) = pages::get(self.path(), self.checksum()) {
+            return Ok(page);
+        }
+
+        let text = overlay::read_to_string(Path::new(self.path()))
+            .await
+            .map_err(to_gql_error)?;
+        let checksum = format!("{:x}", Sha1::digest(text.as_bytes()));
+        let page = parse_indexed_page(checksum, text)
+            .await?
+            .map_err(async_graphql::Error::new)?;
+        pages::insert(self.path(), Arc::clone(&page));
+        Ok(page)
+    }
+
+    /// Returns the indexed page of the file like [`Self::indexed_page`],
+    /// logging and skipping the file if its page cannot be loaded so that one
+    /// bad file does not fail a query over many files
+    pub async fn try_indexed_page(&self) -> Option<Arc<IndexedPage>> {
+        match self.indexed_page().await {
+            Ok(page) => Some(page),
+            Err(x) => {
+                warn!("Skipping {}: {}", self.path(), x.message);
+                None
+            }
+        }
+    }
+}
+
+/// Represents the indexed page of a file within a wiki
+pub struct WikiPage {
+    /// Path to the file of the page
+    pub path: String,
+
+    /// Name of the page relative to its wiki
+    pub name: String,
+
+    /// Contents of the file and the page parsed from them
+    pub indexed: Arc<IndexedPage>,
+}
+
+/// Parses the text of a file on a blocking thread so that parsing a large
+/// page does not stall the executor, producing the error of a page that
+/// fails to parse as a string
+async fn parse_indexed_page(
+    checksum: String,
+    text: String,
+) -> async_graphql::Result<Result<Arc<IndexedPage>, String>> {
+    tokio::task::spawn_blocking(move || {
+        let start = Instant::now();
+        let page = syntax::parse_page(&text)
+            .map(v::Page::into_owned)
+            .map_err(|x| x.to_string());
+        metrics::record_parse(start.elapsed(), page.is_ok());
+        page.map(|page| {
+            Arc::new(IndexedPage {
+                checksum,
+                text,
+                page,
+            })
+        })
+    })
+    .await
+    .map_err(to_gql_error)
 }
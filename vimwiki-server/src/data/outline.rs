@@ -0,0 +1,90 @@
+use super::{to_gql_error, Region};
+use crate::{overlay, syntax};
+use std::path::Path;
+use vimwiki::{outline as o, Page};
+
+/// Represents the kind of element listed within an outline
+#[derive(async_graphql::Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SymbolKind {
+    Header,
+    List,
+    Table,
+}
+
+impl From<o::SymbolKind> for SymbolKind {
+    fn from(kind: o::SymbolKind) -> Self {
+        match kind {
+            o::SymbolKind::Header => Self::Header,
+            o::SymbolKind::List => Self::List,
+            o::SymbolKind::Table => Self::Table,
+        }
+    }
+}
+
+/// Represents an element within the outline of a page, shaped after the
+/// `DocumentSymbol` of the language server protocol
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct OutlineSymbol {
+    /// Text of a header, or a short label for other elements
+    name: String,
+
+    /// Additional details about the element, such as the number of items
+    /// within a list
+    detail: Option<String>,
+
+    /// Kind of element represented by the symbol
+    kind: SymbolKind,
+
+    /// Numeric `SymbolKind` of the language server protocol that best
+    /// represents the element
+    lsp_kind: u32,
+
+    /// Level of a header (1, 2, 3, etc), or none for other elements
+    level: Option<usize>,
+
+    /// Region spanning the element and everything it contains, which for a
+    /// header is its entire section
+    region: Region,
+
+    /// Region of the element itself, which for a header excludes its section
+    selection_region: Region,
+
+    /// Symbols contained within the element
+    children: Vec<OutlineSymbol>,
+}
+
+impl From<o::OutlineSymbol> for OutlineSymbol {
+    fn from(symbol: o::OutlineSymbol) -> Self {
+        Self {
+            name: symbol.name,
+            detail: symbol.detail,
+            kind: SymbolKind::from(symbol.kind),
+            lsp_kind: symbol.kind.to_lsp_kind(),
+            level: symbol.level,
+            region: Region::from(symbol.region),
+            selection_region: Region::from(symbol.selection_region),
+            children: symbol
+                .children
+                .into_iter()
+                .map(OutlineSymbol::from)
+                .collect(),
+        }
+    }
+}
+
+/// Builds the hierarchical outline of the file at the given path (or its
+/// overlay), optionally including the top-level lists and tables within
+/// each section
+pub async fn page_outline(
+    path: impl AsRef<Path>,
+    options: o::OutlineOptions,
+) -> async_graphql::Result<Vec<OutlineSymbol>> {
+    let path = overlay::key(path).await;
+    let text = overlay::read_to_string(&path).await.map_err(to_gql_error)?;
+    let page: Page = syntax::parse_page(&text).map_err(to_gql_error)?;
+
+    Ok(o::outline(&page, options)
+        .into_iter()
+        .map(OutlineSymbol::from)
+        .collect())
+}
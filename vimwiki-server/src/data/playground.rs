@@ -0,0 +1,116 @@
+use super::{find_broken_links, render, to_gql_error, HtmlOptions, Region};
+use crate::{preview, syntax};
+use async_graphql::Json;
+use std::path::Path;
+use vimwiki::{Page, ParseError};
+
+/// Represents a problem found within the text given to the playground
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct PlaygroundDiagnostic {
+    /// Description of the problem
+    message: String,
+
+    /// Region of the text where the problem was found, or none if the
+    /// problem concerns the text as a whole
+    region: Option<Region>,
+}
+
+/// Represents the result of parsing and rendering text in the playground,
+/// none of which is persisted by the server
+#[derive(Clone, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct Playground {
+    /// Top-level elements of the parsed text as JSON, or none if the text
+    /// failed to parse
+    elements: Option<Json<serde_json::Value>>,
+
+    /// HTML fragment rendered from the parsed text, or none if the text
+    /// failed to parse
+    html: Option<String>,
+
+    /// Problems found within the text, such as a failure to parse it or
+    /// links whose targets do not exist
+    diagnostics: Vec<PlaygroundDiagnostic>,
+}
+
+/// Parses and renders the text without loading it into the server, where
+/// links are only checked if the path of the file the text would belong to
+/// is provided
+pub fn playground(
+    text: &str,
+    path: Option<&Path>,
+    options: HtmlOptions,
+) -> async_graphql::Result<Playground> {
+    let page: Page = match syntax::parse_page(text) {
+        Ok(page) => page,
+        Err(x) => {
+            return Ok(Playground {
+                elements: None,
+                html: None,
+                diagnostics: vec![PlaygroundDiagnostic {
+                    message: (x as ParseError).to_string(),
+                    region: None,
+                }],
+            })
+        }
+    };
+
+    let elements =
+        serde_json::to_value(page.elements()).map_err(to_gql_error)?;
+
+    let mut config = preview::html_config();
+    if let Some(path) = path {
+        config = preview::config_for_path(&config, path);
+    }
+    options.apply(&mut config);
+    let html = render(config, &page, None)?;
+
+    let diagnostics = match path {
+        Some(path) => find_broken_links(path, &page)?
+            .into_iter()
+            .map(|x| PlaygroundDiagnostic {
+                message: format!("Broken link {}: {}", x.link, x.reason),
+                region: Some(Region::from(x.region)),
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(Playground {
+        elements: Some(Json(elements)),
+        html,
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playground_should_return_elements_and_html() {
+        let result =
+            playground("= Header =\n", None, HtmlOptions::default()).unwrap();
+        let elements = result.elements.unwrap().0;
+        assert_eq!(elements.as_array().map(Vec::len), Some(1));
+        assert!(result.html.unwrap().contains("<h1"));
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn playground_should_report_broken_links_relative_to_path() {
+        let path = std::env::temp_dir()
+            .join("vimwiki-server-playground")
+            .join("index.wiki");
+        let result = playground(
+            "[[missing page]]\n",
+            Some(&path),
+            HtmlOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(
+            result.diagnostics[0].region,
+            Some(Region::from(vimwiki::Region::new_at_depth(0, 16, 1)))
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use super::to_gql_error;
+use crate::overlay;
+use std::path::Path;
+use vimwiki::{
+    positions::{self as p, LineIndex},
+    Region,
+};
+
+/// Represents the unit used to count columns within a line
+#[derive(async_graphql::Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ColumnUnit {
+    /// Bytes of UTF-8, matching the offsets of regions
+    Byte,
+
+    /// Code units of UTF-16, as used by the language server protocol
+    Utf16,
+
+    /// Unicode scalar values (chars)
+    Char,
+
+    /// Extended grapheme clusters, matching what is displayed as a single
+    /// character
+    Grapheme,
+}
+
+impl From<ColumnUnit> for p::ColumnUnit {
+    fn from(unit: ColumnUnit) -> Self {
+        match unit {
+            ColumnUnit::Byte => Self::Byte,
+            ColumnUnit::Utf16 => Self::Utf16,
+            ColumnUnit::Char => Self::Char,
+            ColumnUnit::Grapheme => Self::Grapheme,
+        }
+    }
+}
+
+/// Represents a line and column within a file, both counted from zero
+#[derive(Copy, Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct TextPosition {
+    line: usize,
+    column: usize,
+}
+
+impl From<p::Position> for TextPosition {
+    fn from(position: p::Position) -> Self {
+        Self {
+            line: position.line,
+            column: position.column,
+        }
+    }
+}
+
+/// Represents the positions from the start up to (but not including) the
+/// end of some text within a file
+#[derive(Copy, Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct TextRange {
+    start: TextPosition,
+    end: TextPosition,
+}
+
+/// Converts the region starting at the byte offset of the file at the path
+/// (or its overlay) into positions with columns in the given unit, or None
+/// if the region is not within the file
+pub async fn text_range(
+    path: impl AsRef<Path>,
+    offset: usize,
+    len: usize,
+    unit: ColumnUnit,
+) -> async_graphql::Result<Option<TextRange>> {
+    let path = overlay::key(path).await;
+    let text = overlay::read_to_string(&path).await.map_err(to_gql_error)?;
+
+    Ok(LineIndex::new(&text)
+        .range(Region::new(offset, len), unit.into())
+        .map(|range| TextRange {
+            start: range.start.into(),
+            end: range.end.into(),
+        }))
+}
+
+/// Converts the line and column (in the given unit) of the file at the path
+/// (or its overlay) into a byte offset, or None if the line is beyond the
+/// file; columns beyond the end of the line are clamped to the end
+pub async fn text_offset(
+    path: impl AsRef<Path>,
+    line: usize,
+    column: usize,
+    unit: ColumnUnit,
+) -> async_graphql::Result<Option<usize>> {
+    let path = overlay::key(path).await;
+    let text = overlay::read_to_string(&path).await.map_err(to_gql_error)?;
+
+    Ok(LineIndex::new(&text)
+        .offset(p::Position::new(line, column), unit.into()))
+}
@@ -0,0 +1,146 @@
+use super::Wiki;
+use vimwiki::site::{SearchDocument, SearchIndex};
+
+/// Weight of each occurrence of a term within the title of a page relative
+/// to an occurrence within its body
+const TITLE_WEIGHT: usize = 10;
+
+/// Maximum characters of the line of text shown with each search result
+const MAX_EXCERPT_LEN: usize = 120;
+
+/// Represents a page whose title or text contains every term of a search
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct SearchResult {
+    /// Index of the wiki containing the page
+    wiki: usize,
+
+    /// Path to the file of the page
+    path: String,
+
+    /// Name of the page relative to its wiki
+    page: String,
+
+    /// Title of the page, otherwise the text of its first header, otherwise
+    /// its name
+    title: String,
+
+    /// Line of plain text containing the first term, trimmed and shortened
+    excerpt: Option<String>,
+
+    /// How well the page matches, where occurrences of terms within the
+    /// title count for more than those within the text
+    score: usize,
+}
+
+impl SearchResult {
+    /// Orders results from best to worst match
+    pub fn sort(results: &mut [Self]) {
+        results.sort_by(|a, b| {
+            b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path))
+        });
+    }
+}
+
+/// Splits the text of a search into lowercase terms
+pub fn search_terms(text: &str) -> Vec<String> {
+    text.split_whitespace().map(str::to_lowercase).collect()
+}
+
+impl Wiki {
+    /// Searches the title and plain text of every page of the wiki for the
+    /// terms, case-insensitively, returning the pages containing all of them
+    pub async fn search(
+        &self,
+        terms: &[String],
+    ) -> async_graphql::Result<Vec<SearchResult>> {
+        let mut results = Vec::new();
+        if terms.is_empty() {
+            return Ok(results);
+        }
+
+        for page in self.indexed_pages().await? {
+            let mut index = SearchIndex::new();
+            index.add_page(&page.name, "", &page.indexed.page);
+            let doc = &index.documents()[0];
+            if let Some(score) = score(doc, terms) {
+                results.push(SearchResult {
+                    wiki: *self.index(),
+                    path: page.path,
+                    page: page.name,
+                    title: doc.title.to_string(),
+                    excerpt: excerpt(&doc.body, &terms[0]),
+                    score,
+                });
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+/// Scores the document by the occurrences of the terms within its title and
+/// body, or None if any term does not occur
+fn score(doc: &SearchDocument, terms: &[String]) -> Option<usize> {
+    let title = doc.title.to_lowercase();
+    let body = doc.body.to_lowercase();
+
+    let mut total = 0;
+    for term in terms {
+        let score = title.matches(term.as_str()).count() * TITLE_WEIGHT
+            + body.matches(term.as_str()).count();
+        if score == 0 {
+            return None;
+        }
+        total += score;
+    }
+    Some(total)
+}
+
+/// Finds the first line of the body containing the term
+fn excerpt(body: &str, term: &str) -> Option<String> {
+    let line = body
+        .lines()
+        .find(|line| line.to_lowercase().contains(term))?
+        .trim();
+    if line.chars().count() > MAX_EXCERPT_LEN {
+        let line: String = line.chars().take(MAX_EXCERPT_LEN).collect();
+        Some(format!("{}...", line))
+    } else {
+        Some(line.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(title: &str, body: &str) -> SearchDocument {
+        SearchDocument {
+            id: String::new(),
+            url: String::new(),
+            title: title.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn score_should_weigh_titles_and_require_every_term() {
+        let terms = search_terms("Rust  wiki");
+        assert_eq!(terms, ["rust", "wiki"]);
+
+        let doc = doc("Rust notes", "a wiki about rust\nand more rust");
+        assert_eq!(score(&doc, &terms), Some(TITLE_WEIGHT + 2 + 1));
+        assert_eq!(score(&doc, &search_terms("rust python")), None);
+    }
+
+    #[test]
+    fn excerpt_should_find_the_first_line_with_the_term() {
+        let body = "first line\n  second Rust line  \nthird rust";
+        assert_eq!(excerpt(body, "rust").as_deref(), Some("second Rust line"));
+        assert_eq!(excerpt(body, "python"), None);
+
+        let long = "x".repeat(MAX_EXCERPT_LEN + 1);
+        let short = excerpt(&long, "x").unwrap();
+        assert_eq!(short.len(), MAX_EXCERPT_LEN + 3);
+    }
+}
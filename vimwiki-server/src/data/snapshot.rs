@@ -0,0 +1,279 @@
+use super::{to_gql_error, ParsedFile, Region, Wiki};
+use crate::{database::gql_db, revisions};
+use entity::*;
+use sha1::{Digest, Sha1};
+use vimwiki::{graph::collect_links, tags::TagIndex};
+
+/// Represents a top-level element of a page within a snapshot
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct SnapshotElement {
+    /// Type of element (e.g. `paragraph`)
+    element_type: String,
+
+    /// Region of the element within its page
+    region: Region,
+}
+
+/// Represents a link found within a page of a snapshot
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct SnapshotLink {
+    /// Target of the link as it was written
+    target: String,
+
+    /// Region of the link within its page
+    region: Region,
+}
+
+/// Represents a tag found within a page of a snapshot
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct SnapshotTag {
+    /// Name of the tag
+    tag: String,
+
+    /// Region of the tags element containing the tag within its page
+    region: Region,
+}
+
+/// Represents a single indexed page of the workspace
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct SnapshotPage {
+    /// Index of the wiki containing the page
+    wiki: usize,
+
+    /// Name of the page relative to its wiki
+    name: String,
+
+    /// Path to the file of the page
+    path: String,
+
+    /// Checksum (sha1) of the contents of the page
+    checksum: String,
+
+    /// Revision of the workspace when the page last changed
+    revision: u64,
+
+    /// Top-level elements of the page in order
+    elements: Vec<SnapshotElement>,
+
+    /// Links of the page in order
+    links: Vec<SnapshotLink>,
+
+    /// Tags of the page in order
+    tags: Vec<SnapshotTag>,
+}
+
+/// Represents one batch of pages of the indexed workspace
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct WorkspaceSnapshot {
+    /// Token identifying the state of the workspace, which only changes
+    /// when a page is added, removed, or modified
+    token: String,
+
+    /// Revision of the workspace when the batch was taken, which can be
+    /// passed to `changesSince` to sync later changes
+    revision: u64,
+
+    /// Total pages within the workspace
+    total: usize,
+
+    /// Offset to use to request the next batch of pages, or null if this is
+    /// the last batch
+    next_offset: Option<usize>,
+
+    /// Pages of the batch, sorted by wiki and then path
+    pages: Vec<SnapshotPage>,
+}
+
+/// Produces a token from the path and checksum of every indexed file, so
+/// that any change to the set of files or their contents changes the token
+fn consistency_token(files: &[(usize, ParsedFile)]) -> String {
+    let mut hasher = Sha1::new();
+    for (wiki, file) in files {
+        hasher.update(format!(
+            "{}\0{}\0{}\n",
+            wiki,
+            file.path(),
+            file.checksum()
+        ));
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+async fn snapshot_page(
+    wiki: &Wiki,
+    file: &ParsedFile,
+) -> async_graphql::Result<Option<SnapshotPage>> {
+    let path = file.path();
+    let name = match wiki.page_name(path) {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+
+    let indexed = match file.try_indexed_page().await {
+        Some(indexed) => indexed,
+        None => return Ok(None),
+    };
+    let page = &indexed.page;
+
+    let mut tag_index = TagIndex::new();
+    tag_index.add_page(&name, page);
+
+    Ok(Some(SnapshotPage {
+        wiki: *wiki.index(),
+        path: path.to_string(),
+        checksum: file.checksum().to_string(),
+        revision: revisions::get(path).map(|x| x.revision).unwrap_or_default(),
+        elements: page
+            .elements()
+            .iter()
+            .map(|x| SnapshotElement {
+                element_type: x.as_inner().kind_name().to_string(),
+                region: Region::from(x.region()),
+            })
+            .collect(),
+        links: collect_links(page)
+            .into_iter()
+            .map(|x| SnapshotLink {
+                target: x.as_inner().to_string(),
+                region: Region::from(x.region()),
+            })
+            .collect(),
+        tags: tag_index
+            .entries()
+            .iter()
+            .map(|x| SnapshotTag {
+                tag: tag_index.resolve(x.tag).to_string(),
+                region: Region::from(x.region),
+            })
+            .collect(),
+        name,
+    }))
+}
+
+/// Exports up to `limit` pages of every wiki starting at the offset
+///
+/// If a token from an earlier batch is provided and the workspace has since
+/// changed, an error is returned so that the sync can start over rather than
+/// mixing pages from different states of the workspace.
+pub async fn workspace_snapshot(
+    token: Option<&str>,
+    offset: usize,
+    limit: usize,
+) -> async_graphql::Result<WorkspaceSnapshot> {
+    let mut wikis = gql_db()?
+        .find_all_typed::<Wiki>(Wiki::query().into())
+        .map_err(to_gql_error)?;
+    wikis.sort_unstable_by_key(|x| *x.index());
+
+    // NOTE: The revision is taken before gathering files so that a change
+    //       made while the batch is built is included in later changes
+    let revision = revisions::current();
+    let mut files = Vec::new();
+    for wiki in wikis.iter() {
+        let mut wiki_files: Vec<(usize, ParsedFile)> = wiki
+            .load_files()
+            .map_err(to_gql_error)?
+            .into_iter()
+            .map(|x| (*wiki.index(), x))
+            .collect();
+        wiki_files.sort_unstable_by(|a, b| a.1.path().cmp(b.1.path()));
+        files.extend(wiki_files);
+    }
+
+    let current = consistency_token(&files);
+    if let Some(token) = token {
+        if token != current {
+            return Err(to_gql_error(
+                "Workspace has changed since the snapshot began",
+            ));
+        }
+    }
+
+    let mut pages = Vec::new();
+    let end = offset.saturating_add(limit).min(files.len());
+    for (idx, file) in files.iter().take(end).skip(offset) {
+        let wiki = match wikis.iter().find(|x| x.index() == idx) {
+            Some(wiki) => wiki,
+            None => continue,
+        };
+        pages.extend(snapshot_page(wiki, file).await?);
+    }
+
+    Ok(WorkspaceSnapshot {
+        token: current,
+        revision,
+        total: files.len(),
+        next_offset: Some(end).filter(|x| *x < files.len()),
+        pages,
+    })
+}
+
+/// Represents a page added, modified, or removed since some revision
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct PageChange {
+    /// Path to the file of the page
+    path: String,
+
+    /// Revision of the workspace when the page last changed
+    revision: u64,
+
+    /// True if the page was removed
+    removed: bool,
+
+    /// Current contents of the page, or null if it was removed or does not
+    /// belong to a wiki
+    page: Option<SnapshotPage>,
+}
+
+/// Represents every page changed since some revision
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct PageChanges {
+    /// Current revision of the workspace, which can be passed to
+    /// `changesSince` to sync the next set of changes
+    revision: u64,
+
+    /// Latest change of each page, ordered from oldest to newest
+    changes: Vec<PageChange>,
+}
+
+/// Collects the latest change of every page changed after the revision
+pub async fn changes_since(
+    revision: u64,
+) -> async_graphql::Result<PageChanges> {
+    let wikis = gql_db()?
+        .find_all_typed::<Wiki>(Wiki::query().into())
+        .map_err(to_gql_error)?;
+
+    let current = revisions::current();
+    let mut changes = Vec::new();
+    for (path, change) in revisions::changed_since(revision) {
+        let path = path.to_string_lossy().to_string();
+        let page = if change.removed {
+            None
+        } else {
+            let file = ParsedFile::query()
+                .where_path(TypedPredicate::equals(path.to_string()))
+                .execute()
+                .map_err(to_gql_error)?
+                .into_iter()
+                .next();
+            let wiki = wikis.iter().find(|x| x.page_name(&path).is_some());
+            match (wiki, file) {
+                (Some(wiki), Some(file)) => snapshot_page(wiki, &file).await?,
+                _ => None,
+            }
+        };
+
+        changes.push(PageChange {
+            path,
+            revision: change.revision,
+            removed: change.removed,
+            page,
+        });
+    }
+
+    Ok(PageChanges {
+        revision: current,
+        changes,
+    })
+}
@@ -0,0 +1,155 @@
+use super::{to_gql_error, Wiki};
+use crate::{overlay, syntax};
+use std::path::Path;
+use vimwiki::{stats as s, Page, Timestamp};
+
+/// Represents the total elements of some type within one or more pages
+#[derive(Clone, Debug, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct ElementCount {
+    /// Type of element (e.g. `paragraph`)
+    element_type: String,
+
+    /// Total elements of the type
+    count: usize,
+}
+
+/// Represents statistics about the content of one or more pages
+#[derive(Clone, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct PageStats {
+    /// Total words of text, including the descriptions of links but
+    /// excluding code, math, and comments
+    words: usize,
+
+    /// Estimated minutes to read the text, rounded up
+    reading_minutes: usize,
+
+    /// Total elements by type, sorted by type
+    elements: Vec<ElementCount>,
+
+    /// Total list items with a TODO status
+    tasks: usize,
+
+    /// Total list items that have been completed or rejected
+    completed_tasks: usize,
+
+    /// Ratio of completed tasks to all tasks (0.0 to 1.0), or null if there
+    /// are no tasks
+    task_completion: Option<f64>,
+
+    /// Total links of any kind
+    links: usize,
+
+    /// Total links to something not local to the system (e.g. a website)
+    external_links: usize,
+
+    /// When the page was last modified (RFC 3339), if known
+    last_modified: Option<String>,
+}
+
+impl From<&s::PageStats> for PageStats {
+    fn from(stats: &s::PageStats) -> Self {
+        Self {
+            words: stats.words,
+            reading_minutes: stats.reading_minutes(),
+            elements: stats
+                .elements
+                .iter()
+                .map(|(ty, count)| ElementCount {
+                    element_type: ty.to_string(),
+                    count: *count,
+                })
+                .collect(),
+            tasks: stats.tasks,
+            completed_tasks: stats.completed_tasks,
+            task_completion: stats.task_completion(),
+            links: stats.links,
+            external_links: stats.external_links,
+            last_modified: stats.last_modified.map(|x| x.to_string()),
+        }
+    }
+}
+
+/// Represents the statistics of a page of a wiki
+#[derive(Clone, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct NamedPageStats {
+    /// Name of the page relative to its wiki
+    name: String,
+
+    /// Statistics of the page
+    stats: PageStats,
+}
+
+/// Represents statistics about every page of a wiki alongside their totals
+#[derive(Clone, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct WikiStats {
+    /// Combined statistics of every page
+    total: PageStats,
+
+    /// Statistics of each page, sorted by name
+    pages: Vec<NamedPageStats>,
+}
+
+impl From<&s::WikiStats> for WikiStats {
+    fn from(stats: &s::WikiStats) -> Self {
+        Self {
+            total: PageStats::from(stats.total()),
+            pages: stats
+                .pages()
+                .iter()
+                .map(|(name, stats)| NamedPageStats {
+                    name: name.to_string(),
+                    stats: PageStats::from(stats),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Computes the statistics of the file at the path, preferring its overlay
+/// over its contents on disk
+async fn file_stats(path: &Path) -> async_graphql::Result<s::PageStats> {
+    let text = overlay::read_to_string(path).await.map_err(to_gql_error)?;
+    let page: Page = syntax::parse_page(&text).map_err(to_gql_error)?;
+
+    Ok(page_stats_at(path, &page).await)
+}
+
+/// Computes the statistics of the page of the file at the path
+async fn page_stats_at(path: &Path, page: &Page<'_>) -> s::PageStats {
+    // NOTE: A file with an overlay has been modified more recently than its
+    //       contents on disk, so it has no known time of modification
+    let last_modified = if overlay::contains(path) {
+        None
+    } else {
+        tokio::fs::metadata(path)
+            .await
+            .and_then(|x| x.modified())
+            .ok()
+            .map(Timestamp::from)
+    };
+
+    s::PageStats::from_page(page).with_last_modified(last_modified)
+}
+
+/// Computes the statistics of the file at the path
+pub async fn page_stats(
+    path: impl AsRef<Path>,
+) -> async_graphql::Result<PageStats> {
+    let path = overlay::key(path).await;
+    Ok(PageStats::from(&file_stats(&path).await?))
+}
+
+impl Wiki {
+    /// Computes the statistics of every page of the wiki
+    pub async fn stats(&self) -> async_graphql::Result<WikiStats> {
+        let mut stats = s::WikiStats::new();
+
+        for page in self.indexed_pages().await? {
+            let page_stats =
+                page_stats_at(Path::new(&page.path), &page.indexed.page).await;
+            stats.add_page(&page.name, page_stats);
+        }
+
+        Ok(WikiStats::from(&stats))
+    }
+}
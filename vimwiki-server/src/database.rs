@@ -1,9 +1,11 @@
-use crate::{data::Wiki, utils, Config, Opt};
+use crate::{data::Wiki, syntax, utils, Config, Opt};
 use async_graphql::ErrorExtensions;
 use entity::*;
 use entity_inmemory::InmemoryDatabase;
+use log::*;
 use snafu::{ResultExt, Snafu};
 use std::path::PathBuf;
+use vimwiki::ParserConfig;
 
 #[derive(Debug, Snafu)]
 pub enum VimwikiDatabaseError {
@@ -56,11 +58,17 @@ pub async fn load(
         return Ok(db);
     }
 
-    // Load our database from a cache file if it exists, otherwise we
-    // start with a clean cache file
+    // Load our database from a cache file if it exists and was parsed with
+    // the same syntax, otherwise we start with a clean cache file
     let database = {
         let path = cache_file(opt);
-        if path.exists() {
+        let same_syntax =
+            cached_syntax(opt).await.as_ref() == Some(&config.syntax);
+        if path.exists() && !same_syntax {
+            info!("Ignoring cached database parsed with a different syntax");
+        }
+
+        if path.exists() && same_syntax {
             let contents = tokio::fs::read_to_string(&path)
                 .await
                 .context(LoadDatabase { path })?;
@@ -120,11 +128,33 @@ pub async fn store(opt: &Opt) -> async_graphql::Result<()> {
         .context(StoreDatabase { path })
         .map_err(|x| x.extend())?;
 
+    let syntax = serde_json::to_string(&syntax::parser_config())
+        .context(DatabaseToJson {})
+        .map_err(|x| x.extend())?;
+    let path = syntax_file(opt);
+    tokio::fs::write(&path, syntax)
+        .await
+        .context(StoreDatabase { path })
+        .map_err(|x| x.extend())?;
+
     Ok(())
 }
 
+/// Reads the syntax that the cached database was parsed with, if known
+async fn cached_syntax(opt: &Opt) -> Option<ParserConfig> {
+    let contents = tokio::fs::read_to_string(syntax_file(opt)).await.ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
 /// Represents the path to the cache file for the database
 #[inline]
 fn cache_file(opt: &Opt) -> PathBuf {
-    opt.cache.join("vimwiki.database")
+    opt.cache().join("vimwiki.database")
+}
+
+/// Represents the path to the file recording the syntax used to parse the
+/// pages of the cached database
+#[inline]
+fn syntax_file(opt: &Opt) -> PathBuf {
+    opt.cache().join("vimwiki.syntax")
 }
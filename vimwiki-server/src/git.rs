@@ -0,0 +1,179 @@
+use crate::config::GitConfig;
+use lazy_static::lazy_static;
+use log::*;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::RwLock,
+};
+
+/// Separates fields of a commit within the log output
+const FIELD_SEP: char = '\x1f';
+
+lazy_static! {
+    static ref GIT_CONFIG: RwLock<GitConfig> =
+        RwLock::new(GitConfig::default());
+}
+
+/// Sets the git settings used by the server when applying edits
+pub fn set_config(config: GitConfig) {
+    if let Ok(mut x) = GIT_CONFIG.write() {
+        *x = config;
+    }
+}
+
+/// Represents a commit that touched a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitInfo {
+    pub id: String,
+    pub author: String,
+    pub timestamp: String,
+    pub summary: String,
+}
+
+/// Returns up to `limit` commits that changed the file at the path, newest
+/// first, following the file across renames
+pub fn page_history(path: &Path, limit: usize) -> io::Result<Vec<CommitInfo>> {
+    let (dir, name) = split_path(path)?;
+    let output = run(
+        &dir,
+        &[
+            "log",
+            "--follow",
+            &format!("--max-count={}", limit),
+            "--format=%H%x1f%an%x1f%aI%x1f%s",
+            "--",
+            &name,
+        ],
+    )?;
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, FIELD_SEP);
+            Some(CommitInfo {
+                id: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                timestamp: fields.next()?.to_string(),
+                summary: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Returns the contents of the file at the path as of the given revision
+pub fn file_at_revision(path: &Path, rev: &str) -> io::Result<String> {
+    let (dir, name) = split_path(path)?;
+    let commit = verify_commit(&dir, rev)?;
+    run(&dir, &["show", &format!("{}:./{}", commit, name)])
+}
+
+/// Commits the file at the path if auto-commit is enabled, using the
+/// configured message with `{path}` and `{description}` replaced
+pub fn auto_commit(path: &Path, description: &str) {
+    let config = match GIT_CONFIG.read() {
+        Ok(config) if config.auto_commit => config.clone(),
+        _ => return,
+    };
+
+    let message = config
+        .commit_message
+        .replace("{path}", &path.to_string_lossy())
+        .replace("{description}", description);
+    if let Err(x) = commit_file(path, &message) {
+        warn!("Failed to commit {:?}: {}", path, x);
+    }
+}
+
+/// Same as [`auto_commit`], running git on a blocking thread so the async
+/// executor is free to serve other requests
+pub async fn auto_commit_async(path: PathBuf, description: String) {
+    let result = tokio::task::spawn_blocking(move || {
+        auto_commit(path.as_path(), description.as_str())
+    })
+    .await;
+    if let Err(x) = result {
+        warn!("Failed to run auto-commit: {}", x);
+    }
+}
+
+/// Stages and commits only the file at the path with the given message
+pub fn commit_file(path: &Path, message: &str) -> io::Result<()> {
+    let (dir, name) = split_path(path)?;
+    run(&dir, &["add", "--", &name])?;
+    run(&dir, &["commit", "--quiet", "-m", message, "--", &name])?;
+    Ok(())
+}
+
+/// Splits a path into its parent directory, used as the working directory
+/// for git, and its file name
+fn split_path(path: &Path) -> io::Result<(PathBuf, String)> {
+    let name = path
+        .file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} is not a file", path),
+            )
+        })?;
+    let dir = path
+        .parent()
+        .filter(|x| !x.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."))
+        .to_path_buf();
+    Ok((dir, name))
+}
+
+/// Resolves the revision to the id of the commit that it names, refusing
+/// anything that git could mistake for an option
+fn verify_commit(dir: &Path, rev: &str) -> io::Result<String> {
+    if rev.starts_with('-') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{:?} is not a valid revision", rev),
+        ));
+    }
+
+    let output = run(
+        dir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--end-of-options",
+            &format!("{}^{{commit}}", rev),
+        ],
+    )?;
+    Ok(output.trim().to_string())
+}
+
+fn run(dir: &Path, args: &[&str]) -> io::Result<String> {
+    trace!("git -C {:?} {:?}", dir, args);
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout)
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))
+    } else {
+        Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_at_revision_should_reject_revisions_that_look_like_options() {
+        let dir = std::env::temp_dir();
+        let output = dir.join("vimwiki-server-git-output");
+        let rev = format!("--output={}", output.to_string_lossy());
+
+        let err = file_at_revision(&dir.join("page.wiki"), &rev).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(!output.exists());
+    }
+}
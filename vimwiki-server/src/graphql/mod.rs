@@ -12,3 +12,33 @@ pub fn new_schema() -> Schema {
     Schema::build(Query::default(), Mutation, async_graphql::EmptySubscription)
         .finish()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_schema_should_register_all_types() {
+        let sdl = new_schema().sdl();
+        assert!(sdl.contains("type BoldText"));
+        assert!(sdl.contains("union DecoratedTextContent"));
+        assert!(sdl.contains("type WikiStats"));
+        assert!(sdl.contains("enum CompletionKind"));
+        assert!(sdl.contains("type Hover"));
+        assert!(sdl.contains("type CreatedLink"));
+        assert!(sdl.contains("enum DiaryDirection"));
+        assert!(sdl.contains("type OutlineSymbol"));
+        assert!(sdl.contains("enum HighlightKind"));
+        assert!(sdl.contains("type TextRange"));
+        assert!(sdl.contains("enum ColumnUnit"));
+        assert!(sdl.contains("type WorkspaceSnapshot"));
+        assert!(sdl.contains("type PageChanges"));
+        assert!(sdl.contains("type PageImage"));
+        assert!(sdl.contains("type KeywordOccurrence"));
+        assert!(sdl.contains("interface Node"));
+        assert!(sdl.contains("input HtmlOptions"));
+        assert!(sdl.contains("type Playground"));
+        assert!(sdl.contains("type Header implements Node"));
+        assert!(sdl.contains("type MultiLineComment implements Node"));
+    }
+}
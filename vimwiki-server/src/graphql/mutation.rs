@@ -1,6 +1,7 @@
 use crate::data::*;
 use entity::Id;
 use log::trace;
+use vimwiki::edit::TextEdit;
 
 pub struct Mutation;
 
@@ -45,7 +46,8 @@ impl Mutation {
     /// Creates a new vimwiki file at the specified path using the given text
     /// as the contents of the file. The contents will be parsed and loaded
     /// into the server. By default, if the file already exists, it will not
-    /// be overwritten and instead will return an error.
+    /// be overwritten and instead will return an error. Overwriting a file
+    /// is recorded in the journal so the previous contents can be restored.
     async fn create_file(
         &self,
         wiki: Option<Id>,
@@ -61,4 +63,138 @@ impl Mutation {
         );
         ParsedFile::create(wiki, path, contents, overwrite).await
     }
+
+    /// Applies edits to the file at the specified path, where each edit's
+    /// region refers to the file's contents prior to any of the edits. The
+    /// edits are recorded in the journal so they can be reverted. Fails
+    /// without applying any edit if the file changes while being edited.
+    async fn edit_file(
+        &self,
+        path: String,
+        edits: Vec<TextEditInput>,
+        #[graphql(default = "edit")] description: String,
+    ) -> async_graphql::Result<ParsedFile> {
+        trace!(
+            "edit_file(path: {:?}, edits: {:?}, description: {:?})",
+            path,
+            edits,
+            description
+        );
+        ParsedFile::edit(
+            path,
+            description,
+            edits.into_iter().map(TextEdit::from).collect(),
+        )
+        .await
+    }
+
+    /// Registers the unsaved contents of an editor's buffer for the file at
+    /// the specified path, which are used in place of the file's contents on
+    /// disk (by queries, analysis, and previews) until the overlay is cleared.
+    /// The file does not need to exist on disk.
+    async fn set_overlay(
+        &self,
+        wiki: Option<Id>,
+        path: String,
+        text: String,
+    ) -> async_graphql::Result<ParsedFile> {
+        trace!("set_overlay(path: {:?}, text: {:?})", path, text);
+        ParsedFile::set_overlay(wiki, path, text).await
+    }
+
+    /// Removes the overlay of the file at the specified path, reloading the
+    /// file from disk. Returns nothing if the file does not exist on disk.
+    async fn clear_overlay(
+        &self,
+        path: String,
+    ) -> async_graphql::Result<Option<ParsedFile>> {
+        trace!("clear_overlay(path: {:?})", path);
+        ParsedFile::clear_overlay(path).await
+    }
+
+    /// Regenerates the content of the dynamic blocks (`{{{query` code
+    /// blocks) within the file at the specified path from the current state
+    /// of all wikis, only replacing the content previously generated for each
+    /// block
+    async fn refresh_dynamic_blocks(
+        &self,
+        path: String,
+    ) -> async_graphql::Result<ParsedFile> {
+        trace!("refresh_dynamic_blocks(path: {:?})", path);
+        ParsedFile::refresh_dynamic_blocks(path).await
+    }
+
+    /// Replaces the text within the region of the file at the specified path
+    /// with a wiki link, where an empty region links the word around the
+    /// offset. The link targets the given page, otherwise the text itself,
+    /// and a missing target page is created from the template (filling in
+    /// `{title}` and `{date}`). Returns nothing if there is no text to link.
+    async fn create_link(
+        &self,
+        path: String,
+        offset: usize,
+        #[graphql(default)] len: usize,
+        target: Option<String>,
+        template: Option<String>,
+    ) -> async_graphql::Result<Option<CreatedLink>> {
+        trace!(
+            "create_link(path: {:?}, offset: {}, len: {}, target: {:?}, template: {:?})",
+            path,
+            offset,
+            len,
+            target,
+            template
+        );
+        create_link(path, vimwiki::Region::new(offset, len), target, template)
+            .await
+    }
+
+    /// Creates the diary entry of the wiki at the given index for the date
+    /// (YYYY-MM-DD, defaulting to today) shifted by the given number of days
+    /// from the template if it does not exist, filling in `{title}` and
+    /// `{date}` with the date
+    async fn make_diary_note(
+        &self,
+        #[graphql(default)] wiki: usize,
+        date: Option<String>,
+        #[graphql(default)] days: i64,
+        template: Option<String>,
+    ) -> async_graphql::Result<DiaryEntry> {
+        trace!(
+            "make_diary_note(wiki: {}, date: {:?}, days: {}, template: {:?})",
+            wiki,
+            date,
+            days,
+            template
+        );
+        make_diary_note(
+            wiki,
+            diary_date(date.as_deref(), days)?,
+            template.as_deref(),
+        )
+        .await
+    }
+
+    /// Toggles the task whose list item contains the given byte offset of
+    /// the file at the specified path, recording the edit in the journal
+    async fn toggle_task(
+        &self,
+        path: String,
+        offset: usize,
+    ) -> async_graphql::Result<ParsedFile> {
+        trace!("toggle_task(path: {:?}, offset: {})", path, offset);
+        ParsedFile::toggle_task(path, offset).await
+    }
+
+    /// Reverts the last `count` journaled operations applied to the file at
+    /// the specified path, newest first. The journal is kept in memory, so
+    /// operations applied before the server last started cannot be reverted.
+    async fn revert_file(
+        &self,
+        path: String,
+        #[graphql(default = 1)] count: usize,
+    ) -> async_graphql::Result<ParsedFile> {
+        trace!("revert_file(path: {:?}, count: {})", path, count);
+        ParsedFile::revert(path, count).await
+    }
 }
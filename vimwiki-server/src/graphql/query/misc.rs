@@ -1,5 +1,21 @@
-use crate::data::Element;
+use crate::{
+    data::{
+        adjacent_diary_entry, changes_since, completions, diary_date,
+        diary_entry, events, highlight_spans, hover_at_offset, journal_entries,
+        link_target_at_offset, page_history, page_images, page_outline,
+        page_revision_diff, page_stats, playground, render_html, search_terms,
+        text_offset, text_range, workspace_snapshot, BlockChange, ColumnUnit,
+        Commit, Completion, DiaryDirection, DiaryEntry, Element, Event,
+        EventKind, HighlightSpan, Hover, HtmlOptions, JournalEntry,
+        KeywordOccurrence, KeywordType, LinkTarget, OutlineSymbol,
+        PageAnalysis, PageChanges, PageImage, PageStats, ParsedFile,
+        Playground, SearchResult, TextRange, Wiki, WikiStats,
+        WorkspaceSnapshot,
+    },
+    overlay,
+};
 use entity::{TypedPredicate as P, *};
+use vimwiki::{graph::AnalysisOptions, outline::OutlineOptions};
 
 #[derive(Default)]
 pub struct MiscQuery;
@@ -31,4 +47,387 @@ impl MiscQuery {
             .map(|x| x.into_iter().next())
             .map_err(|x| async_graphql::Error::new(x.to_string()))
     }
+
+    /// Resolves the target of the link at the given byte offset from the
+    /// start of the file at the given path, supporting go-to-definition
+    async fn link_target(
+        &self,
+        path: String,
+        offset: usize,
+    ) -> async_graphql::Result<Option<LinkTarget>> {
+        link_target_at_offset(path, offset).await
+    }
+
+    /// Returns the classified spans of text (headers, links, tags, etc)
+    /// within the file at the given path, sorted by offset, supporting
+    /// syntax highlighting
+    ///
+    /// If given, only spans overlapping lines from `start_line` up to (but
+    /// not including) `end_line` are returned, where lines are counted from
+    /// zero.
+    async fn highlights(
+        &self,
+        path: String,
+        start_line: Option<usize>,
+        end_line: Option<usize>,
+    ) -> async_graphql::Result<Vec<HighlightSpan>> {
+        let lines = match (start_line, end_line) {
+            (None, None) => None,
+            (start, end) => {
+                Some(start.unwrap_or_default()..end.unwrap_or(usize::MAX))
+            }
+        };
+        highlight_spans(path, lines).await
+    }
+
+    /// Returns the hierarchical outline of headers within the file at the
+    /// given path, optionally including the top-level lists and tables of
+    /// each section, supporting document symbols
+    async fn outline(
+        &self,
+        path: String,
+        #[graphql(default)] include_lists: bool,
+        #[graphql(default)] include_tables: bool,
+    ) -> async_graphql::Result<Vec<OutlineSymbol>> {
+        page_outline(
+            path,
+            OutlineOptions {
+                include_lists,
+                include_tables,
+            },
+        )
+        .await
+    }
+
+    /// Describes the element at the given byte offset from the start of the
+    /// file at the given path, such as a preview of the page targeted by a
+    /// link or the status of a task, supporting hover
+    async fn hover(
+        &self,
+        path: String,
+        offset: usize,
+    ) -> async_graphql::Result<Option<Hover>> {
+        hover_at_offset(path, offset).await
+    }
+
+    /// Converts the region of `len` bytes at the given byte offset of the
+    /// file at the given path into lines and columns, where columns are
+    /// counted in the given unit (e.g. UTF-16 for the language server
+    /// protocol or graphemes for what a terminal displays)
+    async fn text_range(
+        &self,
+        path: String,
+        offset: usize,
+        #[graphql(default)] len: usize,
+        unit: ColumnUnit,
+    ) -> async_graphql::Result<Option<TextRange>> {
+        text_range(path, offset, len, unit).await
+    }
+
+    /// Converts the line and column (counted in the given unit) of the file
+    /// at the given path into a byte offset from the start of the file
+    async fn text_offset(
+        &self,
+        path: String,
+        line: usize,
+        column: usize,
+        unit: ColumnUnit,
+    ) -> async_graphql::Result<Option<usize>> {
+        text_offset(path, line, column, unit).await
+    }
+
+    /// Describes the diary entry of the wiki at the given index for the date
+    /// (YYYY-MM-DD, defaulting to today) shifted by the given number of days,
+    /// such as -1 for yesterday or 1 for tomorrow
+    async fn diary_entry(
+        &self,
+        #[graphql(default)] wiki: usize,
+        date: Option<String>,
+        #[graphql(default)] days: i64,
+    ) -> async_graphql::Result<DiaryEntry> {
+        diary_entry(wiki, diary_date(date.as_deref(), days)?).await
+    }
+
+    /// Finds the closest existing diary entry of the wiki at the given index
+    /// before or after the date (YYYY-MM-DD, defaulting to today), skipping
+    /// dates without an entry, supporting diary navigation
+    async fn adjacent_diary_entry(
+        &self,
+        #[graphql(default)] wiki: usize,
+        date: Option<String>,
+        direction: DiaryDirection,
+    ) -> async_graphql::Result<Option<DiaryEntry>> {
+        adjacent_diary_entry(wiki, diary_date(date.as_deref(), 0)?, direction)
+            .await
+    }
+
+    /// Returns up to `limit` completions for the end of the input (e.g.
+    /// `[[Proj`, `[[page#Intr`, or `:wo`) ranked from best to worst match,
+    /// completing pages relative to the file being edited at the given path
+    async fn completions(
+        &self,
+        input: String,
+        path: Option<String>,
+        limit: Option<usize>,
+    ) -> async_graphql::Result<Vec<Completion>> {
+        completions(&input, path.as_deref().map(std::path::Path::new), limit)
+            .await
+    }
+
+    /// Analyzes the pages of the wiki with the given index, reporting orphan
+    /// pages, stub pages with less content than the threshold (in bytes), and
+    /// pages unreachable from the index page
+    async fn page_analysis(
+        &self,
+        wiki: usize,
+        index: Option<String>,
+        stub_threshold: Option<usize>,
+    ) -> async_graphql::Result<Option<PageAnalysis>> {
+        let options = AnalysisOptions {
+            index: index.unwrap_or_else(AnalysisOptions::default_index),
+            stub_threshold: stub_threshold
+                .unwrap_or_else(AnalysisOptions::default_stub_threshold),
+        };
+
+        let wiki = Wiki::query()
+            .where_index(P::equals(wiki))
+            .execute()
+            .map(|x| x.into_iter().next())
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+        match wiki {
+            Some(wiki) => Ok(Some(wiki.analyze(&options).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Finds the wiki with the given name
+    async fn wiki_by_name(
+        &self,
+        name: String,
+    ) -> async_graphql::Result<Option<Wiki>> {
+        Wiki::query()
+            .where_name(P::equals(Some(name)))
+            .execute()
+            .map(|x| x.into_iter().next())
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Finds the file of the page with the given name (its path relative to
+    /// the wiki without extension, e.g. `projects/todo`) within the wiki with
+    /// the given index
+    async fn page_by_name(
+        &self,
+        wiki: usize,
+        name: String,
+    ) -> async_graphql::Result<Option<ParsedFile>> {
+        let wiki = Wiki::query()
+            .where_index(P::equals(wiki))
+            .execute()
+            .map(|x| x.into_iter().next())
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+        match wiki {
+            Some(wiki) => wiki.find_page(&name),
+            None => Ok(None),
+        }
+    }
+
+    /// Lists every image of the file at the given path with its alternate
+    /// text, attributes, and resolved location, such as for asset pipelines
+    async fn page_images(
+        &self,
+        path: String,
+    ) -> async_graphql::Result<Vec<PageImage>> {
+        page_images(path).await
+    }
+
+    /// Renders the page or element with the given id into an HTML fragment,
+    /// using the HTML configuration of the server adjusted by the options
+    async fn html(
+        &self,
+        id: Id,
+        #[graphql(default)] options: HtmlOptions,
+    ) -> async_graphql::Result<Option<String>> {
+        render_html(id, options).await
+    }
+
+    /// Parses and renders the given text without loading it into the server,
+    /// returning its elements as JSON, its HTML, and any problems found,
+    /// where links are checked relative to the path if provided
+    async fn playground(
+        &self,
+        text: String,
+        path: Option<String>,
+        #[graphql(default)] options: HtmlOptions,
+    ) -> async_graphql::Result<Playground> {
+        playground(&text, path.as_deref().map(std::path::Path::new), options)
+    }
+
+    /// Computes statistics about the content of the file at the given path,
+    /// such as its word count, reading time, and task completion
+    async fn page_stats(
+        &self,
+        path: String,
+    ) -> async_graphql::Result<PageStats> {
+        page_stats(path).await
+    }
+
+    /// Computes statistics about every page of the wiki with the given index
+    /// alongside their totals
+    async fn wiki_stats(
+        &self,
+        wiki: usize,
+    ) -> async_graphql::Result<Option<WikiStats>> {
+        let wiki = Wiki::query()
+            .where_index(P::equals(wiki))
+            .execute()
+            .map(|x| x.into_iter().next())
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+        match wiki {
+            Some(wiki) => Ok(Some(wiki.stats().await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Lists every keyword (TODO, FIXME, XXX, etc) within the pages of the
+    /// wiki with the given index, or of every wiki if not given, optionally
+    /// only those of the given types, with positions whose columns are
+    /// counted in the given unit
+    async fn keyword_occurrences(
+        &self,
+        wiki: Option<usize>,
+        keywords: Option<Vec<KeywordType>>,
+        #[graphql(default_with = "ColumnUnit::Char")] unit: ColumnUnit,
+    ) -> async_graphql::Result<Vec<KeywordOccurrence>> {
+        let query = match wiki {
+            Some(wiki) => Wiki::query().where_index(P::equals(wiki)),
+            None => Wiki::query(),
+        };
+        let mut wikis = query
+            .execute()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        wikis.sort_by_key(|x| *x.index());
+
+        let mut occurrences = Vec::new();
+        for wiki in wikis {
+            occurrences.extend(
+                wiki.keyword_occurrences(keywords.as_deref(), unit).await?,
+            );
+        }
+        Ok(occurrences)
+    }
+
+    /// Returns up to `limit` pages of the wiki with the given index, or of
+    /// every wiki if not given, whose title or text contains every
+    /// whitespace-separated term of the text (ignoring case), best match
+    /// first
+    async fn search(
+        &self,
+        text: String,
+        wiki: Option<usize>,
+        #[graphql(default = 20)] limit: usize,
+    ) -> async_graphql::Result<Vec<SearchResult>> {
+        let terms = search_terms(&text);
+        let query = match wiki {
+            Some(wiki) => Wiki::query().where_index(P::equals(wiki)),
+            None => Wiki::query(),
+        };
+        let wikis = query
+            .execute()
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+
+        let mut results = Vec::new();
+        for wiki in wikis {
+            results.extend(wiki.search(&terms).await?);
+        }
+        SearchResult::sort(&mut results);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Exports up to `limit` pages across every wiki (with their elements,
+    /// links, and tags) starting at `offset`, alongside a token that fails
+    /// later batches if the workspace changes in between
+    async fn workspace_snapshot(
+        &self,
+        token: Option<String>,
+        #[graphql(default = 0)] offset: usize,
+        #[graphql(default = 100)] limit: usize,
+    ) -> async_graphql::Result<WorkspaceSnapshot> {
+        workspace_snapshot(token.as_deref(), offset, limit).await
+    }
+
+    /// Returns every page added, modified, or removed after the given
+    /// revision of the workspace, such as the revision of a snapshot
+    async fn changes_since(
+        &self,
+        revision: u64,
+    ) -> async_graphql::Result<PageChanges> {
+        changes_since(revision).await
+    }
+
+    /// Returns the journal of operations the server applied to the file at
+    /// the given path that can still be reverted, newest first. The journal
+    /// is kept in memory only and starts empty whenever the server starts.
+    async fn journal(
+        &self,
+        path: String,
+    ) -> async_graphql::Result<Vec<JournalEntry>> {
+        let c_path = tokio::fs::canonicalize(path)
+            .await
+            .map_err(|x| async_graphql::Error::new(x.to_string()))?;
+        Ok(journal_entries(&c_path))
+    }
+
+    /// Returns up to `limit` of the most recent events of the index (files
+    /// added, changed, removed, or renamed, parse failures, and applied
+    /// edits), newest first, optionally only those after the event with id
+    /// `since`, of the given kinds, or concerning files within the given path
+    async fn events(
+        &self,
+        since: Option<u64>,
+        kinds: Option<Vec<EventKind>>,
+        path: Option<String>,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Vec<Event> {
+        events(
+            since,
+            kinds.as_deref(),
+            path.as_deref().map(std::path::Path::new),
+            limit,
+        )
+    }
+
+    /// Returns up to `limit` commits of the git repository containing the
+    /// file at the given path that changed the file, newest first
+    async fn page_history(
+        &self,
+        path: String,
+        #[graphql(default = 20)] limit: usize,
+    ) -> async_graphql::Result<Vec<Commit>> {
+        page_history(path, limit).await
+    }
+
+    /// Compares the top-level blocks of the file at the given path between
+    /// two git revisions, comparing against the file's overlay (or the file
+    /// on disk) if no `to` revision is provided
+    async fn page_revision_diff(
+        &self,
+        path: String,
+        from: String,
+        to: Option<String>,
+    ) -> async_graphql::Result<Vec<BlockChange>> {
+        page_revision_diff(path, &from, to.as_deref()).await
+    }
+
+    /// Returns the paths of all files whose unsaved buffer contents have been
+    /// registered as overlays
+    async fn overlays(&self) -> Vec<String> {
+        overlay::paths()
+            .into_iter()
+            .map(|x| x.to_string_lossy().to_string())
+            .collect()
+    }
 }
@@ -129,6 +129,15 @@ impl ObjQuery {
             .map_err(|x| async_graphql::Error::new(x.to_string()))
     }
 
+    /// Queries for a single element of any type by its id as a Node, which
+    /// exposes the fields shared by all elements
+    async fn node(&self, id: Id) -> async_graphql::Result<Option<Node>> {
+        gql_db()?
+            .get(id)
+            .map(|maybe_ent| maybe_ent.and_then(Node::wrap_ent))
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
     /// Queries for instances of BlockElement that matches the given filter, or
     /// return all instances if no filter provided
     async fn block_elements(
@@ -820,19 +829,19 @@ impl ObjQuery {
             .map_err(|x| async_graphql::Error::new(x.to_string()))
     }
 
-    /// Queries for instances of DecoratedText that match the filter, or return all
-    /// instances if no filter provided
+    /// Queries for instances of DecoratedText that matches the given filter,
+    /// or return all instances if no filter provided
     async fn decorated_texts(
         &self,
-        filter: Option<GqlDecoratedTextFilter>,
+        filter: Option<GqlEntFilter>,
     ) -> async_graphql::Result<Vec<DecoratedText>> {
         let query: entity::Query = match filter {
             Some(x) => x.into(),
-            None => DecoratedText::query().into(),
+            None => entity::Query::default().where_created(P::greater_than(0)),
         };
 
-        gql_db()?
-            .find_all_typed::<DecoratedText>(query)
+        DecoratedTextQuery::from(query)
+            .execute()
             .map_err(|x| async_graphql::Error::new(x.to_string()))
     }
 
@@ -841,8 +850,140 @@ impl ObjQuery {
         &self,
         id: Id,
     ) -> async_graphql::Result<Option<DecoratedText>> {
+        DecoratedText::query()
+            .where_id(P::equals(id))
+            .execute()
+            .map(|x| x.into_iter().next())
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for instances of BoldText that match the filter, or return all
+    /// instances if no filter provided
+    async fn bold_texts(
+        &self,
+        filter: Option<GqlBoldTextFilter>,
+    ) -> async_graphql::Result<Vec<BoldText>> {
+        let query: entity::Query = match filter {
+            Some(x) => x.into(),
+            None => BoldText::query().into(),
+        };
+
+        gql_db()?
+            .find_all_typed::<BoldText>(query)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for a single instance of BoldText by its id
+    async fn bold_text(
+        &self,
+        id: Id,
+    ) -> async_graphql::Result<Option<BoldText>> {
+        gql_db()?
+            .get_typed::<BoldText>(id)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for instances of ItalicText that match the filter, or return all
+    /// instances if no filter provided
+    async fn italic_texts(
+        &self,
+        filter: Option<GqlItalicTextFilter>,
+    ) -> async_graphql::Result<Vec<ItalicText>> {
+        let query: entity::Query = match filter {
+            Some(x) => x.into(),
+            None => ItalicText::query().into(),
+        };
+
+        gql_db()?
+            .find_all_typed::<ItalicText>(query)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for a single instance of ItalicText by its id
+    async fn italic_text(
+        &self,
+        id: Id,
+    ) -> async_graphql::Result<Option<ItalicText>> {
+        gql_db()?
+            .get_typed::<ItalicText>(id)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for instances of StrikeoutText that match the filter, or return all
+    /// instances if no filter provided
+    async fn strikeout_texts(
+        &self,
+        filter: Option<GqlStrikeoutTextFilter>,
+    ) -> async_graphql::Result<Vec<StrikeoutText>> {
+        let query: entity::Query = match filter {
+            Some(x) => x.into(),
+            None => StrikeoutText::query().into(),
+        };
+
+        gql_db()?
+            .find_all_typed::<StrikeoutText>(query)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for a single instance of StrikeoutText by its id
+    async fn strikeout_text(
+        &self,
+        id: Id,
+    ) -> async_graphql::Result<Option<StrikeoutText>> {
+        gql_db()?
+            .get_typed::<StrikeoutText>(id)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for instances of SuperscriptText that match the filter, or return all
+    /// instances if no filter provided
+    async fn superscript_texts(
+        &self,
+        filter: Option<GqlSuperscriptTextFilter>,
+    ) -> async_graphql::Result<Vec<SuperscriptText>> {
+        let query: entity::Query = match filter {
+            Some(x) => x.into(),
+            None => SuperscriptText::query().into(),
+        };
+
+        gql_db()?
+            .find_all_typed::<SuperscriptText>(query)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for a single instance of SuperscriptText by its id
+    async fn superscript_text(
+        &self,
+        id: Id,
+    ) -> async_graphql::Result<Option<SuperscriptText>> {
+        gql_db()?
+            .get_typed::<SuperscriptText>(id)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for instances of SubscriptText that match the filter, or return all
+    /// instances if no filter provided
+    async fn subscript_texts(
+        &self,
+        filter: Option<GqlSubscriptTextFilter>,
+    ) -> async_graphql::Result<Vec<SubscriptText>> {
+        let query: entity::Query = match filter {
+            Some(x) => x.into(),
+            None => SubscriptText::query().into(),
+        };
+
+        gql_db()?
+            .find_all_typed::<SubscriptText>(query)
+            .map_err(|x| async_graphql::Error::new(x.to_string()))
+    }
+
+    /// Queries for a single instance of SubscriptText by its id
+    async fn subscript_text(
+        &self,
+        id: Id,
+    ) -> async_graphql::Result<Option<SubscriptText>> {
         gql_db()?
-            .get_typed::<DecoratedText>(id)
+            .get_typed::<SubscriptText>(id)
             .map_err(|x| async_graphql::Error::new(x.to_string()))
     }
 
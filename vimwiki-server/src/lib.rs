@@ -1,11 +1,19 @@
 mod config;
 mod data;
 mod database;
+#[cfg(feature = "git")]
+mod git;
 mod graphql;
+mod metrics;
 mod opt;
+mod overlay;
+mod pages;
+mod preview;
 mod program;
+mod revisions;
+mod syntax;
 mod utils;
 
-pub use config::{Config, WikiConfig};
+pub use config::{Config, GitConfig, WikiConfig};
 pub use opt::Opt;
 pub use program::Program;
@@ -1,10 +1,20 @@
 use flexi_logger::{LevelFilter, LogSpecification, Logger};
+use vimwiki::settings::Settings;
 use vimwiki_server::{Config, Opt, Program};
 
 #[tokio::main]
 async fn main() {
-    let opt = Opt::load();
-    let config = Config::load(&opt).expect("Failed to load config");
+    let mut opt = Opt::load();
+    let settings =
+        match std::env::current_dir().and_then(|dir| Settings::load(&dir)) {
+            Ok(settings) => settings,
+            Err(x) => {
+                eprintln!("Failed to load settings: {}", x);
+                std::process::exit(1);
+            }
+        };
+    opt.apply_settings(&settings.server);
+    let config = Config::load(&opt, &settings).expect("Failed to load config");
 
     // Define our logger where everything but our server is not logged and
     // our server's logging is defined by input configuration
@@ -22,7 +32,8 @@ async fn main() {
         .start()
         .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
 
-    Program::run(opt, config)
-        .await
-        .expect("Program failed unexpectedly");
+    if let Err(x) = Program::run(opt, config).await {
+        eprintln!("{}", x);
+        std::process::exit(1);
+    }
 }
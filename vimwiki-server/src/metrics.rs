@@ -0,0 +1,201 @@
+use lazy_static::lazy_static;
+use std::{
+    fmt::Write,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Upper bounds (in seconds) of the buckets of every duration histogram
+const BUCKETS: [f64; 11] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0,
+];
+
+lazy_static! {
+    static ref METRICS: Mutex<Metrics> = Mutex::new(Metrics::default());
+    static ref STARTED: Instant = Instant::now();
+}
+
+/// Represents a distribution of durations across the buckets
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+    counts: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        for (count, bound) in self.counts.iter_mut().zip(BUCKETS.iter()) {
+            if secs <= *bound {
+                *count += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += secs;
+    }
+
+    fn write(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (count, bound) in self.counts.iter().zip(BUCKETS.iter()) {
+            let _ =
+                writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, count);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, self.count);
+        let _ = writeln!(out, "{}_sum {}", name, self.sum);
+        let _ = writeln!(out, "{}_count {}", name, self.count);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    files_parsed: u64,
+    parse_errors: u64,
+    parse_duration: Histogram,
+    watcher_events: u64,
+    watcher_lag: Histogram,
+}
+
+/// Represents the size of the index at the time metrics are reported
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexSize {
+    /// Total wikis loaded
+    pub wikis: usize,
+
+    /// Total files parsed into pages
+    pub pages: usize,
+}
+
+/// Marks the time the server started, from which its uptime is reported
+pub fn start() {
+    lazy_static::initialize(&STARTED);
+}
+
+/// Records the time spent parsing a file and whether or not it parsed
+pub fn record_parse(duration: Duration, ok: bool) {
+    if let Ok(mut x) = METRICS.lock() {
+        x.files_parsed += 1;
+        if !ok {
+            x.parse_errors += 1;
+        }
+        x.parse_duration.observe(duration);
+    }
+}
+
+/// Records the time between the watcher receiving a file event and the
+/// server beginning to process it
+pub fn record_watcher_lag(lag: Duration) {
+    if let Ok(mut x) = METRICS.lock() {
+        x.watcher_events += 1;
+        x.watcher_lag.observe(lag);
+    }
+}
+
+/// Renders all metrics in the Prometheus text exposition format
+pub fn render(index: IndexSize) -> String {
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        let _ = writeln!(out, "{} {}", name, value);
+    };
+
+    metric(
+        "vimwiki_uptime_seconds",
+        "gauge",
+        "Seconds since the server started",
+        STARTED.elapsed().as_secs_f64().to_string(),
+    );
+    metric(
+        "vimwiki_wikis_indexed",
+        "gauge",
+        "Wikis currently loaded",
+        index.wikis.to_string(),
+    );
+    metric(
+        "vimwiki_pages_indexed",
+        "gauge",
+        "Files currently parsed into pages",
+        index.pages.to_string(),
+    );
+    if let Some(bytes) = resident_memory_bytes() {
+        metric(
+            "vimwiki_resident_memory_bytes",
+            "gauge",
+            "Resident memory of the server process",
+            bytes.to_string(),
+        );
+    }
+
+    if let Ok(x) = METRICS.lock() {
+        metric(
+            "vimwiki_files_parsed_total",
+            "counter",
+            "Files parsed, including those that failed to parse",
+            x.files_parsed.to_string(),
+        );
+        metric(
+            "vimwiki_parse_errors_total",
+            "counter",
+            "Files that failed to parse",
+            x.parse_errors.to_string(),
+        );
+        metric(
+            "vimwiki_watcher_events_total",
+            "counter",
+            "File events received by the watcher",
+            x.watcher_events.to_string(),
+        );
+        x.parse_duration.write(
+            &mut out,
+            "vimwiki_parse_duration_seconds",
+            "Time spent parsing a file",
+        );
+        x.watcher_lag.write(
+            &mut out,
+            "vimwiki_watcher_lag_seconds",
+            "Time between the watcher receiving a file event and processing it",
+        );
+    }
+
+    out
+}
+
+/// Returns the resident memory of the current process, if known
+fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|x| x.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_should_count_durations_within_each_bucket() {
+        let mut histogram = Histogram::default();
+        histogram.observe(Duration::from_millis(2));
+        histogram.observe(Duration::from_millis(200));
+
+        let mut out = String::new();
+        histogram.write(&mut out, "test", "Test");
+        assert!(out.contains("test_bucket{le=\"0.001\"} 0\n"), "{}", out);
+        assert!(out.contains("test_bucket{le=\"0.0025\"} 1\n"), "{}", out);
+        assert!(out.contains("test_bucket{le=\"0.25\"} 2\n"), "{}", out);
+        assert!(out.contains("test_bucket{le=\"+Inf\"} 2\n"), "{}", out);
+        assert!(out.contains("test_count 2\n"), "{}", out);
+    }
+
+    #[test]
+    fn render_should_report_index_size_and_counters() {
+        record_parse(Duration::from_millis(1), false);
+        let out = render(IndexSize { wikis: 2, pages: 7 });
+        assert!(out.contains("vimwiki_wikis_indexed 2\n"), "{}", out);
+        assert!(out.contains("vimwiki_pages_indexed 7\n"), "{}", out);
+        assert!(out.contains("# TYPE vimwiki_parse_errors_total counter\n"));
+        assert!(out.contains("vimwiki_parse_duration_seconds_count"));
+    }
+}
@@ -1,9 +1,13 @@
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use log::LevelFilter;
-use std::{net::IpAddr, path::PathBuf};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+};
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
+use vimwiki::settings::{default_socket_path, workspace_dir, ServerSettings};
 
 lazy_static! {
     static ref DEFAULT_CACHE_DIR: String =
@@ -12,7 +16,7 @@ lazy_static! {
             .unwrap_or_default();
 }
 
-#[derive(StructOpt, Debug)]
+#[derive(StructOpt, Clone, Debug)]
 pub struct Opt {
     /// Verbose mode (-v, -vv, -vvv, etc.)
     #[structopt(short, long, parse(from_occurrences))]
@@ -26,25 +30,32 @@ pub struct Opt {
     #[structopt(long)]
     pub log_dir: Option<PathBuf>,
 
-    /// Mode to run server (http = web; stdin = read input from stdin and reply on stdout)
+    /// Mode to run server (http = web; stdin = read input from stdin and reply on stdout;
+    /// socket = read input from and reply over a unix socket)
     #[structopt(long, possible_values = Mode::VARIANTS, case_insensitive = true, default_value = "http")]
     pub mode: Mode,
 
-    /// Host/IP address of server in http mode
-    #[structopt(long, default_value = "127.0.0.1")]
-    pub host: IpAddr,
+    /// Host/IP address of server in http mode [default: 127.0.0.1]
+    #[structopt(long)]
+    pub host: Option<IpAddr>,
 
-    /// Port of the server in http mode
-    #[structopt(long, default_value = "8000")]
-    pub port: u16,
+    /// Port of the server in http mode [default: 8000]
+    #[structopt(long)]
+    pub port: Option<u16>,
+
+    /// Path of the unix socket in socket mode [default: a socket per
+    /// workspace within the runtime directory of the user]
+    #[structopt(long)]
+    pub socket: Option<PathBuf>,
 
     /// If provided, will include hosting of /graphiql endpoint
     #[structopt(long)]
     pub graphiql: bool,
 
     /// Directory where cache information for use with server will be stored
-    #[structopt(long, default_value = &DEFAULT_CACHE_DIR)]
-    pub cache: PathBuf,
+    /// [default: the cache directory of the user]
+    #[structopt(long)]
+    pub cache: Option<PathBuf>,
 
     /// Path to config file for wiki definitions
     #[structopt(long)]
@@ -66,6 +77,41 @@ impl Opt {
         Self::from_args()
     }
 
+    /// Fills in any options that were not provided with the given settings
+    pub fn apply_settings(&mut self, settings: &ServerSettings) {
+        self.host = self.host.or(settings.host);
+        self.port = self.port.or(settings.port);
+        self.graphiql |= settings.graphiql.unwrap_or_default();
+        self.cache = self.cache.take().or_else(|| settings.cache.clone());
+        self.merge |= settings.merge.unwrap_or_default();
+        self.socket = self.socket.take().or_else(|| settings.socket.clone());
+    }
+
+    /// Host/IP address of the server in http mode
+    pub fn host(&self) -> IpAddr {
+        self.host.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+
+    /// Port of the server in http mode
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(8000)
+    }
+
+    /// Directory where cache information for use with server is stored
+    pub fn cache(&self) -> PathBuf {
+        self.cache
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CACHE_DIR.as_str()))
+    }
+
+    /// Path of the unix socket in socket mode
+    pub fn socket(&self) -> PathBuf {
+        self.socket.clone().unwrap_or_else(|| {
+            let dir = std::env::current_dir().unwrap_or_default();
+            default_socket_path(&workspace_dir(&dir))
+        })
+    }
+
     /// The level to use for logging throughout the server
     pub fn log_level(&self) -> LevelFilter {
         // Quiet mode should still show errors
@@ -81,10 +127,12 @@ impl Opt {
     }
 }
 
-/// Represents the mode to run the server (input from stdin or HTTP)
+/// Represents the mode to run the server (input from stdin, HTTP, or a unix
+/// socket)
 #[derive(Copy, Clone, Debug, PartialEq, Eq, EnumString, EnumVariantNames)]
 #[strum(serialize_all = "kebab_case")]
 pub enum Mode {
     Stdin,
     Http,
+    Socket,
 }
@@ -0,0 +1,107 @@
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+use vimwiki::sources;
+
+lazy_static! {
+    static ref OVERLAYS: RwLock<HashMap<PathBuf, String>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Returns the path used to look up the overlay of a file, which is the
+/// canonical path if the file exists on disk and the path as-is otherwise
+pub async fn key(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    tokio::fs::canonicalize(path)
+        .await
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Registers the unsaved contents of a file's buffer, which are used in place
+/// of the file's contents on disk until the overlay is cleared
+pub async fn set(path: impl AsRef<Path>, text: impl Into<String>) -> PathBuf {
+    let path = key(path).await;
+    if let Ok(mut x) = OVERLAYS.write() {
+        x.insert(path.clone(), text.into());
+    }
+    path
+}
+
+/// Removes the overlay of a file, returning its contents if it had one
+pub async fn clear(path: impl AsRef<Path>) -> Option<String> {
+    let path = key(path).await;
+    OVERLAYS.write().ok().and_then(|mut x| x.remove(&path))
+}
+
+/// Returns the contents of the overlay of the file at the canonical path
+pub fn get(path: impl AsRef<Path>) -> Option<String> {
+    OVERLAYS
+        .read()
+        .ok()
+        .and_then(|x| x.get(path.as_ref()).cloned())
+}
+
+/// Returns true if the file at the canonical path has an overlay
+pub fn contains(path: impl AsRef<Path>) -> bool {
+    OVERLAYS
+        .read()
+        .map(|x| x.contains_key(path.as_ref()))
+        .unwrap_or_default()
+}
+
+/// Returns the paths of all files that have an overlay in sorted order
+pub fn paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = OVERLAYS
+        .read()
+        .map(|x| x.keys().cloned().collect())
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+/// Reads the contents of the file at the canonical path, preferring its
+/// overlay over its contents on disk, which are decoded as UTF-8 or latin-1
+pub async fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    match get(path.as_ref()) {
+        Some(text) => Ok(text),
+        None => {
+            let file = tokio::fs::File::open(path).await?;
+            let (text, _) = sources::read_to_string_async(file).await?;
+            Ok(text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn read_to_string_should_prefer_overlay_over_disk() {
+        let file = std::env::temp_dir().join("vimwiki-server-overlay.wiki");
+        std::fs::write(&file, "on disk").unwrap();
+
+        let path = set(&file, "in buffer").await;
+        assert!(contains(&path));
+        assert!(paths().contains(&path));
+        assert_eq!(read_to_string(&path).await.unwrap(), "in buffer");
+
+        assert_eq!(clear(&file).await.as_deref(), Some("in buffer"));
+        assert!(!contains(&path));
+        assert_eq!(read_to_string(&path).await.unwrap(), "on disk");
+
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[tokio::test]
+    async fn set_should_support_files_missing_from_disk() {
+        let path = set("/missing/overlay.wiki", "= Hi =").await;
+        assert_eq!(path, Path::new("/missing/overlay.wiki"));
+        assert_eq!(read_to_string(&path).await.unwrap(), "= Hi =");
+        assert!(clear(&path).await.is_some());
+    }
+}
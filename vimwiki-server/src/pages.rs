@@ -0,0 +1,99 @@
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+use vimwiki::Page;
+
+lazy_static! {
+    static ref PAGES: RwLock<HashMap<PathBuf, Arc<IndexedPage>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Represents the contents of a file as they were when the file was indexed
+/// alongside the page parsed from them
+#[derive(Debug)]
+pub struct IndexedPage {
+    /// Checksum of the text, matching that of the indexed file
+    pub checksum: String,
+
+    /// Text of the file, which is the overlay of the file if it had one
+    pub text: String,
+
+    /// Page parsed from the text
+    pub page: Page<'static>,
+}
+
+/// Keeps the page parsed from the file at the canonical path, replacing any
+/// page kept from an earlier version of the file
+pub fn insert(path: impl Into<PathBuf>, page: Arc<IndexedPage>) {
+    if let Ok(mut x) = PAGES.write() {
+        x.insert(path.into(), page);
+    }
+}
+
+/// Returns the page parsed from the file at the canonical path if it was
+/// parsed from contents with the given checksum
+pub fn get(path: impl AsRef<Path>, checksum: &str) -> Option<Arc<IndexedPage>> {
+    PAGES
+        .read()
+        .ok()
+        .and_then(|x| x.get(path.as_ref()).cloned())
+        .filter(|x| x.checksum == checksum)
+}
+
+/// Moves the page kept for the file at one canonical path to another
+pub fn rename(from: impl AsRef<Path>, to: impl Into<PathBuf>) {
+    if let Ok(mut x) = PAGES.write() {
+        if let Some(page) = x.remove(from.as_ref()) {
+            x.insert(to.into(), page);
+        }
+    }
+}
+
+/// Forgets the page kept for the file at the canonical path
+pub fn remove(path: impl AsRef<Path>) {
+    if let Ok(mut x) = PAGES.write() {
+        x.remove(path.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vimwiki::Language;
+
+    fn indexed(checksum: &str) -> Arc<IndexedPage> {
+        let text = String::from("some text");
+        let page = Language::from_vimwiki_str(&text)
+            .parse::<Page>()
+            .unwrap()
+            .into_owned();
+        Arc::new(IndexedPage {
+            checksum: checksum.to_string(),
+            text,
+            page,
+        })
+    }
+
+    #[test]
+    fn get_should_only_return_page_with_matching_checksum() {
+        let path = Path::new("/pages/test/get.wiki");
+        insert(path, indexed("abc"));
+        assert!(get(path, "abc").is_some());
+        assert!(get(path, "def").is_none());
+        remove(path);
+        assert!(get(path, "abc").is_none());
+    }
+
+    #[test]
+    fn rename_should_move_page_to_new_path() {
+        let from = Path::new("/pages/test/from.wiki");
+        let to = Path::new("/pages/test/to.wiki");
+        insert(from, indexed("abc"));
+        rename(from, to);
+        assert!(get(from, "abc").is_none());
+        assert!(get(to, "abc").is_some());
+    }
+}
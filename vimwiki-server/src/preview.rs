@@ -0,0 +1,418 @@
+use crate::{overlay, syntax};
+use futures_util::{SinkExt, StreamExt};
+use lazy_static::lazy_static;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+use tokio::sync::broadcast;
+use vimwiki::{HtmlConfig, HtmlFormatter, Output, Page, SourceMap};
+use warp::ws::{Message, WebSocket};
+
+/// Maximum number of file changes queued for a preview that has yet to
+/// process them
+const MAX_QUEUED_CHANGES: usize = 64;
+
+lazy_static! {
+    static ref HTML_CONFIG: RwLock<HtmlConfig> =
+        RwLock::new(HtmlConfig::default());
+    static ref FILE_CHANGES: broadcast::Sender<PathBuf> =
+        broadcast::channel(MAX_QUEUED_CHANGES).0;
+}
+
+/// Sets the config used to render previews
+pub fn set_html_config(config: HtmlConfig) {
+    if let Ok(mut x) = HTML_CONFIG.write() {
+        *x = config;
+    }
+}
+
+/// Returns the config used to render previews
+pub fn html_config() -> HtmlConfig {
+    HTML_CONFIG.read().map(|x| x.clone()).unwrap_or_default()
+}
+
+/// Derives the config to render the file at the path from the given config,
+/// filling in the wiki containing the file and the file itself
+pub fn config_for_path(config: &HtmlConfig, path: &Path) -> HtmlConfig {
+    let mut config = config.clone();
+    config.runtime.wiki_index =
+        config.wikis.iter().position(|w| path.starts_with(&w.path));
+    config.runtime.page = path.to_path_buf();
+    config
+}
+
+/// Notifies all previews that the file at the path has changed on disk
+pub fn notify_file_changed(path: impl Into<PathBuf>) {
+    // NOTE: Sending only fails when there are no previews open
+    let _ = FILE_CHANGES.send(path.into());
+}
+
+/// Represents a message sent by a client of a live preview
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PreviewRequest {
+    /// Previews the file at the path, rendering it again whenever it changes
+    /// on disk
+    Open { path: PathBuf },
+
+    /// Renders the unsaved contents of the previewed file's buffer
+    Update { text: String },
+}
+
+/// Represents a message sent to a client of a live preview
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PreviewResponse {
+    /// Rendered HTML fragment of the previewed file alongside a map from
+    /// the HTML back to the regions of the file's text
+    Render {
+        path: PathBuf,
+        html: String,
+        source_map: SourceMap,
+    },
+
+    /// Failure to handle a request or render the previewed file
+    Error { message: String },
+}
+
+impl PreviewResponse {
+    fn error(message: impl Into<String>) -> Self {
+        Self::Error {
+            message: message.into(),
+        }
+    }
+}
+
+/// Renders the text of the file at the path into an HTML fragment with a
+/// source map
+pub fn render(config: &HtmlConfig, path: &Path, text: &str) -> PreviewResponse {
+    let page: Page = match syntax::parse_page(text) {
+        Ok(page) => page,
+        Err(x) => return PreviewResponse::error(x.to_string()),
+    };
+
+    let mut config = config_for_path(config, path);
+    config.runtime.source_map = true;
+
+    let mut f = HtmlFormatter::new(config);
+    match page.fmt(&mut f) {
+        Ok(_) => PreviewResponse::Render {
+            path: path.to_path_buf(),
+            source_map: f.source_map().clone(),
+            html: f.into_content(),
+        },
+        Err(x) => PreviewResponse::error(x.to_string()),
+    }
+}
+
+/// Renders the file at the path using its contents on disk
+async fn render_file(path: &Path) -> PreviewResponse {
+    match overlay::read_to_string(path).await {
+        Ok(text) => render_text(path, &text),
+        Err(x) => PreviewResponse::error(format!("{:?}: {}", path, x)),
+    }
+}
+
+fn render_text(path: &Path, text: &str) -> PreviewResponse {
+    match HTML_CONFIG.read() {
+        Ok(config) => render(&config, path, text),
+        Err(x) => PreviewResponse::error(x.to_string()),
+    }
+}
+
+/// Returns true if the canonical path is within one of the wikis of the
+/// config used to render previews
+async fn is_within_wikis(path: &Path) -> bool {
+    // NOTE: The paths are copied out so the lock is never held across an
+    //       await
+    let wiki_paths: Vec<PathBuf> = match HTML_CONFIG.read() {
+        Ok(config) => config.wikis.iter().map(|w| w.path.clone()).collect(),
+        Err(_) => return false,
+    };
+
+    for wiki_path in wiki_paths {
+        if let Ok(wiki_path) = tokio::fs::canonicalize(wiki_path).await {
+            if path.starts_with(wiki_path) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Returns true if a client sending the `Origin` header of a websocket
+/// upgrade may preview files of the server listening on the host and port,
+/// being a client that is not a browser (sending no origin) or a page served
+/// from the same address as the server
+///
+/// Origins naming any other host, including ones that resolve to the
+/// server's address, are refused so that websites visited in a browser
+/// cannot read wiki files through the preview
+pub fn is_allowed_origin(
+    origin: Option<&str>,
+    host: IpAddr,
+    port: u16,
+) -> bool {
+    let origin = match origin {
+        Some(origin) => origin,
+        None => return true,
+    };
+
+    let (authority, default_port) =
+        if let Some(x) = origin.strip_prefix("http://") {
+            (x, 80)
+        } else if let Some(x) = origin.strip_prefix("https://") {
+            (x, 443)
+        } else {
+            return false;
+        };
+
+    // NOTE: Addresses within an authority (e.g. `[::1]:8000`) are bracketed
+    //       when they are IPv6, so the port follows the last bracket
+    let (origin_host, origin_port) = match authority.rsplit_once(':') {
+        Some((h, p)) if !p.contains(']') => match p.parse::<u16>() {
+            Ok(p) => (h, p),
+            Err(_) => return false,
+        },
+        _ => (authority, default_port),
+    };
+    if origin_port != port {
+        return false;
+    }
+
+    let origin_host = origin_host.trim_start_matches('[').trim_end_matches(']');
+    match origin_host.parse::<IpAddr>() {
+        Ok(ip) => ip == host || host.is_unspecified(),
+        Err(_) => {
+            origin_host.eq_ignore_ascii_case("localhost")
+                && (host.is_loopback() || host.is_unspecified())
+        }
+    }
+}
+
+/// Handles a request of a client, updating the path of the previewed file
+async fn handle_request(
+    request: PreviewRequest,
+    path: &mut Option<PathBuf>,
+) -> PreviewResponse {
+    match request {
+        PreviewRequest::Open { path: p } => {
+            let p = match tokio::fs::canonicalize(&p).await {
+                Ok(p) => p,
+                Err(x) => {
+                    return PreviewResponse::error(format!("{:?}: {}", p, x))
+                }
+            };
+            if !is_within_wikis(&p).await {
+                return PreviewResponse::error(format!(
+                    "{:?} is not within a wiki",
+                    p
+                ));
+            }
+
+            let response = render_file(&p).await;
+            *path = Some(p);
+            response
+        }
+        PreviewRequest::Update { text } => match path.as_ref() {
+            Some(p) => render_text(p, &text),
+            None => PreviewResponse::error("No file opened for preview"),
+        },
+    }
+}
+
+/// Serves a live preview over the websocket, where the client opens a file
+/// and is sent its rendered HTML whenever the file changes on disk or the
+/// client pushes the unsaved contents of its buffer
+pub async fn serve(socket: WebSocket) {
+    let (mut tx, mut rx) = socket.split();
+    let mut changes = FILE_CHANGES.subscribe();
+    let mut path: Option<PathBuf> = None;
+
+    loop {
+        let response = tokio::select! {
+            msg = rx.next() => match msg {
+                Some(Ok(msg)) if msg.is_text() => {
+                    match serde_json::from_slice(msg.as_bytes()) {
+                        Ok(x) => handle_request(x, &mut path).await,
+                        Err(x) => PreviewResponse::error(x.to_string()),
+                    }
+                }
+                Some(Ok(msg)) if msg.is_close() => break,
+                Some(Ok(_)) => continue,
+                Some(Err(x)) => {
+                    error!("Preview connection failed: {}", x);
+                    break;
+                }
+                None => break,
+            },
+            changed = changes.recv() => match changed {
+                Ok(x) if path.as_ref() == Some(&x) => render_file(&x).await,
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+
+        let text = match serde_json::to_string(&response) {
+            Ok(text) => text,
+            Err(x) => {
+                error!("Failed to serialize preview: {}", x);
+                continue;
+            }
+        };
+
+        if let Err(x) = tx.send(Message::text(text)).await {
+            error!("Failed to send preview: {}", x);
+            break;
+        }
+    }
+
+    trace!("Preview connection closed");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vimwiki::{HtmlWikiConfig, Region};
+
+    #[test]
+    fn render_should_produce_html_with_source_map() {
+        let config = HtmlConfig {
+            wikis: vec![HtmlWikiConfig {
+                path: PathBuf::from("/wiki"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        match render(&config, Path::new("/wiki/page.wiki"), "Some *text*\n") {
+            PreviewResponse::Render {
+                path,
+                html,
+                source_map,
+            } => {
+                assert_eq!(path, Path::new("/wiki/page.wiki"));
+                assert_eq!(
+                    html,
+                    "<p data-region=\"0,12\">Some <strong id=\"text\">text</strong></p>\n"
+                );
+                assert_eq!(
+                    source_map.find_by_output_offset(0),
+                    Some(Region::new(0, 12))
+                );
+                assert_eq!(
+                    source_map
+                        .find_entry_by_output_offset(0)
+                        .and_then(|x| x.path.as_deref()),
+                    Some(Path::new("/wiki/page.wiki"))
+                );
+            }
+            x => panic!("Unexpected response: {:?}", x),
+        }
+    }
+
+    #[tokio::test]
+    async fn open_should_fail_for_file_outside_of_wikis() {
+        let mut path = None;
+        let response = handle_request(
+            PreviewRequest::Open {
+                path: std::env::temp_dir(),
+            },
+            &mut path,
+        )
+        .await;
+        assert!(matches!(response, PreviewResponse::Error { .. }));
+        assert_eq!(path, None);
+    }
+
+    #[tokio::test]
+    async fn open_should_fail_for_missing_file() {
+        let mut path = None;
+        let response = handle_request(
+            PreviewRequest::Open {
+                path: PathBuf::from("/no/such/wiki/page.wiki"),
+            },
+            &mut path,
+        )
+        .await;
+        assert!(matches!(response, PreviewResponse::Error { .. }));
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn is_allowed_origin_should_allow_clients_without_an_origin() {
+        let host = IpAddr::from([127, 0, 0, 1]);
+        assert!(is_allowed_origin(None, host, 8000));
+    }
+
+    #[test]
+    fn is_allowed_origin_should_allow_pages_served_from_the_server() {
+        let host = IpAddr::from([127, 0, 0, 1]);
+        assert!(is_allowed_origin(Some("http://127.0.0.1:8000"), host, 8000));
+        assert!(is_allowed_origin(Some("http://localhost:8000"), host, 8000));
+
+        let host = IpAddr::from([0u16, 0, 0, 0, 0, 0, 0, 1]);
+        assert!(is_allowed_origin(Some("http://[::1]:8000"), host, 8000));
+
+        let host = IpAddr::from([127, 0, 0, 1]);
+        assert!(is_allowed_origin(Some("http://localhost"), host, 80));
+    }
+
+    #[test]
+    fn is_allowed_origin_should_refuse_other_origins() {
+        let host = IpAddr::from([127, 0, 0, 1]);
+        assert!(!is_allowed_origin(
+            Some("http://example.com:8000"),
+            host,
+            8000
+        ));
+        assert!(!is_allowed_origin(
+            Some("http://localhost:9000"),
+            host,
+            8000
+        ));
+        assert!(!is_allowed_origin(
+            Some("http://127.0.0.2:8000"),
+            host,
+            8000
+        ));
+        assert!(!is_allowed_origin(Some("null"), host, 8000));
+
+        let host = IpAddr::from([192, 168, 0, 2]);
+        assert!(!is_allowed_origin(
+            Some("http://localhost:8000"),
+            host,
+            8000
+        ));
+    }
+
+    #[test]
+    fn preview_request_should_be_tagged_by_type() {
+        let request: PreviewRequest = serde_json::from_str(
+            r#"{"type": "open", "path": "/wiki/page.wiki"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            request,
+            PreviewRequest::Open {
+                path: PathBuf::from("/wiki/page.wiki")
+            }
+        );
+
+        let request: PreviewRequest =
+            serde_json::from_str(r#"{"type": "update", "text": "= Hi ="}"#)
+                .unwrap();
+        assert_eq!(
+            request,
+            PreviewRequest::Update {
+                text: String::from("= Hi =")
+            }
+        );
+    }
+}
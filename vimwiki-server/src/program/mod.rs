@@ -1,11 +1,18 @@
 mod server;
+mod signals;
+mod socket;
 mod stdin;
 mod watcher;
 use watcher::*;
 
-use crate::{database, opt::Mode, Config, Opt};
+#[cfg(feature = "git")]
+use crate::git;
+use crate::{data, database, metrics, opt::Mode, preview, syntax, Config, Opt};
 use derive_more::{Display, From};
 use entity::DatabaseRc;
+use log::*;
+use std::sync::Arc;
+use vimwiki::settings::Settings;
 
 /// Alias for a result with a program error
 pub type ProgramResult<T, E = ProgramError> = std::result::Result<T, E>;
@@ -16,6 +23,8 @@ pub enum ProgramError {
     LoadDatabase(async_graphql::Error),
     #[display(fmt = "Could not start file watcher: {}", _0)]
     FileWatcher(notify::Error),
+    #[display(fmt = "Could not lock socket: {}", _0)]
+    LockSocket(std::io::Error),
 }
 
 pub struct Program;
@@ -23,6 +32,19 @@ pub struct Program;
 impl Program {
     /// Runs our program
     pub async fn run(opt: Opt, config: Config) -> ProgramResult<()> {
+        // Claim the socket before loading anything so that a second server
+        // of the same workspace exits without touching the cache
+        let _lock = match opt.mode {
+            Mode::Socket => Some(
+                socket::SocketLock::acquire(&opt.socket())
+                    .map_err(ProgramError::from)?,
+            ),
+            _ => None,
+        };
+
+        metrics::start();
+        apply_config(&config);
+
         // Load our database using the provided opturation and any
         // cached data from a previous run
         let database = database::load(&opt, &config)
@@ -31,16 +53,99 @@ impl Program {
 
         // Initialize our watcher to update the database based on changes
         // that occur in wikis and standalone files
-        let _watcher =
+        let watcher = Arc::new(
             Watcher::initialize(&config, DatabaseRc::clone(&database))
                 .await
-                .map_err(ProgramError::from)?;
+                .map_err(ProgramError::from)?,
+        );
+
+        // Reload the config and rescan wikis whenever asked to, keeping the
+        // current config if the new one fails to load
+        let reload_opt = opt.clone();
+        let reload_watcher = Arc::clone(&watcher);
+        signals::on_reload(move || {
+            let opt = reload_opt.clone();
+            let watcher = Arc::clone(&reload_watcher);
+            async move {
+                if let Err(x) = reload(&opt, &watcher).await {
+                    error!("Failed to reload: {}", x.message);
+                }
+            }
+        });
 
         match opt.mode {
-            Mode::Stdin => stdin::run(opt).await,
-            Mode::Http => server::run(opt).await,
+            Mode::Stdin => stdin::run(opt.clone()).await,
+            Mode::Http => server::run(opt.clone()).await,
+            Mode::Socket => {
+                if let Err(x) = socket::run(opt.clone()).await {
+                    error!("Failed to listen on socket: {}", x);
+                }
+            }
+        }
+
+        // Flush the database so that the next run starts from where this
+        // one left off
+        if let Err(x) = database::store(&opt).await {
+            error!("Failed to store database: {}", x.message);
         }
 
         Ok(())
     }
 }
+
+/// Makes the config available to the parts of the server that use it
+fn apply_config(config: &Config) {
+    #[cfg(feature = "git")]
+    git::set_config(config.git.clone());
+    data::set_link_resolver(config.to_link_resolver());
+    preview::set_html_config(config.to_html_config());
+    syntax::set_parser_config(config.syntax.clone());
+    for template in config.schemes.iter() {
+        vimwiki::schemes::register(template.clone());
+    }
+}
+
+/// Loads the settings and config again, rescanning every configured wiki so
+/// that new wikis are indexed and watched, and then applies the config and
+/// stores the database; nothing is applied if the wikis fail to load
+///
+/// Options given on the command line, such as the host and port, are not
+/// reloaded.
+async fn reload(opt: &Opt, watcher: &Watcher) -> async_graphql::Result<()> {
+    let settings = std::env::current_dir()
+        .and_then(|dir| Settings::load(&dir))
+        .map_err(data::to_gql_error)?;
+    let config = Config::load(opt, &settings).map_err(data::to_gql_error)?;
+
+    // NOTE: Loading parses pages with the new syntax, so it is set while
+    //       loading and restored if loading fails, leaving the rest of the
+    //       config untouched until then
+    let previous_syntax = syntax::parser_config();
+    syntax::set_parser_config(config.syntax.clone());
+    let loaded = async {
+        if config.syntax != previous_syntax {
+            data::ParsedFile::reparse_all().await?;
+        }
+        data::Wiki::load_all_from_config(&config, |_| {}, |_, _, _| {}, |_| {})
+            .await
+    }
+    .await;
+    let wikis = match loaded {
+        Ok(wikis) => wikis,
+        Err(x) => {
+            syntax::set_parser_config(previous_syntax);
+            return Err(x);
+        }
+    };
+    apply_config(&config);
+
+    for wiki in wikis.iter() {
+        if let Err(x) = watcher.watch_wiki(wiki.path()).await {
+            error!("Failed to watch {:?}: {}", wiki.path(), x);
+        }
+    }
+
+    database::store(opt).await?;
+    info!("Reloaded {} wikis", wikis.len());
+    Ok(())
+}
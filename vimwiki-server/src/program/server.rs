@@ -1,7 +1,13 @@
-use crate::{graphql, Opt};
+use super::signals;
+use crate::{
+    data::{ParsedFile, Wiki},
+    database::gql_db,
+    graphql, metrics, preview, Opt,
+};
+use entity::*;
 use log::*;
 use std::convert::Infallible;
-use warp::{reply::Reply, Filter};
+use warp::{http::StatusCode, reply::Reply, Filter};
 
 macro_rules! graphql_endpoint {
     ($path:expr, $program:expr) => {{
@@ -33,18 +39,100 @@ macro_rules! graphiql_endpoint {
     }};
 }
 
+macro_rules! preview_endpoint {
+    ($path:expr, $host:expr, $port:expr) => {{
+        let (host, port) = ($host, $port);
+        warp::path($path)
+            .and(warp::ws())
+            .and(warp::header::optional::<String>("origin"))
+            .map(move |ws: warp::ws::Ws, origin: Option<String>| {
+                if preview::is_allowed_origin(origin.as_deref(), host, port) {
+                    ws.on_upgrade(preview::serve).into_response()
+                } else {
+                    warn!("Refused preview from origin {:?}", origin);
+                    warp::reply::with_status(
+                        "origin not allowed",
+                        StatusCode::FORBIDDEN,
+                    )
+                    .into_response()
+                }
+            })
+    }};
+}
+
+macro_rules! health_endpoint {
+    ($path:expr) => {{
+        warp::path($path)
+            .and(warp::path::end())
+            .map(|| match gql_db() {
+                Ok(_) => warp::reply::with_status("ok", StatusCode::OK),
+                Err(_) => warp::reply::with_status(
+                    "database unavailable",
+                    StatusCode::SERVICE_UNAVAILABLE,
+                ),
+            })
+    }};
+}
+
+macro_rules! metrics_endpoint {
+    ($path:expr) => {{
+        warp::path($path).and(warp::path::end()).map(|| {
+            warp::reply::with_header(
+                metrics::render(index_size()),
+                "content-type",
+                "text/plain; version=0.0.4",
+            )
+        })
+    }};
+}
+
+/// Returns the current size of the index, which is empty if the database
+/// is unavailable
+fn index_size() -> metrics::IndexSize {
+    let db = match gql_db() {
+        Ok(db) => db,
+        Err(_) => return metrics::IndexSize::default(),
+    };
+
+    metrics::IndexSize {
+        wikis: db
+            .find_all_typed::<Wiki>(Wiki::query().into())
+            .map_or(0, |x| x.len()),
+        pages: db
+            .find_all_typed::<ParsedFile>(ParsedFile::query().into())
+            .map_or(0, |x| x.len()),
+    }
+}
+
 pub async fn run(opt: Opt) {
-    let graphql_filter = graphql_endpoint!("graphql", program);
+    let graphql_filter = graphql_endpoint!("graphql", program)
+        .or(preview_endpoint!("preview", opt.host(), opt.port()))
+        .or(health_endpoint!("healthz"))
+        .or(metrics_endpoint!("metrics"));
 
-    info!("Listening on {}:{}", opt.host, opt.port);
+    // NOTE: Once asked to shut down, the server stops accepting connections
+    //       and waits for in-flight requests to complete
+    info!("Listening on {}:{}", opt.host(), opt.port());
     if opt.graphiql {
         info!("Enabling graphiql interface");
         let graphiql_filter = graphiql_endpoint!("graphiql", "/graphql");
         let routes = warp::any().and(graphiql_filter.or(graphql_filter));
-        warp::serve(routes).run((opt.host, opt.port)).await;
+        warp::serve(routes)
+            .bind_with_graceful_shutdown(
+                (opt.host(), opt.port()),
+                signals::shutdown(),
+            )
+            .1
+            .await;
     } else {
         info!("Disabling graphiql interface");
         let routes = warp::any().and(graphql_filter);
-        warp::serve(routes).run((opt.host, opt.port)).await;
+        warp::serve(routes)
+            .bind_with_graceful_shutdown(
+                (opt.host(), opt.port()),
+                signals::shutdown(),
+            )
+            .1
+            .await;
     };
 }
@@ -0,0 +1,60 @@
+use log::*;
+
+/// Waits until the process is asked to stop, either by an interrupt (ctrl-c)
+/// or, on unix, by SIGTERM such as from systemd
+pub async fn shutdown() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(x) => {
+                error!("Failed to listen for SIGTERM: {}", x);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+
+    info!("Shutting down");
+}
+
+/// Invokes the callback each time the process receives SIGHUP, which is how
+/// systemd and similar tools ask a daemon to reload its configuration
+///
+/// Does nothing on platforms without SIGHUP.
+pub fn on_reload<F, Fut>(mut f: F)
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send,
+{
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::hangup()) {
+            Ok(mut hangup) => {
+                tokio::spawn(async move {
+                    while hangup.recv().await.is_some() {
+                        info!("Reloading");
+                        f().await;
+                    }
+                });
+            }
+            Err(x) => error!("Failed to listen for SIGHUP: {}", x),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = &mut f;
+    }
+}
@@ -0,0 +1,189 @@
+#[cfg(unix)]
+pub use self::unix::*;
+
+#[cfg(not(unix))]
+pub use self::other::*;
+
+#[cfg(unix)]
+mod unix {
+    use super::super::{signals, stdin};
+    use crate::{graphql, Opt};
+    use log::*;
+    use std::{
+        fs::{self, File, OpenOptions},
+        io::{self, Write},
+        os::unix::io::AsRawFd,
+        path::{Path, PathBuf},
+    };
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::{UnixListener, UnixStream},
+        sync::watch,
+        task::JoinSet,
+    };
+    use vimwiki::settings::socket_lock_path;
+
+    /// Represents the lock held by the only server of a socket, which
+    /// contains the id of the server's process and is released along with
+    /// the socket when dropped
+    pub struct SocketLock {
+        file: File,
+        socket: PathBuf,
+    }
+
+    impl SocketLock {
+        /// Acquires the lock of the socket at the given path, failing if
+        /// another server already holds it
+        pub fn acquire(socket: &Path) -> io::Result<Self> {
+            if let Some(parent) = socket.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            let lock = socket_lock_path(socket);
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&lock)?;
+
+            // NOTE: The lock belongs to the open file rather than the path,
+            //       so the operating system releases it even if we crash
+            let flags = libc::LOCK_EX | libc::LOCK_NB;
+            if unsafe { libc::flock(file.as_raw_fd(), flags) } != 0 {
+                let pid = fs::read_to_string(&lock).unwrap_or_default();
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!(
+                        "Server already running on {:?} (pid {})",
+                        socket,
+                        pid.trim()
+                    ),
+                ));
+            }
+
+            file.set_len(0)?;
+            write!(file, "{}", std::process::id())?;
+
+            // Holding the lock means that any existing socket was left
+            // behind by a server that did not shut down cleanly
+            if socket.exists() {
+                fs::remove_file(socket)?;
+            }
+
+            Ok(Self {
+                file,
+                socket: socket.to_path_buf(),
+            })
+        }
+    }
+
+    impl Drop for SocketLock {
+        fn drop(&mut self) {
+            // NOTE: The lock file itself is kept, as removing it would let
+            //       another server lock a new file while a third still
+            //       waits on the old one
+            let _ = fs::remove_file(&self.socket);
+            let _ = self.file.set_len(0);
+        }
+    }
+
+    /// Accepts connections on the unix socket, reading input and replying
+    /// in the same form as stdin mode, until asked to shut down
+    pub async fn run(opt: Opt) -> io::Result<()> {
+        let schema = graphql::new_schema();
+        let socket = opt.socket();
+        let listener = UnixListener::bind(&socket)?;
+        info!("Listening on {:?}", socket);
+
+        let (tx, rx) = watch::channel(());
+        let mut connections = JoinSet::new();
+        let shutdown = signals::shutdown();
+        tokio::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                result = listener.accept() => match result {
+                    Ok((stream, _)) => {
+                        connections.spawn(serve(
+                            schema.clone(),
+                            stream,
+                            rx.clone(),
+                        ));
+                    }
+                    Err(x) => error!("Failed to accept connection: {}", x),
+                },
+
+                // Forget connections as they close so only those still open
+                // are kept around
+                Some(_) = connections.join_next(),
+                    if !connections.is_empty() => {}
+            }
+        }
+
+        // Let each connection finish the request it is working on
+        drop(listener);
+        let _ = tx.send(());
+        while connections.join_next().await.is_some() {}
+
+        Ok(())
+    }
+
+    /// Replies to each line read from the stream until it closes or the
+    /// server shuts down
+    async fn serve(
+        schema: graphql::Schema,
+        stream: UnixStream,
+        mut shutdown: watch::Receiver<()>,
+    ) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        loop {
+            let line = tokio::select! {
+                _ = shutdown.changed() => break,
+                line = lines.next_line() => line,
+            };
+
+            match line {
+                Ok(Some(line)) => {
+                    if let Some(msg) = stdin::respond(&schema, &line).await {
+                        let msg = format!("{}\n", msg);
+                        if let Err(x) = writer.write_all(msg.as_bytes()).await {
+                            error!("Failed to write to socket: {}", x);
+                            break;
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(x) => {
+                    error!("Failed to read from socket: {}", x);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod other {
+    use crate::Opt;
+    use std::{io, path::Path};
+
+    /// Represents the lock held by the only server of a socket, which is
+    /// unsupported on this platform
+    pub struct SocketLock;
+
+    impl SocketLock {
+        pub fn acquire(_socket: &Path) -> io::Result<Self> {
+            Err(unsupported())
+        }
+    }
+
+    pub async fn run(_opt: Opt) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    fn unsupported() -> io::Error {
+        io::Error::other("Socket mode requires unix domain sockets")
+    }
+}
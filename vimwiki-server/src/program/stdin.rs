@@ -34,12 +34,8 @@ pub async fn run(_opt: Opt) {
         let result = stdin.read_line(&mut buffer);
         match result {
             Ok(n) if n > 0 => {
-                // Parse our input if possible, in the form of
-                // { "id": ..., "payload": ... }
-                if let Ok(Input { id, payload }) = serde_json::from_str(&buffer)
-                {
-                    let response = schema.execute(&payload).await;
-                    send_response(id, response).await;
+                if let Some(msg) = respond(&schema, &buffer).await {
+                    println!("{}", msg);
                 }
 
                 buffer.clear();
@@ -53,12 +49,22 @@ pub async fn run(_opt: Opt) {
     }
 }
 
-async fn send_response(id: usize, response: async_graphql::Response) {
+/// Executes a line of input in the form of { "id": ..., "payload": ... },
+/// returning the serialized output to send back if the input is valid
+pub(super) async fn respond(
+    schema: &graphql::Schema,
+    line: &str,
+) -> Option<String> {
+    let Input { id, payload } = serde_json::from_str(line).ok()?;
+    let response = schema.execute(&payload).await;
     match serde_json::to_string(&response)
         .map(|payload| Output { id, payload })
         .and_then(|output| serde_json::to_string(&output))
     {
-        Ok(msg) => println!("{}", msg),
-        Err(x) => eprintln!("{}", x),
+        Ok(msg) => Some(msg),
+        Err(x) => {
+            error!("Failed to serialize response: {}", x);
+            None
+        }
     }
 }
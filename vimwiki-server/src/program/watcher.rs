@@ -1,6 +1,6 @@
 use crate::{
     data::{ParsedFile, Wiki},
-    Config,
+    metrics, preview, Config,
 };
 use async_graphql::Pos;
 use entity::{TypedPredicate as P, *};
@@ -14,6 +14,7 @@ use std::{
     ffi::OsStr,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Instant,
 };
 use tokio::{
     sync::{mpsc, Mutex},
@@ -37,11 +38,12 @@ impl Watcher {
 
         trace!("Watching wiki {:?}", path.as_ref());
         if !self.is_watched(path.as_ref()).await {
+            let path_buf = path.as_ref().to_path_buf();
             let result = self
-                .watcher
-                .lock()
-                .await
-                .watch(path.as_ref(), RecursiveMode::Recursive);
+                .with_watcher(move |w| {
+                    w.watch(path_buf.as_path(), RecursiveMode::Recursive)
+                })
+                .await;
 
             if result.is_ok() {
                 self.wikis.lock().await.push(path.as_ref().to_path_buf());
@@ -63,11 +65,12 @@ impl Watcher {
 
         trace!("Watching standalone {:?}", path.as_ref());
         if !self.is_watched(path.as_ref()).await {
+            let path_buf = path.as_ref().to_path_buf();
             let result = self
-                .watcher
-                .lock()
-                .await
-                .watch(path.as_ref(), RecursiveMode::NonRecursive);
+                .with_watcher(move |w| {
+                    w.watch(path_buf.as_path(), RecursiveMode::NonRecursive)
+                })
+                .await;
 
             if result.is_ok() {
                 self.standalone
@@ -92,13 +95,27 @@ impl Watcher {
         if self.wikis.lock().await.iter().any(|w| w == path.as_ref())
             || self.standalone.lock().await.contains(path.as_ref())
         {
-            self.watcher.lock().await.unwatch(path)
+            let path = path.as_ref().to_path_buf();
+            self.with_watcher(move |w| w.unwatch(path.as_path())).await
         } else {
             trace!("{:?} is not being watched", path.as_ref());
             Ok(())
         }
     }
 
+    /// Runs the operation against the internal watcher on a blocking thread
+    /// as watching a directory recursively visits every directory within it
+    async fn with_watcher<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut RecommendedWatcher) -> Result<T, Error> + Send + 'static,
+    {
+        let watcher = Arc::clone(&self.watcher);
+        tokio::task::spawn_blocking(move || f(&mut watcher.blocking_lock()))
+            .await
+            .map_err(|x| Error::generic(&x.to_string()))?
+    }
+
     /// Whether or not the given path is already being watched, either as
     /// part of a wiki directory or as an individual, standalone file
     pub async fn is_watched(&self, path: impl AsRef<Path>) -> bool {
@@ -134,7 +151,7 @@ impl Watcher {
         config: &Config,
         database: DatabaseRc,
     ) -> Result<Self, Error> {
-        let (tx, rx) = mpsc::unbounded_channel::<notify::Event>();
+        let (tx, rx) = mpsc::unbounded_channel::<(notify::Event, Instant)>();
         let internal_watcher = Self::new_internal_watcher(tx)?;
 
         // Build a map of wiki path -> ext for use in file event monitoring
@@ -198,13 +215,15 @@ impl Watcher {
     }
 
     fn new_internal_watcher(
-        tx: mpsc::UnboundedSender<Event>,
+        tx: mpsc::UnboundedSender<(Event, Instant)>,
     ) -> Result<RecommendedWatcher, Error> {
         use notify::Watcher;
 
+        // Events are paired with when they were received so that the time
+        // spent waiting to be processed can be measured
         Watcher::new_immediate(move |res| match res {
             Ok(event) => {
-                if let Err(x) = tx.send(event) {
+                if let Err(x) = tx.send((event, Instant::now())) {
                     error!("Failed to queue event: {}", x);
                 }
             }
@@ -215,32 +234,16 @@ impl Watcher {
     fn spawn_handle(
         ext_map: HashMap<PathBuf, String>,
         _database: DatabaseRc,
-        mut rx: mpsc::UnboundedReceiver<Event>,
+        mut rx: mpsc::UnboundedReceiver<(Event, Instant)>,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
-            while let Some(event) = rx.recv().await {
+            while let Some((event, received)) = rx.recv().await {
+                metrics::record_watcher_lag(received.elapsed());
+
                 // Ensure that the event we receive is for a supported
-                // file extension
-                let not_for_valid_file_exts = event.paths.iter().any(|p| {
-                    let ext = ext_map.iter().find_map(|(path, ext)| {
-                        // Check if the path of the file change event is within
-                        // one of our ext map paths and, if so, apply that
-                        // extension
-                        //
-                        // NOTE: This doesn't support nested paths where one
-                        //       path is /my/wiki and another is /my/wiki/nested
-                        //       as it finds the first match rather than the
-                        //       deepest match
-                        if p.starts_with(path) {
-                            Some(ext.as_str())
-                        } else {
-                            None
-                        }
-                    });
-                    ext.is_some()
-                        && p.extension().and_then(OsStr::to_str) == ext
-                });
-                if not_for_valid_file_exts {
+                // file extension, ignoring other files within a wiki such
+                // as those of a git repository
+                if !is_for_valid_file_exts(&ext_map, &event.paths) {
                     continue;
                 }
 
@@ -253,10 +256,16 @@ impl Watcher {
                 match event.kind {
                     EventKind::Create(CreateKind::File)
                     | EventKind::Modify(ModifyKind::Data(_)) => {
-                        if let Err(x) =
-                            ParsedFile::load_all(None, &event.paths).await
-                        {
-                            error!("{}", x.into_server_error(Pos::default()));
+                        match ParsedFile::load_all(None, &event.paths).await {
+                            Ok(files) => {
+                                for file in files {
+                                    preview::notify_file_changed(file.path());
+                                }
+                            }
+                            Err(x) => error!(
+                                "{}",
+                                x.into_server_error(Pos::default())
+                            ),
                         }
                     }
                     EventKind::Remove(RemoveKind::File) => {
@@ -290,3 +299,69 @@ impl Watcher {
         })
     }
 }
+
+/// Returns true if any of the paths is for a file to be processed, meaning
+/// that it either has the extension of the wiki containing it or is not
+/// within any wiki at all (a standalone file)
+fn is_for_valid_file_exts(
+    ext_map: &HashMap<PathBuf, String>,
+    paths: &[PathBuf],
+) -> bool {
+    paths.iter().any(|p| {
+        let ext = ext_map.iter().find_map(|(path, ext)| {
+            // Check if the path of the file change event is within one of our
+            // ext map paths and, if so, apply that extension
+            //
+            // NOTE: This doesn't support nested paths where one path is
+            //       /my/wiki and another is /my/wiki/nested as it finds the
+            //       first match rather than the deepest match
+            if p.starts_with(path) {
+                Some(ext.as_str())
+            } else {
+                None
+            }
+        });
+        ext.is_none() || p.extension().and_then(OsStr::to_str) == ext
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ext_map() -> HashMap<PathBuf, String> {
+        vec![(PathBuf::from("/my/wiki"), String::from("wiki"))]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn is_for_valid_file_exts_should_accept_wiki_files() {
+        let paths = vec![PathBuf::from("/my/wiki/page.wiki")];
+        assert!(is_for_valid_file_exts(&ext_map(), &paths));
+    }
+
+    #[test]
+    fn is_for_valid_file_exts_should_reject_other_files_within_wiki() {
+        let paths = vec![
+            PathBuf::from("/my/wiki/.git/index"),
+            PathBuf::from("/my/wiki/image.png"),
+        ];
+        assert!(!is_for_valid_file_exts(&ext_map(), &paths));
+    }
+
+    #[test]
+    fn is_for_valid_file_exts_should_accept_if_any_path_is_a_wiki_file() {
+        let paths = vec![
+            PathBuf::from("/my/wiki/page.wiki.swp"),
+            PathBuf::from("/my/wiki/page.wiki"),
+        ];
+        assert!(is_for_valid_file_exts(&ext_map(), &paths));
+    }
+
+    #[test]
+    fn is_for_valid_file_exts_should_accept_standalone_files() {
+        let paths = vec![PathBuf::from("/elsewhere/notes.txt")];
+        assert!(is_for_valid_file_exts(&ext_map(), &paths));
+    }
+}
@@ -0,0 +1,135 @@
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+lazy_static! {
+    static ref REVISIONS: RwLock<Revisions> = RwLock::new(Revisions::default());
+}
+
+#[derive(Default)]
+struct Revisions {
+    current: u64,
+    files: HashMap<PathBuf, Revision>,
+}
+
+/// Represents the latest change made to a file
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Revision {
+    /// Revision of the workspace when the change was made
+    pub revision: u64,
+
+    /// True if the change removed the file
+    pub removed: bool,
+}
+
+fn record(path: impl AsRef<Path>, removed: bool) -> u64 {
+    match REVISIONS.write() {
+        Ok(mut x) => {
+            x.current += 1;
+            let revision = x.current;
+            x.files.insert(
+                path.as_ref().to_path_buf(),
+                Revision { revision, removed },
+            );
+            revision
+        }
+        Err(_) => current(),
+    }
+}
+
+/// Records that the file at the canonical path was added or modified,
+/// returning the new revision of the workspace
+pub fn modified(path: impl AsRef<Path>) -> u64 {
+    record(path, false)
+}
+
+/// Records that the file at the canonical path was removed, returning the new
+/// revision of the workspace
+pub fn removed(path: impl AsRef<Path>) -> u64 {
+    record(path, true)
+}
+
+/// Returns the current revision of the workspace, which starts at zero and
+/// increases with every change to a file
+pub fn current() -> u64 {
+    REVISIONS.read().map(|x| x.current).unwrap_or_default()
+}
+
+/// Returns the latest change made to the file at the canonical path
+pub fn get(path: impl AsRef<Path>) -> Option<Revision> {
+    REVISIONS
+        .read()
+        .ok()
+        .and_then(|x| x.files.get(path.as_ref()).copied())
+}
+
+/// Returns the latest change of every file changed after the revision,
+/// ordered from oldest to newest change
+pub fn changed_since(revision: u64) -> Vec<(PathBuf, Revision)> {
+    let mut changes: Vec<(PathBuf, Revision)> = REVISIONS
+        .read()
+        .map(|x| {
+            x.files
+                .iter()
+                .filter(|(_, x)| x.revision > revision)
+                .map(|(path, x)| (path.to_path_buf(), *x))
+                .collect()
+        })
+        .unwrap_or_default();
+    changes.sort_unstable_by_key(|(_, x)| x.revision);
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_since_should_only_include_latest_change_of_each_file() {
+        let start = current();
+        let a = Path::new("/revisions/a.wiki");
+        let b = Path::new("/revisions/b.wiki");
+
+        modified(a);
+        let middle = modified(b);
+        let last = removed(a);
+        assert_eq!(
+            get(a),
+            Some(Revision {
+                revision: last,
+                removed: true
+            })
+        );
+
+        let changes: Vec<(PathBuf, Revision)> = changed_since(start)
+            .into_iter()
+            .filter(|(path, _)| path.starts_with("/revisions"))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    b.to_path_buf(),
+                    Revision {
+                        revision: middle,
+                        removed: false
+                    }
+                ),
+                (
+                    a.to_path_buf(),
+                    Revision {
+                        revision: last,
+                        removed: true
+                    }
+                ),
+            ]
+        );
+
+        assert!(changed_since(last)
+            .iter()
+            .all(|(path, _)| !path.starts_with("/revisions")));
+    }
+}
@@ -0,0 +1,96 @@
+use lazy_static::lazy_static;
+use std::sync::RwLock;
+use vimwiki::{Language, Page, ParseError, ParserConfig};
+
+lazy_static! {
+    static ref PARSER_CONFIG: RwLock<ParserConfig> =
+        RwLock::new(ParserConfig::default());
+}
+
+/// Sets the syntax used by the server when parsing pages
+pub fn set_parser_config(config: ParserConfig) {
+    if let Ok(mut x) = PARSER_CONFIG.write() {
+        *x = config;
+    }
+}
+
+/// Returns the syntax used by the server when parsing pages
+pub fn parser_config() -> ParserConfig {
+    PARSER_CONFIG.read().map(|x| x.clone()).unwrap_or_default()
+}
+
+/// Parses the text as a vimwiki page using the configured syntax
+pub fn parse_page(text: &str) -> Result<Page<'_>, ParseError<'_>> {
+    Language::from_vimwiki_str(text).parse_with_config(&parser_config())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{data::ParsedFile, pages, utils};
+    use entity::global;
+    use entity_inmemory::InmemoryDatabase;
+    use vimwiki::{settings::Settings, InlineElement, Keyword};
+
+    #[test]
+    fn indexed_page_should_recognize_keywords_from_settings() {
+        let dir = std::env::temp_dir().join("vimwiki-server-syntax");
+        std::fs::create_dir_all(&dir).unwrap();
+        let settings_path = dir.join("vimwiki.toml");
+        std::fs::write(
+            &settings_path,
+            r#"
+            [syntax]
+            keywords = ["TODO", "DONE", "STARTED", "FIXME", "FIXED", "XXX", "WAIT"]
+            "#,
+        )
+        .unwrap();
+        let config_path = dir.join("config.toml");
+        std::fs::write(
+            &config_path,
+            format!("[[wikis]]\npath = {:?}\n", dir.to_string_lossy()),
+        )
+        .unwrap();
+        let page_path = dir.join("page.wiki");
+        std::fs::write(&page_path, "WAIT for it\n").unwrap();
+
+        let settings = Settings::load_from([&settings_path]).unwrap();
+        let config =
+            utils::load_config(config_path.as_path(), &settings, false)
+                .unwrap();
+
+        let (before, after) =
+            global::with_db(InmemoryDatabase::default(), || {
+                let runtime = tokio::runtime::Runtime::new().unwrap();
+                runtime.block_on(async {
+                    let file =
+                        ParsedFile::load(None, &page_path).await.unwrap();
+                    let before =
+                        pages::get(file.path(), file.checksum()).unwrap();
+
+                    // Pages indexed before the syntax changed are parsed
+                    // again even though their contents are the same
+                    set_parser_config(config.syntax.clone());
+                    ParsedFile::reparse_all().await.unwrap();
+                    let file =
+                        ParsedFile::load(None, &page_path).await.unwrap();
+                    let after =
+                        pages::get(file.path(), file.checksum()).unwrap();
+                    (before, after)
+                })
+            });
+        set_parser_config(ParserConfig::default());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let has_wait = |indexed: &pages::IndexedPage| {
+            indexed.page.descendants().any(|e| {
+                matches!(
+                    e.as_inline_element(),
+                    Some(InlineElement::Keyword(Keyword::Other(x))) if x == "WAIT"
+                )
+            })
+        };
+        assert!(!has_wait(&before));
+        assert!(has_wait(&after));
+    }
+}
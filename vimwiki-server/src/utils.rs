@@ -7,6 +7,7 @@ use std::{
     fs, io,
     path::{Component, Path, PathBuf},
 };
+use vimwiki::settings::Settings;
 
 /// Builds a new progress bar for n items
 pub fn new_progress_bar(n: u64) -> ProgressBar {
@@ -110,6 +111,7 @@ pub fn normalize_path(path: &Path) -> PathBuf {
 /// vim/neovim if no wikis are defined or if merge = true
 pub fn load_config<'a, I: Into<Option<&'a Path>>>(
     path: I,
+    settings: &Settings,
     merge: bool,
 ) -> io::Result<Config> {
     let maybe_path = path.into();
@@ -122,6 +124,14 @@ pub fn load_config<'a, I: Into<Option<&'a Path>>>(
         Config::default()
     };
 
+    // Wikis of the settings are only used if the config file has none
+    let html = settings.to_html_config();
+    if config.wikis.is_empty() {
+        config.wikis = html.wikis.iter().map(WikiConfig::from).collect();
+    }
+    config.html = html;
+    config.syntax = settings.syntax.clone();
+
     // Attempt to load wikis from vim if html config has no wikis or if
     // we are explicitly told to merge
     if config.wikis.is_empty() || merge {
@@ -1,10 +1,6 @@
 use crate::utils;
 use std::{borrow::Cow, collections::HashMap, convert::TryFrom};
-use vimwiki::{
-    self as v,
-    vendor::{chrono, uriparse},
-    ToHtmlString,
-};
+use vimwiki::{self as v, ToHtmlString};
 use wasm_bindgen::prelude::*;
 
 /// Represents a wrapper around a vimwiki page
@@ -15,7 +11,7 @@ pub struct Page(v::Page<'static>);
 impl Page {
     /// Returns top-level element at the given index if it exists
     pub fn element_at(&self, idx: usize) -> Option<BlockElement> {
-        self.0.elements.get(idx).map(|x| {
+        self.0.elements().get(idx).map(|x| {
             BlockElement(v::Located::new(
                 x.to_borrowed().into_owned(),
                 x.region(),
@@ -26,7 +22,17 @@ impl Page {
     /// Represents total number of top-level elements within the page
     #[wasm_bindgen(getter)]
     pub fn element_cnt(&self) -> usize {
-        self.0.elements.len()
+        self.0.elements().len()
+    }
+
+    /// Returns an array of all inline code found anywhere within the page
+    pub fn code_inlines(&self) -> js_sys::Array {
+        self.0
+            .code_inlines()
+            .into_iter()
+            .map(|x| CodeInline(x.map(v::CodeInline::into_owned)))
+            .map(JsValue::from)
+            .collect()
     }
 }
 
@@ -233,6 +239,22 @@ impl BlockElement {
         }
     }
 
+    /// Returns true if element is footnote definition
+    pub fn is_footnote_definition(&self) -> bool {
+        matches!(self.0.as_inner(), v::BlockElement::FootnoteDefinition(_))
+    }
+
+    /// Casts to footnote definition if it is one
+    pub fn into_footnote_definition(self) -> Option<FootnoteDefinition> {
+        let region = self.0.region();
+        match self.0.into_inner() {
+            v::BlockElement::FootnoteDefinition(x) => {
+                Some(FootnoteDefinition(v::Located::new(x, region)))
+            }
+            _ => None,
+        }
+    }
+
     /// Returns true if element is table
     pub fn is_table(&self) -> bool {
         matches!(self.0.as_inner(), v::BlockElement::Table(_))
@@ -427,6 +449,22 @@ impl InlineElement {
             _ => None,
         }
     }
+
+    /// Returns true if element is footnote reference
+    pub fn is_footnote_reference(&self) -> bool {
+        matches!(self.0.as_inner(), v::InlineElement::FootnoteReference(_))
+    }
+
+    /// Casts to footnote reference if it is one
+    pub fn into_footnote_reference(self) -> Option<FootnoteReference> {
+        let region = self.0.region();
+        match self.0.into_inner() {
+            v::InlineElement::FootnoteReference(x) => {
+                Some(FootnoteReference(v::Located::new(x, region)))
+            }
+            _ => None,
+        }
+    }
 }
 
 /// Represents a wrapper around a vimwiki inline element container
@@ -468,6 +506,12 @@ impl Blockquote {
         self.0.lines.get(idx).map(ToString::to_string)
     }
 
+    /// Returns the nesting depth of the line at the given index, where 1 is
+    /// the outermost quote and blank lines have a depth of 0
+    pub fn depth_at(&self, idx: usize) -> usize {
+        self.0.line_depth(idx)
+    }
+
     /// Represents total number of lines within the blockquote
     #[wasm_bindgen(getter)]
     pub fn line_cnt(&self) -> usize {
@@ -513,6 +557,16 @@ impl CodeBlock {
     pub fn line_cnt(&self) -> usize {
         self.0.lines.len()
     }
+
+    /// Returns the content exactly as written within the fences
+    pub fn raw_content(&self) -> String {
+        self.0.raw_content()
+    }
+
+    /// Returns the content with indentation shared by all lines removed
+    pub fn dedented_content(&self) -> String {
+        self.0.dedented_content()
+    }
 }
 
 /// Represents a wrapper around a vimwiki definition list
@@ -559,6 +613,51 @@ impl DefinitionList {
 #[wasm_bindgen]
 pub struct Divider(v::Located<v::Divider>);
 
+#[wasm_bindgen]
+impl Divider {
+    /// Represents the total hyphens making up the divider
+    #[wasm_bindgen(getter)]
+    pub fn hyphen_cnt(&self) -> usize {
+        self.0.len
+    }
+
+    /// Represents the total blank lines immediately preceding the divider
+    #[wasm_bindgen(getter)]
+    pub fn blank_lines_before(&self) -> usize {
+        self.0.blank_lines_before
+    }
+
+    /// Represents the total blank lines immediately following the divider
+    #[wasm_bindgen(getter)]
+    pub fn blank_lines_after(&self) -> usize {
+        self.0.blank_lines_after
+    }
+}
+
+/// Represents a wrapper around a vimwiki footnote definition
+#[wasm_bindgen]
+pub struct FootnoteDefinition(v::Located<v::FootnoteDefinition<'static>>);
+
+#[wasm_bindgen]
+impl FootnoteDefinition {
+    /// Represents the label of the footnote being defined
+    #[wasm_bindgen(getter)]
+    pub fn label(&self) -> String {
+        self.0.label.to_string()
+    }
+
+    /// Represents the contents of the footnote
+    #[wasm_bindgen(getter)]
+    pub fn contents(&self) -> InlineElementContainer {
+        InlineElementContainer(self.0.contents.to_borrowed().into_owned())
+    }
+
+    /// Converts footnote definition to a JavaScript string
+    pub fn to_str(&self) -> String {
+        self.0.to_string()
+    }
+}
+
 /// Represents a wrapper around a vimwiki header
 #[wasm_bindgen]
 pub struct Header(v::Located<v::Header<'static>>);
@@ -695,6 +794,18 @@ impl ListItemAttributes {
             .map(ListItemTodoStatus::from)
     }
 
+    /// Represents the date by which the list item is due if it has one
+    #[wasm_bindgen(getter)]
+    pub fn due(&self) -> Option<js_sys::Date> {
+        self.0.due.map(|x| {
+            js_sys::Date::new_with_year_month_day(
+                x.year() as u32,
+                (x.month() - 1) as i32,
+                x.day() as i32,
+            )
+        })
+    }
+
     pub fn is_todo_incomplete(&self) -> bool {
         matches!(self.0.todo_status, Some(v::ListItemTodoStatus::Incomplete))
     }
@@ -889,7 +1000,6 @@ impl Placeholder {
     /// Represents the date associated with the placeholder if it has one
     #[wasm_bindgen(getter)]
     pub fn date(&self) -> Option<js_sys::Date> {
-        use chrono::Datelike;
         match self.0.as_inner() {
             v::Placeholder::Date(x) => {
                 Some(js_sys::Date::new_with_year_month_day(
@@ -1238,6 +1348,10 @@ pub enum Keyword {
     Fixme = "FIXME",
     Fixed = "FIXED",
     Xxx = "XXX",
+
+    /// Keyword recognized through the parser config rather than builtin,
+    /// which cannot be converted back as its name is unknown
+    Other = "OTHER",
 }
 
 impl Keyword {
@@ -1264,6 +1378,7 @@ impl From<v::Keyword> for Keyword {
             v::Keyword::Fixme => Self::Fixme,
             v::Keyword::Fixed => Self::Fixed,
             v::Keyword::Xxx => Self::Xxx,
+            v::Keyword::Other(_) => Self::Other,
         }
     }
 }
@@ -1282,7 +1397,7 @@ impl Link {
     ) -> Result<Link, JsValue> {
         Ok(Self(v::Located::new(
             v::Link::new_wiki_link(
-                uriparse::URIReference::try_from(uri)
+                v::WikiUri::try_from(uri)
                     .map_err(|x| JsValue::from_str(x.to_string().as_str()))?,
                 v::Description::try_from_uri_ref_str(description)
                     .unwrap_or_else(|_| v::Description::from(description)),
@@ -1302,7 +1417,7 @@ impl Link {
         Ok(Self(v::Located::new(
             v::Link::new_indexed_interwiki_link(
                 index,
-                uriparse::URIReference::try_from(uri)
+                v::WikiUri::try_from(uri)
                     .map_err(|x| JsValue::from_str(x.to_string().as_str()))?,
                 v::Description::try_from_uri_ref_str(description)
                     .unwrap_or_else(|_| v::Description::from(description)),
@@ -1322,7 +1437,7 @@ impl Link {
         Ok(Self(v::Located::new(
             v::Link::new_named_interwiki_link(
                 name,
-                uriparse::URIReference::try_from(uri)
+                v::WikiUri::try_from(uri)
                     .map_err(|x| JsValue::from_str(x.to_string().as_str()))?,
                 v::Description::try_from_uri_ref_str(description)
                     .unwrap_or_else(|_| v::Description::from(description)),
@@ -1341,7 +1456,7 @@ impl Link {
     ) -> Link {
         Self(v::Located::new(
             v::Link::new_diary_link(
-                chrono::NaiveDate::from_ymd(
+                v::WikiDate::from_ymd(
                     date.get_utc_full_year() as i32,
                     date.get_utc_month(),
                     date.get_utc_date(),
@@ -1362,7 +1477,7 @@ impl Link {
     ) -> Result<Link, JsValue> {
         Ok(Self(v::Located::new(
             v::Link::new_raw_link(
-                uriparse::URIReference::try_from(uri)
+                v::WikiUri::try_from(uri)
                     .map_err(|x| JsValue::from_str(x.to_string().as_str()))?,
             )
             .into_owned(),
@@ -1377,7 +1492,7 @@ impl Link {
         properties: &js_sys::Object,
         region: Option<Region>,
     ) -> Result<Link, JsValue> {
-        let uri = uriparse::URIReference::try_from(uri)
+        let uri = v::WikiUri::try_from(uri)
             .map_err(|x| JsValue::from_str(x.to_string().as_str()))?;
         let desc = v::Description::try_from_uri_ref_str(description)
             .unwrap_or_else(|_| v::Description::from(description));
@@ -1616,6 +1731,27 @@ impl Comment {
     }
 }
 
+/// Represents a wrapper around a vimwiki footnote reference
+#[wasm_bindgen]
+pub struct FootnoteReference(v::Located<v::FootnoteReference<'static>>);
+
+#[wasm_bindgen]
+impl FootnoteReference {
+    /// Creates a new footnote reference instance using the given label
+    #[wasm_bindgen(constructor)]
+    pub fn new(label: &str, region: Option<Region>) -> Self {
+        Self(v::Located::new(
+            v::FootnoteReference::new(Cow::from(label)).into_owned(),
+            region.map(|x| x.0).unwrap_or_default(),
+        ))
+    }
+
+    /// Converts footnote reference to a JavaScript string
+    pub fn to_str(&self) -> String {
+        self.0.to_string()
+    }
+}
+
 /// Represents a wrapper around a vimwiki text
 #[wasm_bindgen]
 pub struct Text(v::Located<v::Text<'static>>);
@@ -1722,6 +1858,7 @@ impl_from!(
     -Page Element BlockElement InlineBlockElement InlineElement Blockquote
     CodeBlock DefinitionList @Divider Header List MathBlock Paragraph Table
     DecoratedText Link Tags CodeInline MathInline Comment Text
+    FootnoteDefinition FootnoteReference
     -InlineElementContainer DecoratedTextContent ListItem
     Placeholder -@Region
 );
@@ -1772,6 +1909,7 @@ impl_convert!(
     Page Element BlockElement InlineBlockElement InlineElement Blockquote
     CodeBlock DefinitionList Divider Header List MathBlock Paragraph Table
     DecoratedText Link Tags CodeInline MathInline Comment Text
+    FootnoteDefinition FootnoteReference
     InlineElementContainer DecoratedTextContent ListItem
     Placeholder @Region
 );
@@ -1846,7 +1984,7 @@ macro_rules! impl_iter {
 
 impl_iter!(
     Page Element BlockElement InlineBlockElement InlineElement
-    DefinitionList Header List Paragraph Table
+    DefinitionList FootnoteDefinition Header List Paragraph Table
     DecoratedText InlineElementContainer DecoratedTextContent ListItem
 );
 
@@ -1870,8 +2008,8 @@ impl_region!(
     Element BlockElement InlineBlockElement InlineElement
 
     Blockquote CodeBlock DefinitionList Divider Header List MathBlock
-    Paragraph Placeholder Table
+    Paragraph Placeholder Table FootnoteDefinition
 
     DecoratedText Link Tags CodeInline MathInline Comment Text
-    DecoratedTextContent ListItem
+    FootnoteReference DecoratedTextContent ListItem
 );
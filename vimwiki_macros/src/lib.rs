@@ -43,9 +43,11 @@ macro_rules! impl_macro {
 
                 // Perform the action of parsing our language into a
                 // structured format
-                let element: $type = Language::$from_str(&input)
+                // NOTE: Errors point at the offending characters within the
+                //       literal rather than the entire macro invocation
+                let element: $type = Language::$from_str(&input.text)
                     .parse()
-                    .map_err(|x| Error::new(Span::call_site(), &format!("{}", x)))?;
+                    .map_err(|x| input.to_parse_error(&x))?;
 
                 // Stuff our structure language into a proper token stream
                 let ctx = TokenizeContext {
@@ -69,12 +71,66 @@ macro_rules! impl_macro {
     };
 }
 
-/// Macro that generates four macros in the form of
+macro_rules! impl_macro_compact {
+    ($name:ident, $from_str:ident, $type:ty, $raw_mode:expr) => {
+        #[proc_macro]
+        pub fn $name(
+            input: proc_macro::TokenStream,
+        ) -> proc_macro::TokenStream {
+            fn try_expand(input: TokenStream) -> Result<TokenStream> {
+                let first = input.into_iter().next().ok_or_else(|| {
+                    Error::new(
+                        Span::call_site(),
+                        "unexpected end of macro invocation, expected string",
+                    )
+                })?;
+
+                // Load our input into a string and parse it into a structured
+                // format as usual
+                let input = utils::input_to_string(first, $raw_mode)?;
+                let element: $type = Language::$from_str(&input.text)
+                    .parse()
+                    .map_err(|x| input.to_parse_error(&x))?;
+
+                // Rather than generating code to construct every element,
+                // embed the structure as bytes that are restored on first use
+                let bytes = compact::to_bytes(&element).map_err(|x| {
+                    Error::new(Span::call_site(), &x.to_string())
+                })?;
+                let bytes = proc_macro2::Literal::byte_string(&bytes);
+
+                let root = tokens::utils::root_crate();
+                let mut ty: syn::Type = syn::parse_str(stringify!($type))
+                    .expect("macro type is valid");
+                utils::qualify_type(&mut ty, &root);
+                Ok(quote::quote! {{
+                    static ELEMENT: #root::compact::Lazy<#ty> =
+                        #root::compact::Lazy::new(|| {
+                        #root::compact::from_bytes(#bytes)
+                            .expect("embedded vimwiki is valid")
+                    });
+                    ::std::clone::Clone::clone(&*ELEMENT)
+                }})
+            }
+
+            let output = match try_expand(TokenStream::from(input)) {
+                Ok(tokens) => tokens,
+                Err(err) => err.to_compile_error(),
+            };
+
+            proc_macro::TokenStream::from(output)
+        }
+    };
+}
+
+/// Macro that generates six macros in the form of
 ///
 /// 1. vimwiki_${suffix}
 /// 2. vimwiki_${suffix}_raw
 /// 3. vimwiki_${suffix}_format
 /// 4. vimwiki_${suffix}_raw_format
+/// 5. vimwiki_${suffix}_compact
+/// 6. vimwiki_${suffix}_raw_compact
 ///
 /// All convert the given text to the specified vimwiki type at compile time.
 ///
@@ -87,6 +143,11 @@ macro_rules! impl_macro {
 /// format!, println!, and write! can inject content. Note that formatting
 /// with {} currently does not support lists and definition lists as the order
 /// cannot be guaranteed. Instead, use named or numbered injection instead.
+///
+/// The compact versions embed the element as bytes that are deserialized on
+/// first use and cloned thereafter, rather than generating code to construct
+/// every element, which compiles much faster for large pages. They require
+/// the `compact` feature of the `vimwiki` crate.
 macro_rules! impl_macro_vimwiki {
     ($suffix:ident, $type:ty) => {
         paste! {
@@ -94,6 +155,8 @@ macro_rules! impl_macro_vimwiki {
             impl_macro!([<vimwiki_ $suffix _raw>], from_vimwiki_str, $type, true, true);
             impl_macro!([<vimwiki_ $suffix _format>], from_vimwiki_str, $type, false, false);
             impl_macro!([<vimwiki_ $suffix _raw_format>], from_vimwiki_str, $type, true, false);
+            impl_macro_compact!([<vimwiki_ $suffix _compact>], from_vimwiki_str, $type, false);
+            impl_macro_compact!([<vimwiki_ $suffix _raw_compact>], from_vimwiki_str, $type, true);
         }
     };
 }
@@ -111,6 +174,8 @@ impl_macro_vimwiki!(line_comment, Located<LineComment>);
 impl_macro_vimwiki!(multi_line_comment, Located<MultiLineComment>);
 impl_macro_vimwiki!(definition_list, Located<DefinitionList>);
 impl_macro_vimwiki!(divider, Located<Divider>);
+impl_macro_vimwiki!(footnote_definition, Located<FootnoteDefinition>);
+impl_macro_vimwiki!(footnote_reference, Located<FootnoteReference>);
 impl_macro_vimwiki!(header, Located<Header>);
 impl_macro_vimwiki!(link, Located<Link>);
 impl_macro_vimwiki!(list, Located<List>);
@@ -10,7 +10,11 @@ fn tokenize_blockquote(
 ) -> TokenStream {
     let root = root_crate();
     let lines = blockquote.lines.iter().map(|x| do_tokenize!(ctx, x));
+    let depths = blockquote.depths.iter();
     quote! {
-        #root::Blockquote::new(::std::vec![#(#lines),*])
+        #root::Blockquote::with_depths(
+            ::std::vec![#(#lines),*],
+            ::std::vec![#(#depths),*],
+        )
     }
 }
@@ -25,11 +25,20 @@ fn tokenize_code_block(
         |x| do_tokenize!(ctx, x),
     );
     let lines = code_block.lines.iter().map(|x| do_tokenize!(ctx, x));
+    let start_indent = do_tokenize!(ctx, &code_block.start_indent);
+    let end_indent = do_tokenize!(ctx, &code_block.end_indent);
+    let line_indents =
+        code_block.line_indents.iter().map(|x| do_tokenize!(ctx, x));
     quote! {
         #root::CodeBlock::new(
             #lang,
             #metadata,
             ::std::vec![#(#lines),*],
         )
+        .with_indents(
+            #start_indent,
+            #end_indent,
+            ::std::vec![#(#line_indents),*],
+        )
     }
 }
@@ -4,9 +4,14 @@ use quote::quote;
 use vimwiki_core::Divider;
 
 impl_tokenize!(tokenize_divider, Divider);
-fn tokenize_divider(_ctx: &TokenizeContext, _divider: &Divider) -> TokenStream {
+fn tokenize_divider(_ctx: &TokenizeContext, divider: &Divider) -> TokenStream {
     let root = root_crate();
+    let Divider {
+        len,
+        blank_lines_before,
+        blank_lines_after,
+    } = divider;
     quote! {
-        #root::Divider
+        #root::Divider::new(#len, #blank_lines_before, #blank_lines_after)
     }
 }
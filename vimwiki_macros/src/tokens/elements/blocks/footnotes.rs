@@ -0,0 +1,21 @@
+use crate::tokens::{utils::root_crate, Tokenize, TokenizeContext};
+use proc_macro2::TokenStream;
+use quote::quote;
+use vimwiki_core::FootnoteDefinition;
+
+impl_tokenize!(
+    tokenize_footnote_definition,
+    FootnoteDefinition<'a>,
+    'a
+);
+fn tokenize_footnote_definition(
+    ctx: &TokenizeContext,
+    footnote_definition: &FootnoteDefinition,
+) -> TokenStream {
+    let root = root_crate();
+    let label = do_tokenize!(ctx, &footnote_definition.label);
+    let contents = do_tokenize!(ctx, &footnote_definition.contents);
+    quote! {
+        #root::FootnoteDefinition::new(#label, #contents)
+    }
+}
@@ -0,0 +1,21 @@
+use crate::tokens::{utils::root_crate, Tokenize, TokenizeContext};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::borrow::Cow;
+use vimwiki_core::FootnoteReference;
+
+impl_tokenize!(
+    tokenize_footnote_reference,
+    FootnoteReference<'a>,
+    'a
+);
+fn tokenize_footnote_reference(
+    ctx: &TokenizeContext,
+    footnote_reference: &FootnoteReference,
+) -> TokenStream {
+    let root = root_crate();
+    let label = do_tokenize!(ctx, Cow::Borrowed(footnote_reference.as_str()));
+    quote! {
+        #root::FootnoteReference::new(#label)
+    }
+}
@@ -5,6 +5,7 @@ use vimwiki_core::{InlineElement, InlineElementContainer};
 
 pub mod code;
 pub mod comments;
+pub mod footnotes;
 pub mod links;
 pub mod math;
 pub mod tags;
@@ -63,5 +64,9 @@ fn tokenize_inline_element(
             let t = do_tokenize!(ctx, &x);
             quote! { #root::InlineElement::Comment(#t) }
         }
+        InlineElement::FootnoteReference(x) => {
+            let t = do_tokenize!(ctx, &x);
+            quote! { #root::InlineElement::FootnoteReference(#t) }
+        }
     }
 }
@@ -35,6 +35,18 @@ fn tokenize_decorated_text_content(
             let t = do_tokenize!(ctx, &x);
             quote! { #root::DecoratedTextContent::Text(#t) }
         }
+        DecoratedTextContent::Code(x) => {
+            let t = do_tokenize!(ctx, &x);
+            quote! { #root::DecoratedTextContent::Code(#t) }
+        }
+        DecoratedTextContent::Math(x) => {
+            let t = do_tokenize!(ctx, &x);
+            quote! { #root::DecoratedTextContent::Math(#t) }
+        }
+        DecoratedTextContent::FootnoteReference(x) => {
+            let t = do_tokenize!(ctx, &x);
+            quote! { #root::DecoratedTextContent::FootnoteReference(#t) }
+        }
     }
 }
 
@@ -98,7 +110,7 @@ fn tokenize_decorated_text(
 }
 
 impl_tokenize!(tokenize_keyword, Keyword);
-fn tokenize_keyword(_ctx: &TokenizeContext, keyword: &Keyword) -> TokenStream {
+fn tokenize_keyword(ctx: &TokenizeContext, keyword: &Keyword) -> TokenStream {
     let root = root_crate();
     match keyword {
         Keyword::Done => {
@@ -119,5 +131,9 @@ fn tokenize_keyword(_ctx: &TokenizeContext, keyword: &Keyword) -> TokenStream {
         Keyword::Xxx => {
             quote! { #root::Keyword::Xxx }
         }
+        Keyword::Other(x) => {
+            let t = do_tokenize!(ctx, x);
+            quote! { #root::Keyword::Other(::std::string::ToString::to_string(&#t)) }
+        }
     }
 }
@@ -145,9 +145,13 @@ fn tokenize_list_item_attributes(
         tokenize_option(ctx, &list_item_attributes.todo_status, |ctx, x| {
             do_tokenize!(ctx, x)
         });
+    let due = tokenize_option(ctx, &list_item_attributes.due, |ctx, x| {
+        do_tokenize!(ctx, x)
+    });
     quote! {
         #root::ListItemAttributes {
-            todo_status: #todo_status
+            todo_status: #todo_status,
+            due: #due,
         }
     }
 }
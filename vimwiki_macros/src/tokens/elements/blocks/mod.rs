@@ -7,6 +7,7 @@ pub mod blockquotes;
 pub mod code;
 pub mod definitions;
 pub mod dividers;
+pub mod footnotes;
 pub mod headers;
 pub mod inline;
 pub mod lists;
@@ -34,6 +35,10 @@ fn tokenize_block_element(
             let t = do_tokenize!(ctx, &x);
             quote! { #root::BlockElement::Divider(#t) }
         }
+        BlockElement::FootnoteDefinition(x) => {
+            let t = do_tokenize!(ctx, &x);
+            quote! { #root::BlockElement::FootnoteDefinition(#t) }
+        }
         BlockElement::Header(x) => {
             let t = do_tokenize!(ctx, &x);
             quote! { #root::BlockElement::Header(#t) }
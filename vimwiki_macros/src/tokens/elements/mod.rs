@@ -1,7 +1,7 @@
 use crate::tokens::{utils::root_crate, Tokenize, TokenizeContext};
 use proc_macro2::TokenStream;
 use quote::quote;
-use vimwiki_core::Page;
+use vimwiki_core::{LineEnding, Page};
 
 pub mod blocks;
 pub mod location;
@@ -11,7 +11,13 @@ impl_tokenize!(tokenize_page, Page<'a>, 'a);
 fn tokenize_page(ctx: &TokenizeContext, page: &Page) -> TokenStream {
     let root = root_crate();
     let elements = page.elements().iter().map(|x| do_tokenize!(ctx, x));
+    let line_ending = match page.line_ending {
+        LineEnding::Lf => quote!(#root::LineEnding::Lf),
+        LineEnding::CrLf => quote!(#root::LineEnding::CrLf),
+    };
+    let has_bom = page.has_bom;
     quote! {
         #root::Page::new(::std::vec![#(#elements),*])
+            .with_format(#line_ending, #has_bom)
     }
 }
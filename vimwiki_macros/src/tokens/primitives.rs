@@ -1,11 +1,8 @@
-use crate::tokens::{utils::vendor_path, Tokenize, TokenizeContext};
+use crate::tokens::{utils::root_crate, Tokenize, TokenizeContext};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use std::{borrow::Cow, path::Path};
-use vimwiki_core::vendor::{
-    chrono::{Datelike, NaiveDate},
-    uriparse::URIReference,
-};
+use vimwiki_core::{WikiDate, WikiUri};
 
 // Implement primitives that already implement ToTokens via quote crate
 impl_tokenize!(bool);
@@ -51,32 +48,20 @@ pub fn tokenize_cow_path(ctx: &TokenizeContext, path: &Path) -> TokenStream {
     }
 }
 
-impl_tokenize!(tokenize_naive_date, NaiveDate);
-fn tokenize_naive_date(
-    _ctx: &TokenizeContext,
-    naive_date: &NaiveDate,
-) -> TokenStream {
-    let root = vendor_path();
-    let year = naive_date.year();
-    let month = naive_date.month();
-    let day = naive_date.day();
-    quote! { #root::chrono::NaiveDate::from_ymd(#year, #month, #day) }
+impl_tokenize!(tokenize_wiki_date, WikiDate);
+fn tokenize_wiki_date(_ctx: &TokenizeContext, date: &WikiDate) -> TokenStream {
+    let root = root_crate();
+    let year = date.year();
+    let month = date.month();
+    let day = date.day();
+    quote! { #root::WikiDate::from_ymd(#year, #month, #day) }
 }
 
-impl_tokenize!(tokenize_uri_reference, URIReference<'a>, 'a);
-fn tokenize_uri_reference(
-    _ctx: &TokenizeContext,
-    uri: &URIReference,
-) -> TokenStream {
-    let root = vendor_path();
+impl_tokenize!(tokenize_wiki_uri, WikiUri<'a>, 'a);
+fn tokenize_wiki_uri(_ctx: &TokenizeContext, uri: &WikiUri) -> TokenStream {
+    let root = root_crate();
     let uri_string = uri.to_string();
-    quote! {
-        {
-            use ::std::convert::TryFrom;
-            #root::uriparse::URIReference::try_from(#uri_string)
-                .expect("Failed to parse URIReference").into_owned()
-        }
-    }
+    quote! { #root::WikiUri::lazy(#uri_string) }
 }
 
 impl_tokenize!(tokenize_path, Path);
@@ -34,14 +34,6 @@ fn get_crate(cname: &str) -> syn::Result<Path> {
         .map_err(|msg| syn::Error::new(Span::mixed_site(), msg))
 }
 
-/// Generates a `TokenStream` that provides the path to vendor types in
-/// the `vimwiki` crate
-#[inline]
-pub fn vendor_path() -> TokenStream {
-    let root = root_crate();
-    quote! { #root::vendor }
-}
-
 /// Produces a `TokenStream` for the `String` type
 #[inline]
 pub fn tokenize_cow_str_type() -> TokenStream {
@@ -1,5 +1,119 @@
 use crate::error::{Error, Result};
 use proc_macro2::{Span, TokenTree};
+use std::ops::Range;
+use syn::{GenericArgument, Path, PathArguments, Type, TypePath};
+use vimwiki_core::ParseError;
+
+/// Represents the text of a string literal given to a macro, along with
+/// where each of its lines came from within the literal so that errors
+/// can point at the exact characters that caused them
+pub struct LiteralInput {
+    /// Text to parse, after blank lines and indentation are removed
+    pub text: String,
+
+    /// Literal containing the text
+    token: TokenTree,
+
+    /// Where each line of the text came from, in order
+    lines: Vec<LineOrigin>,
+}
+
+/// Represents where a line of text came from within a literal
+struct LineOrigin {
+    /// Byte offset of the start of the line within the text
+    text_start: usize,
+
+    /// Byte offset of the start of the line within the literal as written,
+    /// including its quotes and prefix
+    literal_start: usize,
+
+    /// Line within the literal, starting at 0
+    number: usize,
+
+    /// Characters of indentation removed from the start of the line
+    indentation: usize,
+}
+
+impl LiteralInput {
+    /// Returns the line containing the byte offset within the text
+    fn line_of(&self, offset: usize) -> &LineOrigin {
+        let i = self
+            .lines
+            .iter()
+            .rposition(|x| x.text_start <= offset)
+            .unwrap_or_default();
+        &self.lines[i]
+    }
+
+    /// Converts a byte offset within the text into one within the literal
+    fn literal_offset(&self, offset: usize) -> usize {
+        let line = self.line_of(offset);
+        line.literal_start + (offset - line.text_start)
+    }
+
+    /// Returns the line and column (both starting at 1) within the literal
+    /// of the byte offset within the text
+    pub fn line_and_column(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_of(offset);
+        let column = self.text[line.text_start..offset].chars().count();
+        (line.number + 1, line.indentation + column + 1)
+    }
+
+    /// Returns the span of the characters of the literal that produced the
+    /// range of the text, falling back to the span of the entire literal
+    /// if the compiler cannot point within literals
+    pub fn span(&self, range: Range<usize>) -> Span {
+        let start = self.literal_offset(range.start);
+        let end = self.literal_offset(range.end);
+        match &self.token {
+            TokenTree::Literal(x) => {
+                x.subspan(start..end).unwrap_or_else(|| x.span())
+            }
+            x => x.span(),
+        }
+    }
+
+    /// Converts an error from parsing the text into one spanning the
+    /// offending characters within the literal
+    pub fn to_parse_error(&self, err: &ParseError) -> Error {
+        let range = self.offending_range(err.offset());
+        let (line, column) = self.line_and_column(range.start);
+        Error::new(
+            self.span(range.clone()),
+            &format!(
+                "{}: Line {}, Column {}\n{}",
+                err.context(),
+                line,
+                column,
+                &self.text[range]
+            ),
+        )
+    }
+
+    /// Returns the range from the byte offset to the end of its line,
+    /// covering at least one character if the text has any
+    fn offending_range(&self, offset: usize) -> Range<usize> {
+        let mut start = offset.min(self.text.len());
+        while !self.text.is_char_boundary(start) {
+            start -= 1;
+        }
+
+        let rest = &self.text[start..];
+        let mut end = start + rest.find('\n').unwrap_or(rest.len());
+        if end == start {
+            match rest.chars().next() {
+                Some(c) => end += c.len_utf8(),
+                None => {
+                    start = self.text[..start]
+                        .char_indices()
+                        .next_back()
+                        .map_or(start, |(i, _)| i)
+                }
+            }
+        }
+        start..end
+    }
+}
 
 /// Converts a token tree that is a string or byte string into a Rust string
 /// instance. Removes any blank lines (whitespace only) before and after
@@ -7,9 +121,11 @@ use proc_macro2::{Span, TokenTree};
 ///
 /// If `raw_mode` is specified, will leave lines unaltered, otherwise will
 /// find the minimum indentation level and remove that from all lines.
-pub fn input_to_string(token: TokenTree, raw_mode: bool) -> Result<String> {
+pub fn input_to_string(
+    token: TokenTree,
+    raw_mode: bool,
+) -> Result<LiteralInput> {
     let repr = token.to_string();
-    let repr = repr.trim();
     let is_string = repr.starts_with('"') || repr.starts_with('r');
     let is_byte_string = repr.starts_with("b\"") || repr.starts_with("br");
 
@@ -23,18 +139,26 @@ pub fn input_to_string(token: TokenTree, raw_mode: bool) -> Result<String> {
     // Get the raw string as it appears
     let begin = repr.find('"').unwrap() + 1;
     let end = repr.rfind('"').unwrap();
-    let s = repr[begin..end].to_string();
+    let s = &repr[begin..end];
 
     // Determine which lines in the macro are blank and what each line's
-    // indentation level is, which will be used if not in raw mode
+    // indentation level is, which will be used if not in raw mode; each
+    // line also remembers where it starts within the literal
+    let mut start_of_line = begin;
     let mut line_data = s
-        .lines()
-        .map(move |l| {
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(number, l)| {
+            let literal_start = start_of_line;
+            start_of_line += l.len();
+
+            let l = l.strip_suffix('\n').unwrap_or(l);
+            let l = l.strip_suffix('\r').unwrap_or(l);
             let is_blank = l.trim().is_empty();
             let indentation = l.len() - l.trim_start().len();
-            (is_blank, indentation, l)
+            (is_blank, indentation, l, literal_start, number)
         })
-        .collect::<Vec<(bool, usize, &str)>>();
+        .collect::<Vec<(bool, usize, &str, usize, usize)>>();
 
     // Special handling to remove any blank lines at beginning and end of
     // the string; this includes lines that have whitespace but nothing else
@@ -59,7 +183,7 @@ pub fn input_to_string(token: TokenTree, raw_mode: bool) -> Result<String> {
             line_data = line_data[start..=end].to_vec();
         } else {
             return Err(Error::new(
-                Span::call_site(),
+                token.span(),
                 &format!(
                     "Blank input provided! Need non-empty lines! {}/{}",
                     start, end
@@ -70,23 +194,107 @@ pub fn input_to_string(token: TokenTree, raw_mode: bool) -> Result<String> {
 
     // Process the lines back into a single string, either by doing nothing
     // to them or removing a set minimum indentation from all
-    let lines =
-        if raw_mode {
-            line_data
-                .iter()
-                .map(|x| x.2)
-                .collect::<Vec<&str>>()
-                .join("\n")
-        } else {
-            let min_indentation = line_data
-                .iter()
-                .fold(usize::MAX, |acc, x| if x.1 < acc { x.1 } else { acc });
-            line_data
-                .iter()
-                .map(|x| &x.2[min_indentation..])
-                .collect::<Vec<&str>>()
-                .join("\n")
-        };
-
-    Ok(lines)
+    let min_indentation = if raw_mode {
+        0
+    } else {
+        line_data
+            .iter()
+            .fold(usize::MAX, |acc, x| if x.1 < acc { x.1 } else { acc })
+    };
+
+    let mut text = String::new();
+    let mut lines = Vec::new();
+    for (_, _, l, literal_start, number) in line_data {
+        if !lines.is_empty() {
+            text.push('\n');
+        }
+
+        // NOTE: Indentation is whitespace, so bytes and characters agree
+        lines.push(LineOrigin {
+            text_start: text.len(),
+            literal_start: literal_start + min_indentation,
+            number,
+            indentation: min_indentation,
+        });
+        text.push_str(&l[min_indentation..]);
+    }
+
+    Ok(LiteralInput { text, token, lines })
+}
+
+/// Prefixes every path within the type with the root path, so that a type
+/// such as `Located<Header>` can be named within generated code
+pub fn qualify_type(ty: &mut Type, root: &Path) {
+    if let Type::Path(TypePath { qself: None, path }) = ty {
+        for segment in path.segments.iter_mut() {
+            if let PathArguments::AngleBracketed(args) = &mut segment.arguments
+            {
+                for arg in args.args.iter_mut() {
+                    if let GenericArgument::Type(ty) = arg {
+                        qualify_type(ty, root);
+                    }
+                }
+            }
+        }
+
+        let mut qualified = root.clone();
+        qualified.segments.extend(path.segments.iter().cloned());
+        *path = qualified;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::TokenStream;
+
+    fn load(code: &str, raw_mode: bool) -> LiteralInput {
+        let stream: TokenStream = code.parse().unwrap();
+        let token = stream.into_iter().next().unwrap();
+        match input_to_string(token, raw_mode) {
+            Ok(x) => x,
+            Err(_) => panic!("Failed to load literal {}", code),
+        }
+    }
+
+    #[test]
+    fn input_to_string_should_remove_blank_lines_and_indentation() {
+        let input = load("\"\n    one\n      two\n  \"", false);
+        assert_eq!(input.text, "one\n  two");
+
+        let input = load("r#\"\n  one\n  two\n\"#", true);
+        assert_eq!(input.text, "  one\n  two");
+    }
+
+    #[test]
+    fn line_and_column_should_be_relative_to_the_literal() {
+        let input = load("\"\n\n    one\n      two\"", false);
+
+        // "one" is the third line of the literal, "two" the fourth
+        assert_eq!(input.line_and_column(0), (3, 5));
+        assert_eq!(
+            input.line_and_column(input.text.find("two").unwrap()),
+            (4, 7)
+        );
+    }
+
+    #[test]
+    fn span_should_point_at_characters_within_the_literal() {
+        let input = load("r\"\n    one\n      two\"", false);
+        let offset = input.text.find("two").unwrap();
+
+        let span = input.span(offset..offset + 3);
+        assert_eq!(span.start().line, 3);
+        assert_eq!(span.start().column, 6);
+        assert_eq!(span.end().column, 9);
+    }
+
+    #[test]
+    fn offending_range_should_cover_the_rest_of_the_line() {
+        let input = load("\"ab\ncd\"", true);
+        assert_eq!(input.offending_range(1), 1..2);
+        assert_eq!(input.offending_range(2), 2..3);
+        assert_eq!(input.offending_range(3), 3..5);
+        assert_eq!(input.offending_range(5), 4..5);
+    }
 }
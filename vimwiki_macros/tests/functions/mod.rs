@@ -1,2 +1,3 @@
 mod vimwiki;
+mod vimwiki_compact;
 mod vimwiki_format;
@@ -1,8 +1,5 @@
 use std::{borrow::Cow, convert::TryFrom, iter::FromIterator};
-use vimwiki::{
-    vendor::{chrono::NaiveDate, uriparse::URIReference},
-    *,
-};
+use vimwiki::*;
 use vimwiki_macros::*;
 
 #[test]
@@ -130,7 +127,27 @@ fn vimwiki_definition_list() {
 #[test]
 fn vimwiki_divider() {
     let x = vimwiki_divider!("----");
-    assert_eq!(x.into_inner(), Divider);
+    assert_eq!(x.into_inner(), Divider::new(4, 0, 0));
+}
+
+#[test]
+fn vimwiki_footnote_definition() {
+    let x = vimwiki_footnote_definition!("[^note]: some footnote");
+    assert_eq!(
+        x.into_inner(),
+        FootnoteDefinition::new(
+            Cow::Borrowed("note"),
+            InlineElementContainer::new(vec![Located::from(
+                InlineElement::from(Text::from("some footnote"))
+            )]),
+        )
+    );
+}
+
+#[test]
+fn vimwiki_footnote_reference() {
+    let x = vimwiki_footnote_reference!("[^note]");
+    assert_eq!(x.into_inner(), FootnoteReference::from("note"));
 }
 
 #[test]
@@ -155,7 +172,7 @@ fn vimwiki_link() {
     assert_eq!(
         x.into_inner(),
         Link::new_wiki_link(
-            URIReference::try_from("link").unwrap().into_owned(),
+            WikiUri::try_from("link").unwrap().into_owned(),
             None
         ),
     );
@@ -166,7 +183,7 @@ fn vimwiki_link() {
         x.into_inner(),
         Link::new_indexed_interwiki_link(
             1,
-            URIReference::try_from("link").unwrap().into_owned(),
+            WikiUri::try_from("link").unwrap().into_owned(),
             None
         ),
     );
@@ -177,7 +194,7 @@ fn vimwiki_link() {
         x.into_inner(),
         Link::new_named_interwiki_link(
             "MyWiki",
-            URIReference::try_from("link").unwrap().into_owned(),
+            WikiUri::try_from("link").unwrap().into_owned(),
             None
         ),
     );
@@ -186,7 +203,7 @@ fn vimwiki_link() {
     let x = vimwiki_link!("[[diary:2012-03-05]]");
     assert_eq!(
         x.into_inner(),
-        Link::new_diary_link(NaiveDate::from_ymd(2012, 3, 5), None, None)
+        Link::new_diary_link(WikiDate::from_ymd(2012, 3, 5), None, None)
     );
 
     // File Link
@@ -194,9 +211,7 @@ fn vimwiki_link() {
     assert_eq!(
         x.into_inner(),
         Link::new_wiki_link(
-            URIReference::try_from("file:path/to/file")
-                .unwrap()
-                .into_owned(),
+            WikiUri::try_from("file:path/to/file").unwrap().into_owned(),
             None,
         )
     );
@@ -206,7 +221,7 @@ fn vimwiki_link() {
     assert_eq!(
         x.into_inner(),
         Link::new_raw_link(
-            URIReference::try_from("https://example.com")
+            WikiUri::try_from("https://example.com")
                 .unwrap()
                 .into_owned()
         )
@@ -217,7 +232,7 @@ fn vimwiki_link() {
     assert_eq!(
         x.into_inner(),
         Link::new_transclusion_link(
-            URIReference::try_from("https://example.com/img.jpg")
+            WikiUri::try_from("https://example.com/img.jpg")
                 .unwrap()
                 .into_owned(),
             None,
@@ -301,7 +316,10 @@ fn vimwiki_list_item() {
                     )))
                 ])])
             ))]),
-            ListItemAttributes { todo_status: None }
+            ListItemAttributes {
+                todo_status: None,
+                due: None,
+            }
         )
     );
 }
@@ -378,7 +396,7 @@ fn vimwiki_paragraph() {
 fn vimwiki_placeholder() {
     assert_eq!(
         vimwiki_placeholder!("%date 2012-03-05").into_inner(),
-        Placeholder::Date(NaiveDate::from_ymd(2012, 3, 5)),
+        Placeholder::Date(WikiDate::from_ymd(2012, 3, 5)),
     );
     assert_eq!(
         vimwiki_placeholder!("%nohtml").into_inner(),
@@ -426,6 +444,7 @@ fn vimwiki_code_block_raw() {
             Default::default(),
             vec![Cow::from("    some code")]
         )
+        .with_indents(Cow::from(""), Cow::from("    "), Vec::new())
     );
 }
 
@@ -0,0 +1,71 @@
+use vimwiki::*;
+use vimwiki_macros::*;
+
+#[test]
+fn vimwiki_page_compact() {
+    let x = vimwiki_page_compact! {r#"
+        = Header =
+
+        Some paragraph with *bold* content
+        and some [[links|with description]].
+
+        - [ ] List item 1
+        - [X] List item 2
+
+        | a | b |
+        |---|---|
+        | c | d |
+
+        term:: definition
+        %date 2012-03-05
+    "#};
+    let y = vimwiki_page! {r#"
+        = Header =
+
+        Some paragraph with *bold* content
+        and some [[links|with description]].
+
+        - [ ] List item 1
+        - [X] List item 2
+
+        | a | b |
+        |---|---|
+        | c | d |
+
+        term:: definition
+        %date 2012-03-05
+    "#};
+    assert_eq!(x, y);
+}
+
+#[test]
+fn vimwiki_page_raw_compact() {
+    let x = vimwiki_page_raw_compact!("  some text\n");
+    let y = vimwiki_page_raw!("  some text\n");
+    assert_eq!(x, y);
+}
+
+#[test]
+fn vimwiki_header_compact() {
+    let x = vimwiki_header_compact!("== some *header* ==");
+    let y = vimwiki_header!("== some *header* ==");
+    assert_eq!(x, y);
+}
+
+#[test]
+fn vimwiki_compact_should_keep_everything_parsed() {
+    let x = vimwiki_header_compact!("== some *header* ==");
+    let y: Located<Header> = Language::from_vimwiki_str("== some *header* ==")
+        .parse()
+        .unwrap();
+    assert!(x.strict_eq(&y), "{:#?} != {:#?}", x, y);
+}
+
+#[test]
+fn vimwiki_compact_should_produce_a_new_copy_each_time() {
+    let mut pages: Vec<Page> =
+        (0..2).map(|_| vimwiki_page_compact!("some text")).collect();
+    pages[0].elements_mut().clear();
+    assert!(pages[0].elements().is_empty());
+    assert_eq!(pages[1].elements().len(), 1);
+}
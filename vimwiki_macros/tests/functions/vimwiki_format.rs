@@ -1,8 +1,5 @@
 use std::{borrow::Cow, convert::TryFrom, iter::FromIterator};
-use vimwiki::{
-    vendor::{chrono::NaiveDate, uriparse::URIReference},
-    *,
-};
+use vimwiki::*;
 use vimwiki_macros::*;
 
 #[test]
@@ -167,7 +164,7 @@ fn vimwiki_link() {
     assert_eq!(
         x.into_inner(),
         Link::new_wiki_link(
-            URIReference::try_from("link").unwrap().into_owned(),
+            WikiUri::try_from("link").unwrap().into_owned(),
             Description::from("cool"),
         ),
     );
@@ -178,7 +175,7 @@ fn vimwiki_link() {
         x.into_inner(),
         Link::new_indexed_interwiki_link(
             1,
-            URIReference::try_from("link").unwrap().into_owned(),
+            WikiUri::try_from("link").unwrap().into_owned(),
             Description::from("cool"),
         ),
     );
@@ -189,7 +186,7 @@ fn vimwiki_link() {
         x.into_inner(),
         Link::new_named_interwiki_link(
             "MyWiki",
-            URIReference::try_from("link").unwrap().into_owned(),
+            WikiUri::try_from("link").unwrap().into_owned(),
             Description::from("cool"),
         ),
     );
@@ -199,7 +196,7 @@ fn vimwiki_link() {
     assert_eq!(
         x.into_inner(),
         Link::new_diary_link(
-            NaiveDate::from_ymd(2012, 3, 5),
+            WikiDate::from_ymd(2012, 3, 5),
             Description::from("cool"),
             None,
         )
@@ -210,9 +207,7 @@ fn vimwiki_link() {
     assert_eq!(
         x.into_inner(),
         Link::new_wiki_link(
-            URIReference::try_from("file:path/to/file")
-                .unwrap()
-                .into_owned(),
+            WikiUri::try_from("file:path/to/file").unwrap().into_owned(),
             Description::from("cool"),
         )
     );
@@ -301,7 +296,10 @@ fn vimwiki_list_item() {
                     )))
                 ])])
             ))]),
-            ListItemAttributes { todo_status: None }
+            ListItemAttributes {
+                todo_status: None,
+                due: None,
+            }
         )
     );
 }
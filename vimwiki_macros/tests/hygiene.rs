@@ -20,6 +20,8 @@ fn hygiene_passes_for_vimwiki() {
         :: def 3
     "#};
     let _ = vimwiki_divider!("----");
+    let _ = vimwiki_footnote_definition!("[^note]: some footnote");
+    let _ = vimwiki_footnote_reference!("[^note]");
     let _ = vimwiki_header!("= header =");
     let _ = vimwiki_link!("[[link]]");
     let _ = vimwiki_link!("[[diary:2012-03-05]]");
@@ -48,3 +50,17 @@ fn hygiene_passes_for_vimwiki() {
     let _ = vimwiki_decorated_text!(",,some text,,");
     let _ = vimwiki_keyword!("TODO");
 }
+
+#[test]
+fn hygiene_passes_for_vimwiki_compact() {
+    let _ = vimwiki_page_compact!("some text");
+    let _ = vimwiki_page_raw_compact!("some text");
+    let _ = vimwiki_block_element_compact!("some text");
+    let _ = vimwiki_header_compact!("= header =");
+    let _ = vimwiki_link_compact!("[[diary:2012-03-05]]");
+    let _ = vimwiki_definition_list_compact! {r#"
+        term:: definition
+        term2::
+        :: def 2
+    "#};
+}